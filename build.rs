@@ -0,0 +1,78 @@
+//! Generates the detector arms of the swap and deposit dispatchers from
+//! `crates/core/protocols.toml`.
+
+#[path = "crates/core/build/protocols.rs"]
+mod protocols;
+
+use {
+    protocols::{Action, Protocol},
+    std::{env, fmt::Write, fs, path::Path},
+};
+
+fn main() {
+    let protocols = protocols::load(Path::new("crates/core/protocols.toml"));
+
+    let mut out = String::new();
+    detector(&mut out, &protocols, Action::Swap, "swap_protocol_of");
+    detector(&mut out, &protocols, Action::Deposit, "deposit_protocol_of");
+    swap_context_protocol(&mut out, &protocols);
+
+    let out_dir = env::var("OUT_DIR").unwrap();
+    fs::write(Path::new(&out_dir).join("dispatch.rs"), out).unwrap();
+}
+
+/// `fn name(program_id) -> Option<ProtocolKind>` over the enabled `action` protocols.
+fn detector(out: &mut String, protocols: &[Protocol], action: Action, name: &str) {
+    writeln!(
+        out,
+        "/// The enabled protocol whose program is `program_id`, if any.
+#[inline(always)]
+fn {name}(program_id: &solana_address::Address) -> Option<ProtocolKind> {{"
+    )
+    .unwrap();
+    for protocol in protocols
+        .iter()
+        .filter(|protocol| protocol.action == action)
+    {
+        writeln!(
+            out,
+            "    #[cfg(feature = \"{}\")]
+    if address_eq(program_id, &beethoven_core::program_ids::{}) {{
+        return Some(ProtocolKind::{});
+    }}",
+            protocol.feature, protocol.program_id_const, protocol.name
+        )
+        .unwrap();
+    }
+    out.push_str("    let _ = program_id;\n    None\n}\n\n");
+}
+
+/// `fn swap_context_protocol(&SwapContext) -> Option<ProtocolKind>`, relying on each
+/// `SwapContext` variant being named after its `ProtocolKind`.
+fn swap_context_protocol(out: &mut String, protocols: &[Protocol]) {
+    out.push_str(
+        "/// The protocol of `ctx`'s variant.
+fn swap_context_protocol(ctx: &SwapContext<'_>) -> Option<ProtocolKind> {
+    match ctx {
+",
+    );
+    for protocol in protocols
+        .iter()
+        .filter(|protocol| protocol.action == Action::Swap)
+    {
+        writeln!(
+            out,
+            "        #[cfg(feature = \"{}\")]
+        SwapContext::{}(_) => Some(ProtocolKind::{}),",
+            protocol.feature, protocol.name, protocol.name
+        )
+        .unwrap();
+    }
+    out.push_str(
+        "        #[allow(unreachable_patterns)]
+        _ => None,
+    }
+}
+",
+    );
+}
@@ -0,0 +1,91 @@
+//! Aldrin swap instructions.
+
+use {
+    crate::{instruction_data, AccountSpec, AdapterKeys, EncodeSwapData, FieldSpec, FieldType},
+    beethoven::{
+        aldrin::{self, AldrinSwapData, Side, ALDRIN_PROGRAM_ID},
+        ProtocolKind,
+    },
+    solana_address::Address,
+    solana_instruction::Instruction,
+};
+
+/// Addresses of [`beethoven::aldrin::AldrinSwapAccounts`], without the program.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AldrinSwapKeys {
+    pub pool: Address,
+    pub pool_signer: Address,
+    pub pool_mint: Address,
+    pub base_token_vault: Address,
+    pub quote_token_vault: Address,
+    pub fee_pool_token_account: Address,
+    pub wallet_authority: Address,
+    pub user_base_token_account: Address,
+    pub user_quote_token_account: Address,
+    pub token_program: Address,
+}
+
+impl AdapterKeys for AldrinSwapKeys {
+    const PROTOCOL: ProtocolKind = ProtocolKind::Aldrin;
+
+    const PROGRAM_ID: Address = ALDRIN_PROGRAM_ID;
+
+    const ACCOUNTS: &'static [AccountSpec] = &[
+        AccountSpec::readonly("pool"),
+        AccountSpec::readonly("pool_signer"),
+        AccountSpec::writable("pool_mint"),
+        AccountSpec::writable("base_token_vault"),
+        AccountSpec::writable("quote_token_vault"),
+        AccountSpec::writable("fee_pool_token_account"),
+        AccountSpec::readonly_signer("wallet_authority"),
+        AccountSpec::writable("user_base_token_account"),
+        AccountSpec::writable("user_quote_token_account"),
+        AccountSpec::readonly("token_program"),
+    ];
+
+    fn addresses(&self) -> Vec<Address> {
+        vec![
+            self.pool,
+            self.pool_signer,
+            self.pool_mint,
+            self.base_token_vault,
+            self.quote_token_vault,
+            self.fee_pool_token_account,
+            self.wallet_authority,
+            self.user_base_token_account,
+            self.user_quote_token_account,
+            self.token_program,
+        ]
+    }
+}
+
+impl EncodeSwapData for AldrinSwapData {
+    const FIELDS: &'static [FieldSpec] = &[FieldSpec {
+        name: "side",
+        ty: FieldType::U8,
+        docs: "0 = Bid, 1 = Ask",
+    }];
+
+    fn encode(&self) -> Vec<u8> {
+        vec![match self.side {
+            Side::Bid => 0,
+            Side::Ask => 1,
+        }]
+    }
+}
+
+/// Aldrin swap instruction calling the venue directly.
+pub fn swap_instruction(
+    keys: &AldrinSwapKeys,
+    in_amount: u64,
+    minimum_out_amount: u64,
+    swap_data: &AldrinSwapData,
+) -> Instruction {
+    Instruction {
+        program_id: ALDRIN_PROGRAM_ID,
+        accounts: keys.account_metas(),
+        data: instruction_data::<{ aldrin::MAX_INSTRUCTION_DATA_LEN }, _>(|scratch| {
+            aldrin::swap_instruction_data(scratch, in_amount, minimum_out_amount, swap_data)
+        }),
+    }
+}
@@ -0,0 +1,94 @@
+//! Aldrin V2 swap instructions.
+
+use {
+    crate::{instruction_data, AccountSpec, AdapterKeys, EncodeSwapData, FieldSpec, FieldType},
+    beethoven::{
+        aldrin_v2::{self, AldrinV2SwapData, Side, ALDRIN_V2_PROGRAM_ID},
+        ProtocolKind,
+    },
+    solana_address::Address,
+    solana_instruction::Instruction,
+};
+
+/// Addresses of [`beethoven::aldrin_v2::AldrinV2SwapAccounts`], without the program.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AldrinV2SwapKeys {
+    pub pool: Address,
+    pub pool_signer: Address,
+    pub pool_mint: Address,
+    pub base_token_vault: Address,
+    pub quote_token_vault: Address,
+    pub fee_pool_token_account: Address,
+    pub wallet_authority: Address,
+    pub user_base_token_account: Address,
+    pub user_quote_token_account: Address,
+    pub curve: Address,
+    pub token_program: Address,
+}
+
+impl AdapterKeys for AldrinV2SwapKeys {
+    const PROTOCOL: ProtocolKind = ProtocolKind::AldrinV2;
+
+    const PROGRAM_ID: Address = ALDRIN_V2_PROGRAM_ID;
+
+    const ACCOUNTS: &'static [AccountSpec] = &[
+        AccountSpec::readonly("pool"),
+        AccountSpec::readonly("pool_signer"),
+        AccountSpec::writable("pool_mint"),
+        AccountSpec::writable("base_token_vault"),
+        AccountSpec::writable("quote_token_vault"),
+        AccountSpec::writable("fee_pool_token_account"),
+        AccountSpec::readonly_signer("wallet_authority"),
+        AccountSpec::writable("user_base_token_account"),
+        AccountSpec::writable("user_quote_token_account"),
+        AccountSpec::readonly("curve"),
+        AccountSpec::readonly("token_program"),
+    ];
+
+    fn addresses(&self) -> Vec<Address> {
+        vec![
+            self.pool,
+            self.pool_signer,
+            self.pool_mint,
+            self.base_token_vault,
+            self.quote_token_vault,
+            self.fee_pool_token_account,
+            self.wallet_authority,
+            self.user_base_token_account,
+            self.user_quote_token_account,
+            self.curve,
+            self.token_program,
+        ]
+    }
+}
+
+impl EncodeSwapData for AldrinV2SwapData {
+    const FIELDS: &'static [FieldSpec] = &[FieldSpec {
+        name: "side",
+        ty: FieldType::U8,
+        docs: "0 = Bid, 1 = Ask",
+    }];
+
+    fn encode(&self) -> Vec<u8> {
+        vec![match self.side {
+            Side::Bid => 0,
+            Side::Ask => 1,
+        }]
+    }
+}
+
+/// Aldrin V2 swap instruction calling the venue directly.
+pub fn swap_instruction(
+    keys: &AldrinV2SwapKeys,
+    in_amount: u64,
+    minimum_out_amount: u64,
+    swap_data: &AldrinV2SwapData,
+) -> Instruction {
+    Instruction {
+        program_id: ALDRIN_V2_PROGRAM_ID,
+        accounts: keys.account_metas(),
+        data: instruction_data::<{ aldrin_v2::MAX_INSTRUCTION_DATA_LEN }, _>(|scratch| {
+            aldrin_v2::swap_instruction_data(scratch, in_amount, minimum_out_amount, swap_data)
+        }),
+    }
+}
@@ -0,0 +1,20 @@
+//! Print the `beethoven-test` IDL for every enabled venue.
+//!
+//! Usage: `beethoven-idl [PROGRAM_ID]`, defaulting to the id the integration tests
+//! deploy the program at.
+
+use {
+    beethoven_client::{idl::test_program_idl, test_program::TEST_PROGRAM_ID},
+    solana_address::Address,
+    std::str::FromStr,
+};
+
+fn main() {
+    let program_id = match std::env::args().nth(1) {
+        Some(program_id) => Address::from_str(&program_id).expect("invalid program id"),
+        None => TEST_PROGRAM_ID,
+    };
+
+    let idl = test_program_idl(&program_id);
+    println!("{}", serde_json::to_string_pretty(&idl).unwrap());
+}
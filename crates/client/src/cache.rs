@@ -0,0 +1,212 @@
+//! Keeping the accounts quotes are computed from warm in memory.
+//!
+//! [`QuoteCache`] is an [`AccountSource`] over accounts held in memory, so the
+//! resolvers, quoting functions and the route planner read it like any other source.
+//! [`QuoteCache::watch`] loads the accounts backing a set of pools once; after that the
+//! cache is kept current by a stream of account updates:
+//!
+//! - `subscribe` (with the `pubsub` feature) opens one `accountSubscribe` WebSocket
+//!   subscription per watched account and applies each notification.
+//! - A Geyser gRPC stream (e.g. Yellowstone) is fed in through [`QuoteCache::apply`]
+//!   with the owner and data of each account update, filtered on
+//!   [`QuoteCache::addresses`]. No gRPC client is bundled.
+
+use {
+    crate::resolve::{AccountSource, FetchedAccount, ResolveError},
+    solana_address::Address,
+    std::{
+        collections::HashMap,
+        sync::{PoisonError, RwLock},
+    },
+};
+
+/// Accounts kept in memory for quoting, safe to share between an update stream and
+/// the threads quoting against it.
+#[derive(Debug, Default)]
+pub struct QuoteCache {
+    accounts: RwLock<HashMap<Address, CachedAccount>>,
+}
+
+#[derive(Debug)]
+struct CachedAccount {
+    /// `None` while the account does not exist
+    account: Option<FetchedAccount>,
+    /// Slot of the update the account was last set from, zero for the initial load
+    slot: u64,
+}
+
+impl QuoteCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load the accounts [`quote_accounts`] lists for each of `pools` from `source`,
+    /// and keep them from then on.
+    pub fn watch<S: AccountSource>(
+        &self,
+        source: &S,
+        pools: &[Address],
+    ) -> Result<(), ResolveError<S::Error>> {
+        for pool in pools {
+            for address in quote_accounts(source, pool)? {
+                let account = source.get_account(&address).map_err(ResolveError::Source)?;
+                self.apply(address, account, 0);
+            }
+        }
+        Ok(())
+    }
+
+    /// Set `address` to `account` (`None` if it was closed) as of `slot`.
+    ///
+    /// Updates for accounts the cache does not watch and updates older than the cached
+    /// one are ignored, so several streams can feed the same cache. Returns whether the
+    /// update was applied.
+    pub fn apply(&self, address: Address, account: Option<FetchedAccount>, slot: u64) -> bool {
+        let mut accounts = self
+            .accounts
+            .write()
+            .unwrap_or_else(PoisonError::into_inner);
+        match accounts.get_mut(&address) {
+            Some(cached) if cached.slot > slot => false,
+            Some(cached) => {
+                *cached = CachedAccount { account, slot };
+                true
+            }
+            // The initial load is the only way in
+            None if slot == 0 => {
+                accounts.insert(address, CachedAccount { account, slot });
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Every watched account.
+    pub fn addresses(&self) -> Vec<Address> {
+        let accounts = self.accounts.read().unwrap_or_else(PoisonError::into_inner);
+        accounts.keys().copied().collect()
+    }
+
+    /// Slot of the latest update applied to `address`, if it is watched.
+    pub fn slot(&self, address: &Address) -> Option<u64> {
+        let accounts = self.accounts.read().unwrap_or_else(PoisonError::into_inner);
+        accounts.get(address).map(|cached| cached.slot)
+    }
+}
+
+impl AccountSource for QuoteCache {
+    type Error = core::convert::Infallible;
+
+    fn get_account(&self, address: &Address) -> Result<Option<FetchedAccount>, Self::Error> {
+        let accounts = self.accounts.read().unwrap_or_else(PoisonError::into_inner);
+        Ok(accounts
+            .get(address)
+            .and_then(|cached| cached.account.clone()))
+    }
+}
+
+/// The accounts a quote against `pool` reads, `pool` included, for the venues the
+/// client can quote.
+pub fn quote_accounts<S: AccountSource>(
+    source: &S,
+    pool: &Address,
+) -> Result<Vec<Address>, ResolveError<S::Error>> {
+    let account = crate::resolve::fetch(source, pool)?;
+
+    #[cfg(feature = "gamma-swap")]
+    if account.owner == beethoven::gamma::GAMMA_PROGRAM_ID {
+        let state = beethoven::gamma::state::PoolState::from_account_data(&account.data)
+            .map_err(|_| ResolveError::InvalidAccountData(*pool))?;
+        return Ok(vec![
+            *pool,
+            state.amm_config,
+            state.token_0_vault,
+            state.token_1_vault,
+        ]);
+    }
+
+    #[cfg(feature = "mock_dex-swap")]
+    if account.owner == beethoven::mock_dex::MOCK_DEX_PROGRAM_ID {
+        let state = beethoven::mock_dex::state::Pool::from_account_data(&account.data)
+            .map_err(|_| ResolveError::InvalidAccountData(*pool))?;
+        return Ok(vec![*pool, state.base_vault, state.quote_vault]);
+    }
+
+    let _ = account;
+    Err(ResolveError::InvalidAccountData(*pool))
+}
+
+/// Live `accountSubscribe` subscriptions feeding a [`QuoteCache`].
+///
+/// Dropping it leaves the subscriptions running; call [`Subscriptions::shutdown`] to
+/// close them.
+#[cfg(all(feature = "pubsub", not(target_arch = "wasm32")))]
+pub struct Subscriptions {
+    subscriptions: Vec<solana_pubsub_client::pubsub_client::PubsubAccountClientSubscription>,
+    workers: Vec<std::thread::JoinHandle<()>>,
+}
+
+#[cfg(all(feature = "pubsub", not(target_arch = "wasm32")))]
+impl Subscriptions {
+    /// Unsubscribe every account and wait for the update threads to finish.
+    pub fn shutdown(self) {
+        for mut subscription in self.subscriptions {
+            let _ = subscription.shutdown();
+        }
+        for worker in self.workers {
+            let _ = worker.join();
+        }
+    }
+}
+
+/// Subscribe to every account `cache` watches over the WebSocket endpoint `url`,
+/// applying each notification to `cache` on a background thread per account.
+#[cfg(all(feature = "pubsub", not(target_arch = "wasm32")))]
+pub fn subscribe(
+    cache: std::sync::Arc<QuoteCache>,
+    url: &str,
+) -> Result<Subscriptions, solana_pubsub_client::pubsub_client::PubsubClientError> {
+    use {
+        base64::{engine::general_purpose::STANDARD, Engine},
+        solana_account_decoder_client_types::{UiAccountData, UiAccountEncoding},
+        solana_pubsub_client::pubsub_client::PubsubClient,
+        solana_rpc_client_api::config::RpcAccountInfoConfig,
+    };
+
+    let mut subscriptions = Vec::new();
+    let mut workers = Vec::new();
+    for address in cache.addresses() {
+        let config = RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            ..Default::default()
+        };
+        let (subscription, receiver) = PubsubClient::account_subscribe(
+            url,
+            &solana_pubkey::Pubkey::new_from_array(address.to_bytes()),
+            Some(config),
+        )?;
+        subscriptions.push(subscription);
+
+        let cache = std::sync::Arc::clone(&cache);
+        workers.push(std::thread::spawn(move || {
+            for update in receiver.iter() {
+                let account = update.value;
+                let data = match account.data {
+                    UiAccountData::Binary(data, UiAccountEncoding::Base64) => STANDARD.decode(data),
+                    _ => continue,
+                };
+                let (Ok(data), Ok(owner)) = (data, account.owner.parse()) else {
+                    continue;
+                };
+                // Closed accounts are reported as empty and owned by the system program
+                let account = (account.lamports > 0).then_some(FetchedAccount { owner, data });
+                cache.apply(address, account, update.context.slot);
+            }
+        }));
+    }
+
+    Ok(Subscriptions {
+        subscriptions,
+        workers,
+    })
+}
@@ -0,0 +1,98 @@
+//! Drift perpetuals instructions.
+
+use {
+    crate::{account_metas, AccountSpec, AdapterKeys},
+    beethoven::{
+        drift::{self, DRIFT_PROGRAM_ID},
+        Direction, ProtocolKind,
+    },
+    solana_address::Address,
+    solana_instruction::{AccountMeta, Instruction},
+};
+
+/// Seed of Drift's global state account
+pub const STATE_SEED: &[u8] = b"drift_state";
+
+/// Seed prefix of a Drift user (sub)account
+pub const USER_SEED: &[u8] = b"user";
+
+/// Addresses of [`beethoven::drift::DriftPerpAccounts`], without the program.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DriftPerpKeys {
+    pub state: Address,
+    pub user: Address,
+    pub authority: Address,
+    /// Oracles, then spot markets, then perp markets, flagged as Drift reads them.
+    /// Appended after [`Self::ACCOUNTS`].
+    pub market_accounts: Vec<AccountMeta>,
+}
+
+impl DriftPerpKeys {
+    /// Keys trading from `authority`'s `sub_account_id` user account.
+    pub fn new(authority: Address, sub_account_id: u16, market_accounts: Vec<AccountMeta>) -> Self {
+        Self {
+            state: state_address(),
+            user: user_address(&authority, sub_account_id),
+            authority,
+            market_accounts,
+        }
+    }
+}
+
+impl AdapterKeys for DriftPerpKeys {
+    const PROTOCOL: ProtocolKind = ProtocolKind::Drift;
+
+    const PROGRAM_ID: Address = DRIFT_PROGRAM_ID;
+
+    const ACCOUNTS: &'static [AccountSpec] = &[
+        AccountSpec::readonly("state"),
+        AccountSpec::writable("user"),
+        AccountSpec::readonly_signer("authority"),
+    ];
+
+    fn addresses(&self) -> Vec<Address> {
+        vec![self.state, self.user, self.authority]
+    }
+
+    fn account_metas(&self) -> Vec<AccountMeta> {
+        let mut metas = account_metas(Self::ACCOUNTS, &self.addresses());
+        metas.extend(self.market_accounts.iter().cloned());
+        metas
+    }
+}
+
+/// Drift `place_perp_order` instruction placing a market order, calling the venue
+/// directly.
+pub fn place_perp_order_instruction(
+    keys: &DriftPerpKeys,
+    market_index: u16,
+    direction: Direction,
+    base_amount: u64,
+    price_limit: u64,
+) -> Instruction {
+    Instruction {
+        program_id: DRIFT_PROGRAM_ID,
+        accounts: keys.account_metas(),
+        data: drift::place_perp_order_instruction_data(
+            market_index,
+            direction,
+            base_amount,
+            price_limit,
+        )
+        .to_vec(),
+    }
+}
+
+/// Drift's global state account.
+pub fn state_address() -> Address {
+    Address::find_program_address(&[STATE_SEED], &DRIFT_PROGRAM_ID).0
+}
+
+/// The user account `authority` trades from as `sub_account_id`.
+pub fn user_address(authority: &Address, sub_account_id: u16) -> Address {
+    Address::find_program_address(
+        &[USER_SEED, authority.as_ref(), &sub_account_id.to_le_bytes()],
+        &DRIFT_PROGRAM_ID,
+    )
+    .0
+}
@@ -0,0 +1,45 @@
+//! The accounts `beethoven::swap_with_fees` pays a platform and referral fee with.
+
+use {
+    crate::resolve::associated_token_address, solana_address::Address,
+    solana_instruction::AccountMeta,
+};
+
+/// The fee accounts that go ahead of a swap's routed accounts, in the order
+/// `beethoven::FeeAccounts` reads them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SwapFeeKeys {
+    pub token_program: Address,
+    /// Owner of the swap's destination token account
+    pub authority: Address,
+    pub platform_fee_account: Address,
+    pub referral_fee_account: Address,
+}
+
+impl SwapFeeKeys {
+    /// Fees in `mint` paid by `authority` into the associated token accounts of
+    /// `platform` and `referrer`.
+    pub fn to_associated_accounts(
+        token_program: Address,
+        mint: &Address,
+        authority: Address,
+        platform: &Address,
+        referrer: &Address,
+    ) -> Self {
+        Self {
+            token_program,
+            authority,
+            platform_fee_account: associated_token_address(platform, mint, &token_program),
+            referral_fee_account: associated_token_address(referrer, mint, &token_program),
+        }
+    }
+
+    pub fn account_metas(&self) -> Vec<AccountMeta> {
+        vec![
+            AccountMeta::new_readonly(self.token_program, false),
+            AccountMeta::new_readonly(self.authority, true),
+            AccountMeta::new(self.platform_fee_account, false),
+            AccountMeta::new(self.referral_fee_account, false),
+        ]
+    }
+}
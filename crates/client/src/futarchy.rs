@@ -0,0 +1,88 @@
+//! Futarchy swap instructions.
+
+use {
+    crate::{instruction_data, AccountSpec, AdapterKeys, EncodeSwapData, FieldSpec, FieldType},
+    beethoven::{
+        futarchy::{self, FutarchySwapData, SwapType, FUTARCHY_PROGRAM_ID},
+        ProtocolKind,
+    },
+    solana_address::Address,
+    solana_instruction::Instruction,
+};
+
+/// Addresses of [`beethoven::futarchy::FutarchySwapAccounts`], without the program.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FutarchySwapKeys {
+    pub dao: Address,
+    pub user_base_account: Address,
+    pub user_quote_account: Address,
+    pub amm_base_vault: Address,
+    pub amm_quote_vault: Address,
+    pub user: Address,
+    pub token_program: Address,
+    pub event_authority: Address,
+    pub program: Address,
+}
+
+impl AdapterKeys for FutarchySwapKeys {
+    const PROTOCOL: ProtocolKind = ProtocolKind::Futarchy;
+
+    const PROGRAM_ID: Address = FUTARCHY_PROGRAM_ID;
+
+    const ACCOUNTS: &'static [AccountSpec] = &[
+        AccountSpec::writable("dao"),
+        AccountSpec::writable("user_base_account"),
+        AccountSpec::writable("user_quote_account"),
+        AccountSpec::writable("amm_base_vault"),
+        AccountSpec::writable("amm_quote_vault"),
+        AccountSpec::readonly_signer("user"),
+        AccountSpec::readonly("token_program"),
+        AccountSpec::readonly("event_authority"),
+        AccountSpec::readonly("program"),
+    ];
+
+    fn addresses(&self) -> Vec<Address> {
+        vec![
+            self.dao,
+            self.user_base_account,
+            self.user_quote_account,
+            self.amm_base_vault,
+            self.amm_quote_vault,
+            self.user,
+            self.token_program,
+            self.event_authority,
+            self.program,
+        ]
+    }
+}
+
+impl EncodeSwapData for FutarchySwapData {
+    const FIELDS: &'static [FieldSpec] = &[FieldSpec {
+        name: "swap_type",
+        ty: FieldType::U8,
+        docs: "0 = Buy, 1 = Sell",
+    }];
+
+    fn encode(&self) -> Vec<u8> {
+        vec![match self.swap_type {
+            SwapType::Buy => 0,
+            SwapType::Sell => 1,
+        }]
+    }
+}
+
+/// Futarchy swap instruction calling the venue directly.
+pub fn swap_instruction(
+    keys: &FutarchySwapKeys,
+    in_amount: u64,
+    minimum_out_amount: u64,
+    swap_data: &FutarchySwapData,
+) -> Instruction {
+    Instruction {
+        program_id: FUTARCHY_PROGRAM_ID,
+        accounts: keys.account_metas(),
+        data: instruction_data::<{ futarchy::MAX_INSTRUCTION_DATA_LEN }, _>(|scratch| {
+            futarchy::swap_instruction_data(scratch, in_amount, minimum_out_amount, swap_data)
+        }),
+    }
+}
@@ -0,0 +1,257 @@
+//! Gamma swap instructions.
+
+use {
+    crate::{
+        instruction_data,
+        quote::{price_impact_bps, token_amount, SwapQuote},
+        resolve::{associated_token_address, fetch, AccountSource, FetchedAccount, ResolveError},
+        AccountSpec, AdapterKeys, EncodeSwapData, FieldSpec,
+    },
+    beethoven::{
+        gamma::{
+            self,
+            quote::{quote_exact_in, reserves},
+            state::{AmmConfig, PoolState},
+            GAMMA_PROGRAM_ID,
+        },
+        ProtocolKind,
+    },
+    solana_address::Address,
+    solana_instruction::Instruction,
+};
+
+/// Addresses of [`beethoven::gamma::GammaSwapAccounts`], without the program.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GammaSwapKeys {
+    pub payer: Address,
+    pub authority: Address,
+    pub amm_config: Address,
+    pub pool_state: Address,
+    pub input_token_account: Address,
+    pub output_token_account: Address,
+    pub input_vault: Address,
+    pub output_vault: Address,
+    pub input_token_program: Address,
+    pub output_token_program: Address,
+    pub input_token_mint: Address,
+    pub output_token_mint: Address,
+    pub observation_state: Address,
+}
+
+impl AdapterKeys for GammaSwapKeys {
+    const PROTOCOL: ProtocolKind = ProtocolKind::Gamma;
+
+    const PROGRAM_ID: Address = GAMMA_PROGRAM_ID;
+
+    const ACCOUNTS: &'static [AccountSpec] = &[
+        AccountSpec::readonly_signer("payer"),
+        AccountSpec::readonly("authority"),
+        AccountSpec::readonly("amm_config"),
+        AccountSpec::writable("pool_state"),
+        AccountSpec::writable("input_token_account"),
+        AccountSpec::writable("output_token_account"),
+        AccountSpec::writable("input_vault"),
+        AccountSpec::writable("output_vault"),
+        AccountSpec::readonly("input_token_program"),
+        AccountSpec::readonly("output_token_program"),
+        AccountSpec::readonly("input_token_mint"),
+        AccountSpec::readonly("output_token_mint"),
+        AccountSpec::writable("observation_state"),
+    ];
+
+    fn addresses(&self) -> Vec<Address> {
+        vec![
+            self.payer,
+            self.authority,
+            self.amm_config,
+            self.pool_state,
+            self.input_token_account,
+            self.output_token_account,
+            self.input_vault,
+            self.output_vault,
+            self.input_token_program,
+            self.output_token_program,
+            self.input_token_mint,
+            self.output_token_mint,
+            self.observation_state,
+        ]
+    }
+}
+
+/// Gamma takes no swap data
+impl EncodeSwapData for () {
+    const FIELDS: &'static [FieldSpec] = &[];
+
+    fn encode(&self) -> Vec<u8> {
+        Vec::new()
+    }
+}
+
+/// Gamma swap instruction calling the venue directly.
+pub fn swap_instruction(
+    keys: &GammaSwapKeys,
+    in_amount: u64,
+    minimum_out_amount: u64,
+) -> Instruction {
+    Instruction {
+        program_id: GAMMA_PROGRAM_ID,
+        accounts: keys.account_metas(),
+        data: instruction_data::<{ gamma::MAX_INSTRUCTION_DATA_LEN }, _>(|scratch| {
+            gamma::swap_instruction_data(scratch, in_amount, minimum_out_amount)
+        }),
+    }
+}
+
+/// Seed of the PDA that owns every Gamma pool's vaults
+pub const AUTHORITY_SEED: &[u8] = b"vault_and_lp_mint_auth_seed";
+
+/// Resolve the accounts `payer` needs to sell `input_token_mint` into `pool_state`.
+///
+/// Vaults, mints, token programs and the observation account are read from the pool,
+/// and the user token accounts are `payer`'s associated token accounts.
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(
+        name = "resolve_swap_keys",
+        skip_all,
+        fields(protocol = "gamma", pool = %pool_state)
+    )
+)]
+pub fn resolve_swap_keys<S: AccountSource>(
+    source: &S,
+    pool_state: &Address,
+    payer: &Address,
+    input_token_mint: &Address,
+) -> Result<GammaSwapKeys, ResolveError<S::Error>> {
+    let pool_account = fetch_pool(source, pool_state)?;
+    let pool = load_pool(pool_state, &pool_account.data)?;
+
+    let (input, output) = if input_is_token_0(pool, input_token_mint)? {
+        (0, 1)
+    } else {
+        (1, 0)
+    };
+    let vaults = [pool.token_0_vault, pool.token_1_vault];
+    let mints = [pool.token_0_mint, pool.token_1_mint];
+    let token_programs = [pool.token_0_program, pool.token_1_program];
+
+    Ok(GammaSwapKeys {
+        payer: *payer,
+        authority: authority_address(),
+        amm_config: pool.amm_config,
+        pool_state: *pool_state,
+        input_token_account: associated_token_address(payer, &mints[input], &token_programs[input]),
+        output_token_account: associated_token_address(
+            payer,
+            &mints[output],
+            &token_programs[output],
+        ),
+        input_vault: vaults[input],
+        output_vault: vaults[output],
+        input_token_program: token_programs[input],
+        output_token_program: token_programs[output],
+        input_token_mint: mints[input],
+        output_token_mint: mints[output],
+        observation_state: pool.observation_key,
+    })
+}
+
+/// The vault authority shared by every Gamma pool.
+pub fn authority_address() -> Address {
+    Address::find_program_address(&[AUTHORITY_SEED], &GAMMA_PROGRAM_ID).0
+}
+
+/// Quote selling `in_amount` of `input_token_mint` into `pool_state`, with the same
+/// math as [`beethoven::gamma::quote`].
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(
+        name = "quote_swap",
+        skip_all,
+        fields(protocol = "gamma", pool = %pool_state, in_amount = in_amount)
+    )
+)]
+pub fn quote_swap<S: AccountSource>(
+    source: &S,
+    pool_state: &Address,
+    input_token_mint: &Address,
+    in_amount: u64,
+) -> Result<SwapQuote, ResolveError<S::Error>> {
+    let pool_account = fetch_pool(source, pool_state)?;
+    let pool = load_pool(pool_state, &pool_account.data)?;
+
+    let input_is_token_0 = input_is_token_0(pool, input_token_mint)?;
+    let (input_vault, output_vault) = if input_is_token_0 {
+        (pool.token_0_vault, pool.token_1_vault)
+    } else {
+        (pool.token_1_vault, pool.token_0_vault)
+    };
+
+    let config_account = fetch(source, &pool.amm_config)?;
+    let config = AmmConfig::from_account_data(&config_account.data)
+        .map_err(|_| ResolveError::InvalidAccountData(pool.amm_config))?;
+    let input_vault_amount = token_amount(&input_vault, &fetch(source, &input_vault)?.data)?;
+    let output_vault_amount = token_amount(&output_vault, &fetch(source, &output_vault)?.data)?;
+
+    let (input_reserve, output_reserve) = reserves(
+        pool,
+        input_is_token_0,
+        input_vault_amount,
+        output_vault_amount,
+    )
+    .ok_or(ResolveError::InvalidAccountData(*pool_state))?;
+    let quote = quote_exact_in(config, in_amount, input_reserve, output_reserve)
+        .ok_or(ResolveError::InvalidAccountData(*pool_state))?;
+
+    let spot_out_amount = if input_reserve == 0 {
+        0
+    } else {
+        (in_amount - quote.trade_fee) as u128 * output_reserve as u128 / input_reserve as u128
+    };
+
+    let price_impact_bps = price_impact_bps(quote.out_amount, spot_out_amount);
+    debug_event!(
+        out_amount = quote.out_amount,
+        fee_amount = quote.trade_fee,
+        price_impact_bps,
+        "quoted"
+    );
+
+    Ok(SwapQuote {
+        in_amount,
+        out_amount: quote.out_amount,
+        fee_amount: quote.trade_fee,
+        price_impact_bps,
+    })
+}
+
+fn fetch_pool<S: AccountSource>(
+    source: &S,
+    pool_state: &Address,
+) -> Result<FetchedAccount, ResolveError<S::Error>> {
+    let account = fetch(source, pool_state)?;
+    if account.owner != GAMMA_PROGRAM_ID {
+        return Err(ResolveError::InvalidAccountData(*pool_state));
+    }
+    Ok(account)
+}
+
+fn load_pool<'a, E>(
+    pool_state: &Address,
+    data: &'a [u8],
+) -> Result<&'a PoolState, ResolveError<E>> {
+    PoolState::from_account_data(data).map_err(|_| ResolveError::InvalidAccountData(*pool_state))
+}
+
+fn input_is_token_0<E>(
+    pool: &PoolState,
+    input_token_mint: &Address,
+) -> Result<bool, ResolveError<E>> {
+    if pool.token_0_mint == *input_token_mint {
+        Ok(true)
+    } else if pool.token_1_mint == *input_token_mint {
+        Ok(false)
+    } else {
+        Err(ResolveError::MintNotInPool(*input_token_mint))
+    }
+}
@@ -0,0 +1,123 @@
+//! Heaven swap instructions.
+
+use {
+    crate::{instruction_data, AccountSpec, AdapterKeys, EncodeSwapData, FieldSpec, FieldType},
+    beethoven::{
+        heaven::{self, HeavenSwapData, SwapDirection, HEAVEN_PROGRAM_ID},
+        ProtocolKind,
+    },
+    solana_address::Address,
+    solana_instruction::Instruction,
+};
+
+/// Addresses of [`beethoven::heaven::HeavenSwapAccounts`], without the program.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HeavenSwapKeys {
+    pub token_a_owner: Address,
+    pub token_b_owner: Address,
+    pub ata_program: Address,
+    pub system_program: Address,
+    pub pool_state: Address,
+    pub user: Address,
+    pub token_a_mint: Address,
+    pub token_b_mint: Address,
+    pub user_token_a_account: Address,
+    pub user_token_b_account: Address,
+    pub pool_token_a_account: Address,
+    pub pool_token_b_account: Address,
+    pub protocol_config: Address,
+    pub ix_sysvar: Address,
+    pub chainlink_id: Address,
+    pub chainlink_sol_usd_feed: Address,
+}
+
+impl AdapterKeys for HeavenSwapKeys {
+    const PROTOCOL: ProtocolKind = ProtocolKind::Heaven;
+
+    const PROGRAM_ID: Address = HEAVEN_PROGRAM_ID;
+
+    const ACCOUNTS: &'static [AccountSpec] = &[
+        AccountSpec::readonly("token_a_owner"),
+        AccountSpec::readonly("token_b_owner"),
+        AccountSpec::readonly("ata_program"),
+        AccountSpec::readonly("system_program"),
+        AccountSpec::writable("pool_state"),
+        AccountSpec::readonly_signer("user"),
+        AccountSpec::readonly("token_a_mint"),
+        AccountSpec::readonly("token_b_mint"),
+        AccountSpec::writable("user_token_a_account"),
+        AccountSpec::writable("user_token_b_account"),
+        AccountSpec::writable("pool_token_a_account"),
+        AccountSpec::writable("pool_token_b_account"),
+        AccountSpec::writable("protocol_config"),
+        AccountSpec::readonly("ix_sysvar"),
+        AccountSpec::readonly("chainlink_id"),
+        AccountSpec::readonly("chainlink_sol_usd_feed"),
+    ];
+
+    fn addresses(&self) -> Vec<Address> {
+        vec![
+            self.token_a_owner,
+            self.token_b_owner,
+            self.ata_program,
+            self.system_program,
+            self.pool_state,
+            self.user,
+            self.token_a_mint,
+            self.token_b_mint,
+            self.user_token_a_account,
+            self.user_token_b_account,
+            self.pool_token_a_account,
+            self.pool_token_b_account,
+            self.protocol_config,
+            self.ix_sysvar,
+            self.chainlink_id,
+            self.chainlink_sol_usd_feed,
+        ]
+    }
+}
+
+impl EncodeSwapData for HeavenSwapData<'_> {
+    const FIELDS: &'static [FieldSpec] = &[
+        FieldSpec {
+            name: "direction",
+            ty: FieldType::U8,
+            docs: "0 = Buy, 1 = Sell",
+        },
+        FieldSpec {
+            name: "event",
+            ty: FieldType::RemainingBytes,
+            docs: "Event payload forwarded to Heaven as-is",
+        },
+    ];
+
+    fn encode(&self) -> Vec<u8> {
+        let direction = match self.direction {
+            SwapDirection::Buy => 0,
+            SwapDirection::Sell => 1,
+        };
+        let mut data = vec![direction];
+        data.extend_from_slice(self.event);
+        data
+    }
+}
+
+/// Heaven swap instruction calling the venue directly.
+///
+/// # Panics
+///
+/// If `swap_data.event` is longer than [`heaven::MAX_EVENT_LEN`].
+pub fn swap_instruction(
+    keys: &HeavenSwapKeys,
+    in_amount: u64,
+    minimum_out_amount: u64,
+    swap_data: &HeavenSwapData<'_>,
+) -> Instruction {
+    Instruction {
+        program_id: HEAVEN_PROGRAM_ID,
+        accounts: keys.account_metas(),
+        data: instruction_data::<{ heaven::MAX_INSTRUCTION_DATA_LEN }, _>(|scratch| {
+            heaven::swap_instruction_data(scratch, in_amount, minimum_out_amount, swap_data)
+        }),
+    }
+}
@@ -0,0 +1,175 @@
+//! Anchor IDL (spec `0.1.0`) for the `beethoven-test` program in `program-test`.
+//!
+//! The program takes one deposit and one swap instruction whose accounts depend on the
+//! venue, so the IDL lists one instruction per enabled venue (`deposit_kamino`,
+//! `swap_manifest`, ...), sharing the program's discriminator. Accounts come from each
+//! adapter's [`AdapterKeys::ACCOUNTS`], swap data from [`EncodeSwapData::FIELDS`] and
+//! deposit data from [`EncodeDepositData::FIELDS`], so the IDL follows the adapters as
+//! they change. Codama and Anchor's client generators both read this format.
+
+use {
+    crate::{
+        test_program::discriminator, AccountSpec, AdapterKeys, EncodeDepositData, EncodeSwapData,
+        FieldSpec, FieldType,
+    },
+    serde_json::{json, Value},
+    solana_address::Address,
+};
+
+/// IDL of `beethoven-test` deployed at `program_id`, for every venue enabled in this
+/// build of the client.
+// One `push` per venue feature, each compiled out independently
+#[allow(clippy::vec_init_then_push, unused_mut)]
+pub fn test_program_idl(program_id: &Address) -> Value {
+    let mut instructions: Vec<Value> = Vec::new();
+
+    #[cfg(feature = "kamino-deposit")]
+    instructions.push(deposit_instruction::<
+        crate::kamino::KaminoDepositKeys,
+        beethoven::kamino::KaminoDepositData,
+    >(
+        "kamino",
+        &["Followed by every reserve the obligation references, writable."],
+    ));
+    #[cfg(feature = "jupiter-deposit")]
+    instructions.push(deposit_instruction::<
+        crate::jupiter::JupiterEarnDepositKeys,
+        beethoven::jupiter::JupiterEarnDepositData,
+    >("jupiter", &[]));
+
+    #[cfg(feature = "perena-swap")]
+    instructions.push(swap_instruction::<
+        crate::perena::PerenaSwapKeys,
+        beethoven::perena::PerenaSwapData,
+    >("perena"));
+    #[cfg(feature = "solfi-swap")]
+    instructions.push(swap_instruction::<
+        crate::solfi::SolFiSwapKeys,
+        beethoven::solfi::SolFiSwapData,
+    >("solfi"));
+    #[cfg(feature = "solfi_v2-swap")]
+    instructions.push(swap_instruction::<
+        crate::solfi_v2::SolFiV2SwapKeys,
+        beethoven::solfi_v2::SolFiV2SwapData,
+    >("solfi_v2"));
+    #[cfg(feature = "manifest-swap")]
+    instructions.push(swap_instruction::<
+        crate::manifest::ManifestSwapKeys,
+        beethoven::manifest::ManifestSwapData,
+    >("manifest"));
+    #[cfg(feature = "heaven-swap")]
+    instructions.push(swap_instruction::<
+        crate::heaven::HeavenSwapKeys,
+        beethoven::heaven::HeavenSwapData<'static>,
+    >("heaven"));
+    #[cfg(feature = "aldrin-swap")]
+    instructions.push(swap_instruction::<
+        crate::aldrin::AldrinSwapKeys,
+        beethoven::aldrin::AldrinSwapData,
+    >("aldrin"));
+    #[cfg(feature = "aldrin_v2-swap")]
+    instructions.push(swap_instruction::<
+        crate::aldrin_v2::AldrinV2SwapKeys,
+        beethoven::aldrin_v2::AldrinV2SwapData,
+    >("aldrin_v2"));
+    #[cfg(feature = "futarchy-swap")]
+    instructions.push(swap_instruction::<
+        crate::futarchy::FutarchySwapKeys,
+        beethoven::futarchy::FutarchySwapData,
+    >("futarchy"));
+    #[cfg(feature = "gamma-swap")]
+    instructions.push(swap_instruction::<crate::gamma::GammaSwapKeys, ()>("gamma"));
+    #[cfg(feature = "mock_dex-swap")]
+    instructions.push(swap_instruction::<
+        crate::mock_dex::MockDexSwapKeys,
+        beethoven::mock_dex::MockDexSwapData,
+    >("mock_dex"));
+
+    json!({
+        "address": program_id.to_string(),
+        "metadata": {
+            "name": "beethoven_test",
+            "version": env!("CARGO_PKG_VERSION"),
+            "spec": "0.1.0",
+            "description": "Routes deposits and swaps to the venue named by the first account",
+        },
+        "instructions": instructions,
+    })
+}
+
+fn deposit_instruction<K: AdapterKeys, D: EncodeDepositData>(venue: &str, docs: &[&str]) -> Value {
+    let mut args = vec![json!({ "name": "amount", "type": "u64" })];
+    args.extend(D::FIELDS.iter().map(field));
+
+    json!({
+        "name": format!("deposit_{venue}"),
+        "docs": docs,
+        "discriminator": [discriminator::DEPOSIT],
+        "accounts": accounts::<K>(venue),
+        "args": args,
+    })
+}
+
+fn swap_instruction<K: AdapterKeys, D: EncodeSwapData>(venue: &str) -> Value {
+    let mut args = vec![
+        json!({ "name": "in_amount", "type": "u64" }),
+        json!({ "name": "minimum_out_amount", "type": "u64" }),
+    ];
+    args.extend(D::FIELDS.iter().map(field));
+
+    json!({
+        "name": format!("swap_{venue}"),
+        "discriminator": [discriminator::SWAP],
+        "accounts": accounts::<K>(venue),
+        "args": args,
+    })
+}
+
+/// The detector account, pinned to the venue's program, then the adapter's accounts.
+fn accounts<K: AdapterKeys>(venue: &str) -> Vec<Value> {
+    let mut accounts = vec![json!({
+        "name": format!("{venue}_program"),
+        "address": K::PROGRAM_ID.to_string(),
+    })];
+    accounts.extend(K::ACCOUNTS.iter().map(account));
+    accounts
+}
+
+fn account(spec: &AccountSpec) -> Value {
+    let mut account = json!({ "name": spec.name });
+    if spec.writable {
+        account["writable"] = json!(true);
+    }
+    if spec.signer {
+        account["signer"] = json!(true);
+    }
+    account
+}
+
+fn field(spec: &FieldSpec) -> Value {
+    // The IDL has no type for an unprefixed tail, so it is described as `bytes` and
+    // flagged in the docs
+    let (ty, note) = match spec.ty {
+        FieldType::U8 => ("u8", None),
+        FieldType::Bool => ("bool", None),
+        FieldType::OptionalU64 => (
+            "u64",
+            Some("Optional: omitted from the instruction data when unset, with no presence flag."),
+        ),
+        FieldType::RemainingBytes => (
+            "bytes",
+            Some("Encoded as the remaining instruction data, without a length prefix."),
+        ),
+    };
+    let docs: Vec<&str> = [spec.docs]
+        .into_iter()
+        .filter(|doc| !doc.is_empty())
+        .chain(note)
+        .collect();
+
+    let mut field = json!({ "name": spec.name, "type": ty });
+    if !docs.is_empty() {
+        field["docs"] = json!(docs);
+    }
+    field
+}
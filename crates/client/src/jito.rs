@@ -0,0 +1,118 @@
+//! Packaging assembled swaps into a Jito bundle.
+//!
+//! A bundle is up to [`MAX_BUNDLE_TRANSACTIONS`] transactions the block engine lands
+//! atomically and in order, paid for by a SOL transfer to one of the
+//! [`JITO_TIP_ACCOUNTS`]. [`bundle_instructions`] appends that tip to the last
+//! transaction, so it is only paid if every transaction before it succeeded, and
+//! [`send_bundle_request`] builds the `sendBundle` JSON-RPC body for the signed
+//! transactions. No HTTP client is bundled; post the body to a block engine's
+//! `/api/v1/bundles` endpoint.
+
+use {
+    crate::transaction::system_transfer,
+    base64::{engine::general_purpose::STANDARD, Engine},
+    core::fmt,
+    serde_json::{json, Value},
+    solana_address::Address,
+    solana_instruction::Instruction,
+};
+
+/// Most transactions the block engine accepts in one bundle
+pub const MAX_BUNDLE_TRANSACTIONS: usize = 5;
+
+/// Smallest tip the block engine accepts, in lamports
+pub const MIN_TIP_LAMPORTS: u64 = 1_000;
+
+/// Accounts the block engine accepts tips to. Spreading tips across them avoids
+/// write-locking the same account as every other searcher.
+pub const JITO_TIP_ACCOUNTS: [Address; 8] = [
+    Address::from_str_const("96gYZGLnJYVFmbjzopPSU6QiEV5fGqZNyN9nmNhvrZU5"),
+    Address::from_str_const("HFqU5x63VTqvQss8hp11i4wVV8bD44PvwucfZ2bU7gRe"),
+    Address::from_str_const("Cw8CFyM9FkoMi7K7Crf6HNQqf4uEMzpKw6QNghXLvLkY"),
+    Address::from_str_const("ADaUMid9yfUytqMBgopwjb2DTLSokTSzL1zt6iGPaS49"),
+    Address::from_str_const("DfXygSm4jCyNCybVYYK6DwvWqjKee8pbDmJGcLWNDXjh"),
+    Address::from_str_const("ADuUkR4vqLUMWXxW9gh6D6L8pMSawimctcNZ5pGwDcEt"),
+    Address::from_str_const("DttWaMuVvTiduZRnguLF7jNxTgiMBZ1hyAumKUiL2KRL"),
+    Address::from_str_const("3AVi9Tg9Uo68tJfuvoKvqKNWKkC5wPdSSdeBnizKZ6jT"),
+];
+
+/// The tip paid for a bundle.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BundleTip {
+    /// Pays the tip; usually the owner the swaps were assembled for
+    pub payer: Address,
+    pub lamports: u64,
+    /// Index into [`JITO_TIP_ACCOUNTS`], taken modulo its length so a counter or a
+    /// random number can be passed as is
+    pub tip_account_index: usize,
+}
+
+/// Why a bundle could not be built.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BundleError {
+    /// A bundle holds between one and [`MAX_BUNDLE_TRANSACTIONS`] transactions.
+    TransactionCount(usize),
+    /// The tip is below [`MIN_TIP_LAMPORTS`].
+    TipTooSmall(u64),
+}
+
+impl fmt::Display for BundleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TransactionCount(count) => write!(
+                f,
+                "bundle of {count} transactions, expected 1 to {MAX_BUNDLE_TRANSACTIONS}"
+            ),
+            Self::TipTooSmall(lamports) => write!(
+                f,
+                "tip of {lamports} lamports is below the {MIN_TIP_LAMPORTS} lamport minimum"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for BundleError {}
+
+/// Transfer of `tip.lamports` from `tip.payer` to the tip account it selects.
+pub fn tip_instruction(tip: &BundleTip) -> Instruction {
+    let tip_account = JITO_TIP_ACCOUNTS[tip.tip_account_index % JITO_TIP_ACCOUNTS.len()];
+    system_transfer(&tip.payer, &tip_account, tip.lamports)
+}
+
+/// `transactions` (the instructions of each, e.g. from
+/// [`crate::transaction::assemble_instructions`]) with the tip appended to the last.
+pub fn bundle_instructions(
+    transactions: &[Vec<Instruction>],
+    tip: &BundleTip,
+) -> Result<Vec<Vec<Instruction>>, BundleError> {
+    if transactions.is_empty() || transactions.len() > MAX_BUNDLE_TRANSACTIONS {
+        return Err(BundleError::TransactionCount(transactions.len()));
+    }
+    if tip.lamports < MIN_TIP_LAMPORTS {
+        return Err(BundleError::TipTooSmall(tip.lamports));
+    }
+
+    let mut bundle = transactions.to_vec();
+    if let Some(last) = bundle.last_mut() {
+        last.push(tip_instruction(tip));
+    }
+    Ok(bundle)
+}
+
+/// `sendBundle` request body for `transactions`, each a signed transaction serialized
+/// to its wire format, in bundle order.
+pub fn send_bundle_request(transactions: &[Vec<u8>]) -> Result<Value, BundleError> {
+    if transactions.is_empty() || transactions.len() > MAX_BUNDLE_TRANSACTIONS {
+        return Err(BundleError::TransactionCount(transactions.len()));
+    }
+    let encoded: Vec<String> = transactions
+        .iter()
+        .map(|transaction| STANDARD.encode(transaction))
+        .collect();
+    Ok(json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "sendBundle",
+        "params": [encoded, { "encoding": "base64" }],
+    }))
+}
@@ -0,0 +1,186 @@
+//! Jupiter Earn deposit and withdraw instructions.
+
+use {
+    crate::{AccountSpec, AdapterKeys, EncodeDepositData, FieldSpec, FieldType},
+    beethoven::{
+        jupiter::{self, JUPITER_EARN_PROGRAM_ID},
+        ProtocolKind,
+    },
+    solana_address::Address,
+    solana_instruction::Instruction,
+};
+
+/// Addresses of [`beethoven::jupiter::JupiterEarnDepositAccounts`], without the program.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct JupiterEarnDepositKeys {
+    pub signer: Address,
+    pub depositor_token_account: Address,
+    pub recipient_token_account: Address,
+    pub mint: Address,
+    pub lending_admin: Address,
+    pub lending: Address,
+    pub f_token_mint: Address,
+    pub supply_token_reserves_liquidity: Address,
+    pub lending_supply_position_on_liquidity: Address,
+    pub rate_model: Address,
+    pub vault: Address,
+    pub liquidity: Address,
+    pub liquidity_program: Address,
+    pub rewards_rate_model: Address,
+    pub token_program: Address,
+    pub associated_token_program: Address,
+    pub system_program: Address,
+}
+
+impl AdapterKeys for JupiterEarnDepositKeys {
+    const PROTOCOL: ProtocolKind = ProtocolKind::Jupiter;
+
+    const PROGRAM_ID: Address = JUPITER_EARN_PROGRAM_ID;
+
+    const ACCOUNTS: &'static [AccountSpec] = &[
+        AccountSpec::writable_signer("signer"),
+        AccountSpec::writable("depositor_token_account"),
+        AccountSpec::writable("recipient_token_account"),
+        AccountSpec::readonly("mint"),
+        AccountSpec::readonly("lending_admin"),
+        AccountSpec::writable("lending"),
+        AccountSpec::writable("f_token_mint"),
+        AccountSpec::writable("supply_token_reserves_liquidity"),
+        AccountSpec::writable("lending_supply_position_on_liquidity"),
+        AccountSpec::readonly("rate_model"),
+        AccountSpec::writable("vault"),
+        AccountSpec::writable("liquidity"),
+        AccountSpec::writable("liquidity_program"),
+        AccountSpec::readonly("rewards_rate_model"),
+        AccountSpec::readonly("token_program"),
+        AccountSpec::readonly("associated_token_program"),
+        AccountSpec::readonly("system_program"),
+    ];
+
+    fn addresses(&self) -> Vec<Address> {
+        vec![
+            self.signer,
+            self.depositor_token_account,
+            self.recipient_token_account,
+            self.mint,
+            self.lending_admin,
+            self.lending,
+            self.f_token_mint,
+            self.supply_token_reserves_liquidity,
+            self.lending_supply_position_on_liquidity,
+            self.rate_model,
+            self.vault,
+            self.liquidity,
+            self.liquidity_program,
+            self.rewards_rate_model,
+            self.token_program,
+            self.associated_token_program,
+            self.system_program,
+        ]
+    }
+}
+
+/// Jupiter Earn `deposit` instruction calling the venue directly.
+pub fn deposit_instruction(keys: &JupiterEarnDepositKeys, amount: u64) -> Instruction {
+    Instruction {
+        program_id: JUPITER_EARN_PROGRAM_ID,
+        accounts: keys.account_metas(),
+        data: jupiter::deposit_instruction_data(amount).to_vec(),
+    }
+}
+
+impl EncodeDepositData for jupiter::JupiterEarnDepositData {
+    const FIELDS: &'static [FieldSpec] = &[FieldSpec {
+        name: "minimum_shares_out",
+        ty: FieldType::OptionalU64,
+        docs: "Fail with SlippageExceeded when the deposit mints fewer fTokens.",
+    }];
+
+    fn encode(&self) -> Vec<u8> {
+        self.minimum_shares_out
+            .map(|minimum| minimum.to_le_bytes().to_vec())
+            .unwrap_or_default()
+    }
+}
+
+/// Addresses of [`beethoven::jupiter::JupiterEarnWithdrawAccounts`], without the program.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct JupiterEarnWithdrawKeys {
+    pub signer: Address,
+    pub owner_token_account: Address,
+    pub recipient_token_account: Address,
+    pub lending_admin: Address,
+    pub lending: Address,
+    pub mint: Address,
+    pub f_token_mint: Address,
+    pub supply_token_reserves_liquidity: Address,
+    pub lending_supply_position_on_liquidity: Address,
+    pub rate_model: Address,
+    pub vault: Address,
+    pub claim_account: Address,
+    pub liquidity: Address,
+    pub liquidity_program: Address,
+    pub rewards_rate_model: Address,
+    pub token_program: Address,
+    pub associated_token_program: Address,
+    pub system_program: Address,
+}
+
+impl AdapterKeys for JupiterEarnWithdrawKeys {
+    const PROTOCOL: ProtocolKind = ProtocolKind::Jupiter;
+
+    const PROGRAM_ID: Address = JUPITER_EARN_PROGRAM_ID;
+
+    const ACCOUNTS: &'static [AccountSpec] = &[
+        AccountSpec::writable_signer("signer"),
+        AccountSpec::writable("owner_token_account"),
+        AccountSpec::writable("recipient_token_account"),
+        AccountSpec::readonly("lending_admin"),
+        AccountSpec::writable("lending"),
+        AccountSpec::readonly("mint"),
+        AccountSpec::writable("f_token_mint"),
+        AccountSpec::writable("supply_token_reserves_liquidity"),
+        AccountSpec::writable("lending_supply_position_on_liquidity"),
+        AccountSpec::readonly("rate_model"),
+        AccountSpec::writable("vault"),
+        AccountSpec::writable("claim_account"),
+        AccountSpec::writable("liquidity"),
+        AccountSpec::writable("liquidity_program"),
+        AccountSpec::readonly("rewards_rate_model"),
+        AccountSpec::readonly("token_program"),
+        AccountSpec::readonly("associated_token_program"),
+        AccountSpec::readonly("system_program"),
+    ];
+
+    fn addresses(&self) -> Vec<Address> {
+        vec![
+            self.signer,
+            self.owner_token_account,
+            self.recipient_token_account,
+            self.lending_admin,
+            self.lending,
+            self.mint,
+            self.f_token_mint,
+            self.supply_token_reserves_liquidity,
+            self.lending_supply_position_on_liquidity,
+            self.rate_model,
+            self.vault,
+            self.claim_account,
+            self.liquidity,
+            self.liquidity_program,
+            self.rewards_rate_model,
+            self.token_program,
+            self.associated_token_program,
+            self.system_program,
+        ]
+    }
+}
+
+/// Jupiter Earn `redeem` instruction calling the venue directly; `shares` is in fTokens.
+pub fn withdraw_instruction(keys: &JupiterEarnWithdrawKeys, shares: u64) -> Instruction {
+    Instruction {
+        program_id: JUPITER_EARN_PROGRAM_ID,
+        accounts: keys.account_metas(),
+        data: jupiter::redeem_instruction_data(shares).to_vec(),
+    }
+}
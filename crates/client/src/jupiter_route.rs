@@ -0,0 +1,188 @@
+//! Jupiter v6 quotes as Beethoven swaps.
+//!
+//! Translates each leg of a quote's `routePlan` into the routed accounts and swap data
+//! of the matching adapter, so a program can execute a route Jupiter found through its
+//! own Beethoven CPIs. A leg's venue is identified by the owner of its `ammKey`, and its
+//! accounts are filled in by the venue's resolver, so only venues with a resolver
+//! (Gamma, Manifest) are supported.
+
+pub use crate::planner::RouteLeg;
+use {
+    crate::{
+        resolve::{fetch, AccountSource, ResolveError},
+        AdapterKeys, EncodeSwapData,
+    },
+    beethoven::ProtocolKind,
+    core::fmt,
+    serde::Deserialize,
+    solana_address::Address,
+};
+
+/// The fields of a Jupiter v6 `/quote` response the converter reads.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JupiterQuote {
+    pub input_mint: String,
+    pub in_amount: String,
+    pub output_mint: String,
+    pub out_amount: String,
+    #[serde(default)]
+    pub swap_mode: Option<String>,
+    pub slippage_bps: u16,
+    pub route_plan: Vec<RoutePlanStep>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RoutePlanStep {
+    pub swap_info: SwapInfo,
+    /// Share of the step's input mint routed through this leg
+    pub percent: u8,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SwapInfo {
+    pub amm_key: String,
+    #[serde(default)]
+    pub label: String,
+    pub input_mint: String,
+    pub output_mint: String,
+    pub in_amount: String,
+    pub out_amount: String,
+}
+
+/// Why a quote could not be converted.
+#[derive(Debug)]
+pub enum RouteError<E> {
+    /// Resolving a leg's accounts failed.
+    Resolve(ResolveError<E>),
+    /// A field of the quote is malformed, or the quote is not exact-in.
+    InvalidQuote(&'static str),
+    /// The leg trades on a venue the client cannot resolve accounts for.
+    UnsupportedVenue { amm_key: Address, label: String },
+}
+
+impl<E> From<ResolveError<E>> for RouteError<E> {
+    fn from(error: ResolveError<E>) -> Self {
+        Self::Resolve(error)
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for RouteError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Resolve(error) => write!(f, "{error}"),
+            Self::InvalidQuote(field) => write!(f, "invalid quote field `{field}`"),
+            Self::UnsupportedVenue { amm_key, label } => {
+                write!(f, "unsupported venue {label} ({amm_key:?})")
+            }
+        }
+    }
+}
+
+impl<E: fmt::Debug + fmt::Display> std::error::Error for RouteError<E> {}
+
+/// The Beethoven swap for each leg of `quote`, in route order, trading from `user`'s
+/// associated token accounts.
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(
+        skip_all,
+        fields(legs = quote.route_plan.len(), input_mint = %quote.input_mint)
+    )
+)]
+pub fn route_legs<S: AccountSource>(
+    source: &S,
+    quote: &JupiterQuote,
+    user: &Address,
+) -> Result<Vec<RouteLeg>, RouteError<S::Error>> {
+    if quote
+        .swap_mode
+        .as_deref()
+        .is_some_and(|mode| mode != "ExactIn")
+    {
+        return Err(RouteError::InvalidQuote("swapMode"));
+    }
+    quote
+        .route_plan
+        .iter()
+        .map(|step| route_leg(source, &step.swap_info, quote.slippage_bps, user))
+        .collect()
+}
+
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(skip_all, fields(pool = %info.amm_key, label = %info.label))
+)]
+fn route_leg<S: AccountSource>(
+    source: &S,
+    info: &SwapInfo,
+    slippage_bps: u16,
+    user: &Address,
+) -> Result<RouteLeg, RouteError<S::Error>> {
+    let amm_key = parse_address(&info.amm_key, "ammKey")?;
+    let input_mint = parse_address(&info.input_mint, "inputMint")?;
+    let in_amount = parse_amount(&info.in_amount, "inAmount")?;
+    let out_amount = parse_amount(&info.out_amount, "outAmount")?;
+    let minimum_out_amount =
+        (out_amount as u128 * 10_000u128.saturating_sub(slippage_bps as u128) / 10_000) as u64;
+
+    let venue = fetch(source, &amm_key)?.owner;
+    let leg = |protocol: ProtocolKind, accounts, data| {
+        debug_event!(?protocol, in_amount, out_amount, "resolved leg");
+        RouteLeg {
+            protocol,
+            in_amount,
+            out_amount,
+            minimum_out_amount,
+            accounts,
+            data,
+        }
+    };
+
+    #[cfg(feature = "gamma-swap")]
+    if venue == beethoven::gamma::GAMMA_PROGRAM_ID {
+        let keys = crate::gamma::resolve_swap_keys(source, &amm_key, user, &input_mint)?;
+        return Ok(leg(
+            ProtocolKind::Gamma,
+            keys.routed_account_metas(),
+            ().encode(),
+        ));
+    }
+
+    #[cfg(feature = "manifest-swap")]
+    if venue == beethoven::manifest::MANIFEST_PROGRAM_ID {
+        let mut keys = crate::manifest::resolve_swap_keys(source, &amm_key, user, true)?;
+        let is_base_in = if input_mint == keys.base_mint {
+            true
+        } else if input_mint == keys.quote_mint {
+            (keys.global, keys.global_vault) = crate::manifest::global_addresses(&input_mint);
+            false
+        } else {
+            return Err(ResolveError::MintNotInPool(input_mint).into());
+        };
+        let data = beethoven::manifest::ManifestSwapData {
+            is_base_in,
+            is_exact_in: true,
+        };
+        return Ok(leg(
+            ProtocolKind::Manifest,
+            keys.routed_account_metas(),
+            data.encode(),
+        ));
+    }
+
+    Err(RouteError::UnsupportedVenue {
+        amm_key,
+        label: info.label.clone(),
+    })
+}
+
+fn parse_address<E>(value: &str, field: &'static str) -> Result<Address, RouteError<E>> {
+    value.parse().map_err(|_| RouteError::InvalidQuote(field))
+}
+
+fn parse_amount<E>(value: &str, field: &'static str) -> Result<u64, RouteError<E>> {
+    value.parse().map_err(|_| RouteError::InvalidQuote(field))
+}
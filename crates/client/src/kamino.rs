@@ -0,0 +1,679 @@
+//! Kamino Lend deposit, withdraw, borrow, repay and flash loan instructions.
+
+use {
+    crate::{
+        account_metas,
+        resolve::{associated_token_address, fetch, read_address, AccountSource, ResolveError},
+        transaction::TOKEN_PROGRAM_ID,
+        AccountSpec, AdapterKeys, EncodeDepositData, FieldSpec, FieldType,
+    },
+    beethoven::{
+        kamino::{self, KAMINO_LEND_PROGRAM_ID},
+        ProtocolKind,
+    },
+    solana_address::Address,
+    solana_instruction::{AccountMeta, Instruction},
+};
+
+/// Accounts of the `deposit_reserve_liquidity_and_obligation_collateral_v2` instruction
+/// itself; the adapter additionally takes the Scope oracle and obligation reserves
+const DEPOSIT_ACCOUNTS: usize = 17;
+
+/// Accounts of the `withdraw_obligation_collateral_and_redeem_reserve_collateral_v2`
+/// instruction itself; the adapter additionally takes the Scope oracle and obligation
+/// reserves
+const WITHDRAW_ACCOUNTS: usize = 17;
+
+/// Accounts of the `borrow_obligation_liquidity_v2` instruction itself
+const BORROW_ACCOUNTS: usize = 15;
+
+/// Accounts of the `repay_obligation_liquidity_v2` instruction itself
+const REPAY_ACCOUNTS: usize = 13;
+
+/// Addresses of [`beethoven::kamino::KaminoDepositAccounts`], without the program.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KaminoDepositKeys {
+    pub owner: Address,
+    pub obligation: Address,
+    pub lending_market: Address,
+    pub lending_market_authority: Address,
+    pub reserve: Address,
+    pub reserve_liquidity_mint: Address,
+    pub reserve_liquidity_supply: Address,
+    pub reserve_collateral_mint: Address,
+    pub reserve_destination_deposit_collateral: Address,
+    pub user_source_liquidity: Address,
+    pub placeholder_user_destination_collateral: Address,
+    pub collateral_token_program: Address,
+    pub liquidity_token_program: Address,
+    pub instruction_sysvar_account: Address,
+    pub obligation_farm_user_state: Address,
+    pub reserve_farm_state: Address,
+    pub farms_program: Address,
+    pub scope_oracle: Address,
+    /// Reserves the obligation already references, refreshed before the deposit
+    pub reserve_accounts: Vec<Address>,
+}
+
+impl AdapterKeys for KaminoDepositKeys {
+    const PROTOCOL: ProtocolKind = ProtocolKind::Kamino;
+
+    const PROGRAM_ID: Address = KAMINO_LEND_PROGRAM_ID;
+
+    const ACCOUNTS: &'static [AccountSpec] = &[
+        AccountSpec::writable_signer("owner"),
+        AccountSpec::writable("obligation"),
+        AccountSpec::readonly("lending_market"),
+        AccountSpec::readonly("lending_market_authority"),
+        AccountSpec::writable("reserve"),
+        AccountSpec::readonly("reserve_liquidity_mint"),
+        AccountSpec::writable("reserve_liquidity_supply"),
+        AccountSpec::writable("reserve_collateral_mint"),
+        AccountSpec::writable("reserve_destination_deposit_collateral"),
+        AccountSpec::writable("user_source_liquidity"),
+        AccountSpec::readonly("placeholder_user_destination_collateral"),
+        AccountSpec::readonly("collateral_token_program"),
+        AccountSpec::readonly("liquidity_token_program"),
+        AccountSpec::readonly("instruction_sysvar_account"),
+        AccountSpec::writable("obligation_farm_user_state"),
+        AccountSpec::writable("reserve_farm_state"),
+        AccountSpec::readonly("farms_program"),
+        AccountSpec::readonly("scope_oracle"),
+    ];
+
+    fn addresses(&self) -> Vec<Address> {
+        vec![
+            self.owner,
+            self.obligation,
+            self.lending_market,
+            self.lending_market_authority,
+            self.reserve,
+            self.reserve_liquidity_mint,
+            self.reserve_liquidity_supply,
+            self.reserve_collateral_mint,
+            self.reserve_destination_deposit_collateral,
+            self.user_source_liquidity,
+            self.placeholder_user_destination_collateral,
+            self.collateral_token_program,
+            self.liquidity_token_program,
+            self.instruction_sysvar_account,
+            self.obligation_farm_user_state,
+            self.reserve_farm_state,
+            self.farms_program,
+            self.scope_oracle,
+        ]
+    }
+
+    fn account_metas(&self) -> Vec<AccountMeta> {
+        let mut metas = account_metas(Self::ACCOUNTS, &self.addresses());
+        // `refresh_reserves_batch` writes every obligation reserve
+        metas.extend(
+            self.reserve_accounts
+                .iter()
+                .map(|reserve| AccountMeta::new(*reserve, false)),
+        );
+        metas
+    }
+}
+
+/// Kamino `deposit_reserve_liquidity_and_obligation_collateral_v2` instruction calling
+/// the venue directly.
+///
+/// Unlike the adapter, this does not refresh the reserves and obligation first; the
+/// transaction must do so earlier in the same slot.
+pub fn deposit_instruction(keys: &KaminoDepositKeys, amount: u64) -> Instruction {
+    let mut accounts = keys.account_metas();
+    accounts.truncate(DEPOSIT_ACCOUNTS);
+
+    Instruction {
+        program_id: KAMINO_LEND_PROGRAM_ID,
+        accounts,
+        data: kamino::deposit_instruction_data(amount).to_vec(),
+    }
+}
+
+impl EncodeDepositData for kamino::KaminoDepositData {
+    const FIELDS: &'static [FieldSpec] = &[FieldSpec {
+        name: "minimum_collateral_out",
+        ty: FieldType::OptionalU64,
+        docs: "Fail with SlippageExceeded when the deposit mints less reserve collateral.",
+    }];
+
+    fn encode(&self) -> Vec<u8> {
+        self.minimum_collateral_out
+            .map(|minimum| minimum.to_le_bytes().to_vec())
+            .unwrap_or_default()
+    }
+}
+
+/// Addresses of [`beethoven::kamino::KaminoWithdrawAccounts`], without the program.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KaminoWithdrawKeys {
+    pub owner: Address,
+    pub obligation: Address,
+    pub lending_market: Address,
+    pub lending_market_authority: Address,
+    pub withdraw_reserve: Address,
+    pub reserve_liquidity_mint: Address,
+    pub reserve_source_collateral: Address,
+    pub reserve_collateral_mint: Address,
+    pub reserve_liquidity_supply: Address,
+    pub user_destination_liquidity: Address,
+    pub placeholder_user_destination_collateral: Address,
+    pub collateral_token_program: Address,
+    pub liquidity_token_program: Address,
+    pub instruction_sysvar_account: Address,
+    pub obligation_farm_user_state: Address,
+    pub reserve_farm_state: Address,
+    pub farms_program: Address,
+    pub scope_oracle: Address,
+    /// Reserves the obligation already references, refreshed before the withdraw
+    pub reserve_accounts: Vec<Address>,
+}
+
+impl AdapterKeys for KaminoWithdrawKeys {
+    const PROTOCOL: ProtocolKind = ProtocolKind::Kamino;
+
+    const PROGRAM_ID: Address = KAMINO_LEND_PROGRAM_ID;
+
+    const ACCOUNTS: &'static [AccountSpec] = &[
+        AccountSpec::writable_signer("owner"),
+        AccountSpec::writable("obligation"),
+        AccountSpec::readonly("lending_market"),
+        AccountSpec::readonly("lending_market_authority"),
+        AccountSpec::writable("withdraw_reserve"),
+        AccountSpec::readonly("reserve_liquidity_mint"),
+        AccountSpec::writable("reserve_source_collateral"),
+        AccountSpec::writable("reserve_collateral_mint"),
+        AccountSpec::writable("reserve_liquidity_supply"),
+        AccountSpec::writable("user_destination_liquidity"),
+        AccountSpec::readonly("placeholder_user_destination_collateral"),
+        AccountSpec::readonly("collateral_token_program"),
+        AccountSpec::readonly("liquidity_token_program"),
+        AccountSpec::readonly("instruction_sysvar_account"),
+        AccountSpec::writable("obligation_farm_user_state"),
+        AccountSpec::writable("reserve_farm_state"),
+        AccountSpec::readonly("farms_program"),
+        AccountSpec::readonly("scope_oracle"),
+    ];
+
+    fn addresses(&self) -> Vec<Address> {
+        vec![
+            self.owner,
+            self.obligation,
+            self.lending_market,
+            self.lending_market_authority,
+            self.withdraw_reserve,
+            self.reserve_liquidity_mint,
+            self.reserve_source_collateral,
+            self.reserve_collateral_mint,
+            self.reserve_liquidity_supply,
+            self.user_destination_liquidity,
+            self.placeholder_user_destination_collateral,
+            self.collateral_token_program,
+            self.liquidity_token_program,
+            self.instruction_sysvar_account,
+            self.obligation_farm_user_state,
+            self.reserve_farm_state,
+            self.farms_program,
+            self.scope_oracle,
+        ]
+    }
+
+    fn account_metas(&self) -> Vec<AccountMeta> {
+        let mut metas = account_metas(Self::ACCOUNTS, &self.addresses());
+        // `refresh_reserves_batch` writes every obligation reserve
+        metas.extend(
+            self.reserve_accounts
+                .iter()
+                .map(|reserve| AccountMeta::new(*reserve, false)),
+        );
+        metas
+    }
+}
+
+/// Kamino `withdraw_obligation_collateral_and_redeem_reserve_collateral_v2` instruction
+/// calling the venue directly; `amount` is in collateral.
+///
+/// Like [`deposit_instruction`], this does not refresh the reserves and obligation
+/// first.
+pub fn withdraw_instruction(keys: &KaminoWithdrawKeys, amount: u64) -> Instruction {
+    let mut accounts = keys.account_metas();
+    accounts.truncate(WITHDRAW_ACCOUNTS);
+
+    Instruction {
+        program_id: KAMINO_LEND_PROGRAM_ID,
+        accounts,
+        data: kamino::withdraw_instruction_data(amount).to_vec(),
+    }
+}
+
+/// Addresses of [`beethoven::kamino::KaminoBorrowAccounts`], without the program.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KaminoBorrowKeys {
+    pub owner: Address,
+    pub obligation: Address,
+    pub lending_market: Address,
+    pub lending_market_authority: Address,
+    pub borrow_reserve: Address,
+    pub borrow_reserve_liquidity_mint: Address,
+    pub reserve_source_liquidity: Address,
+    pub borrow_reserve_liquidity_fee_receiver: Address,
+    pub user_destination_liquidity: Address,
+    /// [`KAMINO_LEND_PROGRAM_ID`] without a referrer
+    pub referrer_token_state: Address,
+    pub token_program: Address,
+    pub instruction_sysvar_account: Address,
+    pub obligation_farm_user_state: Address,
+    pub reserve_farm_state: Address,
+    pub farms_program: Address,
+    pub scope_oracle: Address,
+    /// Reserves the obligation already references, refreshed before the borrow
+    pub reserve_accounts: Vec<Address>,
+}
+
+impl AdapterKeys for KaminoBorrowKeys {
+    const PROTOCOL: ProtocolKind = ProtocolKind::Kamino;
+
+    const PROGRAM_ID: Address = KAMINO_LEND_PROGRAM_ID;
+
+    const ACCOUNTS: &'static [AccountSpec] = &[
+        AccountSpec::readonly_signer("owner"),
+        AccountSpec::writable("obligation"),
+        AccountSpec::readonly("lending_market"),
+        AccountSpec::readonly("lending_market_authority"),
+        AccountSpec::writable("borrow_reserve"),
+        AccountSpec::readonly("borrow_reserve_liquidity_mint"),
+        AccountSpec::writable("reserve_source_liquidity"),
+        AccountSpec::writable("borrow_reserve_liquidity_fee_receiver"),
+        AccountSpec::writable("user_destination_liquidity"),
+        AccountSpec::writable("referrer_token_state"),
+        AccountSpec::readonly("token_program"),
+        AccountSpec::readonly("instruction_sysvar_account"),
+        AccountSpec::writable("obligation_farm_user_state"),
+        AccountSpec::writable("reserve_farm_state"),
+        AccountSpec::readonly("farms_program"),
+        AccountSpec::readonly("scope_oracle"),
+    ];
+
+    fn addresses(&self) -> Vec<Address> {
+        vec![
+            self.owner,
+            self.obligation,
+            self.lending_market,
+            self.lending_market_authority,
+            self.borrow_reserve,
+            self.borrow_reserve_liquidity_mint,
+            self.reserve_source_liquidity,
+            self.borrow_reserve_liquidity_fee_receiver,
+            self.user_destination_liquidity,
+            self.referrer_token_state,
+            self.token_program,
+            self.instruction_sysvar_account,
+            self.obligation_farm_user_state,
+            self.reserve_farm_state,
+            self.farms_program,
+            self.scope_oracle,
+        ]
+    }
+
+    fn account_metas(&self) -> Vec<AccountMeta> {
+        let mut metas = account_metas(Self::ACCOUNTS, &self.addresses());
+        // `refresh_reserves_batch` writes every obligation reserve
+        metas.extend(
+            self.reserve_accounts
+                .iter()
+                .map(|reserve| AccountMeta::new(*reserve, false)),
+        );
+        metas
+    }
+}
+
+/// Kamino `borrow_obligation_liquidity_v2` instruction calling the venue directly.
+///
+/// Like [`deposit_instruction`], this does not refresh the reserves and obligation
+/// first.
+pub fn borrow_instruction(keys: &KaminoBorrowKeys, amount: u64) -> Instruction {
+    let mut accounts = keys.account_metas();
+    accounts.truncate(BORROW_ACCOUNTS);
+
+    Instruction {
+        program_id: KAMINO_LEND_PROGRAM_ID,
+        accounts,
+        data: kamino::borrow_instruction_data(amount).to_vec(),
+    }
+}
+
+/// Addresses of [`beethoven::kamino::KaminoRepayAccounts`], without the program.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KaminoRepayKeys {
+    pub owner: Address,
+    pub obligation: Address,
+    pub lending_market: Address,
+    pub repay_reserve: Address,
+    pub reserve_liquidity_mint: Address,
+    pub reserve_destination_liquidity: Address,
+    pub user_source_liquidity: Address,
+    pub token_program: Address,
+    pub instruction_sysvar_account: Address,
+    pub obligation_farm_user_state: Address,
+    pub reserve_farm_state: Address,
+    pub lending_market_authority: Address,
+    pub farms_program: Address,
+    pub scope_oracle: Address,
+    /// Reserves the obligation already references, refreshed before the repay
+    pub reserve_accounts: Vec<Address>,
+}
+
+impl AdapterKeys for KaminoRepayKeys {
+    const PROTOCOL: ProtocolKind = ProtocolKind::Kamino;
+
+    const PROGRAM_ID: Address = KAMINO_LEND_PROGRAM_ID;
+
+    const ACCOUNTS: &'static [AccountSpec] = &[
+        AccountSpec::readonly_signer("owner"),
+        AccountSpec::writable("obligation"),
+        AccountSpec::readonly("lending_market"),
+        AccountSpec::writable("repay_reserve"),
+        AccountSpec::readonly("reserve_liquidity_mint"),
+        AccountSpec::writable("reserve_destination_liquidity"),
+        AccountSpec::writable("user_source_liquidity"),
+        AccountSpec::readonly("token_program"),
+        AccountSpec::readonly("instruction_sysvar_account"),
+        AccountSpec::writable("obligation_farm_user_state"),
+        AccountSpec::writable("reserve_farm_state"),
+        AccountSpec::readonly("lending_market_authority"),
+        AccountSpec::readonly("farms_program"),
+        AccountSpec::readonly("scope_oracle"),
+    ];
+
+    fn addresses(&self) -> Vec<Address> {
+        vec![
+            self.owner,
+            self.obligation,
+            self.lending_market,
+            self.repay_reserve,
+            self.reserve_liquidity_mint,
+            self.reserve_destination_liquidity,
+            self.user_source_liquidity,
+            self.token_program,
+            self.instruction_sysvar_account,
+            self.obligation_farm_user_state,
+            self.reserve_farm_state,
+            self.lending_market_authority,
+            self.farms_program,
+            self.scope_oracle,
+        ]
+    }
+
+    fn account_metas(&self) -> Vec<AccountMeta> {
+        let mut metas = account_metas(Self::ACCOUNTS, &self.addresses());
+        // `refresh_reserves_batch` writes every obligation reserve
+        metas.extend(
+            self.reserve_accounts
+                .iter()
+                .map(|reserve| AccountMeta::new(*reserve, false)),
+        );
+        metas
+    }
+}
+
+/// Kamino `repay_obligation_liquidity_v2` instruction calling the venue directly.
+///
+/// Like [`deposit_instruction`], this does not refresh the reserves and obligation
+/// first.
+pub fn repay_instruction(keys: &KaminoRepayKeys, amount: u64) -> Instruction {
+    let mut accounts = keys.account_metas();
+    accounts.truncate(REPAY_ACCOUNTS);
+
+    Instruction {
+        program_id: KAMINO_LEND_PROGRAM_ID,
+        accounts,
+        data: kamino::repay_instruction_data(amount).to_vec(),
+    }
+}
+
+/// Accounts of Kamino's `flash_borrow_reserve_liquidity` and
+/// `flash_repay_reserve_liquidity`, which take the same accounts in the same order.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KaminoFlashloanKeys {
+    pub user_transfer_authority: Address,
+    pub lending_market_authority: Address,
+    pub lending_market: Address,
+    pub reserve: Address,
+    pub reserve_liquidity_mint: Address,
+    pub reserve_liquidity_supply: Address,
+    pub user_liquidity: Address,
+    pub reserve_liquidity_fee_receiver: Address,
+    /// [`KAMINO_LEND_PROGRAM_ID`] without a referrer
+    pub referrer_token_state: Address,
+    /// [`KAMINO_LEND_PROGRAM_ID`] without a referrer
+    pub referrer_account: Address,
+    pub instruction_sysvar_account: Address,
+    pub token_program: Address,
+}
+
+impl KaminoFlashloanKeys {
+    pub fn account_metas(&self) -> Vec<AccountMeta> {
+        vec![
+            AccountMeta::new_readonly(self.user_transfer_authority, true),
+            AccountMeta::new_readonly(self.lending_market_authority, false),
+            AccountMeta::new_readonly(self.lending_market, false),
+            AccountMeta::new(self.reserve, false),
+            AccountMeta::new_readonly(self.reserve_liquidity_mint, false),
+            AccountMeta::new(self.reserve_liquidity_supply, false),
+            AccountMeta::new(self.user_liquidity, false),
+            AccountMeta::new(self.reserve_liquidity_fee_receiver, false),
+            AccountMeta::new(self.referrer_token_state, false),
+            AccountMeta::new(self.referrer_account, false),
+            AccountMeta::new_readonly(self.instruction_sysvar_account, false),
+            AccountMeta::new_readonly(self.token_program, false),
+        ]
+    }
+}
+
+/// Kamino `flash_borrow_reserve_liquidity` instruction calling the venue directly.
+///
+/// Kamino rejects flash loans issued through CPI, so a program using the liquidity sits
+/// between this and [`flash_repay_instruction`] as top-level instructions; see
+/// [`flashloan_instructions`].
+pub fn flash_borrow_instruction(keys: &KaminoFlashloanKeys, amount: u64) -> Instruction {
+    Instruction {
+        program_id: KAMINO_LEND_PROGRAM_ID,
+        accounts: keys.account_metas(),
+        data: kamino::flash_borrow_instruction_data(amount).to_vec(),
+    }
+}
+
+/// Kamino `flash_repay_reserve_liquidity` instruction repaying the flash borrow at
+/// `borrow_instruction_index` in the transaction.
+pub fn flash_repay_instruction(
+    keys: &KaminoFlashloanKeys,
+    amount: u64,
+    borrow_instruction_index: u8,
+) -> Instruction {
+    Instruction {
+        program_id: KAMINO_LEND_PROGRAM_ID,
+        accounts: keys.account_metas(),
+        data: kamino::flash_repay_instruction_data(amount, borrow_instruction_index).to_vec(),
+    }
+}
+
+/// `instructions` between a flash borrow and repay of `amount`, the borrow landing at
+/// `borrow_instruction_index` in the transaction (after e.g. its compute budget
+/// instructions) and the repay pointing back at it.
+pub fn flashloan_instructions(
+    keys: &KaminoFlashloanKeys,
+    amount: u64,
+    borrow_instruction_index: u8,
+    instructions: impl IntoIterator<Item = Instruction>,
+) -> Vec<Instruction> {
+    let mut flashloan = vec![flash_borrow_instruction(keys, amount)];
+    flashloan.extend(instructions);
+    flashloan.push(flash_repay_instruction(
+        keys,
+        amount,
+        borrow_instruction_index,
+    ));
+    flashloan
+}
+
+/// Kamino Farms, which tracks each obligation's stake in a reserve's farm
+pub const FARMS_PROGRAM_ID: Address =
+    Address::from_str_const("FarmsPZpWu9i7Kky8tPN37rs2TpmMrAZrC7S7vJa91Hr");
+pub const INSTRUCTIONS_SYSVAR_ID: Address =
+    Address::from_str_const("Sysvar1nstructions1111111111111111111111111");
+
+/// Offsets of the `Reserve` fields the resolver reads, discriminator included
+const RESERVE_LENDING_MARKET_OFFSET: usize = 32;
+const RESERVE_FARM_COLLATERAL_OFFSET: usize = 64;
+const RESERVE_LIQUIDITY_MINT_OFFSET: usize = 128;
+const RESERVE_LIQUIDITY_SUPPLY_OFFSET: usize = 160;
+const RESERVE_LIQUIDITY_TOKEN_PROGRAM_OFFSET: usize = 408;
+const RESERVE_COLLATERAL_MINT_OFFSET: usize = 2560;
+const RESERVE_COLLATERAL_SUPPLY_OFFSET: usize = 2600;
+/// `config.token_info.scope_configuration.price_feed`
+const RESERVE_SCOPE_PRICE_FEED_OFFSET: usize = 5113;
+
+/// Offsets of an `Obligation`'s deposit and borrow slots, and the size of each
+const OBLIGATION_DEPOSITS_OFFSET: usize = 96;
+const OBLIGATION_DEPOSIT_LEN: usize = 136;
+const OBLIGATION_DEPOSITS: usize = 8;
+const OBLIGATION_BORROWS_OFFSET: usize = 1208;
+const OBLIGATION_BORROW_LEN: usize = 200;
+const OBLIGATION_BORROWS: usize = 5;
+
+/// The PDA signing for `lending_market`'s vaults.
+pub fn lending_market_authority_address(lending_market: &Address) -> Address {
+    Address::find_program_address(
+        &[
+            kamino::LENDING_MARKET_AUTHORITY_SEED,
+            lending_market.as_ref(),
+        ],
+        &KAMINO_LEND_PROGRAM_ID,
+    )
+    .0
+}
+
+/// `owner`'s vanilla obligation (tag 0, id 0) in `lending_market`, the one
+/// `init_obligation` creates without custom seeds.
+pub fn obligation_address(owner: &Address, lending_market: &Address) -> Address {
+    let unused_seed = Address::default();
+    Address::find_program_address(
+        &[
+            &[0],
+            &[0],
+            owner.as_ref(),
+            lending_market.as_ref(),
+            unused_seed.as_ref(),
+            unused_seed.as_ref(),
+        ],
+        &KAMINO_LEND_PROGRAM_ID,
+    )
+    .0
+}
+
+/// `owner`'s user metadata, created once per wallet before its first obligation.
+pub fn user_metadata_address(owner: &Address) -> Address {
+    Address::find_program_address(&[b"user_meta", owner.as_ref()], &KAMINO_LEND_PROGRAM_ID).0
+}
+
+/// `obligation`'s user state in the farm at `farm_state`.
+pub fn obligation_farm_user_state_address(farm_state: &Address, obligation: &Address) -> Address {
+    Address::find_program_address(
+        &[b"user", farm_state.as_ref(), obligation.as_ref()],
+        &FARMS_PROGRAM_ID,
+    )
+    .0
+}
+
+/// Resolve the accounts `owner` needs to deposit into `reserve` through its vanilla
+/// obligation in `lending_market`.
+///
+/// Mints, vaults, the token program, the collateral farm and the Scope price feed are
+/// read from the reserve; the reserves to refresh are read from the obligation, which
+/// may not exist yet. A reserve without a collateral farm gets the Kamino program in
+/// place of the farm accounts, as Kamino expects for optional accounts.
+pub fn resolve_deposit_keys<S: AccountSource>(
+    source: &S,
+    owner: &Address,
+    lending_market: &Address,
+    reserve: &Address,
+) -> Result<KaminoDepositKeys, ResolveError<S::Error>> {
+    let reserve_account = fetch(source, reserve)?;
+    if reserve_account.owner != KAMINO_LEND_PROGRAM_ID {
+        return Err(ResolveError::InvalidAccountData(*reserve));
+    }
+    let read = |offset| read_address(reserve, &reserve_account.data, offset);
+    if read(RESERVE_LENDING_MARKET_OFFSET)? != *lending_market {
+        return Err(ResolveError::InvalidAccountData(*reserve));
+    }
+    let reserve_liquidity_mint = read(RESERVE_LIQUIDITY_MINT_OFFSET)?;
+    let liquidity_token_program = read(RESERVE_LIQUIDITY_TOKEN_PROGRAM_OFFSET)?;
+    let farm_collateral = read(RESERVE_FARM_COLLATERAL_OFFSET)?;
+
+    let obligation = obligation_address(owner, lending_market);
+    let (obligation_farm_user_state, reserve_farm_state) = if farm_collateral == Address::default()
+    {
+        (KAMINO_LEND_PROGRAM_ID, KAMINO_LEND_PROGRAM_ID)
+    } else {
+        (
+            obligation_farm_user_state_address(&farm_collateral, &obligation),
+            farm_collateral,
+        )
+    };
+
+    let reserve_accounts = match source
+        .get_account(&obligation)
+        .map_err(ResolveError::Source)?
+    {
+        Some(account) => obligation_reserves(&obligation, &account.data)?,
+        None => Vec::new(),
+    };
+
+    Ok(KaminoDepositKeys {
+        owner: *owner,
+        obligation,
+        lending_market: *lending_market,
+        lending_market_authority: lending_market_authority_address(lending_market),
+        reserve: *reserve,
+        reserve_liquidity_mint,
+        reserve_liquidity_supply: read(RESERVE_LIQUIDITY_SUPPLY_OFFSET)?,
+        reserve_collateral_mint: read(RESERVE_COLLATERAL_MINT_OFFSET)?,
+        reserve_destination_deposit_collateral: read(RESERVE_COLLATERAL_SUPPLY_OFFSET)?,
+        user_source_liquidity: associated_token_address(
+            owner,
+            &reserve_liquidity_mint,
+            &liquidity_token_program,
+        ),
+        placeholder_user_destination_collateral: KAMINO_LEND_PROGRAM_ID,
+        collateral_token_program: TOKEN_PROGRAM_ID,
+        liquidity_token_program,
+        instruction_sysvar_account: INSTRUCTIONS_SYSVAR_ID,
+        obligation_farm_user_state,
+        reserve_farm_state,
+        farms_program: FARMS_PROGRAM_ID,
+        scope_oracle: read(RESERVE_SCOPE_PRICE_FEED_OFFSET)?,
+        reserve_accounts,
+    })
+}
+
+/// Every reserve `obligation` holds a deposit or borrow in, deposits first.
+fn obligation_reserves<E>(
+    obligation: &Address,
+    data: &[u8],
+) -> Result<Vec<Address>, ResolveError<E>> {
+    let deposits = (0..OBLIGATION_DEPOSITS)
+        .map(|slot| OBLIGATION_DEPOSITS_OFFSET + slot * OBLIGATION_DEPOSIT_LEN);
+    let borrows = (0..OBLIGATION_BORROWS)
+        .map(|slot| OBLIGATION_BORROWS_OFFSET + slot * OBLIGATION_BORROW_LEN);
+
+    let mut reserves = Vec::new();
+    for offset in deposits.chain(borrows) {
+        let reserve = read_address(obligation, data, offset)?;
+        if reserve != Address::default() {
+            reserves.push(reserve);
+        }
+    }
+    Ok(reserves)
+}
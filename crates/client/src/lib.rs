@@ -0,0 +1,272 @@
+//! Off-chain instruction builders mirroring every Beethoven adapter.
+//!
+//! Each protocol module has a `*Keys` struct holding the addresses its on-chain
+//! accounts struct destructures, in the same order, and builds both the direct venue
+//! instruction and the accounts a Beethoven-routed program expects after the detector
+//! account. Instruction data comes from the adapter crates' own builders, so the bytes
+//! and the on-chain CPI cannot drift apart.
+//!
+//! Builders, resolvers and quotes compile to `wasm32-unknown-unknown`: resolvers read
+//! through [`resolve::AccountSource`], which a frontend fills with accounts it fetched
+//! itself, and the `rpc` feature is ignored on wasm targets.
+//!
+//! With the `tracing` feature, resolution, quoting, transaction assembly and simulation
+//! run inside `tracing` spans labelled with the protocol and pool, and every account
+//! read from an [`resolve::AccountSource`] gets a `trace` span, so a bot can see where
+//! route construction spends its time.
+
+// Builds without every protocol of an action leave the shared swap data builder and
+// IDL helpers unused
+#![cfg_attr(not(all(feature = "deposit", feature = "swap")), allow(dead_code))]
+
+use {
+    beethoven::ProtocolKind, core::mem::MaybeUninit, solana_address::Address,
+    solana_instruction::AccountMeta,
+};
+
+/// `tracing::debug!` with the `tracing` feature, nothing without it.
+macro_rules! debug_event {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "tracing")]
+        tracing::debug!($($arg)*);
+    };
+}
+
+#[cfg(feature = "jupiter-deposit")]
+pub mod jupiter;
+#[cfg(feature = "kamino-deposit")]
+pub mod kamino;
+
+#[cfg(feature = "raydium_cpmm-liquidity")]
+pub mod raydium_cpmm;
+
+#[cfg(feature = "marinade-stake")]
+pub mod marinade;
+#[cfg(feature = "spl_stake_pool-stake")]
+pub mod spl_stake_pool;
+
+#[cfg(feature = "drift-perp")]
+pub mod drift;
+
+#[cfg(feature = "aldrin-swap")]
+pub mod aldrin;
+#[cfg(feature = "aldrin_v2-swap")]
+pub mod aldrin_v2;
+#[cfg(feature = "futarchy-swap")]
+pub mod futarchy;
+#[cfg(feature = "gamma-swap")]
+pub mod gamma;
+#[cfg(feature = "heaven-swap")]
+pub mod heaven;
+#[cfg(feature = "manifest-swap")]
+pub mod manifest;
+#[cfg(feature = "mock_dex-swap")]
+pub mod mock_dex;
+#[cfg(feature = "perena-swap")]
+pub mod perena;
+#[cfg(feature = "solfi-swap")]
+pub mod solfi;
+#[cfg(feature = "solfi_v2-swap")]
+pub mod solfi_v2;
+
+pub mod cache;
+pub mod fee;
+#[cfg(feature = "test-program")]
+pub mod idl;
+#[cfg(feature = "jito")]
+pub mod jito;
+#[cfg(any(feature = "gamma-swap", feature = "manifest-swap"))]
+pub mod jupiter_route;
+pub mod planner;
+pub mod quote;
+pub mod resolve;
+pub mod simulate;
+#[cfg(feature = "test-program")]
+pub mod test_program;
+pub mod transaction;
+
+/// One account of an adapter's account list.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AccountSpec {
+    /// Field name in the adapter's accounts struct
+    pub name: &'static str,
+    pub writable: bool,
+    pub signer: bool,
+}
+
+impl AccountSpec {
+    pub const fn readonly(name: &'static str) -> Self {
+        Self {
+            name,
+            writable: false,
+            signer: false,
+        }
+    }
+
+    pub const fn writable(name: &'static str) -> Self {
+        Self {
+            name,
+            writable: true,
+            signer: false,
+        }
+    }
+
+    pub const fn readonly_signer(name: &'static str) -> Self {
+        Self {
+            name,
+            writable: false,
+            signer: true,
+        }
+    }
+
+    pub const fn writable_signer(name: &'static str) -> Self {
+        Self {
+            name,
+            writable: true,
+            signer: true,
+        }
+    }
+}
+
+/// Addresses of one adapter's accounts.
+pub trait AdapterKeys {
+    /// Protocol the detector account resolves to.
+    const PROTOCOL: ProtocolKind;
+
+    /// Program the adapter CPIs into, passed first as the detector account.
+    const PROGRAM_ID: Address;
+
+    /// Every account after the detector, in the order the adapter's `TryFrom` reads
+    /// them and flagged as its CPIs require.
+    const ACCOUNTS: &'static [AccountSpec];
+
+    /// Addresses of [`Self::ACCOUNTS`], in the same order.
+    fn addresses(&self) -> Vec<Address>;
+
+    /// Metas for [`Self::ACCOUNTS`].
+    fn account_metas(&self) -> Vec<AccountMeta> {
+        account_metas(Self::ACCOUNTS, &self.addresses())
+    }
+
+    /// [`Self::account_metas`] prefixed with the detector account, as
+    /// `try_from_swap_context` / `try_from_deposit_context` expect them.
+    fn routed_account_metas(&self) -> Vec<AccountMeta> {
+        let mut metas = vec![AccountMeta::new_readonly(Self::PROGRAM_ID, false)];
+        metas.extend(self.account_metas());
+        metas
+    }
+}
+
+/// Routed swap account metas for a protocol module, with its accounts keyed by field
+/// name instead of position.
+///
+/// Expands to the protocol's `*SwapKeys` literal and its
+/// [`AdapterKeys::routed_account_metas`], so the metas come out in the order the
+/// on-chain adapter reads them, detector first, and a missing or misspelled field is a
+/// compile error:
+///
+/// ```ignore
+/// let accounts = beethoven_client::swap_accounts!(manifest, {
+///     payer: payer.pubkey(),
+///     owner: payer.pubkey(),
+///     market,
+///     // ...
+/// });
+/// ```
+#[macro_export]
+macro_rules! swap_accounts {
+    (aldrin, $fields:tt) => {
+        $crate::swap_accounts!(@keys aldrin::AldrinSwapKeys, $fields)
+    };
+    (aldrin_v2, $fields:tt) => {
+        $crate::swap_accounts!(@keys aldrin_v2::AldrinV2SwapKeys, $fields)
+    };
+    (futarchy, $fields:tt) => {
+        $crate::swap_accounts!(@keys futarchy::FutarchySwapKeys, $fields)
+    };
+    (gamma, $fields:tt) => {
+        $crate::swap_accounts!(@keys gamma::GammaSwapKeys, $fields)
+    };
+    (heaven, $fields:tt) => {
+        $crate::swap_accounts!(@keys heaven::HeavenSwapKeys, $fields)
+    };
+    (manifest, $fields:tt) => {
+        $crate::swap_accounts!(@keys manifest::ManifestSwapKeys, $fields)
+    };
+    (mock_dex, $fields:tt) => {
+        $crate::swap_accounts!(@keys mock_dex::MockDexSwapKeys, $fields)
+    };
+    (perena, $fields:tt) => {
+        $crate::swap_accounts!(@keys perena::PerenaSwapKeys, $fields)
+    };
+    (solfi, $fields:tt) => {
+        $crate::swap_accounts!(@keys solfi::SolFiSwapKeys, $fields)
+    };
+    (solfi_v2, $fields:tt) => {
+        $crate::swap_accounts!(@keys solfi_v2::SolFiV2SwapKeys, $fields)
+    };
+    (@keys $module:ident :: $keys:ident, { $($field:ident $(: $value:expr)?),* $(,)? }) => {
+        $crate::AdapterKeys::routed_account_metas(&$crate::$module::$keys {
+            $($field $(: $value)?),*
+        })
+    };
+}
+
+/// Type of one field of an adapter's swap or deposit data, as laid out on the wire.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FieldType {
+    U8,
+    Bool,
+    /// A little-endian u64 written only when set, with no presence flag; always last
+    OptionalU64,
+    /// Every remaining byte of the instruction data, without a length prefix
+    RemainingBytes,
+}
+
+/// One field of an adapter's swap or deposit data.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FieldSpec {
+    pub name: &'static str,
+    pub ty: FieldType,
+    pub docs: &'static str,
+}
+
+/// Wire encoding of an adapter's swap data, as the `SwapData` parsers read it.
+pub trait EncodeSwapData {
+    /// Fields of the encoding, in order.
+    const FIELDS: &'static [FieldSpec];
+
+    fn encode(&self) -> Vec<u8>;
+}
+
+/// Wire encoding of an adapter's deposit data, as the `DepositData` parsers read it.
+pub trait EncodeDepositData {
+    /// Fields of the encoding, in order.
+    const FIELDS: &'static [FieldSpec];
+
+    fn encode(&self) -> Vec<u8>;
+}
+
+/// Pair `specs` with `addresses` into metas.
+fn account_metas(specs: &[AccountSpec], addresses: &[Address]) -> Vec<AccountMeta> {
+    debug_assert_eq!(specs.len(), addresses.len());
+    specs
+        .iter()
+        .zip(addresses)
+        .map(|(spec, address)| AccountMeta {
+            pubkey: *address,
+            is_signer: spec.signer,
+            is_writable: spec.writable,
+        })
+        .collect()
+}
+
+/// Run an adapter's `*_instruction_data` builder against an `N`-byte scratch buffer.
+fn instruction_data<const N: usize, E: core::fmt::Debug>(
+    build: impl for<'a> FnOnce(&'a mut [MaybeUninit<u8>]) -> Result<&'a [u8], E>,
+) -> Vec<u8> {
+    let mut scratch = beethoven::scratch::Scratch::<N>::new();
+    build(scratch.as_uninit_mut())
+        .expect("instruction data exceeds the adapter's MAX_INSTRUCTION_DATA_LEN")
+        .to_vec()
+}
@@ -0,0 +1,247 @@
+//! Manifest swap and order instructions.
+
+use {
+    crate::{
+        instruction_data,
+        resolve::{
+            associated_token_address, fetch, read_address, AccountSource, ResolveError,
+            SYSTEM_PROGRAM_ID,
+        },
+        AccountSpec, AdapterKeys, EncodeSwapData, FieldSpec, FieldType,
+    },
+    beethoven::{
+        manifest::{self, ManifestOrderData, ManifestSwapData, MANIFEST_PROGRAM_ID},
+        Direction, OrderType, ProtocolKind,
+    },
+    solana_address::Address,
+    solana_instruction::Instruction,
+};
+
+/// Addresses of [`beethoven::manifest::ManifestSwapAccounts`], without the program.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ManifestSwapKeys {
+    pub payer: Address,
+    pub owner: Address,
+    pub market: Address,
+    pub system_program: Address,
+    pub trader_base: Address,
+    pub trader_quote: Address,
+    pub base_vault: Address,
+    pub quote_vault: Address,
+    pub token_program_base: Address,
+    pub base_mint: Address,
+    pub token_program_quote: Address,
+    pub quote_mint: Address,
+    pub global: Address,
+    pub global_vault: Address,
+}
+
+impl AdapterKeys for ManifestSwapKeys {
+    const PROTOCOL: ProtocolKind = ProtocolKind::Manifest;
+
+    const PROGRAM_ID: Address = MANIFEST_PROGRAM_ID;
+
+    const ACCOUNTS: &'static [AccountSpec] = &[
+        AccountSpec::writable_signer("payer"),
+        AccountSpec::readonly_signer("owner"),
+        AccountSpec::writable("market"),
+        AccountSpec::readonly("system_program"),
+        AccountSpec::writable("trader_base"),
+        AccountSpec::writable("trader_quote"),
+        AccountSpec::writable("base_vault"),
+        AccountSpec::writable("quote_vault"),
+        AccountSpec::readonly("token_program_base"),
+        AccountSpec::readonly("base_mint"),
+        AccountSpec::readonly("token_program_quote"),
+        AccountSpec::readonly("quote_mint"),
+        AccountSpec::writable("global"),
+        AccountSpec::writable("global_vault"),
+    ];
+
+    fn addresses(&self) -> Vec<Address> {
+        vec![
+            self.payer,
+            self.owner,
+            self.market,
+            self.system_program,
+            self.trader_base,
+            self.trader_quote,
+            self.base_vault,
+            self.quote_vault,
+            self.token_program_base,
+            self.base_mint,
+            self.token_program_quote,
+            self.quote_mint,
+            self.global,
+            self.global_vault,
+        ]
+    }
+}
+
+impl EncodeSwapData for ManifestSwapData {
+    const FIELDS: &'static [FieldSpec] = &[
+        FieldSpec {
+            name: "is_base_in",
+            ty: FieldType::Bool,
+            docs: "",
+        },
+        FieldSpec {
+            name: "is_exact_in",
+            ty: FieldType::Bool,
+            docs: "",
+        },
+    ];
+
+    fn encode(&self) -> Vec<u8> {
+        vec![self.is_base_in as u8, self.is_exact_in as u8]
+    }
+}
+
+/// Manifest swap instruction calling the venue directly.
+pub fn swap_instruction(
+    keys: &ManifestSwapKeys,
+    in_amount: u64,
+    minimum_out_amount: u64,
+    swap_data: &ManifestSwapData,
+) -> Instruction {
+    Instruction {
+        program_id: MANIFEST_PROGRAM_ID,
+        accounts: keys.account_metas(),
+        data: instruction_data::<{ manifest::MAX_INSTRUCTION_DATA_LEN }, _>(|scratch| {
+            manifest::swap_instruction_data(scratch, in_amount, minimum_out_amount, swap_data)
+        }),
+    }
+}
+
+/// Addresses of [`beethoven::manifest::ManifestOrderAccounts`], without the program.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ManifestOrderKeys {
+    pub payer: Address,
+    pub market: Address,
+    pub system_program: Address,
+}
+
+impl ManifestOrderKeys {
+    pub const fn new(payer: Address, market: Address) -> Self {
+        Self {
+            payer,
+            market,
+            system_program: SYSTEM_PROGRAM_ID,
+        }
+    }
+}
+
+impl AdapterKeys for ManifestOrderKeys {
+    const PROTOCOL: ProtocolKind = ProtocolKind::Manifest;
+
+    const PROGRAM_ID: Address = MANIFEST_PROGRAM_ID;
+
+    const ACCOUNTS: &'static [AccountSpec] = &[
+        AccountSpec::writable_signer("payer"),
+        AccountSpec::writable("market"),
+        AccountSpec::readonly("system_program"),
+    ];
+
+    fn addresses(&self) -> Vec<Address> {
+        vec![self.payer, self.market, self.system_program]
+    }
+}
+
+/// Manifest `batch_update` instruction placing one order, calling the venue directly.
+pub fn place_order_instruction(
+    keys: &ManifestOrderKeys,
+    side: Direction,
+    base_amount: u64,
+    order_type: OrderType,
+    order: &ManifestOrderData,
+) -> Instruction {
+    Instruction {
+        program_id: MANIFEST_PROGRAM_ID,
+        accounts: keys.account_metas(),
+        data: manifest::place_order_instruction_data(side, base_amount, order_type, order).to_vec(),
+    }
+}
+
+/// Manifest `batch_update` instruction cancelling the order with
+/// `order_sequence_number`, calling the venue directly.
+pub fn cancel_order_instruction(
+    keys: &ManifestOrderKeys,
+    order_sequence_number: u64,
+) -> Instruction {
+    Instruction {
+        program_id: MANIFEST_PROGRAM_ID,
+        accounts: keys.account_metas(),
+        data: manifest::cancel_order_instruction_data(order_sequence_number).to_vec(),
+    }
+}
+
+/// Offsets of the mints and vaults in a Manifest market account's data
+mod market_offsets {
+    pub const BASE_MINT: usize = 16;
+    pub const QUOTE_MINT: usize = 48;
+    pub const BASE_VAULT: usize = 80;
+    pub const QUOTE_VAULT: usize = 112;
+}
+
+/// Resolve the accounts `owner` needs to swap on `market`, paying rent from `owner`.
+///
+/// Mints and vaults are read from the market, token programs from the mints' owners,
+/// and the trader accounts are `owner`'s associated token accounts. The adapter
+/// forwards one global account pair; this resolves the pair for the input mint
+/// (the base mint when `is_base_in`).
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(
+        name = "resolve_swap_keys",
+        skip_all,
+        fields(protocol = "manifest", pool = %market, is_base_in = is_base_in)
+    )
+)]
+pub fn resolve_swap_keys<S: AccountSource>(
+    source: &S,
+    market: &Address,
+    owner: &Address,
+    is_base_in: bool,
+) -> Result<ManifestSwapKeys, ResolveError<S::Error>> {
+    let market_account = fetch(source, market)?;
+    if market_account.owner != MANIFEST_PROGRAM_ID {
+        return Err(ResolveError::InvalidAccountData(*market));
+    }
+    let data = &market_account.data;
+    let base_mint = read_address(market, data, market_offsets::BASE_MINT)?;
+    let quote_mint = read_address(market, data, market_offsets::QUOTE_MINT)?;
+    let base_vault = read_address(market, data, market_offsets::BASE_VAULT)?;
+    let quote_vault = read_address(market, data, market_offsets::QUOTE_VAULT)?;
+
+    let token_program_base = fetch(source, &base_mint)?.owner;
+    let token_program_quote = fetch(source, &quote_mint)?.owner;
+
+    let (global, global_vault) =
+        global_addresses(if is_base_in { &base_mint } else { &quote_mint });
+
+    Ok(ManifestSwapKeys {
+        payer: *owner,
+        owner: *owner,
+        market: *market,
+        system_program: SYSTEM_PROGRAM_ID,
+        trader_base: associated_token_address(owner, &base_mint, &token_program_base),
+        trader_quote: associated_token_address(owner, &quote_mint, &token_program_quote),
+        base_vault,
+        quote_vault,
+        token_program_base,
+        base_mint,
+        token_program_quote,
+        quote_mint,
+        global,
+        global_vault,
+    })
+}
+
+/// Manifest's global account and global vault for `mint`.
+pub fn global_addresses(mint: &Address) -> (Address, Address) {
+    let (global, _) =
+        Address::find_program_address(&[b"global", mint.as_ref()], &MANIFEST_PROGRAM_ID);
+    let (global_vault, _) =
+        Address::find_program_address(&[b"global-vault", mint.as_ref()], &MANIFEST_PROGRAM_ID);
+    (global, global_vault)
+}
@@ -0,0 +1,241 @@
+//! Marinade liquid staking instructions.
+
+use {
+    crate::{
+        account_metas,
+        resolve::{associated_token_address, SYSTEM_PROGRAM_ID},
+        transaction::TOKEN_PROGRAM_ID,
+        AccountSpec, AdapterKeys,
+    },
+    beethoven::{
+        marinade::{self, MARINADE_PROGRAM_ID},
+        ProtocolKind,
+    },
+    solana_address::Address,
+    solana_instruction::{AccountMeta, Instruction},
+};
+
+/// Marinade's mainnet state account
+pub const MARINADE_STATE: Address =
+    Address::from_str_const("8szGkuLTAux9XMgZ2vtY39jVSowEcpBfFfD8hXSEqdGC");
+
+/// The mSOL mint
+pub const MSOL_MINT: Address =
+    Address::from_str_const("mSoLzYCxHdYgdzU16g5QSh3i5K3z3KZK7ytfqcJm7So");
+
+/// The clock sysvar
+pub const CLOCK_SYSVAR_ID: Address =
+    Address::from_str_const("SysvarC1ock11111111111111111111111111111111");
+
+/// The rent sysvar
+pub const RENT_SYSVAR_ID: Address =
+    Address::from_str_const("SysvarRent111111111111111111111111111111111");
+
+/// Addresses of [`beethoven::marinade::MarinadeStakeAccounts`], without the program.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MarinadeStakeKeys {
+    pub state: Address,
+    pub msol_mint: Address,
+    pub liq_pool_sol_leg_pda: Address,
+    pub liq_pool_msol_leg: Address,
+    pub liq_pool_msol_leg_authority: Address,
+    pub reserve_pda: Address,
+    pub transfer_from: Address,
+    pub mint_to: Address,
+    pub msol_mint_authority: Address,
+    pub system_program: Address,
+    pub token_program: Address,
+}
+
+impl AdapterKeys for MarinadeStakeKeys {
+    const PROTOCOL: ProtocolKind = ProtocolKind::Marinade;
+
+    const PROGRAM_ID: Address = MARINADE_PROGRAM_ID;
+
+    const ACCOUNTS: &'static [AccountSpec] = &[
+        AccountSpec::writable("state"),
+        AccountSpec::writable("msol_mint"),
+        AccountSpec::writable("liq_pool_sol_leg_pda"),
+        AccountSpec::writable("liq_pool_msol_leg"),
+        AccountSpec::readonly("liq_pool_msol_leg_authority"),
+        AccountSpec::writable("reserve_pda"),
+        AccountSpec::writable_signer("transfer_from"),
+        AccountSpec::writable("mint_to"),
+        AccountSpec::readonly("msol_mint_authority"),
+        AccountSpec::readonly("system_program"),
+        AccountSpec::readonly("token_program"),
+    ];
+
+    fn addresses(&self) -> Vec<Address> {
+        vec![
+            self.state,
+            self.msol_mint,
+            self.liq_pool_sol_leg_pda,
+            self.liq_pool_msol_leg,
+            self.liq_pool_msol_leg_authority,
+            self.reserve_pda,
+            self.transfer_from,
+            self.mint_to,
+            self.msol_mint_authority,
+            self.system_program,
+            self.token_program,
+        ]
+    }
+}
+
+/// Marinade `deposit` instruction calling the venue directly.
+pub fn stake_instruction(keys: &MarinadeStakeKeys, lamports: u64) -> Instruction {
+    Instruction {
+        program_id: MARINADE_PROGRAM_ID,
+        accounts: keys.account_metas(),
+        data: marinade::deposit_instruction_data(lamports).to_vec(),
+    }
+}
+
+/// Marinade PDA of `state` under `seed`.
+pub fn state_pda_address(state: &Address, seed: &[u8]) -> Address {
+    Address::find_program_address(&[state.as_ref(), seed], &MARINADE_PROGRAM_ID).0
+}
+
+/// Keys staking from `staker` into its mSOL associated token account.
+///
+/// `liq_pool_msol_leg` is the liquidity pool's mSOL token account, stored in the state
+/// account; every other account is derived.
+pub fn stake_keys(
+    state: &Address,
+    msol_mint: &Address,
+    liq_pool_msol_leg: &Address,
+    staker: &Address,
+) -> MarinadeStakeKeys {
+    MarinadeStakeKeys {
+        state: *state,
+        msol_mint: *msol_mint,
+        liq_pool_sol_leg_pda: state_pda_address(state, b"liq_sol"),
+        liq_pool_msol_leg: *liq_pool_msol_leg,
+        liq_pool_msol_leg_authority: state_pda_address(state, b"liq_st_sol_authority"),
+        reserve_pda: state_pda_address(state, b"reserve"),
+        transfer_from: *staker,
+        mint_to: associated_token_address(staker, msol_mint, &TOKEN_PROGRAM_ID),
+        msol_mint_authority: state_pda_address(state, b"st_mint"),
+        system_program: SYSTEM_PROGRAM_ID,
+        token_program: TOKEN_PROGRAM_ID,
+    }
+}
+
+/// Addresses of [`beethoven::marinade::MarinadeUnstakeAccounts`], without the program.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MarinadeUnstakeKeys {
+    pub state: Address,
+    pub msol_mint: Address,
+    pub liq_pool_sol_leg_pda: Address,
+    pub liq_pool_msol_leg: Address,
+    pub treasury_msol_account: Address,
+    pub get_msol_from: Address,
+    pub get_msol_from_authority: Address,
+    pub transfer_sol_to: Address,
+    pub system_program: Address,
+    pub token_program: Address,
+    /// Ticket accounts, appended for a delayed unstake
+    pub ticket: Option<MarinadeTicketKeys>,
+}
+
+/// Accounts only a delayed unstake (`order_unstake`) reads.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MarinadeTicketKeys {
+    /// Zeroed, rent-exempt account owned by Marinade, created earlier in the transaction
+    pub new_ticket_account: Address,
+    pub clock: Address,
+    pub rent: Address,
+}
+
+impl MarinadeTicketKeys {
+    pub const fn new(new_ticket_account: Address) -> Self {
+        Self {
+            new_ticket_account,
+            clock: CLOCK_SYSVAR_ID,
+            rent: RENT_SYSVAR_ID,
+        }
+    }
+}
+
+impl AdapterKeys for MarinadeUnstakeKeys {
+    const PROTOCOL: ProtocolKind = ProtocolKind::Marinade;
+
+    const PROGRAM_ID: Address = MARINADE_PROGRAM_ID;
+
+    const ACCOUNTS: &'static [AccountSpec] = &[
+        AccountSpec::writable("state"),
+        AccountSpec::writable("msol_mint"),
+        AccountSpec::writable("liq_pool_sol_leg_pda"),
+        AccountSpec::writable("liq_pool_msol_leg"),
+        AccountSpec::writable("treasury_msol_account"),
+        AccountSpec::writable("get_msol_from"),
+        AccountSpec::readonly_signer("get_msol_from_authority"),
+        AccountSpec::writable("transfer_sol_to"),
+        AccountSpec::readonly("system_program"),
+        AccountSpec::readonly("token_program"),
+    ];
+
+    fn addresses(&self) -> Vec<Address> {
+        vec![
+            self.state,
+            self.msol_mint,
+            self.liq_pool_sol_leg_pda,
+            self.liq_pool_msol_leg,
+            self.treasury_msol_account,
+            self.get_msol_from,
+            self.get_msol_from_authority,
+            self.transfer_sol_to,
+            self.system_program,
+            self.token_program,
+        ]
+    }
+
+    fn account_metas(&self) -> Vec<AccountMeta> {
+        let mut metas = account_metas(Self::ACCOUNTS, &self.addresses());
+        if let Some(ticket) = &self.ticket {
+            metas.extend([
+                AccountMeta::new(ticket.new_ticket_account, false),
+                AccountMeta::new_readonly(ticket.clock, false),
+                AccountMeta::new_readonly(ticket.rent, false),
+            ]);
+        }
+        metas
+    }
+}
+
+/// Marinade `liquid_unstake` instruction calling the venue directly.
+pub fn liquid_unstake_instruction(keys: &MarinadeUnstakeKeys, msol_amount: u64) -> Instruction {
+    let mut accounts = keys.account_metas();
+    accounts.truncate(MarinadeUnstakeKeys::ACCOUNTS.len());
+
+    Instruction {
+        program_id: MARINADE_PROGRAM_ID,
+        accounts,
+        data: marinade::liquid_unstake_instruction_data(msol_amount).to_vec(),
+    }
+}
+
+/// Marinade `order_unstake` instruction calling the venue directly, or `None` without
+/// [`MarinadeUnstakeKeys::ticket`].
+pub fn order_unstake_instruction(
+    keys: &MarinadeUnstakeKeys,
+    msol_amount: u64,
+) -> Option<Instruction> {
+    let ticket = keys.ticket.as_ref()?;
+
+    Some(Instruction {
+        program_id: MARINADE_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(keys.state, false),
+            AccountMeta::new(keys.msol_mint, false),
+            AccountMeta::new(keys.get_msol_from, false),
+            AccountMeta::new_readonly(keys.get_msol_from_authority, true),
+            AccountMeta::new(ticket.new_ticket_account, false),
+            AccountMeta::new_readonly(ticket.clock, false),
+            AccountMeta::new_readonly(ticket.rent, false),
+            AccountMeta::new_readonly(keys.token_program, false),
+        ],
+        data: marinade::order_unstake_instruction_data(msol_amount).to_vec(),
+    })
+}
@@ -0,0 +1,224 @@
+//! Mock DEX swap instructions and pool setup.
+
+use {
+    crate::{
+        instruction_data,
+        quote::{price_impact_bps, token_amount, SwapQuote},
+        resolve::{associated_token_address, fetch, AccountSource, FetchedAccount, ResolveError},
+        transaction::TOKEN_PROGRAM_ID,
+        AccountSpec, AdapterKeys, EncodeSwapData, FieldSpec, FieldType,
+    },
+    beethoven::{
+        mock_dex::{
+            self,
+            quote::quote_exact_in,
+            state::{Pool, POOL_AUTHORITY_SEED},
+            MockDexSwapData, MOCK_DEX_PROGRAM_ID,
+        },
+        ProtocolKind,
+    },
+    solana_address::Address,
+    solana_instruction::Instruction,
+};
+
+/// Addresses of [`beethoven::mock_dex::MockDexSwapAccounts`], without the program.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MockDexSwapKeys {
+    pub user: Address,
+    pub pool: Address,
+    pub pool_authority: Address,
+    pub base_vault: Address,
+    pub quote_vault: Address,
+    pub user_base_token: Address,
+    pub user_quote_token: Address,
+    pub token_program: Address,
+}
+
+impl AdapterKeys for MockDexSwapKeys {
+    const PROTOCOL: ProtocolKind = ProtocolKind::MockDex;
+
+    const PROGRAM_ID: Address = MOCK_DEX_PROGRAM_ID;
+
+    const ACCOUNTS: &'static [AccountSpec] = &[
+        AccountSpec::readonly_signer("user"),
+        AccountSpec::readonly("pool"),
+        AccountSpec::readonly("pool_authority"),
+        AccountSpec::writable("base_vault"),
+        AccountSpec::writable("quote_vault"),
+        AccountSpec::writable("user_base_token"),
+        AccountSpec::writable("user_quote_token"),
+        AccountSpec::readonly("token_program"),
+    ];
+
+    fn addresses(&self) -> Vec<Address> {
+        vec![
+            self.user,
+            self.pool,
+            self.pool_authority,
+            self.base_vault,
+            self.quote_vault,
+            self.user_base_token,
+            self.user_quote_token,
+            self.token_program,
+        ]
+    }
+}
+
+impl EncodeSwapData for MockDexSwapData {
+    const FIELDS: &'static [FieldSpec] = &[FieldSpec {
+        name: "is_quote_to_base",
+        ty: FieldType::Bool,
+        docs: "",
+    }];
+
+    fn encode(&self) -> Vec<u8> {
+        vec![self.is_quote_to_base as u8]
+    }
+}
+
+/// Mock DEX swap instruction calling the venue directly.
+pub fn swap_instruction(
+    keys: &MockDexSwapKeys,
+    in_amount: u64,
+    minimum_out_amount: u64,
+    swap_data: &MockDexSwapData,
+) -> Instruction {
+    Instruction {
+        program_id: MOCK_DEX_PROGRAM_ID,
+        accounts: keys.account_metas(),
+        data: instruction_data::<{ mock_dex::MAX_INSTRUCTION_DATA_LEN }, _>(|scratch| {
+            mock_dex::swap_instruction_data(scratch, in_amount, minimum_out_amount, swap_data)
+        }),
+    }
+}
+
+/// The PDA owning `pool`'s vaults and its bump.
+pub fn pool_authority_address(pool: &Address) -> (Address, u8) {
+    Address::find_program_address(&[POOL_AUTHORITY_SEED, pool.as_ref()], &MOCK_DEX_PROGRAM_ID)
+}
+
+/// Data of a pool account at `pool` trading `base_mint` against `quote_mint`, to write
+/// into a test SVM. The vaults must be token accounts owned by
+/// [`pool_authority_address`].
+pub fn pool_account_data(
+    pool: &Address,
+    base_mint: &Address,
+    quote_mint: &Address,
+    base_vault: &Address,
+    quote_vault: &Address,
+    fee_bps: u16,
+) -> Vec<u8> {
+    Pool {
+        base_mint: *base_mint,
+        quote_mint: *quote_mint,
+        base_vault: *base_vault,
+        quote_vault: *quote_vault,
+        authority_bump: pool_authority_address(pool).1,
+        fee_bps: fee_bps.into(),
+    }
+    .to_account_data()
+    .to_vec()
+}
+
+/// Resolve the accounts `user` needs to sell `input_mint` into `pool`, and the swap
+/// data selecting the direction.
+///
+/// The user token accounts are `user`'s associated token accounts; the mock DEX only
+/// supports SPL Token mints.
+pub fn resolve_swap<S: AccountSource>(
+    source: &S,
+    pool: &Address,
+    user: &Address,
+    input_mint: &Address,
+) -> Result<(MockDexSwapKeys, MockDexSwapData), ResolveError<S::Error>> {
+    let pool_account = fetch_pool(source, pool)?;
+    let state = load_pool(pool, &pool_account.data)?;
+    let is_quote_to_base = is_quote_to_base(state, input_mint)?;
+
+    let keys = MockDexSwapKeys {
+        user: *user,
+        pool: *pool,
+        pool_authority: pool_authority_address(pool).0,
+        base_vault: state.base_vault,
+        quote_vault: state.quote_vault,
+        user_base_token: associated_token_address(user, &state.base_mint, &TOKEN_PROGRAM_ID),
+        user_quote_token: associated_token_address(user, &state.quote_mint, &TOKEN_PROGRAM_ID),
+        token_program: TOKEN_PROGRAM_ID,
+    };
+    Ok((keys, MockDexSwapData { is_quote_to_base }))
+}
+
+/// Quote selling `in_amount` of `input_mint` into `pool`, with the same math as the
+/// mock DEX program.
+pub fn quote_swap<S: AccountSource>(
+    source: &S,
+    pool: &Address,
+    input_mint: &Address,
+    in_amount: u64,
+) -> Result<SwapQuote, ResolveError<S::Error>> {
+    let pool_account = fetch_pool(source, pool)?;
+    let state = load_pool(pool, &pool_account.data)?;
+
+    let (input_vault, output_vault) = if is_quote_to_base(state, input_mint)? {
+        (state.quote_vault, state.base_vault)
+    } else {
+        (state.base_vault, state.quote_vault)
+    };
+    let input_reserve = token_amount(&input_vault, &fetch(source, &input_vault)?.data)?;
+    let output_reserve = token_amount(&output_vault, &fetch(source, &output_vault)?.data)?;
+
+    let quote = quote_exact_in(
+        state.fee_bps.get(),
+        in_amount,
+        input_reserve,
+        output_reserve,
+    )
+    .ok_or(ResolveError::InvalidAccountData(*pool))?;
+    let spot_out_amount = if input_reserve == 0 {
+        0
+    } else {
+        (in_amount - quote.trade_fee) as u128 * output_reserve as u128 / input_reserve as u128
+    };
+
+    Ok(SwapQuote {
+        in_amount,
+        out_amount: quote.out_amount,
+        fee_amount: quote.trade_fee,
+        price_impact_bps: price_impact_bps(quote.out_amount, spot_out_amount),
+    })
+}
+
+/// The `(base, quote)` mints `pool` trades.
+pub fn pool_mints<S: AccountSource>(
+    source: &S,
+    pool: &Address,
+) -> Result<(Address, Address), ResolveError<S::Error>> {
+    let pool_account = fetch_pool(source, pool)?;
+    let state = load_pool(pool, &pool_account.data)?;
+    Ok((state.base_mint, state.quote_mint))
+}
+
+fn fetch_pool<S: AccountSource>(
+    source: &S,
+    pool: &Address,
+) -> Result<FetchedAccount, ResolveError<S::Error>> {
+    let account = fetch(source, pool)?;
+    if account.owner != MOCK_DEX_PROGRAM_ID {
+        return Err(ResolveError::InvalidAccountData(*pool));
+    }
+    Ok(account)
+}
+
+fn load_pool<'a, E>(pool: &Address, data: &'a [u8]) -> Result<&'a Pool, ResolveError<E>> {
+    Pool::from_account_data(data).map_err(|_| ResolveError::InvalidAccountData(*pool))
+}
+
+fn is_quote_to_base<E>(pool: &Pool, input_mint: &Address) -> Result<bool, ResolveError<E>> {
+    if pool.base_mint == *input_mint {
+        Ok(false)
+    } else if pool.quote_mint == *input_mint {
+        Ok(true)
+    } else {
+        Err(ResolveError::MintNotInPool(*input_mint))
+    }
+}
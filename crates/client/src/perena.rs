@@ -0,0 +1,98 @@
+//! Perena swap instructions.
+
+use {
+    crate::{instruction_data, AccountSpec, AdapterKeys, EncodeSwapData, FieldSpec, FieldType},
+    beethoven::{
+        perena::{self, PerenaSwapData, PERENA_PROGRAM_ID},
+        ProtocolKind,
+    },
+    solana_address::Address,
+    solana_instruction::Instruction,
+};
+
+/// Addresses of [`beethoven::perena::PerenaSwapAccounts`], without the program.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PerenaSwapKeys {
+    pub pool: Address,
+    pub in_mint: Address,
+    pub out_mint: Address,
+    pub in_trader: Address,
+    pub out_trader: Address,
+    pub in_vault: Address,
+    pub out_vault: Address,
+    pub numeraire_config: Address,
+    pub payer: Address,
+    pub token_program: Address,
+    pub token_2022_program: Address,
+}
+
+impl AdapterKeys for PerenaSwapKeys {
+    const PROTOCOL: ProtocolKind = ProtocolKind::Perena;
+
+    const PROGRAM_ID: Address = PERENA_PROGRAM_ID;
+
+    const ACCOUNTS: &'static [AccountSpec] = &[
+        AccountSpec::writable("pool"),
+        AccountSpec::writable("in_mint"),
+        AccountSpec::writable("out_mint"),
+        AccountSpec::writable("in_trader"),
+        AccountSpec::writable("out_trader"),
+        AccountSpec::writable("in_vault"),
+        AccountSpec::writable("out_vault"),
+        AccountSpec::readonly("numeraire_config"),
+        AccountSpec::writable_signer("payer"),
+        AccountSpec::readonly("token_program"),
+        AccountSpec::readonly("token_2022_program"),
+    ];
+
+    fn addresses(&self) -> Vec<Address> {
+        vec![
+            self.pool,
+            self.in_mint,
+            self.out_mint,
+            self.in_trader,
+            self.out_trader,
+            self.in_vault,
+            self.out_vault,
+            self.numeraire_config,
+            self.payer,
+            self.token_program,
+            self.token_2022_program,
+        ]
+    }
+}
+
+impl EncodeSwapData for PerenaSwapData {
+    const FIELDS: &'static [FieldSpec] = &[
+        FieldSpec {
+            name: "in_index",
+            ty: FieldType::U8,
+            docs: "Index of the input mint in the pool",
+        },
+        FieldSpec {
+            name: "out_index",
+            ty: FieldType::U8,
+            docs: "Index of the output mint in the pool",
+        },
+    ];
+
+    fn encode(&self) -> Vec<u8> {
+        vec![self.in_index, self.out_index]
+    }
+}
+
+/// Perena swap instruction calling the venue directly.
+pub fn swap_instruction(
+    keys: &PerenaSwapKeys,
+    in_amount: u64,
+    minimum_out_amount: u64,
+    swap_data: &PerenaSwapData,
+) -> Instruction {
+    Instruction {
+        program_id: PERENA_PROGRAM_ID,
+        accounts: keys.account_metas(),
+        data: instruction_data::<{ perena::MAX_INSTRUCTION_DATA_LEN }, _>(|scratch| {
+            perena::swap_instruction_data(scratch, in_amount, minimum_out_amount, swap_data)
+        }),
+    }
+}
@@ -0,0 +1,367 @@
+//! Off-chain route planning over the venues the client can quote.
+//!
+//! [`plan_route`] quotes selling an input mint for an output mint through each of a set
+//! of candidate pools and picks either the single best pool or a split of the input
+//! across several of them, whichever returns more. Quotes read through an
+//! [`AccountSource`], so a [`crate::cache::QuoteCache`] makes repeated planning cheap.
+//!
+//! A plan is a list of [`RouteLeg`]s, each executable as one Beethoven swap, and
+//! [`RoutePlan::encode`] packs them into the compact route encoding:
+//!
+//! Data: leg count (u8), then per leg: protocol (u8), account count (u8), in_amount
+//! (u64), minimum_out_amount (u64), swap data length (u8), swap data
+//!
+//! Accounts: every leg's routed accounts (detector first), in leg order
+//!
+//! Only venues with a quote (Gamma, the mock DEX) are planned over; other candidate
+//! pools are skipped, as are pools on venues lacking
+//! [`PlanOptions::required_capabilities`].
+
+use {
+    crate::resolve::{fetch, AccountSource, ResolveError},
+    beethoven::{Capabilities, ProtocolKind},
+    core::fmt,
+    solana_address::Address,
+    solana_instruction::AccountMeta,
+};
+
+/// One leg of a route, ready to pass to a Beethoven-routed program.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RouteLeg {
+    pub protocol: ProtocolKind,
+    pub in_amount: u64,
+    /// Output quoted for this leg
+    pub out_amount: u64,
+    /// `out_amount` less the route's slippage tolerance
+    pub minimum_out_amount: u64,
+    /// Detector account first, then the adapter's accounts
+    pub accounts: Vec<AccountMeta>,
+    /// The adapter's swap data, encoded as the program's instruction data tail
+    pub data: Vec<u8>,
+}
+
+/// How [`plan_route`] searches for a route.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PlanOptions {
+    /// Slippage tolerance applied to each leg's `minimum_out_amount`
+    pub slippage_bps: u16,
+    /// Most pools the input is split across; 1 only considers single-pool routes
+    pub max_legs: usize,
+    /// Number of equal parts the input is divided into when searching for a split
+    pub split_parts: u64,
+    /// Capabilities a venue must have for its pools to be planned over
+    pub required_capabilities: Capabilities,
+}
+
+impl Default for PlanOptions {
+    fn default() -> Self {
+        Self {
+            slippage_bps: 50,
+            max_legs: 2,
+            split_parts: 20,
+            required_capabilities: Capabilities::NONE,
+        }
+    }
+}
+
+/// A route selling `in_amount` of `input_mint` for `out_amount` of `output_mint`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RoutePlan {
+    pub input_mint: Address,
+    pub output_mint: Address,
+    pub in_amount: u64,
+    /// Sum of the legs' quoted outputs
+    pub out_amount: u64,
+    /// Legs sharing the input, all trading `input_mint` for `output_mint`
+    pub legs: Vec<RouteLeg>,
+}
+
+/// A [`RoutePlan`] in the compact route encoding.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EncodedRoute {
+    pub accounts: Vec<AccountMeta>,
+    pub data: Vec<u8>,
+}
+
+impl RoutePlan {
+    /// Sum of the legs' minimum outputs.
+    pub fn minimum_out_amount(&self) -> u64 {
+        self.legs.iter().map(|leg| leg.minimum_out_amount).sum()
+    }
+
+    /// Pack the legs into the compact route encoding.
+    ///
+    /// # Panics
+    ///
+    /// If the plan has more than 255 legs, or a leg more than 255 accounts or bytes of
+    /// swap data.
+    pub fn encode(&self) -> EncodedRoute {
+        let mut accounts = Vec::new();
+        let mut data = vec![u8::try_from(self.legs.len()).expect("too many legs")];
+        for leg in &self.legs {
+            data.push(leg.protocol as u8);
+            data.push(u8::try_from(leg.accounts.len()).expect("too many leg accounts"));
+            data.extend_from_slice(&leg.in_amount.to_le_bytes());
+            data.extend_from_slice(&leg.minimum_out_amount.to_le_bytes());
+            data.push(u8::try_from(leg.data.len()).expect("leg swap data too long"));
+            data.extend_from_slice(&leg.data);
+            accounts.extend_from_slice(&leg.accounts);
+        }
+        EncodedRoute { accounts, data }
+    }
+}
+
+/// Why no route could be planned.
+#[derive(Debug)]
+pub enum PlanError<E> {
+    /// Reading or quoting a candidate pool failed.
+    Resolve(ResolveError<E>),
+    /// No candidate pool the client can quote trades the pair, or every quote is zero.
+    NoRoute,
+}
+
+impl<E> From<ResolveError<E>> for PlanError<E> {
+    fn from(error: ResolveError<E>) -> Self {
+        Self::Resolve(error)
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for PlanError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Resolve(error) => write!(f, "{error}"),
+            Self::NoRoute => write!(f, "no candidate pool routes the pair"),
+        }
+    }
+}
+
+impl<E: fmt::Debug + fmt::Display> std::error::Error for PlanError<E> {}
+
+/// A candidate pool trading the pair, with the leg fields that do not depend on the
+/// amount routed through it.
+struct Candidate {
+    pool: Address,
+    protocol: ProtocolKind,
+    accounts: Vec<AccountMeta>,
+    data: Vec<u8>,
+}
+
+/// Plan selling `in_amount` of `input_mint` for `output_mint` through `pools`, trading
+/// from `user`'s associated token accounts.
+///
+/// The split search hands the input out in `options.split_parts` equal parts, each to
+/// the pool whose output grows most from it, capped at `options.max_legs` pools. The
+/// split is kept only if it beats the best single pool.
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(
+        skip_all,
+        fields(%input_mint, %output_mint, in_amount = in_amount, pools = pools.len())
+    )
+)]
+pub fn plan_route<S: AccountSource>(
+    source: &S,
+    user: &Address,
+    input_mint: &Address,
+    output_mint: &Address,
+    in_amount: u64,
+    pools: &[Address],
+    options: &PlanOptions,
+) -> Result<RoutePlan, PlanError<S::Error>> {
+    let mut candidates = Vec::new();
+    for pool in pools {
+        if let Some(candidate) = candidate(source, pool, user, input_mint, output_mint)? {
+            if candidate
+                .protocol
+                .capabilities()
+                .contains(options.required_capabilities)
+            {
+                candidates.push(candidate);
+            }
+        }
+    }
+
+    let quote = |candidate: &Candidate, amount: u64| -> Result<u64, PlanError<S::Error>> {
+        if amount == 0 {
+            return Ok(0);
+        }
+        Ok(quote_out(source, candidate, input_mint, amount)?)
+    };
+
+    // Best single pool
+    let mut best: Option<(Vec<u64>, u64)> = None;
+    for (index, candidate) in candidates.iter().enumerate() {
+        let out_amount = quote(candidate, in_amount)?;
+        if best.as_ref().is_none_or(|(_, best)| out_amount > *best) {
+            let mut amounts = vec![0; candidates.len()];
+            amounts[index] = in_amount;
+            best = Some((amounts, out_amount));
+        }
+    }
+    let Some(mut best) = best else {
+        return Err(PlanError::NoRoute);
+    };
+
+    if options.max_legs > 1 && candidates.len() > 1 && options.split_parts > 1 {
+        let split = split_amounts(&candidates, in_amount, options, quote)?;
+        if split.1 > best.1 {
+            best = split;
+        }
+    }
+
+    let (amounts, out_amount) = best;
+    if out_amount == 0 {
+        return Err(PlanError::NoRoute);
+    }
+
+    let mut legs = Vec::new();
+    for (candidate, in_amount) in candidates.into_iter().zip(amounts) {
+        if in_amount == 0 {
+            continue;
+        }
+        let out_amount = quote(&candidate, in_amount)?;
+        debug_event!(protocol = ?candidate.protocol, pool = %candidate.pool, in_amount, out_amount, "planned leg");
+        legs.push(RouteLeg {
+            protocol: candidate.protocol,
+            in_amount,
+            out_amount,
+            minimum_out_amount: (out_amount as u128
+                * 10_000u128.saturating_sub(options.slippage_bps as u128)
+                / 10_000) as u64,
+            accounts: candidate.accounts,
+            data: candidate.data,
+        });
+    }
+
+    Ok(RoutePlan {
+        input_mint: *input_mint,
+        output_mint: *output_mint,
+        in_amount,
+        out_amount,
+        legs,
+    })
+}
+
+/// Greedily split `in_amount` across `candidates`, returning the amount routed through
+/// each and the total output.
+fn split_amounts<E>(
+    candidates: &[Candidate],
+    in_amount: u64,
+    options: &PlanOptions,
+    quote: impl Fn(&Candidate, u64) -> Result<u64, PlanError<E>>,
+) -> Result<(Vec<u64>, u64), PlanError<E>> {
+    let part = (in_amount / options.split_parts).max(1);
+    let mut amounts = vec![0u64; candidates.len()];
+    let mut outs = vec![0u64; candidates.len()];
+    let mut remaining = in_amount;
+
+    while remaining > 0 {
+        // The last part takes the rounding remainder
+        let amount = if remaining < part * 2 {
+            remaining
+        } else {
+            part
+        };
+        let used = amounts.iter().filter(|amount| **amount > 0).count();
+
+        // (candidate, its output with the part added, the output the part adds)
+        let mut best: Option<(usize, u64, u64)> = None;
+        for (index, candidate) in candidates.iter().enumerate() {
+            if amounts[index] == 0 && used >= options.max_legs {
+                continue;
+            }
+            let out_amount = quote(candidate, amounts[index] + amount)?;
+            let gain = out_amount.saturating_sub(outs[index]);
+            if best.is_none_or(|(_, _, best_gain)| gain > best_gain) {
+                best = Some((index, out_amount, gain));
+            }
+        }
+        let Some((index, out_amount, _)) = best else {
+            break;
+        };
+        amounts[index] += amount;
+        outs[index] = out_amount;
+        remaining -= amount;
+    }
+
+    Ok((amounts, outs.iter().sum()))
+}
+
+/// `pool` as a candidate for selling `input_mint` for `output_mint`, if it is on a
+/// venue the client can quote and trades the pair.
+fn candidate<S: AccountSource>(
+    source: &S,
+    pool: &Address,
+    user: &Address,
+    input_mint: &Address,
+    output_mint: &Address,
+) -> Result<Option<Candidate>, ResolveError<S::Error>> {
+    let account = fetch(source, pool)?;
+    let trades_pair = |mints: [Address; 2]| {
+        mints == [*input_mint, *output_mint] || mints == [*output_mint, *input_mint]
+    };
+
+    #[cfg(feature = "gamma-swap")]
+    if account.owner == beethoven::gamma::GAMMA_PROGRAM_ID {
+        use crate::{AdapterKeys, EncodeSwapData};
+
+        let state = beethoven::gamma::state::PoolState::from_account_data(&account.data)
+            .map_err(|_| ResolveError::InvalidAccountData(*pool))?;
+        if !trades_pair([state.token_0_mint, state.token_1_mint]) {
+            return Ok(None);
+        }
+        let keys = crate::gamma::resolve_swap_keys(source, pool, user, input_mint)?;
+        return Ok(Some(Candidate {
+            pool: *pool,
+            protocol: ProtocolKind::Gamma,
+            accounts: keys.routed_account_metas(),
+            data: ().encode(),
+        }));
+    }
+
+    #[cfg(feature = "mock_dex-swap")]
+    if account.owner == beethoven::mock_dex::MOCK_DEX_PROGRAM_ID {
+        use crate::{AdapterKeys, EncodeSwapData};
+
+        let (base_mint, quote_mint) = crate::mock_dex::pool_mints(source, pool)?;
+        if !trades_pair([base_mint, quote_mint]) {
+            return Ok(None);
+        }
+        let (keys, data) = crate::mock_dex::resolve_swap(source, pool, user, input_mint)?;
+        return Ok(Some(Candidate {
+            pool: *pool,
+            protocol: ProtocolKind::MockDex,
+            accounts: keys.routed_account_metas(),
+            data: data.encode(),
+        }));
+    }
+
+    let _ = (account, user, trades_pair);
+    Ok(None)
+}
+
+/// Output of selling `amount` of `input_mint` through `candidate`.
+fn quote_out<S: AccountSource>(
+    source: &S,
+    candidate: &Candidate,
+    input_mint: &Address,
+    amount: u64,
+) -> Result<u64, ResolveError<S::Error>> {
+    match candidate.protocol {
+        #[cfg(feature = "gamma-swap")]
+        ProtocolKind::Gamma => {
+            crate::gamma::quote_swap(source, &candidate.pool, input_mint, amount)
+                .map(|quote| quote.out_amount)
+        }
+        #[cfg(feature = "mock_dex-swap")]
+        ProtocolKind::MockDex => {
+            crate::mock_dex::quote_swap(source, &candidate.pool, input_mint, amount)
+                .map(|quote| quote.out_amount)
+        }
+        // Candidates are only built for the venues above
+        _ => {
+            let _ = (source, input_mint, amount);
+            Err(ResolveError::InvalidAccountData(candidate.pool))
+        }
+    }
+}
@@ -0,0 +1,39 @@
+//! Expected swap output, computed off-chain from the accounts a venue quotes against.
+
+use {
+    crate::resolve::ResolveError, beethoven::token::TOKEN_ACCOUNT_AMOUNT_OFFSET,
+    solana_address::Address,
+};
+
+/// Expected result of an exact-input swap
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SwapQuote {
+    pub in_amount: u64,
+    pub out_amount: u64,
+    /// Part of the input kept by the venue
+    pub fee_amount: u64,
+    /// Shortfall of `out_amount` against the fee-free output at the current spot price,
+    /// in basis points
+    pub price_impact_bps: u64,
+}
+
+/// Basis points `out_amount` falls short of `spot_out_amount` by.
+pub fn price_impact_bps(out_amount: u64, spot_out_amount: u128) -> u64 {
+    if spot_out_amount == 0 {
+        return 0;
+    }
+    let shortfall = spot_out_amount.saturating_sub(out_amount as u128);
+    (shortfall * 10_000 / spot_out_amount) as u64
+}
+
+/// Read the balance of the token account at `address` from its `data`.
+pub(crate) fn token_amount<E>(address: &Address, data: &[u8]) -> Result<u64, ResolveError<E>> {
+    read_token_amount(data).ok_or(ResolveError::InvalidAccountData(*address))
+}
+
+/// Read the balance of a token account from its `data`.
+pub(crate) fn read_token_amount(data: &[u8]) -> Option<u64> {
+    data.get(TOKEN_ACCOUNT_AMOUNT_OFFSET..TOKEN_ACCOUNT_AMOUNT_OFFSET + 8)
+        .and_then(|bytes| <[u8; 8]>::try_from(bytes).ok())
+        .map(u64::from_le_bytes)
+}
@@ -0,0 +1,177 @@
+//! Raydium CPMM liquidity instructions.
+
+use {
+    crate::{AccountSpec, AdapterKeys},
+    beethoven::{
+        raydium_cpmm::{self, AUTHORITY_SEED, RAYDIUM_CPMM_PROGRAM_ID},
+        ProtocolKind,
+    },
+    solana_address::Address,
+    solana_instruction::Instruction,
+};
+
+/// Addresses of [`beethoven::raydium_cpmm::RaydiumCpmmAddLiquidityAccounts`], without the
+/// program.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RaydiumCpmmAddLiquidityKeys {
+    pub owner: Address,
+    pub authority: Address,
+    pub pool_state: Address,
+    pub owner_lp_token: Address,
+    pub token_0_account: Address,
+    pub token_1_account: Address,
+    pub token_0_vault: Address,
+    pub token_1_vault: Address,
+    pub token_program: Address,
+    pub token_program_2022: Address,
+    pub vault_0_mint: Address,
+    pub vault_1_mint: Address,
+    pub lp_mint: Address,
+}
+
+impl AdapterKeys for RaydiumCpmmAddLiquidityKeys {
+    const PROTOCOL: ProtocolKind = ProtocolKind::RaydiumCpmm;
+
+    const PROGRAM_ID: Address = RAYDIUM_CPMM_PROGRAM_ID;
+
+    const ACCOUNTS: &'static [AccountSpec] = &[
+        AccountSpec::readonly_signer("owner"),
+        AccountSpec::readonly("authority"),
+        AccountSpec::writable("pool_state"),
+        AccountSpec::writable("owner_lp_token"),
+        AccountSpec::writable("token_0_account"),
+        AccountSpec::writable("token_1_account"),
+        AccountSpec::writable("token_0_vault"),
+        AccountSpec::writable("token_1_vault"),
+        AccountSpec::readonly("token_program"),
+        AccountSpec::readonly("token_program_2022"),
+        AccountSpec::readonly("vault_0_mint"),
+        AccountSpec::readonly("vault_1_mint"),
+        AccountSpec::writable("lp_mint"),
+    ];
+
+    fn addresses(&self) -> Vec<Address> {
+        vec![
+            self.owner,
+            self.authority,
+            self.pool_state,
+            self.owner_lp_token,
+            self.token_0_account,
+            self.token_1_account,
+            self.token_0_vault,
+            self.token_1_vault,
+            self.token_program,
+            self.token_program_2022,
+            self.vault_0_mint,
+            self.vault_1_mint,
+            self.lp_mint,
+        ]
+    }
+}
+
+/// Raydium CPMM `deposit` instruction calling the venue directly, minting exactly
+/// `lp_token_amount` for at most the given token amounts.
+pub fn add_liquidity_instruction(
+    keys: &RaydiumCpmmAddLiquidityKeys,
+    maximum_token_0_amount: u64,
+    maximum_token_1_amount: u64,
+    lp_token_amount: u64,
+) -> Instruction {
+    Instruction {
+        program_id: RAYDIUM_CPMM_PROGRAM_ID,
+        accounts: keys.account_metas(),
+        data: raydium_cpmm::deposit_instruction_data(
+            lp_token_amount,
+            maximum_token_0_amount,
+            maximum_token_1_amount,
+        )
+        .to_vec(),
+    }
+}
+
+/// Addresses of [`beethoven::raydium_cpmm::RaydiumCpmmRemoveLiquidityAccounts`], without
+/// the program.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RaydiumCpmmRemoveLiquidityKeys {
+    pub owner: Address,
+    pub authority: Address,
+    pub pool_state: Address,
+    pub owner_lp_token: Address,
+    pub token_0_account: Address,
+    pub token_1_account: Address,
+    pub token_0_vault: Address,
+    pub token_1_vault: Address,
+    pub token_program: Address,
+    pub token_program_2022: Address,
+    pub vault_0_mint: Address,
+    pub vault_1_mint: Address,
+    pub lp_mint: Address,
+    pub memo_program: Address,
+}
+
+impl AdapterKeys for RaydiumCpmmRemoveLiquidityKeys {
+    const PROTOCOL: ProtocolKind = ProtocolKind::RaydiumCpmm;
+
+    const PROGRAM_ID: Address = RAYDIUM_CPMM_PROGRAM_ID;
+
+    const ACCOUNTS: &'static [AccountSpec] = &[
+        AccountSpec::readonly_signer("owner"),
+        AccountSpec::readonly("authority"),
+        AccountSpec::writable("pool_state"),
+        AccountSpec::writable("owner_lp_token"),
+        AccountSpec::writable("token_0_account"),
+        AccountSpec::writable("token_1_account"),
+        AccountSpec::writable("token_0_vault"),
+        AccountSpec::writable("token_1_vault"),
+        AccountSpec::readonly("token_program"),
+        AccountSpec::readonly("token_program_2022"),
+        AccountSpec::readonly("vault_0_mint"),
+        AccountSpec::readonly("vault_1_mint"),
+        AccountSpec::writable("lp_mint"),
+        AccountSpec::readonly("memo_program"),
+    ];
+
+    fn addresses(&self) -> Vec<Address> {
+        vec![
+            self.owner,
+            self.authority,
+            self.pool_state,
+            self.owner_lp_token,
+            self.token_0_account,
+            self.token_1_account,
+            self.token_0_vault,
+            self.token_1_vault,
+            self.token_program,
+            self.token_program_2022,
+            self.vault_0_mint,
+            self.vault_1_mint,
+            self.lp_mint,
+            self.memo_program,
+        ]
+    }
+}
+
+/// Raydium CPMM `withdraw` instruction calling the venue directly, burning `lp_amount`
+/// for at least the given token amounts.
+pub fn remove_liquidity_instruction(
+    keys: &RaydiumCpmmRemoveLiquidityKeys,
+    lp_amount: u64,
+    minimum_token_0_amount: u64,
+    minimum_token_1_amount: u64,
+) -> Instruction {
+    Instruction {
+        program_id: RAYDIUM_CPMM_PROGRAM_ID,
+        accounts: keys.account_metas(),
+        data: raydium_cpmm::withdraw_instruction_data(
+            lp_amount,
+            minimum_token_0_amount,
+            minimum_token_1_amount,
+        )
+        .to_vec(),
+    }
+}
+
+/// The vault and LP mint authority shared by every Raydium CPMM pool.
+pub fn authority_address() -> Address {
+    Address::find_program_address(&[AUTHORITY_SEED], &RAYDIUM_CPMM_PROGRAM_ID).0
+}
@@ -0,0 +1,120 @@
+//! Fetching the on-chain state a `*Keys` struct is derived from.
+//!
+//! Resolvers take the venue's pool or market address and read everything else the
+//! adapter needs (vaults, mints, token programs, PDAs, the user's token accounts) from
+//! an [`AccountSource`], so integrators don't maintain account lists by hand.
+
+use {core::fmt, solana_address::Address, std::collections::HashMap};
+
+pub const SYSTEM_PROGRAM_ID: Address = Address::from_str_const("11111111111111111111111111111111");
+pub const ASSOCIATED_TOKEN_PROGRAM_ID: Address =
+    Address::from_str_const("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+
+/// The parts of an account a resolver reads.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FetchedAccount {
+    pub owner: Address,
+    pub data: Vec<u8>,
+}
+
+/// Where resolvers read accounts from: an RPC node (with the `rpc` feature), or an
+/// in-memory snapshot such as the accounts a browser frontend fetched itself.
+pub trait AccountSource {
+    type Error;
+
+    /// `Ok(None)` when the account does not exist.
+    fn get_account(&self, address: &Address) -> Result<Option<FetchedAccount>, Self::Error>;
+}
+
+impl AccountSource for HashMap<Address, FetchedAccount> {
+    type Error = core::convert::Infallible;
+
+    fn get_account(&self, address: &Address) -> Result<Option<FetchedAccount>, Self::Error> {
+        Ok(self.get(address).cloned())
+    }
+}
+
+#[cfg(all(feature = "rpc", not(target_arch = "wasm32")))]
+impl AccountSource for solana_rpc_client::rpc_client::RpcClient {
+    type Error = solana_rpc_client_api::client_error::Error;
+
+    fn get_account(&self, address: &Address) -> Result<Option<FetchedAccount>, Self::Error> {
+        let pubkey = solana_pubkey::Pubkey::new_from_array(address.to_bytes());
+        let account = self
+            .get_account_with_commitment(&pubkey, self.commitment())?
+            .value;
+
+        Ok(account.map(|account| FetchedAccount {
+            owner: Address::new_from_array(account.owner.to_bytes()),
+            data: account.data,
+        }))
+    }
+}
+
+/// Why an account list could not be resolved.
+#[derive(Debug)]
+pub enum ResolveError<E> {
+    /// The account source failed.
+    Source(E),
+    /// A required account does not exist.
+    AccountNotFound(Address),
+    /// An account's owner or data does not match the layout the resolver expects.
+    InvalidAccountData(Address),
+    /// The mint is not one of the pool's or market's two mints.
+    MintNotInPool(Address),
+}
+
+impl<E: fmt::Display> fmt::Display for ResolveError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Source(error) => write!(f, "account source error: {error}"),
+            Self::AccountNotFound(address) => write!(f, "account {address:?} not found"),
+            Self::InvalidAccountData(address) => {
+                write!(f, "account {address:?} has unexpected data")
+            }
+            Self::MintNotInPool(mint) => write!(f, "mint {mint:?} is not traded by the pool"),
+        }
+    }
+}
+
+impl<E: fmt::Debug + fmt::Display> std::error::Error for ResolveError<E> {}
+
+/// Fetch `address`, failing if it does not exist.
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "trace", skip_all, fields(account = %address))
+)]
+pub(crate) fn fetch<S: AccountSource>(
+    source: &S,
+    address: &Address,
+) -> Result<FetchedAccount, ResolveError<S::Error>> {
+    source
+        .get_account(address)
+        .map_err(ResolveError::Source)?
+        .ok_or(ResolveError::AccountNotFound(*address))
+}
+
+/// Read the 32 bytes at `offset` of `account`'s data as an address.
+pub(crate) fn read_address<E>(
+    address: &Address,
+    data: &[u8],
+    offset: usize,
+) -> Result<Address, ResolveError<E>> {
+    data.get(offset..offset + 32)
+        .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+        .map(Address::new_from_array)
+        .ok_or(ResolveError::InvalidAccountData(*address))
+}
+
+/// `owner`'s associated token account for `mint` under `token_program`.
+pub fn associated_token_address(
+    owner: &Address,
+    mint: &Address,
+    token_program: &Address,
+) -> Address {
+    Address::find_program_address(
+        &[owner.as_ref(), token_program.as_ref(), mint.as_ref()],
+        &ASSOCIATED_TOKEN_PROGRAM_ID,
+    )
+    .0
+}
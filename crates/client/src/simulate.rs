@@ -0,0 +1,148 @@
+//! Pre-flight simulation of an assembled swap.
+//!
+//! Beethoven emits no event of its own, so the realized output is read as the change in
+//! the user's output token account across the simulation, and a failure is traced back
+//! to the custom error code in the program logs. [`simulation_outcome`] works from what
+//! any simulator returns (e.g. a frontend simulating through its own connection);
+//! `simulate_swap` runs the simulation against an `RpcClient` with the `rpc` feature.
+
+use {crate::quote::read_token_amount, beethoven::BeethovenError};
+
+/// What a simulated swap would do.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SimulationOutcome {
+    /// Increase of the output token account's balance, when the simulation succeeded
+    pub out_amount: Option<u64>,
+    pub units_consumed: Option<u64>,
+    pub error: Option<SimulationError>,
+    pub logs: Vec<String>,
+}
+
+/// Why a simulated swap failed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SimulationError {
+    /// Beethoven rejected the accounts before reaching the venue.
+    Beethoven(BeethovenError),
+    /// A program failed with a custom error Beethoven does not define, usually the
+    /// venue's own.
+    Custom(u32),
+    /// Any other failure, as the simulator reported it.
+    Other(String),
+}
+
+/// Build the outcome of a simulation.
+///
+/// `pre_output_amount` is the output token account's balance before the swap (zero if
+/// the transaction creates it), `post_output_account_data` its data after, `err` the
+/// simulator's error if the transaction failed, and `logs` its program logs.
+pub fn simulation_outcome(
+    pre_output_amount: u64,
+    post_output_account_data: Option<&[u8]>,
+    units_consumed: Option<u64>,
+    err: Option<String>,
+    logs: Vec<String>,
+) -> SimulationOutcome {
+    let (out_amount, error) = match err {
+        Some(err) => {
+            let error = match custom_error_code(&logs) {
+                Some(code) => BeethovenError::from_code(code)
+                    .map_or(SimulationError::Custom(code), SimulationError::Beethoven),
+                None => SimulationError::Other(err),
+            };
+            (None, Some(error))
+        }
+        None => {
+            let post_output_amount = post_output_account_data.and_then(read_token_amount);
+            (
+                post_output_amount.map(|amount| amount.saturating_sub(pre_output_amount)),
+                None,
+            )
+        }
+    };
+
+    debug_event!(?out_amount, ?units_consumed, ?error, "simulated");
+
+    SimulationOutcome {
+        out_amount,
+        units_consumed,
+        error,
+        logs,
+    }
+}
+
+/// The code of the first `custom program error` in `logs`, i.e. the innermost failure.
+fn custom_error_code(logs: &[String]) -> Option<u32> {
+    logs.iter().find_map(|log| {
+        let (_, code) = log.split_once("custom program error: 0x")?;
+        u32::from_str_radix(code.trim(), 16).ok()
+    })
+}
+
+/// Simulate `transaction` on `rpc` and report what it would do to
+/// `output_token_account`.
+///
+/// Signatures are not verified and the blockhash is replaced, so an unsigned
+/// transaction from the assembler can be simulated as is.
+#[cfg(all(feature = "rpc", not(target_arch = "wasm32")))]
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(skip_all, fields(output_token_account = %output_token_account))
+)]
+pub fn simulate_swap(
+    rpc: &solana_rpc_client::rpc_client::RpcClient,
+    transaction: &impl solana_rpc_client::rpc_client::SerializableTransaction,
+    output_token_account: &solana_address::Address,
+) -> Result<
+    SimulationOutcome,
+    crate::resolve::ResolveError<solana_rpc_client_api::client_error::Error>,
+> {
+    use {
+        crate::{
+            quote::token_amount,
+            resolve::{AccountSource, ResolveError},
+        },
+        base64::{engine::general_purpose::STANDARD, Engine},
+        solana_account_decoder_client_types::{UiAccountData, UiAccountEncoding},
+        solana_rpc_client_api::config::{
+            RpcSimulateTransactionAccountsConfig, RpcSimulateTransactionConfig,
+        },
+    };
+
+    let pre_output_amount = match AccountSource::get_account(rpc, output_token_account)
+        .map_err(ResolveError::Source)?
+    {
+        Some(account) => token_amount(output_token_account, &account.data)?,
+        None => 0,
+    };
+
+    let config = RpcSimulateTransactionConfig {
+        sig_verify: false,
+        replace_recent_blockhash: true,
+        commitment: Some(rpc.commitment()),
+        accounts: Some(RpcSimulateTransactionAccountsConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            addresses: vec![output_token_account.to_string()],
+        }),
+        ..Default::default()
+    };
+    let result = rpc
+        .simulate_transaction_with_config(transaction, config)
+        .map_err(ResolveError::Source)?
+        .value;
+
+    let post_output_account_data = result
+        .accounts
+        .and_then(|accounts| accounts.into_iter().next().flatten())
+        .and_then(|account| match account.data {
+            UiAccountData::Binary(data, UiAccountEncoding::Base64) => STANDARD.decode(data).ok(),
+            _ => None,
+        });
+
+    Ok(simulation_outcome(
+        pre_output_amount,
+        post_output_account_data.as_deref(),
+        result.units_consumed,
+        result.err.map(|err| format!("{err:?}")),
+        result.logs.unwrap_or_default(),
+    ))
+}
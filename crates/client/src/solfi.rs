@@ -0,0 +1,82 @@
+//! SolFi swap instructions.
+
+use {
+    crate::{instruction_data, AccountSpec, AdapterKeys, EncodeSwapData, FieldSpec, FieldType},
+    beethoven::{
+        solfi::{self, SolFiSwapData, SOLFI_PROGRAM_ID},
+        ProtocolKind,
+    },
+    solana_address::Address,
+    solana_instruction::Instruction,
+};
+
+/// Addresses of [`beethoven::solfi::SolFiSwapAccounts`], without the program.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SolFiSwapKeys {
+    pub token_transfer_authority: Address,
+    pub market_account: Address,
+    pub base_vault: Address,
+    pub quote_vault: Address,
+    pub user_base_ata: Address,
+    pub user_quote_ata: Address,
+    pub token_program: Address,
+    pub instructions_sysvar: Address,
+}
+
+impl AdapterKeys for SolFiSwapKeys {
+    const PROTOCOL: ProtocolKind = ProtocolKind::SolFi;
+
+    const PROGRAM_ID: Address = SOLFI_PROGRAM_ID;
+
+    const ACCOUNTS: &'static [AccountSpec] = &[
+        AccountSpec::writable_signer("token_transfer_authority"),
+        AccountSpec::writable("market_account"),
+        AccountSpec::writable("base_vault"),
+        AccountSpec::writable("quote_vault"),
+        AccountSpec::writable("user_base_ata"),
+        AccountSpec::writable("user_quote_ata"),
+        AccountSpec::readonly("token_program"),
+        AccountSpec::readonly("instructions_sysvar"),
+    ];
+
+    fn addresses(&self) -> Vec<Address> {
+        vec![
+            self.token_transfer_authority,
+            self.market_account,
+            self.base_vault,
+            self.quote_vault,
+            self.user_base_ata,
+            self.user_quote_ata,
+            self.token_program,
+            self.instructions_sysvar,
+        ]
+    }
+}
+
+impl EncodeSwapData for SolFiSwapData {
+    const FIELDS: &'static [FieldSpec] = &[FieldSpec {
+        name: "is_quote_to_base",
+        ty: FieldType::Bool,
+        docs: "",
+    }];
+
+    fn encode(&self) -> Vec<u8> {
+        vec![self.is_quote_to_base as u8]
+    }
+}
+
+/// SolFi swap instruction calling the venue directly.
+pub fn swap_instruction(
+    keys: &SolFiSwapKeys,
+    in_amount: u64,
+    minimum_out_amount: u64,
+    swap_data: &SolFiSwapData,
+) -> Instruction {
+    Instruction {
+        program_id: SOLFI_PROGRAM_ID,
+        accounts: keys.account_metas(),
+        data: instruction_data::<{ solfi::MAX_INSTRUCTION_DATA_LEN }, _>(|scratch| {
+            solfi::swap_instruction_data(scratch, in_amount, minimum_out_amount, swap_data)
+        }),
+    }
+}
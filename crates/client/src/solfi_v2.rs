@@ -0,0 +1,97 @@
+//! SolFi V2 swap instructions.
+
+use {
+    crate::{instruction_data, AccountSpec, AdapterKeys, EncodeSwapData, FieldSpec, FieldType},
+    beethoven::{
+        solfi_v2::{self, SolFiV2SwapData, SOLFI_V2_PROGRAM_ID},
+        ProtocolKind,
+    },
+    solana_address::Address,
+    solana_instruction::Instruction,
+};
+
+/// Addresses of [`beethoven::solfi_v2::SolFiV2SwapAccounts`], without the program.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SolFiV2SwapKeys {
+    pub token_transfer_authority: Address,
+    pub market_account: Address,
+    pub oracle_account: Address,
+    pub config_account: Address,
+    pub base_vault: Address,
+    pub quote_vault: Address,
+    pub user_base_ata: Address,
+    pub user_quote_ata: Address,
+    pub base_mint: Address,
+    pub quote_mint: Address,
+    pub base_token_program: Address,
+    pub quote_token_program: Address,
+    pub instructions_sysvar: Address,
+}
+
+impl AdapterKeys for SolFiV2SwapKeys {
+    const PROTOCOL: ProtocolKind = ProtocolKind::SolFiV2;
+
+    const PROGRAM_ID: Address = SOLFI_V2_PROGRAM_ID;
+
+    const ACCOUNTS: &'static [AccountSpec] = &[
+        AccountSpec::writable_signer("token_transfer_authority"),
+        AccountSpec::writable("market_account"),
+        AccountSpec::readonly("oracle_account"),
+        AccountSpec::readonly("config_account"),
+        AccountSpec::writable("base_vault"),
+        AccountSpec::writable("quote_vault"),
+        AccountSpec::writable("user_base_ata"),
+        AccountSpec::writable("user_quote_ata"),
+        AccountSpec::readonly("base_mint"),
+        AccountSpec::readonly("quote_mint"),
+        AccountSpec::readonly("base_token_program"),
+        AccountSpec::readonly("quote_token_program"),
+        AccountSpec::readonly("instructions_sysvar"),
+    ];
+
+    fn addresses(&self) -> Vec<Address> {
+        vec![
+            self.token_transfer_authority,
+            self.market_account,
+            self.oracle_account,
+            self.config_account,
+            self.base_vault,
+            self.quote_vault,
+            self.user_base_ata,
+            self.user_quote_ata,
+            self.base_mint,
+            self.quote_mint,
+            self.base_token_program,
+            self.quote_token_program,
+            self.instructions_sysvar,
+        ]
+    }
+}
+
+impl EncodeSwapData for SolFiV2SwapData {
+    const FIELDS: &'static [FieldSpec] = &[FieldSpec {
+        name: "is_quote_to_base",
+        ty: FieldType::Bool,
+        docs: "",
+    }];
+
+    fn encode(&self) -> Vec<u8> {
+        vec![self.is_quote_to_base as u8]
+    }
+}
+
+/// SolFi V2 swap instruction calling the venue directly.
+pub fn swap_instruction(
+    keys: &SolFiV2SwapKeys,
+    in_amount: u64,
+    minimum_out_amount: u64,
+    swap_data: &SolFiV2SwapData,
+) -> Instruction {
+    Instruction {
+        program_id: SOLFI_V2_PROGRAM_ID,
+        accounts: keys.account_metas(),
+        data: instruction_data::<{ solfi_v2::MAX_INSTRUCTION_DATA_LEN }, _>(|scratch| {
+            solfi_v2::swap_instruction_data(scratch, in_amount, minimum_out_amount, swap_data)
+        }),
+    }
+}
@@ -0,0 +1,93 @@
+//! SPL stake pool unstake instructions.
+
+use {
+    crate::{AccountSpec, AdapterKeys},
+    beethoven::{
+        spl_stake_pool::{self, SPL_STAKE_POOL_PROGRAM_ID},
+        ProtocolKind,
+    },
+    solana_address::Address,
+    solana_instruction::Instruction,
+};
+
+/// The stake history sysvar
+pub const STAKE_HISTORY_SYSVAR_ID: Address =
+    Address::from_str_const("SysvarStakeHistory1111111111111111111111111");
+
+/// The native stake program
+pub const STAKE_PROGRAM_ID: Address =
+    Address::from_str_const("Stake11111111111111111111111111111111111111");
+
+/// Addresses of [`beethoven::spl_stake_pool::SplStakePoolUnstakeAccounts`], without the
+/// program.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SplStakePoolUnstakeKeys {
+    pub stake_pool: Address,
+    pub withdraw_authority: Address,
+    pub user_transfer_authority: Address,
+    pub pool_tokens_from: Address,
+    pub reserve_stake: Address,
+    pub lamports_to: Address,
+    pub manager_fee_account: Address,
+    pub pool_mint: Address,
+    pub clock: Address,
+    pub stake_history: Address,
+    pub stake_program: Address,
+    pub token_program: Address,
+}
+
+impl AdapterKeys for SplStakePoolUnstakeKeys {
+    const PROTOCOL: ProtocolKind = ProtocolKind::SplStakePool;
+
+    const PROGRAM_ID: Address = SPL_STAKE_POOL_PROGRAM_ID;
+
+    const ACCOUNTS: &'static [AccountSpec] = &[
+        AccountSpec::writable("stake_pool"),
+        AccountSpec::readonly("withdraw_authority"),
+        AccountSpec::readonly_signer("user_transfer_authority"),
+        AccountSpec::writable("pool_tokens_from"),
+        AccountSpec::writable("reserve_stake"),
+        AccountSpec::writable("lamports_to"),
+        AccountSpec::writable("manager_fee_account"),
+        AccountSpec::writable("pool_mint"),
+        AccountSpec::readonly("clock"),
+        AccountSpec::readonly("stake_history"),
+        AccountSpec::readonly("stake_program"),
+        AccountSpec::readonly("token_program"),
+    ];
+
+    fn addresses(&self) -> Vec<Address> {
+        vec![
+            self.stake_pool,
+            self.withdraw_authority,
+            self.user_transfer_authority,
+            self.pool_tokens_from,
+            self.reserve_stake,
+            self.lamports_to,
+            self.manager_fee_account,
+            self.pool_mint,
+            self.clock,
+            self.stake_history,
+            self.stake_program,
+            self.token_program,
+        ]
+    }
+}
+
+/// Stake pool `WithdrawSol` instruction calling the venue directly.
+pub fn unstake_instruction(keys: &SplStakePoolUnstakeKeys, pool_tokens: u64) -> Instruction {
+    Instruction {
+        program_id: SPL_STAKE_POOL_PROGRAM_ID,
+        accounts: keys.account_metas(),
+        data: spl_stake_pool::withdraw_sol_instruction_data(pool_tokens).to_vec(),
+    }
+}
+
+/// The withdraw authority of `stake_pool`.
+pub fn withdraw_authority_address(stake_pool: &Address) -> Address {
+    Address::find_program_address(
+        &[stake_pool.as_ref(), b"withdraw"],
+        &SPL_STAKE_POOL_PROGRAM_ID,
+    )
+    .0
+}
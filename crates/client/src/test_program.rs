@@ -0,0 +1,600 @@
+//! Instructions for the `beethoven-test` program in `program-test`.
+
+pub use beethoven_test_support::{
+    discriminator, AUTHORITY_SEED, REGISTERED_PROGRAM_ID, REGISTERED_SWAP_ERROR, TEST_PROGRAM_ID,
+};
+use {
+    crate::{
+        fee::SwapFeeKeys, planner::EncodedRoute, AdapterKeys, EncodeDepositData, EncodeSwapData,
+    },
+    beethoven::{envelope::Envelope, Detection, Direction, OrderType, SwapFees, SwapOutcome},
+    solana_address::Address,
+    solana_instruction::{AccountMeta, Instruction},
+};
+
+/// The authority PDA of the program deployed at `program_id`, and its bump.
+pub fn authority_address(program_id: &Address) -> (Address, u8) {
+    Address::find_program_address(&[AUTHORITY_SEED], program_id)
+}
+
+/// Route a deposit through `program_id` into the venue `keys` belong to.
+///
+/// Data layout: discriminator (u8), amount (u64)
+pub fn deposit_instruction(
+    program_id: Address,
+    keys: &impl AdapterKeys,
+    amount: u64,
+) -> Instruction {
+    let mut data = vec![discriminator::DEPOSIT];
+    data.extend_from_slice(&amount.to_le_bytes());
+
+    Instruction {
+        program_id,
+        accounts: keys.routed_account_metas(),
+        data,
+    }
+}
+
+/// [`deposit_instruction`] followed by the venue's deposit data.
+///
+/// Data layout: discriminator (u8), amount (u64), deposit data
+pub fn deposit_with_data_instruction(
+    program_id: Address,
+    keys: &impl AdapterKeys,
+    amount: u64,
+    deposit_data: &impl EncodeDepositData,
+) -> Instruction {
+    let mut instruction = deposit_instruction(program_id, keys, amount);
+    instruction.data.extend_from_slice(&deposit_data.encode());
+    instruction
+}
+
+/// Route a withdraw of `amount` receipt tokens through `program_id` out of the venue
+/// `keys` belong to.
+///
+/// Data layout: discriminator (u8), amount (u64)
+pub fn withdraw_instruction(
+    program_id: Address,
+    keys: &impl AdapterKeys,
+    amount: u64,
+) -> Instruction {
+    let mut data = vec![discriminator::WITHDRAW];
+    data.extend_from_slice(&amount.to_le_bytes());
+
+    Instruction {
+        program_id,
+        accounts: keys.routed_account_metas(),
+        data,
+    }
+}
+
+/// Borrow `amount` through `program_id` from the Kamino reserve in `keys`.
+///
+/// Data layout: discriminator (u8), amount (u64)
+pub fn borrow_instruction(
+    program_id: Address,
+    keys: &impl AdapterKeys,
+    amount: u64,
+) -> Instruction {
+    amount_instruction(program_id, keys, discriminator::BORROW, amount)
+}
+
+/// Repay `amount` through `program_id` to the Kamino reserve in `keys`.
+///
+/// Data layout: discriminator (u8), amount (u64)
+pub fn repay_instruction(program_id: Address, keys: &impl AdapterKeys, amount: u64) -> Instruction {
+    amount_instruction(program_id, keys, discriminator::REPAY, amount)
+}
+
+/// Stake `lamports` through `program_id` with the venue `keys` belong to.
+///
+/// Data layout: discriminator (u8), lamports (u64)
+pub fn stake_instruction(
+    program_id: Address,
+    keys: &impl AdapterKeys,
+    lamports: u64,
+) -> Instruction {
+    amount_instruction(program_id, keys, discriminator::STAKE, lamports)
+}
+
+/// Unstake `amount` through `program_id` with the venue `keys` belong to.
+///
+/// Data layout: discriminator (u8), amount (u64), then the venue's unstake data
+/// (Marinade: its `MarinadeUnstakeData` as a u8; SPL stake pools: none)
+pub fn unstake_instruction(
+    program_id: Address,
+    keys: &impl AdapterKeys,
+    amount: u64,
+    unstake_data: &[u8],
+) -> Instruction {
+    let mut instruction = amount_instruction(program_id, keys, discriminator::UNSTAKE, amount);
+    instruction.data.extend_from_slice(unstake_data);
+    instruction
+}
+
+/// Add liquidity through `program_id` to the pool in `keys`.
+///
+/// Data layout: discriminator (u8), maximum_token_0_amount (u64),
+/// maximum_token_1_amount (u64), minimum_lp_amount (u64)
+pub fn add_liquidity_instruction(
+    program_id: Address,
+    keys: &impl AdapterKeys,
+    maximum_token_0_amount: u64,
+    maximum_token_1_amount: u64,
+    minimum_lp_amount: u64,
+) -> Instruction {
+    let mut instruction = amount_instruction(
+        program_id,
+        keys,
+        discriminator::ADD_LIQUIDITY,
+        maximum_token_0_amount,
+    );
+    instruction
+        .data
+        .extend_from_slice(&maximum_token_1_amount.to_le_bytes());
+    instruction
+        .data
+        .extend_from_slice(&minimum_lp_amount.to_le_bytes());
+    instruction
+}
+
+/// Remove liquidity through `program_id` from the pool in `keys`.
+///
+/// Data layout: discriminator (u8), lp_amount (u64), minimum_token_0_amount (u64),
+/// minimum_token_1_amount (u64)
+pub fn remove_liquidity_instruction(
+    program_id: Address,
+    keys: &impl AdapterKeys,
+    lp_amount: u64,
+    minimum_token_0_amount: u64,
+    minimum_token_1_amount: u64,
+) -> Instruction {
+    let mut instruction =
+        amount_instruction(program_id, keys, discriminator::REMOVE_LIQUIDITY, lp_amount);
+    instruction
+        .data
+        .extend_from_slice(&minimum_token_0_amount.to_le_bytes());
+    instruction
+        .data
+        .extend_from_slice(&minimum_token_1_amount.to_le_bytes());
+    instruction
+}
+
+/// Place an order through `program_id` on the Manifest market in `keys`.
+///
+/// Data layout: discriminator (u8), side (u8), base_amount (u64), order type (u8), then
+/// the order's price_mantissa (u32), price_exponent (i8) and last_valid_slot (u32)
+#[cfg(feature = "manifest-swap")]
+pub fn place_order_instruction(
+    program_id: Address,
+    keys: &impl AdapterKeys,
+    side: Direction,
+    base_amount: u64,
+    order_type: OrderType,
+    order: &beethoven::manifest::ManifestOrderData,
+) -> Instruction {
+    let mut data = vec![discriminator::PLACE_ORDER, side as u8];
+    data.extend_from_slice(&base_amount.to_le_bytes());
+    data.push(order_type as u8);
+    data.extend_from_slice(&order.price_mantissa.to_le_bytes());
+    data.push(order.price_exponent as u8);
+    data.extend_from_slice(&order.last_valid_slot.to_le_bytes());
+
+    Instruction {
+        program_id,
+        accounts: keys.routed_account_metas(),
+        data,
+    }
+}
+
+/// Cancel the order with `order_sequence_number` through `program_id` on the market in
+/// `keys`.
+///
+/// Data layout: discriminator (u8), order sequence number (u64)
+pub fn cancel_order_instruction(
+    program_id: Address,
+    keys: &impl AdapterKeys,
+    order_sequence_number: u64,
+) -> Instruction {
+    amount_instruction(
+        program_id,
+        keys,
+        discriminator::CANCEL_ORDER,
+        order_sequence_number,
+    )
+}
+
+/// Place a perp market order through `program_id` on the venue `keys` belong to.
+///
+/// Data layout: discriminator (u8), market_index (u16), direction (u8), base_amount
+/// (u64), price_limit (u64)
+pub fn perp_order_instruction(
+    program_id: Address,
+    keys: &impl AdapterKeys,
+    market_index: u16,
+    direction: Direction,
+    base_amount: u64,
+    price_limit: u64,
+) -> Instruction {
+    let mut data = vec![discriminator::PERP_ORDER];
+    data.extend_from_slice(&market_index.to_le_bytes());
+    data.push(direction as u8);
+    data.extend_from_slice(&base_amount.to_le_bytes());
+    data.extend_from_slice(&price_limit.to_le_bytes());
+
+    Instruction {
+        program_id,
+        accounts: keys.routed_account_metas(),
+        data,
+    }
+}
+
+/// `discriminator` then `amount`, on `keys`' routed accounts.
+fn amount_instruction(
+    program_id: Address,
+    keys: &impl AdapterKeys,
+    discriminator: u8,
+    amount: u64,
+) -> Instruction {
+    let mut data = vec![discriminator];
+    data.extend_from_slice(&amount.to_le_bytes());
+
+    Instruction {
+        program_id,
+        accounts: keys.routed_account_metas(),
+        data,
+    }
+}
+
+/// Route a swap through `program_id` into the venue `keys` belong to.
+///
+/// Data layout: discriminator (u8), in_amount (u64), minimum_out_amount (u64), then the
+/// venue's swap data
+pub fn swap_instruction(
+    program_id: Address,
+    keys: &impl AdapterKeys,
+    in_amount: u64,
+    minimum_out_amount: u64,
+    swap_data: &impl EncodeSwapData,
+) -> Instruction {
+    let mut data = vec![discriminator::SWAP];
+    data.extend_from_slice(&in_amount.to_le_bytes());
+    data.extend_from_slice(&minimum_out_amount.to_le_bytes());
+    data.extend_from_slice(&swap_data.encode());
+
+    Instruction {
+        program_id,
+        accounts: keys.routed_account_metas(),
+        data,
+    }
+}
+
+/// [`swap_instruction`] with the venue's swap data in a versioned
+/// [`Envelope`](beethoven::envelope::Envelope) naming `keys`' protocol.
+///
+/// Data layout: discriminator (u8), in_amount (u64), minimum_out_amount (u64), envelope
+/// version (u8), protocol (u8), then the venue's swap data
+pub fn enveloped_swap_instruction<K: AdapterKeys>(
+    program_id: Address,
+    keys: &K,
+    in_amount: u64,
+    minimum_out_amount: u64,
+    swap_data: &impl EncodeSwapData,
+) -> Instruction {
+    let payload = swap_data.encode();
+    let envelope = Envelope::new(K::PROTOCOL, &payload);
+
+    let mut data = vec![discriminator::SWAP_ENVELOPED];
+    data.extend_from_slice(&in_amount.to_le_bytes());
+    data.extend_from_slice(&minimum_out_amount.to_le_bytes());
+    data.extend_from_slice(&envelope.header());
+    data.extend_from_slice(envelope.payload);
+
+    Instruction {
+        program_id,
+        accounts: keys.routed_account_metas(),
+        data,
+    }
+}
+
+/// [`swap_instruction`] on `accounts` laid out for `detection` rather than detector
+/// first.
+///
+/// Data layout: discriminator (u8), detection kind (u8: 0 program at, 1 owner of,
+/// 2 tagged), index or `ProtocolKind` discriminant (u8), in_amount (u64),
+/// minimum_out_amount (u64), swap data
+pub fn detected_swap_instruction(
+    program_id: Address,
+    accounts: Vec<AccountMeta>,
+    detection: Detection,
+    in_amount: u64,
+    minimum_out_amount: u64,
+    swap_data: &impl EncodeSwapData,
+) -> Instruction {
+    let detection = match detection {
+        Detection::ProgramAt(index) => [0, index as u8],
+        Detection::OwnerOf(index) => [1, index as u8],
+        Detection::Tagged(protocol) => [2, protocol as u8],
+    };
+
+    let mut data = vec![discriminator::SWAP_DETECTED];
+    data.extend_from_slice(&detection);
+    data.extend_from_slice(&in_amount.to_le_bytes());
+    data.extend_from_slice(&minimum_out_amount.to_le_bytes());
+    data.extend(swap_data.encode());
+
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+/// A swap through `beethoven::try_from_swap_context_with` and the test program's
+/// registry, on `accounts` for either a built-in venue or [`REGISTERED_PROGRAM_ID`].
+///
+/// Data layout: discriminator (u8), in_amount (u64), minimum_out_amount (u64), swap
+/// data
+pub fn registry_swap_instruction(
+    program_id: Address,
+    accounts: Vec<AccountMeta>,
+    in_amount: u64,
+    minimum_out_amount: u64,
+    swap_data: &[u8],
+) -> Instruction {
+    let mut data = vec![discriminator::SWAP_REGISTRY];
+    data.extend_from_slice(&in_amount.to_le_bytes());
+    data.extend_from_slice(&minimum_out_amount.to_le_bytes());
+    data.extend_from_slice(swap_data);
+
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+/// [`swap_instruction`] returning the realized `beethoven::SwapOutcome`, which
+/// [`parse_swap_outcome`] reads back from the transaction's return data.
+pub fn swap_outcome_instruction(
+    program_id: Address,
+    keys: &impl AdapterKeys,
+    in_amount: u64,
+    minimum_out_amount: u64,
+    swap_data: &impl EncodeSwapData,
+) -> Instruction {
+    let mut instruction =
+        swap_instruction(program_id, keys, in_amount, minimum_out_amount, swap_data);
+    instruction.data[0] = discriminator::SWAP_OUTCOME;
+    instruction
+}
+
+/// [`swap_instruction`] through the venue's `swap_signed_passthrough`, for SolFi and
+/// SolFi V2 keys.
+pub fn swap_passthrough_instruction(
+    program_id: Address,
+    keys: &impl AdapterKeys,
+    in_amount: u64,
+    minimum_out_amount: u64,
+    swap_data: &impl EncodeSwapData,
+) -> Instruction {
+    let mut instruction =
+        swap_instruction(program_id, keys, in_amount, minimum_out_amount, swap_data);
+    instruction.data[0] = discriminator::SWAP_PASSTHROUGH;
+    instruction
+}
+
+/// [`swap_instruction`] as an exact-out swap: receive exactly `out_amount`, spending at
+/// most `maximum_in_amount`.
+pub fn swap_exact_out_instruction(
+    program_id: Address,
+    keys: &impl AdapterKeys,
+    out_amount: u64,
+    maximum_in_amount: u64,
+    swap_data: &impl EncodeSwapData,
+) -> Instruction {
+    let mut instruction =
+        swap_instruction(program_id, keys, out_amount, maximum_in_amount, swap_data);
+    instruction.data[0] = discriminator::SWAP_EXACT_OUT;
+    instruction
+}
+
+/// The outcome a [`swap_outcome_instruction`] returned, or `None` when `return_data`
+/// is not one.
+pub fn parse_swap_outcome(return_data: &[u8]) -> Option<SwapOutcome> {
+    let (in_spent, out_received) = return_data.split_first_chunk::<8>()?;
+    Some(SwapOutcome {
+        in_spent: u64::from_le_bytes(*in_spent),
+        out_received: u64::from_le_bytes(out_received.try_into().ok()?),
+    })
+}
+
+/// [`deposit_instruction`] with the program's authority PDA (see [`authority_address`])
+/// signing in place of the depositor, who must be that PDA in `keys`.
+pub fn signed_deposit_instruction(
+    program_id: Address,
+    keys: &impl AdapterKeys,
+    amount: u64,
+) -> Instruction {
+    let mut instruction = deposit_instruction(program_id, keys, amount);
+    instruction.data[0] = discriminator::DEPOSIT_SIGNED;
+    unsign_authority(&program_id, &mut instruction.accounts);
+    instruction
+}
+
+/// [`swap_instruction`] with the program's authority PDA (see [`authority_address`])
+/// signing in place of the trader, who must be that PDA in `keys`.
+pub fn signed_swap_instruction(
+    program_id: Address,
+    keys: &impl AdapterKeys,
+    in_amount: u64,
+    minimum_out_amount: u64,
+    swap_data: &impl EncodeSwapData,
+) -> Instruction {
+    let mut instruction =
+        swap_instruction(program_id, keys, in_amount, minimum_out_amount, swap_data);
+    instruction.data[0] = discriminator::SWAP_SIGNED;
+    unsign_authority(&program_id, &mut instruction.accounts);
+    instruction
+}
+
+/// The authority PDA signs through the program's CPI, not the transaction.
+fn unsign_authority(program_id: &Address, metas: &mut [AccountMeta]) {
+    let (authority, _) = authority_address(program_id);
+    for meta in metas.iter_mut().filter(|meta| meta.pubkey == authority) {
+        meta.is_signer = false;
+    }
+}
+
+/// Swap every leg of `route` through `program_id`, in order.
+///
+/// Data layout: discriminator (u8), then the route encoding of
+/// [`crate::planner::RoutePlan::encode`]
+pub fn route_instruction(program_id: Address, route: &EncodedRoute) -> Instruction {
+    let mut data = vec![discriminator::ROUTE];
+    data.extend_from_slice(&route.data);
+
+    Instruction {
+        program_id,
+        accounts: route.accounts.clone(),
+        data,
+    }
+}
+
+/// Swap `in_amount` and pay `fees` out of what it returned, through
+/// `beethoven::swap_with_fees`. The program sets the outcome as return data, read by
+/// [`parse_swap_outcome`], with `out_received` being what the user kept.
+///
+/// Data layout: discriminator (u8), in_amount (u64), minimum_out_amount (u64),
+/// platform_bps (u16), referral_bps (u16), swap data
+pub fn swap_with_fees_instruction(
+    program_id: Address,
+    fee_keys: &SwapFeeKeys,
+    swap_keys: &impl AdapterKeys,
+    in_amount: u64,
+    minimum_out_amount: u64,
+    fees: &SwapFees,
+    swap_data: &impl EncodeSwapData,
+) -> Instruction {
+    let mut data = vec![discriminator::SWAP_WITH_FEES];
+    data.extend_from_slice(&in_amount.to_le_bytes());
+    data.extend_from_slice(&minimum_out_amount.to_le_bytes());
+    data.extend_from_slice(&fees.platform_bps.to_le_bytes());
+    data.extend_from_slice(&fees.referral_bps.to_le_bytes());
+    data.extend(swap_data.encode());
+    let mut accounts = fee_keys.account_metas();
+    accounts.extend(swap_keys.routed_account_metas());
+
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+/// Swap `in_amount` into the account `deposit_keys` spends from, then deposit everything
+/// the swap returned, through `beethoven::zap`.
+///
+/// Data layout: discriminator (u8), swap account count (u8), in_amount (u64),
+/// minimum_out_amount (u64), swap data
+pub fn zap_instruction(
+    program_id: Address,
+    swap_keys: &impl AdapterKeys,
+    deposit_keys: &impl AdapterKeys,
+    in_amount: u64,
+    minimum_out_amount: u64,
+    swap_data: &impl EncodeSwapData,
+) -> Instruction {
+    let mut accounts = swap_keys.routed_account_metas();
+    let mut data = vec![discriminator::ZAP, accounts.len() as u8];
+    data.extend_from_slice(&in_amount.to_le_bytes());
+    data.extend_from_slice(&minimum_out_amount.to_le_bytes());
+    data.extend(swap_data.encode());
+    accounts.extend(deposit_keys.routed_account_metas());
+
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+/// Swap `in_amount` through `legs` with `beethoven::SwapRoute`, each leg spending what
+/// the one before it credited. Each leg is its routed accounts and encoded swap data.
+///
+/// Data layout: discriminator (u8), in_amount (u64), minimum_out_amount (u64), leg
+/// count (u8), then per leg: account count (u8), swap data length (u8) and swap data
+pub fn swap_route_instruction(
+    program_id: Address,
+    in_amount: u64,
+    minimum_out_amount: u64,
+    legs: &[(Vec<AccountMeta>, Vec<u8>)],
+) -> Instruction {
+    let mut data = vec![discriminator::SWAP_ROUTE];
+    data.extend_from_slice(&in_amount.to_le_bytes());
+    data.extend_from_slice(&minimum_out_amount.to_le_bytes());
+    data.push(legs.len() as u8);
+
+    let mut accounts = Vec::new();
+    for (leg_accounts, swap_data) in legs {
+        data.push(leg_accounts.len() as u8);
+        data.push(swap_data.len() as u8);
+        data.extend_from_slice(swap_data);
+        accounts.extend_from_slice(leg_accounts);
+    }
+
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
+
+/// [`swap_route_instruction`] through `beethoven::SharedSwapRoute`: every account the
+/// legs name is passed once, signer or writable if any leg needs it so, and each leg
+/// lists its accounts as indices into them.
+///
+/// Data layout: discriminator (u8), in_amount (u64), minimum_out_amount (u64), leg
+/// count (u8), then per leg: account count (u8), account indices (u8 each), swap data
+/// length (u8) and swap data
+pub fn shared_swap_route_instruction(
+    program_id: Address,
+    in_amount: u64,
+    minimum_out_amount: u64,
+    legs: &[(Vec<AccountMeta>, Vec<u8>)],
+) -> Instruction {
+    let mut data = vec![discriminator::SWAP_ROUTE_SHARED];
+    data.extend_from_slice(&in_amount.to_le_bytes());
+    data.extend_from_slice(&minimum_out_amount.to_le_bytes());
+    data.push(legs.len() as u8);
+
+    let mut accounts: Vec<AccountMeta> = Vec::new();
+    for (leg_accounts, swap_data) in legs {
+        data.push(leg_accounts.len() as u8);
+        for meta in leg_accounts {
+            let index = match accounts
+                .iter()
+                .position(|account| account.pubkey == meta.pubkey)
+            {
+                Some(index) => {
+                    accounts[index].is_signer |= meta.is_signer;
+                    accounts[index].is_writable |= meta.is_writable;
+                    index
+                }
+                None => {
+                    accounts.push(meta.clone());
+                    accounts.len() - 1
+                }
+            };
+            data.push(index as u8);
+        }
+        data.push(swap_data.len() as u8);
+        data.extend_from_slice(swap_data);
+    }
+
+    Instruction {
+        program_id,
+        accounts,
+        data,
+    }
+}
@@ -0,0 +1,419 @@
+//! Wrapping swap instructions into a ready-to-sign transaction message.
+//!
+//! [`assemble_instructions`] surrounds one or more swap instructions (a direct venue
+//! swap, or a program instruction routing through Beethoven) with the instructions a
+//! swap usually needs around it: compute budget, idempotent creation of the owner's
+//! token accounts, and wrapping / unwrapping SOL.
+//!
+//! Multi-leg routes and venues with long account lists (Kamino with its reserves)
+//! quickly outgrow a legacy transaction. [`assemble_v0_message`] compiles against
+//! address lookup tables instead, which [`create_lookup_table`] and
+//! [`extend_lookup_table`] set up and [`load_lookup_table`] reads back.
+//!
+//! Routes are CU-heavy and get dropped without a competitive priority fee.
+//! [`AssembleOptions::with_priority_fee`] fills in the compute unit price from a
+//! [`PriorityFeeEstimator`]: a closure, or (with the `rpc` feature)
+//! [`RecentPrioritizationFees`] over the `getRecentPrioritizationFees` RPC method.
+
+use {
+    crate::resolve::{
+        associated_token_address, fetch, AccountSource, ResolveError, ASSOCIATED_TOKEN_PROGRAM_ID,
+        SYSTEM_PROGRAM_ID,
+    },
+    solana_address::Address,
+    solana_hash::Hash,
+    solana_instruction::{AccountMeta, Instruction},
+    solana_message::{v0, AddressLookupTableAccount, CompileError, Message},
+};
+
+pub const COMPUTE_BUDGET_PROGRAM_ID: Address =
+    Address::from_str_const("ComputeBudget111111111111111111111111111111");
+pub const TOKEN_PROGRAM_ID: Address =
+    Address::from_str_const("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+pub const WSOL_MINT: Address =
+    Address::from_str_const("So11111111111111111111111111111111111111112");
+pub const ADDRESS_LOOKUP_TABLE_PROGRAM_ID: Address =
+    Address::from_str_const("AddressLookupTab1e1111111111111111111111111");
+
+/// Length of the metadata preceding the addresses in a lookup table account
+const LOOKUP_TABLE_META_SIZE: usize = 56;
+
+/// Addresses per `ExtendLookupTable`, keeping each extension within one transaction
+pub const MAX_ADDRESSES_PER_EXTEND: usize = 20;
+
+/// What to add around the swap instructions.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AssembleOptions {
+    /// `SetComputeUnitLimit`, when set
+    pub compute_unit_limit: Option<u32>,
+    /// `SetComputeUnitPrice` in micro-lamports per compute unit, when set
+    pub compute_unit_price: Option<u64>,
+    /// `(mint, token_program)` pairs whose associated token accounts for the owner are
+    /// created (idempotently) before the swap, typically the output mints
+    pub create_token_accounts: Vec<(Address, Address)>,
+    /// Lamports moved into the owner's WSOL account and synced before the swap
+    pub wrap_sol: Option<u64>,
+    /// Close the owner's WSOL account after the swap, returning its balance as SOL
+    pub unwrap_sol: bool,
+}
+
+impl AssembleOptions {
+    /// These options with `compute_unit_price` set from `estimator`, given the accounts
+    /// `swap_instructions` write-lock. A price already set is kept.
+    pub fn with_priority_fee<P: PriorityFeeEstimator>(
+        mut self,
+        estimator: &P,
+        swap_instructions: &[Instruction],
+    ) -> Result<Self, P::Error> {
+        if self.compute_unit_price.is_none() {
+            let price = estimator.compute_unit_price(&writable_accounts(swap_instructions))?;
+            debug_event!(compute_unit_price = price, "estimated priority fee");
+            self.compute_unit_price = Some(price);
+        }
+        Ok(self)
+    }
+}
+
+/// Source of the compute unit price a transaction bids.
+pub trait PriorityFeeEstimator {
+    type Error;
+
+    /// Micro-lamports per compute unit to bid for a transaction write-locking
+    /// `writable_accounts`.
+    fn compute_unit_price(&self, writable_accounts: &[Address]) -> Result<u64, Self::Error>;
+}
+
+impl<F: Fn(&[Address]) -> u64> PriorityFeeEstimator for F {
+    type Error = core::convert::Infallible;
+
+    fn compute_unit_price(&self, writable_accounts: &[Address]) -> Result<u64, Self::Error> {
+        Ok(self(writable_accounts))
+    }
+}
+
+/// Estimates the price from the fees recently paid to write-lock the same accounts,
+/// as reported by `getRecentPrioritizationFees` for the last 150 slots.
+#[cfg(all(feature = "rpc", not(target_arch = "wasm32")))]
+pub struct RecentPrioritizationFees<'a> {
+    pub rpc: &'a solana_rpc_client::rpc_client::RpcClient,
+    /// Percentile of the recent per-slot fees to bid, 0 to 100
+    pub percentile: u8,
+    /// Lower bound of the bid, for quiet accounts with no recent fees
+    pub min_compute_unit_price: u64,
+}
+
+#[cfg(all(feature = "rpc", not(target_arch = "wasm32")))]
+impl PriorityFeeEstimator for RecentPrioritizationFees<'_> {
+    type Error = solana_rpc_client_api::client_error::Error;
+
+    fn compute_unit_price(&self, writable_accounts: &[Address]) -> Result<u64, Self::Error> {
+        let accounts: Vec<_> = writable_accounts
+            .iter()
+            .map(|address| solana_pubkey::Pubkey::new_from_array(address.to_bytes()))
+            .collect();
+        let mut fees: Vec<u64> = self
+            .rpc
+            .get_recent_prioritization_fees(&accounts)?
+            .into_iter()
+            .map(|fee| fee.prioritization_fee)
+            .collect();
+        fees.sort_unstable();
+
+        let percentile = fees
+            .len()
+            .checked_sub(1)
+            .map(|last| fees[last * self.percentile.min(100) as usize / 100])
+            .unwrap_or(0);
+        Ok(percentile.max(self.min_compute_unit_price))
+    }
+}
+
+/// Every account `instructions` write-lock, once each in first-use order.
+fn writable_accounts(instructions: &[Instruction]) -> Vec<Address> {
+    let mut accounts = Vec::new();
+    for meta in instructions.iter().flat_map(|ix| &ix.accounts) {
+        if meta.is_writable && !accounts.contains(&meta.pubkey) {
+            accounts.push(meta.pubkey);
+        }
+    }
+    accounts
+}
+
+/// `swap_instructions` with the instructions `options` asks for around them, in
+/// execution order. `owner` pays rent and holds the token accounts.
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(owner = %owner, swap_instructions = swap_instructions.len())
+    )
+)]
+pub fn assemble_instructions(
+    owner: &Address,
+    swap_instructions: &[Instruction],
+    options: &AssembleOptions,
+) -> Vec<Instruction> {
+    let mut instructions = Vec::with_capacity(swap_instructions.len() + 8);
+
+    if let Some(units) = options.compute_unit_limit {
+        instructions.push(set_compute_unit_limit(units));
+    }
+    if let Some(micro_lamports) = options.compute_unit_price {
+        instructions.push(set_compute_unit_price(micro_lamports));
+    }
+
+    let mut created = Vec::new();
+    let wsol =
+        (options.wrap_sol.is_some() || options.unwrap_sol).then_some((WSOL_MINT, TOKEN_PROGRAM_ID));
+    for (mint, token_program) in options.create_token_accounts.iter().copied().chain(wsol) {
+        if !created.contains(&mint) {
+            created.push(mint);
+            instructions.push(create_associated_token_account_idempotent(
+                owner,
+                owner,
+                &mint,
+                &token_program,
+            ));
+        }
+    }
+
+    let wsol_account = associated_token_address(owner, &WSOL_MINT, &TOKEN_PROGRAM_ID);
+    if let Some(lamports) = options.wrap_sol {
+        instructions.push(system_transfer(owner, &wsol_account, lamports));
+        instructions.push(sync_native(&wsol_account));
+    }
+
+    instructions.extend_from_slice(swap_instructions);
+
+    if options.unwrap_sol {
+        instructions.push(close_account(&wsol_account, owner, owner));
+    }
+    debug_event!(instructions = instructions.len(), "assembled");
+    instructions
+}
+
+/// [`assemble_instructions`] as a legacy message paid for by `owner`.
+pub fn assemble_message(
+    owner: &Address,
+    swap_instructions: &[Instruction],
+    options: &AssembleOptions,
+    recent_blockhash: &Hash,
+) -> Message {
+    Message::new_with_blockhash(
+        &assemble_instructions(owner, swap_instructions, options),
+        Some(owner),
+        recent_blockhash,
+    )
+}
+
+/// [`assemble_instructions`] as a v0 message paid for by `owner`, loading every
+/// account it can from `lookup_tables`.
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(owner = %owner, lookup_tables = lookup_tables.len())
+    )
+)]
+pub fn assemble_v0_message(
+    owner: &Address,
+    swap_instructions: &[Instruction],
+    options: &AssembleOptions,
+    lookup_tables: &[AddressLookupTableAccount],
+    recent_blockhash: &Hash,
+) -> Result<v0::Message, CompileError> {
+    v0::Message::try_compile(
+        owner,
+        &assemble_instructions(owner, swap_instructions, options),
+        lookup_tables,
+        *recent_blockhash,
+    )
+}
+
+/// Accounts of `instructions` a lookup table can hold: every account that neither
+/// signs nor is invoked as a program, deduplicated in first-use order.
+pub fn lookup_table_candidates(instructions: &[Instruction]) -> Vec<Address> {
+    let is_static = |address: &Address| {
+        instructions.iter().any(|instruction| {
+            instruction.program_id == *address
+                || instruction
+                    .accounts
+                    .iter()
+                    .any(|meta| meta.is_signer && meta.pubkey == *address)
+        })
+    };
+
+    let mut candidates = Vec::new();
+    for meta in instructions
+        .iter()
+        .flat_map(|instruction| &instruction.accounts)
+    {
+        if !candidates.contains(&meta.pubkey) && !is_static(&meta.pubkey) {
+            candidates.push(meta.pubkey);
+        }
+    }
+    candidates
+}
+
+/// Create a lookup table owned by `authority`, returning the instruction and the
+/// table's address. `recent_slot` must be a slot the cluster still has a hash for.
+pub fn create_lookup_table(
+    authority: &Address,
+    payer: &Address,
+    recent_slot: u64,
+) -> (Instruction, Address) {
+    let (table, bump) = Address::find_program_address(
+        &[authority.as_ref(), &recent_slot.to_le_bytes()],
+        &ADDRESS_LOOKUP_TABLE_PROGRAM_ID,
+    );
+
+    // `ProgramInstruction::CreateLookupTable { recent_slot, bump_seed }`
+    let mut data = 0u32.to_le_bytes().to_vec();
+    data.extend_from_slice(&recent_slot.to_le_bytes());
+    data.push(bump);
+
+    let instruction = Instruction {
+        program_id: ADDRESS_LOOKUP_TABLE_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(table, false),
+            AccountMeta::new_readonly(*authority, false),
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        ],
+        data,
+    };
+    (instruction, table)
+}
+
+/// Append `addresses` to `table`, in chunks of [`MAX_ADDRESSES_PER_EXTEND`] so each
+/// instruction fits its own transaction.
+pub fn extend_lookup_table(
+    table: &Address,
+    authority: &Address,
+    payer: &Address,
+    addresses: &[Address],
+) -> Vec<Instruction> {
+    addresses
+        .chunks(MAX_ADDRESSES_PER_EXTEND)
+        .map(|chunk| {
+            // `ProgramInstruction::ExtendLookupTable { new_addresses }`, a u64-prefixed vec
+            let mut data = 2u32.to_le_bytes().to_vec();
+            data.extend_from_slice(&(chunk.len() as u64).to_le_bytes());
+            for address in chunk {
+                data.extend_from_slice(address.as_ref());
+            }
+
+            Instruction {
+                program_id: ADDRESS_LOOKUP_TABLE_PROGRAM_ID,
+                accounts: vec![
+                    AccountMeta::new(*table, false),
+                    AccountMeta::new_readonly(*authority, true),
+                    AccountMeta::new(*payer, true),
+                    AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+                ],
+                data,
+            }
+        })
+        .collect()
+}
+
+/// Read the lookup table at `table` for [`assemble_v0_message`].
+pub fn load_lookup_table<S: AccountSource>(
+    source: &S,
+    table: &Address,
+) -> Result<AddressLookupTableAccount, ResolveError<S::Error>> {
+    let account = fetch(source, table)?;
+    if account.owner != ADDRESS_LOOKUP_TABLE_PROGRAM_ID {
+        return Err(ResolveError::InvalidAccountData(*table));
+    }
+    let addresses = account
+        .data
+        .get(LOOKUP_TABLE_META_SIZE..)
+        .filter(|addresses| addresses.len() % 32 == 0)
+        .ok_or(ResolveError::InvalidAccountData(*table))?
+        .chunks_exact(32)
+        .map(|bytes| Address::new_from_array(bytes.try_into().unwrap()))
+        .collect();
+
+    Ok(AddressLookupTableAccount {
+        key: *table,
+        addresses,
+    })
+}
+
+pub fn set_compute_unit_limit(units: u32) -> Instruction {
+    let mut data = vec![2];
+    data.extend_from_slice(&units.to_le_bytes());
+    Instruction {
+        program_id: COMPUTE_BUDGET_PROGRAM_ID,
+        accounts: Vec::new(),
+        data,
+    }
+}
+
+pub fn set_compute_unit_price(micro_lamports: u64) -> Instruction {
+    let mut data = vec![3];
+    data.extend_from_slice(&micro_lamports.to_le_bytes());
+    Instruction {
+        program_id: COMPUTE_BUDGET_PROGRAM_ID,
+        accounts: Vec::new(),
+        data,
+    }
+}
+
+/// Create `owner`'s associated token account for `mint`, unless it already exists.
+pub fn create_associated_token_account_idempotent(
+    payer: &Address,
+    owner: &Address,
+    mint: &Address,
+    token_program: &Address,
+) -> Instruction {
+    Instruction {
+        program_id: ASSOCIATED_TOKEN_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(associated_token_address(owner, mint, token_program), false),
+            AccountMeta::new_readonly(*owner, false),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+            AccountMeta::new_readonly(*token_program, false),
+        ],
+        // `CreateIdempotent`
+        data: vec![1],
+    }
+}
+
+pub(crate) fn system_transfer(from: &Address, to: &Address, lamports: u64) -> Instruction {
+    // `SystemInstruction::Transfer`, a u32 tag then the amount
+    let mut data = 2u32.to_le_bytes().to_vec();
+    data.extend_from_slice(&lamports.to_le_bytes());
+    Instruction {
+        program_id: SYSTEM_PROGRAM_ID,
+        accounts: vec![AccountMeta::new(*from, true), AccountMeta::new(*to, false)],
+        data,
+    }
+}
+
+fn sync_native(account: &Address) -> Instruction {
+    Instruction {
+        program_id: TOKEN_PROGRAM_ID,
+        accounts: vec![AccountMeta::new(*account, false)],
+        // `SyncNative`
+        data: vec![17],
+    }
+}
+
+fn close_account(account: &Address, destination: &Address, owner: &Address) -> Instruction {
+    Instruction {
+        program_id: TOKEN_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(*account, false),
+            AccountMeta::new(*destination, false),
+            AccountMeta::new_readonly(*owner, true),
+        ],
+        // `CloseAccount`
+        data: vec![9],
+    }
+}
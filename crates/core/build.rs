@@ -0,0 +1,168 @@
+//! Generates `ProtocolKind` and the `program_ids` module from `protocols.toml`.
+//!
+//! `BEETHOVEN_<CONST>` environment variables (e.g. `BEETHOVEN_GAMMA_PROGRAM_ID`)
+//! override individual program ids, for staging deployments no profile covers.
+
+#[path = "build/protocols.rs"]
+mod protocols;
+
+use {
+    protocols::Protocol,
+    std::{env, fmt::Write, fs, path::Path},
+};
+
+fn main() {
+    let protocols = protocols::load(Path::new("protocols.toml"));
+    let out_dir = env::var("OUT_DIR").unwrap();
+
+    fs::write(
+        Path::new(&out_dir).join("protocol_kind.rs"),
+        protocol_kind(&protocols),
+    )
+    .unwrap();
+    fs::write(
+        Path::new(&out_dir).join("program_ids.rs"),
+        program_ids(&protocols),
+    )
+    .unwrap();
+}
+
+fn protocol_kind(protocols: &[Protocol]) -> String {
+    let mut out = String::new();
+    out.push_str(
+        "/// Identifies a protocol Beethoven can route to.
+///
+/// Discriminants are stable and independent of which protocol features are enabled, so
+/// they can be embedded in error codes and serialized data.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = \"serde\", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = \"borsh\",
+    derive(borsh::BorshSerialize, borsh::BorshDeserialize),
+    borsh(use_discriminant = true)
+)]
+pub enum ProtocolKind {
+",
+    );
+    for protocol in protocols {
+        writeln!(out, "    {} = {},", protocol.name, protocol.discriminant).unwrap();
+    }
+    out.push_str(
+        "}
+
+impl ProtocolKind {
+    /// The protocol with discriminant `value`, if any.
+    pub const fn from_u8(value: u8) -> Option<Self> {
+        Some(match value {
+",
+    );
+    for protocol in protocols {
+        writeln!(
+            out,
+            "            {} => ProtocolKind::{},",
+            protocol.discriminant, protocol.name
+        )
+        .unwrap();
+    }
+    out.push_str(
+        "            _ => return None,
+        })
+    }
+
+    /// What the protocol's adapter supports, as listed in `protocols.toml`.
+    pub const fn capabilities(self) -> crate::Capabilities {
+        match self {
+",
+    );
+    for protocol in protocols {
+        let capabilities = protocol.capabilities.iter().fold(
+            "crate::Capabilities::NONE".to_string(),
+            |capabilities, capability| {
+                format!(
+                    "{capabilities}.union(crate::Capabilities::{})",
+                    capability.to_uppercase()
+                )
+            },
+        );
+        writeln!(
+            out,
+            "            ProtocolKind::{} => {capabilities},",
+            protocol.name
+        )
+        .unwrap();
+    }
+    out.push_str(
+        "        }
+    }
+
+    /// The variant's name, for logs.
+    pub const fn name(self) -> &'static str {
+        match self {
+",
+    );
+    for protocol in protocols {
+        writeln!(
+            out,
+            "            ProtocolKind::{} => \"{}\",",
+            protocol.name, protocol.name
+        )
+        .unwrap();
+    }
+    out.push_str(
+        "        }
+    }
+
+    /// The program the protocol's adapter invokes, from [`crate::program_ids`].
+    pub const fn program_id(self) -> solana_address::Address {
+        match self {
+",
+    );
+    for protocol in protocols {
+        writeln!(
+            out,
+            "            ProtocolKind::{} => crate::program_ids::{},",
+            protocol.name, protocol.program_id_const
+        )
+        .unwrap();
+    }
+    out.push_str(
+        "        }
+    }
+}
+",
+    );
+    out
+}
+
+/// Each protocol's id: `BEETHOVEN_<CONST>` from the environment if set, else its devnet
+/// deployment with the `devnet` feature, else its mainnet id.
+fn program_ids(protocols: &[Protocol]) -> String {
+    let devnet = env::var_os("CARGO_FEATURE_DEVNET").is_some();
+
+    let mut out = String::new();
+    for protocol in protocols {
+        let override_var = format!("BEETHOVEN_{}", protocol.program_id_const);
+        println!("cargo:rerun-if-env-changed={override_var}");
+
+        let program_id = env::var(&override_var)
+            .ok()
+            .or_else(|| devnet.then(|| protocol.devnet_program_id.clone()).flatten())
+            .or_else(|| protocol.program_id.clone());
+        match program_id {
+            Some(program_id) => writeln!(
+                out,
+                "pub const {}: Address = Address::from_str_const(\"{program_id}\");",
+                protocol.program_id_const
+            ),
+            None => writeln!(
+                out,
+                "/// Placeholder until the adapter targets a deployed program\n\
+                 pub const {}: Address = Address::new_from_array([0; 32]);",
+                protocol.program_id_const
+            ),
+        }
+        .unwrap();
+    }
+    out
+}
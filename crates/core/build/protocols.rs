@@ -0,0 +1,85 @@
+//! Loading `protocols.toml`, shared by the `beethoven-core` and `beethoven` build
+//! scripts.
+
+// Each build script reads a different subset of the fields
+#![allow(dead_code)]
+
+use {serde::Deserialize, std::path::Path};
+
+#[derive(Deserialize)]
+struct Manifest {
+    protocol: Vec<Protocol>,
+}
+
+/// Names `capabilities` entries may use, each a `Capabilities` constant in lowercase.
+pub const CAPABILITIES: &[&str] = &[
+    "supports_exact_out",
+    "supports_pda_authority",
+    "requires_instructions_sysvar",
+    "token2022_ok",
+];
+
+/// One `[[protocol]]` entry.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Protocol {
+    pub name: String,
+    pub discriminant: u8,
+    pub action: Action,
+    pub feature: String,
+    pub program_id_const: String,
+    pub program_id: Option<String>,
+    pub devnet_program_id: Option<String>,
+    #[serde(default)]
+    pub capabilities: Vec<String>,
+}
+
+#[derive(Clone, Copy, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Action {
+    Swap,
+    Deposit,
+    Liquidity,
+    Stake,
+    Perp,
+}
+
+/// Read the protocols listed in `path`, in discriminant order.
+///
+/// Panics (failing the build) on a malformed manifest or a repeated name or
+/// discriminant.
+pub fn load(path: &Path) -> Vec<Protocol> {
+    println!("cargo:rerun-if-changed={}", path.display());
+
+    let text = std::fs::read_to_string(path)
+        .unwrap_or_else(|error| panic!("reading {}: {error}", path.display()));
+    let mut protocols = toml::from_str::<Manifest>(&text)
+        .unwrap_or_else(|error| panic!("parsing {}: {error}", path.display()))
+        .protocol;
+    protocols.sort_by_key(|protocol| protocol.discriminant);
+
+    for pair in protocols.windows(2) {
+        assert_ne!(
+            pair[0].discriminant, pair[1].discriminant,
+            "discriminant {} is used twice",
+            pair[0].discriminant
+        );
+    }
+    for (index, protocol) in protocols.iter().enumerate() {
+        assert!(
+            protocols[..index]
+                .iter()
+                .all(|other| other.name != protocol.name),
+            "protocol {} is listed twice",
+            protocol.name
+        );
+        for capability in &protocol.capabilities {
+            assert!(
+                CAPABILITIES.contains(&capability.as_str()),
+                "protocol {} has unknown capability {capability}",
+                protocol.name
+            );
+        }
+    }
+    protocols
+}
@@ -0,0 +1,59 @@
+use core::ops::BitOr;
+
+/// What an adapter supports or requires beyond a plain exact-in swap or deposit.
+///
+/// Set per protocol in `protocols.toml` and read through
+/// [`ProtocolKind::capabilities`](crate::ProtocolKind::capabilities), so routers and
+/// planners can filter venues without hardcoding which one does what.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Capabilities(u8);
+
+impl Capabilities {
+    /// Nothing beyond exact-in with a signing wallet
+    pub const NONE: Self = Self(0);
+    /// The swap data can fix the output amount instead of the input
+    pub const SUPPORTS_EXACT_OUT: Self = Self(1 << 0);
+    /// The authority may be a PDA signing through `swap_signed` / `deposit_signed`
+    pub const SUPPORTS_PDA_AUTHORITY: Self = Self(1 << 1);
+    /// The accounts include the instructions sysvar, which the venue introspects
+    pub const REQUIRES_INSTRUCTIONS_SYSVAR: Self = Self(1 << 2);
+    /// Token-2022 mints are accepted, with the Token-2022 program among the accounts
+    pub const TOKEN2022_OK: Self = Self(1 << 3);
+
+    pub const fn bits(self) -> u8 {
+        self.0
+    }
+
+    pub const fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    /// Whether every capability in `other` is set.
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub const fn supports_exact_out(self) -> bool {
+        self.contains(Self::SUPPORTS_EXACT_OUT)
+    }
+
+    pub const fn supports_pda_authority(self) -> bool {
+        self.contains(Self::SUPPORTS_PDA_AUTHORITY)
+    }
+
+    pub const fn requires_instructions_sysvar(self) -> bool {
+        self.contains(Self::REQUIRES_INSTRUCTIONS_SYSVAR)
+    }
+
+    pub const fn token2022_ok(self) -> bool {
+        self.contains(Self::TOKEN2022_OK)
+    }
+}
+
+impl BitOr for Capabilities {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        self.union(other)
+    }
+}
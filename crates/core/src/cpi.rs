@@ -0,0 +1,146 @@
+//! The CPI every adapter issues once its metas and account infos are assembled.
+//!
+//! By default this is `solana_instruction_view::cpi::invoke_signed`, which re-checks at
+//! runtime that there are enough account infos, that each info matches its meta and
+//! that no account is already borrowed in a way the meta's flags conflict with.
+//!
+//! Adapters build both arrays from the same typed accounts struct, whose `TryFrom`
+//! already guaranteed every account is present, so with the `fast-cpi` feature the
+//! instruction goes straight to `invoke_signed_unchecked` instead. Debug builds still
+//! check that every meta has a matching account info.
+//!
+//! The runtime matches metas to account infos by address there, so an account an
+//! adapter lists more than once (e.g. one token program for both mints) is handed to
+//! it once.
+//!
+//! # Safety
+//!
+//! With `fast-cpi`, the caller must not hold a borrow of any account passed to an
+//! adapter (e.g. a `try_borrow_mut` guard on a token account's data) across the swap
+//! or deposit call; the runtime may write to those accounts during the CPI.
+
+use {
+    solana_account_view::AccountView,
+    solana_instruction_view::{cpi::Signer, InstructionView},
+    solana_program_error::ProgramResult,
+};
+
+/// Invoke `instruction` with `account_infos`, signing with `signers`.
+#[cfg(not(feature = "fast-cpi"))]
+#[inline(always)]
+pub fn invoke_signed<const N: usize>(
+    instruction: &InstructionView,
+    account_infos: &[&AccountView; N],
+    signers: &[Signer],
+) -> ProgramResult {
+    solana_instruction_view::cpi::invoke_signed(instruction, account_infos, signers)
+}
+
+/// Invoke `instruction` with `account_infos`, signing with `signers`.
+#[cfg(feature = "fast-cpi")]
+#[inline(always)]
+pub fn invoke_signed<const N: usize>(
+    instruction: &InstructionView,
+    account_infos: &[&AccountView; N],
+    signers: &[Signer],
+) -> ProgramResult {
+    use {
+        core::mem::MaybeUninit,
+        solana_instruction_view::cpi::{invoke_signed_unchecked, CpiAccount},
+    };
+
+    debug_assert!(instruction.accounts.len() <= N);
+    debug_assert!(instruction.accounts.iter().all(|meta| {
+        account_infos
+            .iter()
+            .any(|info| solana_address::address_eq(info.address(), meta.address))
+    }));
+
+    let mut accounts = [const { MaybeUninit::<CpiAccount>::uninit() }; N];
+    let len = write_unique(&mut accounts, account_infos);
+
+    // SAFETY: the first `len` slots were written above, every meta refers to one of
+    // `account_infos`, and the caller guarantees none of them is borrowed for the
+    // duration of the CPI (see the module docs).
+    unsafe {
+        let accounts = core::slice::from_raw_parts(accounts.as_ptr() as *const CpiAccount, len);
+        invoke_signed_unchecked(instruction, accounts, signers);
+    }
+
+    Ok(())
+}
+
+/// [`invoke_signed`] for a runtime-sized `account_infos` of at most `MAX` accounts.
+#[cfg(not(feature = "fast-cpi"))]
+#[inline(always)]
+pub fn invoke_signed_with_bounds<const MAX: usize>(
+    instruction: &InstructionView,
+    account_infos: &[&AccountView],
+    signers: &[Signer],
+) -> ProgramResult {
+    solana_instruction_view::cpi::invoke_signed_with_bounds::<MAX>(
+        instruction,
+        account_infos,
+        signers,
+    )
+}
+
+/// [`invoke_signed`] for a runtime-sized `account_infos` of at most `MAX` accounts.
+#[cfg(feature = "fast-cpi")]
+#[inline(always)]
+pub fn invoke_signed_with_bounds<const MAX: usize>(
+    instruction: &InstructionView,
+    account_infos: &[&AccountView],
+    signers: &[Signer],
+) -> ProgramResult {
+    use {
+        core::mem::MaybeUninit,
+        solana_instruction_view::cpi::{invoke_signed_unchecked, CpiAccount},
+        solana_program_error::ProgramError,
+    };
+
+    if account_infos.len() > MAX {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    debug_assert!(instruction.accounts.len() <= account_infos.len());
+    debug_assert!(instruction.accounts.iter().all(|meta| {
+        account_infos
+            .iter()
+            .any(|info| solana_address::address_eq(info.address(), meta.address))
+    }));
+
+    let mut accounts = [const { MaybeUninit::<CpiAccount>::uninit() }; MAX];
+    let len = write_unique(&mut accounts, account_infos);
+
+    // SAFETY: the first `len` slots were written above, every meta refers to one of
+    // `account_infos`, and the caller guarantees none of them is borrowed for the
+    // duration of the CPI (see the module docs).
+    unsafe {
+        let accounts = core::slice::from_raw_parts(accounts.as_ptr() as *const CpiAccount, len);
+        invoke_signed_unchecked(instruction, accounts, signers);
+    }
+
+    Ok(())
+}
+
+/// Write each distinct account of `account_infos` to the front of `accounts`, in order,
+/// returning how many were written.
+#[cfg(feature = "fast-cpi")]
+#[inline(always)]
+fn write_unique(
+    accounts: &mut [core::mem::MaybeUninit<solana_instruction_view::cpi::CpiAccount>],
+    account_infos: &[&AccountView],
+) -> usize {
+    let mut len = 0;
+    for (index, info) in account_infos.iter().enumerate() {
+        let repeated = account_infos[..index]
+            .iter()
+            .any(|seen| solana_address::address_eq(seen.address(), info.address()));
+        if !repeated {
+            accounts[len].write(solana_instruction_view::cpi::CpiAccount::from(*info));
+            len += 1;
+        }
+    }
+    len
+}
@@ -0,0 +1,18 @@
+/// Side of a swap on an order-book or base/quote venue, relative to the base token.
+///
+/// Adapters whose data carries a side convert from this, so a router can express it
+/// once: Aldrin's `Side`, Futarchy's `SwapType` and Heaven's `SwapDirection`.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "borsh",
+    derive(borsh::BorshSerialize, borsh::BorshDeserialize),
+    borsh(use_discriminant = true)
+)]
+pub enum Direction {
+    /// Spend quote for base (Aldrin's bid)
+    Buy = 0,
+    /// Spend base for quote (Aldrin's ask)
+    Sell = 1,
+}
@@ -0,0 +1,75 @@
+//! Versioned envelope for the protocol-specific tail of a dispatcher's instruction data.
+//!
+//! Layout: version (u8), protocol (u8, a [`ProtocolKind`] discriminant), payload
+//!
+//! In [`ENVELOPE_V1`] the payload is the adapter's swap data, exactly as the bare
+//! format carries it. Later versions can add fields (route, fee, exact-out) after the
+//! header without changing what a version 1 caller sends, and a program built before a
+//! version exists rejects it with [`BeethovenError::UnsupportedEnvelopeVersion`]
+//! instead of misreading its bytes. The protocol tag is checked against the detected
+//! venue, so data built for one venue cannot be replayed against another's accounts.
+
+use {
+    crate::{BeethovenError, ProtocolKind},
+    solana_program_error::ProgramError,
+};
+
+/// Header, then the adapter's swap data
+pub const ENVELOPE_V1: u8 = 1;
+
+/// Newest version this build reads and writes
+pub const CURRENT_VERSION: u8 = ENVELOPE_V1;
+
+/// Bytes before the payload: version and protocol
+pub const HEADER_LEN: usize = 2;
+
+/// Protocol-specific instruction data with its version and protocol.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Envelope<'a> {
+    pub version: u8,
+    pub protocol: ProtocolKind,
+    pub payload: &'a [u8],
+}
+
+impl<'a> Envelope<'a> {
+    /// `payload` for `protocol` in the [`CURRENT_VERSION`] layout.
+    pub const fn new(protocol: ProtocolKind, payload: &'a [u8]) -> Self {
+        Self {
+            version: CURRENT_VERSION,
+            protocol,
+            payload,
+        }
+    }
+
+    /// The version and protocol bytes that precede [`Self::payload`].
+    pub const fn header(&self) -> [u8; HEADER_LEN] {
+        [self.version, self.protocol as u8]
+    }
+
+    /// The payload, if the envelope is for `protocol`.
+    pub fn payload_for(&self, protocol: ProtocolKind) -> Result<&'a [u8], ProgramError> {
+        if self.protocol != protocol {
+            return Err(BeethovenError::EnvelopeProtocolMismatch.into());
+        }
+        Ok(self.payload)
+    }
+}
+
+impl<'a> TryFrom<&'a [u8]> for Envelope<'a> {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        let [version, protocol, payload @ ..] = data else {
+            return Err(ProgramError::InvalidInstructionData);
+        };
+        if *version != ENVELOPE_V1 {
+            return Err(BeethovenError::UnsupportedEnvelopeVersion.into());
+        }
+        let protocol = ProtocolKind::from_u8(*protocol).ok_or(BeethovenError::UnknownProtocol)?;
+        Ok(Self {
+            version: *version,
+            protocol,
+            payload,
+        })
+    }
+}
@@ -0,0 +1,106 @@
+use {crate::ProtocolKind, solana_program_error::ProgramError};
+
+/// Offset applied to every [`BeethovenError`] code surfaced as `ProgramError::Custom`.
+pub const ERROR_CODE_OFFSET: u32 = 0xBEE7_0000;
+
+/// Errors raised by Beethoven itself, before control is handed to the target protocol.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BeethovenError {
+    /// The pool's oracle was last updated longer ago than the caller allows.
+    ///
+    /// No adapter raises it: the venues with oracles publish no account layout to read
+    /// the last update from. It keeps code 0 so the codes after it stay put.
+    StaleOracle,
+    /// The swap direction does not match the orientation of the user token accounts
+    DirectionMismatch,
+    /// A user token account is not owned by the authority signing for it
+    TokenAccountOwnerMismatch,
+    /// The detector account does not match any enabled protocol
+    UnknownProtocol,
+    /// The protocol was detected but its accounts could not be parsed
+    AdapterParseError { protocol: ProtocolKind },
+    /// The instruction data's [`Envelope`](crate::envelope::Envelope) has a version
+    /// this build does not know
+    UnsupportedEnvelopeVersion,
+    /// The envelope names a different protocol than the accounts were detected as
+    EnvelopeProtocolMismatch,
+    /// The venue cannot execute the requested [`SwapMode`](crate::SwapMode)
+    UnsupportedSwapMode,
+    /// A route leg does not spend from the token account the leg before it credited
+    RouteLegMismatch,
+    /// The swap delivered less than the caller's minimum output
+    SlippageExceeded,
+    /// The protocol is known but its adapter for this action is not compiled in
+    ProtocolFeatureDisabled,
+    /// The protocol-specific instruction data could not be parsed for the detected
+    /// protocol
+    InvalidExtraData,
+    /// Account counts passed alongside the accounts do not add up to them
+    AccountCountMismatch,
+    /// Fee rates add up to more than the whole amount they are taken from
+    InvalidFee,
+    /// A fee token account does not hold the mint the fee is paid in
+    FeeMintMismatch,
+}
+
+impl BeethovenError {
+    /// Custom program error code for this error.
+    ///
+    /// Protocol-specific errors encode the [`ProtocolKind`] in the low byte, e.g.
+    /// `AdapterParseError` for Manifest is `ERROR_CODE_OFFSET + 0x100 + 5`.
+    pub const fn code(&self) -> u32 {
+        ERROR_CODE_OFFSET
+            + match self {
+                BeethovenError::StaleOracle => 0,
+                BeethovenError::DirectionMismatch => 1,
+                BeethovenError::TokenAccountOwnerMismatch => 2,
+                BeethovenError::UnknownProtocol => 3,
+                BeethovenError::UnsupportedEnvelopeVersion => 4,
+                BeethovenError::EnvelopeProtocolMismatch => 5,
+                BeethovenError::UnsupportedSwapMode => 6,
+                BeethovenError::RouteLegMismatch => 7,
+                BeethovenError::SlippageExceeded => 8,
+                BeethovenError::ProtocolFeatureDisabled => 9,
+                BeethovenError::InvalidExtraData => 10,
+                BeethovenError::AccountCountMismatch => 11,
+                BeethovenError::InvalidFee => 12,
+                BeethovenError::FeeMintMismatch => 13,
+                BeethovenError::AdapterParseError { protocol } => 0x100 + *protocol as u32,
+            }
+    }
+
+    /// The error whose [`code`](Self::code) is `code`, e.g. read back from a failed
+    /// transaction.
+    pub const fn from_code(code: u32) -> Option<Self> {
+        let Some(code) = code.checked_sub(ERROR_CODE_OFFSET) else {
+            return None;
+        };
+        Some(match code {
+            0 => BeethovenError::StaleOracle,
+            1 => BeethovenError::DirectionMismatch,
+            2 => BeethovenError::TokenAccountOwnerMismatch,
+            3 => BeethovenError::UnknownProtocol,
+            4 => BeethovenError::UnsupportedEnvelopeVersion,
+            5 => BeethovenError::EnvelopeProtocolMismatch,
+            6 => BeethovenError::UnsupportedSwapMode,
+            7 => BeethovenError::RouteLegMismatch,
+            8 => BeethovenError::SlippageExceeded,
+            9 => BeethovenError::ProtocolFeatureDisabled,
+            10 => BeethovenError::InvalidExtraData,
+            11 => BeethovenError::AccountCountMismatch,
+            12 => BeethovenError::InvalidFee,
+            13 => BeethovenError::FeeMintMismatch,
+            0x100..=0x1ff => match ProtocolKind::from_u8((code - 0x100) as u8) {
+                Some(protocol) => BeethovenError::AdapterParseError { protocol },
+                None => return None,
+            },
+            _ => return None,
+        })
+    }
+}
+
+impl From<BeethovenError> for ProgramError {
+    fn from(error: BeethovenError) -> Self {
+        ProgramError::Custom(error.code())
+    }
+}
@@ -1,6 +1,35 @@
 #![no_std]
 
-use {solana_instruction_view::cpi::Signer, solana_program_error::ProgramResult};
+use {
+    solana_instruction_view::cpi::Signer,
+    solana_program_error::{ProgramError, ProgramResult},
+};
+
+mod capabilities;
+pub mod cpi;
+mod direction;
+pub mod envelope;
+mod error;
+mod order_type;
+pub mod passthrough;
+pub mod program_ids;
+mod protocol;
+pub mod scratch;
+mod swap_mode;
+mod swap_outcome;
+pub mod token;
+pub mod validation;
+pub mod zero_copy;
+
+pub use {
+    capabilities::Capabilities,
+    direction::Direction,
+    error::{BeethovenError, ERROR_CODE_OFFSET},
+    order_type::OrderType,
+    protocol::ProtocolKind,
+    swap_mode::SwapMode,
+    swap_outcome::SwapOutcome,
+};
 
 /// Core trait for swap operations across different DEX protocols.
 ///
@@ -29,6 +58,76 @@ pub trait Swap<'info> {
         minimum_out_amount: u64,
         data: &Self::Data,
     ) -> ProgramResult;
+
+    /// Execute an exact-out swap with PDA signing capability: receive exactly
+    /// `out_amount`, spending at most `maximum_in_amount`.
+    ///
+    /// Venues without native exact-out support keep this default, which fails with
+    /// [`BeethovenError::UnsupportedSwapMode`].
+    fn swap_exact_out_signed(
+        _ctx: &Self::Accounts,
+        _out_amount: u64,
+        _maximum_in_amount: u64,
+        _data: &Self::Data,
+        _signer_seeds: &[Signer],
+    ) -> ProgramResult {
+        Err(BeethovenError::UnsupportedSwapMode.into())
+    }
+
+    /// Execute an exact-out swap without signing (user is direct signer)
+    fn swap_exact_out(
+        ctx: &Self::Accounts,
+        out_amount: u64,
+        maximum_in_amount: u64,
+        data: &Self::Data,
+    ) -> ProgramResult {
+        Self::swap_exact_out_signed(ctx, out_amount, maximum_in_amount, data, &[])
+    }
+
+    /// Execute a swap in `mode` with PDA signing capability.
+    ///
+    /// `amount` is the side `mode` fixes and `other_amount_threshold` bounds the other:
+    /// the minimum output for [`SwapMode::ExactIn`], the maximum input for
+    /// [`SwapMode::ExactOut`].
+    fn swap_with_mode_signed(
+        ctx: &Self::Accounts,
+        mode: SwapMode,
+        amount: u64,
+        other_amount_threshold: u64,
+        data: &Self::Data,
+        signer_seeds: &[Signer],
+    ) -> ProgramResult {
+        match mode {
+            SwapMode::ExactIn => {
+                Self::swap_signed(ctx, amount, other_amount_threshold, data, signer_seeds)
+            }
+            SwapMode::ExactOut => {
+                Self::swap_exact_out_signed(ctx, amount, other_amount_threshold, data, signer_seeds)
+            }
+        }
+    }
+}
+
+/// Expected output of a swap, computed on-chain from the venue's pool or market accounts.
+///
+/// Lets a program derive `minimum_out_amount` from the state it is about to trade
+/// against instead of trusting an amount quoted off-chain.
+pub trait Quote<'info>: Swap<'info> {
+    /// Output swapping `in_amount` with `data` would receive at the current account state
+    fn quote(ctx: &Self::Accounts, in_amount: u64, data: &Self::Data) -> Result<u64, ProgramError>;
+
+    /// [`Self::quote`] less `slippage_bps` basis points, rounded down, as the
+    /// `minimum_out_amount` of the swap.
+    fn minimum_out_amount(
+        ctx: &Self::Accounts,
+        in_amount: u64,
+        data: &Self::Data,
+        slippage_bps: u16,
+    ) -> Result<u64, ProgramError> {
+        let out_amount = Self::quote(ctx, in_amount, data)?;
+        let tolerance_bps = 10_000u128.saturating_sub(slippage_bps as u128);
+        Ok((out_amount as u128 * tolerance_bps / 10_000) as u64)
+    }
 }
 
 /// Core trait for deposit operations across different protocols.
@@ -44,3 +143,220 @@ pub trait Deposit<'info> {
     /// Execute a deposit without signing (user is direct signer)
     fn deposit(ctx: &Self::Accounts, amount: u64) -> ProgramResult;
 }
+
+/// Core trait for withdraw operations, the inverse of [`Deposit`].
+///
+/// Each protocol implements this trait with its specific account requirements and CPI logic.
+pub trait Withdraw<'info> {
+    /// Protocol-specific accounts required for the withdraw CPI
+    type Accounts;
+
+    /// Execute a withdraw with PDA signing capability
+    fn withdraw_signed(ctx: &Self::Accounts, amount: u64, signer_seeds: &[Signer])
+        -> ProgramResult;
+
+    /// Execute a withdraw without signing (user is direct signer)
+    fn withdraw(ctx: &Self::Accounts, amount: u64) -> ProgramResult;
+}
+
+/// Core trait for borrow operations against deposited collateral.
+///
+/// Each protocol implements this trait with its specific account requirements and CPI logic.
+pub trait Borrow<'info> {
+    /// Protocol-specific accounts required for the borrow CPI
+    type Accounts;
+
+    /// Execute a borrow with PDA signing capability
+    fn borrow_signed(ctx: &Self::Accounts, amount: u64, signer_seeds: &[Signer]) -> ProgramResult;
+
+    /// Execute a borrow without signing (user is direct signer)
+    fn borrow(ctx: &Self::Accounts, amount: u64) -> ProgramResult;
+}
+
+/// Core trait for repaying borrowed liquidity, the inverse of [`Borrow`].
+///
+/// Each protocol implements this trait with its specific account requirements and CPI logic.
+pub trait Repay<'info> {
+    /// Protocol-specific accounts required for the repay CPI
+    type Accounts;
+
+    /// Execute a repay with PDA signing capability
+    fn repay_signed(ctx: &Self::Accounts, amount: u64, signer_seeds: &[Signer]) -> ProgramResult;
+
+    /// Execute a repay without signing (user is direct signer)
+    fn repay(ctx: &Self::Accounts, amount: u64) -> ProgramResult;
+}
+
+/// Core trait for depositing both sides of a pool in exchange for LP tokens.
+///
+/// Each protocol implements this trait with its specific account requirements and CPI logic.
+pub trait AddLiquidity<'info> {
+    /// Protocol-specific accounts required for the add-liquidity CPI
+    type Accounts;
+
+    /// Execute an add-liquidity with PDA signing capability
+    fn add_liquidity_signed(
+        ctx: &Self::Accounts,
+        maximum_token_0_amount: u64,
+        maximum_token_1_amount: u64,
+        minimum_lp_amount: u64,
+        signer_seeds: &[Signer],
+    ) -> ProgramResult;
+
+    /// Execute an add-liquidity without signing (user is direct signer)
+    fn add_liquidity(
+        ctx: &Self::Accounts,
+        maximum_token_0_amount: u64,
+        maximum_token_1_amount: u64,
+        minimum_lp_amount: u64,
+    ) -> ProgramResult;
+}
+
+/// Core trait for burning LP tokens for both sides of a pool, the inverse of
+/// [`AddLiquidity`].
+///
+/// Each protocol implements this trait with its specific account requirements and CPI logic.
+pub trait RemoveLiquidity<'info> {
+    /// Protocol-specific accounts required for the remove-liquidity CPI
+    type Accounts;
+
+    /// Execute a remove-liquidity with PDA signing capability
+    fn remove_liquidity_signed(
+        ctx: &Self::Accounts,
+        lp_amount: u64,
+        minimum_token_0_amount: u64,
+        minimum_token_1_amount: u64,
+        signer_seeds: &[Signer],
+    ) -> ProgramResult;
+
+    /// Execute a remove-liquidity without signing (user is direct signer)
+    fn remove_liquidity(
+        ctx: &Self::Accounts,
+        lp_amount: u64,
+        minimum_token_0_amount: u64,
+        minimum_token_1_amount: u64,
+    ) -> ProgramResult;
+}
+
+/// Core trait for staking SOL into a liquid staking token.
+///
+/// Each protocol implements this trait with its specific account requirements and CPI logic.
+pub trait Stake<'info> {
+    /// Protocol-specific accounts required for the stake CPI
+    type Accounts;
+
+    /// Execute a stake of `lamports` with PDA signing capability
+    fn stake_signed(ctx: &Self::Accounts, lamports: u64, signer_seeds: &[Signer]) -> ProgramResult;
+
+    /// Execute a stake of `lamports` without signing (user is direct signer)
+    fn stake(ctx: &Self::Accounts, lamports: u64) -> ProgramResult;
+}
+
+/// Core trait for redeeming a liquid staking token for SOL, the inverse of [`Stake`].
+///
+/// Each protocol implements this trait with its specific account requirements and CPI logic.
+pub trait Unstake<'info> {
+    /// Protocol-specific accounts required for the unstake CPI
+    type Accounts;
+
+    /// Protocol-specific instruction data beyond the amount, such as whether to unstake
+    /// instantly or through a delayed ticket
+    type Data;
+
+    /// Execute an unstake of `amount` staking tokens with PDA signing capability
+    fn unstake_signed(
+        ctx: &Self::Accounts,
+        amount: u64,
+        data: &Self::Data,
+        signer_seeds: &[Signer],
+    ) -> ProgramResult;
+
+    /// Execute an unstake of `amount` staking tokens without signing (user is direct signer)
+    fn unstake(ctx: &Self::Accounts, amount: u64, data: &Self::Data) -> ProgramResult;
+}
+
+/// Core trait for placing orders on order-book venues.
+///
+/// Each protocol implements this trait with its specific account requirements, price
+/// encoding and CPI logic.
+pub trait LimitOrder<'info> {
+    /// Protocol-specific accounts required for the place-order CPI
+    type Accounts;
+
+    /// Protocol-specific order data beyond side, size and order type, such as the
+    /// venue's price encoding and expiry
+    type Data;
+
+    /// Place an order for `base_amount` base atoms with PDA signing capability
+    fn place_order_signed(
+        ctx: &Self::Accounts,
+        side: Direction,
+        base_amount: u64,
+        order_type: OrderType,
+        data: &Self::Data,
+        signer_seeds: &[Signer],
+    ) -> ProgramResult;
+
+    /// Place an order for `base_amount` base atoms without signing (user is direct
+    /// signer)
+    fn place_order(
+        ctx: &Self::Accounts,
+        side: Direction,
+        base_amount: u64,
+        order_type: OrderType,
+        data: &Self::Data,
+    ) -> ProgramResult;
+}
+
+/// Core trait for cancelling resting orders on order-book venues, the inverse of
+/// [`LimitOrder`].
+///
+/// Each protocol implements this trait with its specific account requirements and CPI logic.
+pub trait CancelOrder<'info> {
+    /// Protocol-specific accounts required for the cancel CPI
+    type Accounts;
+
+    /// How the venue identifies the order to cancel: the exchange-assigned order id, a
+    /// caller-chosen client id, or an enum of both where the venue accepts either
+    type OrderId;
+
+    /// Cancel `order_id` with PDA signing capability
+    fn cancel_order_signed(
+        ctx: &Self::Accounts,
+        order_id: &Self::OrderId,
+        signer_seeds: &[Signer],
+    ) -> ProgramResult;
+
+    /// Cancel `order_id` without signing (user is direct signer)
+    fn cancel_order(ctx: &Self::Accounts, order_id: &Self::OrderId) -> ProgramResult;
+}
+
+/// Core trait for trading perpetual futures.
+///
+/// Each protocol implements this trait with its specific account requirements and CPI logic.
+pub trait PerpTrade<'info> {
+    /// Protocol-specific accounts required for the order CPI
+    type Accounts;
+
+    /// Place a market order for `base_amount` on perp market `market_index` with PDA
+    /// signing capability. [`Direction::Buy`] opens or adds to a long and
+    /// [`Direction::Sell`] a short; `price_limit` is the worst fill price, in the venue's
+    /// price precision, or 0 for none.
+    fn place_perp_order_signed(
+        ctx: &Self::Accounts,
+        market_index: u16,
+        direction: Direction,
+        base_amount: u64,
+        price_limit: u64,
+        signer_seeds: &[Signer],
+    ) -> ProgramResult;
+
+    /// Place a market order without signing (user is direct signer)
+    fn place_perp_order(
+        ctx: &Self::Accounts,
+        market_index: u16,
+        direction: Direction,
+        base_amount: u64,
+        price_limit: u64,
+    ) -> ProgramResult;
+}
@@ -0,0 +1,20 @@
+/// How a limit order interacts with the book when it is placed.
+///
+/// Adapters map this onto the venue's own order types, as Manifest's `OrderType`.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "borsh",
+    derive(borsh::BorshSerialize, borsh::BorshDeserialize),
+    borsh(use_discriminant = true)
+)]
+pub enum OrderType {
+    /// Take whatever crosses, then rest the remainder on the book
+    #[default]
+    Limit = 0,
+    /// Rest on the book only; fail instead of taking liquidity
+    PostOnly = 1,
+    /// Take whatever crosses and drop the remainder
+    ImmediateOrCancel = 2,
+}
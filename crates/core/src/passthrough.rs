@@ -0,0 +1,25 @@
+//! Forwarding an adapter's accounts to its CPI in the order the caller passed them.
+//!
+//! When the slice after the detector account is already laid out exactly as the target
+//! program expects, the CPI metas can be taken straight from it, inheriting each
+//! account's signer and writable flags from the outer instruction, instead of being
+//! rebuilt field by field from a typed accounts struct.
+
+use {
+    solana_account_view::AccountView, solana_instruction_view::InstructionAccount,
+    solana_program_error::ProgramError,
+};
+
+/// CPI metas and account infos for the `N` accounts following the detector account.
+#[inline(always)]
+pub fn forward_accounts<const N: usize>(
+    accounts: &[AccountView],
+) -> Result<([InstructionAccount<'_>; N], [&AccountView; N]), ProgramError> {
+    let forwarded: &[AccountView; N] = accounts
+        .get(1..=N)
+        .and_then(|forwarded| forwarded.try_into().ok())
+        .ok_or(ProgramError::NotEnoughAccountKeys)?;
+
+    let account_infos = forwarded.each_ref();
+    Ok((account_infos.map(InstructionAccount::from), account_infos))
+}
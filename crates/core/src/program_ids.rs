@@ -0,0 +1,13 @@
+//! Program id of every protocol, generated from `protocols.toml`.
+//!
+//! Adapter crates re-export their venue's constant, so `beethoven::gamma::GAMMA_PROGRAM_ID`
+//! and `beethoven_core::program_ids::GAMMA_PROGRAM_ID` are the same value.
+//!
+//! With the `devnet` feature, protocols with a devnet deployment use it instead; the
+//! others keep their mainnet id. A `BEETHOVEN_<CONST>` environment variable at build
+//! time (e.g. `BEETHOVEN_GAMMA_PROGRAM_ID`) overrides a single id, for deployments no
+//! profile covers.
+
+use solana_address::Address;
+
+include!(concat!(env!("OUT_DIR"), "/program_ids.rs"));
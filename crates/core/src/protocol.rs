@@ -0,0 +1,2 @@
+// Generated from `protocols.toml` by `build.rs`
+include!(concat!(env!("OUT_DIR"), "/protocol_kind.rs"));
@@ -0,0 +1,111 @@
+//! Caller-owned scratch space for assembling CPI instruction data.
+//!
+//! Adapters that reserve their own instruction-data arrays add those bytes to every frame
+//! on the call path, whether or not the branch using them runs. Allocating one [`Scratch`]
+//! in the outermost frame, sized for the largest enabled adapter, and lending it down
+//! keeps nested callers within the 4KB stack frame limit.
+
+use {core::mem::MaybeUninit, solana_program_error::ProgramError};
+
+/// Uninitialized stack buffer of `N` bytes.
+pub struct Scratch<const N: usize>([MaybeUninit<u8>; N]);
+
+impl<const N: usize> Scratch<N> {
+    #[inline(always)]
+    pub const fn new() -> Self {
+        Self([const { MaybeUninit::uninit() }; N])
+    }
+
+    #[inline(always)]
+    pub fn as_uninit_mut(&mut self) -> &mut [MaybeUninit<u8>] {
+        &mut self.0
+    }
+}
+
+impl<const N: usize> Default for Scratch<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Instruction data template of `N` bytes: `discriminator` followed by zeroed slots.
+///
+/// Evaluated at compile time for `const` templates, so a discriminator longer than the
+/// layout fails the build.
+pub const fn data_template<const N: usize>(discriminator: &[u8]) -> [u8; N] {
+    let mut template = [0u8; N];
+    let mut i = 0;
+    while i < discriminator.len() {
+        template[i] = discriminator[i];
+        i += 1;
+    }
+    template
+}
+
+/// Copy `template` into the front of `scratch` and return it for the caller to patch the
+/// variable fields, failing with `InvalidArgument` if the scratch buffer is too small.
+#[inline(always)]
+pub fn from_template<'a, const N: usize>(
+    scratch: &'a mut [MaybeUninit<u8>],
+    template: &[u8; N],
+) -> Result<&'a mut [u8; N], ProgramError> {
+    let dst = scratch.get_mut(..N).ok_or(ProgramError::InvalidArgument)?;
+    // SAFETY: `dst` holds exactly `N` bytes, all initialized by the copy before the cast.
+    unsafe {
+        core::ptr::copy_nonoverlapping(template.as_ptr(), dst.as_mut_ptr() as *mut u8, N);
+        Ok(&mut *(dst.as_mut_ptr() as *mut [u8; N]))
+    }
+}
+
+/// Sequential writer over a scratch buffer, yielding the written bytes as instruction data.
+pub struct DataWriter<'a> {
+    buf: &'a mut [MaybeUninit<u8>],
+    len: usize,
+}
+
+impl<'a> DataWriter<'a> {
+    #[inline(always)]
+    pub fn new(buf: &'a mut [MaybeUninit<u8>]) -> Self {
+        Self { buf, len: 0 }
+    }
+
+    /// Append `bytes`, failing with `InvalidArgument` if the scratch buffer is too small.
+    #[inline(always)]
+    pub fn write(&mut self, bytes: &[u8]) -> Result<(), ProgramError> {
+        let end = self.len + bytes.len();
+        let dst = self
+            .buf
+            .get_mut(self.len..end)
+            .ok_or(ProgramError::InvalidArgument)?;
+        // SAFETY: `MaybeUninit<u8>` has the same layout as `u8` and `dst` holds exactly
+        // `bytes.len()` elements.
+        unsafe {
+            core::ptr::copy_nonoverlapping(bytes.as_ptr(), dst.as_mut_ptr() as *mut u8, bytes.len())
+        };
+        self.len = end;
+        Ok(())
+    }
+
+    #[inline(always)]
+    pub fn write_u8(&mut self, value: u8) -> Result<(), ProgramError> {
+        self.write(&[value])
+    }
+
+    #[inline(always)]
+    pub fn write_u32(&mut self, value: u32) -> Result<(), ProgramError> {
+        self.write(&value.to_le_bytes())
+    }
+
+    #[inline(always)]
+    pub fn write_u64(&mut self, value: u64) -> Result<(), ProgramError> {
+        self.write(&value.to_le_bytes())
+    }
+
+    /// The bytes written so far.
+    #[inline(always)]
+    pub fn finish(self) -> &'a [u8] {
+        let buf: &'a [MaybeUninit<u8>] = self.buf;
+        // SAFETY: the first `len` bytes were initialized by `write`.
+        unsafe { core::slice::from_raw_parts(buf.as_ptr() as *const u8, self.len) }
+    }
+}
@@ -0,0 +1,20 @@
+/// Which side of a swap the caller fixes.
+///
+/// Adapters only honor [`SwapMode::ExactOut`] where the venue supports it natively
+/// (see [`Capabilities::SUPPORTS_EXACT_OUT`](crate::Capabilities::SUPPORTS_EXACT_OUT));
+/// the rest fail with [`BeethovenError::UnsupportedSwapMode`](crate::BeethovenError).
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "borsh",
+    derive(borsh::BorshSerialize, borsh::BorshDeserialize),
+    borsh(use_discriminant = true)
+)]
+pub enum SwapMode {
+    /// Spend exactly the given amount, receiving at least the threshold
+    #[default]
+    ExactIn = 0,
+    /// Receive exactly the given amount, spending at most the threshold
+    ExactOut = 1,
+}
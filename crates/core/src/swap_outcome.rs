@@ -0,0 +1,16 @@
+/// What a swap actually moved, measured on the user's token accounts around the CPI.
+///
+/// Programs composing several actions can size the next one from `out_received`
+/// instead of the quote the swap was built from.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "borsh",
+    derive(borsh::BorshSerialize, borsh::BorshDeserialize)
+)]
+pub struct SwapOutcome {
+    /// Decrease of the source token account
+    pub in_spent: u64,
+    /// Increase of the destination token account
+    pub out_received: u64,
+}
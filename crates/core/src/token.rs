@@ -0,0 +1,65 @@
+//! Minimal readers over SPL Token account data.
+//!
+//! The first 72 bytes of a token account share the same layout in Token and Token-2022,
+//! which is all the adapters need to inspect.
+
+use {
+    solana_account_view::AccountView, solana_address::Address, solana_program_error::ProgramError,
+};
+
+/// Byte offset of the mint address in a token account
+pub const TOKEN_ACCOUNT_MINT_OFFSET: usize = 0;
+/// Byte offset of the owner address in a token account
+pub const TOKEN_ACCOUNT_OWNER_OFFSET: usize = 32;
+/// Byte offset of the amount (u64, little-endian) in a token account
+pub const TOKEN_ACCOUNT_AMOUNT_OFFSET: usize = 64;
+
+/// The SPL Token program
+pub const TOKEN_PROGRAM_ID: Address =
+    Address::from_str_const("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+/// The Token-2022 program
+pub const TOKEN_2022_PROGRAM_ID: Address =
+    Address::from_str_const("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+
+/// Amount that stands for the whole balance of the source token account, read when the
+/// dispatcher builds the CPI
+pub const FULL_BALANCE: u64 = u64::MAX;
+
+/// Read the mint of a token account.
+pub fn token_account_mint(account: &AccountView) -> Result<Address, ProgramError> {
+    read_address(account, TOKEN_ACCOUNT_MINT_OFFSET)
+}
+
+/// Read the owner (authority) of a token account.
+pub fn token_account_owner(account: &AccountView) -> Result<Address, ProgramError> {
+    read_address(account, TOKEN_ACCOUNT_OWNER_OFFSET)
+}
+
+/// Read the balance of a token account.
+pub fn token_account_amount(account: &AccountView) -> Result<u64, ProgramError> {
+    let data = account.try_borrow()?;
+    let bytes = data
+        .get(TOKEN_ACCOUNT_AMOUNT_OFFSET..TOKEN_ACCOUNT_AMOUNT_OFFSET + 8)
+        .and_then(|bytes| <[u8; 8]>::try_from(bytes).ok())
+        .ok_or(ProgramError::InvalidAccountData)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+/// `amount`, or the balance of `source` when `amount` is [`FULL_BALANCE`].
+#[inline(always)]
+pub fn resolve_amount(amount: u64, source: &AccountView) -> Result<u64, ProgramError> {
+    if amount == FULL_BALANCE {
+        token_account_amount(source)
+    } else {
+        Ok(amount)
+    }
+}
+
+fn read_address(account: &AccountView, offset: usize) -> Result<Address, ProgramError> {
+    let data = account.try_borrow()?;
+    let bytes = data
+        .get(offset..offset + 32)
+        .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+        .ok_or(ProgramError::InvalidAccountData)?;
+    Ok(Address::new_from_array(bytes))
+}
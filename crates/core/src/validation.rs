@@ -0,0 +1,286 @@
+//! Pre-CPI account checks shared by adapters built with `strict-validation`.
+
+#[cfg(feature = "strict-validation")]
+use solana_instruction_view::cpi::{Seed, Signer};
+use {
+    crate::{
+        token::{token_account_mint, token_account_owner, TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID},
+        BeethovenError,
+    },
+    solana_account_view::AccountView,
+    solana_address::{address_eq, Address},
+    solana_program_error::{ProgramError, ProgramResult},
+};
+
+/// The instructions sysvar
+pub const INSTRUCTIONS_SYSVAR_ID: Address =
+    Address::from_str_const("Sysvar1nstructions1111111111111111111111111");
+
+/// Most seeds a PDA derivation takes, bump included
+#[cfg(feature = "strict-validation")]
+const MAX_SEEDS: usize = 16;
+
+/// Ensure an authority account has signed the outer instruction, unless `signer_seeds`
+/// are passed for the CPI to sign for it.
+///
+/// Forgetting the seeds for a vault PDA otherwise only surfaces as an opaque
+/// privilege-escalation failure inside the target program. Whether the seeds derive
+/// the authority depends on the calling program's id, which [`check_authority`] takes.
+#[cfg(feature = "strict-validation")]
+#[inline(always)]
+pub fn check_signer(authority: &AccountView, signer_seeds: &[Signer]) -> ProgramResult {
+    if authority.is_signer() || !signer_seeds.is_empty() {
+        Ok(())
+    } else {
+        Err(ProgramError::MissingRequiredSignature)
+    }
+}
+
+/// Ensure an authority account will be able to sign a CPI from `program_id`.
+///
+/// The account must either have signed the outer instruction or be the PDA one of
+/// `signer_seeds` (each signer's seeds, bump included) derives under `program_id`, so
+/// passing another PDA's seeds fails here rather than inside the target program. Call it
+/// from the entrypoint, which has the program id, before a `*_signed` call.
+#[cfg(feature = "strict-validation")]
+pub fn check_authority(
+    authority: &AccountView,
+    program_id: &Address,
+    signer_seeds: &[&[Seed]],
+) -> ProgramResult {
+    if authority.is_signer() {
+        return Ok(());
+    }
+    let derives_authority = signer_seeds.iter().any(|seeds| {
+        pda_address(seeds, program_id)
+            .is_some_and(|address| address_eq(&address, authority.address()))
+    });
+
+    if derives_authority {
+        Ok(())
+    } else {
+        Err(ProgramError::MissingRequiredSignature)
+    }
+}
+
+/// `{ pointer, length }` of one seed, as `sol_create_program_address` reads its seed
+/// list
+#[cfg(all(
+    feature = "strict-validation",
+    any(target_os = "solana", target_arch = "bpf")
+))]
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct RawSeed {
+    addr: *const u8,
+    len: u64,
+}
+
+/// The PDA `seeds` derive under `program_id`, or `None` if they are not valid PDA
+/// seeds.
+#[cfg(all(
+    feature = "strict-validation",
+    any(target_os = "solana", target_arch = "bpf")
+))]
+fn pda_address(seeds: &[Seed], program_id: &Address) -> Option<Address> {
+    if seeds.len() > MAX_SEEDS {
+        return None;
+    }
+    let mut raw = [RawSeed {
+        addr: core::ptr::null(),
+        len: 0,
+    }; MAX_SEEDS];
+    for (raw, seed) in raw.iter_mut().zip(seeds) {
+        let seed: &[u8] = seed;
+        *raw = RawSeed {
+            addr: seed.as_ptr(),
+            len: seed.len() as u64,
+        };
+    }
+
+    let mut address = core::mem::MaybeUninit::<[u8; 32]>::uninit();
+    // SAFETY: `raw` holds `seeds.len()` valid `{ pointer, length }` pairs and `address`
+    // is 32 writable bytes
+    let result = unsafe {
+        solana_define_syscall::definitions::sol_create_program_address(
+            raw.as_ptr() as *const u8,
+            seeds.len() as u64,
+            program_id.as_ref().as_ptr(),
+            address.as_mut_ptr() as *mut u8,
+        )
+    };
+    match result {
+        // SAFETY: the syscall wrote the address on success
+        0 => Some(Address::new_from_array(unsafe { address.assume_init() })),
+        _ => None,
+    }
+}
+
+/// Off-chain, where the PDA syscall is unavailable, no seeds derive an address.
+#[cfg(all(
+    feature = "strict-validation",
+    not(any(target_os = "solana", target_arch = "bpf"))
+))]
+fn pda_address(seeds: &[Seed], _program_id: &Address) -> Option<Address> {
+    let _ = (seeds, MAX_SEEDS);
+    None
+}
+
+/// Ensure `token_account` is controlled by `authority`.
+///
+/// Catches a source token account the signer does not own before the target program
+/// rejects the transfer.
+#[inline(always)]
+pub fn check_token_account_owner(
+    token_account: &AccountView,
+    authority: &AccountView,
+) -> ProgramResult {
+    check_token_account(token_account)?;
+    if token_account_owner(token_account)? != *authority.address() {
+        return Err(BeethovenError::TokenAccountOwnerMismatch.into());
+    }
+
+    Ok(())
+}
+
+/// Ensure the user token account a swap spends from holds the same mint as the pool
+/// vault it pays into, logging `label` against `labels` otherwise.
+///
+/// Venues whose swap data picks the direction (Aldrin's `Side`, Futarchy's `SwapType`)
+/// draw from the user account it names, so user accounts passed swapped otherwise only
+/// fail deep inside the venue's CPI.
+pub fn check_source_mint(
+    labels: &[&str],
+    label: &str,
+    user_source: &AccountView,
+    source_vault: &AccountView,
+) -> ProgramResult {
+    if token_account_mint(user_source)? != token_account_mint(source_vault)? {
+        log_account_error(labels, label, "mint differs from the source vault's");
+        return Err(BeethovenError::DirectionMismatch.into());
+    }
+
+    Ok(())
+}
+
+/// Ensure `token_account` belongs to SPL Token or Token-2022, so the bytes read from it
+/// are a token account's.
+#[inline(always)]
+pub fn check_token_account(token_account: &AccountView) -> ProgramResult {
+    if token_account.owned_by(&TOKEN_PROGRAM_ID) || token_account.owned_by(&TOKEN_2022_PROGRAM_ID) {
+        Ok(())
+    } else {
+        Err(ProgramError::InvalidAccountOwner)
+    }
+}
+
+/// Ensure the program account an adapter passes is the program its CPI invokes.
+///
+/// The dispatchers detect the venue from this account, but the direct entry points take
+/// the accounts as given.
+#[inline(always)]
+pub fn check_program_account(account: &AccountView, program_id: &Address) -> ProgramResult {
+    if address_eq(account.address(), program_id) {
+        Ok(())
+    } else {
+        Err(ProgramError::IncorrectProgramId)
+    }
+}
+
+/// Ensure a token program account is SPL Token or Token-2022.
+#[inline(always)]
+pub fn check_token_program(account: &AccountView) -> ProgramResult {
+    if address_eq(account.address(), &TOKEN_PROGRAM_ID)
+        || address_eq(account.address(), &TOKEN_2022_PROGRAM_ID)
+    {
+        Ok(())
+    } else {
+        Err(ProgramError::IncorrectProgramId)
+    }
+}
+
+/// Ensure `account` is the instructions sysvar.
+#[inline(always)]
+pub fn check_instructions_sysvar(account: &AccountView) -> ProgramResult {
+    if address_eq(account.address(), &INSTRUCTIONS_SYSVAR_ID) {
+        Ok(())
+    } else {
+        Err(ProgramError::UnsupportedSysvar)
+    }
+}
+
+/// Run [`check_program_account`], [`check_token_program`] on each labelled token
+/// program and [`check_instructions_sysvar`] on the labelled sysvar, if the adapter
+/// passes one, logging the first failure against `labels`.
+pub fn check_fixed_accounts(
+    labels: &[&str],
+    (program, program_id): (&AccountView, &Address),
+    token_programs: &[(&str, &AccountView)],
+    instructions_sysvar: Option<(&str, &AccountView)>,
+) -> ProgramResult {
+    check_program_account(program, program_id)
+        .inspect_err(|_| log_account_error(labels, "program", "not the adapter's program"))?;
+    for (label, token_program) in token_programs {
+        check_token_program(token_program)
+            .inspect_err(|_| log_account_error(labels, label, "not a token program"))?;
+    }
+    if let Some((label, sysvar)) = instructions_sysvar {
+        check_instructions_sysvar(sysvar)
+            .inspect_err(|_| log_account_error(labels, label, "not the instructions sysvar"))?;
+    }
+
+    Ok(())
+}
+
+/// Longest line [`log_account_error`] logs; longer reasons are cut off
+const MAX_LOG_LEN: usize = 128;
+
+/// Log which account a failed check was about, as
+/// ``account #7 `reserve_collateral_mint`: wrong owner``, where `#7` is `label`'s
+/// position in the adapter's `ACCOUNT_LABELS`.
+///
+/// Meant for the error path of a check (`check(..).inspect_err(|_| ..)`), so the
+/// program log names the account behind an otherwise bare error code.
+#[cold]
+pub fn log_account_error(labels: &[&str], label: &str, reason: &str) {
+    let mut line = [0u8; MAX_LOG_LEN];
+    let mut len = 0;
+    let mut push = |bytes: &[u8]| {
+        let end = (len + bytes.len()).min(MAX_LOG_LEN);
+        line[len..end].copy_from_slice(&bytes[..end - len]);
+        len = end;
+    };
+
+    push(b"account #");
+    match labels.iter().position(|known| *known == label) {
+        Some(index) => {
+            let mut digits = [0u8; 20];
+            let mut start = digits.len();
+            let mut rest = index;
+            loop {
+                start -= 1;
+                digits[start] = b'0' + (rest % 10) as u8;
+                rest /= 10;
+                if rest == 0 {
+                    break;
+                }
+            }
+            push(&digits[start..]);
+        }
+        None => push(b"?"),
+    }
+    push(b" `");
+    push(label.as_bytes());
+    push(b"`: ");
+    push(reason.as_bytes());
+
+    log(&line[..len]);
+}
+
+#[cfg(any(target_os = "solana", target_arch = "bpf"))]
+fn log(message: &[u8]) {
+    unsafe { solana_define_syscall::definitions::sol_log_(message.as_ptr(), message.len() as u64) }
+}
+
+#[cfg(not(any(target_os = "solana", target_arch = "bpf")))]
+fn log(_message: &[u8]) {}
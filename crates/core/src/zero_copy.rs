@@ -0,0 +1,72 @@
+//! Zero-copy views over account data.
+//!
+//! Layouts are `#[repr(C)]` structs made only of byte arrays, [`Address`]es and the
+//! `Pod*` integer wrappers below, so they have alignment 1: casting them over account
+//! data is always aligned, at any offset, and reading a field costs no more than the
+//! bytes it touches. Field offsets are pinned with `offset_of!` assertions next to each
+//! layout.
+//!
+//! [`Address`]: solana_address::Address
+
+use solana_program_error::ProgramError;
+
+/// Plain-old-data layout that can be cast directly from account bytes.
+///
+/// # Safety
+///
+/// Implementors must be `#[repr(C)]` or `#[repr(transparent)]`, have an alignment of 1,
+/// and be valid for every bit pattern.
+pub unsafe trait ZeroCopy: Sized {
+    /// View the first `size_of::<Self>()` bytes of `data` as `Self`.
+    #[inline(always)]
+    fn load(data: &[u8]) -> Result<&Self, ProgramError> {
+        const { assert!(core::mem::align_of::<Self>() == 1) };
+        let bytes = data
+            .get(..core::mem::size_of::<Self>())
+            .ok_or(ProgramError::AccountDataTooSmall)?;
+        // SAFETY: `bytes` covers `Self`, which has alignment 1 and no invalid bit patterns.
+        Ok(unsafe { &*(bytes.as_ptr() as *const Self) })
+    }
+}
+
+/// View an Anchor account's data as `T`, after checking its 8-byte `discriminator`.
+#[inline(always)]
+pub fn load_account<'a, T: ZeroCopy>(
+    data: &'a [u8],
+    discriminator: &[u8; 8],
+) -> Result<&'a T, ProgramError> {
+    match data.split_first_chunk::<8>() {
+        Some((found, layout)) if found == discriminator => T::load(layout),
+        _ => Err(ProgramError::InvalidAccountData),
+    }
+}
+
+macro_rules! pod_int {
+    ($name:ident, $int:ty) => {
+        #[doc = concat!("Little-endian `", stringify!($int), "` with alignment 1.")]
+        #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+        #[repr(transparent)]
+        pub struct $name([u8; core::mem::size_of::<$int>()]);
+
+        impl $name {
+            #[inline(always)]
+            pub const fn get(&self) -> $int {
+                <$int>::from_le_bytes(self.0)
+            }
+        }
+
+        impl From<$int> for $name {
+            fn from(value: $int) -> Self {
+                Self(value.to_le_bytes())
+            }
+        }
+
+        // SAFETY: a byte array, valid for every bit pattern.
+        unsafe impl ZeroCopy for $name {}
+    };
+}
+
+pod_int!(PodU16, u16);
+pod_int!(PodU32, u32);
+pod_int!(PodU64, u64);
+pod_int!(PodU128, u128);
@@ -1,22 +1,71 @@
 #![no_std]
 
+pub use beethoven_core::program_ids::JUPITER_EARN_PROGRAM_ID;
 use {
-    beethoven_core::Deposit,
-    core::mem::MaybeUninit,
-    solana_account_view::AccountView,
-    solana_address::Address,
-    solana_instruction_view::{
-        cpi::{invoke_signed, Signer},
-        InstructionAccount, InstructionView,
+    beethoven_core::{
+        cpi::invoke_signed, scratch::data_template, token::token_account_amount, BeethovenError,
+        Deposit, Withdraw,
     },
+    solana_account_view::AccountView,
+    solana_instruction_view::{cpi::Signer, InstructionAccount, InstructionView},
     solana_program_error::{ProgramError, ProgramResult},
 };
-
-pub const JUPITER_EARN_PROGRAM_ID: Address = Address::new_from_array([0u8; 32]);
 pub const DEPOSIT_DISCRIMINATOR: [u8; 8] = [242, 35, 198, 137, 82, 225, 242, 182];
+pub const REDEEM_DISCRIMINATOR: [u8; 8] = [184, 12, 86, 149, 70, 196, 97, 225];
+
+/// `deposit` instruction data with the amount zeroed.
+///
+/// Layout: discriminator ([u8; 8]), amount (u64)
+pub const DEPOSIT_DATA_TEMPLATE: [u8; 16] = data_template(&DEPOSIT_DISCRIMINATOR);
+
+/// `redeem` instruction data with the share amount zeroed.
+///
+/// Layout: discriminator ([u8; 8]), shares (u64)
+pub const REDEEM_DATA_TEMPLATE: [u8; 16] = data_template(&REDEEM_DISCRIMINATOR);
 
 pub struct JupiterEarn;
 
+/// Parameters of a Jupiter Earn deposit beyond the amount.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct JupiterEarnDepositData {
+    /// Fail with `SlippageExceeded` unless the deposit mints at least this many fTokens
+    /// to the recipient
+    pub minimum_shares_out: Option<u64>,
+}
+
+impl JupiterEarnDepositData {
+    pub const fn new() -> Self {
+        Self {
+            minimum_shares_out: None,
+        }
+    }
+
+    /// Check the fTokens the deposit mints, see
+    /// [`JupiterEarn::deposit_with_data_signed`].
+    pub const fn with_minimum_shares_out(mut self, minimum_shares_out: u64) -> Self {
+        self.minimum_shares_out = Some(minimum_shares_out);
+        self
+    }
+}
+
+/// Layout: minimum_shares_out (u64, little-endian) when the check is wanted, else
+/// nothing
+impl TryFrom<&[u8]> for JupiterEarnDepositData {
+    type Error = ProgramError;
+
+    fn try_from(data: &[u8]) -> Result<Self, Self::Error> {
+        let minimum_shares_out = match data {
+            [] => None,
+            _ => Some(u64::from_le_bytes(
+                data.try_into()
+                    .map_err(|_| ProgramError::InvalidInstructionData)?,
+            )),
+        };
+        Ok(Self { minimum_shares_out })
+    }
+}
+
 pub struct JupiterEarnDepositAccounts<'info> {
     pub lending_program: &'info AccountView,
     pub signer: &'info AccountView,
@@ -41,6 +90,7 @@ pub struct JupiterEarnDepositAccounts<'info> {
 impl<'info> TryFrom<&'info [AccountView]> for JupiterEarnDepositAccounts<'info> {
     type Error = ProgramError;
 
+    #[inline(always)]
     fn try_from(accounts: &'info [AccountView]) -> Result<Self, Self::Error> {
         if accounts.len() < 18 {
             return Err(ProgramError::NotEnoughAccountKeys);
@@ -52,6 +102,24 @@ impl<'info> TryFrom<&'info [AccountView]> for JupiterEarnDepositAccounts<'info>
             return Err(ProgramError::NotEnoughAccountKeys);
         };
 
+        #[cfg(feature = "strict-validation")]
+        beethoven_core::validation::check_token_account_owner(depositor_token_account, signer)
+            .inspect_err(|_| {
+                beethoven_core::validation::log_account_error(
+                    ACCOUNT_LABELS,
+                    "depositor_token_account",
+                    "not owned by `signer`",
+                )
+            })?;
+
+        #[cfg(feature = "strict-validation")]
+        beethoven_core::validation::check_fixed_accounts(
+            ACCOUNT_LABELS,
+            (lending_program, &JUPITER_EARN_PROGRAM_ID),
+            &[("token_program", token_program)],
+            None,
+        )?;
+
         Ok(JupiterEarnDepositAccounts {
             signer,
             depositor_token_account,
@@ -75,6 +143,63 @@ impl<'info> TryFrom<&'info [AccountView]> for JupiterEarnDepositAccounts<'info>
     }
 }
 
+/// What this adapter supports, as `ProtocolKind::Jupiter.capabilities()` reports it
+pub const CAPABILITIES: beethoven_core::Capabilities =
+    beethoven_core::ProtocolKind::Jupiter.capabilities();
+
+/// Name of each account after the detector, in order, for error and log output.
+pub const ACCOUNT_LABELS: &[&str] = &[
+    "signer",
+    "depositor_token_account",
+    "recipient_token_account",
+    "mint",
+    "lending_admin",
+    "lending",
+    "f_token_mint",
+    "supply_token_reserves_liquidity",
+    "lending_supply_position_on_liquidity",
+    "rate_model",
+    "vault",
+    "liquidity",
+    "liquidity_program",
+    "rewards_rate_model",
+    "token_program",
+    "associated_token_program",
+    "system_program",
+];
+
+impl<'info> JupiterEarnDepositAccounts<'info> {
+    /// The accounts in the order the CPI passes them, without the detector.
+    #[inline(always)]
+    pub fn as_ordered_slice(&self) -> [&'info AccountView; 17] {
+        [
+            self.signer,
+            self.depositor_token_account,
+            self.recipient_token_account,
+            self.mint,
+            self.lending_admin,
+            self.lending,
+            self.f_token_mint,
+            self.supply_token_reserves_liquidity,
+            self.lending_supply_position_on_liquidity,
+            self.rate_model,
+            self.vault,
+            self.liquidity,
+            self.liquidity_program,
+            self.rewards_rate_model,
+            self.token_program,
+            self.associated_token_program,
+            self.system_program,
+        ]
+    }
+
+    /// Iterate over [`Self::as_ordered_slice`].
+    #[inline(always)]
+    pub fn iter(&self) -> impl Iterator<Item = &'info AccountView> {
+        self.as_ordered_slice().into_iter()
+    }
+}
+
 impl<'info> Deposit<'info> for JupiterEarn {
     type Accounts = JupiterEarnDepositAccounts<'info>;
 
@@ -83,6 +208,15 @@ impl<'info> Deposit<'info> for JupiterEarn {
         amount: u64,
         signer_seeds: &[Signer],
     ) -> ProgramResult {
+        #[cfg(feature = "strict-validation")]
+        beethoven_core::validation::check_signer(ctx.signer, signer_seeds).inspect_err(|_| {
+            beethoven_core::validation::log_account_error(
+                ACCOUNT_LABELS,
+                "signer",
+                "missing signature",
+            )
+        })?;
+
         let accounts = [
             InstructionAccount::writable_signer(ctx.signer.address()),
             InstructionAccount::writable(ctx.depositor_token_account.address()),
@@ -103,39 +237,12 @@ impl<'info> Deposit<'info> for JupiterEarn {
             InstructionAccount::readonly(ctx.system_program.address()),
         ];
 
-        let account_infos = [
-            ctx.signer,
-            ctx.depositor_token_account,
-            ctx.recipient_token_account,
-            ctx.mint,
-            ctx.lending_admin,
-            ctx.lending,
-            ctx.f_token_mint,
-            ctx.supply_token_reserves_liquidity,
-            ctx.lending_supply_position_on_liquidity,
-            ctx.rate_model,
-            ctx.vault,
-            ctx.liquidity,
-            ctx.liquidity_program,
-            ctx.rewards_rate_model,
-            ctx.token_program,
-            ctx.associated_token_program,
-            ctx.system_program,
-        ];
-
-        let mut instruction_data = MaybeUninit::<[u8; 16]>::uninit();
-        unsafe {
-            let ptr = instruction_data.as_mut_ptr() as *mut u8;
-            core::ptr::copy_nonoverlapping(DEPOSIT_DISCRIMINATOR.as_ptr(), ptr, 8);
-            core::ptr::copy_nonoverlapping(amount.to_le_bytes().as_ptr(), ptr.add(8), 8);
-        }
+        let account_infos = ctx.as_ordered_slice();
 
         let deposit_ix = InstructionView {
             program_id: &JUPITER_EARN_PROGRAM_ID,
             accounts: &accounts,
-            data: unsafe {
-                core::slice::from_raw_parts(instruction_data.as_ptr() as *const u8, 16)
-            },
+            data: &deposit_instruction_data(amount),
         };
 
         invoke_signed(&deposit_ix, &account_infos, signer_seeds)?;
@@ -143,7 +250,236 @@ impl<'info> Deposit<'info> for JupiterEarn {
         Ok(())
     }
 
+    #[inline(always)]
     fn deposit(ctx: &JupiterEarnDepositAccounts<'info>, amount: u64) -> ProgramResult {
         Self::deposit_signed(ctx, amount, &[])
     }
 }
+
+impl JupiterEarn {
+    /// [`Deposit::deposit_signed`], failing with [`BeethovenError::SlippageExceeded`]
+    /// when `data` sets a minimum the fTokens minted to `recipient_token_account` fall
+    /// short of.
+    pub fn deposit_with_data_signed(
+        ctx: &JupiterEarnDepositAccounts<'_>,
+        amount: u64,
+        data: &JupiterEarnDepositData,
+        signer_seeds: &[Signer],
+    ) -> ProgramResult {
+        let Some(minimum_shares_out) = data.minimum_shares_out else {
+            return Self::deposit_signed(ctx, amount, signer_seeds);
+        };
+
+        let before = token_account_amount(ctx.recipient_token_account)?;
+        Self::deposit_signed(ctx, amount, signer_seeds)?;
+        let minted = token_account_amount(ctx.recipient_token_account)?.saturating_sub(before);
+
+        if minted < minimum_shares_out {
+            return Err(BeethovenError::SlippageExceeded.into());
+        }
+        Ok(())
+    }
+}
+
+pub struct JupiterEarnWithdrawAccounts<'info> {
+    pub lending_program: &'info AccountView,
+    pub signer: &'info AccountView,
+    pub owner_token_account: &'info AccountView,
+    pub recipient_token_account: &'info AccountView,
+    pub lending_admin: &'info AccountView,
+    pub lending: &'info AccountView,
+    pub mint: &'info AccountView,
+    pub f_token_mint: &'info AccountView,
+    pub supply_token_reserves_liquidity: &'info AccountView,
+    pub lending_supply_position_on_liquidity: &'info AccountView,
+    pub rate_model: &'info AccountView,
+    pub vault: &'info AccountView,
+    pub claim_account: &'info AccountView,
+    pub liquidity: &'info AccountView,
+    pub liquidity_program: &'info AccountView,
+    pub rewards_rate_model: &'info AccountView,
+    pub token_program: &'info AccountView,
+    pub associated_token_program: &'info AccountView,
+    pub system_program: &'info AccountView,
+}
+
+impl<'info> TryFrom<&'info [AccountView]> for JupiterEarnWithdrawAccounts<'info> {
+    type Error = ProgramError;
+
+    #[inline(always)]
+    fn try_from(accounts: &'info [AccountView]) -> Result<Self, Self::Error> {
+        let [lending_program, signer, owner_token_account, recipient_token_account, lending_admin, lending, mint, f_token_mint, supply_token_reserves_liquidity, lending_supply_position_on_liquidity, rate_model, vault, claim_account, liquidity, liquidity_program, rewards_rate_model, token_program, associated_token_program, system_program, ..] =
+            accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        #[cfg(feature = "strict-validation")]
+        beethoven_core::validation::check_token_account_owner(owner_token_account, signer)
+            .inspect_err(|_| {
+                beethoven_core::validation::log_account_error(
+                    WITHDRAW_ACCOUNT_LABELS,
+                    "owner_token_account",
+                    "not owned by `signer`",
+                )
+            })?;
+
+        #[cfg(feature = "strict-validation")]
+        beethoven_core::validation::check_fixed_accounts(
+            WITHDRAW_ACCOUNT_LABELS,
+            (lending_program, &JUPITER_EARN_PROGRAM_ID),
+            &[("token_program", token_program)],
+            None,
+        )?;
+
+        Ok(JupiterEarnWithdrawAccounts {
+            lending_program,
+            signer,
+            owner_token_account,
+            recipient_token_account,
+            lending_admin,
+            lending,
+            mint,
+            f_token_mint,
+            supply_token_reserves_liquidity,
+            lending_supply_position_on_liquidity,
+            rate_model,
+            vault,
+            claim_account,
+            liquidity,
+            liquidity_program,
+            rewards_rate_model,
+            token_program,
+            associated_token_program,
+            system_program,
+        })
+    }
+}
+
+/// Name of each withdraw account after the detector, in order, for error and log output.
+pub const WITHDRAW_ACCOUNT_LABELS: &[&str] = &[
+    "signer",
+    "owner_token_account",
+    "recipient_token_account",
+    "lending_admin",
+    "lending",
+    "mint",
+    "f_token_mint",
+    "supply_token_reserves_liquidity",
+    "lending_supply_position_on_liquidity",
+    "rate_model",
+    "vault",
+    "claim_account",
+    "liquidity",
+    "liquidity_program",
+    "rewards_rate_model",
+    "token_program",
+    "associated_token_program",
+    "system_program",
+];
+
+impl<'info> JupiterEarnWithdrawAccounts<'info> {
+    /// The accounts in the order the CPI passes them, without the detector.
+    #[inline(always)]
+    pub fn as_ordered_slice(&self) -> [&'info AccountView; 18] {
+        [
+            self.signer,
+            self.owner_token_account,
+            self.recipient_token_account,
+            self.lending_admin,
+            self.lending,
+            self.mint,
+            self.f_token_mint,
+            self.supply_token_reserves_liquidity,
+            self.lending_supply_position_on_liquidity,
+            self.rate_model,
+            self.vault,
+            self.claim_account,
+            self.liquidity,
+            self.liquidity_program,
+            self.rewards_rate_model,
+            self.token_program,
+            self.associated_token_program,
+            self.system_program,
+        ]
+    }
+
+    /// Iterate over [`Self::as_ordered_slice`].
+    #[inline(always)]
+    pub fn iter(&self) -> impl Iterator<Item = &'info AccountView> {
+        self.as_ordered_slice().into_iter()
+    }
+}
+
+/// Burns `amount` fTokens (shares, not underlying) through `redeem` and sends the
+/// underlying they are worth to `recipient_token_account`.
+impl<'info> Withdraw<'info> for JupiterEarn {
+    type Accounts = JupiterEarnWithdrawAccounts<'info>;
+
+    fn withdraw_signed(
+        ctx: &JupiterEarnWithdrawAccounts<'info>,
+        amount: u64,
+        signer_seeds: &[Signer],
+    ) -> ProgramResult {
+        #[cfg(feature = "strict-validation")]
+        beethoven_core::validation::check_signer(ctx.signer, signer_seeds).inspect_err(|_| {
+            beethoven_core::validation::log_account_error(
+                WITHDRAW_ACCOUNT_LABELS,
+                "signer",
+                "missing signature",
+            )
+        })?;
+
+        let accounts = [
+            InstructionAccount::writable_signer(ctx.signer.address()),
+            InstructionAccount::writable(ctx.owner_token_account.address()),
+            InstructionAccount::writable(ctx.recipient_token_account.address()),
+            InstructionAccount::readonly(ctx.lending_admin.address()),
+            InstructionAccount::writable(ctx.lending.address()),
+            InstructionAccount::readonly(ctx.mint.address()),
+            InstructionAccount::writable(ctx.f_token_mint.address()),
+            InstructionAccount::writable(ctx.supply_token_reserves_liquidity.address()),
+            InstructionAccount::writable(ctx.lending_supply_position_on_liquidity.address()),
+            InstructionAccount::readonly(ctx.rate_model.address()),
+            InstructionAccount::writable(ctx.vault.address()),
+            InstructionAccount::writable(ctx.claim_account.address()),
+            InstructionAccount::writable(ctx.liquidity.address()),
+            InstructionAccount::writable(ctx.liquidity_program.address()),
+            InstructionAccount::readonly(ctx.rewards_rate_model.address()),
+            InstructionAccount::readonly(ctx.token_program.address()),
+            InstructionAccount::readonly(ctx.associated_token_program.address()),
+            InstructionAccount::readonly(ctx.system_program.address()),
+        ];
+
+        let account_infos = ctx.as_ordered_slice();
+
+        let redeem_ix = InstructionView {
+            program_id: &JUPITER_EARN_PROGRAM_ID,
+            accounts: &accounts,
+            data: &redeem_instruction_data(amount),
+        };
+
+        invoke_signed(&redeem_ix, &account_infos, signer_seeds)
+    }
+
+    #[inline(always)]
+    fn withdraw(ctx: &JupiterEarnWithdrawAccounts<'info>, amount: u64) -> ProgramResult {
+        Self::withdraw_signed(ctx, amount, &[])
+    }
+}
+
+/// `redeem` instruction data, patched into [`REDEEM_DATA_TEMPLATE`].
+#[inline(always)]
+pub fn redeem_instruction_data(shares: u64) -> [u8; 16] {
+    let mut data = REDEEM_DATA_TEMPLATE;
+    data[8..16].copy_from_slice(&shares.to_le_bytes());
+    data
+}
+
+/// `deposit` instruction data, patched into [`DEPOSIT_DATA_TEMPLATE`].
+#[inline(always)]
+pub fn deposit_instruction_data(amount: u64) -> [u8; 16] {
+    let mut data = DEPOSIT_DATA_TEMPLATE;
+    data[8..16].copy_from_slice(&amount.to_le_bytes());
+    data
+}
@@ -1,25 +1,139 @@
 #![no_std]
 
+pub use beethoven_core::program_ids::KAMINO_LEND_PROGRAM_ID;
 use {
-    beethoven_core::Deposit,
+    beethoven_core::{
+        cpi::{invoke_signed, invoke_signed_with_bounds},
+        scratch::data_template,
+        token::token_account_amount,
+        BeethovenError, Borrow, Deposit, Repay, Withdraw,
+    },
     core::mem::MaybeUninit,
     solana_account_view::AccountView,
-    solana_address::Address,
-    solana_instruction_view::{
-        cpi::{invoke_signed, Signer},
-        InstructionAccount, InstructionView,
-    },
+    solana_address::{address_eq, Address},
+    solana_instruction_view::{cpi::Signer, InstructionAccount, InstructionView},
     solana_program_error::{ProgramError, ProgramResult},
 };
-
-pub const KAMINO_LEND_PROGRAM_ID: Address = Address::new_from_array([0; 32]);
-const REFRESH_RESERVE_DISCRIMINATOR: [u8; 8] = [2, 218, 138, 235, 79, 201, 25, 102];
+const REFRESH_RESERVES_BATCH_DISCRIMINATOR: [u8; 8] = [144, 110, 26, 103, 162, 204, 252, 147];
 const REFRESH_OBLIGATION_DISCRIMINATOR: [u8; 8] = [33, 132, 147, 228, 151, 192, 72, 89];
 const DEPOSIT_RESERVE_LIQUIDITY_AND_OBLIGATION_COLLATERAL_V2_DISCRIMINATOR: [u8; 8] =
     [216, 224, 191, 27, 204, 151, 102, 175];
+const WITHDRAW_OBLIGATION_COLLATERAL_AND_REDEEM_RESERVE_COLLATERAL_V2_DISCRIMINATOR: [u8; 8] =
+    [235, 52, 119, 152, 149, 197, 20, 7];
+const BORROW_OBLIGATION_LIQUIDITY_V2_DISCRIMINATOR: [u8; 8] =
+    [161, 128, 143, 245, 171, 199, 194, 6];
+const REPAY_OBLIGATION_LIQUIDITY_V2_DISCRIMINATOR: [u8; 8] = [116, 174, 213, 76, 180, 53, 210, 144];
+const FLASH_BORROW_RESERVE_LIQUIDITY_DISCRIMINATOR: [u8; 8] = [135, 231, 52, 167, 7, 52, 212, 193];
+const FLASH_REPAY_RESERVE_LIQUIDITY_DISCRIMINATOR: [u8; 8] = [185, 117, 0, 203, 96, 245, 180, 186];
+
+/// `deposit` instruction data with the amount zeroed.
+///
+/// Layout: discriminator ([u8; 8]), amount (u64)
+pub const DEPOSIT_DATA_TEMPLATE: [u8; 16] =
+    data_template(&DEPOSIT_RESERVE_LIQUIDITY_AND_OBLIGATION_COLLATERAL_V2_DISCRIMINATOR);
+
+/// `withdraw` instruction data with the collateral amount zeroed.
+///
+/// Layout: discriminator ([u8; 8]), collateral_amount (u64)
+pub const WITHDRAW_DATA_TEMPLATE: [u8; 16] =
+    data_template(&WITHDRAW_OBLIGATION_COLLATERAL_AND_REDEEM_RESERVE_COLLATERAL_V2_DISCRIMINATOR);
+
+/// `borrow` instruction data with the liquidity amount zeroed.
+///
+/// Layout: discriminator ([u8; 8]), liquidity_amount (u64)
+pub const BORROW_DATA_TEMPLATE: [u8; 16] =
+    data_template(&BORROW_OBLIGATION_LIQUIDITY_V2_DISCRIMINATOR);
+
+/// `repay` instruction data with the liquidity amount zeroed.
+///
+/// Layout: discriminator ([u8; 8]), liquidity_amount (u64)
+pub const REPAY_DATA_TEMPLATE: [u8; 16] =
+    data_template(&REPAY_OBLIGATION_LIQUIDITY_V2_DISCRIMINATOR);
+
+/// `flash_borrow_reserve_liquidity` instruction data with the amount zeroed.
+///
+/// Layout: discriminator ([u8; 8]), liquidity_amount (u64)
+pub const FLASH_BORROW_DATA_TEMPLATE: [u8; 16] =
+    data_template(&FLASH_BORROW_RESERVE_LIQUIDITY_DISCRIMINATOR);
+
+/// `flash_repay_reserve_liquidity` instruction data with every field zeroed.
+///
+/// Layout: discriminator ([u8; 8]), liquidity_amount (u64), borrow_instruction_index (u8)
+pub const FLASH_REPAY_DATA_TEMPLATE: [u8; 17] =
+    data_template(&FLASH_REPAY_RESERVE_LIQUIDITY_DISCRIMINATOR);
+
+/// Maximum number of reserves an obligation can reference (8 deposits + 5 borrows)
+const MAX_OBLIGATION_RESERVES: usize = 13;
+/// Maximum number of reserves refreshed before a deposit: the target reserve plus the
+/// obligation's reserves
+const MAX_REFRESH_RESERVES: usize = 1 + MAX_OBLIGATION_RESERVES;
+/// Accounts `refresh_reserves_batch` expects per reserve, mirroring `refresh_reserve`
+const REFRESH_RESERVE_ACCOUNTS: usize = 6;
+/// Reserves refreshed per `refresh_reserves_batch` CPI. The metas, account infos and the
+/// CPI's own copy of the infos for all `MAX_REFRESH_RESERVES` would not fit one stack
+/// frame, so larger refreshes are split across CPIs.
+const REFRESH_BATCH_RESERVES: usize = 5;
+/// Offset of `last_update.slot` (u64) in a reserve: discriminator (8) + version (8).
+///
+/// klend `programs/klend/src/state/reserve.rs`: `Reserve { version: u64, last_update:
+/// LastUpdate, .. }`, and `state/last_update.rs`: `LastUpdate { slot: u64, stale: u8,
+/// price_status: u8, placeholder: [u8; 6] }`.
+const RESERVE_LAST_UPDATE_SLOT_OFFSET: usize = 16;
+/// Offset of `last_update.stale` (u8) in a reserve, right after `last_update.slot`
+const RESERVE_LAST_UPDATE_STALE_OFFSET: usize = 24;
+/// Offset of `bump_seed` (u64) in a lending market: discriminator (8) + version (8).
+///
+/// klend `programs/klend/src/state/lending_market.rs`: `LendingMarket { version: u64,
+/// bump_seed: u64, .. }`. The bump fits in the low byte, which is read on its own.
+const LENDING_MARKET_BUMP_SEED_OFFSET: usize = 16;
+
+/// Seed prefix of a lending market's authority PDA: `[b"lma", lending_market]`
+pub const LENDING_MARKET_AUTHORITY_SEED: &[u8] = b"lma";
 
 pub struct Kamino;
 
+/// Parameters of a Kamino deposit beyond the amount.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct KaminoDepositData {
+    /// Fail with `SlippageExceeded` unless the deposit mints at least this much reserve
+    /// collateral into the obligation
+    pub minimum_collateral_out: Option<u64>,
+}
+
+impl KaminoDepositData {
+    pub const fn new() -> Self {
+        Self {
+            minimum_collateral_out: None,
+        }
+    }
+
+    /// Check the collateral the deposit mints, see [`Kamino::deposit_with_data_signed`].
+    pub const fn with_minimum_collateral_out(mut self, minimum_collateral_out: u64) -> Self {
+        self.minimum_collateral_out = Some(minimum_collateral_out);
+        self
+    }
+}
+
+/// Layout: minimum_collateral_out (u64, little-endian) when the check is wanted, else
+/// nothing
+impl TryFrom<&[u8]> for KaminoDepositData {
+    type Error = ProgramError;
+
+    fn try_from(data: &[u8]) -> Result<Self, Self::Error> {
+        let minimum_collateral_out = match data {
+            [] => None,
+            _ => Some(u64::from_le_bytes(
+                data.try_into()
+                    .map_err(|_| ProgramError::InvalidInstructionData)?,
+            )),
+        };
+        Ok(Self {
+            minimum_collateral_out,
+        })
+    }
+}
+
 pub struct KaminoDepositAccounts<'info> {
     pub kamino_lending_program: &'info AccountView,
     pub owner: &'info AccountView,
@@ -46,6 +160,7 @@ pub struct KaminoDepositAccounts<'info> {
 impl<'info> TryFrom<&'info [AccountView]> for KaminoDepositAccounts<'info> {
     type Error = ProgramError;
 
+    #[inline(always)]
     fn try_from(accounts: &'info [AccountView]) -> Result<Self, Self::Error> {
         if accounts.len() < 19 {
             return Err(ProgramError::NotEnoughAccountKeys);
@@ -57,14 +172,37 @@ impl<'info> TryFrom<&'info [AccountView]> for KaminoDepositAccounts<'info> {
             return Err(ProgramError::NotEnoughAccountKeys);
         };
 
-        let mut total_reserve_accounts = 0;
-        for reserve in remaining_accounts {
-            if reserve.owned_by(&KAMINO_LEND_PROGRAM_ID) && total_reserve_accounts < 13 {
-                total_reserve_accounts += 1;
-            } else {
-                break;
-            }
-        }
+        #[cfg(feature = "strict-validation")]
+        beethoven_core::validation::check_token_account_owner(user_source_liquidity, owner)
+            .inspect_err(|_| {
+                beethoven_core::validation::log_account_error(
+                    ACCOUNT_LABELS,
+                    "user_source_liquidity",
+                    "not owned by `owner`",
+                )
+            })?;
+
+        #[cfg(feature = "strict-validation")]
+        check_lending_market_authority(lending_market, lending_market_authority).inspect_err(
+            |_| {
+                beethoven_core::validation::log_account_error(
+                    ACCOUNT_LABELS,
+                    "lending_market_authority",
+                    "not the lending market's authority PDA",
+                )
+            },
+        )?;
+
+        #[cfg(feature = "strict-validation")]
+        beethoven_core::validation::check_fixed_accounts(
+            ACCOUNT_LABELS,
+            (kamino_lending_program, &KAMINO_LEND_PROGRAM_ID),
+            &[
+                ("collateral_token_program", collateral_token_program),
+                ("liquidity_token_program", liquidity_token_program),
+            ],
+            Some(("instruction_sysvar_account", instruction_sysvar_account)),
+        )?;
 
         Ok(KaminoDepositAccounts {
             owner,
@@ -86,11 +224,71 @@ impl<'info> TryFrom<&'info [AccountView]> for KaminoDepositAccounts<'info> {
             farms_program,
             scope_oracle,
             kamino_lending_program,
-            reserve_accounts: &remaining_accounts[..total_reserve_accounts],
+            reserve_accounts: obligation_reserve_accounts(remaining_accounts),
         })
     }
 }
 
+/// What this adapter supports, as `ProtocolKind::Kamino.capabilities()` reports it
+pub const CAPABILITIES: beethoven_core::Capabilities =
+    beethoven_core::ProtocolKind::Kamino.capabilities();
+
+/// Name of each account after the detector, in order, for error and log output.
+pub const ACCOUNT_LABELS: &[&str] = &[
+    "owner",
+    "obligation",
+    "lending_market",
+    "lending_market_authority",
+    "reserve",
+    "reserve_liquidity_mint",
+    "reserve_liquidity_supply",
+    "reserve_collateral_mint",
+    "reserve_destination_deposit_collateral",
+    "user_source_liquidity",
+    "placeholder_user_destination_collateral",
+    "collateral_token_program",
+    "liquidity_token_program",
+    "instruction_sysvar_account",
+    "obligation_farm_user_state",
+    "reserve_farm_state",
+    "farms_program",
+    "scope_oracle",
+];
+
+impl<'info> KaminoDepositAccounts<'info> {
+    /// The accounts in the order the deposit CPI passes them, without the detector.
+    ///
+    /// `scope_oracle` and `reserve_accounts` only feed the refresh CPIs before it.
+    #[inline(always)]
+    pub fn as_ordered_slice(&self) -> [&'info AccountView; 17] {
+        [
+            self.owner,
+            self.obligation,
+            self.lending_market,
+            self.lending_market_authority,
+            self.reserve,
+            self.reserve_liquidity_mint,
+            self.reserve_liquidity_supply,
+            self.reserve_collateral_mint,
+            self.reserve_destination_deposit_collateral,
+            self.user_source_liquidity,
+            self.placeholder_user_destination_collateral,
+            self.collateral_token_program,
+            self.liquidity_token_program,
+            self.instruction_sysvar_account,
+            self.obligation_farm_user_state,
+            self.reserve_farm_state,
+            self.farms_program,
+        ]
+    }
+
+    /// Iterate over [`Self::as_ordered_slice`].
+    #[inline(always)]
+    pub fn iter(&self) -> impl Iterator<Item = &'info AccountView> {
+        self.as_ordered_slice().into_iter()
+    }
+}
+
 impl<'info> Deposit<'info> for Kamino {
     type Accounts = KaminoDepositAccounts<'info>;
 
@@ -99,114 +297,292 @@ impl<'info> Deposit<'info> for Kamino {
         amount: u64,
         signer_seeds: &[Signer],
     ) -> ProgramResult {
+        #[cfg(feature = "strict-validation")]
+        beethoven_core::validation::check_signer(ctx.owner, signer_seeds).inspect_err(|_| {
+            beethoven_core::validation::log_account_error(
+                ACCOUNT_LABELS,
+                "owner",
+                "missing signature",
+            )
+        })?;
+
         // Refresh reserves
+        refresh_reserves(
+            ctx.kamino_lending_program,
+            ctx.lending_market,
+            ctx.scope_oracle,
+            ctx.reserve,
+            ctx.reserve_accounts,
+            signer_seeds,
+        )?;
+
+        // Refresh obligation
+        refresh_obligation(
+            ctx.obligation,
+            ctx.lending_market,
+            ctx.reserve_accounts,
+            signer_seeds,
+        )?;
+
+        // Deposit CPI
         let accounts = [
+            InstructionAccount::writable_signer(ctx.owner.address()),
+            InstructionAccount::writable(ctx.obligation.address()),
+            InstructionAccount::readonly(ctx.lending_market.address()),
+            InstructionAccount::readonly(ctx.lending_market_authority.address()),
             InstructionAccount::writable(ctx.reserve.address()),
-            InstructionAccount::readonly(ctx.kamino_lending_program.address()),
-            InstructionAccount::readonly(ctx.kamino_lending_program.address()),
-            InstructionAccount::readonly(ctx.kamino_lending_program.address()),
-            InstructionAccount::readonly(ctx.scope_oracle.address()),
+            InstructionAccount::readonly(ctx.reserve_liquidity_mint.address()),
+            InstructionAccount::writable(ctx.reserve_liquidity_supply.address()),
+            InstructionAccount::writable(ctx.reserve_collateral_mint.address()),
+            InstructionAccount::writable(ctx.reserve_destination_deposit_collateral.address()),
+            InstructionAccount::writable(ctx.user_source_liquidity.address()),
+            InstructionAccount::readonly(ctx.placeholder_user_destination_collateral.address()),
+            InstructionAccount::readonly(ctx.collateral_token_program.address()),
+            InstructionAccount::readonly(ctx.liquidity_token_program.address()),
+            InstructionAccount::readonly(ctx.instruction_sysvar_account.address()),
+            InstructionAccount::writable(ctx.obligation_farm_user_state.address()),
+            InstructionAccount::writable(ctx.reserve_farm_state.address()),
+            InstructionAccount::readonly(ctx.farms_program.address()),
         ];
 
-        let account_infos = [
-            ctx.reserve,
-            ctx.kamino_lending_program,
-            ctx.kamino_lending_program,
-            ctx.kamino_lending_program,
-            ctx.scope_oracle,
-        ];
+        let account_infos = ctx.as_ordered_slice();
 
-        let instruction = InstructionView {
+        let deposit_ix = InstructionView {
             program_id: &KAMINO_LEND_PROGRAM_ID,
             accounts: &accounts,
-            data: &REFRESH_RESERVE_DISCRIMINATOR,
+            data: &deposit_instruction_data(amount),
         };
 
-        invoke_signed(&instruction, &account_infos, signer_seeds)?;
-
-        for reserve in ctx.reserve_accounts {
-            let accounts = [
-                InstructionAccount::writable(reserve.address()),
-                InstructionAccount::readonly(ctx.kamino_lending_program.address()),
-                InstructionAccount::readonly(ctx.kamino_lending_program.address()),
-                InstructionAccount::readonly(ctx.kamino_lending_program.address()),
-                InstructionAccount::readonly(ctx.scope_oracle.address()),
-            ];
-
-            let account_infos = [
-                ctx.reserve,
-                ctx.kamino_lending_program,
-                ctx.kamino_lending_program,
-                ctx.kamino_lending_program,
-                ctx.scope_oracle,
-            ];
-
-            let instruction = InstructionView {
-                program_id: &KAMINO_LEND_PROGRAM_ID,
-                accounts: &accounts,
-                data: &REFRESH_RESERVE_DISCRIMINATOR,
-            };
-
-            invoke_signed(&instruction, &account_infos, signer_seeds)?;
-        }
+        invoke_signed(&deposit_ix, &account_infos, signer_seeds)?;
 
-        // Refresh obligation
-        const MAX_REFRESH_OBLIGATION_ACCOUNTS: usize = 15;
-
-        let mut obligation_accounts =
-            MaybeUninit::<[InstructionAccount; MAX_REFRESH_OBLIGATION_ACCOUNTS]>::uninit();
-        let obligation_accounts_ptr = obligation_accounts.as_mut_ptr() as *mut InstructionAccount;
-
-        unsafe {
-            core::ptr::write(
-                obligation_accounts_ptr,
-                InstructionAccount::writable(ctx.obligation.address()),
-            );
-            core::ptr::write(
-                obligation_accounts_ptr.add(1),
-                InstructionAccount::readonly(ctx.lending_market.address()),
-            );
-
-            for (i, reserve) in ctx.reserve_accounts.iter().enumerate() {
-                core::ptr::write(
-                    obligation_accounts_ptr.add(2 + i),
-                    InstructionAccount::readonly(reserve.address()),
-                );
-            }
-        }
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn deposit(ctx: &KaminoDepositAccounts<'info>, amount: u64) -> ProgramResult {
+        Self::deposit_signed(ctx, amount, &[])
+    }
+}
 
-        let obligation_accounts_len = 2 + ctx.reserve_accounts.len();
-        let obligation_accounts_slice = unsafe {
-            core::slice::from_raw_parts(obligation_accounts_ptr, obligation_accounts_len)
+impl Kamino {
+    /// [`Deposit::deposit_signed`], failing with [`BeethovenError::SlippageExceeded`]
+    /// when `data` sets a minimum the collateral minted into
+    /// `reserve_destination_deposit_collateral` falls short of.
+    pub fn deposit_with_data_signed(
+        ctx: &KaminoDepositAccounts<'_>,
+        amount: u64,
+        data: &KaminoDepositData,
+        signer_seeds: &[Signer],
+    ) -> ProgramResult {
+        let Some(minimum_collateral_out) = data.minimum_collateral_out else {
+            return Self::deposit_signed(ctx, amount, signer_seeds);
         };
 
-        let mut obligation_account_infos = [ctx.obligation; MAX_REFRESH_OBLIGATION_ACCOUNTS];
-        obligation_account_infos[1] = ctx.lending_market;
+        let before = token_account_amount(ctx.reserve_destination_deposit_collateral)?;
+        Self::deposit_signed(ctx, amount, signer_seeds)?;
+        let minted = token_account_amount(ctx.reserve_destination_deposit_collateral)?
+            .saturating_sub(before);
 
-        for (i, reserve) in ctx.reserve_accounts.iter().enumerate() {
-            obligation_account_infos[2 + i] = reserve;
+        if minted < minimum_collateral_out {
+            return Err(BeethovenError::SlippageExceeded.into());
         }
+        Ok(())
+    }
+}
 
-        let instruction = InstructionView {
-            program_id: &KAMINO_LEND_PROGRAM_ID,
-            accounts: obligation_accounts_slice,
-            data: &REFRESH_OBLIGATION_DISCRIMINATOR,
+pub struct KaminoWithdrawAccounts<'info> {
+    pub kamino_lending_program: &'info AccountView,
+    pub owner: &'info AccountView,
+    pub obligation: &'info AccountView,
+    pub lending_market: &'info AccountView,
+    pub lending_market_authority: &'info AccountView,
+    pub withdraw_reserve: &'info AccountView,
+    pub reserve_liquidity_mint: &'info AccountView,
+    pub reserve_source_collateral: &'info AccountView,
+    pub reserve_collateral_mint: &'info AccountView,
+    pub reserve_liquidity_supply: &'info AccountView,
+    pub user_destination_liquidity: &'info AccountView,
+    pub placeholder_user_destination_collateral: &'info AccountView,
+    pub collateral_token_program: &'info AccountView,
+    pub liquidity_token_program: &'info AccountView,
+    pub instruction_sysvar_account: &'info AccountView,
+    pub obligation_farm_user_state: &'info AccountView,
+    pub reserve_farm_state: &'info AccountView,
+    pub farms_program: &'info AccountView,
+    pub scope_oracle: &'info AccountView,
+    pub reserve_accounts: &'info [AccountView],
+}
+
+impl<'info> TryFrom<&'info [AccountView]> for KaminoWithdrawAccounts<'info> {
+    type Error = ProgramError;
+
+    #[inline(always)]
+    fn try_from(accounts: &'info [AccountView]) -> Result<Self, Self::Error> {
+        let [kamino_lending_program, owner, obligation, lending_market, lending_market_authority, withdraw_reserve, reserve_liquidity_mint, reserve_source_collateral, reserve_collateral_mint, reserve_liquidity_supply, user_destination_liquidity, placeholder_user_destination_collateral, collateral_token_program, liquidity_token_program, instruction_sysvar_account, obligation_farm_user_state, reserve_farm_state, farms_program, scope_oracle, remaining_accounts @ ..] =
+            accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
         };
 
-        invoke_signed(&instruction, &obligation_account_infos, signer_seeds)?;
+        #[cfg(feature = "strict-validation")]
+        beethoven_core::validation::check_token_account_owner(user_destination_liquidity, owner)
+            .inspect_err(|_| {
+                beethoven_core::validation::log_account_error(
+                    WITHDRAW_ACCOUNT_LABELS,
+                    "user_destination_liquidity",
+                    "not owned by `owner`",
+                )
+            })?;
+
+        #[cfg(feature = "strict-validation")]
+        check_lending_market_authority(lending_market, lending_market_authority).inspect_err(
+            |_| {
+                beethoven_core::validation::log_account_error(
+                    WITHDRAW_ACCOUNT_LABELS,
+                    "lending_market_authority",
+                    "not the lending market's authority PDA",
+                )
+            },
+        )?;
+
+        #[cfg(feature = "strict-validation")]
+        beethoven_core::validation::check_fixed_accounts(
+            WITHDRAW_ACCOUNT_LABELS,
+            (kamino_lending_program, &KAMINO_LEND_PROGRAM_ID),
+            &[
+                ("collateral_token_program", collateral_token_program),
+                ("liquidity_token_program", liquidity_token_program),
+            ],
+            Some(("instruction_sysvar_account", instruction_sysvar_account)),
+        )?;
+
+        Ok(KaminoWithdrawAccounts {
+            kamino_lending_program,
+            owner,
+            obligation,
+            lending_market,
+            lending_market_authority,
+            withdraw_reserve,
+            reserve_liquidity_mint,
+            reserve_source_collateral,
+            reserve_collateral_mint,
+            reserve_liquidity_supply,
+            user_destination_liquidity,
+            placeholder_user_destination_collateral,
+            collateral_token_program,
+            liquidity_token_program,
+            instruction_sysvar_account,
+            obligation_farm_user_state,
+            reserve_farm_state,
+            farms_program,
+            scope_oracle,
+            reserve_accounts: obligation_reserve_accounts(remaining_accounts),
+        })
+    }
+}
+
+/// Name of each withdraw account after the detector, in order, for error and log output.
+pub const WITHDRAW_ACCOUNT_LABELS: &[&str] = &[
+    "owner",
+    "obligation",
+    "lending_market",
+    "lending_market_authority",
+    "withdraw_reserve",
+    "reserve_liquidity_mint",
+    "reserve_source_collateral",
+    "reserve_collateral_mint",
+    "reserve_liquidity_supply",
+    "user_destination_liquidity",
+    "placeholder_user_destination_collateral",
+    "collateral_token_program",
+    "liquidity_token_program",
+    "instruction_sysvar_account",
+    "obligation_farm_user_state",
+    "reserve_farm_state",
+    "farms_program",
+    "scope_oracle",
+];
+
+impl<'info> KaminoWithdrawAccounts<'info> {
+    /// The accounts in the order the withdraw CPI passes them, without the detector.
+    ///
+    /// `scope_oracle` and `reserve_accounts` only feed the refresh CPIs before it.
+    #[inline(always)]
+    pub fn as_ordered_slice(&self) -> [&'info AccountView; 17] {
+        [
+            self.owner,
+            self.obligation,
+            self.lending_market,
+            self.lending_market_authority,
+            self.withdraw_reserve,
+            self.reserve_liquidity_mint,
+            self.reserve_source_collateral,
+            self.reserve_collateral_mint,
+            self.reserve_liquidity_supply,
+            self.user_destination_liquidity,
+            self.placeholder_user_destination_collateral,
+            self.collateral_token_program,
+            self.liquidity_token_program,
+            self.instruction_sysvar_account,
+            self.obligation_farm_user_state,
+            self.reserve_farm_state,
+            self.farms_program,
+        ]
+    }
+
+    /// Iterate over [`Self::as_ordered_slice`].
+    #[inline(always)]
+    pub fn iter(&self) -> impl Iterator<Item = &'info AccountView> {
+        self.as_ordered_slice().into_iter()
+    }
+}
+
+/// Withdraws `amount` of collateral (cTokens, not liquidity) from the obligation and
+/// redeems it for the reserve's liquidity; `u64::MAX` withdraws the whole deposit.
+impl<'info> Withdraw<'info> for Kamino {
+    type Accounts = KaminoWithdrawAccounts<'info>;
+
+    fn withdraw_signed(
+        ctx: &KaminoWithdrawAccounts<'info>,
+        amount: u64,
+        signer_seeds: &[Signer],
+    ) -> ProgramResult {
+        #[cfg(feature = "strict-validation")]
+        beethoven_core::validation::check_signer(ctx.owner, signer_seeds).inspect_err(|_| {
+            beethoven_core::validation::log_account_error(
+                WITHDRAW_ACCOUNT_LABELS,
+                "owner",
+                "missing signature",
+            )
+        })?;
+
+        refresh_reserves(
+            ctx.kamino_lending_program,
+            ctx.lending_market,
+            ctx.scope_oracle,
+            ctx.withdraw_reserve,
+            ctx.reserve_accounts,
+            signer_seeds,
+        )?;
+
+        refresh_obligation(
+            ctx.obligation,
+            ctx.lending_market,
+            ctx.reserve_accounts,
+            signer_seeds,
+        )?;
 
-        // Deposit CPI
         let accounts = [
             InstructionAccount::writable_signer(ctx.owner.address()),
             InstructionAccount::writable(ctx.obligation.address()),
             InstructionAccount::readonly(ctx.lending_market.address()),
             InstructionAccount::readonly(ctx.lending_market_authority.address()),
-            InstructionAccount::writable(ctx.reserve.address()),
+            InstructionAccount::writable(ctx.withdraw_reserve.address()),
             InstructionAccount::readonly(ctx.reserve_liquidity_mint.address()),
-            InstructionAccount::writable(ctx.reserve_liquidity_supply.address()),
+            InstructionAccount::writable(ctx.reserve_source_collateral.address()),
             InstructionAccount::writable(ctx.reserve_collateral_mint.address()),
-            InstructionAccount::writable(ctx.reserve_destination_deposit_collateral.address()),
-            InstructionAccount::writable(ctx.user_source_liquidity.address()),
+            InstructionAccount::writable(ctx.reserve_liquidity_supply.address()),
+            InstructionAccount::writable(ctx.user_destination_liquidity.address()),
             InstructionAccount::readonly(ctx.placeholder_user_destination_collateral.address()),
             InstructionAccount::readonly(ctx.collateral_token_program.address()),
             InstructionAccount::readonly(ctx.liquidity_token_program.address()),
@@ -216,51 +592,743 @@ impl<'info> Deposit<'info> for Kamino {
             InstructionAccount::readonly(ctx.farms_program.address()),
         ];
 
-        let account_infos = [
-            ctx.owner,
+        let account_infos = ctx.as_ordered_slice();
+
+        let withdraw_ix = InstructionView {
+            program_id: &KAMINO_LEND_PROGRAM_ID,
+            accounts: &accounts,
+            data: &withdraw_instruction_data(amount),
+        };
+
+        invoke_signed(&withdraw_ix, &account_infos, signer_seeds)
+    }
+
+    #[inline(always)]
+    fn withdraw(ctx: &KaminoWithdrawAccounts<'info>, amount: u64) -> ProgramResult {
+        Self::withdraw_signed(ctx, amount, &[])
+    }
+}
+
+pub struct KaminoBorrowAccounts<'info> {
+    pub kamino_lending_program: &'info AccountView,
+    pub owner: &'info AccountView,
+    pub obligation: &'info AccountView,
+    pub lending_market: &'info AccountView,
+    pub lending_market_authority: &'info AccountView,
+    pub borrow_reserve: &'info AccountView,
+    pub borrow_reserve_liquidity_mint: &'info AccountView,
+    pub reserve_source_liquidity: &'info AccountView,
+    pub borrow_reserve_liquidity_fee_receiver: &'info AccountView,
+    pub user_destination_liquidity: &'info AccountView,
+    pub referrer_token_state: &'info AccountView,
+    pub token_program: &'info AccountView,
+    pub instruction_sysvar_account: &'info AccountView,
+    pub obligation_farm_user_state: &'info AccountView,
+    pub reserve_farm_state: &'info AccountView,
+    pub farms_program: &'info AccountView,
+    pub scope_oracle: &'info AccountView,
+    pub reserve_accounts: &'info [AccountView],
+}
+
+impl<'info> TryFrom<&'info [AccountView]> for KaminoBorrowAccounts<'info> {
+    type Error = ProgramError;
+
+    #[inline(always)]
+    fn try_from(accounts: &'info [AccountView]) -> Result<Self, Self::Error> {
+        let [kamino_lending_program, owner, obligation, lending_market, lending_market_authority, borrow_reserve, borrow_reserve_liquidity_mint, reserve_source_liquidity, borrow_reserve_liquidity_fee_receiver, user_destination_liquidity, referrer_token_state, token_program, instruction_sysvar_account, obligation_farm_user_state, reserve_farm_state, farms_program, scope_oracle, remaining_accounts @ ..] =
+            accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        #[cfg(feature = "strict-validation")]
+        beethoven_core::validation::check_token_account_owner(user_destination_liquidity, owner)
+            .inspect_err(|_| {
+                beethoven_core::validation::log_account_error(
+                    BORROW_ACCOUNT_LABELS,
+                    "user_destination_liquidity",
+                    "not owned by `owner`",
+                )
+            })?;
+
+        #[cfg(feature = "strict-validation")]
+        check_lending_market_authority(lending_market, lending_market_authority).inspect_err(
+            |_| {
+                beethoven_core::validation::log_account_error(
+                    BORROW_ACCOUNT_LABELS,
+                    "lending_market_authority",
+                    "not the lending market's authority PDA",
+                )
+            },
+        )?;
+
+        #[cfg(feature = "strict-validation")]
+        beethoven_core::validation::check_fixed_accounts(
+            BORROW_ACCOUNT_LABELS,
+            (kamino_lending_program, &KAMINO_LEND_PROGRAM_ID),
+            &[("token_program", token_program)],
+            Some(("instruction_sysvar_account", instruction_sysvar_account)),
+        )?;
+
+        Ok(KaminoBorrowAccounts {
+            kamino_lending_program,
+            owner,
+            obligation,
+            lending_market,
+            lending_market_authority,
+            borrow_reserve,
+            borrow_reserve_liquidity_mint,
+            reserve_source_liquidity,
+            borrow_reserve_liquidity_fee_receiver,
+            user_destination_liquidity,
+            referrer_token_state,
+            token_program,
+            instruction_sysvar_account,
+            obligation_farm_user_state,
+            reserve_farm_state,
+            farms_program,
+            scope_oracle,
+            reserve_accounts: obligation_reserve_accounts(remaining_accounts),
+        })
+    }
+}
+
+/// Name of each borrow account after the detector, in order, for error and log output.
+pub const BORROW_ACCOUNT_LABELS: &[&str] = &[
+    "owner",
+    "obligation",
+    "lending_market",
+    "lending_market_authority",
+    "borrow_reserve",
+    "borrow_reserve_liquidity_mint",
+    "reserve_source_liquidity",
+    "borrow_reserve_liquidity_fee_receiver",
+    "user_destination_liquidity",
+    "referrer_token_state",
+    "token_program",
+    "instruction_sysvar_account",
+    "obligation_farm_user_state",
+    "reserve_farm_state",
+    "farms_program",
+    "scope_oracle",
+];
+
+impl<'info> KaminoBorrowAccounts<'info> {
+    /// The accounts in the order the borrow CPI passes them, without the detector.
+    ///
+    /// `scope_oracle` and `reserve_accounts` only feed the refresh CPIs before it.
+    #[inline(always)]
+    pub fn as_ordered_slice(&self) -> [&'info AccountView; 15] {
+        [
+            self.owner,
+            self.obligation,
+            self.lending_market,
+            self.lending_market_authority,
+            self.borrow_reserve,
+            self.borrow_reserve_liquidity_mint,
+            self.reserve_source_liquidity,
+            self.borrow_reserve_liquidity_fee_receiver,
+            self.user_destination_liquidity,
+            self.referrer_token_state,
+            self.token_program,
+            self.instruction_sysvar_account,
+            self.obligation_farm_user_state,
+            self.reserve_farm_state,
+            self.farms_program,
+        ]
+    }
+
+    /// Iterate over [`Self::as_ordered_slice`].
+    #[inline(always)]
+    pub fn iter(&self) -> impl Iterator<Item = &'info AccountView> {
+        self.as_ordered_slice().into_iter()
+    }
+}
+
+/// Borrows `amount` of the reserve's liquidity against the obligation's collateral.
+///
+/// `reserve_accounts` must list every reserve the obligation deposits into or borrows
+/// from, so the refreshed obligation values all of its collateral and debt.
+impl<'info> Borrow<'info> for Kamino {
+    type Accounts = KaminoBorrowAccounts<'info>;
+
+    fn borrow_signed(
+        ctx: &KaminoBorrowAccounts<'info>,
+        amount: u64,
+        signer_seeds: &[Signer],
+    ) -> ProgramResult {
+        #[cfg(feature = "strict-validation")]
+        beethoven_core::validation::check_signer(ctx.owner, signer_seeds).inspect_err(|_| {
+            beethoven_core::validation::log_account_error(
+                BORROW_ACCOUNT_LABELS,
+                "owner",
+                "missing signature",
+            )
+        })?;
+
+        refresh_reserves(
+            ctx.kamino_lending_program,
+            ctx.lending_market,
+            ctx.scope_oracle,
+            ctx.borrow_reserve,
+            ctx.reserve_accounts,
+            signer_seeds,
+        )?;
+
+        refresh_obligation(
             ctx.obligation,
             ctx.lending_market,
-            ctx.lending_market_authority,
-            ctx.reserve,
-            ctx.reserve_liquidity_mint,
-            ctx.reserve_liquidity_supply,
-            ctx.reserve_collateral_mint,
-            ctx.reserve_destination_deposit_collateral,
-            ctx.user_source_liquidity,
-            ctx.placeholder_user_destination_collateral,
-            ctx.collateral_token_program,
-            ctx.liquidity_token_program,
-            ctx.instruction_sysvar_account,
-            ctx.obligation_farm_user_state,
-            ctx.reserve_farm_state,
-            ctx.farms_program,
+            ctx.reserve_accounts,
+            signer_seeds,
+        )?;
+
+        let accounts = [
+            InstructionAccount::readonly_signer(ctx.owner.address()),
+            InstructionAccount::writable(ctx.obligation.address()),
+            InstructionAccount::readonly(ctx.lending_market.address()),
+            InstructionAccount::readonly(ctx.lending_market_authority.address()),
+            InstructionAccount::writable(ctx.borrow_reserve.address()),
+            InstructionAccount::readonly(ctx.borrow_reserve_liquidity_mint.address()),
+            InstructionAccount::writable(ctx.reserve_source_liquidity.address()),
+            InstructionAccount::writable(ctx.borrow_reserve_liquidity_fee_receiver.address()),
+            InstructionAccount::writable(ctx.user_destination_liquidity.address()),
+            InstructionAccount::writable(ctx.referrer_token_state.address()),
+            InstructionAccount::readonly(ctx.token_program.address()),
+            InstructionAccount::readonly(ctx.instruction_sysvar_account.address()),
+            InstructionAccount::writable(ctx.obligation_farm_user_state.address()),
+            InstructionAccount::writable(ctx.reserve_farm_state.address()),
+            InstructionAccount::readonly(ctx.farms_program.address()),
         ];
 
-        let mut instruction_data = MaybeUninit::<[u8; 16]>::uninit();
-        unsafe {
-            let ptr = instruction_data.as_mut_ptr() as *mut u8;
-            core::ptr::copy_nonoverlapping(
-                DEPOSIT_RESERVE_LIQUIDITY_AND_OBLIGATION_COLLATERAL_V2_DISCRIMINATOR.as_ptr(),
-                ptr,
-                8,
-            );
-            core::ptr::copy_nonoverlapping(amount.to_le_bytes().as_ptr(), ptr.add(8), 8);
-        }
+        let account_infos = ctx.as_ordered_slice();
 
-        let deposit_ix = InstructionView {
+        let borrow_ix = InstructionView {
             program_id: &KAMINO_LEND_PROGRAM_ID,
             accounts: &accounts,
-            data: unsafe {
-                core::slice::from_raw_parts(instruction_data.as_ptr() as *const u8, 16)
+            data: &borrow_instruction_data(amount),
+        };
+
+        invoke_signed(&borrow_ix, &account_infos, signer_seeds)
+    }
+
+    #[inline(always)]
+    fn borrow(ctx: &KaminoBorrowAccounts<'info>, amount: u64) -> ProgramResult {
+        Self::borrow_signed(ctx, amount, &[])
+    }
+}
+
+pub struct KaminoRepayAccounts<'info> {
+    pub kamino_lending_program: &'info AccountView,
+    pub owner: &'info AccountView,
+    pub obligation: &'info AccountView,
+    pub lending_market: &'info AccountView,
+    pub repay_reserve: &'info AccountView,
+    pub reserve_liquidity_mint: &'info AccountView,
+    pub reserve_destination_liquidity: &'info AccountView,
+    pub user_source_liquidity: &'info AccountView,
+    pub token_program: &'info AccountView,
+    pub instruction_sysvar_account: &'info AccountView,
+    pub obligation_farm_user_state: &'info AccountView,
+    pub reserve_farm_state: &'info AccountView,
+    pub lending_market_authority: &'info AccountView,
+    pub farms_program: &'info AccountView,
+    pub scope_oracle: &'info AccountView,
+    pub reserve_accounts: &'info [AccountView],
+}
+
+impl<'info> TryFrom<&'info [AccountView]> for KaminoRepayAccounts<'info> {
+    type Error = ProgramError;
+
+    #[inline(always)]
+    fn try_from(accounts: &'info [AccountView]) -> Result<Self, Self::Error> {
+        let [kamino_lending_program, owner, obligation, lending_market, repay_reserve, reserve_liquidity_mint, reserve_destination_liquidity, user_source_liquidity, token_program, instruction_sysvar_account, obligation_farm_user_state, reserve_farm_state, lending_market_authority, farms_program, scope_oracle, remaining_accounts @ ..] =
+            accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        #[cfg(feature = "strict-validation")]
+        beethoven_core::validation::check_token_account_owner(user_source_liquidity, owner)
+            .inspect_err(|_| {
+                beethoven_core::validation::log_account_error(
+                    REPAY_ACCOUNT_LABELS,
+                    "user_source_liquidity",
+                    "not owned by `owner`",
+                )
+            })?;
+
+        #[cfg(feature = "strict-validation")]
+        check_lending_market_authority(lending_market, lending_market_authority).inspect_err(
+            |_| {
+                beethoven_core::validation::log_account_error(
+                    REPAY_ACCOUNT_LABELS,
+                    "lending_market_authority",
+                    "not the lending market's authority PDA",
+                )
             },
+        )?;
+
+        #[cfg(feature = "strict-validation")]
+        beethoven_core::validation::check_fixed_accounts(
+            REPAY_ACCOUNT_LABELS,
+            (kamino_lending_program, &KAMINO_LEND_PROGRAM_ID),
+            &[("token_program", token_program)],
+            Some(("instruction_sysvar_account", instruction_sysvar_account)),
+        )?;
+
+        Ok(KaminoRepayAccounts {
+            kamino_lending_program,
+            owner,
+            obligation,
+            lending_market,
+            repay_reserve,
+            reserve_liquidity_mint,
+            reserve_destination_liquidity,
+            user_source_liquidity,
+            token_program,
+            instruction_sysvar_account,
+            obligation_farm_user_state,
+            reserve_farm_state,
+            lending_market_authority,
+            farms_program,
+            scope_oracle,
+            reserve_accounts: obligation_reserve_accounts(remaining_accounts),
+        })
+    }
+}
+
+/// Name of each repay account after the detector, in order, for error and log output.
+pub const REPAY_ACCOUNT_LABELS: &[&str] = &[
+    "owner",
+    "obligation",
+    "lending_market",
+    "repay_reserve",
+    "reserve_liquidity_mint",
+    "reserve_destination_liquidity",
+    "user_source_liquidity",
+    "token_program",
+    "instruction_sysvar_account",
+    "obligation_farm_user_state",
+    "reserve_farm_state",
+    "lending_market_authority",
+    "farms_program",
+    "scope_oracle",
+];
+
+impl<'info> KaminoRepayAccounts<'info> {
+    /// The accounts in the order the repay CPI passes them, without the detector.
+    ///
+    /// `scope_oracle` and `reserve_accounts` only feed the refresh CPIs before it.
+    #[inline(always)]
+    pub fn as_ordered_slice(&self) -> [&'info AccountView; 13] {
+        [
+            self.owner,
+            self.obligation,
+            self.lending_market,
+            self.repay_reserve,
+            self.reserve_liquidity_mint,
+            self.reserve_destination_liquidity,
+            self.user_source_liquidity,
+            self.token_program,
+            self.instruction_sysvar_account,
+            self.obligation_farm_user_state,
+            self.reserve_farm_state,
+            self.lending_market_authority,
+            self.farms_program,
+        ]
+    }
+
+    /// Iterate over [`Self::as_ordered_slice`].
+    #[inline(always)]
+    pub fn iter(&self) -> impl Iterator<Item = &'info AccountView> {
+        self.as_ordered_slice().into_iter()
+    }
+}
+
+/// Repays `amount` of the reserve's liquidity; `u64::MAX` repays the whole debt.
+impl<'info> Repay<'info> for Kamino {
+    type Accounts = KaminoRepayAccounts<'info>;
+
+    fn repay_signed(
+        ctx: &KaminoRepayAccounts<'info>,
+        amount: u64,
+        signer_seeds: &[Signer],
+    ) -> ProgramResult {
+        #[cfg(feature = "strict-validation")]
+        beethoven_core::validation::check_signer(ctx.owner, signer_seeds).inspect_err(|_| {
+            beethoven_core::validation::log_account_error(
+                REPAY_ACCOUNT_LABELS,
+                "owner",
+                "missing signature",
+            )
+        })?;
+
+        refresh_reserves(
+            ctx.kamino_lending_program,
+            ctx.lending_market,
+            ctx.scope_oracle,
+            ctx.repay_reserve,
+            ctx.reserve_accounts,
+            signer_seeds,
+        )?;
+
+        // Kamino rejects a repay against an obligation not refreshed in this slot
+        refresh_obligation(
+            ctx.obligation,
+            ctx.lending_market,
+            ctx.reserve_accounts,
+            signer_seeds,
+        )?;
+
+        let accounts = [
+            InstructionAccount::readonly_signer(ctx.owner.address()),
+            InstructionAccount::writable(ctx.obligation.address()),
+            InstructionAccount::readonly(ctx.lending_market.address()),
+            InstructionAccount::writable(ctx.repay_reserve.address()),
+            InstructionAccount::readonly(ctx.reserve_liquidity_mint.address()),
+            InstructionAccount::writable(ctx.reserve_destination_liquidity.address()),
+            InstructionAccount::writable(ctx.user_source_liquidity.address()),
+            InstructionAccount::readonly(ctx.token_program.address()),
+            InstructionAccount::readonly(ctx.instruction_sysvar_account.address()),
+            InstructionAccount::writable(ctx.obligation_farm_user_state.address()),
+            InstructionAccount::writable(ctx.reserve_farm_state.address()),
+            InstructionAccount::readonly(ctx.lending_market_authority.address()),
+            InstructionAccount::readonly(ctx.farms_program.address()),
+        ];
+
+        let account_infos = ctx.as_ordered_slice();
+
+        let repay_ix = InstructionView {
+            program_id: &KAMINO_LEND_PROGRAM_ID,
+            accounts: &accounts,
+            data: &repay_instruction_data(amount),
         };
 
-        invoke_signed(&deposit_ix, &account_infos, signer_seeds)?;
+        invoke_signed(&repay_ix, &account_infos, signer_seeds)
+    }
 
-        Ok(())
+    #[inline(always)]
+    fn repay(ctx: &KaminoRepayAccounts<'info>, amount: u64) -> ProgramResult {
+        Self::repay_signed(ctx, amount, &[])
     }
+}
 
-    fn deposit(ctx: &KaminoDepositAccounts<'info>, amount: u64) -> ProgramResult {
-        Self::deposit_signed(ctx, amount, &[])
+/// The leading Kamino-owned accounts of `remaining_accounts`, up to the most reserves an
+/// obligation can reference.
+#[inline(always)]
+fn obligation_reserve_accounts(remaining_accounts: &[AccountView]) -> &[AccountView] {
+    let mut total_reserve_accounts = 0;
+    for reserve in remaining_accounts {
+        if reserve.owned_by(&KAMINO_LEND_PROGRAM_ID)
+            && total_reserve_accounts < MAX_OBLIGATION_RESERVES
+        {
+            total_reserve_accounts += 1;
+        } else {
+            break;
+        }
+    }
+    &remaining_accounts[..total_reserve_accounts]
+}
+
+/// Refresh `obligation` against its reserves.
+///
+/// Kept out of line so its meta and account arrays only occupy the stack while the CPI
+/// is being assembled, rather than sitting in `deposit_signed`'s frame next to the
+/// deposit CPI's. Metas and infos are filled in one pass over `reserve_accounts`, and
+/// only the filled prefix of each is handed to the CPI.
+#[inline(never)]
+fn refresh_obligation<'a>(
+    obligation: &'a AccountView,
+    lending_market: &'a AccountView,
+    reserve_accounts: &'a [AccountView],
+    signer_seeds: &[Signer],
+) -> ProgramResult {
+    const MAX_REFRESH_OBLIGATION_ACCOUNTS: usize = 2 + MAX_OBLIGATION_RESERVES;
+
+    // `reserve_accounts` is capped by `TryFrom`, but the field is public. Past the cap the
+    // zip below drops the extra reserves, so `release-fast` only re-checks in debug builds.
+    #[cfg(not(feature = "release-fast"))]
+    if reserve_accounts.len() > MAX_OBLIGATION_RESERVES {
+        return Err(ProgramError::InvalidArgument);
+    }
+    #[cfg(feature = "release-fast")]
+    debug_assert!(reserve_accounts.len() <= MAX_OBLIGATION_RESERVES);
+
+    let mut obligation_accounts: [InstructionAccount; MAX_REFRESH_OBLIGATION_ACCOUNTS] =
+        core::array::from_fn(|_| InstructionAccount::readonly(lending_market.address()));
+    obligation_accounts[0] = InstructionAccount::writable(obligation.address());
+
+    let mut obligation_account_infos = [lending_market; MAX_REFRESH_OBLIGATION_ACCOUNTS];
+    obligation_account_infos[0] = obligation;
+
+    let mut len = 2;
+    for ((meta, info), reserve) in obligation_accounts[2..]
+        .iter_mut()
+        .zip(&mut obligation_account_infos[2..])
+        .zip(reserve_accounts)
+    {
+        *meta = InstructionAccount::readonly(reserve.address());
+        *info = reserve;
+        len += 1;
     }
+
+    let instruction = InstructionView {
+        program_id: &KAMINO_LEND_PROGRAM_ID,
+        accounts: &obligation_accounts[..len],
+        data: &REFRESH_OBLIGATION_DISCRIMINATOR,
+    };
+
+    invoke_signed_with_bounds::<MAX_REFRESH_OBLIGATION_ACCOUNTS>(
+        &instruction,
+        &obligation_account_infos[..len],
+        signer_seeds,
+    )
+}
+
+/// Refresh `reserve` and the obligation's reserves with `refresh_reserves_batch` CPIs
+/// instead of one `refresh_reserve` CPI per reserve.
+///
+/// Obligation reserves that are the target reserve itself are only refreshed once, and
+/// reserves already refreshed in the current slot (e.g. earlier in the same transaction)
+/// are skipped; no CPI is issued when every reserve is fresh. Stale reserves are
+/// refreshed [`REFRESH_BATCH_RESERVES`] at a time.
+#[inline(never)]
+fn refresh_reserves<'a>(
+    kamino_lending_program: &'a AccountView,
+    lending_market: &'a AccountView,
+    scope_oracle: &'a AccountView,
+    reserve: &'a AccountView,
+    reserve_accounts: &'a [AccountView],
+    signer_seeds: &[Signer],
+) -> ProgramResult {
+    // If the clock is unavailable every reserve is treated as stale.
+    let current_slot = current_slot();
+
+    let mut stale_reserves = core::iter::once(reserve)
+        .chain(reserve_accounts.iter().filter(|obligation_reserve| {
+            !address_eq(obligation_reserve.address(), reserve.address())
+        }))
+        .take(MAX_REFRESH_RESERVES)
+        .filter(|refreshed_reserve| {
+            !current_slot.is_some_and(|slot| is_reserve_fresh(refreshed_reserve, slot))
+        })
+        .peekable();
+
+    while stale_reserves.peek().is_some() {
+        refresh_reserve_batch(
+            kamino_lending_program,
+            lending_market,
+            scope_oracle,
+            &mut stale_reserves,
+            signer_seeds,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Refresh up to [`REFRESH_BATCH_RESERVES`] of `reserves` with one
+/// `refresh_reserves_batch` CPI, leaving the rest in the iterator.
+///
+/// Each reserve contributes the same six accounts `refresh_reserve` takes, with the
+/// program id standing in for the unused Pyth and Switchboard oracles. Metas are built
+/// from the account infos, so both hold the same accounts in the same order, and only
+/// the filled prefix of each is handed to the CPI.
+#[inline(never)]
+fn refresh_reserve_batch<'a>(
+    kamino_lending_program: &'a AccountView,
+    lending_market: &'a AccountView,
+    scope_oracle: &'a AccountView,
+    reserves: impl Iterator<Item = &'a AccountView>,
+    signer_seeds: &[Signer],
+) -> ProgramResult {
+    const MAX_REFRESH_BATCH_ACCOUNTS: usize = REFRESH_RESERVE_ACCOUNTS * REFRESH_BATCH_RESERVES;
+
+    let mut accounts: [InstructionAccount; MAX_REFRESH_BATCH_ACCOUNTS] =
+        core::array::from_fn(|_| InstructionAccount::readonly(lending_market.address()));
+    let mut account_infos = [lending_market; MAX_REFRESH_BATCH_ACCOUNTS];
+
+    let mut len = 0;
+    for ((metas, infos), refreshed_reserve) in accounts
+        .chunks_exact_mut(REFRESH_RESERVE_ACCOUNTS)
+        .zip(account_infos.chunks_exact_mut(REFRESH_RESERVE_ACCOUNTS))
+        .zip(reserves)
+    {
+        infos.copy_from_slice(&[
+            refreshed_reserve,
+            lending_market,
+            kamino_lending_program,
+            kamino_lending_program,
+            kamino_lending_program,
+            scope_oracle,
+        ]);
+        for (meta, &info) in metas.iter_mut().zip(infos.iter()) {
+            *meta = InstructionAccount::readonly(info.address());
+        }
+        metas[0] = InstructionAccount::writable(refreshed_reserve.address());
+        len += REFRESH_RESERVE_ACCOUNTS;
+    }
+
+    // skip_price_updates = false
+    let mut instruction_data = [0u8; 9];
+    instruction_data[..8].copy_from_slice(&REFRESH_RESERVES_BATCH_DISCRIMINATOR);
+
+    let instruction = InstructionView {
+        program_id: &KAMINO_LEND_PROGRAM_ID,
+        accounts: &accounts[..len],
+        data: &instruction_data,
+    };
+
+    invoke_signed_with_bounds::<MAX_REFRESH_BATCH_ACCOUNTS>(
+        &instruction,
+        &account_infos[..len],
+        signer_seeds,
+    )
+}
+
+/// Whether `reserve` was refreshed in `current_slot` and has not been marked stale since.
+fn is_reserve_fresh(reserve: &AccountView, current_slot: u64) -> bool {
+    let Ok(data) = reserve.try_borrow() else {
+        return false;
+    };
+
+    match (
+        data.get(RESERVE_LAST_UPDATE_SLOT_OFFSET..RESERVE_LAST_UPDATE_SLOT_OFFSET + 8),
+        data.get(RESERVE_LAST_UPDATE_STALE_OFFSET),
+    ) {
+        (Some(slot), Some(&stale)) => stale == 0 && slot == current_slot.to_le_bytes(),
+        _ => false,
+    }
+}
+
+/// Derive the authority PDA of `lending_market`, with the bump the market stores.
+///
+/// The authority is pure derived data, so deriving it on-chain instead of trusting the
+/// passed account catches it misordered or taken from another market. Off-chain, where
+/// the PDA syscall is unavailable, this returns `InvalidArgument`; derive it with
+/// `beethoven_client::kamino::lending_market_authority_address` there.
+pub fn lending_market_authority_address(
+    lending_market: &AccountView,
+) -> Result<Address, ProgramError> {
+    let bump = *lending_market
+        .try_borrow()?
+        .get(LENDING_MARKET_BUMP_SEED_OFFSET)
+        .ok_or(ProgramError::InvalidAccountData)?;
+    create_program_address(&[
+        LENDING_MARKET_AUTHORITY_SEED,
+        lending_market.address().as_ref(),
+        &[bump],
+    ])
+}
+
+/// Ensure `lending_market_authority` is the authority PDA of `lending_market`.
+#[inline(always)]
+pub fn check_lending_market_authority(
+    lending_market: &AccountView,
+    lending_market_authority: &AccountView,
+) -> ProgramResult {
+    if !address_eq(
+        &lending_market_authority_address(lending_market)?,
+        lending_market_authority.address(),
+    ) {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    Ok(())
+}
+
+/// PDA of `seeds` (bump included) under the Kamino Lend program.
+#[cfg(any(target_os = "solana", target_arch = "bpf"))]
+fn create_program_address(seeds: &[&[u8]]) -> Result<Address, ProgramError> {
+    let mut address = MaybeUninit::<[u8; 32]>::uninit();
+    let result = unsafe {
+        solana_define_syscall::definitions::sol_create_program_address(
+            seeds.as_ptr() as *const u8,
+            seeds.len() as u64,
+            KAMINO_LEND_PROGRAM_ID.as_ref().as_ptr(),
+            address.as_mut_ptr() as *mut u8,
+        )
+    };
+    match result {
+        0 => Ok(Address::new_from_array(unsafe { address.assume_init() })),
+        _ => Err(ProgramError::InvalidSeeds),
+    }
+}
+
+#[cfg(not(any(target_os = "solana", target_arch = "bpf")))]
+fn create_program_address(_seeds: &[&[u8]]) -> Result<Address, ProgramError> {
+    Err(ProgramError::InvalidArgument)
+}
+
+/// Current slot from the Clock sysvar.
+#[cfg(any(target_os = "solana", target_arch = "bpf"))]
+fn current_slot() -> Option<u64> {
+    // Clock begins with `slot: u64`, followed by four more 8-byte fields
+    let mut clock = MaybeUninit::<[u64; 5]>::uninit();
+    let result = unsafe {
+        solana_define_syscall::definitions::sol_get_clock_sysvar(clock.as_mut_ptr() as *mut u8)
+    };
+    (result == 0).then(|| unsafe { clock.assume_init() }[0])
+}
+
+#[cfg(not(any(target_os = "solana", target_arch = "bpf")))]
+fn current_slot() -> Option<u64> {
+    None
+}
+
+/// `borrow` instruction data, patched into [`BORROW_DATA_TEMPLATE`].
+#[inline(always)]
+pub fn borrow_instruction_data(liquidity_amount: u64) -> [u8; 16] {
+    let mut data = BORROW_DATA_TEMPLATE;
+    data[8..16].copy_from_slice(&liquidity_amount.to_le_bytes());
+    data
+}
+
+/// `repay` instruction data, patched into [`REPAY_DATA_TEMPLATE`].
+#[inline(always)]
+pub fn repay_instruction_data(liquidity_amount: u64) -> [u8; 16] {
+    let mut data = REPAY_DATA_TEMPLATE;
+    data[8..16].copy_from_slice(&liquidity_amount.to_le_bytes());
+    data
+}
+
+/// `flash_borrow_reserve_liquidity` instruction data, patched into
+/// [`FLASH_BORROW_DATA_TEMPLATE`].
+///
+/// Kamino rejects flash borrows and repays issued through CPI, so there is no adapter
+/// for them: `beethoven_client::kamino` builds both as top-level instructions.
+#[inline(always)]
+pub fn flash_borrow_instruction_data(liquidity_amount: u64) -> [u8; 16] {
+    let mut data = FLASH_BORROW_DATA_TEMPLATE;
+    data[8..16].copy_from_slice(&liquidity_amount.to_le_bytes());
+    data
+}
+
+/// `flash_repay_reserve_liquidity` instruction data, patched into
+/// [`FLASH_REPAY_DATA_TEMPLATE`].
+#[inline(always)]
+pub fn flash_repay_instruction_data(
+    liquidity_amount: u64,
+    borrow_instruction_index: u8,
+) -> [u8; 17] {
+    let mut data = FLASH_REPAY_DATA_TEMPLATE;
+    data[8..16].copy_from_slice(&liquidity_amount.to_le_bytes());
+    data[16] = borrow_instruction_index;
+    data
+}
+
+/// `withdraw` instruction data, patched into [`WITHDRAW_DATA_TEMPLATE`].
+#[inline(always)]
+pub fn withdraw_instruction_data(collateral_amount: u64) -> [u8; 16] {
+    let mut data = WITHDRAW_DATA_TEMPLATE;
+    data[8..16].copy_from_slice(&collateral_amount.to_le_bytes());
+    data
+}
+
+/// `deposit` instruction data, patched into [`DEPOSIT_DATA_TEMPLATE`].
+#[inline(always)]
+pub fn deposit_instruction_data(amount: u64) -> [u8; 16] {
+    let mut data = DEPOSIT_DATA_TEMPLATE;
+    data[8..16].copy_from_slice(&amount.to_le_bytes());
+    data
 }
@@ -0,0 +1,108 @@
+//! Checks that `SwapData::parse` dispatches exactly the swap protocols whose features
+//! this build enables, rejecting the rest as it would an unknown venue, and that the
+//! contexts of an action with no protocol enabled fail with `ProtocolFeatureDisabled`.
+
+use beethoven::{BeethovenError, ProtocolKind, SwapData};
+
+/// Every protocol and whether its swap adapter is enabled in this build.
+const PROTOCOLS: [(ProtocolKind, bool); 16] = [
+    (ProtocolKind::Kamino, false),
+    (ProtocolKind::Jupiter, false),
+    (ProtocolKind::Perena, cfg!(feature = "perena-swap")),
+    (ProtocolKind::SolFi, cfg!(feature = "solfi-swap")),
+    (ProtocolKind::SolFiV2, cfg!(feature = "solfi_v2-swap")),
+    (ProtocolKind::Manifest, cfg!(feature = "manifest-swap")),
+    (ProtocolKind::Heaven, cfg!(feature = "heaven-swap")),
+    (ProtocolKind::Aldrin, cfg!(feature = "aldrin-swap")),
+    (ProtocolKind::AldrinV2, cfg!(feature = "aldrin_v2-swap")),
+    (ProtocolKind::Futarchy, cfg!(feature = "futarchy-swap")),
+    (ProtocolKind::Gamma, cfg!(feature = "gamma-swap")),
+    (ProtocolKind::MockDex, cfg!(feature = "mock_dex-swap")),
+    (ProtocolKind::RaydiumCpmm, false),
+    (ProtocolKind::Marinade, false),
+    (ProtocolKind::SplStakePool, false),
+    (ProtocolKind::Drift, false),
+];
+
+/// Swap data every swap adapter accepts
+const SWAP_DATA: [u8; 2] = [0, 1];
+
+fn main() {
+    for (protocol, enabled) in PROTOCOLS {
+        let dispatched = SwapData::parse(protocol, &SWAP_DATA).is_ok();
+        assert_eq!(
+            dispatched, enabled,
+            "{protocol:?} swap data dispatched: {dispatched}, adapter enabled: {enabled}"
+        );
+    }
+
+    #[cfg(not(any(
+        feature = "perena-swap",
+        feature = "solfi-swap",
+        feature = "solfi_v2-swap",
+        feature = "manifest-swap",
+        feature = "heaven-swap",
+        feature = "aldrin-swap",
+        feature = "aldrin_v2-swap",
+        feature = "futarchy-swap",
+        feature = "gamma-swap",
+        feature = "mock_dex-swap"
+    )))]
+    check_no_swap_protocol();
+
+    #[cfg(not(any(feature = "kamino-deposit", feature = "jupiter-deposit")))]
+    check_no_deposit_protocol();
+}
+
+#[cfg(not(any(
+    feature = "perena-swap",
+    feature = "solfi-swap",
+    feature = "solfi_v2-swap",
+    feature = "manifest-swap",
+    feature = "heaven-swap",
+    feature = "aldrin-swap",
+    feature = "aldrin_v2-swap",
+    feature = "futarchy-swap",
+    feature = "gamma-swap",
+    feature = "mock_dex-swap"
+)))]
+fn check_no_swap_protocol() {
+    use beethoven::{Swap, SwapContext};
+
+    let ctx = SwapContext::__Unused(core::marker::PhantomData);
+    assert_eq!(
+        ctx.protocol_id(),
+        Err(BeethovenError::ProtocolFeatureDisabled.into())
+    );
+    assert_eq!(
+        ctx.try_from_swap_data(&SWAP_DATA).err(),
+        Some(BeethovenError::ProtocolFeatureDisabled.into())
+    );
+
+    // No swap data matches a context without a protocol
+    assert_eq!(
+        SwapContext::swap(&ctx, 1, 0, &SwapData::__Unused(core::marker::PhantomData)),
+        Err(BeethovenError::InvalidExtraData.into())
+    );
+}
+
+#[cfg(not(any(feature = "kamino-deposit", feature = "jupiter-deposit")))]
+fn check_no_deposit_protocol() {
+    use beethoven::{Deposit, DepositContext, Withdraw, WithdrawContext};
+
+    let ctx = DepositContext::__Unused(core::marker::PhantomData);
+    assert_eq!(
+        ctx.try_from_deposit_data(&[]).err(),
+        Some(BeethovenError::ProtocolFeatureDisabled.into())
+    );
+    assert_eq!(
+        DepositContext::deposit(&ctx, 1),
+        Err(BeethovenError::ProtocolFeatureDisabled.into())
+    );
+
+    let ctx = WithdrawContext::__Unused(core::marker::PhantomData);
+    assert_eq!(
+        WithdrawContext::withdraw(&ctx, 1),
+        Err(BeethovenError::ProtocolFeatureDisabled.into())
+    );
+}
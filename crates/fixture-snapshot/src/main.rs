@@ -0,0 +1,252 @@
+//! Dump the mainnet accounts an adapter needs as JSON fixtures.
+//!
+//! ```text
+//! fixture-snapshot <protocol> <pool> [--input-mint <mint>] [--account <name>=<address>]...
+//!                  [--rpc <url>] [--out <dir>] [--with-program]
+//! ```
+//!
+//! Accounts are resolved with the client's `resolve_swap_keys` for `<pool>` (a Gamma
+//! pool state or Manifest market) and written as `<out>/<protocol>_<account>.json`,
+//! in the format `load_json_fixture` reads. Venues without a resolver dump `<pool>` as
+//! `<protocol>_pool.json` plus every `--account`, named as given. The user's own accounts don't exist for
+//! the placeholder owner and are skipped, as are programs; `--with-program` also dumps
+//! the venue's program to `<out>/<protocol>_program.so`.
+
+use {
+    base64::{engine::general_purpose::STANDARD, Engine},
+    beethoven_client::{
+        aldrin, aldrin_v2, futarchy, gamma, heaven, jupiter, kamino, manifest, perena, solfi,
+        solfi_v2, AdapterKeys,
+    },
+    serde_json::json,
+    solana_address::Address,
+    solana_pubkey::Pubkey,
+    solana_rpc_client::rpc_client::RpcClient,
+    std::{
+        collections::HashSet,
+        env,
+        error::Error,
+        fs,
+        path::{Path, PathBuf},
+        process,
+    },
+};
+
+const DEFAULT_RPC_URL: &str = "https://api.mainnet-beta.solana.com";
+
+const BPF_LOADER_UPGRADEABLE_ID: Address =
+    Address::from_str_const("BPFLoaderUpgradeab1e11111111111111111111111");
+
+/// Length of the header preceding the ELF in an upgradeable program's data account
+const PROGRAM_DATA_HEADER_LEN: usize = 45;
+
+/// Stands in for the user when resolving; none of its accounts exist on chain.
+const PLACEHOLDER_OWNER: Address = Address::new_from_array([0xbe; 32]);
+
+const USAGE: &str = "usage: fixture-snapshot <protocol> <pool> [--input-mint <mint>] \
+                     [--account <name>=<address>]... [--rpc <url>] [--out <dir>] [--with-program]";
+
+/// Named accounts an adapter takes, in its account order
+type NamedAccounts = Vec<(String, Address)>;
+
+struct Args {
+    protocol: String,
+    pool: Address,
+    input_mint: Option<Address>,
+    /// Extra accounts to dump, for venues without a resolver
+    accounts: NamedAccounts,
+    rpc_url: String,
+    out: Option<PathBuf>,
+    with_program: bool,
+}
+
+fn main() {
+    let args = match parse_args(env::args().skip(1)) {
+        Ok(args) => args,
+        Err(error) => {
+            eprintln!("{error}\n{USAGE}");
+            process::exit(2);
+        }
+    };
+
+    if let Err(error) = run(&args) {
+        eprintln!("error: {error}");
+        process::exit(1);
+    }
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Args, String> {
+    let mut positional = Vec::new();
+    let mut input_mint = None;
+    let mut accounts = Vec::new();
+    let mut rpc_url = env::var("SOLANA_RPC_URL").unwrap_or_else(|_| DEFAULT_RPC_URL.into());
+    let mut out = None;
+    let mut with_program = false;
+
+    while let Some(arg) = args.next() {
+        let mut value = |flag: &str| args.next().ok_or(format!("{flag} needs a value"));
+        match arg.as_str() {
+            "--input-mint" => input_mint = Some(parse_address(&value("--input-mint")?)?),
+            "--account" => accounts.push(parse_named_account(&value("--account")?)?),
+            "--rpc" => rpc_url = value("--rpc")?,
+            "--out" => out = Some(PathBuf::from(value("--out")?)),
+            "--with-program" => with_program = true,
+            flag if flag.starts_with("--") => return Err(format!("unknown option {flag}")),
+            _ => positional.push(arg),
+        }
+    }
+
+    let [protocol, pool] = <[String; 2]>::try_from(positional)
+        .map_err(|_| "expected a protocol and a pool address".to_string())?;
+
+    Ok(Args {
+        pool: parse_address(&pool)?,
+        protocol,
+        input_mint,
+        accounts,
+        rpc_url,
+        out,
+        with_program,
+    })
+}
+
+fn parse_address(value: &str) -> Result<Address, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid address {value}"))
+}
+
+fn parse_named_account(value: &str) -> Result<(String, Address), String> {
+    let (name, address) = value
+        .split_once('=')
+        .ok_or(format!("expected <name>=<address>, got {value}"))?;
+    Ok((name.to_string(), parse_address(address)?))
+}
+
+fn run(args: &Args) -> Result<(), Box<dyn Error>> {
+    let rpc = RpcClient::new(args.rpc_url.clone());
+    let (program_id, accounts) = resolve(&rpc, args)?;
+
+    let out = args
+        .out
+        .clone()
+        .unwrap_or_else(|| Path::new("fixtures/swap").join(&args.protocol));
+    fs::create_dir_all(&out)?;
+
+    let mut written = HashSet::new();
+    for (name, address) in accounts {
+        // Both sides of a pool can share a token program or mint
+        if !written.insert(address) {
+            continue;
+        }
+        let Some(account) = rpc
+            .get_account_with_commitment(&to_pubkey(&address), rpc.commitment())?
+            .value
+        else {
+            println!("skip {name} ({address}): not found");
+            continue;
+        };
+        if account.executable {
+            println!("skip {name} ({address}): program");
+            continue;
+        }
+
+        let fixture = json!({
+            "account": {
+                "data": [STANDARD.encode(&account.data), "base64"],
+                "executable": account.executable,
+                "lamports": account.lamports,
+                "owner": Address::new_from_array(account.owner.to_bytes()).to_string(),
+                "rentEpoch": account.rent_epoch,
+                "space": account.data.len(),
+            },
+            "pubkey": address.to_string(),
+        });
+        let path = out.join(format!("{}_{name}.json", args.protocol));
+        fs::write(&path, serde_json::to_string(&fixture)?)?;
+        println!("wrote {}", path.display());
+    }
+
+    if args.with_program {
+        let path = out.join(format!("{}_program.so", args.protocol));
+        fs::write(&path, program_elf(&rpc, &program_id)?)?;
+        println!("wrote {}", path.display());
+    }
+
+    Ok(())
+}
+
+/// The venue's program id and the named accounts its adapter takes for `args.pool`.
+fn resolve(rpc: &RpcClient, args: &Args) -> Result<(Address, NamedAccounts), Box<dyn Error>> {
+    match args.protocol.as_str() {
+        "gamma" => {
+            let input_mint = args
+                .input_mint
+                .ok_or("gamma needs --input-mint to orient the pool")?;
+            let keys = gamma::resolve_swap_keys(rpc, &args.pool, &PLACEHOLDER_OWNER, &input_mint)?;
+            Ok(named_accounts(&keys))
+        }
+        "manifest" => {
+            let mut keys = manifest::resolve_swap_keys(rpc, &args.pool, &PLACEHOLDER_OWNER, true)?;
+            if args.input_mint == Some(keys.quote_mint) {
+                keys = manifest::resolve_swap_keys(rpc, &args.pool, &PLACEHOLDER_OWNER, false)?;
+            }
+            Ok(named_accounts(&keys))
+        }
+        protocol => {
+            let program_id = program_id(protocol).ok_or(format!("unknown protocol {protocol}"))?;
+            let mut accounts = vec![("pool".to_string(), args.pool)];
+            accounts.extend_from_slice(&args.accounts);
+            Ok((program_id, accounts))
+        }
+    }
+}
+
+/// Program id of the venue named `protocol`, as feature names spell it.
+fn program_id(protocol: &str) -> Option<Address> {
+    Some(match protocol {
+        "kamino" => kamino::KaminoDepositKeys::PROGRAM_ID,
+        "jupiter" => jupiter::JupiterEarnDepositKeys::PROGRAM_ID,
+        "perena" => perena::PerenaSwapKeys::PROGRAM_ID,
+        "solfi" => solfi::SolFiSwapKeys::PROGRAM_ID,
+        "solfi_v2" => solfi_v2::SolFiV2SwapKeys::PROGRAM_ID,
+        "heaven" => heaven::HeavenSwapKeys::PROGRAM_ID,
+        "aldrin" => aldrin::AldrinSwapKeys::PROGRAM_ID,
+        "aldrin_v2" => aldrin_v2::AldrinV2SwapKeys::PROGRAM_ID,
+        "futarchy" => futarchy::FutarchySwapKeys::PROGRAM_ID,
+        _ => return None,
+    })
+}
+
+fn named_accounts<K: AdapterKeys>(keys: &K) -> (Address, NamedAccounts) {
+    let accounts = K::ACCOUNTS
+        .iter()
+        .zip(keys.addresses())
+        .map(|(spec, address)| (spec.name.to_string(), address))
+        .collect();
+    (K::PROGRAM_ID, accounts)
+}
+
+/// The ELF of the upgradeable program at `program_id`, as `solana program dump` writes it.
+fn program_elf(rpc: &RpcClient, program_id: &Address) -> Result<Vec<u8>, Box<dyn Error>> {
+    let program = rpc.get_account(&to_pubkey(program_id))?;
+    if Address::new_from_array(program.owner.to_bytes()) != BPF_LOADER_UPGRADEABLE_ID {
+        return Err(format!("{program_id} is not an upgradeable program").into());
+    }
+    // `UpgradeableLoaderState::Program { programdata_address }`, after the u32 tag
+    let programdata = program
+        .data
+        .get(4..36)
+        .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+        .ok_or("malformed program account")?;
+
+    let mut data = rpc.get_account(&Pubkey::new_from_array(programdata))?.data;
+    if data.len() < PROGRAM_DATA_HEADER_LEN {
+        return Err("malformed program data account".into());
+    }
+    Ok(data.split_off(PROGRAM_DATA_HEADER_LEN))
+}
+
+fn to_pubkey(address: &Address) -> Pubkey {
+    Pubkey::new_from_array(address.to_bytes())
+}
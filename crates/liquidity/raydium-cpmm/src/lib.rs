@@ -0,0 +1,438 @@
+#![no_std]
+
+pub use beethoven_core::program_ids::RAYDIUM_CPMM_PROGRAM_ID;
+use {
+    beethoven_core::{cpi::invoke_signed, scratch::data_template, AddLiquidity, RemoveLiquidity},
+    solana_account_view::AccountView,
+    solana_instruction_view::{cpi::Signer, InstructionAccount, InstructionView},
+    solana_program_error::{ProgramError, ProgramResult},
+};
+
+pub const DEPOSIT_DISCRIMINATOR: [u8; 8] = [242, 35, 198, 137, 82, 225, 242, 182];
+
+/// `deposit` instruction data with every amount zeroed.
+///
+/// Layout: discriminator ([u8; 8]), lp_token_amount (u64), maximum_token_0_amount (u64),
+/// maximum_token_1_amount (u64)
+pub const DEPOSIT_DATA_TEMPLATE: [u8; 32] = data_template(&DEPOSIT_DISCRIMINATOR);
+
+pub const WITHDRAW_DISCRIMINATOR: [u8; 8] = [183, 18, 70, 156, 148, 109, 161, 34];
+
+/// `withdraw` instruction data with every amount zeroed.
+///
+/// Layout: discriminator ([u8; 8]), lp_token_amount (u64), minimum_token_0_amount (u64),
+/// minimum_token_1_amount (u64)
+pub const WITHDRAW_DATA_TEMPLATE: [u8; 32] = data_template(&WITHDRAW_DISCRIMINATOR);
+
+/// Seed of the PDA that owns every pool's vaults and LP mint
+pub const AUTHORITY_SEED: &[u8] = b"vault_and_lp_mint_auth_seed";
+
+pub struct RaydiumCpmm;
+
+pub struct RaydiumCpmmAddLiquidityAccounts<'info> {
+    pub raydium_cpmm_program: &'info AccountView,
+    pub owner: &'info AccountView,
+    pub authority: &'info AccountView,
+    pub pool_state: &'info AccountView,
+    pub owner_lp_token: &'info AccountView,
+    pub token_0_account: &'info AccountView,
+    pub token_1_account: &'info AccountView,
+    pub token_0_vault: &'info AccountView,
+    pub token_1_vault: &'info AccountView,
+    pub token_program: &'info AccountView,
+    pub token_program_2022: &'info AccountView,
+    pub vault_0_mint: &'info AccountView,
+    pub vault_1_mint: &'info AccountView,
+    pub lp_mint: &'info AccountView,
+}
+
+impl<'info> TryFrom<&'info [AccountView]> for RaydiumCpmmAddLiquidityAccounts<'info> {
+    type Error = ProgramError;
+
+    #[inline(always)]
+    fn try_from(accounts: &'info [AccountView]) -> Result<Self, Self::Error> {
+        let [raydium_cpmm_program, owner, authority, pool_state, owner_lp_token, token_0_account, token_1_account, token_0_vault, token_1_vault, token_program, token_program_2022, vault_0_mint, vault_1_mint, lp_mint, ..] =
+            accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        #[cfg(feature = "strict-validation")]
+        for (account, label) in [
+            (token_0_account, "token_0_account"),
+            (token_1_account, "token_1_account"),
+        ] {
+            beethoven_core::validation::check_token_account_owner(account, owner).inspect_err(
+                |_| {
+                    beethoven_core::validation::log_account_error(
+                        ADD_LIQUIDITY_ACCOUNT_LABELS,
+                        label,
+                        "not owned by `owner`",
+                    )
+                },
+            )?;
+        }
+
+        #[cfg(feature = "strict-validation")]
+        beethoven_core::validation::check_fixed_accounts(
+            ADD_LIQUIDITY_ACCOUNT_LABELS,
+            (raydium_cpmm_program, &RAYDIUM_CPMM_PROGRAM_ID),
+            &[
+                ("token_program", token_program),
+                ("token_program_2022", token_program_2022),
+            ],
+            None,
+        )?;
+
+        Ok(RaydiumCpmmAddLiquidityAccounts {
+            raydium_cpmm_program,
+            owner,
+            authority,
+            pool_state,
+            owner_lp_token,
+            token_0_account,
+            token_1_account,
+            token_0_vault,
+            token_1_vault,
+            token_program,
+            token_program_2022,
+            vault_0_mint,
+            vault_1_mint,
+            lp_mint,
+        })
+    }
+}
+
+/// What this adapter supports, as `ProtocolKind::RaydiumCpmm.capabilities()` reports it
+pub const CAPABILITIES: beethoven_core::Capabilities =
+    beethoven_core::ProtocolKind::RaydiumCpmm.capabilities();
+
+/// Name of each add-liquidity account after the detector, in order, for error and log
+/// output.
+pub const ADD_LIQUIDITY_ACCOUNT_LABELS: &[&str] = &[
+    "owner",
+    "authority",
+    "pool_state",
+    "owner_lp_token",
+    "token_0_account",
+    "token_1_account",
+    "token_0_vault",
+    "token_1_vault",
+    "token_program",
+    "token_program_2022",
+    "vault_0_mint",
+    "vault_1_mint",
+    "lp_mint",
+];
+
+impl<'info> RaydiumCpmmAddLiquidityAccounts<'info> {
+    /// The accounts in the order the CPI passes them, without the detector.
+    #[inline(always)]
+    pub fn as_ordered_slice(&self) -> [&'info AccountView; 13] {
+        [
+            self.owner,
+            self.authority,
+            self.pool_state,
+            self.owner_lp_token,
+            self.token_0_account,
+            self.token_1_account,
+            self.token_0_vault,
+            self.token_1_vault,
+            self.token_program,
+            self.token_program_2022,
+            self.vault_0_mint,
+            self.vault_1_mint,
+            self.lp_mint,
+        ]
+    }
+
+    /// Iterate over [`Self::as_ordered_slice`].
+    #[inline(always)]
+    pub fn iter(&self) -> impl Iterator<Item = &'info AccountView> {
+        self.as_ordered_slice().into_iter()
+    }
+}
+
+/// Raydium's `deposit` mints an exact LP amount, so `minimum_lp_amount` is the amount
+/// minted and the token amounts cap what the pool may pull for it.
+impl<'info> AddLiquidity<'info> for RaydiumCpmm {
+    type Accounts = RaydiumCpmmAddLiquidityAccounts<'info>;
+
+    fn add_liquidity_signed(
+        ctx: &RaydiumCpmmAddLiquidityAccounts<'info>,
+        maximum_token_0_amount: u64,
+        maximum_token_1_amount: u64,
+        minimum_lp_amount: u64,
+        signer_seeds: &[Signer],
+    ) -> ProgramResult {
+        #[cfg(feature = "strict-validation")]
+        beethoven_core::validation::check_signer(ctx.owner, signer_seeds).inspect_err(|_| {
+            beethoven_core::validation::log_account_error(
+                ADD_LIQUIDITY_ACCOUNT_LABELS,
+                "owner",
+                "missing signature",
+            )
+        })?;
+
+        let accounts = [
+            InstructionAccount::readonly_signer(ctx.owner.address()),
+            InstructionAccount::readonly(ctx.authority.address()),
+            InstructionAccount::writable(ctx.pool_state.address()),
+            InstructionAccount::writable(ctx.owner_lp_token.address()),
+            InstructionAccount::writable(ctx.token_0_account.address()),
+            InstructionAccount::writable(ctx.token_1_account.address()),
+            InstructionAccount::writable(ctx.token_0_vault.address()),
+            InstructionAccount::writable(ctx.token_1_vault.address()),
+            InstructionAccount::readonly(ctx.token_program.address()),
+            InstructionAccount::readonly(ctx.token_program_2022.address()),
+            InstructionAccount::readonly(ctx.vault_0_mint.address()),
+            InstructionAccount::readonly(ctx.vault_1_mint.address()),
+            InstructionAccount::writable(ctx.lp_mint.address()),
+        ];
+
+        let account_infos = ctx.as_ordered_slice();
+
+        let deposit_ix = InstructionView {
+            program_id: &RAYDIUM_CPMM_PROGRAM_ID,
+            accounts: &accounts,
+            data: &deposit_instruction_data(
+                minimum_lp_amount,
+                maximum_token_0_amount,
+                maximum_token_1_amount,
+            ),
+        };
+
+        invoke_signed(&deposit_ix, &account_infos, signer_seeds)
+    }
+
+    #[inline(always)]
+    fn add_liquidity(
+        ctx: &RaydiumCpmmAddLiquidityAccounts<'info>,
+        maximum_token_0_amount: u64,
+        maximum_token_1_amount: u64,
+        minimum_lp_amount: u64,
+    ) -> ProgramResult {
+        Self::add_liquidity_signed(
+            ctx,
+            maximum_token_0_amount,
+            maximum_token_1_amount,
+            minimum_lp_amount,
+            &[],
+        )
+    }
+}
+
+/// `deposit` instruction data, patched into [`DEPOSIT_DATA_TEMPLATE`].
+#[inline(always)]
+pub fn deposit_instruction_data(
+    lp_token_amount: u64,
+    maximum_token_0_amount: u64,
+    maximum_token_1_amount: u64,
+) -> [u8; 32] {
+    let mut data = DEPOSIT_DATA_TEMPLATE;
+    data[8..16].copy_from_slice(&lp_token_amount.to_le_bytes());
+    data[16..24].copy_from_slice(&maximum_token_0_amount.to_le_bytes());
+    data[24..32].copy_from_slice(&maximum_token_1_amount.to_le_bytes());
+    data
+}
+
+pub struct RaydiumCpmmRemoveLiquidityAccounts<'info> {
+    pub raydium_cpmm_program: &'info AccountView,
+    pub owner: &'info AccountView,
+    pub authority: &'info AccountView,
+    pub pool_state: &'info AccountView,
+    pub owner_lp_token: &'info AccountView,
+    pub token_0_account: &'info AccountView,
+    pub token_1_account: &'info AccountView,
+    pub token_0_vault: &'info AccountView,
+    pub token_1_vault: &'info AccountView,
+    pub token_program: &'info AccountView,
+    pub token_program_2022: &'info AccountView,
+    pub vault_0_mint: &'info AccountView,
+    pub vault_1_mint: &'info AccountView,
+    pub lp_mint: &'info AccountView,
+    pub memo_program: &'info AccountView,
+}
+
+impl<'info> TryFrom<&'info [AccountView]> for RaydiumCpmmRemoveLiquidityAccounts<'info> {
+    type Error = ProgramError;
+
+    #[inline(always)]
+    fn try_from(accounts: &'info [AccountView]) -> Result<Self, Self::Error> {
+        let [raydium_cpmm_program, owner, authority, pool_state, owner_lp_token, token_0_account, token_1_account, token_0_vault, token_1_vault, token_program, token_program_2022, vault_0_mint, vault_1_mint, lp_mint, memo_program, ..] =
+            accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        #[cfg(feature = "strict-validation")]
+        beethoven_core::validation::check_token_account_owner(owner_lp_token, owner).inspect_err(
+            |_| {
+                beethoven_core::validation::log_account_error(
+                    REMOVE_LIQUIDITY_ACCOUNT_LABELS,
+                    "owner_lp_token",
+                    "not owned by `owner`",
+                )
+            },
+        )?;
+
+        #[cfg(feature = "strict-validation")]
+        beethoven_core::validation::check_fixed_accounts(
+            REMOVE_LIQUIDITY_ACCOUNT_LABELS,
+            (raydium_cpmm_program, &RAYDIUM_CPMM_PROGRAM_ID),
+            &[
+                ("token_program", token_program),
+                ("token_program_2022", token_program_2022),
+            ],
+            None,
+        )?;
+
+        Ok(RaydiumCpmmRemoveLiquidityAccounts {
+            raydium_cpmm_program,
+            owner,
+            authority,
+            pool_state,
+            owner_lp_token,
+            token_0_account,
+            token_1_account,
+            token_0_vault,
+            token_1_vault,
+            token_program,
+            token_program_2022,
+            vault_0_mint,
+            vault_1_mint,
+            lp_mint,
+            memo_program,
+        })
+    }
+}
+
+/// Name of each remove-liquidity account after the detector, in order, for error and
+/// log output.
+pub const REMOVE_LIQUIDITY_ACCOUNT_LABELS: &[&str] = &[
+    "owner",
+    "authority",
+    "pool_state",
+    "owner_lp_token",
+    "token_0_account",
+    "token_1_account",
+    "token_0_vault",
+    "token_1_vault",
+    "token_program",
+    "token_program_2022",
+    "vault_0_mint",
+    "vault_1_mint",
+    "lp_mint",
+    "memo_program",
+];
+
+impl<'info> RaydiumCpmmRemoveLiquidityAccounts<'info> {
+    /// The accounts in the order the CPI passes them, without the detector.
+    #[inline(always)]
+    pub fn as_ordered_slice(&self) -> [&'info AccountView; 14] {
+        [
+            self.owner,
+            self.authority,
+            self.pool_state,
+            self.owner_lp_token,
+            self.token_0_account,
+            self.token_1_account,
+            self.token_0_vault,
+            self.token_1_vault,
+            self.token_program,
+            self.token_program_2022,
+            self.vault_0_mint,
+            self.vault_1_mint,
+            self.lp_mint,
+            self.memo_program,
+        ]
+    }
+
+    /// Iterate over [`Self::as_ordered_slice`].
+    #[inline(always)]
+    pub fn iter(&self) -> impl Iterator<Item = &'info AccountView> {
+        self.as_ordered_slice().into_iter()
+    }
+}
+
+impl<'info> RemoveLiquidity<'info> for RaydiumCpmm {
+    type Accounts = RaydiumCpmmRemoveLiquidityAccounts<'info>;
+
+    fn remove_liquidity_signed(
+        ctx: &RaydiumCpmmRemoveLiquidityAccounts<'info>,
+        lp_amount: u64,
+        minimum_token_0_amount: u64,
+        minimum_token_1_amount: u64,
+        signer_seeds: &[Signer],
+    ) -> ProgramResult {
+        #[cfg(feature = "strict-validation")]
+        beethoven_core::validation::check_signer(ctx.owner, signer_seeds).inspect_err(|_| {
+            beethoven_core::validation::log_account_error(
+                REMOVE_LIQUIDITY_ACCOUNT_LABELS,
+                "owner",
+                "missing signature",
+            )
+        })?;
+
+        let accounts = [
+            InstructionAccount::readonly_signer(ctx.owner.address()),
+            InstructionAccount::readonly(ctx.authority.address()),
+            InstructionAccount::writable(ctx.pool_state.address()),
+            InstructionAccount::writable(ctx.owner_lp_token.address()),
+            InstructionAccount::writable(ctx.token_0_account.address()),
+            InstructionAccount::writable(ctx.token_1_account.address()),
+            InstructionAccount::writable(ctx.token_0_vault.address()),
+            InstructionAccount::writable(ctx.token_1_vault.address()),
+            InstructionAccount::readonly(ctx.token_program.address()),
+            InstructionAccount::readonly(ctx.token_program_2022.address()),
+            InstructionAccount::readonly(ctx.vault_0_mint.address()),
+            InstructionAccount::readonly(ctx.vault_1_mint.address()),
+            InstructionAccount::writable(ctx.lp_mint.address()),
+            InstructionAccount::readonly(ctx.memo_program.address()),
+        ];
+
+        let account_infos = ctx.as_ordered_slice();
+
+        let withdraw_ix = InstructionView {
+            program_id: &RAYDIUM_CPMM_PROGRAM_ID,
+            accounts: &accounts,
+            data: &withdraw_instruction_data(
+                lp_amount,
+                minimum_token_0_amount,
+                minimum_token_1_amount,
+            ),
+        };
+
+        invoke_signed(&withdraw_ix, &account_infos, signer_seeds)
+    }
+
+    #[inline(always)]
+    fn remove_liquidity(
+        ctx: &RaydiumCpmmRemoveLiquidityAccounts<'info>,
+        lp_amount: u64,
+        minimum_token_0_amount: u64,
+        minimum_token_1_amount: u64,
+    ) -> ProgramResult {
+        Self::remove_liquidity_signed(
+            ctx,
+            lp_amount,
+            minimum_token_0_amount,
+            minimum_token_1_amount,
+            &[],
+        )
+    }
+}
+
+/// `withdraw` instruction data, patched into [`WITHDRAW_DATA_TEMPLATE`].
+#[inline(always)]
+pub fn withdraw_instruction_data(
+    lp_token_amount: u64,
+    minimum_token_0_amount: u64,
+    minimum_token_1_amount: u64,
+) -> [u8; 32] {
+    let mut data = WITHDRAW_DATA_TEMPLATE;
+    data[8..16].copy_from_slice(&lp_token_amount.to_le_bytes());
+    data[16..24].copy_from_slice(&minimum_token_0_amount.to_le_bytes());
+    data[24..32].copy_from_slice(&minimum_token_1_amount.to_le_bytes());
+    data
+}
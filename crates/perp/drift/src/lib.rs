@@ -0,0 +1,198 @@
+#![no_std]
+
+pub use beethoven_core::program_ids::DRIFT_PROGRAM_ID;
+use {
+    beethoven_core::{
+        cpi::invoke_signed_with_bounds, scratch::data_template, Direction, PerpTrade,
+    },
+    solana_account_view::AccountView,
+    solana_instruction_view::{cpi::Signer, InstructionAccount, InstructionView},
+    solana_program_error::{ProgramError, ProgramResult},
+};
+
+pub const PLACE_PERP_ORDER_DISCRIMINATOR: [u8; 8] = [69, 161, 93, 202, 120, 126, 76, 185];
+
+/// Most oracle and market accounts forwarded after `authority`
+pub const MAX_MARKET_ACCOUNTS: usize = 16;
+
+/// Accounts `place_perp_order` takes before the market accounts
+const ORDER_ACCOUNTS: usize = 3;
+
+/// `place_perp_order` instruction data for a perp market order, with every varying field
+/// zeroed.
+///
+/// Layout: discriminator ([u8; 8]), then `OrderParams`: order_type (u8, `Market`),
+/// market_type (u8, `Perp`), direction (u8), user_order_id (u8), base_asset_amount (u64),
+/// price (u64), market_index (u16), reduce_only (u8), post_only (u8),
+/// bit_flags (u8), max_ts, trigger_price (`Option`s, `None`), trigger_condition (u8),
+/// oracle_price_offset, auction_duration, auction_start_price, auction_end_price
+/// (`Option`s, `None`)
+pub const PLACE_PERP_ORDER_DATA_TEMPLATE: [u8; 40] = {
+    let mut template: [u8; 40] = data_template(&PLACE_PERP_ORDER_DISCRIMINATOR);
+    // market_type: Perp
+    template[9] = 1;
+    template
+};
+
+pub struct Drift;
+
+pub struct DriftPerpAccounts<'info> {
+    pub drift_program: &'info AccountView,
+    pub state: &'info AccountView,
+    pub user: &'info AccountView,
+    pub authority: &'info AccountView,
+    /// Oracles, spot markets and perp markets Drift reads, in the order its SDK's
+    /// remaining accounts list them
+    pub market_accounts: &'info [AccountView],
+}
+
+impl<'info> TryFrom<&'info [AccountView]> for DriftPerpAccounts<'info> {
+    type Error = ProgramError;
+
+    #[inline(always)]
+    fn try_from(accounts: &'info [AccountView]) -> Result<Self, Self::Error> {
+        let [drift_program, state, user, authority, market_accounts @ ..] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        if market_accounts.len() > MAX_MARKET_ACCOUNTS {
+            #[cfg(feature = "strict-validation")]
+            beethoven_core::validation::log_account_error(
+                ACCOUNT_LABELS,
+                "authority",
+                "followed by more market accounts than MAX_MARKET_ACCOUNTS",
+            );
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        #[cfg(feature = "strict-validation")]
+        beethoven_core::validation::check_fixed_accounts(
+            ACCOUNT_LABELS,
+            (drift_program, &DRIFT_PROGRAM_ID),
+            &[],
+            None,
+        )?;
+
+        Ok(DriftPerpAccounts {
+            drift_program,
+            state,
+            user,
+            authority,
+            market_accounts,
+        })
+    }
+}
+
+/// What this adapter supports, as `ProtocolKind::Drift.capabilities()` reports it
+pub const CAPABILITIES: beethoven_core::Capabilities =
+    beethoven_core::ProtocolKind::Drift.capabilities();
+
+/// Name of each account after the detector, in order, for error and log output; the
+/// market accounts follow.
+pub const ACCOUNT_LABELS: &[&str] = &["state", "user", "authority"];
+
+impl<'info> DriftPerpAccounts<'info> {
+    /// The fixed accounts in the order the CPI passes them, without the detector or the
+    /// market accounts.
+    #[inline(always)]
+    pub fn as_ordered_slice(&self) -> [&'info AccountView; 3] {
+        [self.state, self.user, self.authority]
+    }
+
+    /// Iterate over [`Self::as_ordered_slice`], then the market accounts.
+    #[inline(always)]
+    pub fn iter(&self) -> impl Iterator<Item = &'info AccountView> {
+        self.as_ordered_slice()
+            .into_iter()
+            .chain(self.market_accounts.iter())
+    }
+}
+
+/// Market orders only; the market accounts keep the writable flags the caller gave them.
+impl<'info> PerpTrade<'info> for Drift {
+    type Accounts = DriftPerpAccounts<'info>;
+
+    fn place_perp_order_signed(
+        ctx: &DriftPerpAccounts<'info>,
+        market_index: u16,
+        direction: Direction,
+        base_amount: u64,
+        price_limit: u64,
+        signer_seeds: &[Signer],
+    ) -> ProgramResult {
+        #[cfg(feature = "strict-validation")]
+        beethoven_core::validation::check_signer(ctx.authority, signer_seeds).inspect_err(
+            |_| {
+                beethoven_core::validation::log_account_error(
+                    ACCOUNT_LABELS,
+                    "authority",
+                    "missing signature",
+                )
+            },
+        )?;
+
+        const MAX_ACCOUNTS: usize = ORDER_ACCOUNTS + MAX_MARKET_ACCOUNTS;
+
+        let mut accounts: [InstructionAccount; MAX_ACCOUNTS] =
+            core::array::from_fn(|_| InstructionAccount::readonly(ctx.state.address()));
+        accounts[1] = InstructionAccount::writable(ctx.user.address());
+        accounts[2] = InstructionAccount::readonly_signer(ctx.authority.address());
+
+        let mut account_infos = [ctx.state; MAX_ACCOUNTS];
+        account_infos[1] = ctx.user;
+        account_infos[2] = ctx.authority;
+
+        let mut len = ORDER_ACCOUNTS;
+        for ((meta, info), market) in accounts[ORDER_ACCOUNTS..]
+            .iter_mut()
+            .zip(&mut account_infos[ORDER_ACCOUNTS..])
+            .zip(ctx.market_accounts)
+        {
+            *meta = InstructionAccount::from(market);
+            *info = market;
+            len += 1;
+        }
+
+        let instruction = InstructionView {
+            program_id: &DRIFT_PROGRAM_ID,
+            accounts: &accounts[..len],
+            data: &place_perp_order_instruction_data(
+                market_index,
+                direction,
+                base_amount,
+                price_limit,
+            ),
+        };
+
+        invoke_signed_with_bounds::<MAX_ACCOUNTS>(&instruction, &account_infos[..len], signer_seeds)
+    }
+
+    #[inline(always)]
+    fn place_perp_order(
+        ctx: &DriftPerpAccounts<'info>,
+        market_index: u16,
+        direction: Direction,
+        base_amount: u64,
+        price_limit: u64,
+    ) -> ProgramResult {
+        Self::place_perp_order_signed(ctx, market_index, direction, base_amount, price_limit, &[])
+    }
+}
+
+/// `place_perp_order` instruction data for a market order, patched into
+/// [`PLACE_PERP_ORDER_DATA_TEMPLATE`].
+#[inline(always)]
+pub fn place_perp_order_instruction_data(
+    market_index: u16,
+    direction: Direction,
+    base_amount: u64,
+    price_limit: u64,
+) -> [u8; 40] {
+    let mut data = PLACE_PERP_ORDER_DATA_TEMPLATE;
+    // Drift's `PositionDirection`: Long = 0, Short = 1
+    data[10] = (direction == Direction::Sell) as u8;
+    data[12..20].copy_from_slice(&base_amount.to_le_bytes());
+    data[20..28].copy_from_slice(&price_limit.to_le_bytes());
+    data[28..30].copy_from_slice(&market_index.to_le_bytes());
+    data
+}
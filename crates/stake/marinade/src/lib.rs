@@ -0,0 +1,446 @@
+#![no_std]
+
+pub use beethoven_core::program_ids::MARINADE_PROGRAM_ID;
+use {
+    beethoven_core::{cpi::invoke_signed, scratch::data_template, Stake, Unstake},
+    solana_account_view::AccountView,
+    solana_instruction_view::{cpi::Signer, InstructionAccount, InstructionView},
+    solana_program_error::{ProgramError, ProgramResult},
+};
+
+pub const DEPOSIT_DISCRIMINATOR: [u8; 8] = [242, 35, 198, 137, 82, 225, 242, 182];
+
+/// `deposit` instruction data with the lamports zeroed.
+///
+/// Layout: discriminator ([u8; 8]), lamports (u64)
+pub const DEPOSIT_DATA_TEMPLATE: [u8; 16] = data_template(&DEPOSIT_DISCRIMINATOR);
+
+pub const LIQUID_UNSTAKE_DISCRIMINATOR: [u8; 8] = [30, 30, 119, 240, 191, 227, 12, 16];
+
+/// `liquid_unstake` instruction data with the mSOL amount zeroed.
+///
+/// Layout: discriminator ([u8; 8]), msol_amount (u64)
+pub const LIQUID_UNSTAKE_DATA_TEMPLATE: [u8; 16] = data_template(&LIQUID_UNSTAKE_DISCRIMINATOR);
+
+pub const ORDER_UNSTAKE_DISCRIMINATOR: [u8; 8] = [97, 167, 144, 107, 117, 190, 128, 36];
+
+/// `order_unstake` instruction data with the mSOL amount zeroed.
+///
+/// Layout: discriminator ([u8; 8]), msol_amount (u64)
+pub const ORDER_UNSTAKE_DATA_TEMPLATE: [u8; 16] = data_template(&ORDER_UNSTAKE_DISCRIMINATOR);
+
+pub struct Marinade;
+
+pub struct MarinadeStakeAccounts<'info> {
+    pub marinade_program: &'info AccountView,
+    pub state: &'info AccountView,
+    pub msol_mint: &'info AccountView,
+    pub liq_pool_sol_leg_pda: &'info AccountView,
+    pub liq_pool_msol_leg: &'info AccountView,
+    pub liq_pool_msol_leg_authority: &'info AccountView,
+    pub reserve_pda: &'info AccountView,
+    pub transfer_from: &'info AccountView,
+    pub mint_to: &'info AccountView,
+    pub msol_mint_authority: &'info AccountView,
+    pub system_program: &'info AccountView,
+    pub token_program: &'info AccountView,
+}
+
+impl<'info> TryFrom<&'info [AccountView]> for MarinadeStakeAccounts<'info> {
+    type Error = ProgramError;
+
+    #[inline(always)]
+    fn try_from(accounts: &'info [AccountView]) -> Result<Self, Self::Error> {
+        let [marinade_program, state, msol_mint, liq_pool_sol_leg_pda, liq_pool_msol_leg, liq_pool_msol_leg_authority, reserve_pda, transfer_from, mint_to, msol_mint_authority, system_program, token_program, ..] =
+            accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        #[cfg(feature = "strict-validation")]
+        beethoven_core::validation::check_fixed_accounts(
+            STAKE_ACCOUNT_LABELS,
+            (marinade_program, &MARINADE_PROGRAM_ID),
+            &[("token_program", token_program)],
+            None,
+        )?;
+
+        Ok(MarinadeStakeAccounts {
+            marinade_program,
+            state,
+            msol_mint,
+            liq_pool_sol_leg_pda,
+            liq_pool_msol_leg,
+            liq_pool_msol_leg_authority,
+            reserve_pda,
+            transfer_from,
+            mint_to,
+            msol_mint_authority,
+            system_program,
+            token_program,
+        })
+    }
+}
+
+/// What this adapter supports, as `ProtocolKind::Marinade.capabilities()` reports it
+pub const CAPABILITIES: beethoven_core::Capabilities =
+    beethoven_core::ProtocolKind::Marinade.capabilities();
+
+/// Name of each stake account after the detector, in order, for error and log output.
+pub const STAKE_ACCOUNT_LABELS: &[&str] = &[
+    "state",
+    "msol_mint",
+    "liq_pool_sol_leg_pda",
+    "liq_pool_msol_leg",
+    "liq_pool_msol_leg_authority",
+    "reserve_pda",
+    "transfer_from",
+    "mint_to",
+    "msol_mint_authority",
+    "system_program",
+    "token_program",
+];
+
+impl<'info> MarinadeStakeAccounts<'info> {
+    /// The accounts in the order the CPI passes them, without the detector.
+    #[inline(always)]
+    pub fn as_ordered_slice(&self) -> [&'info AccountView; 11] {
+        [
+            self.state,
+            self.msol_mint,
+            self.liq_pool_sol_leg_pda,
+            self.liq_pool_msol_leg,
+            self.liq_pool_msol_leg_authority,
+            self.reserve_pda,
+            self.transfer_from,
+            self.mint_to,
+            self.msol_mint_authority,
+            self.system_program,
+            self.token_program,
+        ]
+    }
+
+    /// Iterate over [`Self::as_ordered_slice`].
+    #[inline(always)]
+    pub fn iter(&self) -> impl Iterator<Item = &'info AccountView> {
+        self.as_ordered_slice().into_iter()
+    }
+}
+
+/// Marinade's `deposit` swaps from the liquidity pool's mSOL leg while it holds any and
+/// mints the rest, so `transfer_from` pays `lamports` and `mint_to` receives the mSOL.
+impl<'info> Stake<'info> for Marinade {
+    type Accounts = MarinadeStakeAccounts<'info>;
+
+    fn stake_signed(
+        ctx: &MarinadeStakeAccounts<'info>,
+        lamports: u64,
+        signer_seeds: &[Signer],
+    ) -> ProgramResult {
+        #[cfg(feature = "strict-validation")]
+        beethoven_core::validation::check_signer(ctx.transfer_from, signer_seeds).inspect_err(
+            |_| {
+                beethoven_core::validation::log_account_error(
+                    STAKE_ACCOUNT_LABELS,
+                    "transfer_from",
+                    "missing signature",
+                )
+            },
+        )?;
+
+        let accounts = [
+            InstructionAccount::writable(ctx.state.address()),
+            InstructionAccount::writable(ctx.msol_mint.address()),
+            InstructionAccount::writable(ctx.liq_pool_sol_leg_pda.address()),
+            InstructionAccount::writable(ctx.liq_pool_msol_leg.address()),
+            InstructionAccount::readonly(ctx.liq_pool_msol_leg_authority.address()),
+            InstructionAccount::writable(ctx.reserve_pda.address()),
+            InstructionAccount::writable_signer(ctx.transfer_from.address()),
+            InstructionAccount::writable(ctx.mint_to.address()),
+            InstructionAccount::readonly(ctx.msol_mint_authority.address()),
+            InstructionAccount::readonly(ctx.system_program.address()),
+            InstructionAccount::readonly(ctx.token_program.address()),
+        ];
+
+        let account_infos = ctx.as_ordered_slice();
+
+        let deposit_ix = InstructionView {
+            program_id: &MARINADE_PROGRAM_ID,
+            accounts: &accounts,
+            data: &deposit_instruction_data(lamports),
+        };
+
+        invoke_signed(&deposit_ix, &account_infos, signer_seeds)
+    }
+
+    #[inline(always)]
+    fn stake(ctx: &MarinadeStakeAccounts<'info>, lamports: u64) -> ProgramResult {
+        Self::stake_signed(ctx, lamports, &[])
+    }
+}
+
+/// `deposit` instruction data, patched into [`DEPOSIT_DATA_TEMPLATE`].
+#[inline(always)]
+pub fn deposit_instruction_data(lamports: u64) -> [u8; 16] {
+    let mut data = DEPOSIT_DATA_TEMPLATE;
+    data[8..16].copy_from_slice(&lamports.to_le_bytes());
+    data
+}
+
+/// How [`Marinade`] unstakes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[repr(u8)]
+pub enum MarinadeUnstakeData {
+    /// `liquid_unstake`: swap mSOL for SOL against the liquidity pool, paying its fee
+    #[default]
+    Instant = 0,
+    /// `order_unstake`: burn mSOL into a ticket claimable for SOL once the stake
+    /// deactivates, without the pool fee
+    Delayed = 1,
+}
+
+impl TryFrom<&[u8]> for MarinadeUnstakeData {
+    type Error = ProgramError;
+
+    fn try_from(data: &[u8]) -> Result<Self, Self::Error> {
+        match data {
+            [0, ..] => Ok(Self::Instant),
+            [1, ..] => Ok(Self::Delayed),
+            _ => Err(ProgramError::InvalidInstructionData),
+        }
+    }
+}
+
+/// Accounts of both unstake paths: `liquid_unstake`'s, then the ticket accounts only
+/// [`MarinadeUnstakeData::Delayed`] reads.
+pub struct MarinadeUnstakeAccounts<'info> {
+    pub marinade_program: &'info AccountView,
+    pub state: &'info AccountView,
+    pub msol_mint: &'info AccountView,
+    pub liq_pool_sol_leg_pda: &'info AccountView,
+    pub liq_pool_msol_leg: &'info AccountView,
+    pub treasury_msol_account: &'info AccountView,
+    pub get_msol_from: &'info AccountView,
+    pub get_msol_from_authority: &'info AccountView,
+    pub transfer_sol_to: &'info AccountView,
+    pub system_program: &'info AccountView,
+    pub token_program: &'info AccountView,
+    /// Zeroed account owned by Marinade and rent-exempt for a ticket, created by the
+    /// caller beforehand
+    pub new_ticket_account: Option<&'info AccountView>,
+    pub clock: Option<&'info AccountView>,
+    pub rent: Option<&'info AccountView>,
+}
+
+impl<'info> TryFrom<&'info [AccountView]> for MarinadeUnstakeAccounts<'info> {
+    type Error = ProgramError;
+
+    #[inline(always)]
+    fn try_from(accounts: &'info [AccountView]) -> Result<Self, Self::Error> {
+        let [marinade_program, state, msol_mint, liq_pool_sol_leg_pda, liq_pool_msol_leg, treasury_msol_account, get_msol_from, get_msol_from_authority, transfer_sol_to, system_program, token_program, remaining @ ..] =
+            accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        #[cfg(feature = "strict-validation")]
+        beethoven_core::validation::check_token_account_owner(
+            get_msol_from,
+            get_msol_from_authority,
+        )
+        .inspect_err(|_| {
+            beethoven_core::validation::log_account_error(
+                UNSTAKE_ACCOUNT_LABELS,
+                "get_msol_from",
+                "not owned by `get_msol_from_authority`",
+            )
+        })?;
+
+        let (new_ticket_account, clock, rent) = match remaining {
+            [new_ticket_account, clock, rent, ..] => {
+                (Some(new_ticket_account), Some(clock), Some(rent))
+            }
+            _ => (None, None, None),
+        };
+
+        #[cfg(feature = "strict-validation")]
+        beethoven_core::validation::check_fixed_accounts(
+            UNSTAKE_ACCOUNT_LABELS,
+            (marinade_program, &MARINADE_PROGRAM_ID),
+            &[("token_program", token_program)],
+            None,
+        )?;
+
+        Ok(MarinadeUnstakeAccounts {
+            marinade_program,
+            state,
+            msol_mint,
+            liq_pool_sol_leg_pda,
+            liq_pool_msol_leg,
+            treasury_msol_account,
+            get_msol_from,
+            get_msol_from_authority,
+            transfer_sol_to,
+            system_program,
+            token_program,
+            new_ticket_account,
+            clock,
+            rent,
+        })
+    }
+}
+
+/// Name of each unstake account after the detector, in order, for error and log output;
+/// the last three are only passed for a delayed unstake.
+pub const UNSTAKE_ACCOUNT_LABELS: &[&str] = &[
+    "state",
+    "msol_mint",
+    "liq_pool_sol_leg_pda",
+    "liq_pool_msol_leg",
+    "treasury_msol_account",
+    "get_msol_from",
+    "get_msol_from_authority",
+    "transfer_sol_to",
+    "system_program",
+    "token_program",
+    "new_ticket_account",
+    "clock",
+    "rent",
+];
+
+impl<'info> MarinadeUnstakeAccounts<'info> {
+    /// The `liquid_unstake` accounts in the order the CPI passes them, without the
+    /// detector.
+    #[inline(always)]
+    pub fn as_ordered_slice(&self) -> [&'info AccountView; 10] {
+        [
+            self.state,
+            self.msol_mint,
+            self.liq_pool_sol_leg_pda,
+            self.liq_pool_msol_leg,
+            self.treasury_msol_account,
+            self.get_msol_from,
+            self.get_msol_from_authority,
+            self.transfer_sol_to,
+            self.system_program,
+            self.token_program,
+        ]
+    }
+
+    /// Iterate over [`Self::as_ordered_slice`].
+    #[inline(always)]
+    pub fn iter(&self) -> impl Iterator<Item = &'info AccountView> {
+        self.as_ordered_slice().into_iter()
+    }
+}
+
+impl<'info> Unstake<'info> for Marinade {
+    type Accounts = MarinadeUnstakeAccounts<'info>;
+    type Data = MarinadeUnstakeData;
+
+    fn unstake_signed(
+        ctx: &MarinadeUnstakeAccounts<'info>,
+        amount: u64,
+        data: &MarinadeUnstakeData,
+        signer_seeds: &[Signer],
+    ) -> ProgramResult {
+        #[cfg(feature = "strict-validation")]
+        beethoven_core::validation::check_signer(ctx.get_msol_from_authority, signer_seeds)
+            .inspect_err(|_| {
+                beethoven_core::validation::log_account_error(
+                    UNSTAKE_ACCOUNT_LABELS,
+                    "get_msol_from_authority",
+                    "missing signature",
+                )
+            })?;
+
+        match data {
+            MarinadeUnstakeData::Instant => {
+                let accounts = [
+                    InstructionAccount::writable(ctx.state.address()),
+                    InstructionAccount::writable(ctx.msol_mint.address()),
+                    InstructionAccount::writable(ctx.liq_pool_sol_leg_pda.address()),
+                    InstructionAccount::writable(ctx.liq_pool_msol_leg.address()),
+                    InstructionAccount::writable(ctx.treasury_msol_account.address()),
+                    InstructionAccount::writable(ctx.get_msol_from.address()),
+                    InstructionAccount::readonly_signer(ctx.get_msol_from_authority.address()),
+                    InstructionAccount::writable(ctx.transfer_sol_to.address()),
+                    InstructionAccount::readonly(ctx.system_program.address()),
+                    InstructionAccount::readonly(ctx.token_program.address()),
+                ];
+
+                let account_infos = ctx.as_ordered_slice();
+
+                let liquid_unstake_ix = InstructionView {
+                    program_id: &MARINADE_PROGRAM_ID,
+                    accounts: &accounts,
+                    data: &liquid_unstake_instruction_data(amount),
+                };
+
+                invoke_signed(&liquid_unstake_ix, &account_infos, signer_seeds)
+            }
+            MarinadeUnstakeData::Delayed => {
+                let (Some(new_ticket_account), Some(clock), Some(rent)) =
+                    (ctx.new_ticket_account, ctx.clock, ctx.rent)
+                else {
+                    return Err(ProgramError::NotEnoughAccountKeys);
+                };
+
+                let accounts = [
+                    InstructionAccount::writable(ctx.state.address()),
+                    InstructionAccount::writable(ctx.msol_mint.address()),
+                    InstructionAccount::writable(ctx.get_msol_from.address()),
+                    InstructionAccount::readonly_signer(ctx.get_msol_from_authority.address()),
+                    InstructionAccount::writable(new_ticket_account.address()),
+                    InstructionAccount::readonly(clock.address()),
+                    InstructionAccount::readonly(rent.address()),
+                    InstructionAccount::readonly(ctx.token_program.address()),
+                ];
+
+                let account_infos = [
+                    ctx.state,
+                    ctx.msol_mint,
+                    ctx.get_msol_from,
+                    ctx.get_msol_from_authority,
+                    new_ticket_account,
+                    clock,
+                    rent,
+                    ctx.token_program,
+                ];
+
+                let order_unstake_ix = InstructionView {
+                    program_id: &MARINADE_PROGRAM_ID,
+                    accounts: &accounts,
+                    data: &order_unstake_instruction_data(amount),
+                };
+
+                invoke_signed(&order_unstake_ix, &account_infos, signer_seeds)
+            }
+        }
+    }
+
+    #[inline(always)]
+    fn unstake(
+        ctx: &MarinadeUnstakeAccounts<'info>,
+        amount: u64,
+        data: &MarinadeUnstakeData,
+    ) -> ProgramResult {
+        Self::unstake_signed(ctx, amount, data, &[])
+    }
+}
+
+/// `liquid_unstake` instruction data, patched into [`LIQUID_UNSTAKE_DATA_TEMPLATE`].
+#[inline(always)]
+pub fn liquid_unstake_instruction_data(msol_amount: u64) -> [u8; 16] {
+    let mut data = LIQUID_UNSTAKE_DATA_TEMPLATE;
+    data[8..16].copy_from_slice(&msol_amount.to_le_bytes());
+    data
+}
+
+/// `order_unstake` instruction data, patched into [`ORDER_UNSTAKE_DATA_TEMPLATE`].
+#[inline(always)]
+pub fn order_unstake_instruction_data(msol_amount: u64) -> [u8; 16] {
+    let mut data = ORDER_UNSTAKE_DATA_TEMPLATE;
+    data[8..16].copy_from_slice(&msol_amount.to_le_bytes());
+    data
+}
@@ -0,0 +1,193 @@
+#![no_std]
+
+pub use beethoven_core::program_ids::SPL_STAKE_POOL_PROGRAM_ID;
+use {
+    beethoven_core::{cpi::invoke_signed, Unstake},
+    solana_account_view::AccountView,
+    solana_instruction_view::{cpi::Signer, InstructionAccount, InstructionView},
+    solana_program_error::{ProgramError, ProgramResult},
+};
+
+/// `StakePoolInstruction::WithdrawSol` tag
+pub const WITHDRAW_SOL_TAG: u8 = 16;
+
+pub struct SplStakePool;
+
+pub struct SplStakePoolUnstakeAccounts<'info> {
+    pub stake_pool_program: &'info AccountView,
+    pub stake_pool: &'info AccountView,
+    pub withdraw_authority: &'info AccountView,
+    pub user_transfer_authority: &'info AccountView,
+    pub pool_tokens_from: &'info AccountView,
+    pub reserve_stake: &'info AccountView,
+    pub lamports_to: &'info AccountView,
+    pub manager_fee_account: &'info AccountView,
+    pub pool_mint: &'info AccountView,
+    pub clock: &'info AccountView,
+    pub stake_history: &'info AccountView,
+    pub stake_program: &'info AccountView,
+    pub token_program: &'info AccountView,
+}
+
+impl<'info> TryFrom<&'info [AccountView]> for SplStakePoolUnstakeAccounts<'info> {
+    type Error = ProgramError;
+
+    #[inline(always)]
+    fn try_from(accounts: &'info [AccountView]) -> Result<Self, Self::Error> {
+        let [stake_pool_program, stake_pool, withdraw_authority, user_transfer_authority, pool_tokens_from, reserve_stake, lamports_to, manager_fee_account, pool_mint, clock, stake_history, stake_program, token_program, ..] =
+            accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        #[cfg(feature = "strict-validation")]
+        beethoven_core::validation::check_token_account_owner(
+            pool_tokens_from,
+            user_transfer_authority,
+        )
+        .inspect_err(|_| {
+            beethoven_core::validation::log_account_error(
+                UNSTAKE_ACCOUNT_LABELS,
+                "pool_tokens_from",
+                "not owned by `user_transfer_authority`",
+            )
+        })?;
+
+        #[cfg(feature = "strict-validation")]
+        beethoven_core::validation::check_fixed_accounts(
+            UNSTAKE_ACCOUNT_LABELS,
+            (stake_pool_program, &SPL_STAKE_POOL_PROGRAM_ID),
+            &[("token_program", token_program)],
+            None,
+        )?;
+
+        Ok(SplStakePoolUnstakeAccounts {
+            stake_pool_program,
+            stake_pool,
+            withdraw_authority,
+            user_transfer_authority,
+            pool_tokens_from,
+            reserve_stake,
+            lamports_to,
+            manager_fee_account,
+            pool_mint,
+            clock,
+            stake_history,
+            stake_program,
+            token_program,
+        })
+    }
+}
+
+/// What this adapter supports, as `ProtocolKind::SplStakePool.capabilities()` reports it
+pub const CAPABILITIES: beethoven_core::Capabilities =
+    beethoven_core::ProtocolKind::SplStakePool.capabilities();
+
+/// Name of each unstake account after the detector, in order, for error and log output.
+pub const UNSTAKE_ACCOUNT_LABELS: &[&str] = &[
+    "stake_pool",
+    "withdraw_authority",
+    "user_transfer_authority",
+    "pool_tokens_from",
+    "reserve_stake",
+    "lamports_to",
+    "manager_fee_account",
+    "pool_mint",
+    "clock",
+    "stake_history",
+    "stake_program",
+    "token_program",
+];
+
+impl<'info> SplStakePoolUnstakeAccounts<'info> {
+    /// The accounts in the order the CPI passes them, without the detector.
+    #[inline(always)]
+    pub fn as_ordered_slice(&self) -> [&'info AccountView; 12] {
+        [
+            self.stake_pool,
+            self.withdraw_authority,
+            self.user_transfer_authority,
+            self.pool_tokens_from,
+            self.reserve_stake,
+            self.lamports_to,
+            self.manager_fee_account,
+            self.pool_mint,
+            self.clock,
+            self.stake_history,
+            self.stake_program,
+            self.token_program,
+        ]
+    }
+
+    /// Iterate over [`Self::as_ordered_slice`].
+    #[inline(always)]
+    pub fn iter(&self) -> impl Iterator<Item = &'info AccountView> {
+        self.as_ordered_slice().into_iter()
+    }
+}
+
+/// `WithdrawSol` pays out of the pool's reserve stake account immediately, so this is
+/// the instant path only and takes no data; pools whose reserve cannot cover `amount`
+/// fail the CPI. Pools with a SOL withdraw authority set are not supported.
+impl<'info> Unstake<'info> for SplStakePool {
+    type Accounts = SplStakePoolUnstakeAccounts<'info>;
+    type Data = ();
+
+    fn unstake_signed(
+        ctx: &SplStakePoolUnstakeAccounts<'info>,
+        amount: u64,
+        _data: &(),
+        signer_seeds: &[Signer],
+    ) -> ProgramResult {
+        #[cfg(feature = "strict-validation")]
+        beethoven_core::validation::check_signer(ctx.user_transfer_authority, signer_seeds)
+            .inspect_err(|_| {
+                beethoven_core::validation::log_account_error(
+                    UNSTAKE_ACCOUNT_LABELS,
+                    "user_transfer_authority",
+                    "missing signature",
+                )
+            })?;
+
+        let accounts = [
+            InstructionAccount::writable(ctx.stake_pool.address()),
+            InstructionAccount::readonly(ctx.withdraw_authority.address()),
+            InstructionAccount::readonly_signer(ctx.user_transfer_authority.address()),
+            InstructionAccount::writable(ctx.pool_tokens_from.address()),
+            InstructionAccount::writable(ctx.reserve_stake.address()),
+            InstructionAccount::writable(ctx.lamports_to.address()),
+            InstructionAccount::writable(ctx.manager_fee_account.address()),
+            InstructionAccount::writable(ctx.pool_mint.address()),
+            InstructionAccount::readonly(ctx.clock.address()),
+            InstructionAccount::readonly(ctx.stake_history.address()),
+            InstructionAccount::readonly(ctx.stake_program.address()),
+            InstructionAccount::readonly(ctx.token_program.address()),
+        ];
+
+        let account_infos = ctx.as_ordered_slice();
+
+        let withdraw_sol_ix = InstructionView {
+            program_id: &SPL_STAKE_POOL_PROGRAM_ID,
+            accounts: &accounts,
+            data: &withdraw_sol_instruction_data(amount),
+        };
+
+        invoke_signed(&withdraw_sol_ix, &account_infos, signer_seeds)
+    }
+
+    #[inline(always)]
+    fn unstake(ctx: &SplStakePoolUnstakeAccounts<'info>, amount: u64, data: &()) -> ProgramResult {
+        Self::unstake_signed(ctx, amount, data, &[])
+    }
+}
+
+/// `WithdrawSol` instruction data.
+///
+/// Layout: tag (u8), pool_tokens (u64)
+#[inline(always)]
+pub fn withdraw_sol_instruction_data(pool_tokens: u64) -> [u8; 9] {
+    let mut data = [0u8; 9];
+    data[0] = WITHDRAW_SOL_TAG;
+    data[1..9].copy_from_slice(&pool_tokens.to_le_bytes());
+    data
+}
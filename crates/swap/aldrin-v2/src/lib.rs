@@ -1,42 +1,80 @@
 #![no_std]
 
+pub use beethoven_core::program_ids::ALDRIN_V2_PROGRAM_ID;
 use {
-    beethoven_core::Swap,
+    beethoven_core::{
+        cpi::invoke_signed,
+        scratch::{data_template, from_template, Scratch},
+        Direction, Swap,
+    },
     core::mem::MaybeUninit,
     solana_account_view::AccountView,
-    solana_address::Address,
-    solana_instruction_view::{
-        cpi::{invoke_signed, Signer},
-        InstructionAccount, InstructionView,
-    },
+    solana_instruction_view::{cpi::Signer, InstructionAccount, InstructionView},
     solana_program_error::{ProgramError, ProgramResult},
 };
 
-pub const ALDRIN_V2_PROGRAM_ID: Address =
-    Address::from_str_const("CURVGoZn8zycx6FXwwevgBTB2gVvdbGTEpvMJDbgs2t4");
-
 const SWAP_DISCRIMINATOR: [u8; 8] = [248, 198, 158, 145, 225, 117, 135, 200];
 
+/// Largest instruction data this adapter builds
+pub const MAX_INSTRUCTION_DATA_LEN: usize = 25;
+
+/// `swap` instruction data with every field after the discriminator zeroed.
+///
+/// Layout: discriminator ([u8; 8]), in_amount (u64), minimum_out_amount (u64), side (u8)
+pub const SWAP_DATA_TEMPLATE: [u8; MAX_INSTRUCTION_DATA_LEN] = data_template(&SWAP_DISCRIMINATOR);
+
 pub struct AldrinV2;
 
 #[repr(u8)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "borsh",
+    derive(borsh::BorshSerialize, borsh::BorshDeserialize),
+    borsh(use_discriminant = true)
+)]
 pub enum Side {
     Bid = 0,
     Ask = 1,
 }
 
+impl From<Direction> for Side {
+    fn from(direction: Direction) -> Self {
+        match direction {
+            Direction::Buy => Side::Bid,
+            Direction::Sell => Side::Ask,
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "borsh",
+    derive(borsh::BorshSerialize, borsh::BorshDeserialize)
+)]
 pub struct AldrinV2SwapData {
     pub side: Side,
 }
 
+impl AldrinV2SwapData {
+    pub const fn new(side: Side) -> Self {
+        Self { side }
+    }
+}
+
+impl From<Side> for AldrinV2SwapData {
+    fn from(side: Side) -> Self {
+        Self { side }
+    }
+}
+
 impl TryFrom<&[u8]> for AldrinV2SwapData {
     type Error = ProgramError;
 
     fn try_from(data: &[u8]) -> Result<Self, Self::Error> {
-        if data.is_empty() {
+        let [side, ..] = data else {
             return Err(ProgramError::InvalidInstructionData);
-        }
-        let side = match data[0] {
+        };
+        let side = match side {
             0 => Side::Bid,
             1 => Side::Ask,
             _ => return Err(ProgramError::InvalidInstructionData),
@@ -63,6 +101,7 @@ pub struct AldrinV2SwapAccounts<'info> {
 impl<'info> TryFrom<&'info [AccountView]> for AldrinV2SwapAccounts<'info> {
     type Error = ProgramError;
 
+    #[inline(always)]
     fn try_from(accounts: &'info [AccountView]) -> Result<Self, Self::Error> {
         if accounts.len() < 12 {
             return Err(ProgramError::NotEnoughAccountKeys);
@@ -74,6 +113,14 @@ impl<'info> TryFrom<&'info [AccountView]> for AldrinV2SwapAccounts<'info> {
             return Err(ProgramError::NotEnoughAccountKeys);
         };
 
+        #[cfg(feature = "strict-validation")]
+        beethoven_core::validation::check_fixed_accounts(
+            ACCOUNT_LABELS,
+            (aldrin_v2_program, &ALDRIN_V2_PROGRAM_ID),
+            &[("token_program", token_program)],
+            None,
+        )?;
+
         Ok(AldrinV2SwapAccounts {
             aldrin_v2_program,
             pool,
@@ -91,17 +138,102 @@ impl<'info> TryFrom<&'info [AccountView]> for AldrinV2SwapAccounts<'info> {
     }
 }
 
-impl<'info> Swap<'info> for AldrinV2 {
-    type Accounts = AldrinV2SwapAccounts<'info>;
-    type Data = AldrinV2SwapData;
+/// What this adapter supports, as `ProtocolKind::AldrinV2.capabilities()` reports it
+pub const CAPABILITIES: beethoven_core::Capabilities =
+    beethoven_core::ProtocolKind::AldrinV2.capabilities();
 
-    fn swap_signed(
-        ctx: &Self::Accounts,
+/// Name of each account after the detector, in order, for error and log output.
+pub const ACCOUNT_LABELS: &[&str] = &[
+    "pool",
+    "pool_signer",
+    "pool_mint",
+    "base_token_vault",
+    "quote_token_vault",
+    "fee_pool_token_account",
+    "wallet_authority",
+    "user_base_token_account",
+    "user_quote_token_account",
+    "curve",
+    "token_program",
+];
+
+impl<'info> AldrinV2SwapAccounts<'info> {
+    /// The accounts in the order the CPI passes them, without the detector.
+    #[inline(always)]
+    pub fn as_ordered_slice(&self) -> [&'info AccountView; 11] {
+        [
+            self.pool,
+            self.pool_signer,
+            self.pool_mint,
+            self.base_token_vault,
+            self.quote_token_vault,
+            self.fee_pool_token_account,
+            self.wallet_authority,
+            self.user_base_token_account,
+            self.user_quote_token_account,
+            self.curve,
+            self.token_program,
+        ]
+    }
+
+    /// Iterate over [`Self::as_ordered_slice`].
+    #[inline(always)]
+    pub fn iter(&self) -> impl Iterator<Item = &'info AccountView> {
+        self.as_ordered_slice().into_iter()
+    }
+}
+
+impl AldrinV2SwapAccounts<'_> {
+    /// [`check_source_mint`](beethoven_core::validation::check_source_mint) on the user
+    /// account and pool vault `side` spends from: the quote token for Bid, the base token
+    /// for Ask.
+    pub fn check_side(&self, side: &Side) -> ProgramResult {
+        let (user_source, source_vault, label) = match side {
+            Side::Bid => (
+                self.user_quote_token_account,
+                self.quote_token_vault,
+                "user_quote_token_account",
+            ),
+            Side::Ask => (
+                self.user_base_token_account,
+                self.base_token_vault,
+                "user_base_token_account",
+            ),
+        };
+
+        beethoven_core::validation::check_source_mint(
+            ACCOUNT_LABELS,
+            label,
+            user_source,
+            source_vault,
+        )
+    }
+}
+
+impl AldrinV2 {
+    /// [`Swap::swap_signed`] with the instruction data assembled in caller-provided
+    /// `scratch` (at least [`MAX_INSTRUCTION_DATA_LEN`] bytes) instead of this frame.
+    pub fn swap_signed_with_scratch(
+        ctx: &AldrinV2SwapAccounts<'_>,
         in_amount: u64,
         minimum_out_amount: u64,
-        data: &Self::Data,
+        data: &AldrinV2SwapData,
         signer_seeds: &[Signer],
+        scratch: &mut [MaybeUninit<u8>],
     ) -> ProgramResult {
+        #[cfg(feature = "strict-validation")]
+        beethoven_core::validation::check_signer(ctx.wallet_authority, signer_seeds).inspect_err(
+            |_| {
+                beethoven_core::validation::log_account_error(
+                    ACCOUNT_LABELS,
+                    "wallet_authority",
+                    "missing signature",
+                )
+            },
+        )?;
+        #[cfg(feature = "strict-validation")]
+        ctx.check_side(&data.side)?;
+
         let accounts = [
             InstructionAccount::readonly(ctx.pool.address()),
             InstructionAccount::readonly(ctx.pool_signer.address()),
@@ -116,48 +248,41 @@ impl<'info> Swap<'info> for AldrinV2 {
             InstructionAccount::readonly(ctx.token_program.address()),
         ];
 
-        let account_infos = [
-            ctx.pool,
-            ctx.pool_signer,
-            ctx.pool_mint,
-            ctx.base_token_vault,
-            ctx.quote_token_vault,
-            ctx.fee_pool_token_account,
-            ctx.wallet_authority,
-            ctx.user_base_token_account,
-            ctx.user_quote_token_account,
-            ctx.curve,
-            ctx.token_program,
-        ];
-
-        let mut instruction_data = MaybeUninit::<[u8; 25]>::uninit();
-        unsafe {
-            let ptr = instruction_data.as_mut_ptr() as *mut u8;
-            core::ptr::copy_nonoverlapping(SWAP_DISCRIMINATOR.as_ptr(), ptr, 8);
-            core::ptr::copy_nonoverlapping(in_amount.to_le_bytes().as_ptr(), ptr.add(8), 8);
-            core::ptr::copy_nonoverlapping(
-                minimum_out_amount.to_le_bytes().as_ptr(),
-                ptr.add(16),
-                8,
-            );
-            let side_byte = match data.side {
-                Side::Bid => 0u8,
-                Side::Ask => 1u8,
-            };
-            core::ptr::write(ptr.add(24), side_byte);
-        }
+        let account_infos = ctx.as_ordered_slice();
 
         let instruction = InstructionView {
             program_id: &ALDRIN_V2_PROGRAM_ID,
             accounts: &accounts,
-            data: unsafe {
-                core::slice::from_raw_parts(instruction_data.as_ptr() as *const u8, 25)
-            },
+            data: swap_instruction_data(scratch, in_amount, minimum_out_amount, data)?,
         };
 
         invoke_signed(&instruction, &account_infos, signer_seeds)
     }
+}
+
+impl<'info> Swap<'info> for AldrinV2 {
+    type Accounts = AldrinV2SwapAccounts<'info>;
+    type Data = AldrinV2SwapData;
+
+    fn swap_signed(
+        ctx: &Self::Accounts,
+        in_amount: u64,
+        minimum_out_amount: u64,
+        data: &Self::Data,
+        signer_seeds: &[Signer],
+    ) -> ProgramResult {
+        let mut scratch = Scratch::<MAX_INSTRUCTION_DATA_LEN>::new();
+        Self::swap_signed_with_scratch(
+            ctx,
+            in_amount,
+            minimum_out_amount,
+            data,
+            signer_seeds,
+            scratch.as_uninit_mut(),
+        )
+    }
 
+    #[inline(always)]
     fn swap(
         ctx: &Self::Accounts,
         in_amount: u64,
@@ -167,3 +292,21 @@ impl<'info> Swap<'info> for AldrinV2 {
         Self::swap_signed(ctx, in_amount, minimum_out_amount, data, &[])
     }
 }
+
+/// Write the `swap` instruction data into `scratch` by patching [`SWAP_DATA_TEMPLATE`].
+#[inline(always)]
+pub fn swap_instruction_data<'a>(
+    scratch: &'a mut [MaybeUninit<u8>],
+    in_amount: u64,
+    minimum_out_amount: u64,
+    swap_data: &AldrinV2SwapData,
+) -> Result<&'a [u8], ProgramError> {
+    let data = from_template(scratch, &SWAP_DATA_TEMPLATE)?;
+    data[8..16].copy_from_slice(&in_amount.to_le_bytes());
+    data[16..24].copy_from_slice(&minimum_out_amount.to_le_bytes());
+    data[24] = match swap_data.side {
+        Side::Bid => 0,
+        Side::Ask => 1,
+    };
+    Ok(data)
+}
@@ -0,0 +1,58 @@
+//! Constant-product quoting over Aldrin pool state.
+
+use crate::state::Fees;
+
+/// Result of selling an exact input amount into a pool
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ExactInQuote {
+    /// Output the swap transfers to the user
+    pub out_amount: u64,
+    /// Part of the input kept by the pool and its owner as trade fees
+    pub fee_amount: u64,
+}
+
+/// Quote selling `amount_in` against `input_reserve` / `output_reserve`.
+///
+/// The trade and owner fees are each rounded up and taken from the input; the output is
+/// rounded down. Returns `None` on overflow, a zero fee denominator or an empty pool.
+pub fn quote_exact_in(
+    fees: &Fees,
+    amount_in: u64,
+    input_reserve: u64,
+    output_reserve: u64,
+) -> Option<ExactInQuote> {
+    let trade_fee = fee(
+        amount_in,
+        fees.trade_fee_numerator.get(),
+        fees.trade_fee_denominator.get(),
+    )?;
+    let owner_fee = fee(
+        amount_in,
+        fees.owner_trade_fee_numerator.get(),
+        fees.owner_trade_fee_denominator.get(),
+    )?;
+    let fee_amount = trade_fee.checked_add(owner_fee)?;
+    let amount_in_after_fee = (amount_in as u128).checked_sub(fee_amount)?;
+
+    let denominator = (input_reserve as u128).checked_add(amount_in_after_fee)?;
+    if denominator == 0 {
+        return None;
+    }
+    let out_amount = amount_in_after_fee.checked_mul(output_reserve as u128)? / denominator;
+
+    Some(ExactInQuote {
+        out_amount: u64::try_from(out_amount).ok()?,
+        fee_amount: u64::try_from(fee_amount).ok()?,
+    })
+}
+
+/// `amount` times `numerator / denominator`, rounded up.
+fn fee(amount: u64, numerator: u64, denominator: u64) -> Option<u128> {
+    if numerator == 0 {
+        return Some(0);
+    }
+    if denominator == 0 {
+        return None;
+    }
+    Some((amount as u128 * numerator as u128).div_ceil(denominator as u128))
+}
@@ -0,0 +1,60 @@
+//! Zero-copy reader over an Aldrin pool, for quoting against it on-chain.
+//!
+//! Only the prefix of the account needed to compute a swap is mapped; offsets are
+//! relative to the start of the account data, after the Anchor discriminator.
+
+use {
+    beethoven_core::zero_copy::{load_account, PodU64, ZeroCopy},
+    core::mem::offset_of,
+    solana_address::Address,
+    solana_program_error::ProgramError,
+};
+
+pub const POOL_DISCRIMINATOR: [u8; 8] = [241, 154, 109, 4, 17, 177, 109, 188];
+
+/// Leading fields of an Aldrin `Pool` account
+#[repr(C)]
+pub struct Pool {
+    pub lp_token_freeze_vault: Address,
+    pub pool_mint: Address,
+    pub pool_signer: Address,
+    pub pool_signer_nonce: u8,
+    pub authority: Address,
+    pub initializer_account: Address,
+    pub fee_base_account: Address,
+    pub fee_quote_account: Address,
+    pub fee_pool_token_account: Address,
+    pub base_token_vault: Address,
+    pub base_token_mint: Address,
+    pub quote_token_vault: Address,
+    pub quote_token_mint: Address,
+    pub fees: Fees,
+}
+
+/// Fee fractions of an Aldrin pool, each a numerator over a denominator
+#[repr(C)]
+pub struct Fees {
+    pub trade_fee_numerator: PodU64,
+    pub trade_fee_denominator: PodU64,
+    pub owner_trade_fee_numerator: PodU64,
+    pub owner_trade_fee_denominator: PodU64,
+    pub owner_withdraw_fee_numerator: PodU64,
+    pub owner_withdraw_fee_denominator: PodU64,
+}
+
+const _: () = {
+    assert!(offset_of!(Pool, pool_signer_nonce) == 96);
+    assert!(offset_of!(Pool, base_token_vault) == 257);
+    assert!(offset_of!(Pool, quote_token_vault) == 321);
+    assert!(offset_of!(Pool, fees) == 385);
+};
+
+// SAFETY: `#[repr(C)]` over alignment-1 fields, valid for every bit pattern.
+unsafe impl ZeroCopy for Pool {}
+
+impl Pool {
+    /// View `data` (a `Pool` account's full data) in place.
+    pub fn from_account_data(data: &[u8]) -> Result<&Self, ProgramError> {
+        load_account(data, &POOL_DISCRIMINATOR)
+    }
+}
@@ -1,42 +1,81 @@
 #![no_std]
 
+pub use beethoven_core::program_ids::FUTARCHY_PROGRAM_ID;
 use {
-    beethoven_core::Swap,
+    beethoven_core::{
+        cpi::invoke_signed,
+        scratch::{data_template, from_template, Scratch},
+        Direction, Swap,
+    },
     core::mem::MaybeUninit,
     solana_account_view::AccountView,
-    solana_address::Address,
-    solana_instruction_view::{
-        cpi::{invoke_signed, Signer},
-        InstructionAccount, InstructionView,
-    },
+    solana_instruction_view::{cpi::Signer, InstructionAccount, InstructionView},
     solana_program_error::{ProgramError, ProgramResult},
 };
 
-pub const FUTARCHY_PROGRAM_ID: Address =
-    Address::from_str_const("FUTARELBfJfQ8RDGhg1wdhddq1odMAJUePHFuBYfUxKq");
-
 const SWAP_DISCRIMINATOR: [u8; 8] = [167, 97, 12, 231, 237, 78, 166, 251];
 
+/// Largest instruction data this adapter builds
+pub const MAX_INSTRUCTION_DATA_LEN: usize = 25;
+
+/// `swap` instruction data with every field after the discriminator zeroed.
+///
+/// Layout: discriminator ([u8; 8]), in_amount (u64), swap_type (u8),
+/// minimum_out_amount (u64)
+pub const SWAP_DATA_TEMPLATE: [u8; MAX_INSTRUCTION_DATA_LEN] = data_template(&SWAP_DISCRIMINATOR);
+
 pub struct Futarchy;
 
 #[repr(u8)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "borsh",
+    derive(borsh::BorshSerialize, borsh::BorshDeserialize),
+    borsh(use_discriminant = true)
+)]
 pub enum SwapType {
     Buy = 0,
     Sell = 1,
 }
 
+impl From<Direction> for SwapType {
+    fn from(direction: Direction) -> Self {
+        match direction {
+            Direction::Buy => SwapType::Buy,
+            Direction::Sell => SwapType::Sell,
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "borsh",
+    derive(borsh::BorshSerialize, borsh::BorshDeserialize)
+)]
 pub struct FutarchySwapData {
     pub swap_type: SwapType,
 }
 
+impl FutarchySwapData {
+    pub const fn new(swap_type: SwapType) -> Self {
+        Self { swap_type }
+    }
+}
+
+impl From<SwapType> for FutarchySwapData {
+    fn from(swap_type: SwapType) -> Self {
+        Self { swap_type }
+    }
+}
+
 impl TryFrom<&[u8]> for FutarchySwapData {
     type Error = ProgramError;
 
     fn try_from(data: &[u8]) -> Result<Self, Self::Error> {
-        if data.is_empty() {
+        let [swap_type, ..] = data else {
             return Err(ProgramError::InvalidInstructionData);
-        }
-        let swap_type = match data[0] {
+        };
+        let swap_type = match swap_type {
             0 => SwapType::Buy,
             1 => SwapType::Sell,
             _ => return Err(ProgramError::InvalidInstructionData),
@@ -61,6 +100,7 @@ pub struct FutarchySwapAccounts<'info> {
 impl<'info> TryFrom<&'info [AccountView]> for FutarchySwapAccounts<'info> {
     type Error = ProgramError;
 
+    #[inline(always)]
     fn try_from(accounts: &'info [AccountView]) -> Result<Self, Self::Error> {
         if accounts.len() < 10 {
             return Err(ProgramError::NotEnoughAccountKeys);
@@ -72,6 +112,14 @@ impl<'info> TryFrom<&'info [AccountView]> for FutarchySwapAccounts<'info> {
             return Err(ProgramError::NotEnoughAccountKeys);
         };
 
+        #[cfg(feature = "strict-validation")]
+        beethoven_core::validation::check_fixed_accounts(
+            ACCOUNT_LABELS,
+            (futarchy_program, &FUTARCHY_PROGRAM_ID),
+            &[("token_program", token_program)],
+            None,
+        )?;
+
         Ok(FutarchySwapAccounts {
             futarchy_program,
             dao,
@@ -87,17 +135,96 @@ impl<'info> TryFrom<&'info [AccountView]> for FutarchySwapAccounts<'info> {
     }
 }
 
-impl<'info> Swap<'info> for Futarchy {
-    type Accounts = FutarchySwapAccounts<'info>;
-    type Data = FutarchySwapData;
+/// What this adapter supports, as `ProtocolKind::Futarchy.capabilities()` reports it
+pub const CAPABILITIES: beethoven_core::Capabilities =
+    beethoven_core::ProtocolKind::Futarchy.capabilities();
 
-    fn swap_signed(
-        ctx: &Self::Accounts,
+/// Name of each account after the detector, in order, for error and log output.
+pub const ACCOUNT_LABELS: &[&str] = &[
+    "dao",
+    "user_base_account",
+    "user_quote_account",
+    "amm_base_vault",
+    "amm_quote_vault",
+    "user",
+    "token_program",
+    "event_authority",
+    "program",
+];
+
+impl<'info> FutarchySwapAccounts<'info> {
+    /// The accounts in the order the CPI passes them, without the detector.
+    #[inline(always)]
+    pub fn as_ordered_slice(&self) -> [&'info AccountView; 9] {
+        [
+            self.dao,
+            self.user_base_account,
+            self.user_quote_account,
+            self.amm_base_vault,
+            self.amm_quote_vault,
+            self.user,
+            self.token_program,
+            self.event_authority,
+            self.program,
+        ]
+    }
+
+    /// Iterate over [`Self::as_ordered_slice`].
+    #[inline(always)]
+    pub fn iter(&self) -> impl Iterator<Item = &'info AccountView> {
+        self.as_ordered_slice().into_iter()
+    }
+}
+
+impl FutarchySwapAccounts<'_> {
+    /// [`check_source_mint`](beethoven_core::validation::check_source_mint) on the user
+    /// account and AMM vault `swap_type` spends from: the quote token for Buy, the base
+    /// token for Sell.
+    pub fn check_swap_type(&self, swap_type: &SwapType) -> ProgramResult {
+        let (user_source, source_vault, label) = match swap_type {
+            SwapType::Buy => (
+                self.user_quote_account,
+                self.amm_quote_vault,
+                "user_quote_account",
+            ),
+            SwapType::Sell => (
+                self.user_base_account,
+                self.amm_base_vault,
+                "user_base_account",
+            ),
+        };
+
+        beethoven_core::validation::check_source_mint(
+            ACCOUNT_LABELS,
+            label,
+            user_source,
+            source_vault,
+        )
+    }
+}
+
+impl Futarchy {
+    /// [`Swap::swap_signed`] with the instruction data assembled in caller-provided
+    /// `scratch` (at least [`MAX_INSTRUCTION_DATA_LEN`] bytes) instead of this frame.
+    pub fn swap_signed_with_scratch(
+        ctx: &FutarchySwapAccounts<'_>,
         in_amount: u64,
         minimum_out_amount: u64,
-        data: &Self::Data,
+        data: &FutarchySwapData,
         signer_seeds: &[Signer],
+        scratch: &mut [MaybeUninit<u8>],
     ) -> ProgramResult {
+        #[cfg(feature = "strict-validation")]
+        beethoven_core::validation::check_signer(ctx.user, signer_seeds).inspect_err(|_| {
+            beethoven_core::validation::log_account_error(
+                ACCOUNT_LABELS,
+                "user",
+                "missing signature",
+            )
+        })?;
+        #[cfg(feature = "strict-validation")]
+        ctx.check_swap_type(&data.swap_type)?;
+
         let accounts = [
             InstructionAccount::writable(ctx.dao.address()),
             InstructionAccount::writable(ctx.user_base_account.address()),
@@ -110,46 +237,41 @@ impl<'info> Swap<'info> for Futarchy {
             InstructionAccount::readonly(ctx.program.address()),
         ];
 
-        let account_infos = [
-            ctx.dao,
-            ctx.user_base_account,
-            ctx.user_quote_account,
-            ctx.amm_base_vault,
-            ctx.amm_quote_vault,
-            ctx.user,
-            ctx.token_program,
-            ctx.event_authority,
-            ctx.program,
-        ];
-
-        let mut instruction_data = MaybeUninit::<[u8; 25]>::uninit();
-        unsafe {
-            let ptr = instruction_data.as_mut_ptr() as *mut u8;
-            core::ptr::copy_nonoverlapping(SWAP_DISCRIMINATOR.as_ptr(), ptr, 8);
-            core::ptr::copy_nonoverlapping(in_amount.to_le_bytes().as_ptr(), ptr.add(8), 8);
-            let swap_type_byte = match data.swap_type {
-                SwapType::Buy => 0u8,
-                SwapType::Sell => 1u8,
-            };
-            core::ptr::write(ptr.add(16), swap_type_byte);
-            core::ptr::copy_nonoverlapping(
-                minimum_out_amount.to_le_bytes().as_ptr(),
-                ptr.add(17),
-                8,
-            );
-        }
+        let account_infos = ctx.as_ordered_slice();
 
         let instruction = InstructionView {
             program_id: &FUTARCHY_PROGRAM_ID,
             accounts: &accounts,
-            data: unsafe {
-                core::slice::from_raw_parts(instruction_data.as_ptr() as *const u8, 25)
-            },
+            data: swap_instruction_data(scratch, in_amount, minimum_out_amount, data)?,
         };
 
         invoke_signed(&instruction, &account_infos, signer_seeds)
     }
+}
 
+impl<'info> Swap<'info> for Futarchy {
+    type Accounts = FutarchySwapAccounts<'info>;
+    type Data = FutarchySwapData;
+
+    fn swap_signed(
+        ctx: &Self::Accounts,
+        in_amount: u64,
+        minimum_out_amount: u64,
+        data: &Self::Data,
+        signer_seeds: &[Signer],
+    ) -> ProgramResult {
+        let mut scratch = Scratch::<MAX_INSTRUCTION_DATA_LEN>::new();
+        Self::swap_signed_with_scratch(
+            ctx,
+            in_amount,
+            minimum_out_amount,
+            data,
+            signer_seeds,
+            scratch.as_uninit_mut(),
+        )
+    }
+
+    #[inline(always)]
     fn swap(
         ctx: &Self::Accounts,
         in_amount: u64,
@@ -159,3 +281,21 @@ impl<'info> Swap<'info> for Futarchy {
         Self::swap_signed(ctx, in_amount, minimum_out_amount, data, &[])
     }
 }
+
+/// Write the `swap` instruction data into `scratch` by patching [`SWAP_DATA_TEMPLATE`].
+#[inline(always)]
+pub fn swap_instruction_data<'a>(
+    scratch: &'a mut [MaybeUninit<u8>],
+    in_amount: u64,
+    minimum_out_amount: u64,
+    swap_data: &FutarchySwapData,
+) -> Result<&'a [u8], ProgramError> {
+    let data = from_template(scratch, &SWAP_DATA_TEMPLATE)?;
+    data[8..16].copy_from_slice(&in_amount.to_le_bytes());
+    data[16] = match swap_data.swap_type {
+        SwapType::Buy => 0,
+        SwapType::Sell => 1,
+    };
+    data[17..25].copy_from_slice(&minimum_out_amount.to_le_bytes());
+    Ok(data)
+}
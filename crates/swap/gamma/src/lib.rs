@@ -1,22 +1,33 @@
 #![no_std]
 
+pub mod quote;
+pub mod state;
+
+pub use beethoven_core::program_ids::GAMMA_PROGRAM_ID;
 use {
-    beethoven_core::Swap,
+    beethoven_core::{
+        cpi::invoke_signed,
+        scratch::{data_template, from_template, Scratch},
+        token::token_account_amount,
+        Quote, Swap,
+    },
     core::mem::MaybeUninit,
     solana_account_view::AccountView,
-    solana_address::Address,
-    solana_instruction_view::{
-        cpi::{invoke_signed, Signer},
-        InstructionAccount, InstructionView,
-    },
+    solana_address::address_eq,
+    solana_instruction_view::{cpi::Signer, InstructionAccount, InstructionView},
     solana_program_error::{ProgramError, ProgramResult},
 };
 
-pub const GAMMA_PROGRAM_ID: Address =
-    Address::from_str_const("GAMMA7meSFWaBXF25oSUgmGRwaW6sCMFLmBNiMSdbHVT");
-
 const SWAP_DISCRIMINATOR: [u8; 8] = [239, 82, 192, 187, 160, 26, 223, 223];
 
+/// Largest instruction data this adapter builds
+pub const MAX_INSTRUCTION_DATA_LEN: usize = 24;
+
+/// `swap` instruction data with every field after the discriminator zeroed.
+///
+/// Layout: discriminator ([u8; 8]), in_amount (u64), minimum_out_amount (u64)
+pub const SWAP_DATA_TEMPLATE: [u8; MAX_INSTRUCTION_DATA_LEN] = data_template(&SWAP_DISCRIMINATOR);
+
 pub struct Gamma;
 
 pub struct GammaSwapAccounts<'info> {
@@ -39,6 +50,7 @@ pub struct GammaSwapAccounts<'info> {
 impl<'info> TryFrom<&'info [AccountView]> for GammaSwapAccounts<'info> {
     type Error = ProgramError;
 
+    #[inline(always)]
     fn try_from(accounts: &'info [AccountView]) -> Result<Self, Self::Error> {
         if accounts.len() < 14 {
             return Err(ProgramError::NotEnoughAccountKeys);
@@ -50,6 +62,17 @@ impl<'info> TryFrom<&'info [AccountView]> for GammaSwapAccounts<'info> {
             return Err(ProgramError::NotEnoughAccountKeys);
         };
 
+        #[cfg(feature = "strict-validation")]
+        beethoven_core::validation::check_fixed_accounts(
+            ACCOUNT_LABELS,
+            (gamma_program, &GAMMA_PROGRAM_ID),
+            &[
+                ("input_token_program", input_token_program),
+                ("output_token_program", output_token_program),
+            ],
+            None,
+        )?;
+
         Ok(GammaSwapAccounts {
             gamma_program,
             payer,
@@ -69,17 +92,75 @@ impl<'info> TryFrom<&'info [AccountView]> for GammaSwapAccounts<'info> {
     }
 }
 
-impl<'info> Swap<'info> for Gamma {
-    type Accounts = GammaSwapAccounts<'info>;
-    type Data = ();
+/// What this adapter supports, as `ProtocolKind::Gamma.capabilities()` reports it
+pub const CAPABILITIES: beethoven_core::Capabilities =
+    beethoven_core::ProtocolKind::Gamma.capabilities();
 
-    fn swap_signed(
-        ctx: &Self::Accounts,
+/// Name of each account after the detector, in order, for error and log output.
+pub const ACCOUNT_LABELS: &[&str] = &[
+    "payer",
+    "authority",
+    "amm_config",
+    "pool_state",
+    "input_token_account",
+    "output_token_account",
+    "input_vault",
+    "output_vault",
+    "input_token_program",
+    "output_token_program",
+    "input_token_mint",
+    "output_token_mint",
+    "observation_state",
+];
+
+impl<'info> GammaSwapAccounts<'info> {
+    /// The accounts in the order the CPI passes them, without the detector.
+    #[inline(always)]
+    pub fn as_ordered_slice(&self) -> [&'info AccountView; 13] {
+        [
+            self.payer,
+            self.authority,
+            self.amm_config,
+            self.pool_state,
+            self.input_token_account,
+            self.output_token_account,
+            self.input_vault,
+            self.output_vault,
+            self.input_token_program,
+            self.output_token_program,
+            self.input_token_mint,
+            self.output_token_mint,
+            self.observation_state,
+        ]
+    }
+
+    /// Iterate over [`Self::as_ordered_slice`].
+    #[inline(always)]
+    pub fn iter(&self) -> impl Iterator<Item = &'info AccountView> {
+        self.as_ordered_slice().into_iter()
+    }
+}
+
+impl Gamma {
+    /// [`Swap::swap_signed`] with the instruction data assembled in caller-provided
+    /// `scratch` (at least [`MAX_INSTRUCTION_DATA_LEN`] bytes) instead of this frame.
+    pub fn swap_signed_with_scratch(
+        ctx: &GammaSwapAccounts<'_>,
         in_amount: u64,
         minimum_out_amount: u64,
         _data: &(),
         signer_seeds: &[Signer],
+        scratch: &mut [MaybeUninit<u8>],
     ) -> ProgramResult {
+        #[cfg(feature = "strict-validation")]
+        beethoven_core::validation::check_signer(ctx.payer, signer_seeds).inspect_err(|_| {
+            beethoven_core::validation::log_account_error(
+                ACCOUNT_LABELS,
+                "payer",
+                "missing signature",
+            )
+        })?;
+
         let accounts = [
             InstructionAccount::readonly_signer(ctx.payer.address()),
             InstructionAccount::readonly(ctx.authority.address()),
@@ -96,45 +177,41 @@ impl<'info> Swap<'info> for Gamma {
             InstructionAccount::writable(ctx.observation_state.address()),
         ];
 
-        let account_infos = [
-            ctx.payer,
-            ctx.authority,
-            ctx.amm_config,
-            ctx.pool_state,
-            ctx.input_token_account,
-            ctx.output_token_account,
-            ctx.input_vault,
-            ctx.output_vault,
-            ctx.input_token_program,
-            ctx.output_token_program,
-            ctx.input_token_mint,
-            ctx.output_token_mint,
-            ctx.observation_state,
-        ];
-
-        let mut instruction_data = MaybeUninit::<[u8; 24]>::uninit();
-        unsafe {
-            let ptr = instruction_data.as_mut_ptr() as *mut u8;
-            core::ptr::copy_nonoverlapping(SWAP_DISCRIMINATOR.as_ptr(), ptr, 8);
-            core::ptr::copy_nonoverlapping(in_amount.to_le_bytes().as_ptr(), ptr.add(8), 8);
-            core::ptr::copy_nonoverlapping(
-                minimum_out_amount.to_le_bytes().as_ptr(),
-                ptr.add(16),
-                8,
-            );
-        }
+        let account_infos = ctx.as_ordered_slice();
 
         let instruction = InstructionView {
             program_id: &GAMMA_PROGRAM_ID,
             accounts: &accounts,
-            data: unsafe {
-                core::slice::from_raw_parts(instruction_data.as_ptr() as *const u8, 24)
-            },
+            data: swap_instruction_data(scratch, in_amount, minimum_out_amount)?,
         };
 
         invoke_signed(&instruction, &account_infos, signer_seeds)
     }
+}
+
+impl<'info> Swap<'info> for Gamma {
+    type Accounts = GammaSwapAccounts<'info>;
+    type Data = ();
 
+    fn swap_signed(
+        ctx: &Self::Accounts,
+        in_amount: u64,
+        minimum_out_amount: u64,
+        data: &(),
+        signer_seeds: &[Signer],
+    ) -> ProgramResult {
+        let mut scratch = Scratch::<MAX_INSTRUCTION_DATA_LEN>::new();
+        Self::swap_signed_with_scratch(
+            ctx,
+            in_amount,
+            minimum_out_amount,
+            data,
+            signer_seeds,
+            scratch.as_uninit_mut(),
+        )
+    }
+
+    #[inline(always)]
     fn swap(
         ctx: &Self::Accounts,
         in_amount: u64,
@@ -144,3 +221,43 @@ impl<'info> Swap<'info> for Gamma {
         Self::swap_signed(ctx, in_amount, minimum_out_amount, data, &[])
     }
 }
+
+/// Quotes against the pool's vault balances less accrued fees, with the same math as
+/// the off-chain client.
+impl<'info> Quote<'info> for Gamma {
+    fn quote(ctx: &Self::Accounts, in_amount: u64, _data: &()) -> Result<u64, ProgramError> {
+        let input_vault_amount = token_account_amount(ctx.input_vault)?;
+        let output_vault_amount = token_account_amount(ctx.output_vault)?;
+
+        let pool_data = ctx.pool_state.try_borrow()?;
+        let pool = state::PoolState::from_account_data(&pool_data)?;
+        let config_data = ctx.amm_config.try_borrow()?;
+        let config = state::AmmConfig::from_account_data(&config_data)?;
+
+        let input_is_token_0 = address_eq(ctx.input_vault.address(), &pool.token_0_vault);
+        let (input_reserve, output_reserve) = quote::reserves(
+            pool,
+            input_is_token_0,
+            input_vault_amount,
+            output_vault_amount,
+        )
+        .ok_or(ProgramError::InvalidAccountData)?;
+
+        quote::quote_exact_in(config, in_amount, input_reserve, output_reserve)
+            .map(|quote| quote.out_amount)
+            .ok_or(ProgramError::InvalidAccountData)
+    }
+}
+
+/// Write the `swap` instruction data into `scratch` by patching [`SWAP_DATA_TEMPLATE`].
+#[inline(always)]
+pub fn swap_instruction_data(
+    scratch: &mut [MaybeUninit<u8>],
+    in_amount: u64,
+    minimum_out_amount: u64,
+) -> Result<&[u8], ProgramError> {
+    let data = from_template(scratch, &SWAP_DATA_TEMPLATE)?;
+    data[8..16].copy_from_slice(&in_amount.to_le_bytes());
+    data[16..24].copy_from_slice(&minimum_out_amount.to_le_bytes());
+    Ok(data)
+}
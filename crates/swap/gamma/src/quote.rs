@@ -0,0 +1,56 @@
+//! Constant-product quoting over Gamma pool state.
+//!
+//! Shared by on-chain callers reading [`PoolState`] / [`AmmConfig`] in place and the
+//! off-chain client, so both compute the same output for the same accounts.
+
+use crate::state::{AmmConfig, PoolState, FEE_RATE_DENOMINATOR};
+
+/// Result of selling an exact input amount into a pool
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ExactInQuote {
+    /// Output the swap transfers to the user
+    pub out_amount: u64,
+    /// Part of the input kept by the pool as the trade fee
+    pub trade_fee: u64,
+}
+
+/// Tradable reserves of `pool` from its vault balances, excluding accrued fees.
+///
+/// Returns `(input_reserve, output_reserve)`.
+pub fn reserves(
+    pool: &PoolState,
+    input_is_token_0: bool,
+    input_vault_amount: u64,
+    output_vault_amount: u64,
+) -> Option<(u64, u64)> {
+    Some((
+        input_vault_amount.checked_sub(pool.accrued_fees(input_is_token_0))?,
+        output_vault_amount.checked_sub(pool.accrued_fees(!input_is_token_0))?,
+    ))
+}
+
+/// Quote selling `amount_in` against `input_reserve` / `output_reserve`.
+///
+/// The trade fee is rounded up and taken from the input; the output is rounded down.
+/// Returns `None` on overflow or an empty pool.
+pub fn quote_exact_in(
+    config: &AmmConfig,
+    amount_in: u64,
+    input_reserve: u64,
+    output_reserve: u64,
+) -> Option<ExactInQuote> {
+    let trade_fee = (amount_in as u128 * config.trade_fee_rate.get() as u128)
+        .div_ceil(FEE_RATE_DENOMINATOR as u128);
+    let amount_in_after_fee = (amount_in as u128).checked_sub(trade_fee)?;
+
+    let denominator = (input_reserve as u128).checked_add(amount_in_after_fee)?;
+    if denominator == 0 {
+        return None;
+    }
+    let out_amount = amount_in_after_fee.checked_mul(output_reserve as u128)? / denominator;
+
+    Some(ExactInQuote {
+        out_amount: u64::try_from(out_amount).ok()?,
+        trade_fee: u64::try_from(trade_fee).ok()?,
+    })
+}
@@ -0,0 +1,101 @@
+//! Zero-copy readers over Gamma pool state, for quoting against the pool on-chain.
+//!
+//! Only the prefix of each account needed to compute a swap is mapped; offsets are
+//! relative to the start of the account data, after the Anchor discriminator.
+
+use {
+    beethoven_core::zero_copy::{load_account, PodU16, PodU64, ZeroCopy},
+    core::mem::offset_of,
+    solana_address::Address,
+    solana_program_error::ProgramError,
+};
+
+pub const POOL_STATE_DISCRIMINATOR: [u8; 8] = [247, 237, 227, 245, 215, 195, 222, 70];
+pub const AMM_CONFIG_DISCRIMINATOR: [u8; 8] = [218, 244, 33, 104, 203, 203, 43, 111];
+
+/// Denominator of the fee rates in [`AmmConfig`] (rates are in hundredths of a bip)
+pub const FEE_RATE_DENOMINATOR: u64 = 1_000_000;
+
+/// Leading fields of a Gamma `PoolState` account
+#[repr(C)]
+pub struct PoolState {
+    pub amm_config: Address,
+    pub pool_creator: Address,
+    pub token_0_vault: Address,
+    pub token_1_vault: Address,
+    pub token_0_mint: Address,
+    pub token_1_mint: Address,
+    pub token_0_program: Address,
+    pub token_1_program: Address,
+    pub observation_key: Address,
+    pub auth_bump: u8,
+    pub status: u8,
+    pub mint_0_decimals: u8,
+    pub mint_1_decimals: u8,
+    pub lp_supply: PodU64,
+    pub protocol_fees_token_0: PodU64,
+    pub protocol_fees_token_1: PodU64,
+    pub fund_fees_token_0: PodU64,
+    pub fund_fees_token_1: PodU64,
+    pub open_time: PodU64,
+}
+
+const _: () = {
+    assert!(offset_of!(PoolState, token_0_vault) == 64);
+    assert!(offset_of!(PoolState, token_0_mint) == 128);
+    assert!(offset_of!(PoolState, auth_bump) == 288);
+    assert!(offset_of!(PoolState, lp_supply) == 292);
+    assert!(offset_of!(PoolState, protocol_fees_token_0) == 300);
+    assert!(offset_of!(PoolState, fund_fees_token_0) == 316);
+    assert!(offset_of!(PoolState, open_time) == 332);
+};
+
+// SAFETY: `#[repr(C)]` over alignment-1 fields, valid for every bit pattern.
+unsafe impl ZeroCopy for PoolState {}
+
+impl PoolState {
+    /// View `data` (a `PoolState` account's full data) in place.
+    pub fn from_account_data(data: &[u8]) -> Result<&Self, ProgramError> {
+        load_account(data, &POOL_STATE_DISCRIMINATOR)
+    }
+
+    /// Fees accrued to the protocol and fund in the vault for `token_0` or `token_1`,
+    /// which are held in the vault but not part of the tradable reserve.
+    pub fn accrued_fees(&self, token_0: bool) -> u64 {
+        if token_0 {
+            self.protocol_fees_token_0
+                .get()
+                .saturating_add(self.fund_fees_token_0.get())
+        } else {
+            self.protocol_fees_token_1
+                .get()
+                .saturating_add(self.fund_fees_token_1.get())
+        }
+    }
+}
+
+/// Leading fields of a Gamma `AmmConfig` account
+#[repr(C)]
+pub struct AmmConfig {
+    pub bump: u8,
+    pub disable_create_pool: u8,
+    pub index: PodU16,
+    pub trade_fee_rate: PodU64,
+    pub protocol_fee_rate: PodU64,
+    pub fund_fee_rate: PodU64,
+}
+
+const _: () = {
+    assert!(offset_of!(AmmConfig, trade_fee_rate) == 4);
+    assert!(offset_of!(AmmConfig, fund_fee_rate) == 20);
+};
+
+// SAFETY: `#[repr(C)]` over alignment-1 fields, valid for every bit pattern.
+unsafe impl ZeroCopy for AmmConfig {}
+
+impl AmmConfig {
+    /// View `data` (an `AmmConfig` account's full data) in place.
+    pub fn from_account_data(data: &[u8]) -> Result<&Self, ProgramError> {
+        load_account(data, &AMM_CONFIG_DISCRIMINATOR)
+    }
+}
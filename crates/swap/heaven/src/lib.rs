@@ -1,52 +1,70 @@
 #![no_std]
 
+pub use beethoven_core::program_ids::HEAVEN_PROGRAM_ID;
 use {
-    beethoven_core::Swap,
+    beethoven_core::{
+        cpi::invoke_signed,
+        scratch::{DataWriter, Scratch},
+        Direction, Swap,
+    },
     core::mem::MaybeUninit,
     solana_account_view::AccountView,
-    solana_address::Address,
-    solana_instruction_view::{
-        cpi::{invoke_signed, Signer},
-        InstructionAccount, InstructionView,
-    },
+    solana_instruction_view::{cpi::Signer, InstructionAccount, InstructionView},
     solana_program_error::{ProgramError, ProgramResult},
 };
 
-pub const HEAVEN_PROGRAM_ID: Address =
-    Address::from_str_const("HEAVENoP2qxoeuF8Dj2oT1GHEnu49U5mJYkdeC8BAX2o");
-
 const BUY_DISCRIMINATOR: [u8; 8] = [102, 6, 61, 18, 1, 218, 235, 234];
 const SELL_DISCRIMINATOR: [u8; 8] = [51, 230, 133, 164, 1, 127, 131, 173];
 
+/// Largest swap event forwarded to Heaven
+pub const MAX_EVENT_LEN: usize = 256;
+
+/// Largest instruction data this adapter builds
+pub const MAX_INSTRUCTION_DATA_LEN: usize = 28 + MAX_EVENT_LEN;
+
 pub struct Heaven;
 
 #[repr(u8)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum SwapDirection {
     Buy = 0,
     Sell = 1,
 }
 
+impl From<Direction> for SwapDirection {
+    fn from(direction: Direction) -> Self {
+        match direction {
+            Direction::Buy => SwapDirection::Buy,
+            Direction::Sell => SwapDirection::Sell,
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct HeavenSwapData<'a> {
     pub direction: SwapDirection,
     pub event: &'a [u8],
 }
 
+impl<'a> HeavenSwapData<'a> {
+    pub const fn new(direction: SwapDirection, event: &'a [u8]) -> Self {
+        Self { direction, event }
+    }
+}
+
 impl<'a> TryFrom<&'a [u8]> for HeavenSwapData<'a> {
     type Error = ProgramError;
 
     fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
-        if data.is_empty() {
+        let [direction, event @ ..] = data else {
             return Err(ProgramError::InvalidInstructionData);
-        }
-        let direction = match data[0] {
+        };
+        let direction = match direction {
             0 => SwapDirection::Buy,
             1 => SwapDirection::Sell,
             _ => return Err(ProgramError::InvalidInstructionData),
         };
-        Ok(Self {
-            direction,
-            event: &data[1..],
-        })
+        Ok(Self { direction, event })
     }
 }
 
@@ -73,6 +91,7 @@ pub struct HeavenSwapAccounts<'info> {
 impl<'info> TryFrom<&'info [AccountView]> for HeavenSwapAccounts<'info> {
     type Error = ProgramError;
 
+    #[inline(always)]
     fn try_from(accounts: &'info [AccountView]) -> Result<Self, Self::Error> {
         if accounts.len() < 17 {
             return Err(ProgramError::NotEnoughAccountKeys);
@@ -84,6 +103,17 @@ impl<'info> TryFrom<&'info [AccountView]> for HeavenSwapAccounts<'info> {
             return Err(ProgramError::NotEnoughAccountKeys);
         };
 
+        #[cfg(feature = "strict-validation")]
+        beethoven_core::validation::check_fixed_accounts(
+            ACCOUNT_LABELS,
+            (heaven_program, &HEAVEN_PROGRAM_ID),
+            &[
+                ("token_a_owner", token_a_owner),
+                ("token_b_owner", token_b_owner),
+            ],
+            Some(("ix_sysvar", ix_sysvar)),
+        )?;
+
         Ok(HeavenSwapAccounts {
             heaven_program,
             token_a_owner,
@@ -106,17 +136,81 @@ impl<'info> TryFrom<&'info [AccountView]> for HeavenSwapAccounts<'info> {
     }
 }
 
-impl<'info> Swap<'info> for Heaven {
-    type Accounts = HeavenSwapAccounts<'info>;
-    type Data = HeavenSwapData<'info>;
+/// What this adapter supports, as `ProtocolKind::Heaven.capabilities()` reports it
+pub const CAPABILITIES: beethoven_core::Capabilities =
+    beethoven_core::ProtocolKind::Heaven.capabilities();
 
-    fn swap_signed(
-        ctx: &Self::Accounts,
+/// Name of each account after the detector, in order, for error and log output.
+pub const ACCOUNT_LABELS: &[&str] = &[
+    "token_a_owner",
+    "token_b_owner",
+    "ata_program",
+    "system_program",
+    "pool_state",
+    "user",
+    "token_a_mint",
+    "token_b_mint",
+    "user_token_a_account",
+    "user_token_b_account",
+    "pool_token_a_account",
+    "pool_token_b_account",
+    "protocol_config",
+    "ix_sysvar",
+    "chainlink_id",
+    "chainlink_sol_usd_feed",
+];
+
+impl<'info> HeavenSwapAccounts<'info> {
+    /// The accounts in the order the CPI passes them, without the detector.
+    #[inline(always)]
+    pub fn as_ordered_slice(&self) -> [&'info AccountView; 16] {
+        [
+            self.token_a_owner,
+            self.token_b_owner,
+            self.ata_program,
+            self.system_program,
+            self.pool_state,
+            self.user,
+            self.token_a_mint,
+            self.token_b_mint,
+            self.user_token_a_account,
+            self.user_token_b_account,
+            self.pool_token_a_account,
+            self.pool_token_b_account,
+            self.protocol_config,
+            self.ix_sysvar,
+            self.chainlink_id,
+            self.chainlink_sol_usd_feed,
+        ]
+    }
+
+    /// Iterate over [`Self::as_ordered_slice`].
+    #[inline(always)]
+    pub fn iter(&self) -> impl Iterator<Item = &'info AccountView> {
+        self.as_ordered_slice().into_iter()
+    }
+}
+
+impl Heaven {
+    /// [`Swap::swap_signed`] with the instruction data assembled in caller-provided
+    /// `scratch` (at least [`MAX_INSTRUCTION_DATA_LEN`] bytes) instead of this frame.
+    pub fn swap_signed_with_scratch(
+        ctx: &HeavenSwapAccounts<'_>,
         in_amount: u64,
         minimum_out_amount: u64,
-        data: &Self::Data,
+        data: &HeavenSwapData<'_>,
         signer_seeds: &[Signer],
+        scratch: &mut [MaybeUninit<u8>],
     ) -> ProgramResult {
+        #[cfg(feature = "strict-validation")]
+        beethoven_core::validation::check_signer(ctx.user, signer_seeds).inspect_err(|_| {
+            beethoven_core::validation::log_account_error(
+                ACCOUNT_LABELS,
+                "user",
+                "missing signature",
+            )
+        })?;
+
         let accounts = [
             InstructionAccount::readonly(ctx.token_a_owner.address()),
             InstructionAccount::readonly(ctx.token_b_owner.address()),
@@ -136,95 +230,41 @@ impl<'info> Swap<'info> for Heaven {
             InstructionAccount::readonly(ctx.chainlink_sol_usd_feed.address()),
         ];
 
-        let account_infos = [
-            ctx.token_a_owner,
-            ctx.token_b_owner,
-            ctx.ata_program,
-            ctx.system_program,
-            ctx.pool_state,
-            ctx.user,
-            ctx.token_a_mint,
-            ctx.token_b_mint,
-            ctx.user_token_a_account,
-            ctx.user_token_b_account,
-            ctx.pool_token_a_account,
-            ctx.pool_token_b_account,
-            ctx.protocol_config,
-            ctx.ix_sysvar,
-            ctx.chainlink_id,
-            ctx.chainlink_sol_usd_feed,
-        ];
-
-        let event_len = data.event.len();
-        let instruction_data_len = 8 + 8 + 8 + 4 + event_len;
-
-        let discriminator = match data.direction {
-            SwapDirection::Buy => &BUY_DISCRIMINATOR,
-            SwapDirection::Sell => &SELL_DISCRIMINATOR,
-        };
-
-        if event_len == 0 {
-            let mut instruction_data = MaybeUninit::<[u8; 28]>::uninit();
-            unsafe {
-                let ptr = instruction_data.as_mut_ptr() as *mut u8;
-                core::ptr::copy_nonoverlapping(discriminator.as_ptr(), ptr, 8);
-                core::ptr::copy_nonoverlapping(in_amount.to_le_bytes().as_ptr(), ptr.add(8), 8);
-                core::ptr::copy_nonoverlapping(
-                    minimum_out_amount.to_le_bytes().as_ptr(),
-                    ptr.add(16),
-                    8,
-                );
-                core::ptr::copy_nonoverlapping(0u32.to_le_bytes().as_ptr(), ptr.add(24), 4);
-            }
-
-            let instruction = InstructionView {
-                program_id: &HEAVEN_PROGRAM_ID,
-                accounts: &accounts,
-                data: unsafe {
-                    core::slice::from_raw_parts(instruction_data.as_ptr() as *const u8, 28)
-                },
-            };
-
-            return invoke_signed(&instruction, &account_infos, signer_seeds);
-        }
-
-        const MAX_EVENT_LEN: usize = 256;
-        if event_len > MAX_EVENT_LEN {
-            return Err(ProgramError::InvalidInstructionData);
-        }
-
-        let mut instruction_data = MaybeUninit::<[u8; 28 + MAX_EVENT_LEN]>::uninit();
-        unsafe {
-            let ptr = instruction_data.as_mut_ptr() as *mut u8;
-            core::ptr::copy_nonoverlapping(discriminator.as_ptr(), ptr, 8);
-            core::ptr::copy_nonoverlapping(in_amount.to_le_bytes().as_ptr(), ptr.add(8), 8);
-            core::ptr::copy_nonoverlapping(
-                minimum_out_amount.to_le_bytes().as_ptr(),
-                ptr.add(16),
-                8,
-            );
-            core::ptr::copy_nonoverlapping(
-                (event_len as u32).to_le_bytes().as_ptr(),
-                ptr.add(24),
-                4,
-            );
-            core::ptr::copy_nonoverlapping(data.event.as_ptr(), ptr.add(28), event_len);
-        }
+        let account_infos = ctx.as_ordered_slice();
 
         let instruction = InstructionView {
             program_id: &HEAVEN_PROGRAM_ID,
             accounts: &accounts,
-            data: unsafe {
-                core::slice::from_raw_parts(
-                    instruction_data.as_ptr() as *const u8,
-                    instruction_data_len,
-                )
-            },
+            data: swap_instruction_data(scratch, in_amount, minimum_out_amount, data)?,
         };
 
         invoke_signed(&instruction, &account_infos, signer_seeds)
     }
+}
+
+impl<'info> Swap<'info> for Heaven {
+    type Accounts = HeavenSwapAccounts<'info>;
+    type Data = HeavenSwapData<'info>;
 
+    fn swap_signed(
+        ctx: &Self::Accounts,
+        in_amount: u64,
+        minimum_out_amount: u64,
+        data: &Self::Data,
+        signer_seeds: &[Signer],
+    ) -> ProgramResult {
+        let mut scratch = Scratch::<MAX_INSTRUCTION_DATA_LEN>::new();
+        Self::swap_signed_with_scratch(
+            ctx,
+            in_amount,
+            minimum_out_amount,
+            data,
+            signer_seeds,
+            scratch.as_uninit_mut(),
+        )
+    }
+
+    #[inline(always)]
     fn swap(
         ctx: &Self::Accounts,
         in_amount: u64,
@@ -234,3 +274,33 @@ impl<'info> Swap<'info> for Heaven {
         Self::swap_signed(ctx, in_amount, minimum_out_amount, data, &[])
     }
 }
+
+/// Write the `buy` / `sell` instruction data into `scratch`.
+///
+/// Layout: discriminator ([u8; 8]), in_amount (u64), minimum_out_amount (u64),
+/// event length (u32), event
+#[inline(always)]
+pub fn swap_instruction_data<'a>(
+    scratch: &'a mut [MaybeUninit<u8>],
+    in_amount: u64,
+    minimum_out_amount: u64,
+    swap_data: &HeavenSwapData<'_>,
+) -> Result<&'a [u8], ProgramError> {
+    let event_len = swap_data.event.len();
+    if event_len > MAX_EVENT_LEN {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    let discriminator = match swap_data.direction {
+        SwapDirection::Buy => &BUY_DISCRIMINATOR,
+        SwapDirection::Sell => &SELL_DISCRIMINATOR,
+    };
+
+    let mut instruction_data = DataWriter::new(scratch);
+    instruction_data.write(discriminator)?;
+    instruction_data.write_u64(in_amount)?;
+    instruction_data.write_u64(minimum_out_amount)?;
+    instruction_data.write_u32(event_len as u32)?;
+    instruction_data.write(swap_data.event)?;
+    Ok(instruction_data.finish())
+}
@@ -1,39 +1,78 @@
 #![no_std]
 
+pub use beethoven_core::program_ids::MANIFEST_PROGRAM_ID;
 use {
-    beethoven_core::Swap,
+    beethoven_core::{
+        cpi::invoke_signed,
+        scratch::{data_template, from_template, Scratch},
+        CancelOrder, Direction, LimitOrder, OrderType, Swap,
+    },
     core::mem::MaybeUninit,
     solana_account_view::AccountView,
-    solana_address::Address,
-    solana_instruction_view::{
-        cpi::{invoke_signed, Signer},
-        InstructionAccount, InstructionView,
-    },
+    solana_instruction_view::{cpi::Signer, InstructionAccount, InstructionView},
     solana_program_error::{ProgramError, ProgramResult},
 };
 
-pub const MANIFEST_PROGRAM_ID: Address =
-    Address::from_str_const("MNFSTqtC93rEfYHB6hF82sKdZpUDFWkViLByLd1k1Ms");
-
 const SWAP_DISCRIMINATOR: u8 = 13;
+const BATCH_UPDATE_DISCRIMINATOR: u8 = 6;
+
+/// `batch_update` instruction data placing one order, with every order field zeroed.
+///
+/// Layout: discriminator (u8), trader_index_hint (`Option<u32>`, always `None`),
+/// cancels (`Vec`, always empty), orders (`Vec` of one): base_atoms (u64),
+/// price_mantissa (u32), price_exponent (i8), is_bid (u8), last_valid_slot (u32),
+/// order_type (u8)
+pub const PLACE_ORDER_DATA_TEMPLATE: [u8; 29] =
+    data_template(&[BATCH_UPDATE_DISCRIMINATOR, 0, 0, 0, 0, 0, 1, 0, 0, 0]);
+
+/// `batch_update` instruction data cancelling one order, with its sequence number zeroed.
+///
+/// Layout: discriminator (u8), trader_index_hint (`Option<u32>`, always `None`),
+/// cancels (`Vec` of one): order_sequence_number (u64), order_index_hint (`Option<u32>`,
+/// always `None`); orders (`Vec`, always empty)
+pub const CANCEL_ORDER_DATA_TEMPLATE: [u8; 19] =
+    data_template(&[BATCH_UPDATE_DISCRIMINATOR, 0, 1, 0, 0, 0]);
+
+/// Largest instruction data this adapter builds
+pub const MAX_INSTRUCTION_DATA_LEN: usize = 19;
+
+/// `swap` instruction data with every field after the discriminator zeroed.
+///
+/// Layout: discriminator (u8), in_amount (u64), minimum_out_amount (u64),
+/// is_base_in (u8), is_exact_in (u8)
+pub const SWAP_DATA_TEMPLATE: [u8; MAX_INSTRUCTION_DATA_LEN] = data_template(&[SWAP_DISCRIMINATOR]);
 
 pub struct Manifest;
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "borsh",
+    derive(borsh::BorshSerialize, borsh::BorshDeserialize)
+)]
 pub struct ManifestSwapData {
     pub is_base_in: bool,
     pub is_exact_in: bool,
 }
 
+impl ManifestSwapData {
+    pub const fn new(is_base_in: bool, is_exact_in: bool) -> Self {
+        Self {
+            is_base_in,
+            is_exact_in,
+        }
+    }
+}
+
 impl TryFrom<&[u8]> for ManifestSwapData {
     type Error = ProgramError;
 
     fn try_from(data: &[u8]) -> Result<Self, Self::Error> {
-        if data.len() < 2 {
+        let [is_base_in, is_exact_in, ..] = data else {
             return Err(ProgramError::InvalidInstructionData);
-        }
+        };
         Ok(Self {
-            is_base_in: data[0] != 0,
-            is_exact_in: data[1] != 0,
+            is_base_in: *is_base_in != 0,
+            is_exact_in: *is_exact_in != 0,
         })
     }
 }
@@ -59,6 +98,7 @@ pub struct ManifestSwapAccounts<'info> {
 impl<'info> TryFrom<&'info [AccountView]> for ManifestSwapAccounts<'info> {
     type Error = ProgramError;
 
+    #[inline(always)]
     fn try_from(accounts: &'info [AccountView]) -> Result<Self, Self::Error> {
         if accounts.len() < 15 {
             return Err(ProgramError::NotEnoughAccountKeys);
@@ -70,6 +110,17 @@ impl<'info> TryFrom<&'info [AccountView]> for ManifestSwapAccounts<'info> {
             return Err(ProgramError::NotEnoughAccountKeys);
         };
 
+        #[cfg(feature = "strict-validation")]
+        beethoven_core::validation::check_fixed_accounts(
+            ACCOUNT_LABELS,
+            (manifest_program, &MANIFEST_PROGRAM_ID),
+            &[
+                ("token_program_base", token_program_base),
+                ("token_program_quote", token_program_quote),
+            ],
+            None,
+        )?;
+
         Ok(ManifestSwapAccounts {
             manifest_program,
             payer,
@@ -90,17 +141,85 @@ impl<'info> TryFrom<&'info [AccountView]> for ManifestSwapAccounts<'info> {
     }
 }
 
-impl<'info> Swap<'info> for Manifest {
-    type Accounts = ManifestSwapAccounts<'info>;
-    type Data = ManifestSwapData;
+/// What this adapter supports, as `ProtocolKind::Manifest.capabilities()` reports it
+pub const CAPABILITIES: beethoven_core::Capabilities =
+    beethoven_core::ProtocolKind::Manifest.capabilities();
 
-    fn swap_signed(
-        ctx: &Self::Accounts,
+/// Name of each account after the detector, in order, for error and log output.
+pub const ACCOUNT_LABELS: &[&str] = &[
+    "payer",
+    "owner",
+    "market",
+    "system_program",
+    "trader_base",
+    "trader_quote",
+    "base_vault",
+    "quote_vault",
+    "token_program_base",
+    "base_mint",
+    "token_program_quote",
+    "quote_mint",
+    "global",
+    "global_vault",
+];
+
+impl<'info> ManifestSwapAccounts<'info> {
+    /// The accounts in the order the CPI passes them, without the detector.
+    #[inline(always)]
+    pub fn as_ordered_slice(&self) -> [&'info AccountView; 14] {
+        [
+            self.payer,
+            self.owner,
+            self.market,
+            self.system_program,
+            self.trader_base,
+            self.trader_quote,
+            self.base_vault,
+            self.quote_vault,
+            self.token_program_base,
+            self.base_mint,
+            self.token_program_quote,
+            self.quote_mint,
+            self.global,
+            self.global_vault,
+        ]
+    }
+
+    /// Iterate over [`Self::as_ordered_slice`].
+    #[inline(always)]
+    pub fn iter(&self) -> impl Iterator<Item = &'info AccountView> {
+        self.as_ordered_slice().into_iter()
+    }
+}
+
+impl Manifest {
+    /// [`Swap::swap_signed`] with the instruction data assembled in caller-provided
+    /// `scratch` (at least [`MAX_INSTRUCTION_DATA_LEN`] bytes) instead of this frame.
+    pub fn swap_signed_with_scratch(
+        ctx: &ManifestSwapAccounts<'_>,
         in_amount: u64,
         minimum_out_amount: u64,
-        data: &Self::Data,
+        data: &ManifestSwapData,
         signer_seeds: &[Signer],
+        scratch: &mut [MaybeUninit<u8>],
     ) -> ProgramResult {
+        #[cfg(feature = "strict-validation")]
+        beethoven_core::validation::check_signer(ctx.payer, signer_seeds).inspect_err(|_| {
+            beethoven_core::validation::log_account_error(
+                ACCOUNT_LABELS,
+                "payer",
+                "missing signature",
+            )
+        })?;
+        #[cfg(feature = "strict-validation")]
+        beethoven_core::validation::check_signer(ctx.owner, signer_seeds).inspect_err(|_| {
+            beethoven_core::validation::log_account_error(
+                ACCOUNT_LABELS,
+                "owner",
+                "missing signature",
+            )
+        })?;
+
         let accounts = [
             InstructionAccount::writable_signer(ctx.payer.address()),
             InstructionAccount::readonly_signer(ctx.owner.address()),
@@ -118,48 +237,41 @@ impl<'info> Swap<'info> for Manifest {
             InstructionAccount::writable(ctx.global_vault.address()),
         ];
 
-        let account_infos = [
-            ctx.payer,
-            ctx.owner,
-            ctx.market,
-            ctx.system_program,
-            ctx.trader_base,
-            ctx.trader_quote,
-            ctx.base_vault,
-            ctx.quote_vault,
-            ctx.token_program_base,
-            ctx.base_mint,
-            ctx.token_program_quote,
-            ctx.quote_mint,
-            ctx.global,
-            ctx.global_vault,
-        ];
-
-        let mut instruction_data = MaybeUninit::<[u8; 19]>::uninit();
-        unsafe {
-            let ptr = instruction_data.as_mut_ptr() as *mut u8;
-            core::ptr::write(ptr, SWAP_DISCRIMINATOR);
-            core::ptr::copy_nonoverlapping(in_amount.to_le_bytes().as_ptr(), ptr.add(1), 8);
-            core::ptr::copy_nonoverlapping(
-                minimum_out_amount.to_le_bytes().as_ptr(),
-                ptr.add(9),
-                8,
-            );
-            core::ptr::write(ptr.add(17), data.is_base_in as u8);
-            core::ptr::write(ptr.add(18), data.is_exact_in as u8);
-        }
+        let account_infos = ctx.as_ordered_slice();
 
         let instruction = InstructionView {
             program_id: &MANIFEST_PROGRAM_ID,
             accounts: &accounts,
-            data: unsafe {
-                core::slice::from_raw_parts(instruction_data.as_ptr() as *const u8, 19)
-            },
+            data: swap_instruction_data(scratch, in_amount, minimum_out_amount, data)?,
         };
 
         invoke_signed(&instruction, &account_infos, signer_seeds)
     }
+}
+
+impl<'info> Swap<'info> for Manifest {
+    type Accounts = ManifestSwapAccounts<'info>;
+    type Data = ManifestSwapData;
+
+    fn swap_signed(
+        ctx: &Self::Accounts,
+        in_amount: u64,
+        minimum_out_amount: u64,
+        data: &Self::Data,
+        signer_seeds: &[Signer],
+    ) -> ProgramResult {
+        let mut scratch = Scratch::<MAX_INSTRUCTION_DATA_LEN>::new();
+        Self::swap_signed_with_scratch(
+            ctx,
+            in_amount,
+            minimum_out_amount,
+            data,
+            signer_seeds,
+            scratch.as_uninit_mut(),
+        )
+    }
 
+    #[inline(always)]
     fn swap(
         ctx: &Self::Accounts,
         in_amount: u64,
@@ -168,4 +280,252 @@ impl<'info> Swap<'info> for Manifest {
     ) -> ProgramResult {
         Self::swap_signed(ctx, in_amount, minimum_out_amount, data, &[])
     }
+
+    /// Manifest fills exact-out natively: `is_exact_in` is cleared, so the first amount
+    /// is the most the trader spends and the second the exact amount received.
+    fn swap_exact_out_signed(
+        ctx: &Self::Accounts,
+        out_amount: u64,
+        maximum_in_amount: u64,
+        data: &Self::Data,
+        signer_seeds: &[Signer],
+    ) -> ProgramResult {
+        let mut scratch = Scratch::<MAX_INSTRUCTION_DATA_LEN>::new();
+        Self::swap_signed_with_scratch(
+            ctx,
+            maximum_in_amount,
+            out_amount,
+            &ManifestSwapData::new(data.is_base_in, false),
+            signer_seeds,
+            scratch.as_uninit_mut(),
+        )
+    }
+}
+
+/// Write the `swap` instruction data into `scratch` by patching [`SWAP_DATA_TEMPLATE`].
+#[inline(always)]
+pub fn swap_instruction_data<'a>(
+    scratch: &'a mut [MaybeUninit<u8>],
+    in_amount: u64,
+    minimum_out_amount: u64,
+    swap_data: &ManifestSwapData,
+) -> Result<&'a [u8], ProgramError> {
+    let data = from_template(scratch, &SWAP_DATA_TEMPLATE)?;
+    data[1..9].copy_from_slice(&in_amount.to_le_bytes());
+    data[9..17].copy_from_slice(&minimum_out_amount.to_le_bytes());
+    data[17] = swap_data.is_base_in as u8;
+    data[18] = swap_data.is_exact_in as u8;
+    Ok(data)
+}
+
+/// Price and expiry of a Manifest order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "borsh",
+    derive(borsh::BorshSerialize, borsh::BorshDeserialize)
+)]
+pub struct ManifestOrderData {
+    /// Quote atoms per base atom is `price_mantissa * 10^price_exponent`
+    pub price_mantissa: u32,
+    pub price_exponent: i8,
+    /// Last slot the order rests on the book; 0 never expires
+    pub last_valid_slot: u32,
+}
+
+impl ManifestOrderData {
+    pub const fn new(price_mantissa: u32, price_exponent: i8, last_valid_slot: u32) -> Self {
+        Self {
+            price_mantissa,
+            price_exponent,
+            last_valid_slot,
+        }
+    }
+}
+
+impl TryFrom<&[u8]> for ManifestOrderData {
+    type Error = ProgramError;
+
+    fn try_from(data: &[u8]) -> Result<Self, Self::Error> {
+        let [m0, m1, m2, m3, price_exponent, s0, s1, s2, s3, ..] = data else {
+            return Err(ProgramError::InvalidInstructionData);
+        };
+        Ok(Self {
+            price_mantissa: u32::from_le_bytes([*m0, *m1, *m2, *m3]),
+            price_exponent: *price_exponent as i8,
+            last_valid_slot: u32::from_le_bytes([*s0, *s1, *s2, *s3]),
+        })
+    }
+}
+
+/// Accounts of a `batch_update` that only touches the trader's seat: orders are funded
+/// from, and cancelled back into, balances deposited in the market rather than token
+/// accounts.
+pub struct ManifestOrderAccounts<'info> {
+    pub manifest_program: &'info AccountView,
+    pub payer: &'info AccountView,
+    pub market: &'info AccountView,
+    pub system_program: &'info AccountView,
+}
+
+impl<'info> TryFrom<&'info [AccountView]> for ManifestOrderAccounts<'info> {
+    type Error = ProgramError;
+
+    #[inline(always)]
+    fn try_from(accounts: &'info [AccountView]) -> Result<Self, Self::Error> {
+        let [manifest_program, payer, market, system_program, ..] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        #[cfg(feature = "strict-validation")]
+        beethoven_core::validation::check_fixed_accounts(
+            ORDER_ACCOUNT_LABELS,
+            (manifest_program, &MANIFEST_PROGRAM_ID),
+            &[],
+            None,
+        )?;
+
+        Ok(ManifestOrderAccounts {
+            manifest_program,
+            payer,
+            market,
+            system_program,
+        })
+    }
+}
+
+/// Name of each order account after the detector, in order, for error and log output.
+pub const ORDER_ACCOUNT_LABELS: &[&str] = &["payer", "market", "system_program"];
+
+impl<'info> ManifestOrderAccounts<'info> {
+    /// The accounts in the order the CPI passes them, without the detector.
+    #[inline(always)]
+    pub fn as_ordered_slice(&self) -> [&'info AccountView; 3] {
+        [self.payer, self.market, self.system_program]
+    }
+
+    /// Iterate over [`Self::as_ordered_slice`].
+    #[inline(always)]
+    pub fn iter(&self) -> impl Iterator<Item = &'info AccountView> {
+        self.as_ordered_slice().into_iter()
+    }
+
+    /// Invoke `batch_update` with `data`, signed by `payer`.
+    #[inline(always)]
+    fn batch_update(&self, data: &[u8], signer_seeds: &[Signer]) -> ProgramResult {
+        #[cfg(feature = "strict-validation")]
+        beethoven_core::validation::check_signer(self.payer, signer_seeds).inspect_err(|_| {
+            beethoven_core::validation::log_account_error(
+                ORDER_ACCOUNT_LABELS,
+                "payer",
+                "missing signature",
+            )
+        })?;
+
+        let accounts = [
+            InstructionAccount::writable_signer(self.payer.address()),
+            InstructionAccount::writable(self.market.address()),
+            InstructionAccount::readonly(self.system_program.address()),
+        ];
+
+        let account_infos = self.as_ordered_slice();
+
+        let instruction = InstructionView {
+            program_id: &MANIFEST_PROGRAM_ID,
+            accounts: &accounts,
+            data,
+        };
+
+        invoke_signed(&instruction, &account_infos, signer_seeds)
+    }
+}
+
+/// Places one order through `batch_update`. `payer` must already hold a seat on the
+/// market with enough deposited base (asks) or quote (bids) to back it.
+impl<'info> LimitOrder<'info> for Manifest {
+    type Accounts = ManifestOrderAccounts<'info>;
+    type Data = ManifestOrderData;
+
+    fn place_order_signed(
+        ctx: &ManifestOrderAccounts<'info>,
+        side: Direction,
+        base_amount: u64,
+        order_type: OrderType,
+        data: &ManifestOrderData,
+        signer_seeds: &[Signer],
+    ) -> ProgramResult {
+        ctx.batch_update(
+            &place_order_instruction_data(side, base_amount, order_type, data),
+            signer_seeds,
+        )
+    }
+
+    #[inline(always)]
+    fn place_order(
+        ctx: &ManifestOrderAccounts<'info>,
+        side: Direction,
+        base_amount: u64,
+        order_type: OrderType,
+        data: &ManifestOrderData,
+    ) -> ProgramResult {
+        Self::place_order_signed(ctx, side, base_amount, order_type, data, &[])
+    }
+}
+
+/// Cancels by the order's sequence number, which Manifest assigns at placement; it has
+/// no client order ids.
+impl<'info> CancelOrder<'info> for Manifest {
+    type Accounts = ManifestOrderAccounts<'info>;
+    type OrderId = u64;
+
+    fn cancel_order_signed(
+        ctx: &ManifestOrderAccounts<'info>,
+        order_id: &u64,
+        signer_seeds: &[Signer],
+    ) -> ProgramResult {
+        ctx.batch_update(&cancel_order_instruction_data(*order_id), signer_seeds)
+    }
+
+    #[inline(always)]
+    fn cancel_order(ctx: &ManifestOrderAccounts<'info>, order_id: &u64) -> ProgramResult {
+        Self::cancel_order_signed(ctx, order_id, &[])
+    }
+}
+
+/// Manifest's `OrderType` value for `order_type`.
+#[inline(always)]
+pub const fn manifest_order_type(order_type: OrderType) -> u8 {
+    match order_type {
+        OrderType::Limit => 0,
+        OrderType::ImmediateOrCancel => 1,
+        OrderType::PostOnly => 2,
+    }
+}
+
+/// `batch_update` instruction data placing one order, patched into
+/// [`PLACE_ORDER_DATA_TEMPLATE`].
+#[inline(always)]
+pub fn place_order_instruction_data(
+    side: Direction,
+    base_amount: u64,
+    order_type: OrderType,
+    order: &ManifestOrderData,
+) -> [u8; 29] {
+    let mut data = PLACE_ORDER_DATA_TEMPLATE;
+    data[10..18].copy_from_slice(&base_amount.to_le_bytes());
+    data[18..22].copy_from_slice(&order.price_mantissa.to_le_bytes());
+    data[22] = order.price_exponent as u8;
+    data[23] = (side == Direction::Buy) as u8;
+    data[24..28].copy_from_slice(&order.last_valid_slot.to_le_bytes());
+    data[28] = manifest_order_type(order_type);
+    data
+}
+
+/// `batch_update` instruction data cancelling the order with `order_sequence_number`,
+/// patched into [`CANCEL_ORDER_DATA_TEMPLATE`].
+#[inline(always)]
+pub fn cancel_order_instruction_data(order_sequence_number: u64) -> [u8; 19] {
+    let mut data = CANCEL_ORDER_DATA_TEMPLATE;
+    data[6..14].copy_from_slice(&order_sequence_number.to_le_bytes());
+    data
 }
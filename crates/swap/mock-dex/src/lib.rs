@@ -0,0 +1,246 @@
+//! Adapter for the mock constant-product DEX in `program-mock-dex`.
+//!
+//! The mock DEX exists so the dispatcher and everything layered on it can be tested
+//! against a venue whose program and accounts live in this repository, without mainnet
+//! dumps. It is deliberately left out of the `swap` feature group.
+
+#![no_std]
+
+pub mod quote;
+pub mod state;
+
+pub use beethoven_core::program_ids::MOCK_DEX_PROGRAM_ID;
+use {
+    beethoven_core::{
+        cpi::invoke_signed,
+        scratch::{data_template, from_template, Scratch},
+        Swap,
+    },
+    core::mem::MaybeUninit,
+    solana_account_view::AccountView,
+    solana_instruction_view::{cpi::Signer, InstructionAccount, InstructionView},
+    solana_program_error::{ProgramError, ProgramResult},
+};
+
+pub const SWAP_DISCRIMINATOR: u8 = 0;
+
+/// Custom error the mock DEX fails with when the output is below `minimum_out_amount`
+pub const SLIPPAGE_EXCEEDED_ERROR: u32 = 1;
+
+/// Largest instruction data this adapter builds
+pub const MAX_INSTRUCTION_DATA_LEN: usize = 18;
+
+/// `swap` instruction data with every field after the discriminator zeroed.
+///
+/// Layout: discriminator (u8), in_amount (u64), minimum_out_amount (u64),
+/// is_quote_to_base (u8)
+pub const SWAP_DATA_TEMPLATE: [u8; MAX_INSTRUCTION_DATA_LEN] = data_template(&[SWAP_DISCRIMINATOR]);
+
+pub struct MockDex;
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "borsh",
+    derive(borsh::BorshSerialize, borsh::BorshDeserialize)
+)]
+pub struct MockDexSwapData {
+    pub is_quote_to_base: bool,
+}
+
+impl MockDexSwapData {
+    pub const fn new(is_quote_to_base: bool) -> Self {
+        Self { is_quote_to_base }
+    }
+}
+
+impl TryFrom<&[u8]> for MockDexSwapData {
+    type Error = ProgramError;
+
+    fn try_from(data: &[u8]) -> Result<Self, Self::Error> {
+        let [is_quote_to_base, ..] = data else {
+            return Err(ProgramError::InvalidInstructionData);
+        };
+        Ok(Self {
+            is_quote_to_base: *is_quote_to_base != 0,
+        })
+    }
+}
+
+pub struct MockDexSwapAccounts<'info> {
+    pub mock_dex_program: &'info AccountView,
+    pub user: &'info AccountView,
+    pub pool: &'info AccountView,
+    pub pool_authority: &'info AccountView,
+    pub base_vault: &'info AccountView,
+    pub quote_vault: &'info AccountView,
+    pub user_base_token: &'info AccountView,
+    pub user_quote_token: &'info AccountView,
+    pub token_program: &'info AccountView,
+}
+
+impl<'info> TryFrom<&'info [AccountView]> for MockDexSwapAccounts<'info> {
+    type Error = ProgramError;
+
+    #[inline(always)]
+    fn try_from(accounts: &'info [AccountView]) -> Result<Self, Self::Error> {
+        if accounts.len() < 9 {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        }
+
+        let [mock_dex_program, user, pool, pool_authority, base_vault, quote_vault, user_base_token, user_quote_token, token_program, ..] =
+            accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        #[cfg(feature = "strict-validation")]
+        beethoven_core::validation::check_fixed_accounts(
+            ACCOUNT_LABELS,
+            (mock_dex_program, &MOCK_DEX_PROGRAM_ID),
+            &[("token_program", token_program)],
+            None,
+        )?;
+
+        Ok(MockDexSwapAccounts {
+            mock_dex_program,
+            user,
+            pool,
+            pool_authority,
+            base_vault,
+            quote_vault,
+            user_base_token,
+            user_quote_token,
+            token_program,
+        })
+    }
+}
+
+/// What this adapter supports, as `ProtocolKind::MockDex.capabilities()` reports it
+pub const CAPABILITIES: beethoven_core::Capabilities =
+    beethoven_core::ProtocolKind::MockDex.capabilities();
+
+/// Name of each account after the detector, in order, for error and log output.
+pub const ACCOUNT_LABELS: &[&str] = &[
+    "user",
+    "pool",
+    "pool_authority",
+    "base_vault",
+    "quote_vault",
+    "user_base_token",
+    "user_quote_token",
+    "token_program",
+];
+
+impl<'info> MockDexSwapAccounts<'info> {
+    /// The accounts in the order the CPI passes them, without the detector.
+    #[inline(always)]
+    pub fn as_ordered_slice(&self) -> [&'info AccountView; 8] {
+        [
+            self.user,
+            self.pool,
+            self.pool_authority,
+            self.base_vault,
+            self.quote_vault,
+            self.user_base_token,
+            self.user_quote_token,
+            self.token_program,
+        ]
+    }
+
+    /// Iterate over [`Self::as_ordered_slice`].
+    #[inline(always)]
+    pub fn iter(&self) -> impl Iterator<Item = &'info AccountView> {
+        self.as_ordered_slice().into_iter()
+    }
+}
+
+impl MockDex {
+    /// [`Swap::swap_signed`] with the instruction data assembled in caller-provided
+    /// `scratch` (at least [`MAX_INSTRUCTION_DATA_LEN`] bytes) instead of this frame.
+    pub fn swap_signed_with_scratch(
+        ctx: &MockDexSwapAccounts<'_>,
+        in_amount: u64,
+        minimum_out_amount: u64,
+        data: &MockDexSwapData,
+        signer_seeds: &[Signer],
+        scratch: &mut [MaybeUninit<u8>],
+    ) -> ProgramResult {
+        #[cfg(feature = "strict-validation")]
+        beethoven_core::validation::check_signer(ctx.user, signer_seeds).inspect_err(|_| {
+            beethoven_core::validation::log_account_error(
+                ACCOUNT_LABELS,
+                "user",
+                "missing signature",
+            )
+        })?;
+
+        let accounts = [
+            InstructionAccount::readonly_signer(ctx.user.address()),
+            InstructionAccount::readonly(ctx.pool.address()),
+            InstructionAccount::readonly(ctx.pool_authority.address()),
+            InstructionAccount::writable(ctx.base_vault.address()),
+            InstructionAccount::writable(ctx.quote_vault.address()),
+            InstructionAccount::writable(ctx.user_base_token.address()),
+            InstructionAccount::writable(ctx.user_quote_token.address()),
+            InstructionAccount::readonly(ctx.token_program.address()),
+        ];
+
+        let account_infos = ctx.as_ordered_slice();
+
+        let instruction = InstructionView {
+            program_id: &MOCK_DEX_PROGRAM_ID,
+            accounts: &accounts,
+            data: swap_instruction_data(scratch, in_amount, minimum_out_amount, data)?,
+        };
+
+        invoke_signed(&instruction, &account_infos, signer_seeds)
+    }
+}
+
+impl<'info> Swap<'info> for MockDex {
+    type Accounts = MockDexSwapAccounts<'info>;
+    type Data = MockDexSwapData;
+
+    fn swap_signed(
+        ctx: &Self::Accounts,
+        in_amount: u64,
+        minimum_out_amount: u64,
+        data: &Self::Data,
+        signer_seeds: &[Signer],
+    ) -> ProgramResult {
+        let mut scratch = Scratch::<MAX_INSTRUCTION_DATA_LEN>::new();
+        Self::swap_signed_with_scratch(
+            ctx,
+            in_amount,
+            minimum_out_amount,
+            data,
+            signer_seeds,
+            scratch.as_uninit_mut(),
+        )
+    }
+
+    #[inline(always)]
+    fn swap(
+        ctx: &Self::Accounts,
+        in_amount: u64,
+        minimum_out_amount: u64,
+        data: &Self::Data,
+    ) -> ProgramResult {
+        Self::swap_signed(ctx, in_amount, minimum_out_amount, data, &[])
+    }
+}
+
+/// Write the `swap` instruction data into `scratch` by patching [`SWAP_DATA_TEMPLATE`].
+#[inline(always)]
+pub fn swap_instruction_data<'a>(
+    scratch: &'a mut [MaybeUninit<u8>],
+    in_amount: u64,
+    minimum_out_amount: u64,
+    swap_data: &MockDexSwapData,
+) -> Result<&'a [u8], ProgramError> {
+    let data = from_template(scratch, &SWAP_DATA_TEMPLATE)?;
+    data[1..9].copy_from_slice(&in_amount.to_le_bytes());
+    data[9..17].copy_from_slice(&minimum_out_amount.to_le_bytes());
+    data[17] = swap_data.is_quote_to_base as u8;
+    Ok(data)
+}
@@ -0,0 +1,41 @@
+//! Constant-product quoting over a mock DEX pool.
+//!
+//! The mock DEX program executes swaps with exactly this function, so a test can
+//! compute the output it should observe from the vault balances it set up.
+
+use crate::state::FEE_BPS_DENOMINATOR;
+
+/// Result of selling an exact input amount into a pool
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ExactInQuote {
+    /// Output the swap transfers to the user
+    pub out_amount: u64,
+    /// Part of the input kept by the pool as the trade fee
+    pub trade_fee: u64,
+}
+
+/// Quote selling `amount_in` against `input_reserve` / `output_reserve` with a
+/// `fee_bps` trade fee.
+///
+/// The trade fee is rounded up and taken from the input; the output is rounded down.
+/// Returns `None` on overflow or an empty pool.
+pub fn quote_exact_in(
+    fee_bps: u16,
+    amount_in: u64,
+    input_reserve: u64,
+    output_reserve: u64,
+) -> Option<ExactInQuote> {
+    let trade_fee = (amount_in as u128 * fee_bps as u128).div_ceil(FEE_BPS_DENOMINATOR as u128);
+    let amount_in_after_fee = (amount_in as u128).checked_sub(trade_fee)?;
+
+    let denominator = (input_reserve as u128).checked_add(amount_in_after_fee)?;
+    if denominator == 0 {
+        return None;
+    }
+    let out_amount = amount_in_after_fee.checked_mul(output_reserve as u128)? / denominator;
+
+    Some(ExactInQuote {
+        out_amount: u64::try_from(out_amount).ok()?,
+        trade_fee: u64::try_from(trade_fee).ok()?,
+    })
+}
@@ -0,0 +1,64 @@
+//! Layout of a mock DEX pool account.
+//!
+//! Shared by the mock DEX program, which reads it in place, and the tests and client,
+//! which write it directly instead of running an initialize instruction.
+
+use {
+    beethoven_core::zero_copy::{load_account, PodU16, ZeroCopy},
+    core::mem::{offset_of, size_of},
+    solana_address::Address,
+    solana_program_error::ProgramError,
+};
+
+pub const POOL_DISCRIMINATOR: [u8; 8] = *b"mockpool";
+
+/// Seed of the PDA that owns a pool's vaults, followed by the pool address
+pub const POOL_AUTHORITY_SEED: &[u8] = b"authority";
+
+/// Denominator of [`Pool::fee_bps`]
+pub const FEE_BPS_DENOMINATOR: u64 = 10_000;
+
+/// Length of a pool account's data, discriminator included
+pub const POOL_ACCOUNT_LEN: usize = 8 + size_of::<Pool>();
+
+/// A constant-product pool over two token vaults
+#[repr(C)]
+pub struct Pool {
+    pub base_mint: Address,
+    pub quote_mint: Address,
+    pub base_vault: Address,
+    pub quote_vault: Address,
+    /// Bump of the pool authority PDA
+    pub authority_bump: u8,
+    /// Trade fee taken from the input, in basis points
+    pub fee_bps: PodU16,
+}
+
+const _: () = {
+    assert!(offset_of!(Pool, base_vault) == 64);
+    assert!(offset_of!(Pool, authority_bump) == 128);
+    assert!(offset_of!(Pool, fee_bps) == 129);
+};
+
+// SAFETY: `#[repr(C)]` over alignment-1 fields, valid for every bit pattern.
+unsafe impl ZeroCopy for Pool {}
+
+impl Pool {
+    /// View `data` (a pool account's full data) in place.
+    pub fn from_account_data(data: &[u8]) -> Result<&Self, ProgramError> {
+        load_account(data, &POOL_DISCRIMINATOR)
+    }
+
+    /// The pool account's data, discriminator included.
+    pub fn to_account_data(&self) -> [u8; POOL_ACCOUNT_LEN] {
+        let mut data = [0; POOL_ACCOUNT_LEN];
+        data[..8].copy_from_slice(&POOL_DISCRIMINATOR);
+        data[8..40].copy_from_slice(self.base_mint.as_ref());
+        data[40..72].copy_from_slice(self.quote_mint.as_ref());
+        data[72..104].copy_from_slice(self.base_vault.as_ref());
+        data[104..136].copy_from_slice(self.quote_vault.as_ref());
+        data[136] = self.authority_bump;
+        data[137..139].copy_from_slice(&self.fee_bps.get().to_le_bytes());
+        data
+    }
+}
@@ -1,39 +1,60 @@
 #![no_std]
 
+pub use beethoven_core::program_ids::PERENA_PROGRAM_ID;
 use {
-    beethoven_core::Swap,
+    beethoven_core::{
+        cpi::invoke_signed,
+        scratch::{data_template, from_template, Scratch},
+        Swap,
+    },
     core::mem::MaybeUninit,
     solana_account_view::AccountView,
-    solana_address::Address,
-    solana_instruction_view::{
-        cpi::{invoke_signed, Signer},
-        InstructionAccount, InstructionView,
-    },
+    solana_instruction_view::{cpi::Signer, InstructionAccount, InstructionView},
     solana_program_error::{ProgramError, ProgramResult},
 };
 
-pub const PERENA_PROGRAM_ID: Address =
-    Address::from_str_const("NUMERUNsFCP3kuNmWZuXtm1AaQCPj9uw6Guv2Ekoi5P");
-
 const SWAP_DISCRIMINATOR: [u8; 8] = [104, 104, 131, 86, 161, 189, 180, 216];
 
+/// Largest instruction data this adapter builds
+pub const MAX_INSTRUCTION_DATA_LEN: usize = 26;
+
+/// `swap` instruction data with every field after the discriminator zeroed.
+///
+/// Layout: discriminator ([u8; 8]), in_index (u8), out_index (u8), in_amount (u64),
+/// minimum_out_amount (u64)
+pub const SWAP_DATA_TEMPLATE: [u8; MAX_INSTRUCTION_DATA_LEN] = data_template(&SWAP_DISCRIMINATOR);
+
 pub struct Perena;
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "borsh",
+    derive(borsh::BorshSerialize, borsh::BorshDeserialize)
+)]
 pub struct PerenaSwapData {
     pub in_index: u8,
     pub out_index: u8,
 }
 
+impl PerenaSwapData {
+    pub const fn new(in_index: u8, out_index: u8) -> Self {
+        Self {
+            in_index,
+            out_index,
+        }
+    }
+}
+
 impl TryFrom<&[u8]> for PerenaSwapData {
     type Error = ProgramError;
 
     fn try_from(data: &[u8]) -> Result<Self, Self::Error> {
-        if data.len() < 2 {
+        let [in_index, out_index, ..] = *data else {
             return Err(ProgramError::InvalidInstructionData);
-        }
+        };
         Ok(Self {
-            in_index: data[0],
-            out_index: data[1],
+            in_index,
+            out_index,
         })
     }
 }
@@ -56,6 +77,7 @@ pub struct PerenaSwapAccounts<'info> {
 impl<'info> TryFrom<&'info [AccountView]> for PerenaSwapAccounts<'info> {
     type Error = ProgramError;
 
+    #[inline(always)]
     fn try_from(accounts: &'info [AccountView]) -> Result<Self, Self::Error> {
         if accounts.len() < 12 {
             return Err(ProgramError::NotEnoughAccountKeys);
@@ -67,6 +89,17 @@ impl<'info> TryFrom<&'info [AccountView]> for PerenaSwapAccounts<'info> {
             return Err(ProgramError::NotEnoughAccountKeys);
         };
 
+        #[cfg(feature = "strict-validation")]
+        beethoven_core::validation::check_fixed_accounts(
+            ACCOUNT_LABELS,
+            (perena_program, &PERENA_PROGRAM_ID),
+            &[
+                ("token_program", token_program),
+                ("token_2022_program", token_2022_program),
+            ],
+            None,
+        )?;
+
         Ok(PerenaSwapAccounts {
             perena_program,
             pool,
@@ -84,17 +117,71 @@ impl<'info> TryFrom<&'info [AccountView]> for PerenaSwapAccounts<'info> {
     }
 }
 
-impl<'info> Swap<'info> for Perena {
-    type Accounts = PerenaSwapAccounts<'info>;
-    type Data = PerenaSwapData;
+/// What this adapter supports, as `ProtocolKind::Perena.capabilities()` reports it
+pub const CAPABILITIES: beethoven_core::Capabilities =
+    beethoven_core::ProtocolKind::Perena.capabilities();
 
-    fn swap_signed(
-        ctx: &Self::Accounts,
+/// Name of each account after the detector, in order, for error and log output.
+pub const ACCOUNT_LABELS: &[&str] = &[
+    "pool",
+    "in_mint",
+    "out_mint",
+    "in_trader",
+    "out_trader",
+    "in_vault",
+    "out_vault",
+    "numeraire_config",
+    "payer",
+    "token_program",
+    "token_2022_program",
+];
+
+impl<'info> PerenaSwapAccounts<'info> {
+    /// The accounts in the order the CPI passes them, without the detector.
+    #[inline(always)]
+    pub fn as_ordered_slice(&self) -> [&'info AccountView; 11] {
+        [
+            self.pool,
+            self.in_mint,
+            self.out_mint,
+            self.in_trader,
+            self.out_trader,
+            self.in_vault,
+            self.out_vault,
+            self.numeraire_config,
+            self.payer,
+            self.token_program,
+            self.token_2022_program,
+        ]
+    }
+
+    /// Iterate over [`Self::as_ordered_slice`].
+    #[inline(always)]
+    pub fn iter(&self) -> impl Iterator<Item = &'info AccountView> {
+        self.as_ordered_slice().into_iter()
+    }
+}
+
+impl Perena {
+    /// [`Swap::swap_signed`] with the instruction data assembled in caller-provided
+    /// `scratch` (at least [`MAX_INSTRUCTION_DATA_LEN`] bytes) instead of this frame.
+    pub fn swap_signed_with_scratch(
+        ctx: &PerenaSwapAccounts<'_>,
         in_amount: u64,
         minimum_out_amount: u64,
-        data: &Self::Data,
+        data: &PerenaSwapData,
         signer_seeds: &[Signer],
+        scratch: &mut [MaybeUninit<u8>],
     ) -> ProgramResult {
+        #[cfg(feature = "strict-validation")]
+        beethoven_core::validation::check_signer(ctx.payer, signer_seeds).inspect_err(|_| {
+            beethoven_core::validation::log_account_error(
+                ACCOUNT_LABELS,
+                "payer",
+                "missing signature",
+            )
+        })?;
+
         let accounts = [
             InstructionAccount::writable(ctx.pool.address()),
             InstructionAccount::writable(ctx.in_mint.address()),
@@ -109,45 +196,41 @@ impl<'info> Swap<'info> for Perena {
             InstructionAccount::readonly(ctx.token_2022_program.address()),
         ];
 
-        let account_infos = [
-            ctx.pool,
-            ctx.in_mint,
-            ctx.out_mint,
-            ctx.in_trader,
-            ctx.out_trader,
-            ctx.in_vault,
-            ctx.out_vault,
-            ctx.numeraire_config,
-            ctx.payer,
-            ctx.token_program,
-            ctx.token_2022_program,
-        ];
-
-        let mut instruction_data = MaybeUninit::<[u8; 26]>::uninit();
-        unsafe {
-            let ptr = instruction_data.as_mut_ptr() as *mut u8;
-            core::ptr::copy_nonoverlapping(SWAP_DISCRIMINATOR.as_ptr(), ptr, 8);
-            core::ptr::write(ptr.add(8), data.in_index);
-            core::ptr::write(ptr.add(9), data.out_index);
-            core::ptr::copy_nonoverlapping(in_amount.to_le_bytes().as_ptr(), ptr.add(10), 8);
-            core::ptr::copy_nonoverlapping(
-                minimum_out_amount.to_le_bytes().as_ptr(),
-                ptr.add(18),
-                8,
-            );
-        }
+        let account_infos = ctx.as_ordered_slice();
 
         let instruction = InstructionView {
             program_id: &PERENA_PROGRAM_ID,
             accounts: &accounts,
-            data: unsafe {
-                core::slice::from_raw_parts(instruction_data.as_ptr() as *const u8, 26)
-            },
+            data: swap_instruction_data(scratch, in_amount, minimum_out_amount, data)?,
         };
 
         invoke_signed(&instruction, &account_infos, signer_seeds)
     }
+}
+
+impl<'info> Swap<'info> for Perena {
+    type Accounts = PerenaSwapAccounts<'info>;
+    type Data = PerenaSwapData;
+
+    fn swap_signed(
+        ctx: &Self::Accounts,
+        in_amount: u64,
+        minimum_out_amount: u64,
+        data: &Self::Data,
+        signer_seeds: &[Signer],
+    ) -> ProgramResult {
+        let mut scratch = Scratch::<MAX_INSTRUCTION_DATA_LEN>::new();
+        Self::swap_signed_with_scratch(
+            ctx,
+            in_amount,
+            minimum_out_amount,
+            data,
+            signer_seeds,
+            scratch.as_uninit_mut(),
+        )
+    }
 
+    #[inline(always)]
     fn swap(
         ctx: &Self::Accounts,
         in_amount: u64,
@@ -157,3 +240,19 @@ impl<'info> Swap<'info> for Perena {
         Self::swap_signed(ctx, in_amount, minimum_out_amount, data, &[])
     }
 }
+
+/// Write the `swap` instruction data into `scratch` by patching [`SWAP_DATA_TEMPLATE`].
+#[inline(always)]
+pub fn swap_instruction_data<'a>(
+    scratch: &'a mut [MaybeUninit<u8>],
+    in_amount: u64,
+    minimum_out_amount: u64,
+    swap_data: &PerenaSwapData,
+) -> Result<&'a [u8], ProgramError> {
+    let data = from_template(scratch, &SWAP_DATA_TEMPLATE)?;
+    data[8] = swap_data.in_index;
+    data[9] = swap_data.out_index;
+    data[10..18].copy_from_slice(&in_amount.to_le_bytes());
+    data[18..26].copy_from_slice(&minimum_out_amount.to_le_bytes());
+    Ok(data)
+}
@@ -1,37 +1,56 @@
 #![no_std]
 
+pub use beethoven_core::program_ids::SOLFI_PROGRAM_ID;
 use {
-    beethoven_core::Swap,
+    beethoven_core::{
+        cpi::invoke_signed,
+        passthrough::forward_accounts,
+        scratch::{data_template, from_template, Scratch},
+        Swap,
+    },
     core::mem::MaybeUninit,
     solana_account_view::AccountView,
-    solana_address::Address,
-    solana_instruction_view::{
-        cpi::{invoke_signed, Signer},
-        InstructionAccount, InstructionView,
-    },
+    solana_instruction_view::{cpi::Signer, InstructionAccount, InstructionView},
     solana_program_error::{ProgramError, ProgramResult},
 };
 
-pub const SOLFI_PROGRAM_ID: Address =
-    Address::from_str_const("SoLFiHG9TfgtdUXUjWAxi3LtvYuFyDLVhBWxdMZxyCe");
-
 const SWAP_DISCRIMINATOR: u8 = 7;
 
+/// Largest instruction data this adapter builds
+pub const MAX_INSTRUCTION_DATA_LEN: usize = 18;
+
+/// `swap` instruction data with every field after the discriminator zeroed.
+///
+/// Layout: discriminator (u8), in_amount (u64), minimum_out_amount (u64),
+/// is_quote_to_base (u8)
+pub const SWAP_DATA_TEMPLATE: [u8; MAX_INSTRUCTION_DATA_LEN] = data_template(&[SWAP_DISCRIMINATOR]);
+
 pub struct SolFi;
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "borsh",
+    derive(borsh::BorshSerialize, borsh::BorshDeserialize)
+)]
 pub struct SolFiSwapData {
     pub is_quote_to_base: bool,
 }
 
+impl SolFiSwapData {
+    pub const fn new(is_quote_to_base: bool) -> Self {
+        Self { is_quote_to_base }
+    }
+}
+
 impl TryFrom<&[u8]> for SolFiSwapData {
     type Error = ProgramError;
 
     fn try_from(data: &[u8]) -> Result<Self, Self::Error> {
-        if data.is_empty() {
+        let [is_quote_to_base, ..] = data else {
             return Err(ProgramError::InvalidInstructionData);
-        }
+        };
         Ok(Self {
-            is_quote_to_base: data[0] != 0,
+            is_quote_to_base: *is_quote_to_base != 0,
         })
     }
 }
@@ -51,6 +70,7 @@ pub struct SolFiSwapAccounts<'info> {
 impl<'info> TryFrom<&'info [AccountView]> for SolFiSwapAccounts<'info> {
     type Error = ProgramError;
 
+    #[inline(always)]
     fn try_from(accounts: &'info [AccountView]) -> Result<Self, Self::Error> {
         if accounts.len() < 9 {
             return Err(ProgramError::NotEnoughAccountKeys);
@@ -62,6 +82,14 @@ impl<'info> TryFrom<&'info [AccountView]> for SolFiSwapAccounts<'info> {
             return Err(ProgramError::NotEnoughAccountKeys);
         };
 
+        #[cfg(feature = "strict-validation")]
+        beethoven_core::validation::check_fixed_accounts(
+            ACCOUNT_LABELS,
+            (solfi_program, &SOLFI_PROGRAM_ID),
+            &[("token_program", token_program)],
+            Some(("instructions_sysvar", instructions_sysvar)),
+        )?;
+
         Ok(SolFiSwapAccounts {
             solfi_program,
             token_transfer_authority,
@@ -76,17 +104,66 @@ impl<'info> TryFrom<&'info [AccountView]> for SolFiSwapAccounts<'info> {
     }
 }
 
-impl<'info> Swap<'info> for SolFi {
-    type Accounts = SolFiSwapAccounts<'info>;
-    type Data = SolFiSwapData;
+/// What this adapter supports, as `ProtocolKind::SolFi.capabilities()` reports it
+pub const CAPABILITIES: beethoven_core::Capabilities =
+    beethoven_core::ProtocolKind::SolFi.capabilities();
 
-    fn swap_signed(
-        ctx: &Self::Accounts,
+/// Name of each account after the detector, in order, for error and log output.
+pub const ACCOUNT_LABELS: &[&str] = &[
+    "token_transfer_authority",
+    "market_account",
+    "base_vault",
+    "quote_vault",
+    "user_base_ata",
+    "user_quote_ata",
+    "token_program",
+    "instructions_sysvar",
+];
+
+impl<'info> SolFiSwapAccounts<'info> {
+    /// The accounts in the order the CPI passes them, without the detector.
+    #[inline(always)]
+    pub fn as_ordered_slice(&self) -> [&'info AccountView; 8] {
+        [
+            self.token_transfer_authority,
+            self.market_account,
+            self.base_vault,
+            self.quote_vault,
+            self.user_base_ata,
+            self.user_quote_ata,
+            self.token_program,
+            self.instructions_sysvar,
+        ]
+    }
+
+    /// Iterate over [`Self::as_ordered_slice`].
+    #[inline(always)]
+    pub fn iter(&self) -> impl Iterator<Item = &'info AccountView> {
+        self.as_ordered_slice().into_iter()
+    }
+}
+
+impl SolFi {
+    /// [`Swap::swap_signed`] with the instruction data assembled in caller-provided
+    /// `scratch` (at least [`MAX_INSTRUCTION_DATA_LEN`] bytes) instead of this frame.
+    pub fn swap_signed_with_scratch(
+        ctx: &SolFiSwapAccounts<'_>,
         in_amount: u64,
         minimum_out_amount: u64,
-        data: &Self::Data,
+        data: &SolFiSwapData,
         signer_seeds: &[Signer],
+        scratch: &mut [MaybeUninit<u8>],
     ) -> ProgramResult {
+        #[cfg(feature = "strict-validation")]
+        beethoven_core::validation::check_signer(ctx.token_transfer_authority, signer_seeds)
+            .inspect_err(|_| {
+                beethoven_core::validation::log_account_error(
+                    ACCOUNT_LABELS,
+                    "token_transfer_authority",
+                    "missing signature",
+                )
+            })?;
+
         let accounts = [
             InstructionAccount::writable_signer(ctx.token_transfer_authority.address()),
             InstructionAccount::writable(ctx.market_account.address()),
@@ -98,41 +175,91 @@ impl<'info> Swap<'info> for SolFi {
             InstructionAccount::readonly(ctx.instructions_sysvar.address()),
         ];
 
-        let account_infos = [
-            ctx.token_transfer_authority,
-            ctx.market_account,
-            ctx.base_vault,
-            ctx.quote_vault,
-            ctx.user_base_ata,
-            ctx.user_quote_ata,
-            ctx.token_program,
-            ctx.instructions_sysvar,
-        ];
-
-        let mut instruction_data = MaybeUninit::<[u8; 18]>::uninit();
-        unsafe {
-            let ptr = instruction_data.as_mut_ptr() as *mut u8;
-            core::ptr::write(ptr, SWAP_DISCRIMINATOR);
-            core::ptr::copy_nonoverlapping(in_amount.to_le_bytes().as_ptr(), ptr.add(1), 8);
-            core::ptr::copy_nonoverlapping(
-                minimum_out_amount.to_le_bytes().as_ptr(),
-                ptr.add(9),
-                8,
-            );
-            core::ptr::write(ptr.add(17), data.is_quote_to_base as u8);
-        }
+        let account_infos = ctx.as_ordered_slice();
 
         let instruction = InstructionView {
             program_id: &SOLFI_PROGRAM_ID,
             accounts: &accounts,
-            data: unsafe {
-                core::slice::from_raw_parts(instruction_data.as_ptr() as *const u8, 18)
+            data: swap_instruction_data(scratch, in_amount, minimum_out_amount, data)?,
+        };
+
+        invoke_signed(&instruction, &account_infos, signer_seeds)
+    }
+
+    /// Swap by forwarding the 8 accounts after the SolFi program to the CPI unchanged,
+    /// skipping the per-field meta rebuild of [`SolFiSwapAccounts`].
+    ///
+    /// `accounts` must already be in [`SolFiSwapAccounts`] order, with every account the
+    /// swap writes to passed writable. Flags are inherited from the outer instruction,
+    /// except the transfer authority, which is always forwarded as a signer so PDA
+    /// authorities can sign through `signer_seeds`.
+    ///
+    /// With `strict-validation`, the accounts get the same checks as through
+    /// [`SolFiSwapAccounts`]'s `TryFrom`.
+    pub fn swap_signed_passthrough(
+        accounts: &[AccountView],
+        in_amount: u64,
+        minimum_out_amount: u64,
+        data: &SolFiSwapData,
+        signer_seeds: &[Signer],
+    ) -> ProgramResult {
+        #[cfg(feature = "strict-validation")]
+        SolFiSwapAccounts::try_from(accounts)?;
+
+        let (mut metas, account_infos) = forward_accounts::<8>(accounts)?;
+
+        #[cfg(feature = "strict-validation")]
+        beethoven_core::validation::check_signer(account_infos[0], signer_seeds).inspect_err(
+            |_| {
+                beethoven_core::validation::log_account_error(
+                    ACCOUNT_LABELS,
+                    "token_transfer_authority",
+                    "missing signature",
+                )
             },
+        )?;
+
+        metas[0] = InstructionAccount::writable_signer(account_infos[0].address());
+
+        let mut scratch = Scratch::<MAX_INSTRUCTION_DATA_LEN>::new();
+        let instruction = InstructionView {
+            program_id: &SOLFI_PROGRAM_ID,
+            accounts: &metas,
+            data: swap_instruction_data(
+                scratch.as_uninit_mut(),
+                in_amount,
+                minimum_out_amount,
+                data,
+            )?,
         };
 
         invoke_signed(&instruction, &account_infos, signer_seeds)
     }
+}
 
+impl<'info> Swap<'info> for SolFi {
+    type Accounts = SolFiSwapAccounts<'info>;
+    type Data = SolFiSwapData;
+
+    fn swap_signed(
+        ctx: &Self::Accounts,
+        in_amount: u64,
+        minimum_out_amount: u64,
+        data: &Self::Data,
+        signer_seeds: &[Signer],
+    ) -> ProgramResult {
+        let mut scratch = Scratch::<MAX_INSTRUCTION_DATA_LEN>::new();
+        Self::swap_signed_with_scratch(
+            ctx,
+            in_amount,
+            minimum_out_amount,
+            data,
+            signer_seeds,
+            scratch.as_uninit_mut(),
+        )
+    }
+
+    #[inline(always)]
     fn swap(
         ctx: &Self::Accounts,
         in_amount: u64,
@@ -142,3 +269,18 @@ impl<'info> Swap<'info> for SolFi {
         Self::swap_signed(ctx, in_amount, minimum_out_amount, data, &[])
     }
 }
+
+/// Write the `swap` instruction data into `scratch` by patching [`SWAP_DATA_TEMPLATE`].
+#[inline(always)]
+pub fn swap_instruction_data<'a>(
+    scratch: &'a mut [MaybeUninit<u8>],
+    in_amount: u64,
+    minimum_out_amount: u64,
+    swap_data: &SolFiSwapData,
+) -> Result<&'a [u8], ProgramError> {
+    let data = from_template(scratch, &SWAP_DATA_TEMPLATE)?;
+    data[1..9].copy_from_slice(&in_amount.to_le_bytes());
+    data[9..17].copy_from_slice(&minimum_out_amount.to_le_bytes());
+    data[17] = swap_data.is_quote_to_base as u8;
+    Ok(data)
+}
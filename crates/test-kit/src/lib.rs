@@ -0,0 +1,420 @@
+//! LiteSVM and Mollusk helpers for integration tests of programs built on Beethoven.
+//!
+//! Beethoven's own integration tests use these to set up the SVM, create mints and
+//! token accounts, load mainnet accounts dumped as JSON fixtures (see
+//! `fixture-snapshot`) and send transactions; programs routing through Beethoven can
+//! use the same helpers against their own program.
+
+use {
+    base64::{engine::general_purpose::STANDARD, Engine as _},
+    litesvm::LiteSVM,
+    mollusk_svm::{program::keyed_account_for_system_program, result::ProgramResult, Mollusk},
+    solana_account::Account,
+    solana_address::Address,
+    solana_instruction::Instruction,
+    solana_keypair::Keypair,
+    solana_program_option::COption,
+    solana_program_pack::Pack,
+    solana_rent::Rent,
+    solana_signer::Signer,
+    solana_transaction::Transaction,
+    spl_token_interface::state::{Account as TokenAccount, AccountState, Mint},
+    std::str::FromStr,
+};
+
+// =============================================================================
+// Constants
+// =============================================================================
+
+pub const TOKEN_PROGRAM_ID: Address =
+    Address::from_str_const("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+pub const SYSTEM_PROGRAM_ID: Address = Address::from_str_const("11111111111111111111111111111111");
+pub const BPF_LOADER: Address =
+    Address::from_str_const("BPFLoader2111111111111111111111111111111111");
+
+// =============================================================================
+// SVM Setup
+// =============================================================================
+
+pub fn setup_svm() -> LiteSVM {
+    LiteSVM::new()
+}
+
+pub fn setup_svm_with_program(program_id: Address, program_bytes: &[u8]) -> LiteSVM {
+    let mut svm = LiteSVM::new();
+    let _ = svm.add_program(program_id, program_bytes);
+    svm
+}
+
+// =============================================================================
+// Mollusk Setup
+// =============================================================================
+
+/// Mollusk with each of `programs` loaded, plus the SPL Token program.
+pub fn setup_mollusk_with_programs(programs: &[(Address, &[u8])]) -> Mollusk {
+    let mut mollusk = Mollusk::default();
+
+    for (program_id, bytes) in programs {
+        mollusk.add_program_with_loader_and_elf(program_id, &BPF_LOADER, bytes);
+    }
+
+    // Add the SPL Token program
+    mollusk_svm_programs_token::token::add_program(&mut mollusk);
+
+    mollusk
+}
+
+pub fn get_mollusk_system_program() -> (Address, Account) {
+    keyed_account_for_system_program()
+}
+
+pub fn get_mollusk_token_program() -> (Address, Account) {
+    mollusk_svm_programs_token::token::keyed_account()
+}
+
+pub fn create_mollusk_program_account(program_bytes: &[u8]) -> Account {
+    Account {
+        lamports: 1,
+        data: program_bytes.to_vec(),
+        owner: BPF_LOADER,
+        executable: true,
+        rent_epoch: 0,
+    }
+}
+
+/// Verify mollusk result is successful and return resulting accounts
+pub fn assert_mollusk_success(result: &mollusk_svm::result::InstructionResult) {
+    match &result.program_result {
+        ProgramResult::Success => {}
+        ProgramResult::Failure(e) => {
+            panic!(
+                "Mollusk execution failed: {:?}. Compute units: {}",
+                e, result.compute_units_consumed
+            );
+        }
+        ProgramResult::UnknownError(e) => {
+            panic!(
+                "Mollusk unknown error: {:?}. Compute units: {}",
+                e, result.compute_units_consumed
+            );
+        }
+    }
+}
+
+// =============================================================================
+// Token Program Helpers
+// =============================================================================
+
+/// Create an Account for a Mint
+pub fn create_account_for_mint(mint_data: Mint) -> Account {
+    let mut data = vec![0u8; Mint::LEN];
+    Mint::pack(mint_data, &mut data).unwrap();
+
+    Account {
+        lamports: Rent::default().minimum_balance(Mint::LEN),
+        data,
+        owner: TOKEN_PROGRAM_ID,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+/// Create an Account for a Token Account
+pub fn create_account_for_token_account(token_account_data: TokenAccount) -> Account {
+    let mut data = vec![0u8; TokenAccount::LEN];
+    TokenAccount::pack(token_account_data, &mut data).unwrap();
+
+    Account {
+        lamports: Rent::default().minimum_balance(TokenAccount::LEN),
+        data,
+        owner: TOKEN_PROGRAM_ID,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+/// Create and set a token account in the SVM
+pub fn create_token_account(
+    svm: &mut LiteSVM,
+    owner: &Address,
+    mint: &Address,
+    amount: u64,
+) -> Address {
+    let pubkey = Keypair::new().pubkey();
+    let account = create_account_for_token_account(TokenAccount {
+        mint: *mint,
+        owner: *owner,
+        amount,
+        delegate: COption::None,
+        state: AccountState::Initialized,
+        is_native: COption::None,
+        delegated_amount: 0,
+        close_authority: COption::None,
+    });
+    svm.set_account(pubkey, account).unwrap();
+    pubkey
+}
+
+/// Create and set a token account at a specific address
+pub fn create_token_account_at(
+    svm: &mut LiteSVM,
+    pubkey: Address,
+    owner: &Address,
+    mint: &Address,
+    amount: u64,
+) {
+    let account = create_account_for_token_account(TokenAccount {
+        mint: *mint,
+        owner: *owner,
+        amount,
+        delegate: COption::None,
+        state: AccountState::Initialized,
+        is_native: COption::None,
+        delegated_amount: 0,
+        close_authority: COption::None,
+    });
+    svm.set_account(pubkey, account).unwrap();
+}
+
+/// Create and set a mint in the SVM
+pub fn create_mint(svm: &mut LiteSVM, mint_authority: &Address, decimals: u8) -> Address {
+    let pubkey = Keypair::new().pubkey();
+    let account = create_account_for_mint(Mint {
+        mint_authority: COption::Some(*mint_authority),
+        supply: 0,
+        decimals,
+        is_initialized: true,
+        freeze_authority: COption::None,
+    });
+    svm.set_account(pubkey, account).unwrap();
+    pubkey
+}
+
+/// Create and set a mint at a specific address
+pub fn create_mint_at(
+    svm: &mut LiteSVM,
+    pubkey: Address,
+    mint_authority: &Address,
+    decimals: u8,
+    supply: u64,
+) {
+    let account = create_account_for_mint(Mint {
+        mint_authority: COption::Some(*mint_authority),
+        supply,
+        decimals,
+        is_initialized: true,
+        freeze_authority: COption::None,
+    });
+    svm.set_account(pubkey, account).unwrap();
+}
+
+// =============================================================================
+// Mock Protocol Account Helpers
+// =============================================================================
+
+pub fn create_program_account(svm: &mut LiteSVM, program_id: Address) {
+    svm.set_account(
+        program_id,
+        Account {
+            lamports: Rent::default().minimum_balance(0),
+            data: vec![],
+            owner: solana_sdk_ids::bpf_loader::ID,
+            executable: true,
+            rent_epoch: 0,
+        },
+    )
+    .unwrap();
+}
+
+pub fn create_mock_account(svm: &mut LiteSVM, owner: &Address, data: Vec<u8>) -> Address {
+    let pubkey = Keypair::new().pubkey();
+    svm.set_account(
+        pubkey,
+        Account {
+            lamports: Rent::default().minimum_balance(data.len()),
+            data,
+            owner: *owner,
+            executable: false,
+            rent_epoch: 0,
+        },
+    )
+    .unwrap();
+    pubkey
+}
+
+pub fn create_mock_account_at(svm: &mut LiteSVM, pubkey: Address, owner: &Address, data: Vec<u8>) {
+    svm.set_account(
+        pubkey,
+        Account {
+            lamports: Rent::default().minimum_balance(data.len()),
+            data,
+            owner: *owner,
+            executable: false,
+            rent_epoch: 0,
+        },
+    )
+    .unwrap();
+}
+
+// =============================================================================
+// Transaction Helpers
+// =============================================================================
+
+pub fn send_transaction(
+    svm: &mut LiteSVM,
+    payer: &Keypair,
+    instruction: Instruction,
+) -> Result<u64, String> {
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&payer.pubkey()),
+        &[payer],
+        svm.latest_blockhash(),
+    );
+
+    match svm.send_transaction(tx) {
+        Ok(meta) => {
+            for log in &meta.logs {
+                println!("{}", log);
+            }
+            println!("Compute units consumed: {}", meta.compute_units_consumed);
+            Ok(meta.compute_units_consumed)
+        }
+        Err(e) => {
+            for log in &e.meta.logs {
+                println!("{}", log);
+            }
+            Err(format!("{:?}", e.err))
+        }
+    }
+}
+
+pub fn send_transaction_with_signers(
+    svm: &mut LiteSVM,
+    payer: &Keypair,
+    signers: &[&Keypair],
+    instruction: Instruction,
+) -> Result<u64, String> {
+    let mut all_signers: Vec<&Keypair> = vec![payer];
+    all_signers.extend(signers);
+
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&payer.pubkey()),
+        &all_signers,
+        svm.latest_blockhash(),
+    );
+
+    match svm.send_transaction(tx) {
+        Ok(meta) => {
+            for log in &meta.logs {
+                println!("{}", log);
+            }
+            println!("Compute units consumed: {}", meta.compute_units_consumed);
+            Ok(meta.compute_units_consumed)
+        }
+        Err(e) => {
+            for log in &e.meta.logs {
+                println!("{}", log);
+            }
+            Err(format!("{:?}", e.err))
+        }
+    }
+}
+
+/// Send `instructions` in order in one transaction, which lands or fails as a whole.
+pub fn send_instructions(
+    svm: &mut LiteSVM,
+    payer: &Keypair,
+    instructions: &[Instruction],
+) -> Result<u64, String> {
+    let tx = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&payer.pubkey()),
+        &[payer],
+        svm.latest_blockhash(),
+    );
+
+    match svm.send_transaction(tx) {
+        Ok(meta) => {
+            for log in &meta.logs {
+                println!("{}", log);
+            }
+            println!("Compute units consumed: {}", meta.compute_units_consumed);
+            Ok(meta.compute_units_consumed)
+        }
+        Err(e) => {
+            for log in &e.meta.logs {
+                println!("{}", log);
+            }
+            Err(format!("{:?}", e.err))
+        }
+    }
+}
+
+// =============================================================================
+// Fixture Loading
+// =============================================================================
+
+pub fn load_fixture_bytes(path: &str) -> Vec<u8> {
+    std::fs::read(path).unwrap_or_else(|_| panic!("Failed to read fixture: {}", path))
+}
+
+pub fn load_fixture_account(path: &str, owner: &Address) -> Account {
+    let data = load_fixture_bytes(path);
+    Account {
+        lamports: Rent::default().minimum_balance(data.len()),
+        data,
+        owner: *owner,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+/// Load a JSON fixture exported by `solana account --output json-compact`
+/// Returns (pubkey, Account)
+pub fn load_json_fixture(path: &str) -> (Address, Account) {
+    let contents = std::fs::read_to_string(path)
+        .unwrap_or_else(|_| panic!("Failed to read fixture: {}", path));
+    let json: serde_json::Value = serde_json::from_str(&contents)
+        .unwrap_or_else(|_| panic!("Failed to parse JSON: {}", path));
+
+    let pubkey_str = json["pubkey"].as_str().expect("Missing pubkey field");
+    let pubkey = Address::from_str(pubkey_str).expect("Invalid pubkey");
+
+    let account_json = &json["account"];
+    let lamports = account_json["lamports"].as_u64().expect("Missing lamports");
+    let owner_str = account_json["owner"].as_str().expect("Missing owner");
+    let owner = Address::from_str(owner_str).expect("Invalid owner pubkey");
+    let executable = account_json["executable"].as_bool().unwrap_or(false);
+
+    let data_array = account_json["data"].as_array().expect("Missing data array");
+    let data_b64 = data_array[0].as_str().expect("Missing data string");
+    let data = STANDARD
+        .decode(data_b64)
+        .expect("Failed to decode base64 data");
+
+    (
+        pubkey,
+        Account {
+            lamports,
+            data,
+            owner,
+            executable,
+            rent_epoch: 0,
+        },
+    )
+}
+
+/// Load JSON fixture and set it in the SVM
+pub fn load_and_set_json_fixture(svm: &mut LiteSVM, path: &str) -> Address {
+    let (pubkey, account) = load_json_fixture(path);
+    svm.set_account(pubkey, account).unwrap();
+    pubkey
+}
+
+/// Load and deploy a program from .so file
+pub fn load_program(svm: &mut LiteSVM, program_id: Address, so_path: &str) {
+    let program_bytes = load_fixture_bytes(so_path);
+    let _ = svm.add_program(program_id, &program_bytes);
+}
@@ -0,0 +1,82 @@
+//! The interface of the `beethoven-test` program in `program-test`: its test deployment
+//! address, instruction discriminators and authority PDA seed.
+//!
+//! The program matches on these constants and `beethoven_client::test_program` builds
+//! instructions from them, so the two cannot drift apart.
+
+#![no_std]
+
+use solana_address::Address;
+
+/// Address the integration tests deploy `beethoven-test` at
+pub const TEST_PROGRAM_ID: Address = Address::new_from_array([0x01; 32]);
+
+/// First byte of every `beethoven-test` instruction.
+pub mod discriminator {
+    pub const DEPOSIT: u8 = 0;
+    pub const SWAP: u8 = 1;
+    pub const ROUTE: u8 = 2;
+    pub const DEPOSIT_SIGNED: u8 = 3;
+    pub const SWAP_SIGNED: u8 = 4;
+    /// [`SWAP`] with the swap data in a versioned `beethoven::envelope::Envelope`
+    pub const SWAP_ENVELOPED: u8 = 5;
+    /// A multi-hop swap through `beethoven::SwapRoute`, each leg spending what the one
+    /// before it credited
+    pub const SWAP_ROUTE: u8 = 6;
+    /// [`SWAP`] setting the realized `beethoven::SwapOutcome` as return data: in_spent
+    /// (u64), then out_received (u64)
+    pub const SWAP_OUTCOME: u8 = 7;
+    /// A swap followed by a deposit of everything it returned, through `beethoven::zap`
+    pub const ZAP: u8 = 8;
+    /// [`SWAP`] on the venue a `beethoven::Detection` identifies, encoded before the
+    /// amounts: kind (u8), then index or protocol (u8)
+    pub const SWAP_DETECTED: u8 = 9;
+    /// [`SWAP`] through `beethoven::try_from_swap_context_with`, which knows
+    /// [`REGISTERED_PROGRAM_ID`](super::REGISTERED_PROGRAM_ID) from the program's
+    /// registry
+    pub const SWAP_REGISTRY: u8 = 10;
+    /// Withdraw `amount` in the venue's receipt tokens through
+    /// `beethoven::WithdrawContext`
+    pub const WITHDRAW: u8 = 11;
+    /// Borrow `amount` of a Kamino reserve's liquidity
+    pub const BORROW: u8 = 12;
+    /// Repay `amount` of a Kamino reserve's liquidity
+    pub const REPAY: u8 = 13;
+    /// Stake `lamports` with Marinade
+    pub const STAKE: u8 = 15;
+    /// Unstake `amount` with Marinade or an SPL stake pool, followed by the venue's
+    /// unstake data
+    pub const UNSTAKE: u8 = 16;
+    /// Add liquidity to a Raydium CPMM pool
+    pub const ADD_LIQUIDITY: u8 = 17;
+    /// Remove liquidity from a Raydium CPMM pool
+    pub const REMOVE_LIQUIDITY: u8 = 18;
+    /// Place a limit order on a Manifest market
+    pub const PLACE_ORDER: u8 = 19;
+    /// Cancel a Manifest order by its sequence number
+    pub const CANCEL_ORDER: u8 = 20;
+    /// Place a Drift perp market order
+    pub const PERP_ORDER: u8 = 21;
+    /// [`SWAP_OUTCOME`] through `beethoven::swap_with_fees`, the platform and referral
+    /// rates (u16 each) after the amounts
+    pub const SWAP_WITH_FEES: u8 = 22;
+    /// [`SWAP`] on SolFi or SolFi V2 through the venue's `swap_signed_passthrough`,
+    /// forwarding the accounts as passed
+    pub const SWAP_PASSTHROUGH: u8 = 23;
+    /// [`SWAP`] through `beethoven::swap_exact_out`, the amounts being the exact
+    /// out_amount, then the maximum_in_amount
+    pub const SWAP_EXACT_OUT: u8 = 24;
+    /// [`SWAP_ROUTE`] through `beethoven::SharedSwapRoute`, each account passed once and
+    /// the legs naming theirs by index
+    pub const SWAP_ROUTE_SHARED: u8 = 25;
+}
+
+/// Program the test program registers as an external swap venue
+pub const REGISTERED_PROGRAM_ID: Address = Address::new_from_array([0x02; 32]);
+
+/// Error the registered venue's handler fails with, so tests can see it was reached
+pub const REGISTERED_SWAP_ERROR: u32 = 0x7e57;
+
+/// Seed of the program's authority PDA, which owns the accounts the `*_signed`
+/// instructions move funds from
+pub const AUTHORITY_SEED: &[u8] = b"authority";
@@ -0,0 +1,49 @@
+//! Echoes the instruction it receives back as return data. Deployed at a venue's
+//! program id in tests, it records the CPI an adapter issues without the venue itself.
+//!
+//! Return data: program id (32 bytes), account count (u8), then per account its
+//! address (32 bytes), is_signer (u8) and is_writable (u8), then the instruction data.
+
+#![no_std]
+#![allow(unexpected_cfgs)]
+
+use {
+    pinocchio::{error::ProgramError, AccountView, Address, ProgramResult},
+    solana_instruction_view::cpi::set_return_data,
+};
+
+/// Runtime limit on return data
+const MAX_RETURN_DATA: usize = 1024;
+const ACCOUNT_LEN: usize = 34;
+
+pinocchio::no_allocator!();
+pinocchio::nostd_panic_handler!();
+pinocchio::program_entrypoint!(process_instruction);
+
+#[inline(never)]
+pub fn process_instruction(
+    program_id: &Address,
+    accounts: &[AccountView],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let len = 33 + accounts.len() * ACCOUNT_LEN + instruction_data.len();
+    if len > MAX_RETURN_DATA {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    let mut echo = [0u8; MAX_RETURN_DATA];
+    echo[..32].copy_from_slice(program_id.as_ref());
+    echo[32] = accounts.len() as u8;
+    for (account, slot) in accounts
+        .iter()
+        .zip(echo[33..].chunks_exact_mut(ACCOUNT_LEN))
+    {
+        slot[..32].copy_from_slice(account.address().as_ref());
+        slot[32] = account.is_signer() as u8;
+        slot[33] = account.is_writable() as u8;
+    }
+    echo[len - instruction_data.len()..len].copy_from_slice(instruction_data);
+
+    set_return_data(&echo[..len]);
+    Ok(())
+}
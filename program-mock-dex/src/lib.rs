@@ -0,0 +1,144 @@
+//! Minimal constant-product DEX, deployed at `MOCK_DEX_PROGRAM_ID` in tests.
+//!
+//! Pools are written directly into the test SVM (see `beethoven_swap_mock_dex::state`),
+//! so the only instruction is `swap`, laid out as the adapter builds it:
+//!
+//! Accounts: user (signer), pool, pool_authority, base_vault, quote_vault,
+//! user_base_token, user_quote_token, token_program
+//!
+//! Data: discriminator (u8), in_amount (u64), minimum_out_amount (u64),
+//! is_quote_to_base (u8)
+
+#![no_std]
+#![allow(unexpected_cfgs)]
+
+use {
+    beethoven_core::token::token_account_amount,
+    beethoven_swap_mock_dex::{
+        quote::quote_exact_in,
+        state::{Pool, POOL_AUTHORITY_SEED},
+        MockDexSwapData, SLIPPAGE_EXCEEDED_ERROR, SWAP_DISCRIMINATOR,
+    },
+    pinocchio::{error::ProgramError, AccountView, Address, ProgramResult},
+    solana_instruction_view::{
+        cpi::{invoke_signed, Seed, Signer},
+        InstructionAccount, InstructionView,
+    },
+};
+
+const TOKEN_PROGRAM_ID: Address =
+    Address::from_str_const("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+
+pinocchio::no_allocator!();
+pinocchio::nostd_panic_handler!();
+pinocchio::program_entrypoint!(process_instruction);
+
+#[inline(never)]
+pub fn process_instruction(
+    program_id: &Address,
+    accounts: &[AccountView],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let (discriminator, data) = instruction_data
+        .split_first()
+        .ok_or(ProgramError::InvalidInstructionData)?;
+
+    match *discriminator {
+        SWAP_DISCRIMINATOR => swap(program_id, accounts, data),
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
+
+fn swap(program_id: &Address, accounts: &[AccountView], data: &[u8]) -> ProgramResult {
+    let [user, pool, pool_authority, base_vault, quote_vault, user_base_token, user_quote_token, token_program, ..] =
+        accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    let Some((in_amount, data)) = data.split_first_chunk::<8>() else {
+        return Err(ProgramError::InvalidInstructionData);
+    };
+    let Some((minimum_out_amount, data)) = data.split_first_chunk::<8>() else {
+        return Err(ProgramError::InvalidInstructionData);
+    };
+    let in_amount = u64::from_le_bytes(*in_amount);
+    let minimum_out_amount = u64::from_le_bytes(*minimum_out_amount);
+    let swap_data = MockDexSwapData::try_from(data)?;
+
+    if !pool.owned_by(program_id) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+    if token_program.address() != &TOKEN_PROGRAM_ID {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let (authority_bump, fee_bps) = {
+        let data = pool.try_borrow()?;
+        let state = Pool::from_account_data(&data)?;
+        if state.base_vault != *base_vault.address() || state.quote_vault != *quote_vault.address()
+        {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        (state.authority_bump, state.fee_bps.get())
+    };
+
+    let (user_in, vault_in, vault_out, user_out) = if swap_data.is_quote_to_base {
+        (user_quote_token, quote_vault, base_vault, user_base_token)
+    } else {
+        (user_base_token, base_vault, quote_vault, user_quote_token)
+    };
+
+    let quote = quote_exact_in(
+        fee_bps,
+        in_amount,
+        token_account_amount(vault_in)?,
+        token_account_amount(vault_out)?,
+    )
+    .ok_or(ProgramError::ArithmeticOverflow)?;
+    if quote.out_amount < minimum_out_amount {
+        return Err(ProgramError::Custom(SLIPPAGE_EXCEEDED_ERROR));
+    }
+
+    transfer(user_in, vault_in, user, in_amount, &[])?;
+
+    let bump = [authority_bump];
+    let seeds = [
+        Seed::from(POOL_AUTHORITY_SEED),
+        Seed::from(pool.address().as_ref()),
+        Seed::from(&bump),
+    ];
+    transfer(
+        vault_out,
+        user_out,
+        pool_authority,
+        quote.out_amount,
+        &[Signer::from(&seeds)],
+    )
+}
+
+/// SPL Token `Transfer` of `amount` from `from` to `to`.
+fn transfer(
+    from: &AccountView,
+    to: &AccountView,
+    authority: &AccountView,
+    amount: u64,
+    signers: &[Signer],
+) -> ProgramResult {
+    let mut data = [0; 9];
+    data[0] = 3;
+    data[1..].copy_from_slice(&amount.to_le_bytes());
+
+    let accounts = [
+        InstructionAccount::writable(from.address()),
+        InstructionAccount::writable(to.address()),
+        InstructionAccount::readonly_signer(authority.address()),
+    ];
+    let instruction = InstructionView {
+        program_id: &TOKEN_PROGRAM_ID,
+        accounts: &accounts,
+        data: &data,
+    };
+
+    invoke_signed(&instruction, &[from, to, authority], signers)
+}
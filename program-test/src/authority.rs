@@ -0,0 +1,28 @@
+#[cfg(feature = "strict-validation")]
+use pinocchio::{AccountView, ProgramResult};
+use {
+    beethoven_test_support::AUTHORITY_SEED,
+    pinocchio::Address,
+    solana_instruction_view::cpi::{Seed, Signer},
+};
+
+/// Run `f` with the seeds of `program_id`'s authority PDA, derived on chain.
+fn with_authority_seeds<R>(program_id: &Address, f: impl FnOnce(&[Seed]) -> R) -> R {
+    let (_, bump) = Address::find_program_address(&[AUTHORITY_SEED], program_id);
+    let bump = [bump];
+    f(&[Seed::from(AUTHORITY_SEED), Seed::from(&bump)])
+}
+
+/// Run `f` with the signer seeds of `program_id`'s authority PDA, derived on chain.
+pub fn with_authority_signer<R>(program_id: &Address, f: impl FnOnce(&[Signer]) -> R) -> R {
+    with_authority_seeds(program_id, |seeds| f(&[Signer::from(seeds)]))
+}
+
+/// Fail unless `authority` signed or is `program_id`'s authority PDA, the account
+/// [`with_authority_signer`] signs for.
+#[cfg(feature = "strict-validation")]
+pub fn check_authority(program_id: &Address, authority: &AccountView) -> ProgramResult {
+    with_authority_seeds(program_id, |seeds| {
+        beethoven::validation::check_authority(authority, program_id, &[seeds])
+    })
+}
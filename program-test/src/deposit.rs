@@ -1,51 +1,80 @@
 use {
-    beethoven::{try_from_deposit_context, Deposit, DepositContext},
-    pinocchio::{error::ProgramError, AccountView, ProgramResult},
+    crate::authority::with_authority_signer,
+    beethoven::prelude::*,
+    pinocchio::{error::ProgramError, AccountView, Address, ProgramResult},
+    solana_instruction_view::cpi::Signer,
 };
 
 /// Instruction data for Deposit
 ///
 /// Layout:
 /// [0..8] - amount (u64, little-endian)
-pub struct DepositInstructionData {
+/// [8..]  - protocol-specific data (parsed via DepositContext::try_from_deposit_data)
+pub struct DepositInstructionData<'a> {
     pub amount: u64,
+    pub extra_data: &'a [u8],
 }
 
-impl TryFrom<&[u8]> for DepositInstructionData {
+impl<'a> TryFrom<&'a [u8]> for DepositInstructionData<'a> {
     type Error = ProgramError;
 
-    fn try_from(data: &[u8]) -> Result<Self, Self::Error> {
-        if data.len() < 8 {
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        let Some((amount, extra_data)) = data.split_first_chunk() else {
             return Err(ProgramError::InvalidInstructionData);
-        }
+        };
         Ok(Self {
-            amount: u64::from_le_bytes(data[0..8].try_into().unwrap()),
+            amount: u64::from_le_bytes(*amount),
+            extra_data,
         })
     }
 }
 
 pub struct DepositInstruction<'a> {
     pub accounts: DepositContext<'a>,
-    pub data: DepositInstructionData,
+    pub data: DepositData,
+    pub amount: u64,
 }
 
 impl<'a> TryFrom<(&'a [AccountView], &[u8])> for DepositInstruction<'a> {
     type Error = ProgramError;
 
     fn try_from((accounts, data): (&'a [AccountView], &[u8])) -> Result<Self, Self::Error> {
+        let data = DepositInstructionData::try_from(data)?;
+        let accounts = try_from_deposit_context(accounts)?;
+
         Ok(Self {
-            accounts: try_from_deposit_context(accounts)?,
-            data: DepositInstructionData::try_from(data)?,
+            data: accounts.try_from_deposit_data(data.extra_data)?,
+            accounts,
+            amount: data.amount,
         })
     }
 }
 
 impl<'a> DepositInstruction<'a> {
     pub fn process(&self) -> ProgramResult {
-        DepositContext::deposit(&self.accounts, self.data.amount)
+        DepositContext::deposit_with_data(&self.accounts, self.amount, &self.data)
+    }
+
+    pub fn process_signed(&self, signer_seeds: &[Signer]) -> ProgramResult {
+        DepositContext::deposit_with_data_signed(
+            &self.accounts,
+            self.amount,
+            &self.data,
+            signer_seeds,
+        )
     }
 }
 
 pub fn process(accounts: &[AccountView], data: &[u8]) -> ProgramResult {
     DepositInstruction::try_from((accounts, data))?.process()
 }
+
+/// [`process`] with the program's authority PDA signing for the depositor.
+pub fn process_signed(
+    program_id: &Address,
+    accounts: &[AccountView],
+    data: &[u8],
+) -> ProgramResult {
+    let instruction = DepositInstruction::try_from((accounts, data))?;
+    with_authority_signer(program_id, |signers| instruction.process_signed(signers))
+}
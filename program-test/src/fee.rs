@@ -0,0 +1,45 @@
+use {
+    beethoven::prelude::*,
+    pinocchio::{error::ProgramError, AccountView, ProgramResult},
+    solana_instruction_view::cpi::set_return_data,
+};
+
+/// Swap and pay a platform and referral fee out of what it returned, with
+/// [`swap_with_fees`], setting the outcome as return data: in_spent (u64), then what the
+/// user kept (u64).
+///
+/// Data layout: in_amount (u64), minimum_out_amount (u64), platform_bps (u16),
+/// referral_bps (u16), then the swap venue's data. Accounts: the fee accounts, then the
+/// swap's routed accounts.
+pub fn process(accounts: &[AccountView], data: &[u8]) -> ProgramResult {
+    let Some((in_amount, data)) = data.split_first_chunk() else {
+        return Err(ProgramError::InvalidInstructionData);
+    };
+    let Some((minimum_out_amount, data)) = data.split_first_chunk() else {
+        return Err(ProgramError::InvalidInstructionData);
+    };
+    let Some((platform_bps, data)) = data.split_first_chunk() else {
+        return Err(ProgramError::InvalidInstructionData);
+    };
+    let Some((referral_bps, swap_data)) = data.split_first_chunk() else {
+        return Err(ProgramError::InvalidInstructionData);
+    };
+    let fees = SwapFees {
+        platform_bps: u16::from_le_bytes(*platform_bps),
+        referral_bps: u16::from_le_bytes(*referral_bps),
+    };
+
+    let outcome = swap_with_fees(
+        accounts,
+        u64::from_le_bytes(*in_amount),
+        u64::from_le_bytes(*minimum_out_amount),
+        &fees,
+        swap_data,
+    )?;
+
+    let mut return_data = [0u8; 16];
+    return_data[..8].copy_from_slice(&outcome.in_spent.to_le_bytes());
+    return_data[8..].copy_from_slice(&outcome.out_received.to_le_bytes());
+    set_return_data(&return_data);
+    Ok(())
+}
@@ -0,0 +1,26 @@
+use {
+    crate::venue::{expect_venue, split_u64},
+    beethoven::{
+        kamino::{Kamino, KaminoBorrowAccounts, KaminoRepayAccounts, KAMINO_LEND_PROGRAM_ID},
+        prelude::*,
+    },
+    pinocchio::{AccountView, ProgramResult},
+};
+
+/// Borrow from a Kamino reserve.
+///
+/// Data layout: amount (u64)
+pub fn process_borrow(accounts: &[AccountView], data: &[u8]) -> ProgramResult {
+    expect_venue(accounts, &KAMINO_LEND_PROGRAM_ID)?;
+    let (amount, _) = split_u64(data)?;
+    Kamino::borrow(&KaminoBorrowAccounts::try_from(accounts)?, amount)
+}
+
+/// Repay a Kamino reserve.
+///
+/// Data layout: amount (u64)
+pub fn process_repay(accounts: &[AccountView], data: &[u8]) -> ProgramResult {
+    expect_venue(accounts, &KAMINO_LEND_PROGRAM_ID)?;
+    let (amount, _) = split_u64(data)?;
+    Kamino::repay(&KaminoRepayAccounts::try_from(accounts)?, amount)
+}
@@ -1,10 +1,43 @@
 #![no_std]
 #![allow(unexpected_cfgs)]
 
-use pinocchio::{error::ProgramError, AccountView, Address, ProgramResult};
+use {
+    beethoven_test_support::discriminator,
+    pinocchio::{error::ProgramError, AccountView, Address, ProgramResult},
+};
 
+#[cfg(any(feature = "deposit", feature = "swap"))]
+mod authority;
+#[cfg(feature = "deposit")]
 mod deposit;
+#[cfg(feature = "swap")]
+mod fee;
+#[cfg(feature = "deposit")]
+mod lend;
+#[cfg(feature = "liquidity")]
+mod liquidity;
+#[cfg(feature = "swap")]
+mod order;
+#[cfg(feature = "perp")]
+mod perp;
+#[cfg(feature = "swap")]
+mod route;
+#[cfg(feature = "stake")]
+mod stake;
+#[cfg(feature = "swap")]
 mod swap;
+#[cfg(any(
+    feature = "deposit",
+    feature = "swap",
+    feature = "liquidity",
+    feature = "stake",
+    feature = "perp"
+))]
+mod venue;
+#[cfg(feature = "deposit")]
+mod withdraw;
+#[cfg(all(feature = "deposit", feature = "swap"))]
+mod zap;
 
 pinocchio::no_allocator!();
 pinocchio::nostd_panic_handler!();
@@ -12,17 +45,65 @@ pinocchio::program_entrypoint!(process_instruction);
 
 #[inline(never)]
 pub fn process_instruction(
-    _program_id: &Address,
+    program_id: &Address,
     accounts: &[AccountView],
     instruction_data: &[u8],
 ) -> ProgramResult {
-    let (discriminator, data) = instruction_data
+    let (kind, data) = instruction_data
         .split_first()
         .ok_or(ProgramError::InvalidInstructionData)?;
 
-    match discriminator {
-        0 => deposit::process(accounts, data),
-        1 => swap::process(accounts, data),
+    match *kind {
+        #[cfg(feature = "deposit")]
+        discriminator::DEPOSIT => deposit::process(accounts, data),
+        #[cfg(feature = "swap")]
+        discriminator::SWAP => swap::process(accounts, data),
+        #[cfg(feature = "swap")]
+        discriminator::ROUTE => route::process(accounts, data),
+        #[cfg(feature = "deposit")]
+        discriminator::DEPOSIT_SIGNED => deposit::process_signed(program_id, accounts, data),
+        #[cfg(feature = "swap")]
+        discriminator::SWAP_SIGNED => swap::process_signed(program_id, accounts, data),
+        #[cfg(feature = "swap")]
+        discriminator::SWAP_ENVELOPED => swap::process_enveloped(accounts, data),
+        #[cfg(feature = "swap")]
+        discriminator::SWAP_ROUTE => route::process_swap_route(accounts, data),
+        #[cfg(feature = "swap")]
+        discriminator::SWAP_ROUTE_SHARED => route::process_shared_swap_route(accounts, data),
+        #[cfg(feature = "swap")]
+        discriminator::SWAP_OUTCOME => swap::process_with_outcome(accounts, data),
+        #[cfg(feature = "swap")]
+        discriminator::SWAP_DETECTED => swap::process_detected(accounts, data),
+        #[cfg(feature = "swap")]
+        discriminator::SWAP_REGISTRY => swap::process_with_registry(accounts, data),
+        #[cfg(all(feature = "deposit", feature = "swap"))]
+        discriminator::ZAP => zap::process(accounts, data),
+        #[cfg(feature = "deposit")]
+        discriminator::WITHDRAW => withdraw::process(accounts, data),
+        #[cfg(feature = "deposit")]
+        discriminator::BORROW => lend::process_borrow(accounts, data),
+        #[cfg(feature = "deposit")]
+        discriminator::REPAY => lend::process_repay(accounts, data),
+        #[cfg(feature = "stake")]
+        discriminator::STAKE => stake::process_stake(accounts, data),
+        #[cfg(feature = "stake")]
+        discriminator::UNSTAKE => stake::process_unstake(accounts, data),
+        #[cfg(feature = "liquidity")]
+        discriminator::ADD_LIQUIDITY => liquidity::process_add(accounts, data),
+        #[cfg(feature = "liquidity")]
+        discriminator::REMOVE_LIQUIDITY => liquidity::process_remove(accounts, data),
+        #[cfg(feature = "swap")]
+        discriminator::PLACE_ORDER => order::process_place(accounts, data),
+        #[cfg(feature = "swap")]
+        discriminator::CANCEL_ORDER => order::process_cancel(accounts, data),
+        #[cfg(feature = "perp")]
+        discriminator::PERP_ORDER => perp::process(accounts, data),
+        #[cfg(feature = "swap")]
+        discriminator::SWAP_WITH_FEES => fee::process(accounts, data),
+        #[cfg(feature = "swap")]
+        discriminator::SWAP_PASSTHROUGH => swap::process_passthrough(accounts, data),
+        #[cfg(feature = "swap")]
+        discriminator::SWAP_EXACT_OUT => swap::process_exact_out(accounts, data),
         _ => Err(ProgramError::InvalidInstructionData),
     }
 }
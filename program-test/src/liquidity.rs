@@ -0,0 +1,47 @@
+use {
+    crate::venue::{expect_venue, split_u64},
+    beethoven::{
+        prelude::*,
+        raydium_cpmm::{
+            RaydiumCpmm, RaydiumCpmmAddLiquidityAccounts, RaydiumCpmmRemoveLiquidityAccounts,
+            RAYDIUM_CPMM_PROGRAM_ID,
+        },
+    },
+    pinocchio::{AccountView, ProgramResult},
+};
+
+/// Add liquidity to a Raydium CPMM pool.
+///
+/// Data layout: maximum_token_0_amount (u64), maximum_token_1_amount (u64),
+/// minimum_lp_amount (u64)
+pub fn process_add(accounts: &[AccountView], data: &[u8]) -> ProgramResult {
+    expect_venue(accounts, &RAYDIUM_CPMM_PROGRAM_ID)?;
+    let (maximum_token_0_amount, data) = split_u64(data)?;
+    let (maximum_token_1_amount, data) = split_u64(data)?;
+    let (minimum_lp_amount, _) = split_u64(data)?;
+
+    RaydiumCpmm::add_liquidity(
+        &RaydiumCpmmAddLiquidityAccounts::try_from(accounts)?,
+        maximum_token_0_amount,
+        maximum_token_1_amount,
+        minimum_lp_amount,
+    )
+}
+
+/// Remove liquidity from a Raydium CPMM pool.
+///
+/// Data layout: lp_amount (u64), minimum_token_0_amount (u64), minimum_token_1_amount
+/// (u64)
+pub fn process_remove(accounts: &[AccountView], data: &[u8]) -> ProgramResult {
+    expect_venue(accounts, &RAYDIUM_CPMM_PROGRAM_ID)?;
+    let (lp_amount, data) = split_u64(data)?;
+    let (minimum_token_0_amount, data) = split_u64(data)?;
+    let (minimum_token_1_amount, _) = split_u64(data)?;
+
+    RaydiumCpmm::remove_liquidity(
+        &RaydiumCpmmRemoveLiquidityAccounts::try_from(accounts)?,
+        lp_amount,
+        minimum_token_0_amount,
+        minimum_token_1_amount,
+    )
+}
@@ -0,0 +1,55 @@
+use {
+    crate::venue::{expect_venue, split_u64},
+    beethoven::{
+        manifest::{Manifest, ManifestOrderAccounts, ManifestOrderData, MANIFEST_PROGRAM_ID},
+        prelude::*,
+    },
+    pinocchio::{error::ProgramError, AccountView, ProgramResult},
+};
+
+/// Place a limit order on a Manifest market.
+///
+/// Data layout: side (u8: 0 buy, 1 sell), base_amount (u64), order type (u8: 0 limit,
+/// 1 post only, 2 immediate or cancel), then the `ManifestOrderData`
+pub fn process_place(accounts: &[AccountView], data: &[u8]) -> ProgramResult {
+    expect_venue(accounts, &MANIFEST_PROGRAM_ID)?;
+    let Some((&side, data)) = data.split_first() else {
+        return Err(ProgramError::InvalidInstructionData);
+    };
+    let (base_amount, data) = split_u64(data)?;
+    let Some((&order_type, order_data)) = data.split_first() else {
+        return Err(ProgramError::InvalidInstructionData);
+    };
+
+    let side = match side {
+        0 => Direction::Buy,
+        1 => Direction::Sell,
+        _ => return Err(ProgramError::InvalidInstructionData),
+    };
+    let order_type = match order_type {
+        0 => OrderType::Limit,
+        1 => OrderType::PostOnly,
+        2 => OrderType::ImmediateOrCancel,
+        _ => return Err(ProgramError::InvalidInstructionData),
+    };
+
+    Manifest::place_order(
+        &ManifestOrderAccounts::try_from(accounts)?,
+        side,
+        base_amount,
+        order_type,
+        &ManifestOrderData::try_from(order_data)?,
+    )
+}
+
+/// Cancel a Manifest order.
+///
+/// Data layout: order sequence number (u64)
+pub fn process_cancel(accounts: &[AccountView], data: &[u8]) -> ProgramResult {
+    expect_venue(accounts, &MANIFEST_PROGRAM_ID)?;
+    let (order_sequence_number, _) = split_u64(data)?;
+    Manifest::cancel_order(
+        &ManifestOrderAccounts::try_from(accounts)?,
+        &order_sequence_number,
+    )
+}
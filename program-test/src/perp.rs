@@ -0,0 +1,38 @@
+use {
+    crate::venue::{expect_venue, split_u64},
+    beethoven::{
+        drift::{Drift, DriftPerpAccounts, DRIFT_PROGRAM_ID},
+        prelude::*,
+    },
+    pinocchio::{error::ProgramError, AccountView, ProgramResult},
+};
+
+/// Place a Drift perp market order.
+///
+/// Data layout: market_index (u16), direction (u8: 0 long, 1 short), base_amount
+/// (u64), price_limit (u64)
+pub fn process(accounts: &[AccountView], data: &[u8]) -> ProgramResult {
+    expect_venue(accounts, &DRIFT_PROGRAM_ID)?;
+    let Some((market_index, data)) = data.split_first_chunk() else {
+        return Err(ProgramError::InvalidInstructionData);
+    };
+    let Some((&direction, data)) = data.split_first() else {
+        return Err(ProgramError::InvalidInstructionData);
+    };
+    let (base_amount, data) = split_u64(data)?;
+    let (price_limit, _) = split_u64(data)?;
+
+    let direction = match direction {
+        0 => Direction::Buy,
+        1 => Direction::Sell,
+        _ => return Err(ProgramError::InvalidInstructionData),
+    };
+
+    Drift::place_perp_order(
+        &DriftPerpAccounts::try_from(accounts)?,
+        u16::from_le_bytes(*market_index),
+        direction,
+        base_amount,
+        price_limit,
+    )
+}
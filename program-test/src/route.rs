@@ -0,0 +1,204 @@
+use {
+    beethoven::prelude::*,
+    pinocchio::{error::ProgramError, AccountView, ProgramResult},
+};
+
+/// One leg of a route, as `beethoven_client::planner::RoutePlan::encode` packs it
+///
+/// Layout:
+/// [0]      - protocol (u8, `ProtocolKind` discriminant)
+/// [1]      - account count (u8), including the leg's detector account
+/// [2..10]  - in_amount (u64, little-endian)
+/// [10..18] - minimum_out_amount (u64, little-endian)
+/// [18]     - swap data length (u8)
+/// [19..]   - swap data (parsed via LazySwapContext::try_from_swap_data)
+pub struct RouteLegData<'a> {
+    pub protocol: u8,
+    pub account_count: usize,
+    pub in_amount: u64,
+    pub minimum_out_amount: u64,
+    pub extra_data: &'a [u8],
+}
+
+impl<'a> RouteLegData<'a> {
+    /// Parse the leg at the start of `data`, returning it and the legs after it.
+    fn parse(data: &'a [u8]) -> Result<(Self, &'a [u8]), ProgramError> {
+        let Some(([protocol, account_count], data)) = data.split_first_chunk() else {
+            return Err(ProgramError::InvalidInstructionData);
+        };
+        let Some((in_amount, data)) = data.split_first_chunk() else {
+            return Err(ProgramError::InvalidInstructionData);
+        };
+        let Some((minimum_out_amount, data)) = data.split_first_chunk() else {
+            return Err(ProgramError::InvalidInstructionData);
+        };
+        let Some((data_len, data)) = data.split_first() else {
+            return Err(ProgramError::InvalidInstructionData);
+        };
+        if data.len() < *data_len as usize {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        let (extra_data, rest) = data.split_at(*data_len as usize);
+
+        Ok((
+            Self {
+                protocol: *protocol,
+                account_count: *account_count as usize,
+                in_amount: u64::from_le_bytes(*in_amount),
+                minimum_out_amount: u64::from_le_bytes(*minimum_out_amount),
+                extra_data,
+            },
+            rest,
+        ))
+    }
+}
+
+/// Swap each leg in order against its slice of `accounts`.
+///
+/// Data layout: leg count (u8), then each leg as [`RouteLegData`]. Accounts: every
+/// leg's routed accounts, detector first, in leg order.
+pub fn process(accounts: &[AccountView], data: &[u8]) -> ProgramResult {
+    let Some((leg_count, mut data)) = data.split_first() else {
+        return Err(ProgramError::InvalidInstructionData);
+    };
+    let mut accounts = accounts;
+
+    for _ in 0..*leg_count {
+        let (leg, rest) = RouteLegData::parse(data)?;
+        data = rest;
+
+        if accounts.len() < leg.account_count {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        }
+        let (leg_accounts, rest) = accounts.split_at(leg.account_count);
+        accounts = rest;
+
+        let ctx = try_from_swap_context_lazy(leg_accounts)?;
+        if ctx.protocol as u8 != leg.protocol {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let swap_data = ctx.try_from_swap_data(leg.extra_data)?;
+        LazySwapContext::swap(&ctx, leg.in_amount, leg.minimum_out_amount, &swap_data)?;
+    }
+
+    if !data.is_empty() {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    Ok(())
+}
+
+/// Most legs [`process_swap_route`] takes
+const MAX_SWAP_ROUTE_LEGS: usize = 4;
+
+/// Swap `in_amount` through every leg with [`SwapRoute`], each leg spending what the
+/// one before it credited.
+///
+/// Data layout: in_amount (u64), minimum_out_amount (u64), leg count (u8), then per
+/// leg: account count (u8, including the detector), swap data length (u8) and swap
+/// data. Accounts: every leg's routed accounts, in leg order.
+pub fn process_swap_route(accounts: &[AccountView], data: &[u8]) -> ProgramResult {
+    let Some((in_amount, data)) = data.split_first_chunk() else {
+        return Err(ProgramError::InvalidInstructionData);
+    };
+    let Some((minimum_out_amount, data)) = data.split_first_chunk() else {
+        return Err(ProgramError::InvalidInstructionData);
+    };
+    let Some((&leg_count, mut data)) = data.split_first() else {
+        return Err(ProgramError::InvalidInstructionData);
+    };
+    let leg_count = leg_count as usize;
+    if leg_count > MAX_SWAP_ROUTE_LEGS {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    let mut account_counts = [0u8; MAX_SWAP_ROUTE_LEGS];
+    let mut leg_data: [&[u8]; MAX_SWAP_ROUTE_LEGS] = [&[]; MAX_SWAP_ROUTE_LEGS];
+    for (account_count, swap_data) in account_counts.iter_mut().zip(&mut leg_data).take(leg_count) {
+        let Some(([count, data_len], rest)) = data.split_first_chunk() else {
+            return Err(ProgramError::InvalidInstructionData);
+        };
+        if rest.len() < *data_len as usize {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        let (leg, rest) = rest.split_at(*data_len as usize);
+        *account_count = *count;
+        *swap_data = leg;
+        data = rest;
+    }
+    if !data.is_empty() {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    SwapRoute::new(accounts, &account_counts[..leg_count])?.swap(
+        u64::from_le_bytes(*in_amount),
+        u64::from_le_bytes(*minimum_out_amount),
+        &leg_data[..leg_count],
+    )
+}
+
+/// Most account indices [`process_shared_swap_route`] takes, across every leg
+const MAX_SHARED_SWAP_ROUTE_INDICES: usize = MAX_SWAP_ROUTE_LEGS * MAX_ROUTE_LEG_ACCOUNTS;
+
+/// [`process_swap_route`] with [`SharedSwapRoute`], each account passed once.
+///
+/// Data layout: in_amount (u64), minimum_out_amount (u64), leg count (u8), then per
+/// leg: account count (u8, including the detector), that many account indices (u8
+/// each), swap data length (u8) and swap data. Accounts: every distinct account of the
+/// route, in any order.
+pub fn process_shared_swap_route(accounts: &[AccountView], data: &[u8]) -> ProgramResult {
+    let Some((in_amount, data)) = data.split_first_chunk() else {
+        return Err(ProgramError::InvalidInstructionData);
+    };
+    let Some((minimum_out_amount, data)) = data.split_first_chunk() else {
+        return Err(ProgramError::InvalidInstructionData);
+    };
+    let Some((&leg_count, mut data)) = data.split_first() else {
+        return Err(ProgramError::InvalidInstructionData);
+    };
+    let leg_count = leg_count as usize;
+    if leg_count > MAX_SWAP_ROUTE_LEGS {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    let mut account_counts = [0u8; MAX_SWAP_ROUTE_LEGS];
+    let mut account_indices = [0u8; MAX_SHARED_SWAP_ROUTE_INDICES];
+    let mut index_count = 0;
+    let mut leg_data: [&[u8]; MAX_SWAP_ROUTE_LEGS] = [&[]; MAX_SWAP_ROUTE_LEGS];
+    for (account_count, swap_data) in account_counts.iter_mut().zip(&mut leg_data).take(leg_count) {
+        let Some((&count, rest)) = data.split_first() else {
+            return Err(ProgramError::InvalidInstructionData);
+        };
+        let count = count as usize;
+        if count > MAX_ROUTE_LEG_ACCOUNTS || rest.len() < count {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        let (indices, rest) = rest.split_at(count);
+        account_indices[index_count..index_count + count].copy_from_slice(indices);
+        index_count += count;
+
+        let Some((&data_len, rest)) = rest.split_first() else {
+            return Err(ProgramError::InvalidInstructionData);
+        };
+        if rest.len() < data_len as usize {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        let (leg, rest) = rest.split_at(data_len as usize);
+        *account_count = count as u8;
+        *swap_data = leg;
+        data = rest;
+    }
+    if !data.is_empty() {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    SharedSwapRoute::new(
+        accounts,
+        &account_indices[..index_count],
+        &account_counts[..leg_count],
+    )?
+    .swap(
+        u64::from_le_bytes(*in_amount),
+        u64::from_le_bytes(*minimum_out_amount),
+        &leg_data[..leg_count],
+    )
+}
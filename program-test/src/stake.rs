@@ -0,0 +1,45 @@
+use {
+    crate::venue::{expect_venue, split_u64},
+    beethoven::{
+        marinade::{
+            Marinade, MarinadeStakeAccounts, MarinadeUnstakeAccounts, MarinadeUnstakeData,
+            MARINADE_PROGRAM_ID,
+        },
+        prelude::*,
+        spl_stake_pool::{SplStakePool, SplStakePoolUnstakeAccounts, SPL_STAKE_POOL_PROGRAM_ID},
+    },
+    pinocchio::{error::ProgramError, AccountView, ProgramResult},
+};
+
+/// Stake with Marinade.
+///
+/// Data layout: lamports (u64)
+pub fn process_stake(accounts: &[AccountView], data: &[u8]) -> ProgramResult {
+    expect_venue(accounts, &MARINADE_PROGRAM_ID)?;
+    let (lamports, _) = split_u64(data)?;
+    Marinade::stake(&MarinadeStakeAccounts::try_from(accounts)?, lamports)
+}
+
+/// Unstake with Marinade or an SPL stake pool, whichever the detector account is.
+///
+/// Data layout: amount (u64), then the venue's unstake data: a `MarinadeUnstakeData`
+/// (u8) for Marinade, nothing for a stake pool
+pub fn process_unstake(accounts: &[AccountView], data: &[u8]) -> ProgramResult {
+    let detector_account = accounts.first().ok_or(ProgramError::NotEnoughAccountKeys)?;
+    let (amount, unstake_data) = split_u64(data)?;
+
+    if detector_account.address() == &SPL_STAKE_POOL_PROGRAM_ID {
+        return SplStakePool::unstake(
+            &SplStakePoolUnstakeAccounts::try_from(accounts)?,
+            amount,
+            &(),
+        );
+    }
+
+    expect_venue(accounts, &MARINADE_PROGRAM_ID)?;
+    Marinade::unstake(
+        &MarinadeUnstakeAccounts::try_from(accounts)?,
+        amount,
+        &MarinadeUnstakeData::try_from(unstake_data)?,
+    )
+}
@@ -1,6 +1,16 @@
+#[cfg(feature = "strict-validation")]
+use crate::authority::check_authority;
 use {
-    beethoven::{try_from_swap_context, Swap, SwapContext, SwapData},
-    pinocchio::{error::ProgramError, AccountView, ProgramResult},
+    crate::authority::with_authority_signer,
+    beethoven::{
+        prelude::*,
+        solfi::{SolFi, SolFiSwapData, SOLFI_PROGRAM_ID},
+        solfi_v2::{SolFiV2, SolFiV2SwapData, SOLFI_V2_PROGRAM_ID},
+    },
+    beethoven_test_support::{REGISTERED_PROGRAM_ID, REGISTERED_SWAP_ERROR},
+    pinocchio::{error::ProgramError, AccountView, Address, ProgramResult},
+    solana_address::address_eq,
+    solana_instruction_view::cpi::{set_return_data, Signer},
 };
 
 /// Instruction data for Swap
@@ -8,7 +18,7 @@ use {
 /// Layout:
 /// [0..8]  - in_amount (u64, little-endian)
 /// [8..16] - minimum_out_amount (u64, little-endian)
-/// [16..]  - protocol-specific data (parsed via SwapContext::try_from_swap_data)
+/// [16..]  - protocol-specific data (parsed via LazySwapContext::try_from_swap_data)
 pub struct SwapInstructionData<'a> {
     pub in_amount: u64,
     pub minimum_out_amount: u64,
@@ -19,19 +29,22 @@ impl<'a> TryFrom<&'a [u8]> for SwapInstructionData<'a> {
     type Error = ProgramError;
 
     fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
-        if data.len() < 16 {
+        let Some((in_amount, data)) = data.split_first_chunk() else {
             return Err(ProgramError::InvalidInstructionData);
-        }
+        };
+        let Some((minimum_out_amount, extra_data)) = data.split_first_chunk() else {
+            return Err(ProgramError::InvalidInstructionData);
+        };
         Ok(Self {
-            in_amount: u64::from_le_bytes(data[0..8].try_into().unwrap()),
-            minimum_out_amount: u64::from_le_bytes(data[8..16].try_into().unwrap()),
-            extra_data: &data[16..],
+            in_amount: u64::from_le_bytes(*in_amount),
+            minimum_out_amount: u64::from_le_bytes(*minimum_out_amount),
+            extra_data,
         })
     }
 }
 
 pub struct SwapInstruction<'a> {
-    pub accounts: SwapContext<'a>,
+    pub accounts: LazySwapContext<'a>,
     pub data: SwapData<'a>,
     pub in_amount: u64,
     pub minimum_out_amount: u64,
@@ -41,9 +54,30 @@ impl<'a> TryFrom<(&'a [AccountView], &'a [u8])> for SwapInstruction<'a> {
     type Error = ProgramError;
 
     fn try_from((accounts, data): (&'a [AccountView], &'a [u8])) -> Result<Self, Self::Error> {
+        Self::parse(
+            accounts,
+            data,
+            Detection::default(),
+            LazySwapContext::try_from_swap_data,
+        )
+    }
+}
+
+impl<'a> SwapInstruction<'a> {
+    /// The swap in `data` on the venue `detection` identifies, with `extra_data`
+    /// (everything after the amounts) read by `parse_extra_data`.
+    fn parse(
+        accounts: &'a [AccountView],
+        data: &'a [u8],
+        detection: Detection,
+        parse_extra_data: impl FnOnce(
+            &LazySwapContext<'a>,
+            &'a [u8],
+        ) -> Result<SwapData<'a>, ProgramError>,
+    ) -> Result<Self, ProgramError> {
         let instruction_data = SwapInstructionData::try_from(data)?;
-        let ctx = try_from_swap_context(accounts)?;
-        let swap_data = ctx.try_from_swap_data(instruction_data.extra_data)?;
+        let ctx = try_from_swap_context_by(accounts, detection)?;
+        let swap_data = parse_extra_data(&ctx, instruction_data.extra_data)?;
 
         Ok(Self {
             accounts: ctx,
@@ -52,19 +86,161 @@ impl<'a> TryFrom<(&'a [AccountView], &'a [u8])> for SwapInstruction<'a> {
             minimum_out_amount: instruction_data.minimum_out_amount,
         })
     }
-}
 
-impl<'a> SwapInstruction<'a> {
     pub fn process(&self) -> ProgramResult {
-        SwapContext::swap(
+        LazySwapContext::swap(
             &self.accounts,
             self.in_amount,
             self.minimum_out_amount,
             &self.data,
         )
     }
+
+    /// [`Self::process`], returning what the swap moved.
+    pub fn process_with_outcome(&self) -> Result<SwapOutcome, ProgramError> {
+        LazySwapContext::swap_signed_with_outcome(
+            &self.accounts,
+            self.in_amount,
+            self.minimum_out_amount,
+            &self.data,
+            &[],
+        )
+    }
+
+    pub fn process_signed(&self, signer_seeds: &[Signer]) -> ProgramResult {
+        LazySwapContext::swap_signed(
+            &self.accounts,
+            self.in_amount,
+            self.minimum_out_amount,
+            &self.data,
+            signer_seeds,
+        )
+    }
 }
 
 pub fn process(accounts: &[AccountView], data: &[u8]) -> ProgramResult {
     SwapInstruction::try_from((accounts, data))?.process()
 }
+
+/// [`process`], setting the swap's outcome as return data: in_spent (u64), then
+/// out_received (u64).
+pub fn process_with_outcome(accounts: &[AccountView], data: &[u8]) -> ProgramResult {
+    let outcome = SwapInstruction::try_from((accounts, data))?.process_with_outcome()?;
+
+    let mut return_data = [0u8; 16];
+    return_data[..8].copy_from_slice(&outcome.in_spent.to_le_bytes());
+    return_data[8..].copy_from_slice(&outcome.out_received.to_le_bytes());
+    set_return_data(&return_data);
+    Ok(())
+}
+
+/// [`process`] with the extra data in a versioned envelope:
+/// version (u8), protocol (u8), then the protocol-specific data.
+pub fn process_enveloped(accounts: &[AccountView], data: &[u8]) -> ProgramResult {
+    SwapInstruction::parse(
+        accounts,
+        data,
+        Detection::default(),
+        LazySwapContext::try_from_enveloped_swap_data,
+    )?
+    .process()
+}
+
+/// [`process`] on the venue a [`Detection`] in front of the swap identifies: kind (u8:
+/// 0 program at an index, 1 owner of an index, 2 tagged), then the index or the
+/// `ProtocolKind` discriminant (u8).
+pub fn process_detected(accounts: &[AccountView], data: &[u8]) -> ProgramResult {
+    let Some(([kind, value], data)) = data.split_first_chunk() else {
+        return Err(ProgramError::InvalidInstructionData);
+    };
+    let detection = match kind {
+        0 => Detection::ProgramAt(*value as usize),
+        1 => Detection::OwnerOf(*value as usize),
+        2 => Detection::Tagged(
+            ProtocolKind::from_u8(*value).ok_or(ProgramError::InvalidInstructionData)?,
+        ),
+        _ => return Err(ProgramError::InvalidInstructionData),
+    };
+
+    SwapInstruction::parse(
+        accounts,
+        data,
+        detection,
+        LazySwapContext::try_from_swap_data,
+    )?
+    .process()
+}
+
+/// [`process`] as an exact-out swap: the amounts are the exact out_amount, then the
+/// maximum_in_amount.
+pub fn process_exact_out(accounts: &[AccountView], data: &[u8]) -> ProgramResult {
+    let instruction = SwapInstruction::try_from((accounts, data))?;
+    LazySwapContext::swap_exact_out(
+        &instruction.accounts,
+        instruction.in_amount,
+        instruction.minimum_out_amount,
+        &instruction.data,
+    )
+}
+
+/// [`process`] on SolFi or SolFi V2 through `swap_signed_passthrough`, which forwards
+/// the accounts after the venue's program to the CPI as passed.
+pub fn process_passthrough(accounts: &[AccountView], data: &[u8]) -> ProgramResult {
+    let data = SwapInstructionData::try_from(data)?;
+    let program = accounts.first().ok_or(ProgramError::NotEnoughAccountKeys)?;
+
+    if address_eq(program.address(), &SOLFI_PROGRAM_ID) {
+        SolFi::swap_signed_passthrough(
+            accounts,
+            data.in_amount,
+            data.minimum_out_amount,
+            &SolFiSwapData::try_from(data.extra_data)?,
+            &[],
+        )
+    } else if address_eq(program.address(), &SOLFI_V2_PROGRAM_ID) {
+        SolFiV2::swap_signed_passthrough(
+            accounts,
+            data.in_amount,
+            data.minimum_out_amount,
+            &SolFiV2SwapData::try_from(data.extra_data)?,
+            &[],
+        )
+    } else {
+        Err(ProgramError::IncorrectProgramId)
+    }
+}
+
+/// The registry [`process_with_registry`] passes: one venue whose handler always fails
+/// with [`REGISTERED_SWAP_ERROR`]
+const REGISTRY: &[ProtocolEntry] = &[ProtocolEntry::swap(
+    REGISTERED_PROGRAM_ID,
+    |_, _, _, _, _| Err(ProgramError::Custom(REGISTERED_SWAP_ERROR)),
+)];
+
+/// [`process`] through [`try_from_swap_context_with`], falling back to [`REGISTRY`]
+/// for programs no adapter detects.
+pub fn process_with_registry(accounts: &[AccountView], data: &[u8]) -> ProgramResult {
+    let data = SwapInstructionData::try_from(data)?;
+    let ctx = try_from_swap_context_with(accounts, REGISTRY)?;
+    RegistrySwapContext::swap(
+        &ctx,
+        data.in_amount,
+        data.minimum_out_amount,
+        data.extra_data,
+    )
+}
+
+/// [`process`] with the program's authority PDA signing for the trader.
+///
+/// With `strict-validation`, the trader must be that PDA (or have signed).
+pub fn process_signed(
+    program_id: &Address,
+    accounts: &[AccountView],
+    data: &[u8],
+) -> ProgramResult {
+    let instruction = SwapInstruction::try_from((accounts, data))?;
+    #[cfg(feature = "strict-validation")]
+    check_authority(program_id, instruction.accounts.parse()?.user_authority()?)?;
+
+    with_authority_signer(program_id, |signers| instruction.process_signed(signers))
+}
@@ -0,0 +1,22 @@
+use {
+    beethoven::prelude::*,
+    pinocchio::{error::ProgramError, AccountView, Address},
+};
+
+/// Fail with `UnknownProtocol` unless the detector account is `program_id`, for the
+/// actions dispatched straight to one venue's adapter.
+pub fn expect_venue(accounts: &[AccountView], program_id: &Address) -> Result<(), ProgramError> {
+    let detector_account = accounts.first().ok_or(ProgramError::NotEnoughAccountKeys)?;
+    if detector_account.address() != program_id {
+        return Err(BeethovenError::UnknownProtocol.into());
+    }
+    Ok(())
+}
+
+/// The leading u64 of `data` and what follows it.
+pub fn split_u64(data: &[u8]) -> Result<(u64, &[u8]), ProgramError> {
+    let (value, rest) = data
+        .split_first_chunk()
+        .ok_or(ProgramError::InvalidInstructionData)?;
+    Ok((u64::from_le_bytes(*value), rest))
+}
@@ -0,0 +1,53 @@
+use {
+    beethoven::prelude::*,
+    pinocchio::{error::ProgramError, AccountView, ProgramResult},
+};
+
+/// Instruction data for Withdraw
+///
+/// Layout:
+/// [0..8] - amount (u64, little-endian), in the venue's receipt tokens
+pub struct WithdrawInstructionData {
+    pub amount: u64,
+}
+
+impl TryFrom<&[u8]> for WithdrawInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &[u8]) -> Result<Self, Self::Error> {
+        let Some(amount) = data.first_chunk() else {
+            return Err(ProgramError::InvalidInstructionData);
+        };
+        Ok(Self {
+            amount: u64::from_le_bytes(*amount),
+        })
+    }
+}
+
+pub struct WithdrawInstruction<'a> {
+    pub accounts: WithdrawContext<'a>,
+    pub amount: u64,
+}
+
+impl<'a> TryFrom<(&'a [AccountView], &[u8])> for WithdrawInstruction<'a> {
+    type Error = ProgramError;
+
+    fn try_from((accounts, data): (&'a [AccountView], &[u8])) -> Result<Self, Self::Error> {
+        let data = WithdrawInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts: try_from_withdraw_context(accounts)?,
+            amount: data.amount,
+        })
+    }
+}
+
+impl<'a> WithdrawInstruction<'a> {
+    pub fn process(&self) -> ProgramResult {
+        WithdrawContext::withdraw(&self.accounts, self.amount)
+    }
+}
+
+pub fn process(accounts: &[AccountView], data: &[u8]) -> ProgramResult {
+    WithdrawInstruction::try_from((accounts, data))?.process()
+}
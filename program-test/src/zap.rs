@@ -0,0 +1,29 @@
+use {
+    beethoven::prelude::*,
+    pinocchio::{error::ProgramError, AccountView, ProgramResult},
+};
+
+/// Swap, then deposit everything the swap returned, with [`zap`].
+///
+/// Data layout: swap account count (u8, including the swap's detector),
+/// in_amount (u64), minimum_out_amount (u64), then the swap venue's data. Accounts: the
+/// swap's routed accounts, then the deposit's.
+pub fn process(accounts: &[AccountView], data: &[u8]) -> ProgramResult {
+    let Some((&swap_account_count, data)) = data.split_first() else {
+        return Err(ProgramError::InvalidInstructionData);
+    };
+    let Some((in_amount, data)) = data.split_first_chunk() else {
+        return Err(ProgramError::InvalidInstructionData);
+    };
+    let Some((minimum_out_amount, swap_data)) = data.split_first_chunk() else {
+        return Err(ProgramError::InvalidInstructionData);
+    };
+
+    zap(
+        accounts,
+        swap_account_count as usize,
+        u64::from_le_bytes(*in_amount),
+        u64::from_le_bytes(*minimum_out_amount),
+        swap_data,
+    )
+}
@@ -0,0 +1,81 @@
+//! Entry points taking `solana-program`'s `AccountInfo`s and signer seeds.
+//!
+//! Programs written against `solana-program` can pass their instruction's accounts
+//! (detector first, as usual) straight to these. Each call allocates the
+//! [`AccountView`]s and signers it hands to the adapters; the adapters and their CPIs
+//! are unchanged.
+//!
+//! # Safety
+//!
+//! The conversion relies on every `AccountInfo` having been deserialized by the program
+//! entrypoint, so that its `key` points into the account's record in the runtime input
+//! buffer. `AccountInfo`s built by hand (e.g. in off-chain tests) must not be passed.
+
+use {
+    crate::{
+        interop::{account_view_at_key, seeds, signers},
+        try_from_deposit_context, try_from_swap_context_lazy, Deposit, DepositContext,
+        LazySwapContext, Swap, SwapData,
+    },
+    alloc::vec::Vec,
+    solana_account_info::AccountInfo,
+    solana_account_view::AccountView,
+    solana_program_error::ProgramResult,
+};
+
+/// View `AccountInfo`s as the [`AccountView`]s the adapters take.
+///
+/// See the module docs for when this is sound.
+pub fn account_views(account_infos: &[AccountInfo<'_>]) -> Vec<AccountView> {
+    account_infos
+        .iter()
+        // SAFETY: every `info` was deserialized by the entrypoint (see the module docs).
+        .map(|info| unsafe { account_view_at_key(info.key) })
+        .collect()
+}
+
+/// Route a swap through `accounts`, signing with `invoke_signed`-style `signer_seeds`.
+pub fn swap_signed(
+    accounts: &[AccountInfo<'_>],
+    in_amount: u64,
+    minimum_out_amount: u64,
+    data: &SwapData<'_>,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    let accounts = account_views(accounts);
+    let seeds = seeds(signer_seeds);
+    let signers = signers(&seeds);
+
+    let ctx = try_from_swap_context_lazy(&accounts)?;
+    LazySwapContext::swap_signed(&ctx, in_amount, minimum_out_amount, data, &signers)
+}
+
+/// Route a swap through `accounts`.
+pub fn swap(
+    accounts: &[AccountInfo<'_>],
+    in_amount: u64,
+    minimum_out_amount: u64,
+    data: &SwapData<'_>,
+) -> ProgramResult {
+    swap_signed(accounts, in_amount, minimum_out_amount, data, &[])
+}
+
+/// Route a deposit through `accounts`, signing with `invoke_signed`-style
+/// `signer_seeds`.
+pub fn deposit_signed(
+    accounts: &[AccountInfo<'_>],
+    amount: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    let accounts = account_views(accounts);
+    let seeds = seeds(signer_seeds);
+    let signers = signers(&seeds);
+
+    let ctx = try_from_deposit_context(&accounts)?;
+    DepositContext::deposit_signed(&ctx, amount, &signers)
+}
+
+/// Route a deposit through `accounts`.
+pub fn deposit(accounts: &[AccountInfo<'_>], amount: u64) -> ProgramResult {
+    deposit_signed(accounts, amount, &[])
+}
@@ -0,0 +1,114 @@
+//! Entry points taking Anchor's `AccountInfo`s and signer seeds.
+//!
+//! Anchor programs can pass `ctx.remaining_accounts` (detector first, as usual) straight
+//! to these instead of converting each account themselves. Each call allocates the
+//! [`AccountView`]s and signers it hands to the adapters.
+//!
+//! # Safety
+//!
+//! The conversion relies on every `AccountInfo` having been deserialized by the program
+//! entrypoint, so that its `key` points into the account's record in the runtime input
+//! buffer. That holds for every account Anchor hands to an instruction handler, but not
+//! for `AccountInfo`s built by hand (e.g. in off-chain tests).
+
+use {
+    crate::{
+        interop::{account_view_at_key, seeds, signers},
+        try_from_deposit_context, try_from_swap_context_lazy, Deposit, DepositContext,
+        LazySwapContext, Swap, SwapData,
+    },
+    alloc::vec::Vec,
+    anchor_lang::{
+        prelude::{AccountInfo, CpiContext, ProgramError as AnchorProgramError},
+        ToAccountInfos, ToAccountMetas,
+    },
+    solana_account_view::AccountView,
+    solana_program_error::ProgramError,
+};
+
+/// View Anchor `AccountInfo`s as the [`AccountView`]s the adapters take.
+///
+/// See the module docs for when this is sound.
+pub fn account_views(account_infos: &[AccountInfo<'_>]) -> Vec<AccountView> {
+    account_infos
+        .iter()
+        // SAFETY: every `info` was deserialized by the entrypoint (see the module docs).
+        .map(|info| unsafe { account_view_at_key(info.key) })
+        .collect()
+}
+
+/// Route a swap through `remaining_accounts`, signing with Anchor-style `signer_seeds`.
+pub fn swap_signed(
+    remaining_accounts: &[AccountInfo<'_>],
+    in_amount: u64,
+    minimum_out_amount: u64,
+    data: &SwapData<'_>,
+    signer_seeds: &[&[&[u8]]],
+) -> anchor_lang::Result<()> {
+    let accounts = account_views(remaining_accounts);
+    let seeds = seeds(signer_seeds);
+    let signers = signers(&seeds);
+
+    let ctx = try_from_swap_context_lazy(&accounts).map_err(to_anchor_error)?;
+    LazySwapContext::swap_signed(&ctx, in_amount, minimum_out_amount, data, &signers)
+        .map_err(to_anchor_error)
+}
+
+/// Route a swap through `remaining_accounts`.
+pub fn swap(
+    remaining_accounts: &[AccountInfo<'_>],
+    in_amount: u64,
+    minimum_out_amount: u64,
+    data: &SwapData<'_>,
+) -> anchor_lang::Result<()> {
+    swap_signed(remaining_accounts, in_amount, minimum_out_amount, data, &[])
+}
+
+/// [`swap_signed`] with the remaining accounts and signer seeds of `ctx`.
+pub fn swap_with_cpi_context<'info, T: ToAccountMetas + ToAccountInfos<'info>>(
+    ctx: CpiContext<'_, '_, '_, 'info, T>,
+    in_amount: u64,
+    minimum_out_amount: u64,
+    data: &SwapData<'_>,
+) -> anchor_lang::Result<()> {
+    swap_signed(
+        &ctx.remaining_accounts,
+        in_amount,
+        minimum_out_amount,
+        data,
+        ctx.signer_seeds,
+    )
+}
+
+/// Route a deposit through `remaining_accounts`, signing with Anchor-style
+/// `signer_seeds`.
+pub fn deposit_signed(
+    remaining_accounts: &[AccountInfo<'_>],
+    amount: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> anchor_lang::Result<()> {
+    let accounts = account_views(remaining_accounts);
+    let seeds = seeds(signer_seeds);
+    let signers = signers(&seeds);
+
+    let ctx = try_from_deposit_context(&accounts).map_err(to_anchor_error)?;
+    DepositContext::deposit_signed(&ctx, amount, &signers).map_err(to_anchor_error)
+}
+
+/// Route a deposit through `remaining_accounts`.
+pub fn deposit(remaining_accounts: &[AccountInfo<'_>], amount: u64) -> anchor_lang::Result<()> {
+    deposit_signed(remaining_accounts, amount, &[])
+}
+
+/// [`deposit_signed`] with the remaining accounts and signer seeds of `ctx`.
+pub fn deposit_with_cpi_context<'info, T: ToAccountMetas + ToAccountInfos<'info>>(
+    ctx: CpiContext<'_, '_, '_, 'info, T>,
+    amount: u64,
+) -> anchor_lang::Result<()> {
+    deposit_signed(&ctx.remaining_accounts, amount, ctx.signer_seeds)
+}
+
+/// Carry a Beethoven error code over to Anchor's `ProgramError`.
+fn to_anchor_error(error: ProgramError) -> anchor_lang::error::Error {
+    AnchorProgramError::from(u64::from(error)).into()
+}
@@ -1,7 +1,12 @@
 use {
-    crate::Swap,
+    crate::{
+        scratch::Scratch,
+        token::{resolve_amount, token_account_amount, FULL_BALANCE},
+        BeethovenError, ProtocolKind, Swap, SwapOutcome,
+    },
+    core::mem::MaybeUninit,
     solana_account_view::AccountView,
-    solana_address::address_eq,
+    solana_address::{address_eq, Address},
     solana_instruction_view::cpi::Signer,
     solana_program_error::{ProgramError, ProgramResult},
 };
@@ -34,9 +39,32 @@ pub enum SwapContext<'info> {
 
     #[cfg(feature = "gamma-swap")]
     Gamma(crate::gamma::GammaSwapAccounts<'info>),
+
+    #[cfg(feature = "mock_dex-swap")]
+    MockDex(crate::mock_dex::MockDexSwapAccounts<'info>),
+
+    /// Keeps `'info` in use when no swap protocol is enabled
+    #[cfg(not(any(
+        feature = "perena-swap",
+        feature = "solfi-swap",
+        feature = "solfi_v2-swap",
+        feature = "manifest-swap",
+        feature = "heaven-swap",
+        feature = "aldrin-swap",
+        feature = "aldrin_v2-swap",
+        feature = "futarchy-swap",
+        feature = "gamma-swap",
+        feature = "mock_dex-swap"
+    )))]
+    #[doc(hidden)]
+    __Unused(core::marker::PhantomData<&'info ()>),
 }
 
 /// Protocol-specific swap data enum for use with SwapContext
+///
+/// With the `serde` feature, Heaven's data (which borrows its event from the
+/// instruction) serializes but does not deserialize.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum SwapData<'a> {
     #[cfg(feature = "perena-swap")]
     Perena(crate::perena::PerenaSwapData),
@@ -51,6 +79,7 @@ pub enum SwapData<'a> {
     Manifest(crate::manifest::ManifestSwapData),
 
     #[cfg(feature = "heaven-swap")]
+    #[cfg_attr(feature = "serde", serde(skip_deserializing))]
     Heaven(crate::heaven::HeavenSwapData<'a>),
 
     #[cfg(feature = "aldrin-swap")]
@@ -64,63 +93,415 @@ pub enum SwapData<'a> {
 
     #[cfg(feature = "gamma-swap")]
     Gamma(()),
+
+    #[cfg(feature = "mock_dex-swap")]
+    MockDex(crate::mock_dex::MockDexSwapData),
+
+    /// Keeps `'a` in use when no variant borrows the instruction data
+    #[cfg(not(feature = "heaven-swap"))]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    #[doc(hidden)]
+    __Unused(core::marker::PhantomData<&'a ()>),
+}
+
+impl<'a> SwapData<'a> {
+    /// Parse `data` as the swap data of `protocol`.
+    #[inline(always)]
+    pub fn parse(protocol: ProtocolKind, data: &'a [u8]) -> Result<Self, ProgramError> {
+        let data: Result<Self, ProgramError> = match protocol {
+            #[cfg(feature = "perena-swap")]
+            ProtocolKind::Perena => {
+                crate::perena::PerenaSwapData::try_from(data).map(SwapData::Perena)
+            }
+
+            #[cfg(feature = "solfi-swap")]
+            ProtocolKind::SolFi => crate::solfi::SolFiSwapData::try_from(data).map(SwapData::SolFi),
+
+            #[cfg(feature = "solfi_v2-swap")]
+            ProtocolKind::SolFiV2 => {
+                crate::solfi_v2::SolFiV2SwapData::try_from(data).map(SwapData::SolFiV2)
+            }
+
+            #[cfg(feature = "manifest-swap")]
+            ProtocolKind::Manifest => {
+                crate::manifest::ManifestSwapData::try_from(data).map(SwapData::Manifest)
+            }
+
+            #[cfg(feature = "heaven-swap")]
+            ProtocolKind::Heaven => {
+                crate::heaven::HeavenSwapData::try_from(data).map(SwapData::Heaven)
+            }
+
+            #[cfg(feature = "aldrin-swap")]
+            ProtocolKind::Aldrin => {
+                crate::aldrin::AldrinSwapData::try_from(data).map(SwapData::Aldrin)
+            }
+
+            #[cfg(feature = "aldrin_v2-swap")]
+            ProtocolKind::AldrinV2 => {
+                crate::aldrin_v2::AldrinV2SwapData::try_from(data).map(SwapData::AldrinV2)
+            }
+
+            #[cfg(feature = "futarchy-swap")]
+            ProtocolKind::Futarchy => {
+                crate::futarchy::FutarchySwapData::try_from(data).map(SwapData::Futarchy)
+            }
+
+            #[cfg(feature = "gamma-swap")]
+            ProtocolKind::Gamma => Ok(SwapData::Gamma(())),
+
+            #[cfg(feature = "mock_dex-swap")]
+            ProtocolKind::MockDex => {
+                crate::mock_dex::MockDexSwapData::try_from(data).map(SwapData::MockDex)
+            }
+
+            _ => {
+                let _ = data;
+                Err(BeethovenError::ProtocolFeatureDisabled.into())
+            }
+        };
+
+        data.map_err(invalid_extra_data)
+    }
+}
+
+macro_rules! swap_data_from {
+    ($($feature:literal, $variant:ident => $data:ty;)*) => {
+        $(
+            #[cfg(feature = $feature)]
+            impl From<$data> for SwapData<'_> {
+                #[inline(always)]
+                fn from(data: $data) -> Self {
+                    SwapData::$variant(data)
+                }
+            }
+        )*
+    };
+}
+
+swap_data_from! {
+    "perena-swap", Perena => crate::perena::PerenaSwapData;
+    "solfi-swap", SolFi => crate::solfi::SolFiSwapData;
+    "solfi_v2-swap", SolFiV2 => crate::solfi_v2::SolFiV2SwapData;
+    "manifest-swap", Manifest => crate::manifest::ManifestSwapData;
+    "aldrin-swap", Aldrin => crate::aldrin::AldrinSwapData;
+    "aldrin_v2-swap", AldrinV2 => crate::aldrin_v2::AldrinV2SwapData;
+    "futarchy-swap", Futarchy => crate::futarchy::FutarchySwapData;
+    "mock_dex-swap", MockDex => crate::mock_dex::MockDexSwapData;
+}
+
+#[cfg(feature = "heaven-swap")]
+impl<'a> From<crate::heaven::HeavenSwapData<'a>> for SwapData<'a> {
+    #[inline(always)]
+    fn from(data: crate::heaven::HeavenSwapData<'a>) -> Self {
+        SwapData::Heaven(data)
+    }
 }
 
 impl<'a> SwapContext<'a> {
+    /// Destructure `accounts` into the typed swap accounts of `protocol`.
+    #[inline(always)]
+    pub fn parse(
+        protocol: ProtocolKind,
+        accounts: &'a [AccountView],
+    ) -> Result<Self, ProgramError> {
+        let ctx: Result<Self, ProgramError> =
+            match protocol {
+                #[cfg(feature = "perena-swap")]
+                ProtocolKind::Perena => {
+                    crate::perena::PerenaSwapAccounts::try_from(accounts).map(SwapContext::Perena)
+                }
+
+                #[cfg(feature = "solfi-swap")]
+                ProtocolKind::SolFi => {
+                    crate::solfi::SolFiSwapAccounts::try_from(accounts).map(SwapContext::SolFi)
+                }
+
+                #[cfg(feature = "solfi_v2-swap")]
+                ProtocolKind::SolFiV2 => crate::solfi_v2::SolFiV2SwapAccounts::try_from(accounts)
+                    .map(SwapContext::SolFiV2),
+
+                #[cfg(feature = "manifest-swap")]
+                ProtocolKind::Manifest => crate::manifest::ManifestSwapAccounts::try_from(accounts)
+                    .map(SwapContext::Manifest),
+
+                #[cfg(feature = "heaven-swap")]
+                ProtocolKind::Heaven => {
+                    crate::heaven::HeavenSwapAccounts::try_from(accounts).map(SwapContext::Heaven)
+                }
+
+                #[cfg(feature = "aldrin-swap")]
+                ProtocolKind::Aldrin => {
+                    crate::aldrin::AldrinSwapAccounts::try_from(accounts).map(SwapContext::Aldrin)
+                }
+
+                #[cfg(feature = "aldrin_v2-swap")]
+                ProtocolKind::AldrinV2 => {
+                    crate::aldrin_v2::AldrinV2SwapAccounts::try_from(accounts)
+                        .map(SwapContext::AldrinV2)
+                }
+
+                #[cfg(feature = "futarchy-swap")]
+                ProtocolKind::Futarchy => crate::futarchy::FutarchySwapAccounts::try_from(accounts)
+                    .map(SwapContext::Futarchy),
+
+                #[cfg(feature = "gamma-swap")]
+                ProtocolKind::Gamma => {
+                    crate::gamma::GammaSwapAccounts::try_from(accounts).map(SwapContext::Gamma)
+                }
+
+                #[cfg(feature = "mock_dex-swap")]
+                ProtocolKind::MockDex => crate::mock_dex::MockDexSwapAccounts::try_from(accounts)
+                    .map(SwapContext::MockDex),
+
+                // Beethoven errors pass through `adapter_parse_error` unchanged
+                _ => {
+                    let _ = accounts;
+                    Err(BeethovenError::ProtocolFeatureDisabled.into())
+                }
+            };
+
+        ctx.map_err(adapter_parse_error(protocol))
+    }
+
     pub fn try_from_swap_data(&self, data: &'a [u8]) -> Result<SwapData<'a>, ProgramError> {
+        SwapData::parse(self.protocol_kind()?, data)
+    }
+
+    fn protocol_kind(&self) -> Result<ProtocolKind, ProgramError> {
+        swap_context_protocol(self).ok_or(BeethovenError::ProtocolFeatureDisabled.into())
+    }
+
+    /// Program id of the detected venue.
+    pub fn protocol_id(&self) -> Result<Address, ProgramError> {
+        self.protocol_kind().map(ProtocolKind::program_id)
+    }
+
+    /// Name of the detected venue's `ProtocolKind` variant, for logs.
+    pub fn protocol_name(&self) -> Result<&'static str, ProgramError> {
+        self.protocol_kind().map(ProtocolKind::name)
+    }
+
+    /// The user token account a swap with `data` spends from; which of the venue's
+    /// accounts that is depends on the direction in `data`.
+    pub fn user_source_account(
+        &self,
+        data: &SwapData<'_>,
+    ) -> Result<&'a AccountView, ProgramError> {
+        self.user_token_accounts(data).map(|(source, _)| source)
+    }
+
+    /// The user token account a swap with `data` credits.
+    pub fn user_destination_account(
+        &self,
+        data: &SwapData<'_>,
+    ) -> Result<&'a AccountView, ProgramError> {
+        self.user_token_accounts(data)
+            .map(|(_, destination)| destination)
+    }
+
+    /// The account that signs for the user's token accounts, e.g. for
+    /// [`validation::check_authority`](crate::validation::check_authority).
+    pub fn user_authority(&self) -> Result<&'a AccountView, ProgramError> {
         match self {
             #[cfg(feature = "perena-swap")]
-            SwapContext::Perena(_) => Ok(SwapData::Perena(
-                crate::perena::PerenaSwapData::try_from(data)?,
-            )),
+            SwapContext::Perena(accounts) => Ok(accounts.payer),
 
             #[cfg(feature = "solfi-swap")]
-            SwapContext::SolFi(_) => Ok(SwapData::SolFi(crate::solfi::SolFiSwapData::try_from(
-                data,
-            )?)),
+            SwapContext::SolFi(accounts) => Ok(accounts.token_transfer_authority),
 
             #[cfg(feature = "solfi_v2-swap")]
-            SwapContext::SolFiV2(_) => Ok(SwapData::SolFiV2(
-                crate::solfi_v2::SolFiV2SwapData::try_from(data)?,
+            SwapContext::SolFiV2(accounts) => Ok(accounts.token_transfer_authority),
+
+            #[cfg(feature = "manifest-swap")]
+            SwapContext::Manifest(accounts) => Ok(accounts.owner),
+
+            #[cfg(feature = "heaven-swap")]
+            SwapContext::Heaven(accounts) => Ok(accounts.user),
+
+            #[cfg(feature = "aldrin-swap")]
+            SwapContext::Aldrin(accounts) => Ok(accounts.wallet_authority),
+
+            #[cfg(feature = "aldrin_v2-swap")]
+            SwapContext::AldrinV2(accounts) => Ok(accounts.wallet_authority),
+
+            #[cfg(feature = "futarchy-swap")]
+            SwapContext::Futarchy(accounts) => Ok(accounts.user),
+
+            #[cfg(feature = "gamma-swap")]
+            SwapContext::Gamma(accounts) => Ok(accounts.payer),
+
+            #[cfg(feature = "mock_dex-swap")]
+            SwapContext::MockDex(accounts) => Ok(accounts.user),
+
+            #[allow(unreachable_patterns)]
+            _ => Err(BeethovenError::ProtocolFeatureDisabled.into()),
+        }
+    }
+
+    /// The user token accounts `data` spends from and credits, in that order.
+    pub(crate) fn user_token_accounts(
+        &self,
+        data: &SwapData<'_>,
+    ) -> Result<(&'a AccountView, &'a AccountView), ProgramError> {
+        match (self, data) {
+            #[cfg(feature = "perena-swap")]
+            (SwapContext::Perena(accounts), SwapData::Perena(_)) => {
+                Ok((accounts.in_trader, accounts.out_trader))
+            }
+
+            #[cfg(feature = "solfi-swap")]
+            (SwapContext::SolFi(accounts), SwapData::SolFi(d)) => Ok(oriented(
+                d.is_quote_to_base,
+                accounts.user_quote_ata,
+                accounts.user_base_ata,
+            )),
+
+            #[cfg(feature = "solfi_v2-swap")]
+            (SwapContext::SolFiV2(accounts), SwapData::SolFiV2(d)) => Ok(oriented(
+                d.is_quote_to_base,
+                accounts.user_quote_ata,
+                accounts.user_base_ata,
             )),
 
             #[cfg(feature = "manifest-swap")]
-            SwapContext::Manifest(_) => Ok(SwapData::Manifest(
-                crate::manifest::ManifestSwapData::try_from(data)?,
+            (SwapContext::Manifest(accounts), SwapData::Manifest(d)) => Ok(oriented(
+                d.is_base_in,
+                accounts.trader_base,
+                accounts.trader_quote,
             )),
 
+            // Buying spends token B (the quote) for token A
             #[cfg(feature = "heaven-swap")]
-            SwapContext::Heaven(_) => Ok(SwapData::Heaven(
-                crate::heaven::HeavenSwapData::try_from(data)?,
+            (SwapContext::Heaven(accounts), SwapData::Heaven(d)) => Ok(oriented(
+                matches!(d.direction, crate::heaven::SwapDirection::Buy),
+                accounts.user_token_b_account,
+                accounts.user_token_a_account,
             )),
 
             #[cfg(feature = "aldrin-swap")]
-            SwapContext::Aldrin(_) => Ok(SwapData::Aldrin(
-                crate::aldrin::AldrinSwapData::try_from(data)?,
+            (SwapContext::Aldrin(accounts), SwapData::Aldrin(d)) => Ok(oriented(
+                matches!(d.side, crate::aldrin::Side::Bid),
+                accounts.user_quote_token_account,
+                accounts.user_base_token_account,
             )),
 
             #[cfg(feature = "aldrin_v2-swap")]
-            SwapContext::AldrinV2(_) => Ok(SwapData::AldrinV2(
-                crate::aldrin_v2::AldrinV2SwapData::try_from(data)?,
+            (SwapContext::AldrinV2(accounts), SwapData::AldrinV2(d)) => Ok(oriented(
+                matches!(d.side, crate::aldrin_v2::Side::Bid),
+                accounts.user_quote_token_account,
+                accounts.user_base_token_account,
             )),
 
             #[cfg(feature = "futarchy-swap")]
-            SwapContext::Futarchy(_) => Ok(SwapData::Futarchy(
-                crate::futarchy::FutarchySwapData::try_from(data)?,
+            (SwapContext::Futarchy(accounts), SwapData::Futarchy(d)) => Ok(oriented(
+                matches!(d.swap_type, crate::futarchy::SwapType::Buy),
+                accounts.user_quote_account,
+                accounts.user_base_account,
             )),
 
             #[cfg(feature = "gamma-swap")]
-            SwapContext::Gamma(_) => Ok(SwapData::Gamma(())),
+            (SwapContext::Gamma(accounts), SwapData::Gamma(())) => {
+                Ok((accounts.input_token_account, accounts.output_token_account))
+            }
+
+            #[cfg(feature = "mock_dex-swap")]
+            (SwapContext::MockDex(accounts), SwapData::MockDex(d)) => Ok(oriented(
+                d.is_quote_to_base,
+                accounts.user_quote_token,
+                accounts.user_base_token,
+            )),
 
             #[allow(unreachable_patterns)]
-            _ => Err(ProgramError::InvalidAccountData),
+            _ => Err(BeethovenError::InvalidExtraData.into()),
         }
     }
 }
 
-impl<'a> Swap<'a> for SwapContext<'a> {
+/// `(first, second)` when `first_in`, otherwise `(second, first)`.
+#[allow(dead_code)]
+#[inline(always)]
+fn oriented<T>(first_in: bool, first: T, second: T) -> (T, T) {
+    if first_in {
+        (first, second)
+    } else {
+        (second, first)
+    }
+}
+
+/// A detected swap protocol and the raw accounts for it.
+///
+/// Returned by [`try_from_swap_context_lazy`], which only compares the detector account
+/// against the enabled program ids. The accounts are destructured into a
+/// [`SwapContext`] when the swap is issued, so programs that detect the venue and then
+/// bail out never pay for parsing them.
+#[derive(Clone, Copy)]
+pub struct LazySwapContext<'info> {
+    pub protocol: ProtocolKind,
+    pub accounts: &'info [AccountView],
+}
+
+impl<'info> LazySwapContext<'info> {
+    /// Destructure the accounts into the typed [`SwapContext`] of the detected protocol.
+    pub fn parse(&self) -> Result<SwapContext<'info>, ProgramError> {
+        SwapContext::parse(self.protocol, self.accounts)
+    }
+
+    pub fn try_from_swap_data<'a>(&self, data: &'a [u8]) -> Result<SwapData<'a>, ProgramError> {
+        SwapData::parse(self.protocol, data)
+    }
+
+    /// [`Self::try_from_swap_data`] on the payload of a versioned
+    /// [`Envelope`](crate::envelope::Envelope), which must name the detected
+    /// protocol.
+    pub fn try_from_enveloped_swap_data<'a>(
+        &self,
+        data: &'a [u8],
+    ) -> Result<SwapData<'a>, ProgramError> {
+        let envelope = crate::envelope::Envelope::try_from(data)?;
+        self.try_from_swap_data(envelope.payload_for(self.protocol)?)
+    }
+
+    /// [`SwapContext::swap_signed_with_outcome`] on the parsed accounts.
+    pub fn swap_signed_with_outcome(
+        ctx: &Self,
+        in_amount: u64,
+        minimum_out_amount: u64,
+        data: &SwapData<'_>,
+        signer_seeds: &[Signer],
+    ) -> Result<SwapOutcome, ProgramError> {
+        SwapContext::swap_signed_with_outcome(
+            &ctx.parse()?,
+            in_amount,
+            minimum_out_amount,
+            data,
+            signer_seeds,
+        )
+    }
+
+    /// [`SwapContext::swap_signed_with_scratch`] on the parsed accounts.
+    pub fn swap_signed_with_scratch(
+        ctx: &Self,
+        in_amount: u64,
+        minimum_out_amount: u64,
+        data: &SwapData<'_>,
+        signer_seeds: &[Signer],
+        scratch: &mut [MaybeUninit<u8>],
+    ) -> ProgramResult {
+        SwapContext::swap_signed_with_scratch(
+            &ctx.parse()?,
+            in_amount,
+            minimum_out_amount,
+            data,
+            signer_seeds,
+            scratch,
+        )
+    }
+}
+
+impl<'info> Swap<'info> for LazySwapContext<'info> {
     type Accounts = Self;
-    type Data = SwapData<'a>;
+    type Data = SwapData<'info>;
 
     fn swap_signed(
         ctx: &Self::Accounts,
@@ -129,109 +510,310 @@ impl<'a> Swap<'a> for SwapContext<'a> {
         data: &Self::Data,
         signer_seeds: &[Signer],
     ) -> ProgramResult {
+        SwapContext::swap_signed(
+            &ctx.parse()?,
+            in_amount,
+            minimum_out_amount,
+            data,
+            signer_seeds,
+        )
+    }
+
+    #[inline(always)]
+    fn swap(
+        ctx: &Self::Accounts,
+        in_amount: u64,
+        minimum_out_amount: u64,
+        data: &Self::Data,
+    ) -> ProgramResult {
+        Self::swap_signed(ctx, in_amount, minimum_out_amount, data, &[])
+    }
+
+    fn swap_exact_out_signed(
+        ctx: &Self::Accounts,
+        out_amount: u64,
+        maximum_in_amount: u64,
+        data: &Self::Data,
+        signer_seeds: &[Signer],
+    ) -> ProgramResult {
+        SwapContext::swap_exact_out_signed(
+            &ctx.parse()?,
+            out_amount,
+            maximum_in_amount,
+            data,
+            signer_seeds,
+        )
+    }
+}
+
+/// Scratch space large enough for the instruction data of every enabled swap adapter.
+pub const SWAP_SCRATCH_LEN: usize = {
+    let len = 0;
+    #[cfg(feature = "perena-swap")]
+    let len = max(len, crate::perena::MAX_INSTRUCTION_DATA_LEN);
+    #[cfg(feature = "solfi-swap")]
+    let len = max(len, crate::solfi::MAX_INSTRUCTION_DATA_LEN);
+    #[cfg(feature = "solfi_v2-swap")]
+    let len = max(len, crate::solfi_v2::MAX_INSTRUCTION_DATA_LEN);
+    #[cfg(feature = "manifest-swap")]
+    let len = max(len, crate::manifest::MAX_INSTRUCTION_DATA_LEN);
+    #[cfg(feature = "heaven-swap")]
+    let len = max(len, crate::heaven::MAX_INSTRUCTION_DATA_LEN);
+    #[cfg(feature = "aldrin-swap")]
+    let len = max(len, crate::aldrin::MAX_INSTRUCTION_DATA_LEN);
+    #[cfg(feature = "aldrin_v2-swap")]
+    let len = max(len, crate::aldrin_v2::MAX_INSTRUCTION_DATA_LEN);
+    #[cfg(feature = "futarchy-swap")]
+    let len = max(len, crate::futarchy::MAX_INSTRUCTION_DATA_LEN);
+    #[cfg(feature = "gamma-swap")]
+    let len = max(len, crate::gamma::MAX_INSTRUCTION_DATA_LEN);
+    #[cfg(feature = "mock_dex-swap")]
+    let len = max(len, crate::mock_dex::MAX_INSTRUCTION_DATA_LEN);
+    len
+};
+
+#[allow(dead_code)]
+const fn max(a: usize, b: usize) -> usize {
+    if a > b {
+        a
+    } else {
+        b
+    }
+}
+
+impl SwapContext<'_> {
+    /// Dispatch the swap with the instruction data assembled in caller-provided `scratch`
+    /// (at least [`SWAP_SCRATCH_LEN`] bytes).
+    ///
+    /// Callers issuing several CPIs can allocate one [`Scratch`] up front and reuse it,
+    /// instead of every adapter frame reserving its own buffer.
+    ///
+    /// An `in_amount` of [`FULL_BALANCE`] swaps the whole balance of the token account
+    /// `data` spends from.
+    pub fn swap_signed_with_scratch(
+        ctx: &Self,
+        in_amount: u64,
+        minimum_out_amount: u64,
+        data: &SwapData<'_>,
+        signer_seeds: &[Signer],
+        scratch: &mut [MaybeUninit<u8>],
+    ) -> ProgramResult {
+        let in_amount = if in_amount == FULL_BALANCE {
+            token_account_amount(ctx.user_token_accounts(data)?.0)?
+        } else {
+            in_amount
+        };
+
         match (ctx, data) {
             #[cfg(feature = "perena-swap")]
             (SwapContext::Perena(accounts), SwapData::Perena(d)) => {
-                crate::perena::Perena::swap_signed(
+                crate::perena::Perena::swap_signed_with_scratch(
                     accounts,
                     in_amount,
                     minimum_out_amount,
                     d,
                     signer_seeds,
+                    scratch,
                 )
             }
 
             #[cfg(feature = "solfi-swap")]
-            (SwapContext::SolFi(accounts), SwapData::SolFi(d)) => crate::solfi::SolFi::swap_signed(
-                accounts,
-                in_amount,
-                minimum_out_amount,
-                d,
-                signer_seeds,
-            ),
+            (SwapContext::SolFi(accounts), SwapData::SolFi(d)) => {
+                crate::solfi::SolFi::swap_signed_with_scratch(
+                    accounts,
+                    in_amount,
+                    minimum_out_amount,
+                    d,
+                    signer_seeds,
+                    scratch,
+                )
+            }
 
             #[cfg(feature = "solfi_v2-swap")]
             (SwapContext::SolFiV2(accounts), SwapData::SolFiV2(d)) => {
-                crate::solfi_v2::SolFiV2::swap_signed(
+                crate::solfi_v2::SolFiV2::swap_signed_with_scratch(
                     accounts,
                     in_amount,
                     minimum_out_amount,
                     d,
                     signer_seeds,
+                    scratch,
                 )
             }
 
             #[cfg(feature = "manifest-swap")]
             (SwapContext::Manifest(accounts), SwapData::Manifest(d)) => {
-                crate::manifest::Manifest::swap_signed(
+                crate::manifest::Manifest::swap_signed_with_scratch(
                     accounts,
                     in_amount,
                     minimum_out_amount,
                     d,
                     signer_seeds,
+                    scratch,
                 )
             }
 
             #[cfg(feature = "heaven-swap")]
             (SwapContext::Heaven(accounts), SwapData::Heaven(d)) => {
-                crate::heaven::Heaven::swap_signed(
+                crate::heaven::Heaven::swap_signed_with_scratch(
                     accounts,
                     in_amount,
                     minimum_out_amount,
                     d,
                     signer_seeds,
+                    scratch,
                 )
             }
 
             #[cfg(feature = "aldrin-swap")]
             (SwapContext::Aldrin(accounts), SwapData::Aldrin(d)) => {
-                crate::aldrin::Aldrin::swap_signed(
+                crate::aldrin::Aldrin::swap_signed_with_scratch(
                     accounts,
                     in_amount,
                     minimum_out_amount,
                     d,
                     signer_seeds,
+                    scratch,
                 )
             }
 
             #[cfg(feature = "aldrin_v2-swap")]
             (SwapContext::AldrinV2(accounts), SwapData::AldrinV2(d)) => {
-                crate::aldrin_v2::AldrinV2::swap_signed(
+                crate::aldrin_v2::AldrinV2::swap_signed_with_scratch(
                     accounts,
                     in_amount,
                     minimum_out_amount,
                     d,
                     signer_seeds,
+                    scratch,
                 )
             }
 
             #[cfg(feature = "futarchy-swap")]
             (SwapContext::Futarchy(accounts), SwapData::Futarchy(d)) => {
-                crate::futarchy::Futarchy::swap_signed(
+                crate::futarchy::Futarchy::swap_signed_with_scratch(
                     accounts,
                     in_amount,
                     minimum_out_amount,
                     d,
                     signer_seeds,
+                    scratch,
                 )
             }
 
             #[cfg(feature = "gamma-swap")]
             (SwapContext::Gamma(accounts), SwapData::Gamma(())) => {
-                crate::gamma::Gamma::swap_signed(
+                crate::gamma::Gamma::swap_signed_with_scratch(
                     accounts,
                     in_amount,
                     minimum_out_amount,
                     &(),
                     signer_seeds,
+                    scratch,
+                )
+            }
+
+            #[cfg(feature = "mock_dex-swap")]
+            (SwapContext::MockDex(accounts), SwapData::MockDex(d)) => {
+                crate::mock_dex::MockDex::swap_signed_with_scratch(
+                    accounts,
+                    in_amount,
+                    minimum_out_amount,
+                    d,
+                    signer_seeds,
+                    scratch,
                 )
             }
 
             #[allow(unreachable_patterns)]
-            _ => Err(ProgramError::InvalidAccountData),
+            _ => {
+                let _ = (in_amount, minimum_out_amount, signer_seeds, scratch);
+                Err(BeethovenError::InvalidExtraData.into())
+            }
         }
     }
 
+    /// [`Swap::swap_signed`] between two caller checks on the parsed accounts.
+    ///
+    /// `pre` runs before the CPI and can reject it (e.g. a market not on a whitelist);
+    /// whatever it returns is handed to `post`, which runs after the CPI, so a balance
+    /// snapshotted before the swap can be checked against the one after it. An error
+    /// from either fails the swap.
+    pub fn execute_with_hooks<T>(
+        ctx: &Self,
+        in_amount: u64,
+        minimum_out_amount: u64,
+        data: &SwapData<'_>,
+        signer_seeds: &[Signer],
+        pre: impl FnOnce(&Self) -> Result<T, ProgramError>,
+        post: impl FnOnce(&Self, T) -> ProgramResult,
+    ) -> ProgramResult {
+        let checked = pre(ctx)?;
+        Self::swap_signed(ctx, in_amount, minimum_out_amount, data, signer_seeds)?;
+        post(ctx, checked)
+    }
+
+    /// [`Swap::swap_signed`], returning what the swap moved on the user token accounts
+    /// `data` spends from and credits.
+    pub fn swap_signed_with_outcome(
+        ctx: &Self,
+        in_amount: u64,
+        minimum_out_amount: u64,
+        data: &SwapData<'_>,
+        signer_seeds: &[Signer],
+    ) -> Result<SwapOutcome, ProgramError> {
+        measure_swap(ctx, data, || {
+            Self::swap_signed(ctx, in_amount, minimum_out_amount, data, signer_seeds)
+        })
+    }
+}
+
+/// Run `swap` and measure the change of `ctx`'s user token accounts for `data` around
+/// it.
+fn measure_swap(
+    ctx: &SwapContext<'_>,
+    data: &SwapData<'_>,
+    swap: impl FnOnce() -> ProgramResult,
+) -> Result<SwapOutcome, ProgramError> {
+    let (input, output) = ctx.user_token_accounts(data)?;
+    let input_before = token_account_amount(input)?;
+    let output_before = token_account_amount(output)?;
+
+    swap()?;
+
+    Ok(SwapOutcome {
+        in_spent: input_before
+            .checked_sub(token_account_amount(input)?)
+            .ok_or(ProgramError::ArithmeticOverflow)?,
+        out_received: token_account_amount(output)?
+            .checked_sub(output_before)
+            .ok_or(ProgramError::ArithmeticOverflow)?,
+    })
+}
+
+impl<'a> Swap<'a> for SwapContext<'a> {
+    type Accounts = Self;
+    type Data = SwapData<'a>;
+
+    fn swap_signed(
+        ctx: &Self::Accounts,
+        in_amount: u64,
+        minimum_out_amount: u64,
+        data: &Self::Data,
+        signer_seeds: &[Signer],
+    ) -> ProgramResult {
+        let mut scratch = Scratch::<SWAP_SCRATCH_LEN>::new();
+        Self::swap_signed_with_scratch(
+            ctx,
+            in_amount,
+            minimum_out_amount,
+            data,
+            signer_seeds,
+            scratch.as_uninit_mut(),
+        )
+    }
+
+    #[inline(always)]
     fn swap(
         ctx: &Self::Accounts,
         in_amount: u64,
@@ -240,126 +822,565 @@ impl<'a> Swap<'a> for SwapContext<'a> {
     ) -> ProgramResult {
         Self::swap_signed(ctx, in_amount, minimum_out_amount, data, &[])
     }
+
+    /// Only venues with [`Capabilities::SUPPORTS_EXACT_OUT`](crate::Capabilities) swap;
+    /// every other context fails with [`BeethovenError::UnsupportedSwapMode`].
+    fn swap_exact_out_signed(
+        ctx: &Self::Accounts,
+        out_amount: u64,
+        maximum_in_amount: u64,
+        data: &Self::Data,
+        signer_seeds: &[Signer],
+    ) -> ProgramResult {
+        match (ctx, data) {
+            #[cfg(feature = "manifest-swap")]
+            (SwapContext::Manifest(accounts), SwapData::Manifest(d)) => {
+                crate::manifest::Manifest::swap_exact_out_signed(
+                    accounts,
+                    out_amount,
+                    maximum_in_amount,
+                    d,
+                    signer_seeds,
+                )
+            }
+
+            #[allow(unreachable_patterns)]
+            _ => {
+                let _ = (out_amount, maximum_in_amount, signer_seeds);
+                Err(BeethovenError::UnsupportedSwapMode.into())
+            }
+        }
+    }
 }
 
-pub fn try_from_swap_context<'info>(
-    accounts: &'info [AccountView],
-) -> Result<SwapContext<'info>, ProgramError> {
+/// Attribute a failure to parse a detected protocol's accounts to that protocol.
+///
+/// Beethoven's own typed errors (e.g. strict-validation failures) are passed through.
+#[inline(always)]
+pub(crate) fn adapter_parse_error(protocol: ProtocolKind) -> impl Fn(ProgramError) -> ProgramError {
+    move |error| match error {
+        ProgramError::Custom(_) => error,
+        _ => BeethovenError::AdapterParseError { protocol }.into(),
+    }
+}
+
+/// Report a failure to parse a protocol's swap data as [`BeethovenError::InvalidExtraData`],
+/// passing Beethoven's own typed errors through.
+#[inline(always)]
+fn invalid_extra_data(error: ProgramError) -> ProgramError {
+    match error {
+        ProgramError::Custom(_) => error,
+        _ => BeethovenError::InvalidExtraData.into(),
+    }
+}
+
+/// Identify the swap protocol `accounts` are laid out for from the detector account.
+#[inline(always)]
+pub fn detect_swap_protocol(accounts: &[AccountView]) -> Result<ProtocolKind, ProgramError> {
     let detector_account = accounts.first().ok_or(ProgramError::NotEnoughAccountKeys)?;
+    swap_protocol_of(detector_account.address()).ok_or(BeethovenError::UnknownProtocol.into())
+}
 
-    #[cfg(feature = "perena-swap")]
-    if address_eq(
-        detector_account.address(),
-        &crate::perena::PERENA_PROGRAM_ID,
-    ) {
-        let ctx = crate::perena::PerenaSwapAccounts::try_from(accounts)?;
-        return Ok(SwapContext::Perena(ctx));
+/// Detect the swap protocol of `accounts` and destructure them into its typed accounts.
+#[inline(always)]
+pub fn try_from_swap_context(accounts: &[AccountView]) -> Result<SwapContext<'_>, ProgramError> {
+    try_from_swap_context_lazy(accounts)?.parse()
+}
+
+/// [`try_from_swap_context`], deferring the parsing of `accounts` to the swap itself.
+#[inline(always)]
+pub fn try_from_swap_context_lazy(
+    accounts: &[AccountView],
+) -> Result<LazySwapContext<'_>, ProgramError> {
+    Ok(LazySwapContext {
+        protocol: detect_swap_protocol(accounts)?,
+        accounts,
+    })
+}
+
+/// How [`try_from_swap_context_by`] identifies the swap venue, for routers whose
+/// accounts do not start with the venue's program.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Detection {
+    /// The venue's program is the account at this index, and the adapter's accounts
+    /// start there. `ProgramAt(0)` is what [`try_from_swap_context_lazy`] does.
+    ProgramAt(usize),
+    /// The venue's program owns the account at this index, e.g. its pool or market.
+    OwnerOf(usize),
+    /// The caller names the venue, e.g. from a tag in its instruction data. Nothing is
+    /// compared; the adapter's CPI still targets its own program.
+    Tagged(ProtocolKind),
+}
+
+impl Default for Detection {
+    fn default() -> Self {
+        Detection::ProgramAt(0)
     }
+}
 
-    #[cfg(feature = "solfi-swap")]
-    if address_eq(detector_account.address(), &crate::solfi::SOLFI_PROGRAM_ID) {
-        let ctx = crate::solfi::SolFiSwapAccounts::try_from(accounts)?;
-        return Ok(SwapContext::SolFi(ctx));
+/// Identify the swap protocol of `accounts` by `detection`, returning it and the
+/// accounts its adapter reads.
+#[inline(always)]
+pub fn detect_swap_protocol_by(
+    accounts: &[AccountView],
+    detection: Detection,
+) -> Result<(ProtocolKind, &[AccountView]), ProgramError> {
+    match detection {
+        Detection::ProgramAt(index) => {
+            let accounts = accounts
+                .get(index..)
+                .ok_or(ProgramError::NotEnoughAccountKeys)?;
+            Ok((detect_swap_protocol(accounts)?, accounts))
+        }
+        Detection::OwnerOf(index) => {
+            let account = accounts
+                .get(index)
+                .ok_or(ProgramError::NotEnoughAccountKeys)?;
+            // SAFETY: the owner is read once, before any CPI could reassign the account
+            let owner = unsafe { account.owner() };
+            let protocol = swap_protocol_of(owner).ok_or(BeethovenError::UnknownProtocol)?;
+            Ok((protocol, accounts))
+        }
+        Detection::Tagged(protocol) => Ok((protocol, accounts)),
     }
+}
 
-    #[cfg(feature = "solfi_v2-swap")]
-    if address_eq(
-        detector_account.address(),
-        &crate::solfi_v2::SOLFI_V2_PROGRAM_ID,
-    ) {
-        let ctx = crate::solfi_v2::SolFiV2SwapAccounts::try_from(accounts)?;
-        return Ok(SwapContext::SolFiV2(ctx));
+/// [`try_from_swap_context_lazy`], identifying the venue by `detection` instead of the
+/// first account.
+#[inline(always)]
+pub fn try_from_swap_context_by(
+    accounts: &[AccountView],
+    detection: Detection,
+) -> Result<LazySwapContext<'_>, ProgramError> {
+    let (protocol, accounts) = detect_swap_protocol_by(accounts, detection)?;
+    Ok(LazySwapContext { protocol, accounts })
+}
+
+pub fn swap_signed(
+    accounts: &[AccountView],
+    in_amount: u64,
+    minimum_out_amount: u64,
+    data: &SwapData<'_>,
+    signer_seeds: &[Signer],
+) -> ProgramResult {
+    let ctx = try_from_swap_context_lazy(accounts)?;
+    LazySwapContext::swap_signed(&ctx, in_amount, minimum_out_amount, data, signer_seeds)
+}
+
+#[inline(always)]
+pub fn swap(
+    accounts: &[AccountView],
+    in_amount: u64,
+    minimum_out_amount: u64,
+    data: &SwapData<'_>,
+) -> ProgramResult {
+    swap_signed(accounts, in_amount, minimum_out_amount, data, &[])
+}
+
+/// Exact-out counterpart of [`swap_signed`]: receive exactly `out_amount`, spending at
+/// most `maximum_in_amount`.
+pub fn swap_exact_out_signed(
+    accounts: &[AccountView],
+    out_amount: u64,
+    maximum_in_amount: u64,
+    data: &SwapData<'_>,
+    signer_seeds: &[Signer],
+) -> ProgramResult {
+    let ctx = try_from_swap_context_lazy(accounts)?;
+    LazySwapContext::swap_exact_out_signed(&ctx, out_amount, maximum_in_amount, data, signer_seeds)
+}
+
+#[inline(always)]
+pub fn swap_exact_out(
+    accounts: &[AccountView],
+    out_amount: u64,
+    maximum_in_amount: u64,
+    data: &SwapData<'_>,
+) -> ProgramResult {
+    swap_exact_out_signed(accounts, out_amount, maximum_in_amount, data, &[])
+}
+
+/// A multi-hop swap: legs on any enabled venues, run in order against their own slices
+/// of one flat account list.
+///
+/// `accounts` holds every leg's routed accounts back to back, each leg's detector first,
+/// and `leg_account_counts[i]` is how many of them leg `i` takes. Each leg after the
+/// first must spend from the token account the leg before it credited, and spends
+/// exactly what that leg delivered, so intermediate amounts never have to be known up
+/// front. Only the route's final output is held to a minimum.
+#[derive(Clone, Copy)]
+pub struct SwapRoute<'a> {
+    pub accounts: &'a [AccountView],
+    pub leg_account_counts: &'a [u8],
+}
+
+impl<'a> SwapRoute<'a> {
+    /// A route over `accounts`, which the legs' counts must cover exactly.
+    pub fn new(
+        accounts: &'a [AccountView],
+        leg_account_counts: &'a [u8],
+    ) -> Result<Self, ProgramError> {
+        if leg_account_counts.is_empty() {
+            return Err(ProgramError::InvalidArgument);
+        }
+        let total: usize = leg_account_counts.iter().map(|&count| count as usize).sum();
+        if total != accounts.len() {
+            return Err(BeethovenError::AccountCountMismatch.into());
+        }
+
+        Ok(Self {
+            accounts,
+            leg_account_counts,
+        })
     }
 
-    #[cfg(feature = "manifest-swap")]
-    if address_eq(
-        detector_account.address(),
-        &crate::manifest::MANIFEST_PROGRAM_ID,
-    ) {
-        let ctx = crate::manifest::ManifestSwapAccounts::try_from(accounts)?;
-        return Ok(SwapContext::Manifest(ctx));
+    /// Each leg's accounts, in route order.
+    ///
+    /// A leg past the end of `accounts` (possible when the route was built without
+    /// [`Self::new`]) is cut short, and fails to parse when swapped.
+    pub fn legs(&self) -> impl Iterator<Item = &'a [AccountView]> + 'a {
+        let (mut accounts, counts) = (self.accounts, self.leg_account_counts);
+        counts.iter().map(move |&count| {
+            let (leg, rest) = accounts.split_at((count as usize).min(accounts.len()));
+            accounts = rest;
+            leg
+        })
     }
 
-    #[cfg(feature = "heaven-swap")]
-    if address_eq(
-        detector_account.address(),
-        &crate::heaven::HEAVEN_PROGRAM_ID,
-    ) {
-        let ctx = crate::heaven::HeavenSwapAccounts::try_from(accounts)?;
-        return Ok(SwapContext::Heaven(ctx));
+    /// Swap `in_amount` through every leg, failing unless the last leg credits at least
+    /// `minimum_out_amount`.
+    ///
+    /// `leg_data[i]` is leg `i`'s swap data, parsed for the protocol its detector
+    /// account names. Legs are swapped with a zero minimum, the overall minimum standing
+    /// in for theirs.
+    ///
+    /// Each leg reads only its output's balance, before and after it swaps: what it
+    /// credited is the next leg's `in_amount`, so the account a leg spends from is never
+    /// read.
+    pub fn swap_signed(
+        &self,
+        in_amount: u64,
+        minimum_out_amount: u64,
+        leg_data: &[&[u8]],
+        signer_seeds: &[Signer],
+    ) -> ProgramResult {
+        if leg_data.len() != self.leg_account_counts.len() {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let mut scratch = Scratch::<SWAP_SCRATCH_LEN>::new();
+        let mut amount = in_amount;
+        let mut previous_output = None;
+
+        for (leg_accounts, data) in self.legs().zip(leg_data) {
+            let (out_received, output) = swap_route_leg(
+                leg_accounts,
+                data,
+                amount,
+                previous_output.as_ref(),
+                signer_seeds,
+                scratch.as_uninit_mut(),
+            )?;
+            amount = out_received;
+            previous_output = Some(output);
+        }
+
+        if amount < minimum_out_amount {
+            return Err(BeethovenError::SlippageExceeded.into());
+        }
+        Ok(())
     }
 
-    #[cfg(feature = "aldrin-swap")]
-    if address_eq(
-        detector_account.address(),
-        &crate::aldrin::ALDRIN_PROGRAM_ID,
-    ) {
-        let ctx = crate::aldrin::AldrinSwapAccounts::try_from(accounts)?;
-        return Ok(SwapContext::Aldrin(ctx));
+    #[inline(always)]
+    pub fn swap(
+        &self,
+        in_amount: u64,
+        minimum_out_amount: u64,
+        leg_data: &[&[u8]],
+    ) -> ProgramResult {
+        self.swap_signed(in_amount, minimum_out_amount, leg_data, &[])
     }
+}
 
-    #[cfg(feature = "aldrin_v2-swap")]
-    if address_eq(
-        detector_account.address(),
-        &crate::aldrin_v2::ALDRIN_V2_PROGRAM_ID,
-    ) {
-        let ctx = crate::aldrin_v2::AldrinV2SwapAccounts::try_from(accounts)?;
-        return Ok(SwapContext::AldrinV2(ctx));
+/// Swap `amount` through the leg on `leg_accounts`, returning what it credited and the
+/// token account it credited it to.
+///
+/// The leg must spend from `previous_output`, the account the leg before it credited,
+/// when there is one.
+fn swap_route_leg(
+    leg_accounts: &[AccountView],
+    data: &[u8],
+    amount: u64,
+    previous_output: Option<&Address>,
+    signer_seeds: &[Signer],
+    scratch: &mut [MaybeUninit<u8>],
+) -> Result<(u64, Address), ProgramError> {
+    let leg = try_from_swap_context_lazy(leg_accounts)?;
+    let swap_data = leg.try_from_swap_data(data)?;
+    let ctx = leg.parse()?;
+
+    let (input, output) = ctx.user_token_accounts(&swap_data)?;
+    if let Some(previous_output) = previous_output {
+        if !address_eq(input.address(), previous_output) {
+            return Err(BeethovenError::RouteLegMismatch.into());
+        }
     }
 
-    #[cfg(feature = "futarchy-swap")]
-    if address_eq(
-        detector_account.address(),
-        &crate::futarchy::FUTARCHY_PROGRAM_ID,
-    ) {
-        let ctx = crate::futarchy::FutarchySwapAccounts::try_from(accounts)?;
-        return Ok(SwapContext::Futarchy(ctx));
+    // The leg spends `amount`, so unlike `measure_swap` only the output is read
+    let output_before = token_account_amount(output)?;
+    SwapContext::swap_signed_with_scratch(&ctx, amount, 0, &swap_data, signer_seeds, scratch)?;
+    let out_received = token_account_amount(output)?
+        .checked_sub(output_before)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    Ok((out_received, *output.address()))
+}
+
+/// Most accounts one leg of a [`SharedSwapRoute`] can take, more than any swap adapter
+/// routes (detector included).
+pub const MAX_ROUTE_LEG_ACCOUNTS: usize = 24;
+
+/// A [`SwapRoute`] whose legs pick their accounts out of one table of distinct accounts.
+///
+/// `accounts` holds every account the route touches once, and leg `i` takes the next
+/// `leg_account_counts[i]` entries of `account_indices`, each an index into `accounts`,
+/// in the order its adapter expects them. Accounts several legs name (the token
+/// program, the user's authority, the token account one leg credits and the next spends
+/// from) are passed to the program once rather than once per leg, which keeps routes of
+/// three or more hops within a transaction's account limit. Legs are swapped exactly as
+/// [`SwapRoute::swap_signed`] swaps them.
+#[derive(Clone, Copy)]
+pub struct SharedSwapRoute<'a> {
+    pub accounts: &'a [AccountView],
+    pub account_indices: &'a [u8],
+    pub leg_account_counts: &'a [u8],
+}
+
+impl<'a> SharedSwapRoute<'a> {
+    /// A route over `accounts`, the legs' counts covering `account_indices` exactly and
+    /// every index naming one of `accounts`.
+    pub fn new(
+        accounts: &'a [AccountView],
+        account_indices: &'a [u8],
+        leg_account_counts: &'a [u8],
+    ) -> Result<Self, ProgramError> {
+        if leg_account_counts.is_empty()
+            || leg_account_counts
+                .iter()
+                .any(|&count| count as usize > MAX_ROUTE_LEG_ACCOUNTS)
+        {
+            return Err(ProgramError::InvalidArgument);
+        }
+        let total: usize = leg_account_counts.iter().map(|&count| count as usize).sum();
+        if total != account_indices.len() {
+            return Err(BeethovenError::AccountCountMismatch.into());
+        }
+        if account_indices
+            .iter()
+            .any(|&index| index as usize >= accounts.len())
+        {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        }
+
+        Ok(Self {
+            accounts,
+            account_indices,
+            leg_account_counts,
+        })
     }
 
-    #[cfg(feature = "gamma-swap")]
-    if address_eq(detector_account.address(), &crate::gamma::GAMMA_PROGRAM_ID) {
-        let ctx = crate::gamma::GammaSwapAccounts::try_from(accounts)?;
-        return Ok(SwapContext::Gamma(ctx));
+    /// Each leg's account indices, in route order, cut short like [`SwapRoute::legs`].
+    pub fn leg_indices(&self) -> impl Iterator<Item = &'a [u8]> + 'a {
+        let (mut indices, counts) = (self.account_indices, self.leg_account_counts);
+        counts.iter().map(move |&count| {
+            let (leg, rest) = indices.split_at((count as usize).min(indices.len()));
+            indices = rest;
+            leg
+        })
     }
 
-    Err(ProgramError::InvalidAccountData)
+    /// [`SwapRoute::swap_signed`], each leg's accounts gathered from the table.
+    pub fn swap_signed(
+        &self,
+        in_amount: u64,
+        minimum_out_amount: u64,
+        leg_data: &[&[u8]],
+        signer_seeds: &[Signer],
+    ) -> ProgramResult {
+        if leg_data.len() != self.leg_account_counts.len() {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        let first = self
+            .accounts
+            .first()
+            .ok_or(ProgramError::NotEnoughAccountKeys)?;
+
+        let mut scratch = Scratch::<SWAP_SCRATCH_LEN>::new();
+        let mut leg_accounts: [AccountView; MAX_ROUTE_LEG_ACCOUNTS] =
+            core::array::from_fn(|_| first.clone());
+        let mut amount = in_amount;
+        let mut previous_output = None;
+
+        for (indices, data) in self.leg_indices().zip(leg_data) {
+            // The fields are public, so the bounds `new` checked are checked again
+            if indices.len() > MAX_ROUTE_LEG_ACCOUNTS {
+                return Err(ProgramError::InvalidArgument);
+            }
+            for (slot, &index) in leg_accounts.iter_mut().zip(indices) {
+                *slot = self
+                    .accounts
+                    .get(index as usize)
+                    .ok_or(ProgramError::NotEnoughAccountKeys)?
+                    .clone();
+            }
+
+            let (out_received, output) = swap_route_leg(
+                &leg_accounts[..indices.len()],
+                data,
+                amount,
+                previous_output.as_ref(),
+                signer_seeds,
+                scratch.as_uninit_mut(),
+            )?;
+            amount = out_received;
+            previous_output = Some(output);
+        }
+
+        if amount < minimum_out_amount {
+            return Err(BeethovenError::SlippageExceeded.into());
+        }
+        Ok(())
+    }
+
+    #[inline(always)]
+    pub fn swap(
+        &self,
+        in_amount: u64,
+        minimum_out_amount: u64,
+        leg_data: &[&[u8]],
+    ) -> ProgramResult {
+        self.swap_signed(in_amount, minimum_out_amount, leg_data, &[])
+    }
 }
 
-pub fn swap_signed(
+/// [`swap_signed`] of the whole balance of the token account `data` spends from, e.g.
+/// to sweep a PDA-owned account.
+pub fn swap_all_signed(
     accounts: &[AccountView],
-    in_amount: u64,
     minimum_out_amount: u64,
     data: &SwapData<'_>,
     signer_seeds: &[Signer],
 ) -> ProgramResult {
-    let ctx = try_from_swap_context(accounts)?;
-    SwapContext::swap_signed(&ctx, in_amount, minimum_out_amount, data, signer_seeds)
+    swap_signed(
+        accounts,
+        FULL_BALANCE,
+        minimum_out_amount,
+        data,
+        signer_seeds,
+    )
 }
 
-pub fn swap(
+#[inline(always)]
+pub fn swap_all(
     accounts: &[AccountView],
-    in_amount: u64,
     minimum_out_amount: u64,
     data: &SwapData<'_>,
 ) -> ProgramResult {
-    swap_signed(accounts, in_amount, minimum_out_amount, data, &[])
+    swap_all_signed(accounts, minimum_out_amount, data, &[])
 }
 
 // Deposit context - similar pattern
 use crate::Deposit;
 
+/// Protocol-specific deposit data enum for use with DepositContext
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DepositData {
+    #[cfg(feature = "kamino-deposit")]
+    Kamino(crate::kamino::KaminoDepositData),
+
+    #[cfg(feature = "jupiter-deposit")]
+    Jupiter(crate::jupiter::JupiterEarnDepositData),
+}
+
+impl DepositData {
+    /// Parse `data` as the deposit data of `protocol`.
+    #[inline(always)]
+    pub fn parse(protocol: ProtocolKind, data: &[u8]) -> Result<Self, ProgramError> {
+        match protocol {
+            #[cfg(feature = "kamino-deposit")]
+            ProtocolKind::Kamino => {
+                crate::kamino::KaminoDepositData::try_from(data).map(DepositData::Kamino)
+            }
+
+            #[cfg(feature = "jupiter-deposit")]
+            ProtocolKind::Jupiter => {
+                crate::jupiter::JupiterEarnDepositData::try_from(data).map(DepositData::Jupiter)
+            }
+
+            _ => {
+                let _ = data;
+                Err(BeethovenError::ProtocolFeatureDisabled.into())
+            }
+        }
+    }
+}
+
 pub enum DepositContext<'info> {
     #[cfg(feature = "kamino-deposit")]
     Kamino(crate::kamino::KaminoDepositAccounts<'info>),
 
     #[cfg(feature = "jupiter-deposit")]
     Jupiter(crate::jupiter::JupiterEarnDepositAccounts<'info>),
+
+    /// Keeps `'info` in use when no deposit protocol is enabled
+    #[cfg(not(any(feature = "kamino-deposit", feature = "jupiter-deposit")))]
+    #[doc(hidden)]
+    __Unused(core::marker::PhantomData<&'info ()>),
+}
+
+impl<'info> DepositContext<'info> {
+    /// Parse `data` as the deposit data of the detected protocol.
+    #[inline(always)]
+    pub fn try_from_deposit_data(&self, data: &[u8]) -> Result<DepositData, ProgramError> {
+        DepositData::parse(self.protocol_kind()?, data)
+    }
+
+    fn protocol_kind(&self) -> Result<ProtocolKind, ProgramError> {
+        match self {
+            #[cfg(feature = "kamino-deposit")]
+            DepositContext::Kamino(_) => Ok(ProtocolKind::Kamino),
+
+            #[cfg(feature = "jupiter-deposit")]
+            DepositContext::Jupiter(_) => Ok(ProtocolKind::Jupiter),
+
+            #[allow(unreachable_patterns)]
+            _ => Err(BeethovenError::ProtocolFeatureDisabled.into()),
+        }
+    }
+
+    /// The token account the deposit spends from.
+    pub(crate) fn user_source_account(&self) -> Result<&'info AccountView, ProgramError> {
+        match self {
+            #[cfg(feature = "kamino-deposit")]
+            DepositContext::Kamino(accounts) => Ok(accounts.user_source_liquidity),
+
+            #[cfg(feature = "jupiter-deposit")]
+            DepositContext::Jupiter(accounts) => Ok(accounts.depositor_token_account),
+
+            #[allow(unreachable_patterns)]
+            _ => Err(BeethovenError::ProtocolFeatureDisabled.into()),
+        }
+    }
 }
 
 impl<'info> Deposit<'info> for DepositContext<'info> {
     type Accounts = Self;
 
+    /// An `amount` of [`FULL_BALANCE`] deposits the whole balance of the depositor's
+    /// token account.
     fn deposit_signed(ctx: &Self::Accounts, amount: u64, signer_seeds: &[Signer]) -> ProgramResult {
+        let amount = resolve_amount(amount, ctx.user_source_account()?)?;
+
         match ctx {
             #[cfg(feature = "kamino-deposit")]
             DepositContext::Kamino(accounts) => {
@@ -372,37 +1393,215 @@ impl<'info> Deposit<'info> for DepositContext<'info> {
             }
 
             #[allow(unreachable_patterns)]
-            _ => Err(ProgramError::InvalidAccountData),
+            _ => {
+                let _ = (amount, signer_seeds);
+                Err(BeethovenError::ProtocolFeatureDisabled.into())
+            }
         }
     }
 
+    #[inline(always)]
     fn deposit(ctx: &Self::Accounts, amount: u64) -> ProgramResult {
         Self::deposit_signed(ctx, amount, &[])
     }
 }
 
+impl DepositContext<'_> {
+    /// [`Deposit::deposit_signed`] with the venue's `data`, from
+    /// [`Self::try_from_deposit_data`]; data for another venue fails with
+    /// [`BeethovenError::InvalidExtraData`].
+    pub fn deposit_with_data_signed(
+        ctx: &Self,
+        amount: u64,
+        data: &DepositData,
+        signer_seeds: &[Signer],
+    ) -> ProgramResult {
+        let amount = resolve_amount(amount, ctx.user_source_account()?)?;
+
+        match (ctx, data) {
+            #[cfg(feature = "kamino-deposit")]
+            (DepositContext::Kamino(accounts), DepositData::Kamino(d)) => {
+                crate::kamino::Kamino::deposit_with_data_signed(accounts, amount, d, signer_seeds)
+            }
+
+            #[cfg(feature = "jupiter-deposit")]
+            (DepositContext::Jupiter(accounts), DepositData::Jupiter(d)) => {
+                crate::jupiter::JupiterEarn::deposit_with_data_signed(
+                    accounts,
+                    amount,
+                    d,
+                    signer_seeds,
+                )
+            }
+
+            #[allow(unreachable_patterns)]
+            _ => {
+                let _ = (amount, signer_seeds);
+                Err(BeethovenError::InvalidExtraData.into())
+            }
+        }
+    }
+
+    #[inline(always)]
+    pub fn deposit_with_data(ctx: &Self, amount: u64, data: &DepositData) -> ProgramResult {
+        Self::deposit_with_data_signed(ctx, amount, data, &[])
+    }
+}
+
+#[inline(always)]
 pub fn try_from_deposit_context<'info>(
     accounts: &'info [AccountView],
 ) -> Result<DepositContext<'info>, ProgramError> {
     let detector_account = accounts.first().ok_or(ProgramError::NotEnoughAccountKeys)?;
 
-    #[cfg(feature = "kamino-deposit")]
-    if address_eq(
-        detector_account.address(),
-        &crate::kamino::KAMINO_LEND_PROGRAM_ID,
-    ) {
-        let ctx = crate::kamino::KaminoDepositAccounts::try_from(accounts)?;
-        return Ok(DepositContext::Kamino(ctx));
+    match deposit_protocol_of(detector_account.address()) {
+        #[cfg(feature = "kamino-deposit")]
+        Some(protocol @ ProtocolKind::Kamino) => {
+            crate::kamino::KaminoDepositAccounts::try_from(accounts)
+                .map(DepositContext::Kamino)
+                .map_err(adapter_parse_error(protocol))
+        }
+
+        #[cfg(feature = "jupiter-deposit")]
+        Some(protocol @ ProtocolKind::Jupiter) => {
+            crate::jupiter::JupiterEarnDepositAccounts::try_from(accounts)
+                .map(DepositContext::Jupiter)
+                .map_err(adapter_parse_error(protocol))
+        }
+
+        _ => Err(BeethovenError::UnknownProtocol.into()),
     }
+}
+
+pub fn deposit_signed(
+    accounts: &[AccountView],
+    amount: u64,
+    signer_seeds: &[Signer],
+) -> ProgramResult {
+    let ctx = try_from_deposit_context(accounts)?;
+    DepositContext::deposit_signed(&ctx, amount, signer_seeds)
+}
+
+#[inline(always)]
+pub fn deposit(accounts: &[AccountView], amount: u64) -> ProgramResult {
+    deposit_signed(accounts, amount, &[])
+}
+
+/// [`deposit_signed`] of the whole balance of the depositor's token account.
+pub fn deposit_all_signed(accounts: &[AccountView], signer_seeds: &[Signer]) -> ProgramResult {
+    deposit_signed(accounts, FULL_BALANCE, signer_seeds)
+}
+
+#[inline(always)]
+pub fn deposit_all(accounts: &[AccountView]) -> ProgramResult {
+    deposit_all_signed(accounts, &[])
+}
+
+// Withdraw context, detected from the same lending program as the deposit
+use crate::Withdraw;
+
+pub enum WithdrawContext<'info> {
+    #[cfg(feature = "kamino-deposit")]
+    Kamino(crate::kamino::KaminoWithdrawAccounts<'info>),
 
     #[cfg(feature = "jupiter-deposit")]
-    if address_eq(
-        detector_account.address(),
-        &crate::jupiter::JUPITER_EARN_PROGRAM_ID,
-    ) {
-        let ctx = crate::jupiter::JupiterEarnDepositAccounts::try_from(accounts)?;
-        return Ok(DepositContext::Jupiter(ctx));
+    Jupiter(crate::jupiter::JupiterEarnWithdrawAccounts<'info>),
+
+    /// Keeps `'info` in use when no deposit protocol is enabled
+    #[cfg(not(any(feature = "kamino-deposit", feature = "jupiter-deposit")))]
+    #[doc(hidden)]
+    __Unused(core::marker::PhantomData<&'info ()>),
+}
+
+impl<'info> WithdrawContext<'info> {
+    /// The token account the withdrawn underlying is paid into.
+    pub(crate) fn user_destination_account(&self) -> Result<&'info AccountView, ProgramError> {
+        match self {
+            #[cfg(feature = "kamino-deposit")]
+            WithdrawContext::Kamino(accounts) => Ok(accounts.user_destination_liquidity),
+
+            #[cfg(feature = "jupiter-deposit")]
+            WithdrawContext::Jupiter(accounts) => Ok(accounts.recipient_token_account),
+
+            #[allow(unreachable_patterns)]
+            _ => Err(BeethovenError::ProtocolFeatureDisabled.into()),
+        }
     }
+}
+
+impl<'info> Withdraw<'info> for WithdrawContext<'info> {
+    type Accounts = Self;
 
-    Err(ProgramError::InvalidAccountData)
+    /// `amount` is in the venue's receipt token: Kamino collateral or Jupiter fTokens.
+    fn withdraw_signed(
+        ctx: &Self::Accounts,
+        amount: u64,
+        signer_seeds: &[Signer],
+    ) -> ProgramResult {
+        match ctx {
+            #[cfg(feature = "kamino-deposit")]
+            WithdrawContext::Kamino(accounts) => {
+                crate::kamino::Kamino::withdraw_signed(accounts, amount, signer_seeds)
+            }
+
+            #[cfg(feature = "jupiter-deposit")]
+            WithdrawContext::Jupiter(accounts) => {
+                crate::jupiter::JupiterEarn::withdraw_signed(accounts, amount, signer_seeds)
+            }
+
+            #[allow(unreachable_patterns)]
+            _ => {
+                let _ = (amount, signer_seeds);
+                Err(BeethovenError::ProtocolFeatureDisabled.into())
+            }
+        }
+    }
+
+    #[inline(always)]
+    fn withdraw(ctx: &Self::Accounts, amount: u64) -> ProgramResult {
+        Self::withdraw_signed(ctx, amount, &[])
+    }
+}
+
+#[inline(always)]
+pub fn try_from_withdraw_context<'info>(
+    accounts: &'info [AccountView],
+) -> Result<WithdrawContext<'info>, ProgramError> {
+    let detector_account = accounts.first().ok_or(ProgramError::NotEnoughAccountKeys)?;
+
+    match deposit_protocol_of(detector_account.address()) {
+        #[cfg(feature = "kamino-deposit")]
+        Some(protocol @ ProtocolKind::Kamino) => {
+            crate::kamino::KaminoWithdrawAccounts::try_from(accounts)
+                .map(WithdrawContext::Kamino)
+                .map_err(adapter_parse_error(protocol))
+        }
+
+        #[cfg(feature = "jupiter-deposit")]
+        Some(protocol @ ProtocolKind::Jupiter) => {
+            crate::jupiter::JupiterEarnWithdrawAccounts::try_from(accounts)
+                .map(WithdrawContext::Jupiter)
+                .map_err(adapter_parse_error(protocol))
+        }
+
+        _ => Err(BeethovenError::UnknownProtocol.into()),
+    }
+}
+
+pub fn withdraw_signed(
+    accounts: &[AccountView],
+    amount: u64,
+    signer_seeds: &[Signer],
+) -> ProgramResult {
+    let ctx = try_from_withdraw_context(accounts)?;
+    WithdrawContext::withdraw_signed(&ctx, amount, signer_seeds)
 }
+
+#[inline(always)]
+pub fn withdraw(accounts: &[AccountView], amount: u64) -> ProgramResult {
+    withdraw_signed(accounts, amount, &[])
+}
+
+// Generated from `crates/core/protocols.toml` by `build.rs`: `swap_protocol_of`,
+// `deposit_protocol_of` and `swap_context_protocol`
+include!(concat!(env!("OUT_DIR"), "/dispatch.rs"));
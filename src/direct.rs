@@ -0,0 +1,67 @@
+//! Per-protocol swap entry points for programs that statically know the venue.
+//!
+//! Each function parses the venue's accounts and calls its adapter directly, skipping
+//! protocol detection, `SwapContext` construction and the dispatch match.
+
+// Unused when no swap protocol is enabled
+#[allow(unused_imports)]
+use {
+    crate::Swap, solana_account_view::AccountView, solana_instruction_view::cpi::Signer,
+    solana_program_error::ProgramResult,
+};
+
+macro_rules! direct_swap {
+    ($(
+        $feature:literal, $name:ident, $protocol:ident =>
+            $module:ident::$adapter:ident($accounts:ident, $data:ty);
+    )*) => {
+        $(
+            #[cfg(feature = $feature)]
+            #[doc = concat!(
+                "Swap through `", stringify!($module), "` without protocol detection."
+            )]
+            #[inline]
+            pub fn $name(
+                accounts: &[AccountView],
+                in_amount: u64,
+                minimum_out_amount: u64,
+                data: &$data,
+                signer_seeds: &[Signer],
+            ) -> ProgramResult {
+                let ctx = crate::$module::$accounts::try_from(accounts).map_err(
+                    crate::context::adapter_parse_error(crate::ProtocolKind::$protocol),
+                )?;
+                crate::$module::$adapter::swap_signed(
+                    &ctx,
+                    in_amount,
+                    minimum_out_amount,
+                    data,
+                    signer_seeds,
+                )
+            }
+        )*
+    };
+}
+
+direct_swap! {
+    "perena-swap", swap_perena, Perena =>
+        perena::Perena(PerenaSwapAccounts, crate::perena::PerenaSwapData);
+    "solfi-swap", swap_solfi, SolFi =>
+        solfi::SolFi(SolFiSwapAccounts, crate::solfi::SolFiSwapData);
+    "solfi_v2-swap", swap_solfi_v2, SolFiV2 =>
+        solfi_v2::SolFiV2(SolFiV2SwapAccounts, crate::solfi_v2::SolFiV2SwapData);
+    "manifest-swap", swap_manifest, Manifest =>
+        manifest::Manifest(ManifestSwapAccounts, crate::manifest::ManifestSwapData);
+    "heaven-swap", swap_heaven, Heaven =>
+        heaven::Heaven(HeavenSwapAccounts, crate::heaven::HeavenSwapData<'_>);
+    "aldrin-swap", swap_aldrin, Aldrin =>
+        aldrin::Aldrin(AldrinSwapAccounts, crate::aldrin::AldrinSwapData);
+    "aldrin_v2-swap", swap_aldrin_v2, AldrinV2 =>
+        aldrin_v2::AldrinV2(AldrinV2SwapAccounts, crate::aldrin_v2::AldrinV2SwapData);
+    "futarchy-swap", swap_futarchy, Futarchy =>
+        futarchy::Futarchy(FutarchySwapAccounts, crate::futarchy::FutarchySwapData);
+    "gamma-swap", swap_gamma, Gamma =>
+        gamma::Gamma(GammaSwapAccounts, ());
+    "mock_dex-swap", swap_mock_dex, MockDex =>
+        mock_dex::MockDex(MockDexSwapAccounts, crate::mock_dex::MockDexSwapData);
+}
@@ -0,0 +1,268 @@
+//! Platform and referral fees: swap, then transfer a share of what the swap returned
+//! from the user's destination token account to the platform's and the referrer's.
+
+use {
+    crate::{
+        token::{token_account_mint, TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID},
+        try_from_swap_context_lazy, BeethovenError, LazySwapContext, SwapContext, SwapData,
+        SwapOutcome,
+    },
+    beethoven_core::cpi::invoke_signed,
+    solana_account_view::AccountView,
+    solana_address::address_eq,
+    solana_instruction_view::{cpi::Signer, InstructionAccount, InstructionView},
+    solana_program_error::{ProgramError, ProgramResult},
+};
+
+/// Denominator of the rates in [`SwapFees`]
+pub const FEE_BPS_DENOMINATOR: u64 = 10_000;
+
+/// Number of [`FeeAccounts`] ahead of the swap's routed accounts
+pub const FEE_ACCOUNT_COUNT: usize = 4;
+
+/// SPL Token `Transfer` discriminator, the same in Token-2022
+const TRANSFER_DISCRIMINATOR: u8 = 3;
+
+/// Fee rates on a swap's output, in basis points of what the swap returned.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SwapFees {
+    pub platform_bps: u16,
+    pub referral_bps: u16,
+}
+
+impl SwapFees {
+    /// The platform and referral fees on `out_amount`, each rounded down.
+    ///
+    /// Fails with [`BeethovenError::InvalidFee`] if the two rates add up to more than
+    /// the whole output.
+    pub fn amounts(&self, out_amount: u64) -> Result<(u64, u64), ProgramError> {
+        if self.platform_bps as u64 + self.referral_bps as u64 > FEE_BPS_DENOMINATOR {
+            return Err(BeethovenError::InvalidFee.into());
+        }
+        let fee =
+            |bps: u16| (out_amount as u128 * bps as u128 / FEE_BPS_DENOMINATOR as u128) as u64;
+        Ok((fee(self.platform_bps), fee(self.referral_bps)))
+    }
+}
+
+/// The accounts fees are paid with, in this order ahead of the swap's routed accounts.
+pub struct FeeAccounts<'info> {
+    /// Token program of the swap's output mint (Token or Token-2022)
+    pub token_program: &'info AccountView,
+    /// Owner of the swap's destination token account, signing the fee transfers
+    pub authority: &'info AccountView,
+    /// Token account of the output mint the platform fee is paid to
+    pub platform_fee_account: &'info AccountView,
+    /// Token account of the output mint the referral fee is paid to; any account when
+    /// the referral rate is zero
+    pub referral_fee_account: &'info AccountView,
+}
+
+impl<'info> TryFrom<&'info [AccountView]> for FeeAccounts<'info> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'info [AccountView]) -> Result<Self, Self::Error> {
+        let [token_program, authority, platform_fee_account, referral_fee_account, ..] = accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        // The fee transfers are CPIs into this program, so it must be a token program
+        if !address_eq(token_program.address(), &TOKEN_PROGRAM_ID)
+            && !address_eq(token_program.address(), &TOKEN_2022_PROGRAM_ID)
+        {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        Ok(Self {
+            token_program,
+            authority,
+            platform_fee_account,
+            referral_fee_account,
+        })
+    }
+}
+
+impl<'info> FeeAccounts<'info> {
+    /// Ensure the accounts `fees` are paid into hold the mint of `destination`, the
+    /// swap's destination account they are taken from. The referral account is only
+    /// read when the referral rate is non-zero.
+    fn check_mints(&self, fees: &SwapFees, destination: &AccountView) -> ProgramResult {
+        let mint = token_account_mint(destination)?;
+        if token_account_mint(self.platform_fee_account)? != mint
+            || (fees.referral_bps > 0 && token_account_mint(self.referral_fee_account)? != mint)
+        {
+            return Err(BeethovenError::FeeMintMismatch.into());
+        }
+        Ok(())
+    }
+
+    /// Transfer `amount` from `source` to `destination`, signed by the authority.
+    fn transfer(
+        &self,
+        source: &'info AccountView,
+        destination: &'info AccountView,
+        amount: u64,
+        signer_seeds: &[Signer],
+    ) -> ProgramResult {
+        let accounts = [
+            InstructionAccount::writable(source.address()),
+            InstructionAccount::writable(destination.address()),
+            InstructionAccount::readonly_signer(self.authority.address()),
+        ];
+        let mut data = [0u8; 9];
+        data[0] = TRANSFER_DISCRIMINATOR;
+        data[1..].copy_from_slice(&amount.to_le_bytes());
+
+        let instruction = InstructionView {
+            program_id: self.token_program.address(),
+            accounts: &accounts,
+            data: &data,
+        };
+        invoke_signed(
+            &instruction,
+            &[source, destination, self.authority],
+            signer_seeds,
+        )
+    }
+}
+
+/// [`FeeAccounts`] followed by a swap's routed accounts, detected together.
+pub struct FeeSwapContext<'info> {
+    pub fees: FeeAccounts<'info>,
+    pub swap: LazySwapContext<'info>,
+}
+
+impl<'info> FeeSwapContext<'info> {
+    /// [`LazySwapContext::try_from_swap_data`] for the swap.
+    pub fn try_from_swap_data<'a>(&self, data: &'a [u8]) -> Result<SwapData<'a>, ProgramError> {
+        self.swap.try_from_swap_data(data)
+    }
+
+    /// Swap `in_amount`, then pay `fees` out of what the swap returned, failing unless
+    /// the user keeps at least `minimum_out_amount` after them.
+    ///
+    /// Fails with [`BeethovenError::FeeMintMismatch`] before the swap if a fee account
+    /// does not hold the mint of the swap's destination account.
+    ///
+    /// The returned outcome's `out_received` is what the user kept.
+    pub fn swap_with_fees_signed(
+        ctx: &Self,
+        in_amount: u64,
+        minimum_out_amount: u64,
+        fees: &SwapFees,
+        data: &SwapData<'_>,
+        signer_seeds: &[Signer],
+    ) -> Result<SwapOutcome, ProgramError> {
+        // Reject bad rates before spending compute on the swap
+        fees.amounts(0)?;
+
+        let swap = ctx.swap.parse()?;
+        let destination = swap.user_destination_account(data)?;
+        ctx.fees.check_mints(fees, destination)?;
+        // The user keeps at most what the swap returned, so the venue can enforce the
+        // same minimum
+        let outcome = SwapContext::swap_signed_with_outcome(
+            &swap,
+            in_amount,
+            minimum_out_amount,
+            data,
+            signer_seeds,
+        )?;
+
+        let (platform_fee, referral_fee) = fees.amounts(outcome.out_received)?;
+        let kept = outcome.out_received - platform_fee - referral_fee;
+        if kept < minimum_out_amount {
+            return Err(BeethovenError::SlippageExceeded.into());
+        }
+
+        if platform_fee > 0 {
+            ctx.fees.transfer(
+                destination,
+                ctx.fees.platform_fee_account,
+                platform_fee,
+                signer_seeds,
+            )?;
+        }
+        if referral_fee > 0 {
+            ctx.fees.transfer(
+                destination,
+                ctx.fees.referral_fee_account,
+                referral_fee,
+                signer_seeds,
+            )?;
+        }
+
+        Ok(SwapOutcome {
+            in_spent: outcome.in_spent,
+            out_received: kept,
+        })
+    }
+
+    #[inline(always)]
+    pub fn swap_with_fees(
+        ctx: &Self,
+        in_amount: u64,
+        minimum_out_amount: u64,
+        fees: &SwapFees,
+        data: &SwapData<'_>,
+    ) -> Result<SwapOutcome, ProgramError> {
+        Self::swap_with_fees_signed(ctx, in_amount, minimum_out_amount, fees, data, &[])
+    }
+}
+
+/// Read the [`FeeAccounts`] from the first [`FEE_ACCOUNT_COUNT`] of `accounts` and
+/// detect the swap in the rest.
+pub fn try_from_fee_swap_context(
+    accounts: &[AccountView],
+) -> Result<FeeSwapContext<'_>, ProgramError> {
+    if accounts.len() < FEE_ACCOUNT_COUNT {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+    let (fee_accounts, swap_accounts) = accounts.split_at(FEE_ACCOUNT_COUNT);
+
+    Ok(FeeSwapContext {
+        fees: FeeAccounts::try_from(fee_accounts)?,
+        swap: try_from_swap_context_lazy(swap_accounts)?,
+    })
+}
+
+/// Swap `in_amount` with the accounts after the [`FeeAccounts`] and pay `fees` out of
+/// what it returned, `swap_data` being the swap venue's data.
+pub fn swap_with_fees_signed(
+    accounts: &[AccountView],
+    in_amount: u64,
+    minimum_out_amount: u64,
+    fees: &SwapFees,
+    swap_data: &[u8],
+    signer_seeds: &[Signer],
+) -> Result<SwapOutcome, ProgramError> {
+    let ctx = try_from_fee_swap_context(accounts)?;
+    let data = ctx.try_from_swap_data(swap_data)?;
+    FeeSwapContext::swap_with_fees_signed(
+        &ctx,
+        in_amount,
+        minimum_out_amount,
+        fees,
+        &data,
+        signer_seeds,
+    )
+}
+
+#[inline(always)]
+pub fn swap_with_fees(
+    accounts: &[AccountView],
+    in_amount: u64,
+    minimum_out_amount: u64,
+    fees: &SwapFees,
+    swap_data: &[u8],
+) -> Result<SwapOutcome, ProgramError> {
+    swap_with_fees_signed(
+        accounts,
+        in_amount,
+        minimum_out_amount,
+        fees,
+        swap_data,
+        &[],
+    )
+}
@@ -0,0 +1,40 @@
+//! Shared plumbing for the entry points taking classic `AccountInfo`s.
+//!
+//! An entrypoint-deserialized `AccountInfo`'s `key` points at the address inside the
+//! account's record in the runtime input buffer, the same record an [`AccountView`]
+//! wraps. Both `solana-program`'s and Anchor's `AccountInfo` are viewed through it.
+
+use {
+    alloc::vec::Vec,
+    solana_account_view::{AccountView, RuntimeAccount},
+    solana_instruction_view::cpi::{Seed, Signer},
+};
+
+/// Offset of the account address in a runtime account record
+const ADDRESS_OFFSET: usize = 8;
+
+/// View the runtime account record whose address `key` points to.
+///
+/// # Safety
+///
+/// `key` must be the `key` of an `AccountInfo` deserialized by the program entrypoint.
+#[inline(always)]
+pub(crate) unsafe fn account_view_at_key<T>(key: *const T) -> AccountView {
+    AccountView::new_unchecked((key as *mut u8).sub(ADDRESS_OFFSET) as *mut RuntimeAccount)
+}
+
+/// Convert `[&[&[u8]]]` signer seeds into the adapters' [`Seed`]s, one `Vec` per signer.
+pub(crate) fn seeds<'a>(signer_seeds: &[&[&'a [u8]]]) -> Vec<Vec<Seed<'a>>> {
+    signer_seeds
+        .iter()
+        .map(|seeds| seeds.iter().map(|seed| Seed::from(*seed)).collect())
+        .collect()
+}
+
+/// One [`Signer`] per set of [`seeds`].
+pub(crate) fn signers<'a, 'b>(seeds: &'b [Vec<Seed<'a>>]) -> Vec<Signer<'a, 'b>> {
+    seeds
+        .iter()
+        .map(|seeds| Signer::from(seeds.as_slice()))
+        .collect()
+}
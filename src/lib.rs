@@ -1,12 +1,27 @@
 #![no_std]
 
+#[cfg(any(feature = "anchor-interop", feature = "solana-program"))]
+extern crate alloc;
+
 // Re-export core traits
-pub use beethoven_core::{Deposit, Swap};
+pub use beethoven_core::{
+    envelope, scratch, token, validation, AddLiquidity, BeethovenError, Borrow, CancelOrder,
+    Capabilities, Deposit, Direction, LimitOrder, OrderType, PerpTrade, ProtocolKind, Quote,
+    RemoveLiquidity, Repay, Stake, Swap, SwapMode, SwapOutcome, Unstake, Withdraw,
+};
 #[cfg(feature = "jupiter-deposit")]
 pub use beethoven_deposit_jupiter as jupiter;
 // Re-export protocol crates under feature flags
 #[cfg(feature = "kamino-deposit")]
 pub use beethoven_deposit_kamino as kamino;
+#[cfg(feature = "raydium_cpmm-liquidity")]
+pub use beethoven_liquidity_raydium_cpmm as raydium_cpmm;
+#[cfg(feature = "drift-perp")]
+pub use beethoven_perp_drift as drift;
+#[cfg(feature = "marinade-stake")]
+pub use beethoven_stake_marinade as marinade;
+#[cfg(feature = "spl_stake_pool-stake")]
+pub use beethoven_stake_spl_stake_pool as spl_stake_pool;
 #[cfg(feature = "aldrin-swap")]
 pub use beethoven_swap_aldrin as aldrin;
 #[cfg(feature = "aldrin_v2-swap")]
@@ -19,6 +34,8 @@ pub use beethoven_swap_gamma as gamma;
 pub use beethoven_swap_heaven as heaven;
 #[cfg(feature = "manifest-swap")]
 pub use beethoven_swap_manifest as manifest;
+#[cfg(feature = "mock_dex-swap")]
+pub use beethoven_swap_mock_dex as mock_dex;
 #[cfg(feature = "perena-swap")]
 pub use beethoven_swap_perena as perena;
 #[cfg(feature = "solfi-swap")]
@@ -29,3 +46,29 @@ pub use beethoven_swap_solfi_v2 as solfi_v2;
 // Context enums and convenience functions
 mod context;
 pub use context::*;
+
+// Direct per-protocol entry points
+mod direct;
+pub use direct::*;
+
+// Swap then deposit, or withdraw then swap, in one call
+mod zap;
+pub use zap::*;
+
+// Platform and referral fees on a swap's output
+mod fee;
+pub use fee::*;
+
+// Venues registered by downstream crates
+mod registry;
+pub use registry::*;
+
+// Single-import re-exports for integrating programs
+pub mod prelude;
+
+#[cfg(feature = "solana-program")]
+pub mod account_info;
+#[cfg(feature = "anchor-interop")]
+pub mod anchor;
+#[cfg(any(feature = "anchor-interop", feature = "solana-program"))]
+mod interop;
@@ -0,0 +1,24 @@
+//! The traits, contexts, entry points and error type a program routing through
+//! Beethoven needs, behind one import:
+//!
+//! ```ignore
+//! use beethoven::prelude::*;
+//! ```
+//!
+//! Per-protocol account and data types stay under their protocol's module.
+
+pub use crate::{
+    deposit, deposit_all, deposit_all_signed, deposit_signed, detect_swap_protocol,
+    detect_swap_protocol_by, direct::*, scratch::Scratch, swap, swap_all, swap_all_signed,
+    swap_exact_out, swap_exact_out_signed, swap_signed, swap_with_fees, swap_with_fees_signed,
+    token::FULL_BALANCE, try_from_deposit_context, try_from_deposit_context_with,
+    try_from_fee_swap_context, try_from_swap_context, try_from_swap_context_by,
+    try_from_swap_context_lazy, try_from_swap_context_with, try_from_unzap_context,
+    try_from_withdraw_context, try_from_zap_context, unzap, unzap_signed, withdraw,
+    withdraw_signed, zap, zap_signed, AddLiquidity, BeethovenError, Borrow, CancelOrder,
+    Capabilities, Deposit, DepositContext, DepositData, Detection, Direction, FeeSwapContext,
+    LazySwapContext, LimitOrder, OrderType, PerpTrade, ProtocolEntry, ProtocolKind, Quote,
+    RegistryDepositContext, RegistrySwapContext, RemoveLiquidity, Repay, SharedSwapRoute, Stake,
+    Swap, SwapContext, SwapData, SwapFees, SwapMode, SwapOutcome, SwapRoute, Unstake, UnzapContext,
+    Withdraw, WithdrawContext, ZapContext, MAX_ROUTE_LEG_ACCOUNTS, SWAP_SCRATCH_LEN,
+};
@@ -0,0 +1,179 @@
+//! Venues registered at runtime by downstream crates, dispatched next to the built-in
+//! adapters without forking Beethoven.
+
+use {
+    crate::{
+        try_from_deposit_context, try_from_swap_context_lazy, BeethovenError, Deposit,
+        DepositContext, LazySwapContext, Swap,
+    },
+    solana_account_view::AccountView,
+    solana_address::{address_eq, Address},
+    solana_instruction_view::cpi::Signer,
+    solana_program_error::{ProgramError, ProgramResult},
+};
+
+/// Swaps `in_amount` on a registered venue: its routed accounts (program first), the
+/// minimum output, its raw swap data and the signer seeds.
+pub type SwapHandler = fn(&[AccountView], u64, u64, &[u8], &[Signer]) -> ProgramResult;
+
+/// Deposits `amount` into a registered venue: its routed accounts (program first), the
+/// amount and the signer seeds.
+pub type DepositHandler = fn(&[AccountView], u64, &[Signer]) -> ProgramResult;
+
+/// A venue Beethoven has no adapter for, detected like the built-in ones by its
+/// program being the first account.
+///
+/// Handlers are plain functions, so a table of entries can be a `const`:
+///
+/// ```ignore
+/// const REGISTRY: &[ProtocolEntry] = &[ProtocolEntry::swap(
+///     MY_DEX_PROGRAM_ID,
+///     |accounts, in_amount, min_out, data, signer_seeds| {
+///         let ctx = MyDexAccounts::try_from(accounts)?;
+///         let data = MyDexData::try_from(data)?;
+///         MyDex::swap_signed(&ctx, in_amount, min_out, &data, signer_seeds)
+///     },
+/// )];
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct ProtocolEntry {
+    pub program_id: Address,
+    pub swap: Option<SwapHandler>,
+    pub deposit: Option<DepositHandler>,
+}
+
+impl ProtocolEntry {
+    /// An entry that only swaps.
+    pub const fn swap(program_id: Address, handler: SwapHandler) -> Self {
+        Self {
+            program_id,
+            swap: Some(handler),
+            deposit: None,
+        }
+    }
+
+    /// An entry that only deposits.
+    pub const fn deposit(program_id: Address, handler: DepositHandler) -> Self {
+        Self {
+            program_id,
+            swap: None,
+            deposit: Some(handler),
+        }
+    }
+}
+
+/// The entry of `registry` for the program of `accounts`' detector account, if any.
+fn registered<'r>(
+    accounts: &[AccountView],
+    registry: &'r [ProtocolEntry],
+    has_handler: impl Fn(&ProtocolEntry) -> bool,
+) -> Result<&'r ProtocolEntry, ProgramError> {
+    let detector_account = accounts.first().ok_or(ProgramError::NotEnoughAccountKeys)?;
+    registry
+        .iter()
+        .find(|entry| {
+            has_handler(entry) && address_eq(&entry.program_id, detector_account.address())
+        })
+        .ok_or(BeethovenError::UnknownProtocol.into())
+}
+
+/// A swap on a built-in adapter or on a venue from a [`ProtocolEntry`] table.
+pub enum RegistrySwapContext<'info, 'r> {
+    Builtin(LazySwapContext<'info>),
+    Registered {
+        entry: &'r ProtocolEntry,
+        accounts: &'info [AccountView],
+    },
+}
+
+impl RegistrySwapContext<'_, '_> {
+    /// Swap with the venue's raw swap data, parsed by the built-in adapter or left to
+    /// the registered handler.
+    pub fn swap_signed(
+        ctx: &Self,
+        in_amount: u64,
+        minimum_out_amount: u64,
+        data: &[u8],
+        signer_seeds: &[Signer],
+    ) -> ProgramResult {
+        match ctx {
+            RegistrySwapContext::Builtin(ctx) => {
+                let data = ctx.try_from_swap_data(data)?;
+                LazySwapContext::swap_signed(
+                    ctx,
+                    in_amount,
+                    minimum_out_amount,
+                    &data,
+                    signer_seeds,
+                )
+            }
+            RegistrySwapContext::Registered { entry, accounts } => {
+                let handler = entry.swap.ok_or(BeethovenError::UnknownProtocol)?;
+                handler(accounts, in_amount, minimum_out_amount, data, signer_seeds)
+            }
+        }
+    }
+
+    #[inline(always)]
+    pub fn swap(ctx: &Self, in_amount: u64, minimum_out_amount: u64, data: &[u8]) -> ProgramResult {
+        Self::swap_signed(ctx, in_amount, minimum_out_amount, data, &[])
+    }
+}
+
+/// [`try_from_swap_context_lazy`], falling back to the swap entries of `registry` for
+/// programs no enabled adapter detects.
+pub fn try_from_swap_context_with<'info, 'r>(
+    accounts: &'info [AccountView],
+    registry: &'r [ProtocolEntry],
+) -> Result<RegistrySwapContext<'info, 'r>, ProgramError> {
+    match try_from_swap_context_lazy(accounts) {
+        Err(error) if error == ProgramError::from(BeethovenError::UnknownProtocol) => {
+            let entry = registered(accounts, registry, |entry| entry.swap.is_some())?;
+            Ok(RegistrySwapContext::Registered { entry, accounts })
+        }
+        ctx => ctx.map(RegistrySwapContext::Builtin),
+    }
+}
+
+/// A deposit into a built-in adapter or into a venue from a [`ProtocolEntry`] table.
+pub enum RegistryDepositContext<'info, 'r> {
+    Builtin(DepositContext<'info>),
+    Registered {
+        entry: &'r ProtocolEntry,
+        accounts: &'info [AccountView],
+    },
+}
+
+impl RegistryDepositContext<'_, '_> {
+    pub fn deposit_signed(ctx: &Self, amount: u64, signer_seeds: &[Signer]) -> ProgramResult {
+        match ctx {
+            RegistryDepositContext::Builtin(ctx) => {
+                DepositContext::deposit_signed(ctx, amount, signer_seeds)
+            }
+            RegistryDepositContext::Registered { entry, accounts } => {
+                let handler = entry.deposit.ok_or(BeethovenError::UnknownProtocol)?;
+                handler(accounts, amount, signer_seeds)
+            }
+        }
+    }
+
+    #[inline(always)]
+    pub fn deposit(ctx: &Self, amount: u64) -> ProgramResult {
+        Self::deposit_signed(ctx, amount, &[])
+    }
+}
+
+/// [`try_from_deposit_context`], falling back to the deposit entries of `registry` for
+/// programs no enabled adapter detects.
+pub fn try_from_deposit_context_with<'info, 'r>(
+    accounts: &'info [AccountView],
+    registry: &'r [ProtocolEntry],
+) -> Result<RegistryDepositContext<'info, 'r>, ProgramError> {
+    match try_from_deposit_context(accounts) {
+        Err(error) if error == ProgramError::from(BeethovenError::UnknownProtocol) => {
+            let entry = registered(accounts, registry, |entry| entry.deposit.is_some())?;
+            Ok(RegistryDepositContext::Registered { entry, accounts })
+        }
+        ctx => ctx.map(RegistryDepositContext::Builtin),
+    }
+}
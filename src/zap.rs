@@ -0,0 +1,221 @@
+//! Zap: swap into a venue's deposit token, then deposit exactly what the swap returned.
+//! Unzap: the reverse, withdrawing and then swapping exactly what the withdraw paid out.
+
+use {
+    crate::{
+        token::token_account_amount, try_from_deposit_context, try_from_swap_context_lazy,
+        try_from_withdraw_context, BeethovenError, Deposit, DepositContext, LazySwapContext, Swap,
+        SwapContext, SwapData, Withdraw, WithdrawContext,
+    },
+    solana_account_view::AccountView,
+    solana_address::address_eq,
+    solana_instruction_view::cpi::Signer,
+    solana_program_error::{ProgramError, ProgramResult},
+};
+
+/// A swap's routed accounts followed by a deposit's, detected together.
+///
+/// The swap must credit the token account the deposit spends from; the deposit is sized
+/// from what the swap actually delivered, so its amount never has to be quoted up front.
+pub struct ZapContext<'info> {
+    pub swap: LazySwapContext<'info>,
+    pub deposit: DepositContext<'info>,
+}
+
+impl<'info> ZapContext<'info> {
+    /// [`LazySwapContext::try_from_swap_data`] for the swap leg.
+    pub fn try_from_swap_data<'a>(&self, data: &'a [u8]) -> Result<SwapData<'a>, ProgramError> {
+        self.swap.try_from_swap_data(data)
+    }
+
+    /// Swap `in_amount` and deposit everything it returned, failing unless that is at
+    /// least `minimum_out_amount`.
+    pub fn zap_signed(
+        ctx: &Self,
+        in_amount: u64,
+        minimum_out_amount: u64,
+        data: &SwapData<'_>,
+        signer_seeds: &[Signer],
+    ) -> ProgramResult {
+        let swap = ctx.swap.parse()?;
+        if !address_eq(
+            swap.user_destination_account(data)?.address(),
+            ctx.deposit.user_source_account()?.address(),
+        ) {
+            return Err(BeethovenError::RouteLegMismatch.into());
+        }
+
+        let outcome = SwapContext::swap_signed_with_outcome(
+            &swap,
+            in_amount,
+            minimum_out_amount,
+            data,
+            signer_seeds,
+        )?;
+        DepositContext::deposit_signed(&ctx.deposit, outcome.out_received, signer_seeds)
+    }
+
+    #[inline(always)]
+    pub fn zap(
+        ctx: &Self,
+        in_amount: u64,
+        minimum_out_amount: u64,
+        data: &SwapData<'_>,
+    ) -> ProgramResult {
+        Self::zap_signed(ctx, in_amount, minimum_out_amount, data, &[])
+    }
+}
+
+/// Detect the swap in the first `swap_account_count` of `accounts` and the deposit in
+/// the rest, each from its own detector account.
+pub fn try_from_zap_context(
+    accounts: &[AccountView],
+    swap_account_count: usize,
+) -> Result<ZapContext<'_>, ProgramError> {
+    if accounts.len() < swap_account_count {
+        return Err(BeethovenError::AccountCountMismatch.into());
+    }
+    let (swap_accounts, deposit_accounts) = accounts.split_at(swap_account_count);
+
+    Ok(ZapContext {
+        swap: try_from_swap_context_lazy(swap_accounts)?,
+        deposit: try_from_deposit_context(deposit_accounts)?,
+    })
+}
+
+/// Swap `in_amount` with the first `swap_account_count` accounts and deposit what it
+/// returned with the rest, `swap_data` being the swap venue's data.
+pub fn zap_signed(
+    accounts: &[AccountView],
+    swap_account_count: usize,
+    in_amount: u64,
+    minimum_out_amount: u64,
+    swap_data: &[u8],
+    signer_seeds: &[Signer],
+) -> ProgramResult {
+    let ctx = try_from_zap_context(accounts, swap_account_count)?;
+    let data = ctx.try_from_swap_data(swap_data)?;
+    ZapContext::zap_signed(&ctx, in_amount, minimum_out_amount, &data, signer_seeds)
+}
+
+#[inline(always)]
+pub fn zap(
+    accounts: &[AccountView],
+    swap_account_count: usize,
+    in_amount: u64,
+    minimum_out_amount: u64,
+    swap_data: &[u8],
+) -> ProgramResult {
+    zap_signed(
+        accounts,
+        swap_account_count,
+        in_amount,
+        minimum_out_amount,
+        swap_data,
+        &[],
+    )
+}
+
+/// A withdraw's routed accounts followed by a swap's, detected together.
+///
+/// The swap must spend from the token account the withdraw pays into; it is sized from
+/// what the withdraw actually paid out, so the underlying a receipt amount redeems for
+/// never has to be computed up front.
+pub struct UnzapContext<'info> {
+    pub withdraw: WithdrawContext<'info>,
+    pub swap: LazySwapContext<'info>,
+}
+
+impl<'info> UnzapContext<'info> {
+    /// [`LazySwapContext::try_from_swap_data`] for the swap leg.
+    pub fn try_from_swap_data<'a>(&self, data: &'a [u8]) -> Result<SwapData<'a>, ProgramError> {
+        self.swap.try_from_swap_data(data)
+    }
+
+    /// Withdraw `amount` of the venue's receipt token and swap all the underlying it
+    /// paid out, failing unless the swap returns at least `minimum_out_amount`.
+    pub fn unzap_signed(
+        ctx: &Self,
+        amount: u64,
+        minimum_out_amount: u64,
+        data: &SwapData<'_>,
+        signer_seeds: &[Signer],
+    ) -> ProgramResult {
+        let swap = ctx.swap.parse()?;
+        let withdraw_output = ctx.withdraw.user_destination_account()?;
+        if !address_eq(
+            swap.user_source_account(data)?.address(),
+            withdraw_output.address(),
+        ) {
+            return Err(BeethovenError::RouteLegMismatch.into());
+        }
+
+        let balance_before = token_account_amount(withdraw_output)?;
+        WithdrawContext::withdraw_signed(&ctx.withdraw, amount, signer_seeds)?;
+        let withdrawn = token_account_amount(withdraw_output)?
+            .checked_sub(balance_before)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+
+        SwapContext::swap_signed(&swap, withdrawn, minimum_out_amount, data, signer_seeds)
+    }
+
+    #[inline(always)]
+    pub fn unzap(
+        ctx: &Self,
+        amount: u64,
+        minimum_out_amount: u64,
+        data: &SwapData<'_>,
+    ) -> ProgramResult {
+        Self::unzap_signed(ctx, amount, minimum_out_amount, data, &[])
+    }
+}
+
+/// Detect the withdraw in the first `withdraw_account_count` of `accounts` and the swap
+/// in the rest, each from its own detector account.
+pub fn try_from_unzap_context(
+    accounts: &[AccountView],
+    withdraw_account_count: usize,
+) -> Result<UnzapContext<'_>, ProgramError> {
+    if accounts.len() < withdraw_account_count {
+        return Err(BeethovenError::AccountCountMismatch.into());
+    }
+    let (withdraw_accounts, swap_accounts) = accounts.split_at(withdraw_account_count);
+
+    Ok(UnzapContext {
+        withdraw: try_from_withdraw_context(withdraw_accounts)?,
+        swap: try_from_swap_context_lazy(swap_accounts)?,
+    })
+}
+
+/// Withdraw `amount` with the first `withdraw_account_count` accounts and swap what it
+/// paid out with the rest, `swap_data` being the swap venue's data.
+pub fn unzap_signed(
+    accounts: &[AccountView],
+    withdraw_account_count: usize,
+    amount: u64,
+    minimum_out_amount: u64,
+    swap_data: &[u8],
+    signer_seeds: &[Signer],
+) -> ProgramResult {
+    let ctx = try_from_unzap_context(accounts, withdraw_account_count)?;
+    let data = ctx.try_from_swap_data(swap_data)?;
+    UnzapContext::unzap_signed(&ctx, amount, minimum_out_amount, &data, signer_seeds)
+}
+
+#[inline(always)]
+pub fn unzap(
+    accounts: &[AccountView],
+    withdraw_account_count: usize,
+    amount: u64,
+    minimum_out_amount: u64,
+    swap_data: &[u8],
+) -> ProgramResult {
+    unzap_signed(
+        accounts,
+        withdraw_account_count,
+        amount,
+        minimum_out_amount,
+        swap_data,
+        &[],
+    )
+}
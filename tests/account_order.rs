@@ -0,0 +1,226 @@
+//! Snapshot of every adapter's account order, rendered from the client's account specs.
+//!
+//! `tests/account_order.md` is checked in, so a change to the accounts an adapter
+//! takes, their order or their flags shows up as a reviewed diff of that file rather
+//! than a silent break for integrators building account lists by hand. Set
+//! `UPDATE_ACCOUNT_ORDER=1` to rewrite it after an intended change.
+
+use {
+    beethoven::ProtocolKind,
+    beethoven_client::{
+        aldrin, aldrin_v2, drift, futarchy, gamma, heaven, jupiter, kamino, manifest, marinade,
+        mock_dex, perena, raydium_cpmm, solfi, solfi_v2, spl_stake_pool, swap_accounts,
+        AdapterKeys,
+    },
+    solana_address::Address,
+    std::fmt::Write,
+};
+
+const SNAPSHOT_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/account_order.md");
+
+/// `K`'s accounts as a table, the detector first as `try_from_*_context` reads them.
+fn render<K: AdapterKeys>(out: &mut String, venue: &str) {
+    writeln!(out, "\n## {venue}\n").unwrap();
+    writeln!(out, "| # | account | writable | signer |").unwrap();
+    writeln!(out, "|---|---------|----------|--------|").unwrap();
+    writeln!(out, "| 0 | program (detector) |  |  |").unwrap();
+    let flag = |set: bool| if set { "x" } else { "" };
+    for (index, spec) in K::ACCOUNTS.iter().enumerate() {
+        writeln!(
+            out,
+            "| {} | {} | {} | {} |",
+            index + 1,
+            spec.name,
+            flag(spec.writable),
+            flag(spec.signer)
+        )
+        .unwrap();
+    }
+}
+
+#[test]
+fn test_account_order_matches_snapshot() {
+    let mut rendered = String::from(
+        "# Adapter account order\n\n\
+         Generated by `tests/account_order.rs` from the client's account specs; \
+         rerun with `UPDATE_ACCOUNT_ORDER=1` after an intended change.\n",
+    );
+    render::<kamino::KaminoDepositKeys>(&mut rendered, "kamino (deposit)");
+    render::<kamino::KaminoWithdrawKeys>(&mut rendered, "kamino (withdraw)");
+    render::<kamino::KaminoBorrowKeys>(&mut rendered, "kamino (borrow)");
+    render::<kamino::KaminoRepayKeys>(&mut rendered, "kamino (repay)");
+    render::<jupiter::JupiterEarnDepositKeys>(&mut rendered, "jupiter (deposit)");
+    render::<jupiter::JupiterEarnWithdrawKeys>(&mut rendered, "jupiter (withdraw)");
+    render::<perena::PerenaSwapKeys>(&mut rendered, "perena (swap)");
+    render::<solfi::SolFiSwapKeys>(&mut rendered, "solfi (swap)");
+    render::<solfi_v2::SolFiV2SwapKeys>(&mut rendered, "solfi_v2 (swap)");
+    render::<manifest::ManifestSwapKeys>(&mut rendered, "manifest (swap)");
+    render::<manifest::ManifestOrderKeys>(&mut rendered, "manifest (order)");
+    render::<heaven::HeavenSwapKeys>(&mut rendered, "heaven (swap)");
+    render::<aldrin::AldrinSwapKeys>(&mut rendered, "aldrin (swap)");
+    render::<aldrin_v2::AldrinV2SwapKeys>(&mut rendered, "aldrin_v2 (swap)");
+    render::<futarchy::FutarchySwapKeys>(&mut rendered, "futarchy (swap)");
+    render::<gamma::GammaSwapKeys>(&mut rendered, "gamma (swap)");
+    render::<mock_dex::MockDexSwapKeys>(&mut rendered, "mock_dex (swap)");
+    render::<raydium_cpmm::RaydiumCpmmAddLiquidityKeys>(
+        &mut rendered,
+        "raydium_cpmm (add liquidity)",
+    );
+    render::<raydium_cpmm::RaydiumCpmmRemoveLiquidityKeys>(
+        &mut rendered,
+        "raydium_cpmm (remove liquidity)",
+    );
+    render::<marinade::MarinadeStakeKeys>(&mut rendered, "marinade (stake)");
+    render::<marinade::MarinadeUnstakeKeys>(&mut rendered, "marinade (unstake)");
+    render::<spl_stake_pool::SplStakePoolUnstakeKeys>(&mut rendered, "spl_stake_pool (unstake)");
+    render::<drift::DriftPerpKeys>(&mut rendered, "drift (perp order)");
+
+    if std::env::var("UPDATE_ACCOUNT_ORDER").is_ok() {
+        std::fs::write(SNAPSHOT_PATH, &rendered).expect("Failed to write account order snapshot");
+        println!("Recorded account order snapshot: {}", SNAPSHOT_PATH);
+        return;
+    }
+
+    let snapshot = std::fs::read_to_string(SNAPSHOT_PATH)
+        .unwrap_or_else(|_| panic!("Failed to read snapshot: {}", SNAPSHOT_PATH));
+    assert!(
+        rendered == snapshot,
+        "adapter account order changed; review the diff and rerun with UPDATE_ACCOUNT_ORDER=1\n\
+         --- snapshot\n{snapshot}\n+++ rendered\n{rendered}"
+    );
+}
+
+/// `labels` (an adapter's `ACCOUNT_LABELS`) names `K`'s accounts in the client's order.
+fn assert_labels<K: AdapterKeys>(labels: &[&str], venue: &str) {
+    let names: Vec<_> = K::ACCOUNTS.iter().map(|spec| spec.name).collect();
+    assert_eq!(labels, names.as_slice(), "{venue}");
+}
+
+#[test]
+fn test_account_labels_match_client_specs() {
+    assert_labels::<kamino::KaminoDepositKeys>(beethoven::kamino::ACCOUNT_LABELS, "kamino");
+    // The reserve accounts after these are whatever the obligation holds
+    assert_labels::<kamino::KaminoWithdrawKeys>(
+        beethoven::kamino::WITHDRAW_ACCOUNT_LABELS,
+        "kamino (withdraw)",
+    );
+    assert_labels::<kamino::KaminoBorrowKeys>(
+        beethoven::kamino::BORROW_ACCOUNT_LABELS,
+        "kamino (borrow)",
+    );
+    assert_labels::<kamino::KaminoRepayKeys>(
+        beethoven::kamino::REPAY_ACCOUNT_LABELS,
+        "kamino (repay)",
+    );
+    assert_labels::<jupiter::JupiterEarnDepositKeys>(beethoven::jupiter::ACCOUNT_LABELS, "jupiter");
+    assert_labels::<jupiter::JupiterEarnWithdrawKeys>(
+        beethoven::jupiter::WITHDRAW_ACCOUNT_LABELS,
+        "jupiter (withdraw)",
+    );
+    assert_labels::<perena::PerenaSwapKeys>(beethoven::perena::ACCOUNT_LABELS, "perena");
+    assert_labels::<solfi::SolFiSwapKeys>(beethoven::solfi::ACCOUNT_LABELS, "solfi");
+    assert_labels::<solfi_v2::SolFiV2SwapKeys>(beethoven::solfi_v2::ACCOUNT_LABELS, "solfi_v2");
+    assert_labels::<manifest::ManifestSwapKeys>(beethoven::manifest::ACCOUNT_LABELS, "manifest");
+    assert_labels::<manifest::ManifestOrderKeys>(
+        beethoven::manifest::ORDER_ACCOUNT_LABELS,
+        "manifest (order)",
+    );
+    assert_labels::<heaven::HeavenSwapKeys>(beethoven::heaven::ACCOUNT_LABELS, "heaven");
+    assert_labels::<aldrin::AldrinSwapKeys>(beethoven::aldrin::ACCOUNT_LABELS, "aldrin");
+    assert_labels::<aldrin_v2::AldrinV2SwapKeys>(beethoven::aldrin_v2::ACCOUNT_LABELS, "aldrin_v2");
+    assert_labels::<futarchy::FutarchySwapKeys>(beethoven::futarchy::ACCOUNT_LABELS, "futarchy");
+    assert_labels::<gamma::GammaSwapKeys>(beethoven::gamma::ACCOUNT_LABELS, "gamma");
+    assert_labels::<mock_dex::MockDexSwapKeys>(beethoven::mock_dex::ACCOUNT_LABELS, "mock_dex");
+    assert_labels::<raydium_cpmm::RaydiumCpmmAddLiquidityKeys>(
+        beethoven::raydium_cpmm::ADD_LIQUIDITY_ACCOUNT_LABELS,
+        "raydium_cpmm",
+    );
+    assert_labels::<raydium_cpmm::RaydiumCpmmRemoveLiquidityKeys>(
+        beethoven::raydium_cpmm::REMOVE_LIQUIDITY_ACCOUNT_LABELS,
+        "raydium_cpmm (remove liquidity)",
+    );
+    assert_labels::<marinade::MarinadeStakeKeys>(
+        beethoven::marinade::STAKE_ACCOUNT_LABELS,
+        "marinade",
+    );
+    // The ticket accounts after these are only passed for a delayed unstake
+    assert_labels::<marinade::MarinadeUnstakeKeys>(
+        &beethoven::marinade::UNSTAKE_ACCOUNT_LABELS[..10],
+        "marinade (unstake)",
+    );
+    assert_labels::<spl_stake_pool::SplStakePoolUnstakeKeys>(
+        beethoven::spl_stake_pool::UNSTAKE_ACCOUNT_LABELS,
+        "spl_stake_pool",
+    );
+    // The market accounts after these vary with the user's positions
+    assert_labels::<drift::DriftPerpKeys>(beethoven::drift::ACCOUNT_LABELS, "drift");
+}
+
+#[test]
+fn test_swap_accounts_macro_follows_adapter_order() {
+    // Fields given out of order, each address standing for its field's position
+    let [user, pool, pool_authority, base_vault, quote_vault, user_base_token, user_quote_token, token_program] =
+        core::array::from_fn(|index| Address::new_from_array([index as u8 + 1; 32]));
+    let metas = swap_accounts!(mock_dex, {
+        token_program,
+        quote_vault,
+        user_quote_token,
+        pool,
+        base_vault,
+        user_base_token,
+        pool_authority,
+        user,
+    });
+
+    assert_eq!(metas[0].pubkey, mock_dex::MockDexSwapKeys::PROGRAM_ID);
+    for (index, meta) in metas[1..].iter().enumerate() {
+        assert_eq!(
+            meta.pubkey.as_ref()[0],
+            index as u8 + 1,
+            "{}",
+            mock_dex::MockDexSwapKeys::ACCOUNTS[index].name
+        );
+    }
+}
+
+/// `K`'s accounts include a sysvar exactly when `protocol` says it requires the
+/// instructions sysvar.
+fn assert_sysvar_capability<K: AdapterKeys>(protocol: ProtocolKind) {
+    let has_sysvar = K::ACCOUNTS.iter().any(|spec| spec.name.contains("sysvar"));
+    assert_eq!(
+        has_sysvar,
+        protocol.capabilities().requires_instructions_sysvar(),
+        "{protocol:?}"
+    );
+}
+
+#[test]
+fn test_instructions_sysvar_capability_matches_accounts() {
+    assert_sysvar_capability::<kamino::KaminoDepositKeys>(ProtocolKind::Kamino);
+    assert_sysvar_capability::<jupiter::JupiterEarnDepositKeys>(ProtocolKind::Jupiter);
+    assert_sysvar_capability::<perena::PerenaSwapKeys>(ProtocolKind::Perena);
+    assert_sysvar_capability::<solfi::SolFiSwapKeys>(ProtocolKind::SolFi);
+    assert_sysvar_capability::<solfi_v2::SolFiV2SwapKeys>(ProtocolKind::SolFiV2);
+    assert_sysvar_capability::<manifest::ManifestSwapKeys>(ProtocolKind::Manifest);
+    assert_sysvar_capability::<manifest::ManifestOrderKeys>(ProtocolKind::Manifest);
+    assert_sysvar_capability::<heaven::HeavenSwapKeys>(ProtocolKind::Heaven);
+    assert_sysvar_capability::<aldrin::AldrinSwapKeys>(ProtocolKind::Aldrin);
+    assert_sysvar_capability::<aldrin_v2::AldrinV2SwapKeys>(ProtocolKind::AldrinV2);
+    assert_sysvar_capability::<futarchy::FutarchySwapKeys>(ProtocolKind::Futarchy);
+    assert_sysvar_capability::<gamma::GammaSwapKeys>(ProtocolKind::Gamma);
+    assert_sysvar_capability::<mock_dex::MockDexSwapKeys>(ProtocolKind::MockDex);
+    assert_sysvar_capability::<raydium_cpmm::RaydiumCpmmAddLiquidityKeys>(
+        ProtocolKind::RaydiumCpmm,
+    );
+    assert_sysvar_capability::<raydium_cpmm::RaydiumCpmmRemoveLiquidityKeys>(
+        ProtocolKind::RaydiumCpmm,
+    );
+    assert_sysvar_capability::<marinade::MarinadeStakeKeys>(ProtocolKind::Marinade);
+    assert_sysvar_capability::<marinade::MarinadeUnstakeKeys>(ProtocolKind::Marinade);
+    assert_sysvar_capability::<spl_stake_pool::SplStakePoolUnstakeKeys>(ProtocolKind::SplStakePool);
+    assert_sysvar_capability::<drift::DriftPerpKeys>(ProtocolKind::Drift);
+    assert_eq!(
+        beethoven::kamino::CAPABILITIES,
+        ProtocolKind::Kamino.capabilities()
+    );
+}
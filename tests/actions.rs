@@ -0,0 +1,344 @@
+//! Borrow, repay, stake, unstake, liquidity, order and perp adapters
+//! dispatched through the test program, checked against the venue instruction the
+//! client builds for a direct call.
+//!
+//! As in `withdraw.rs`, the venue is replaced by `program-cpi-echo` in LiteSVM, which
+//! records the last CPI, for Kamino the one after its refreshes.
+
+use {
+    crate::helper::*,
+    beethoven::{
+        drift::DRIFT_PROGRAM_ID,
+        kamino::KAMINO_LEND_PROGRAM_ID,
+        manifest::{ManifestOrderData, MANIFEST_PROGRAM_ID},
+        marinade::{MarinadeUnstakeData, MARINADE_PROGRAM_ID},
+        raydium_cpmm::RAYDIUM_CPMM_PROGRAM_ID,
+        spl_stake_pool::SPL_STAKE_POOL_PROGRAM_ID,
+        BeethovenError, Direction, OrderType,
+    },
+    beethoven_client::{
+        drift::{self, DriftPerpKeys},
+        kamino::{self, KaminoBorrowKeys, KaminoRepayKeys},
+        manifest::{self, ManifestOrderKeys},
+        marinade::{self, MarinadeStakeKeys, MarinadeTicketKeys, MarinadeUnstakeKeys},
+        raydium_cpmm::{self, RaydiumCpmmAddLiquidityKeys, RaydiumCpmmRemoveLiquidityKeys},
+        spl_stake_pool::{self, SplStakePoolUnstakeKeys},
+        test_program,
+    },
+    solana_address::Address,
+    solana_instruction::{AccountMeta, Instruction},
+    solana_keypair::Keypair,
+    solana_signer::Signer,
+};
+
+const AMOUNT: u64 = 1_000_000;
+
+#[test]
+fn test_kamino_borrow_cpi_matches_client() {
+    let owner = Keypair::new();
+    let [obligation, lending_market, lending_market_authority, borrow_reserve, borrow_reserve_liquidity_mint, reserve_source_liquidity, borrow_reserve_liquidity_fee_receiver, user_destination_liquidity, referrer_token_state, token_program, instruction_sysvar_account, obligation_farm_user_state, reserve_farm_state, farms_program, scope_oracle] =
+        addresses();
+    let keys = KaminoBorrowKeys {
+        owner: owner.pubkey(),
+        obligation,
+        lending_market,
+        lending_market_authority,
+        borrow_reserve,
+        borrow_reserve_liquidity_mint,
+        reserve_source_liquidity,
+        borrow_reserve_liquidity_fee_receiver,
+        user_destination_liquidity,
+        referrer_token_state,
+        token_program,
+        instruction_sysvar_account,
+        obligation_farm_user_state,
+        reserve_farm_state,
+        farms_program,
+        scope_oracle,
+        reserve_accounts: vec![borrow_reserve],
+    };
+
+    let instruction = test_program::borrow_instruction(TEST_PROGRAM_ID, &keys, AMOUNT);
+    assert_eq!(
+        echo_cpi_svm(instruction, KAMINO_LEND_PROGRAM_ID, &owner),
+        kamino::borrow_instruction(&keys, AMOUNT)
+    );
+}
+
+#[test]
+fn test_kamino_repay_cpi_matches_client() {
+    let owner = Keypair::new();
+    let [obligation, lending_market, repay_reserve, reserve_liquidity_mint, reserve_destination_liquidity, user_source_liquidity, token_program, instruction_sysvar_account, obligation_farm_user_state, reserve_farm_state, lending_market_authority, farms_program, scope_oracle] =
+        addresses();
+    let keys = KaminoRepayKeys {
+        owner: owner.pubkey(),
+        obligation,
+        lending_market,
+        repay_reserve,
+        reserve_liquidity_mint,
+        reserve_destination_liquidity,
+        user_source_liquidity,
+        token_program,
+        instruction_sysvar_account,
+        obligation_farm_user_state,
+        reserve_farm_state,
+        lending_market_authority,
+        farms_program,
+        scope_oracle,
+        reserve_accounts: vec![repay_reserve],
+    };
+
+    let instruction = test_program::repay_instruction(TEST_PROGRAM_ID, &keys, AMOUNT);
+    assert_eq!(
+        echo_cpi_svm(instruction, KAMINO_LEND_PROGRAM_ID, &owner),
+        kamino::repay_instruction(&keys, AMOUNT)
+    );
+}
+
+#[test]
+fn test_marinade_stake_cpi_matches_client() {
+    let staker = Keypair::new();
+    let [state, msol_mint, liq_pool_sol_leg_pda, liq_pool_msol_leg, liq_pool_msol_leg_authority, reserve_pda, mint_to, msol_mint_authority, system_program, token_program] =
+        addresses();
+    let keys = MarinadeStakeKeys {
+        state,
+        msol_mint,
+        liq_pool_sol_leg_pda,
+        liq_pool_msol_leg,
+        liq_pool_msol_leg_authority,
+        reserve_pda,
+        transfer_from: staker.pubkey(),
+        mint_to,
+        msol_mint_authority,
+        system_program,
+        token_program,
+    };
+
+    let instruction = test_program::stake_instruction(TEST_PROGRAM_ID, &keys, AMOUNT);
+    assert_eq!(
+        echo_cpi_svm(instruction, MARINADE_PROGRAM_ID, &staker),
+        marinade::stake_instruction(&keys, AMOUNT)
+    );
+}
+
+/// Marinade unstake keys with `authority` owning the mSOL, and a ticket when `ticket`.
+fn marinade_unstake_keys(authority: Address, ticket: bool) -> MarinadeUnstakeKeys {
+    let [state, msol_mint, liq_pool_sol_leg_pda, liq_pool_msol_leg, treasury_msol_account, get_msol_from, transfer_sol_to, system_program, token_program, new_ticket_account, clock, rent] =
+        addresses();
+    MarinadeUnstakeKeys {
+        state,
+        msol_mint,
+        liq_pool_sol_leg_pda,
+        liq_pool_msol_leg,
+        treasury_msol_account,
+        get_msol_from,
+        get_msol_from_authority: authority,
+        transfer_sol_to,
+        system_program,
+        token_program,
+        ticket: ticket.then_some(MarinadeTicketKeys {
+            new_ticket_account,
+            clock,
+            rent,
+        }),
+    }
+}
+
+#[test]
+fn test_marinade_unstake_cpis_match_client() {
+    let authority = Keypair::new();
+
+    let keys = marinade_unstake_keys(authority.pubkey(), false);
+    let instant = test_program::unstake_instruction(
+        TEST_PROGRAM_ID,
+        &keys,
+        AMOUNT,
+        &[MarinadeUnstakeData::Instant as u8],
+    );
+    assert_eq!(
+        echo_cpi_svm(instant, MARINADE_PROGRAM_ID, &authority),
+        marinade::liquid_unstake_instruction(&keys, AMOUNT)
+    );
+
+    let keys = marinade_unstake_keys(authority.pubkey(), true);
+    let delayed = test_program::unstake_instruction(
+        TEST_PROGRAM_ID,
+        &keys,
+        AMOUNT,
+        &[MarinadeUnstakeData::Delayed as u8],
+    );
+    assert_eq!(
+        echo_cpi_svm(delayed, MARINADE_PROGRAM_ID, &authority),
+        marinade::order_unstake_instruction(&keys, AMOUNT).unwrap()
+    );
+}
+
+#[test]
+fn test_spl_stake_pool_unstake_cpi_matches_client() {
+    let authority = Keypair::new();
+    let [stake_pool, withdraw_authority, pool_tokens_from, reserve_stake, lamports_to, manager_fee_account, pool_mint, clock, stake_history, stake_program, token_program] =
+        addresses();
+    let keys = SplStakePoolUnstakeKeys {
+        stake_pool,
+        withdraw_authority,
+        user_transfer_authority: authority.pubkey(),
+        pool_tokens_from,
+        reserve_stake,
+        lamports_to,
+        manager_fee_account,
+        pool_mint,
+        clock,
+        stake_history,
+        stake_program,
+        token_program,
+    };
+
+    let instruction = test_program::unstake_instruction(TEST_PROGRAM_ID, &keys, AMOUNT, &[]);
+    assert_eq!(
+        echo_cpi_svm(instruction, SPL_STAKE_POOL_PROGRAM_ID, &authority),
+        spl_stake_pool::unstake_instruction(&keys, AMOUNT)
+    );
+}
+
+#[test]
+fn test_raydium_cpmm_liquidity_cpis_match_client() {
+    let owner = Keypair::new();
+    let [authority, pool_state, owner_lp_token, token_0_account, token_1_account, token_0_vault, token_1_vault, token_program, token_program_2022, vault_0_mint, vault_1_mint, lp_mint, memo_program] =
+        addresses();
+    let add_keys = RaydiumCpmmAddLiquidityKeys {
+        owner: owner.pubkey(),
+        authority,
+        pool_state,
+        owner_lp_token,
+        token_0_account,
+        token_1_account,
+        token_0_vault,
+        token_1_vault,
+        token_program,
+        token_program_2022,
+        vault_0_mint,
+        vault_1_mint,
+        lp_mint,
+    };
+    let remove_keys = RaydiumCpmmRemoveLiquidityKeys {
+        owner: owner.pubkey(),
+        authority,
+        pool_state,
+        owner_lp_token,
+        token_0_account,
+        token_1_account,
+        token_0_vault,
+        token_1_vault,
+        token_program,
+        token_program_2022,
+        vault_0_mint,
+        vault_1_mint,
+        lp_mint,
+        memo_program,
+    };
+
+    let add = test_program::add_liquidity_instruction(TEST_PROGRAM_ID, &add_keys, 3, 4, 5);
+    assert_eq!(
+        echo_cpi_svm(add, RAYDIUM_CPMM_PROGRAM_ID, &owner),
+        raydium_cpmm::add_liquidity_instruction(&add_keys, 3, 4, 5)
+    );
+
+    let remove = test_program::remove_liquidity_instruction(TEST_PROGRAM_ID, &remove_keys, 5, 3, 4);
+    assert_eq!(
+        echo_cpi_svm(remove, RAYDIUM_CPMM_PROGRAM_ID, &owner),
+        raydium_cpmm::remove_liquidity_instruction(&remove_keys, 5, 3, 4)
+    );
+}
+
+#[test]
+fn test_manifest_order_cpis_match_client() {
+    let payer = Keypair::new();
+    let [market, system_program] = addresses();
+    let keys = ManifestOrderKeys {
+        payer: payer.pubkey(),
+        market,
+        system_program,
+    };
+    let order = ManifestOrderData::new(15, -3, 1_000);
+
+    for (side, order_type) in [
+        (Direction::Buy, OrderType::Limit),
+        (Direction::Sell, OrderType::PostOnly),
+        (Direction::Buy, OrderType::ImmediateOrCancel),
+    ] {
+        let place = test_program::place_order_instruction(
+            TEST_PROGRAM_ID,
+            &keys,
+            side,
+            AMOUNT,
+            order_type,
+            &order,
+        );
+        assert_eq!(
+            echo_cpi_svm(place, MANIFEST_PROGRAM_ID, &payer),
+            manifest::place_order_instruction(&keys, side, AMOUNT, order_type, &order)
+        );
+    }
+
+    let cancel = test_program::cancel_order_instruction(TEST_PROGRAM_ID, &keys, 42);
+    assert_eq!(
+        echo_cpi_svm(cancel, MANIFEST_PROGRAM_ID, &payer),
+        manifest::cancel_order_instruction(&keys, 42)
+    );
+}
+
+#[test]
+fn test_drift_perp_order_cpi_matches_client() {
+    let authority = Keypair::new();
+    let [state, user, oracle, perp_market] = addresses();
+    let keys = DriftPerpKeys {
+        state,
+        user,
+        authority: authority.pubkey(),
+        market_accounts: vec![
+            AccountMeta::new_readonly(oracle, false),
+            AccountMeta::new(perp_market, false),
+        ],
+    };
+
+    for direction in [Direction::Buy, Direction::Sell] {
+        let instruction = test_program::perp_order_instruction(
+            TEST_PROGRAM_ID,
+            &keys,
+            7,
+            direction,
+            AMOUNT,
+            u64::MAX,
+        );
+        assert_eq!(
+            echo_cpi_svm(instruction, DRIFT_PROGRAM_ID, &authority),
+            drift::place_perp_order_instruction(&keys, 7, direction, AMOUNT, u64::MAX)
+        );
+    }
+}
+
+#[test]
+fn test_action_rejects_another_venue() {
+    let payer = Keypair::new();
+    let mut svm = setup_svm_with_program(&load_fixture_bytes(&test_program_path()));
+    svm.airdrop(&payer.pubkey(), 1_000_000_000).unwrap();
+
+    // Borrow accounts led by the Drift program instead of Kamino's
+    let mut accounts = routed_metas::<KaminoBorrowKeys>(&payer.pubkey());
+    accounts[0] = AccountMeta::new_readonly(DRIFT_PROGRAM_ID, false);
+    let mut data = vec![discriminator::BORROW];
+    data.extend_from_slice(&AMOUNT.to_le_bytes());
+
+    assert_beethoven_error(
+        send_transaction(
+            &mut svm,
+            &payer,
+            Instruction {
+                program_id: TEST_PROGRAM_ID,
+                accounts,
+                data,
+            },
+        ),
+        BeethovenError::UnknownProtocol,
+    );
+}
@@ -0,0 +1,1058 @@
+//! Off-chain builders in `beethoven-client` against the on-chain account order and the
+//! instruction layouts the integration tests build by hand.
+
+use {
+    crate::helper::*,
+    beethoven_client::{
+        kamino::KaminoDepositKeys,
+        manifest::ManifestSwapKeys,
+        resolve::{associated_token_address, FetchedAccount},
+        test_program, AdapterKeys,
+    },
+    solana_address::Address,
+    solana_instruction::AccountMeta,
+    std::{collections::HashMap, str::FromStr},
+};
+
+fn key(seed: u8) -> Address {
+    Address::new_from_array([seed; 32])
+}
+
+#[test]
+fn test_client_manifest_routed_swap_matches_manual_accounts() {
+    let keys = ManifestSwapKeys {
+        payer: key(2),
+        owner: key(2),
+        market: key(3),
+        system_program: SYSTEM_PROGRAM_ID,
+        trader_base: key(4),
+        trader_quote: key(5),
+        base_vault: key(6),
+        quote_vault: key(7),
+        token_program_base: TOKEN_PROGRAM_ID,
+        base_mint: key(8),
+        token_program_quote: TOKEN_PROGRAM_ID,
+        quote_mint: key(9),
+        global: key(10),
+        global_vault: key(11),
+    };
+
+    // Same list `test_manifest_swap_cpi` passes
+    let accounts = vec![
+        AccountMeta::new_readonly(MANIFEST_PROGRAM_ID, false),
+        AccountMeta::new(key(2), true),
+        AccountMeta::new_readonly(key(2), true),
+        AccountMeta::new(key(3), false),
+        AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        AccountMeta::new(key(4), false),
+        AccountMeta::new(key(5), false),
+        AccountMeta::new(key(6), false),
+        AccountMeta::new(key(7), false),
+        AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+        AccountMeta::new_readonly(key(8), false),
+        AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+        AccountMeta::new_readonly(key(9), false),
+        AccountMeta::new(key(10), false),
+        AccountMeta::new(key(11), false),
+    ];
+    let expected = build_swap_instruction(accounts, 100, 1, &[1, 1]);
+
+    let instruction = test_program::swap_instruction(
+        TEST_PROGRAM_ID,
+        &keys,
+        100,
+        1,
+        &beethoven::manifest::ManifestSwapData {
+            is_base_in: true,
+            is_exact_in: true,
+        },
+    );
+    assert_eq!(instruction, expected);
+}
+
+#[test]
+fn test_client_kamino_deposit_accounts() {
+    let keys = KaminoDepositKeys {
+        owner: key(1),
+        obligation: key(2),
+        lending_market: key(3),
+        lending_market_authority: key(4),
+        reserve: key(5),
+        reserve_liquidity_mint: key(6),
+        reserve_liquidity_supply: key(7),
+        reserve_collateral_mint: key(8),
+        reserve_destination_deposit_collateral: key(9),
+        user_source_liquidity: key(10),
+        placeholder_user_destination_collateral: key(11),
+        collateral_token_program: TOKEN_PROGRAM_ID,
+        liquidity_token_program: TOKEN_PROGRAM_ID,
+        instruction_sysvar_account: key(12),
+        obligation_farm_user_state: key(13),
+        reserve_farm_state: key(14),
+        farms_program: key(15),
+        scope_oracle: key(16),
+        reserve_accounts: vec![key(17), key(18)],
+    };
+
+    // Detector, 18 fixed accounts, then the obligation reserves, writable
+    let routed = keys.routed_account_metas();
+    assert_eq!(routed.len(), 1 + 18 + 2);
+    assert_eq!(
+        routed[0],
+        AccountMeta::new_readonly(beethoven::kamino::KAMINO_LEND_PROGRAM_ID, false)
+    );
+    assert_eq!(routed[1], AccountMeta::new(key(1), true));
+    assert_eq!(
+        routed[19..],
+        [
+            AccountMeta::new(key(17), false),
+            AccountMeta::new(key(18), false)
+        ]
+    );
+
+    // The direct deposit drops the oracle and reserves the adapter only uses to refresh
+    let direct = beethoven_client::kamino::deposit_instruction(&keys, 5_000);
+    assert_eq!(direct.accounts, routed[1..18]);
+    assert_eq!(
+        direct.data,
+        beethoven::kamino::deposit_instruction_data(5_000)
+    );
+}
+
+#[test]
+fn test_client_kamino_flashloan_wraps_instructions() {
+    use beethoven_client::kamino::{flashloan_instructions, KaminoFlashloanKeys};
+
+    let keys = KaminoFlashloanKeys {
+        user_transfer_authority: key(1),
+        lending_market_authority: key(2),
+        lending_market: key(3),
+        reserve: key(4),
+        reserve_liquidity_mint: key(5),
+        reserve_liquidity_supply: key(6),
+        user_liquidity: key(7),
+        reserve_liquidity_fee_receiver: key(8),
+        referrer_token_state: beethoven::kamino::KAMINO_LEND_PROGRAM_ID,
+        referrer_account: beethoven::kamino::KAMINO_LEND_PROGRAM_ID,
+        instruction_sysvar_account: key(9),
+        token_program: TOKEN_PROGRAM_ID,
+    };
+    let inner = solana_instruction::Instruction {
+        program_id: TEST_PROGRAM_ID,
+        accounts: vec![AccountMeta::new(key(7), false)],
+        data: vec![1],
+    };
+
+    // Behind a compute budget instruction, so the borrow lands at index 1
+    let instructions = flashloan_instructions(&keys, 5_000, 1, [inner.clone()]);
+    let [borrow, wrapped, repay] = &instructions[..] else {
+        panic!("expected a borrow, the wrapped instruction and a repay");
+    };
+    assert_eq!(wrapped, &inner);
+    for instruction in [borrow, repay] {
+        assert_eq!(
+            instruction.program_id,
+            beethoven::kamino::KAMINO_LEND_PROGRAM_ID
+        );
+        assert_eq!(instruction.accounts, keys.account_metas());
+    }
+    // Only the user signs, for both
+    assert_eq!(
+        borrow
+            .accounts
+            .iter()
+            .filter(|meta| meta.is_signer)
+            .map(|meta| meta.pubkey)
+            .collect::<Vec<_>>(),
+        vec![key(1)]
+    );
+    assert_eq!(
+        borrow.data,
+        beethoven::kamino::flash_borrow_instruction_data(5_000)
+    );
+    assert_eq!(
+        repay.data,
+        beethoven::kamino::flash_repay_instruction_data(5_000, 1)
+    );
+}
+
+#[test]
+fn test_client_resolve_kamino_deposit_keys() {
+    use {
+        beethoven::kamino::KAMINO_LEND_PROGRAM_ID,
+        beethoven_client::kamino::{
+            lending_market_authority_address, obligation_address,
+            obligation_farm_user_state_address, resolve_deposit_keys, FARMS_PROGRAM_ID,
+            INSTRUCTIONS_SYSVAR_ID,
+        },
+    };
+
+    let (owner, lending_market, reserve, farm) = (key(1), key(2), key(3), key(4));
+    let (mint, liquidity_supply, collateral_mint, collateral_supply, scope) =
+        (key(5), key(6), key(7), key(8), key(9));
+
+    let mut reserve_data = vec![0u8; 8624];
+    for (offset, address) in [
+        (32, lending_market),
+        (64, farm),
+        (128, mint),
+        (160, liquidity_supply),
+        (408, TOKEN_PROGRAM_ID),
+        (2560, collateral_mint),
+        (2600, collateral_supply),
+        (5113, scope),
+    ] {
+        reserve_data[offset..offset + 32].copy_from_slice(address.as_ref());
+    }
+    let mut source = HashMap::from([(
+        reserve,
+        FetchedAccount {
+            owner: KAMINO_LEND_PROGRAM_ID,
+            data: reserve_data,
+        },
+    )]);
+
+    // Without an obligation there is nothing to refresh besides the reserve itself
+    let keys = resolve_deposit_keys(&source, &owner, &lending_market, &reserve).unwrap();
+    let obligation = obligation_address(&owner, &lending_market);
+    assert_eq!(
+        keys,
+        KaminoDepositKeys {
+            owner,
+            obligation,
+            lending_market,
+            lending_market_authority: lending_market_authority_address(&lending_market),
+            reserve,
+            reserve_liquidity_mint: mint,
+            reserve_liquidity_supply: liquidity_supply,
+            reserve_collateral_mint: collateral_mint,
+            reserve_destination_deposit_collateral: collateral_supply,
+            user_source_liquidity: associated_token_address(&owner, &mint, &TOKEN_PROGRAM_ID),
+            placeholder_user_destination_collateral: KAMINO_LEND_PROGRAM_ID,
+            collateral_token_program: TOKEN_PROGRAM_ID,
+            liquidity_token_program: TOKEN_PROGRAM_ID,
+            instruction_sysvar_account: INSTRUCTIONS_SYSVAR_ID,
+            obligation_farm_user_state: obligation_farm_user_state_address(&farm, &obligation),
+            reserve_farm_state: farm,
+            farms_program: FARMS_PROGRAM_ID,
+            scope_oracle: scope,
+            reserve_accounts: vec![],
+        }
+    );
+    assert_eq!(keys.routed_account_metas().len(), 19);
+
+    // An existing obligation's deposit and borrow reserves are refreshed too
+    let mut obligation_data = vec![0u8; 3344];
+    for (offset, address) in [(96, key(10)), (96 + 136, key(11)), (1208, key(12))] {
+        obligation_data[offset..offset + 32].copy_from_slice(address.as_ref());
+    }
+    source.insert(
+        obligation,
+        FetchedAccount {
+            owner: KAMINO_LEND_PROGRAM_ID,
+            data: obligation_data,
+        },
+    );
+    let keys = resolve_deposit_keys(&source, &owner, &lending_market, &reserve).unwrap();
+    assert_eq!(keys.reserve_accounts, vec![key(10), key(11), key(12)]);
+    assert_eq!(keys.routed_account_metas().len(), 19 + 3);
+
+    // The reserve must belong to the market
+    assert!(matches!(
+        resolve_deposit_keys(&source, &owner, &key(20), &reserve),
+        Err(beethoven_client::resolve::ResolveError::InvalidAccountData(
+            _
+        ))
+    ));
+}
+
+#[test]
+fn test_client_direct_swap_data_matches_adapter() {
+    let data = beethoven::solfi::SolFiSwapData {
+        is_quote_to_base: true,
+    };
+    let keys = beethoven_client::solfi::SolFiSwapKeys {
+        token_transfer_authority: key(1),
+        market_account: key(2),
+        base_vault: key(3),
+        quote_vault: key(4),
+        user_base_ata: key(5),
+        user_quote_ata: key(6),
+        token_program: TOKEN_PROGRAM_ID,
+        instructions_sysvar: key(7),
+    };
+
+    let instruction = beethoven_client::solfi::swap_instruction(&keys, 1_000, 42, &data);
+    assert_eq!(instruction.program_id, beethoven::solfi::SOLFI_PROGRAM_ID);
+    assert_eq!(instruction.accounts, keys.account_metas());
+
+    let mut expected = vec![7];
+    expected.extend_from_slice(&1_000u64.to_le_bytes());
+    expected.extend_from_slice(&42u64.to_le_bytes());
+    expected.push(1);
+    assert_eq!(instruction.data, expected);
+}
+
+/// The SOL/USDC Manifest market fixture and its mints, as a resolver source.
+fn manifest_market_source() -> HashMap<Address, FetchedAccount> {
+    let dir = env!("CARGO_MANIFEST_DIR");
+    [
+        "fixtures/swap/manifest/manifest_usdc_sol_market.json",
+        "fixtures/common/wsol_mint.json",
+        "fixtures/common/usdc_mint.json",
+    ]
+    .into_iter()
+    .map(|path| {
+        let (address, account) = load_json_fixture(&format!("{dir}/{path}"));
+        (
+            address,
+            FetchedAccount {
+                owner: account.owner,
+                data: account.data,
+            },
+        )
+    })
+    .collect()
+}
+
+#[test]
+fn test_client_resolve_manifest_swap_keys_from_fixtures() {
+    let source = manifest_market_source();
+
+    let address = |s: &str| Address::from_str(s).unwrap();
+    let market = address("ENhU8LsaR7vDD2G1CsWcsuSGNrih9Cv5WZEk7q9kPapQ");
+    let wsol = address("So11111111111111111111111111111111111111112");
+    let usdc = address("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v");
+    let owner = key(2);
+
+    let keys =
+        beethoven_client::manifest::resolve_swap_keys(&source, &market, &owner, true).unwrap();
+    assert_eq!(
+        keys,
+        ManifestSwapKeys {
+            payer: owner,
+            owner,
+            market,
+            system_program: SYSTEM_PROGRAM_ID,
+            trader_base: associated_token_address(&owner, &wsol, &TOKEN_PROGRAM_ID),
+            trader_quote: associated_token_address(&owner, &usdc, &TOKEN_PROGRAM_ID),
+            base_vault: address("AKjfJDv4ywdpCDrj7AURuNkGA3696GTVFgrMwk4TjkKs"),
+            quote_vault: address("FN9K6rTdWtRDUPmLTN2FnGvLZpHVNRN2MeRghKknSGDs"),
+            token_program_base: TOKEN_PROGRAM_ID,
+            base_mint: wsol,
+            token_program_quote: TOKEN_PROGRAM_ID,
+            quote_mint: usdc,
+            global: address("7mR36vj6pvg1U1cRatvUbLG57yqsd1ojLbrgxb6azaQ1"),
+            global_vault: address("E1mBVQyt7BHK8SaBSfME7usYxx94T4DtHEjbUpEBhZx"),
+        }
+    );
+
+    // A market the source doesn't hold
+    assert!(matches!(
+        beethoven_client::manifest::resolve_swap_keys(&source, &key(9), &owner, true),
+        Err(beethoven_client::resolve::ResolveError::AccountNotFound(missing)) if missing == key(9)
+    ));
+}
+
+/// A Gamma pool (`key(1)`) trading `key(5)` against `key(6)` with a 0.25% fee, and the
+/// accounts quoting reads, with `vault_0_amount` / `vault_1_amount` in the vaults.
+fn gamma_pool_source(vault_0_amount: u64, vault_1_amount: u64) -> HashMap<Address, FetchedAccount> {
+    use beethoven::gamma::{
+        state::{AMM_CONFIG_DISCRIMINATOR, POOL_STATE_DISCRIMINATOR},
+        GAMMA_PROGRAM_ID,
+    };
+
+    let (pool_state, amm_config, vault_0, vault_1, mint_0, mint_1) =
+        (key(1), key(2), key(3), key(4), key(5), key(6));
+
+    let mut pool = vec![0u8; 8 + 340];
+    pool[..8].copy_from_slice(&POOL_STATE_DISCRIMINATOR);
+    for (offset, address) in [
+        (0, amm_config),
+        (64, vault_0),
+        (96, vault_1),
+        (128, mint_0),
+        (160, mint_1),
+    ] {
+        pool[8 + offset..8 + offset + 32].copy_from_slice(address.as_ref());
+    }
+    // 10 protocol + 5 fund fees accrued in token 0
+    pool[8 + 300..8 + 308].copy_from_slice(&10u64.to_le_bytes());
+    pool[8 + 316..8 + 324].copy_from_slice(&5u64.to_le_bytes());
+
+    // 0.25% trade fee
+    let mut config = vec![0u8; 8 + 28];
+    config[..8].copy_from_slice(&AMM_CONFIG_DISCRIMINATOR);
+    config[8 + 4..8 + 12].copy_from_slice(&2_500u64.to_le_bytes());
+
+    HashMap::from([
+        (
+            pool_state,
+            FetchedAccount {
+                owner: GAMMA_PROGRAM_ID,
+                data: pool,
+            },
+        ),
+        (
+            amm_config,
+            FetchedAccount {
+                owner: GAMMA_PROGRAM_ID,
+                data: config,
+            },
+        ),
+        (vault_0, token_account(vault_0_amount)),
+        (vault_1, token_account(vault_1_amount)),
+    ])
+}
+
+fn token_account(amount: u64) -> FetchedAccount {
+    let mut data = vec![0u8; 165];
+    data[64..72].copy_from_slice(&amount.to_le_bytes());
+    FetchedAccount {
+        owner: TOKEN_PROGRAM_ID,
+        data,
+    }
+}
+
+#[test]
+fn test_client_gamma_quote_excludes_accrued_fees() {
+    let (pool_state, mint_0) = (key(1), key(5));
+    let source = gamma_pool_source(1_000_000_015, 2_000_000_000);
+
+    let quote =
+        beethoven_client::gamma::quote_swap(&source, &pool_state, &mint_0, 1_000_000).unwrap();
+    assert_eq!(
+        quote,
+        beethoven_client::quote::SwapQuote {
+            in_amount: 1_000_000,
+            out_amount: 1_993_011,
+            fee_amount: 2_500,
+            price_impact_bps: 9,
+        }
+    );
+
+    assert!(matches!(
+        beethoven_client::gamma::quote_swap(&source, &pool_state, &key(9), 1_000_000),
+        Err(beethoven_client::resolve::ResolveError::MintNotInPool(_))
+    ));
+}
+
+#[test]
+fn test_client_quote_cache_serves_quotes_and_applies_newer_updates() {
+    use beethoven_client::{cache::QuoteCache, gamma::quote_swap};
+
+    let (pool_state, mint_0, vault_1) = (key(1), key(5), key(4));
+    let source = gamma_pool_source(1_000_000_015, 2_000_000_000);
+
+    let cache = QuoteCache::new();
+    cache.watch(&source, &[pool_state]).unwrap();
+    let mut watched = cache.addresses();
+    watched.sort();
+    assert_eq!(watched, vec![key(1), key(2), key(3), key(4)]);
+    assert_eq!(
+        quote_swap(&cache, &pool_state, &mint_0, 1_000_000).unwrap(),
+        quote_swap(&source, &pool_state, &mint_0, 1_000_000).unwrap()
+    );
+
+    // Half the output reserve as of slot 10 halves the output
+    assert!(cache.apply(vault_1, Some(token_account(1_000_000_000)), 10));
+    let out_amount = quote_swap(&cache, &pool_state, &mint_0, 1_000_000)
+        .unwrap()
+        .out_amount;
+    assert_eq!(out_amount, 1_993_011 / 2);
+
+    // Older updates and accounts outside the watched pools are ignored
+    assert!(!cache.apply(vault_1, Some(token_account(2_000_000_000)), 9));
+    assert!(!cache.apply(key(9), Some(token_account(1)), 11));
+    assert_eq!(cache.slot(&vault_1), Some(10));
+    assert_eq!(cache.slot(&key(9)), None);
+    assert_eq!(
+        quote_swap(&cache, &pool_state, &mint_0, 1_000_000)
+            .unwrap()
+            .out_amount,
+        out_amount
+    );
+}
+
+#[test]
+fn test_client_planner_splits_across_pools_and_encodes_route() {
+    use {
+        beethoven::{mock_dex::MOCK_DEX_PROGRAM_ID, Capabilities, ProtocolKind},
+        beethoven_client::{
+            gamma,
+            mock_dex::{self, pool_account_data},
+            planner::{plan_route, PlanOptions},
+        },
+    };
+
+    let (gamma_pool, mint_0, mint_1, user) = (key(1), key(5), key(6), key(7));
+    let (mock_pool, other_pool) = (key(20), key(30));
+    let mut source = gamma_pool_source(1_000_000_015, 2_000_000_000);
+    source.insert(
+        mock_pool,
+        FetchedAccount {
+            owner: MOCK_DEX_PROGRAM_ID,
+            data: pool_account_data(&mock_pool, &mint_0, &mint_1, &key(21), &key(22), 30),
+        },
+    );
+    source.insert(key(21), token_account(1_000_000_000));
+    source.insert(key(22), token_account(2_000_000_000));
+    // Trades another pair, so it is not a candidate
+    source.insert(
+        other_pool,
+        FetchedAccount {
+            owner: MOCK_DEX_PROGRAM_ID,
+            data: pool_account_data(&other_pool, &mint_0, &key(9), &key(31), &key(32), 30),
+        },
+    );
+
+    let in_amount = 100_000_000;
+    let pools = [gamma_pool, mock_pool, other_pool];
+    let gamma_out = gamma::quote_swap(&source, &gamma_pool, &mint_0, in_amount)
+        .unwrap()
+        .out_amount;
+    let mock_out = mock_dex::quote_swap(&source, &mock_pool, &mint_0, in_amount)
+        .unwrap()
+        .out_amount;
+
+    // Single pool: the better of the two quotes
+    let single = PlanOptions {
+        max_legs: 1,
+        ..PlanOptions::default()
+    };
+    let plan = plan_route(&source, &user, &mint_0, &mint_1, in_amount, &pools, &single).unwrap();
+    assert_eq!(plan.legs.len(), 1);
+    assert_eq!(plan.out_amount, gamma_out.max(mock_out));
+
+    // Splitting across two similar pools beats either alone
+    let plan = plan_route(
+        &source,
+        &user,
+        &mint_0,
+        &mint_1,
+        in_amount,
+        &pools,
+        &PlanOptions::default(),
+    )
+    .unwrap();
+    assert_eq!(
+        plan.legs.iter().map(|leg| leg.protocol).collect::<Vec<_>>(),
+        vec![ProtocolKind::Gamma, ProtocolKind::MockDex]
+    );
+    assert_eq!(
+        plan.legs.iter().map(|leg| leg.in_amount).sum::<u64>(),
+        in_amount
+    );
+    assert_eq!(
+        plan.legs.iter().map(|leg| leg.out_amount).sum::<u64>(),
+        plan.out_amount
+    );
+    assert!(plan.out_amount > gamma_out.max(mock_out));
+    for leg in &plan.legs {
+        assert_eq!(leg.minimum_out_amount, leg.out_amount * 9_950 / 10_000);
+    }
+    assert_eq!(plan.legs[1].data, vec![0]);
+
+    let encoded = plan.encode();
+    let (gamma_leg, mock_leg) = (&plan.legs[0], &plan.legs[1]);
+    let mut expected = vec![2, ProtocolKind::Gamma as u8, 14];
+    expected.extend_from_slice(&gamma_leg.in_amount.to_le_bytes());
+    expected.extend_from_slice(&gamma_leg.minimum_out_amount.to_le_bytes());
+    expected.extend_from_slice(&[0, ProtocolKind::MockDex as u8, 9]);
+    expected.extend_from_slice(&mock_leg.in_amount.to_le_bytes());
+    expected.extend_from_slice(&mock_leg.minimum_out_amount.to_le_bytes());
+    expected.extend_from_slice(&[1, 0]);
+    assert_eq!(encoded.data, expected);
+    assert_eq!(
+        encoded.accounts,
+        [gamma_leg.accounts.clone(), mock_leg.accounts.clone()].concat()
+    );
+
+    assert!(matches!(
+        plan_route(
+            &source,
+            &user,
+            &mint_0,
+            &key(9),
+            in_amount,
+            &[gamma_pool],
+            &PlanOptions::default()
+        ),
+        Err(beethoven_client::planner::PlanError::NoRoute)
+    ));
+
+    // Venues without the required capabilities are left out
+    let token2022 = PlanOptions {
+        required_capabilities: Capabilities::TOKEN2022_OK,
+        ..PlanOptions::default()
+    };
+    let plan = plan_route(
+        &source, &user, &mint_0, &mint_1, in_amount, &pools, &token2022,
+    )
+    .unwrap();
+    assert_eq!(
+        plan.legs.iter().map(|leg| leg.protocol).collect::<Vec<_>>(),
+        vec![ProtocolKind::Gamma]
+    );
+    let exact_out = PlanOptions {
+        required_capabilities: Capabilities::SUPPORTS_EXACT_OUT,
+        ..PlanOptions::default()
+    };
+    assert!(matches!(
+        plan_route(&source, &user, &mint_0, &mint_1, in_amount, &pools, &exact_out),
+        Err(beethoven_client::planner::PlanError::NoRoute)
+    ));
+}
+
+#[test]
+fn test_client_jito_bundle_tips_in_last_transaction() {
+    use beethoven_client::jito::{
+        bundle_instructions, send_bundle_request, BundleError, BundleTip, JITO_TIP_ACCOUNTS,
+        MAX_BUNDLE_TRANSACTIONS,
+    };
+
+    let payer = key(7);
+    let swap = solana_instruction::Instruction {
+        program_id: TEST_PROGRAM_ID,
+        accounts: vec![AccountMeta::new(payer, true)],
+        data: vec![1],
+    };
+    let tip = BundleTip {
+        payer,
+        lamports: 10_000,
+        tip_account_index: JITO_TIP_ACCOUNTS.len() + 2,
+    };
+
+    let bundle = bundle_instructions(&[vec![swap.clone()], vec![swap.clone()]], &tip).unwrap();
+    assert_eq!(bundle[0], vec![swap.clone()]);
+    assert_eq!(bundle[1].len(), 2);
+    let transfer = &bundle[1][1];
+    assert_eq!(transfer.program_id, SYSTEM_PROGRAM_ID);
+    assert_eq!(
+        transfer.accounts,
+        vec![
+            AccountMeta::new(payer, true),
+            AccountMeta::new(JITO_TIP_ACCOUNTS[2], false)
+        ]
+    );
+    assert_eq!(transfer.data[..4], 2u32.to_le_bytes());
+    assert_eq!(transfer.data[4..], 10_000u64.to_le_bytes());
+
+    let too_many = vec![vec![swap]; MAX_BUNDLE_TRANSACTIONS + 1];
+    assert_eq!(
+        bundle_instructions(&too_many, &tip),
+        Err(BundleError::TransactionCount(MAX_BUNDLE_TRANSACTIONS + 1))
+    );
+    assert_eq!(
+        bundle_instructions(&too_many[..1], &BundleTip { lamports: 1, ..tip }),
+        Err(BundleError::TipTooSmall(1))
+    );
+
+    let request = send_bundle_request(&[vec![1, 2, 3]]).unwrap();
+    assert_eq!(request["method"], "sendBundle");
+    assert_eq!(request["params"][1]["encoding"], "base64");
+    assert_eq!(request["params"][0].as_array().unwrap().len(), 1);
+    assert_eq!(
+        send_bundle_request(&[]),
+        Err(BundleError::TransactionCount(0))
+    );
+}
+
+#[test]
+fn test_client_priority_fee_estimator_sees_writable_accounts() {
+    use {
+        beethoven_client::transaction::{
+            assemble_instructions, set_compute_unit_price, AssembleOptions,
+        },
+        std::cell::RefCell,
+    };
+
+    let owner = key(7);
+    let swap = solana_instruction::Instruction {
+        program_id: TEST_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(owner, true),
+            AccountMeta::new_readonly(key(1), false),
+            AccountMeta::new(key(2), false),
+            AccountMeta::new(owner, true),
+        ],
+        data: vec![1],
+    };
+
+    let seen = RefCell::new(Vec::new());
+    let estimator = |accounts: &[Address]| {
+        seen.borrow_mut().extend_from_slice(accounts);
+        25_000
+    };
+    let options = AssembleOptions::default()
+        .with_priority_fee(&estimator, std::slice::from_ref(&swap))
+        .unwrap();
+    assert_eq!(*seen.borrow(), vec![owner, key(2)]);
+    assert_eq!(options.compute_unit_price, Some(25_000));
+    assert_eq!(
+        assemble_instructions(&owner, std::slice::from_ref(&swap), &options),
+        vec![set_compute_unit_price(25_000), swap.clone()]
+    );
+
+    // An explicit price wins over the estimate
+    let options = AssembleOptions {
+        compute_unit_price: Some(1),
+        ..AssembleOptions::default()
+    }
+    .with_priority_fee(&|_: &[Address]| 25_000, &[swap])
+    .unwrap();
+    assert_eq!(options.compute_unit_price, Some(1));
+}
+
+#[test]
+fn test_client_idl_lists_each_venue_in_adapter_order() {
+    let idl = beethoven_client::idl::test_program_idl(&TEST_PROGRAM_ID);
+    let instructions = idl["instructions"].as_array().unwrap();
+
+    let manifest = instructions
+        .iter()
+        .find(|instruction| instruction["name"] == "swap_manifest")
+        .unwrap();
+    assert_eq!(manifest["discriminator"], serde_json::json!([1]));
+
+    // Detector pinned to the venue, then the adapter's accounts with their flags
+    let accounts = manifest["accounts"].as_array().unwrap();
+    assert_eq!(accounts.len(), 1 + ManifestSwapKeys::ACCOUNTS.len());
+    assert_eq!(
+        accounts[0]["address"],
+        MANIFEST_PROGRAM_ID.to_string().as_str()
+    );
+    assert_eq!(
+        accounts[1],
+        serde_json::json!({ "name": "payer", "writable": true, "signer": true })
+    );
+    assert_eq!(
+        accounts[2],
+        serde_json::json!({ "name": "owner", "signer": true })
+    );
+
+    let args: Vec<_> = manifest["args"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|arg| arg["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        args,
+        [
+            "in_amount",
+            "minimum_out_amount",
+            "is_base_in",
+            "is_exact_in"
+        ]
+    );
+}
+
+#[test]
+fn test_client_jupiter_quote_leg_resolves_manifest_swap() {
+    use beethoven_client::jupiter_route::{route_legs, JupiterQuote, RouteError, RouteLeg};
+
+    let source = manifest_market_source();
+    let market = "ENhU8LsaR7vDD2G1CsWcsuSGNrih9Cv5WZEk7q9kPapQ";
+    let wsol = "So11111111111111111111111111111111111111112";
+    let usdc = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+    let quote_json = |amm_key: &str| {
+        format!(
+            r#"{{
+                "inputMint": "{usdc}", "inAmount": "150000000",
+                "outputMint": "{wsol}", "outAmount": "1000000000",
+                "otherAmountThreshold": "995000000", "swapMode": "ExactIn",
+                "slippageBps": 50, "priceImpactPct": "0",
+                "routePlan": [{{
+                    "swapInfo": {{
+                        "ammKey": "{amm_key}", "label": "Manifest",
+                        "inputMint": "{usdc}", "outputMint": "{wsol}",
+                        "inAmount": "150000000", "outAmount": "1000000000",
+                        "feeAmount": "0", "feeMint": "{usdc}"
+                    }},
+                    "percent": 100
+                }}],
+                "contextSlot": 1, "timeTaken": 0.01
+            }}"#
+        )
+    };
+    let quote: JupiterQuote = serde_json::from_str(&quote_json(market)).unwrap();
+    let owner = key(2);
+
+    // Selling the quote mint: the leg uses USDC's global pair
+    let keys = beethoven_client::manifest::resolve_swap_keys(
+        &source,
+        &Address::from_str(market).unwrap(),
+        &owner,
+        false,
+    )
+    .unwrap();
+    assert_eq!(
+        (keys.global, keys.global_vault),
+        beethoven_client::manifest::global_addresses(&Address::from_str(usdc).unwrap())
+    );
+
+    let legs = route_legs(&source, &quote, &owner).unwrap();
+    assert_eq!(
+        legs,
+        [RouteLeg {
+            protocol: beethoven::ProtocolKind::Manifest,
+            in_amount: 150_000_000,
+            out_amount: 1_000_000_000,
+            minimum_out_amount: 995_000_000,
+            accounts: keys.routed_account_metas(),
+            data: vec![0, 1],
+        }]
+    );
+
+    // A leg on an account no supported venue owns
+    let quote: JupiterQuote = serde_json::from_str(&quote_json(wsol)).unwrap();
+    assert!(matches!(
+        route_legs(&source, &quote, &owner),
+        Err(RouteError::UnsupportedVenue { label, .. }) if label == "Manifest"
+    ));
+}
+
+#[test]
+fn test_client_assembled_instructions_wrap_and_unwrap_sol() {
+    use {
+        beethoven_client::transaction::{assemble_instructions, AssembleOptions, WSOL_MINT},
+        solana_keypair::Keypair,
+        solana_program_pack::Pack,
+        solana_signer::Signer,
+        solana_transaction::Transaction,
+        spl_token_interface::state::Account as TokenAccount,
+    };
+
+    let dir = env!("CARGO_MANIFEST_DIR");
+    let mut svm = setup_svm();
+    load_and_set_json_fixture(&mut svm, &format!("{dir}/fixtures/common/wsol_mint.json"));
+    let usdc =
+        load_and_set_json_fixture(&mut svm, &format!("{dir}/fixtures/common/usdc_mint.json"));
+
+    let owner = Keypair::new();
+    svm.airdrop(&owner.pubkey(), 10_000_000_000).unwrap();
+    let wsol_account = associated_token_address(&owner.pubkey(), &WSOL_MINT, &TOKEN_PROGRAM_ID);
+    let usdc_account = associated_token_address(&owner.pubkey(), &usdc, &TOKEN_PROGRAM_ID);
+
+    // No swap in between: only the instructions the assembler adds around it
+    let send = |svm: &mut litesvm::LiteSVM, options: &AssembleOptions| {
+        let instructions = assemble_instructions(&owner.pubkey(), &[], options);
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&owner.pubkey()),
+            &[&owner],
+            svm.latest_blockhash(),
+        );
+        svm.send_transaction(tx).unwrap();
+    };
+
+    let options = AssembleOptions {
+        compute_unit_limit: Some(200_000),
+        compute_unit_price: Some(1),
+        create_token_accounts: vec![(usdc, TOKEN_PROGRAM_ID)],
+        wrap_sol: Some(1_000_000_000),
+        unwrap_sol: false,
+    };
+    send(&mut svm, &options);
+    send(
+        &mut svm,
+        &AssembleOptions {
+            compute_unit_limit: None,
+            compute_unit_price: None,
+            wrap_sol: Some(500_000_000),
+            ..options
+        },
+    );
+
+    let token_amount = |svm: &litesvm::LiteSVM, address: &Address| {
+        TokenAccount::unpack(&svm.get_account(address).unwrap().data)
+            .unwrap()
+            .amount
+    };
+    assert_eq!(token_amount(&svm, &wsol_account), 1_500_000_000);
+    assert_eq!(token_amount(&svm, &usdc_account), 0);
+
+    send(
+        &mut svm,
+        &AssembleOptions {
+            unwrap_sol: true,
+            ..AssembleOptions::default()
+        },
+    );
+    assert!(svm
+        .get_account(&wsol_account)
+        .is_none_or(|account| account.lamports == 0));
+}
+
+#[test]
+fn test_client_v0_message_loads_routed_accounts_from_lookup_table() {
+    use {
+        beethoven_client::transaction::{
+            assemble_v0_message, extend_lookup_table, load_lookup_table, lookup_table_candidates,
+            AssembleOptions, ADDRESS_LOOKUP_TABLE_PROGRAM_ID, MAX_ADDRESSES_PER_EXTEND,
+        },
+        solana_hash::Hash,
+    };
+
+    let keys = ManifestSwapKeys {
+        payer: key(2),
+        owner: key(2),
+        market: key(3),
+        system_program: SYSTEM_PROGRAM_ID,
+        trader_base: key(4),
+        trader_quote: key(5),
+        base_vault: key(6),
+        quote_vault: key(7),
+        token_program_base: TOKEN_PROGRAM_ID,
+        base_mint: key(8),
+        token_program_quote: TOKEN_PROGRAM_ID,
+        quote_mint: key(9),
+        global: key(10),
+        global_vault: key(11),
+    };
+    let instruction = test_program::swap_instruction(
+        TEST_PROGRAM_ID,
+        &keys,
+        100,
+        1,
+        &beethoven::manifest::ManifestSwapData {
+            is_base_in: true,
+            is_exact_in: true,
+        },
+    );
+
+    // Everything but the signer and the invoked program, the detector included
+    let candidates = lookup_table_candidates(std::slice::from_ref(&instruction));
+    assert_eq!(
+        candidates,
+        [
+            MANIFEST_PROGRAM_ID,
+            key(3),
+            SYSTEM_PROGRAM_ID,
+            key(4),
+            key(5),
+            key(6),
+            key(7),
+            TOKEN_PROGRAM_ID,
+            key(8),
+            key(9),
+            key(10),
+            key(11),
+        ]
+    );
+
+    let table = key(20);
+    let mut data = vec![0u8; 56];
+    for address in &candidates {
+        data.extend_from_slice(address.as_ref());
+    }
+    let source = HashMap::from([(
+        table,
+        FetchedAccount {
+            owner: ADDRESS_LOOKUP_TABLE_PROGRAM_ID,
+            data,
+        },
+    )]);
+    let lookup_table = load_lookup_table(&source, &table).unwrap();
+    assert_eq!(lookup_table.addresses, candidates);
+
+    let message = assemble_v0_message(
+        &key(2),
+        &[instruction],
+        &AssembleOptions::default(),
+        &[lookup_table],
+        &Hash::default(),
+    )
+    .unwrap();
+    assert_eq!(message.account_keys, [key(2), TEST_PROGRAM_ID]);
+    assert_eq!(message.address_table_lookups.len(), 1);
+    let lookup = &message.address_table_lookups[0];
+    assert_eq!(lookup.account_key, table);
+    assert_eq!(lookup.writable_indexes.len(), 7);
+    assert_eq!(lookup.readonly_indexes.len(), 5);
+
+    let extensions = extend_lookup_table(&table, &key(2), &key(2), &[key(1); 45]);
+    assert_eq!(extensions.len(), 3);
+    assert_eq!(
+        extensions[0].data.len(),
+        4 + 8 + 32 * MAX_ADDRESSES_PER_EXTEND
+    );
+    assert_eq!(extensions[2].data[4..12], 5u64.to_le_bytes());
+}
+
+#[test]
+fn test_client_simulation_outcome_reads_output_and_typed_errors() {
+    use {
+        beethoven::{BeethovenError, ProtocolKind},
+        beethoven_client::simulate::{simulation_outcome, SimulationError},
+    };
+
+    let mut output_account = vec![0u8; 165];
+    output_account[64..72].copy_from_slice(&1_250u64.to_le_bytes());
+    let outcome = simulation_outcome(250, Some(&output_account), Some(48_000), None, vec![]);
+    assert_eq!(outcome.out_amount, Some(1_000));
+    assert_eq!(outcome.units_consumed, Some(48_000));
+    assert_eq!(outcome.error, None);
+
+    let failed = |logs: &[&str]| {
+        simulation_outcome(
+            0,
+            None,
+            Some(12_000),
+            Some("InstructionError(0, Custom(..))".to_string()),
+            logs.iter().map(|log| log.to_string()).collect(),
+        )
+    };
+
+    let parse_error = BeethovenError::AdapterParseError {
+        protocol: ProtocolKind::Manifest,
+    };
+    let outcome = failed(&[&format!(
+        "Program {TEST_PROGRAM_ID} failed: custom program error: {:#x}",
+        parse_error.code()
+    )]);
+    assert_eq!(outcome.out_amount, None);
+    assert_eq!(outcome.error, Some(SimulationError::Beethoven(parse_error)));
+
+    // The venue's own error surfaces first, then propagates through the caller
+    let outcome = failed(&[
+        &format!("Program {MANIFEST_PROGRAM_ID} failed: custom program error: 0x1771"),
+        &format!("Program {TEST_PROGRAM_ID} failed: custom program error: 0x1771"),
+    ]);
+    assert_eq!(outcome.error, Some(SimulationError::Custom(0x1771)));
+
+    let outcome = failed(&["Program log: out of funds"]);
+    assert_eq!(
+        outcome.error,
+        Some(SimulationError::Other(
+            "InstructionError(0, Custom(..))".to_string()
+        ))
+    );
+
+    for error in [
+        BeethovenError::StaleOracle,
+        BeethovenError::UnknownProtocol,
+        BeethovenError::UnsupportedEnvelopeVersion,
+        BeethovenError::EnvelopeProtocolMismatch,
+        BeethovenError::UnsupportedSwapMode,
+        BeethovenError::RouteLegMismatch,
+        BeethovenError::SlippageExceeded,
+        BeethovenError::ProtocolFeatureDisabled,
+        BeethovenError::InvalidExtraData,
+        BeethovenError::AccountCountMismatch,
+        BeethovenError::InvalidFee,
+        BeethovenError::FeeMintMismatch,
+        BeethovenError::AdapterParseError {
+            protocol: ProtocolKind::Gamma,
+        },
+    ] {
+        assert_eq!(BeethovenError::from_code(error.code()), Some(error));
+    }
+    assert_eq!(BeethovenError::from_code(0x1771), None);
+    // `StaleOracle` sits at the offset; 16 is past the last protocol
+    assert_eq!(
+        BeethovenError::from_code(BeethovenError::StaleOracle.code() + 0x100 + 16),
+        None
+    );
+}
@@ -0,0 +1,292 @@
+//! Fast Mollusk checks that each swap adapter, dispatched through the test program,
+//! issues exactly the venue instruction the client builds for a direct call: the same
+//! program, account metas in order with their flags, and data bytes.
+//!
+//! The venue is replaced by `program-cpi-echo`, so these run in milliseconds without
+//! fixtures; the LiteSVM tests under `swap/` cover the venues themselves.
+
+use {
+    crate::helper::*,
+    beethoven::{
+        aldrin::{self, AldrinSwapData},
+        aldrin_v2::{self, AldrinV2SwapData},
+        futarchy::{FutarchySwapData, SwapType},
+        heaven::{HeavenSwapData, SwapDirection},
+        manifest::ManifestSwapData,
+        mock_dex::MockDexSwapData,
+        perena::PerenaSwapData,
+        solfi::SolFiSwapData,
+        solfi_v2::SolFiV2SwapData,
+    },
+    beethoven_client::{
+        aldrin::AldrinSwapKeys, aldrin_v2::AldrinV2SwapKeys, futarchy::FutarchySwapKeys,
+        gamma::GammaSwapKeys, heaven::HeavenSwapKeys, manifest::ManifestSwapKeys,
+        mock_dex::MockDexSwapKeys, perena::PerenaSwapKeys, solfi::SolFiSwapKeys,
+        solfi_v2::SolFiV2SwapKeys, AdapterKeys, EncodeSwapData,
+    },
+    solana_instruction::Instruction,
+};
+
+const IN_AMOUNT: u64 = 1_000_000;
+const MINIMUM_OUT_AMOUNT: u64 = 990_000;
+
+/// Swap `keys` through the test program and compare the echoed CPI to `expected`.
+fn assert_cpi_matches<K: AdapterKeys>(keys: &K, swap_data: &[u8], expected: Instruction) {
+    let instruction = build_swap_instruction(
+        keys.routed_account_metas(),
+        IN_AMOUNT,
+        MINIMUM_OUT_AMOUNT,
+        swap_data,
+    );
+    assert_eq!(echo_cpi(&instruction, K::PROGRAM_ID), expected);
+}
+
+#[test]
+fn test_perena_cpi_matches_client() {
+    let [pool, in_mint, out_mint, in_trader, out_trader, in_vault, out_vault, numeraire_config, payer, token_program, token_2022_program] =
+        addresses();
+    let keys = PerenaSwapKeys {
+        pool,
+        in_mint,
+        out_mint,
+        in_trader,
+        out_trader,
+        in_vault,
+        out_vault,
+        numeraire_config,
+        payer,
+        token_program,
+        token_2022_program,
+    };
+    let data = PerenaSwapData {
+        in_index: 0,
+        out_index: 1,
+    };
+    let expected =
+        beethoven_client::perena::swap_instruction(&keys, IN_AMOUNT, MINIMUM_OUT_AMOUNT, &data);
+    assert_cpi_matches(&keys, &data.encode(), expected);
+}
+
+#[test]
+fn test_solfi_cpi_matches_client() {
+    let [token_transfer_authority, market_account, base_vault, quote_vault, user_base_ata, user_quote_ata, token_program, instructions_sysvar] =
+        addresses();
+    let keys = SolFiSwapKeys {
+        token_transfer_authority,
+        market_account,
+        base_vault,
+        quote_vault,
+        user_base_ata,
+        user_quote_ata,
+        token_program,
+        instructions_sysvar,
+    };
+    let data = SolFiSwapData {
+        is_quote_to_base: true,
+    };
+    let expected =
+        beethoven_client::solfi::swap_instruction(&keys, IN_AMOUNT, MINIMUM_OUT_AMOUNT, &data);
+    assert_cpi_matches(&keys, &data.encode(), expected);
+}
+
+#[test]
+fn test_solfi_v2_cpi_matches_client() {
+    let [token_transfer_authority, market_account, oracle_account, config_account, base_vault, quote_vault, user_base_ata, user_quote_ata, base_mint, quote_mint, base_token_program, quote_token_program, instructions_sysvar] =
+        addresses();
+    let keys = SolFiV2SwapKeys {
+        token_transfer_authority,
+        market_account,
+        oracle_account,
+        config_account,
+        base_vault,
+        quote_vault,
+        user_base_ata,
+        user_quote_ata,
+        base_mint,
+        quote_mint,
+        base_token_program,
+        quote_token_program,
+        instructions_sysvar,
+    };
+    let data = SolFiV2SwapData::new(false);
+    let expected =
+        beethoven_client::solfi_v2::swap_instruction(&keys, IN_AMOUNT, MINIMUM_OUT_AMOUNT, &data);
+    assert_cpi_matches(&keys, &data.encode(), expected);
+}
+
+#[test]
+fn test_manifest_cpi_matches_client() {
+    let [payer, owner, market, system_program, trader_base, trader_quote, base_vault, quote_vault, token_program_base, base_mint, token_program_quote, quote_mint, global, global_vault] =
+        addresses();
+    let keys = ManifestSwapKeys {
+        payer,
+        owner,
+        market,
+        system_program,
+        trader_base,
+        trader_quote,
+        base_vault,
+        quote_vault,
+        token_program_base,
+        base_mint,
+        token_program_quote,
+        quote_mint,
+        global,
+        global_vault,
+    };
+    let data = ManifestSwapData {
+        is_base_in: true,
+        is_exact_in: true,
+    };
+    let expected =
+        beethoven_client::manifest::swap_instruction(&keys, IN_AMOUNT, MINIMUM_OUT_AMOUNT, &data);
+    assert_cpi_matches(&keys, &data.encode(), expected);
+}
+
+#[test]
+fn test_heaven_cpi_matches_client() {
+    let [token_a_owner, token_b_owner, ata_program, system_program, pool_state, user, token_a_mint, token_b_mint, user_token_a_account, user_token_b_account, pool_token_a_account, pool_token_b_account, protocol_config, ix_sysvar, chainlink_id, chainlink_sol_usd_feed] =
+        addresses();
+    let keys = HeavenSwapKeys {
+        token_a_owner,
+        token_b_owner,
+        ata_program,
+        system_program,
+        pool_state,
+        user,
+        token_a_mint,
+        token_b_mint,
+        user_token_a_account,
+        user_token_b_account,
+        pool_token_a_account,
+        pool_token_b_account,
+        protocol_config,
+        ix_sysvar,
+        chainlink_id,
+        chainlink_sol_usd_feed,
+    };
+    let data = HeavenSwapData {
+        direction: SwapDirection::Sell,
+        event: b"beethoven-test",
+    };
+    let expected =
+        beethoven_client::heaven::swap_instruction(&keys, IN_AMOUNT, MINIMUM_OUT_AMOUNT, &data);
+    assert_cpi_matches(&keys, &data.encode(), expected);
+}
+
+#[test]
+fn test_aldrin_cpi_matches_client() {
+    let [pool, pool_signer, pool_mint, base_token_vault, quote_token_vault, fee_pool_token_account, wallet_authority, user_base_token_account, user_quote_token_account, token_program] =
+        addresses();
+    let keys = AldrinSwapKeys {
+        pool,
+        pool_signer,
+        pool_mint,
+        base_token_vault,
+        quote_token_vault,
+        fee_pool_token_account,
+        wallet_authority,
+        user_base_token_account,
+        user_quote_token_account,
+        token_program,
+    };
+    let data = AldrinSwapData {
+        side: aldrin::Side::Ask,
+    };
+    let expected =
+        beethoven_client::aldrin::swap_instruction(&keys, IN_AMOUNT, MINIMUM_OUT_AMOUNT, &data);
+    assert_cpi_matches(&keys, &data.encode(), expected);
+}
+
+#[test]
+fn test_aldrin_v2_cpi_matches_client() {
+    let [pool, pool_signer, pool_mint, base_token_vault, quote_token_vault, fee_pool_token_account, wallet_authority, user_base_token_account, user_quote_token_account, curve, token_program] =
+        addresses();
+    let keys = AldrinV2SwapKeys {
+        pool,
+        pool_signer,
+        pool_mint,
+        base_token_vault,
+        quote_token_vault,
+        fee_pool_token_account,
+        wallet_authority,
+        user_base_token_account,
+        user_quote_token_account,
+        curve,
+        token_program,
+    };
+    let data = AldrinV2SwapData {
+        side: aldrin_v2::Side::Bid,
+    };
+    let expected =
+        beethoven_client::aldrin_v2::swap_instruction(&keys, IN_AMOUNT, MINIMUM_OUT_AMOUNT, &data);
+    assert_cpi_matches(&keys, &data.encode(), expected);
+}
+
+#[test]
+fn test_futarchy_cpi_matches_client() {
+    let [dao, user_base_account, user_quote_account, amm_base_vault, amm_quote_vault, user, token_program, event_authority] =
+        addresses();
+    let keys = FutarchySwapKeys {
+        dao,
+        user_base_account,
+        user_quote_account,
+        amm_base_vault,
+        amm_quote_vault,
+        user,
+        token_program,
+        event_authority,
+        program: FutarchySwapKeys::PROGRAM_ID,
+    };
+    let data = FutarchySwapData {
+        swap_type: SwapType::Buy,
+    };
+    let expected =
+        beethoven_client::futarchy::swap_instruction(&keys, IN_AMOUNT, MINIMUM_OUT_AMOUNT, &data);
+    assert_cpi_matches(&keys, &data.encode(), expected);
+}
+
+#[test]
+fn test_gamma_cpi_matches_client() {
+    let [payer, authority, amm_config, pool_state, input_token_account, output_token_account, input_vault, output_vault, input_token_program, output_token_program, input_token_mint, output_token_mint, observation_state] =
+        addresses();
+    let keys = GammaSwapKeys {
+        payer,
+        authority,
+        amm_config,
+        pool_state,
+        input_token_account,
+        output_token_account,
+        input_vault,
+        output_vault,
+        input_token_program,
+        output_token_program,
+        input_token_mint,
+        output_token_mint,
+        observation_state,
+    };
+    let expected = beethoven_client::gamma::swap_instruction(&keys, IN_AMOUNT, MINIMUM_OUT_AMOUNT);
+    assert_cpi_matches(&keys, &[], expected);
+}
+
+#[test]
+fn test_mock_dex_cpi_matches_client() {
+    let [user, pool, pool_authority, base_vault, quote_vault, user_base_token, user_quote_token, token_program] =
+        addresses();
+    let keys = MockDexSwapKeys {
+        user,
+        pool,
+        pool_authority,
+        base_vault,
+        quote_vault,
+        user_base_token,
+        user_quote_token,
+        token_program,
+    };
+    let data = MockDexSwapData {
+        is_quote_to_base: false,
+    };
+    let expected =
+        beethoven_client::mock_dex::swap_instruction(&keys, IN_AMOUNT, MINIMUM_OUT_AMOUNT, &data);
+    assert_cpi_matches(&keys, &data.encode(), expected);
+}
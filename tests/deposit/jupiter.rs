@@ -1,14 +1,109 @@
-use {crate::helper::*, solana_keypair::Keypair, solana_signer::Signer};
+//! Jupiter Earn deposits dispatched through the test program's `DEPOSIT` instruction,
+//! against `program-cpi-echo` deployed at the venue's program id.
 
-#[test]
-fn test_jupiter_deposit() {
+use {
+    crate::helper::*,
+    beethoven::{jupiter::JupiterEarnDepositData, BeethovenError},
+    beethoven_client::{
+        jupiter::{self, JupiterEarnDepositKeys},
+        resolve::ASSOCIATED_TOKEN_PROGRAM_ID,
+        test_program::deposit_instruction,
+        AdapterKeys, EncodeDepositData,
+    },
+    solana_instruction::Instruction,
+    solana_keypair::Keypair,
+    solana_signer::Signer,
+    solana_transaction::Transaction,
+};
+
+const ECHO_AMOUNT: u64 = 1_000_000;
+
+/// Deposit `ECHO_AMOUNT` followed by `deposit_data` into Jupiter Earn replaced by
+/// `program-cpi-echo`, returning the keys and the CPI it recorded.
+fn echo_deposit(deposit_data: &[u8]) -> (JupiterEarnDepositKeys, Result<Instruction, String>) {
     let mut svm = setup_svm();
     let payer = Keypair::new();
     svm.airdrop(&payer.pubkey(), 10_000_000_000).unwrap();
+    load_program(&mut svm, TEST_PROGRAM_ID, &test_program_path());
+    load_program(
+        &mut svm,
+        JupiterEarnDepositKeys::PROGRAM_ID,
+        &cpi_echo_program_path(),
+    );
+
+    let mint = create_mint(&mut svm, &payer.pubkey(), 6);
+    let f_token_mint = create_mint(&mut svm, &payer.pubkey(), 6);
+    let [lending_admin, lending, supply_token_reserves_liquidity, lending_supply_position_on_liquidity, rate_model, vault, liquidity, liquidity_program, rewards_rate_model] =
+        addresses();
+    let keys = JupiterEarnDepositKeys {
+        signer: payer.pubkey(),
+        depositor_token_account: create_token_account(
+            &mut svm,
+            &payer.pubkey(),
+            &mint,
+            ECHO_AMOUNT,
+        ),
+        recipient_token_account: create_token_account(&mut svm, &payer.pubkey(), &f_token_mint, 0),
+        mint,
+        lending_admin,
+        lending,
+        f_token_mint,
+        supply_token_reserves_liquidity,
+        lending_supply_position_on_liquidity,
+        rate_model,
+        vault,
+        liquidity,
+        liquidity_program,
+        rewards_rate_model,
+        token_program: TOKEN_PROGRAM_ID,
+        associated_token_program: ASSOCIATED_TOKEN_PROGRAM_ID,
+        system_program: solana_sdk_ids::system_program::ID,
+    };
+
+    let mut instruction = deposit_instruction(TEST_PROGRAM_ID, &keys, ECHO_AMOUNT);
+    instruction.data.extend_from_slice(deposit_data);
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&payer.pubkey()),
+        &[&payer],
+        svm.latest_blockhash(),
+    );
+    let result = svm
+        .send_transaction(tx)
+        .map(|meta| parse_echo(&meta.return_data.data))
+        .map_err(|failed| format!("{:?}", failed.err));
+    (keys, result)
+}
+
+#[test]
+fn test_jupiter_deposit_cpi_matches_client() {
+    let (keys, result) = echo_deposit(&[]);
+    assert_eq!(
+        result.expect("Jupiter Earn deposit failed"),
+        jupiter::deposit_instruction(&keys, ECHO_AMOUNT)
+    );
+}
+
+#[test]
+fn test_jupiter_deposit_checks_minimum_shares_out() {
+    // The echo mints nothing, so only a zero minimum is met
+    let data = JupiterEarnDepositData::new().with_minimum_shares_out(0);
+    let (keys, result) = echo_deposit(&data.encode());
+    assert_eq!(
+        result.expect("Jupiter Earn deposit failed"),
+        jupiter::deposit_instruction(&keys, ECHO_AMOUNT)
+    );
 
-    // TODO: Load beethoven-test program
-    // TODO: Load jupiter program or mock
-    // TODO: Set up accounts from fixtures
-    // TODO: Execute deposit instruction
-    // TODO: Verify results
+    let data = JupiterEarnDepositData::new().with_minimum_shares_out(1);
+    let (_, result) = echo_deposit(&data.encode());
+    assert_beethoven_error(result.map(|_| 0), BeethovenError::SlippageExceeded);
+}
+
+#[test]
+fn test_jupiter_deposit_rejects_malformed_deposit_data() {
+    // Half a minimum, and a minimum with a byte after it
+    for deposit_data in [&[1, 2, 3, 4][..], &[0; 9]] {
+        let (_, result) = echo_deposit(deposit_data);
+        assert_fails_with(result.map(|_| 0), "InvalidInstructionData");
+    }
 }
@@ -1,14 +1,102 @@
-use {crate::helper::*, solana_keypair::Keypair, solana_signer::Signer};
+//! Kamino deposits dispatched through the test program's `DEPOSIT` instruction.
+//!
+//! A reserve's Scope price, its lending market and an obligation in it can only be
+//! dumped from mainnet together, so instead of running klend the venue is replaced by
+//! `program-cpi-echo` and the CPIs the adapter issues are checked against the client.
+
+use {
+    crate::helper::*,
+    beethoven::kamino::KAMINO_LEND_PROGRAM_ID,
+    beethoven_client::{
+        kamino::{self, KaminoDepositKeys},
+        test_program::deposit_instruction,
+    },
+    solana_address::Address,
+    solana_clock::Clock,
+    solana_keypair::Keypair,
+    solana_signer::Signer,
+};
+
+const AMOUNT: u64 = 1_000_000;
+/// `refresh_obligation`'s discriminator
+const REFRESH_OBLIGATION_DATA: [u8; 8] = [33, 132, 147, 228, 151, 192, 72, 89];
+
+/// Kamino deposit keys with `owner` owning the obligation.
+fn kamino_keys(owner: Address) -> KaminoDepositKeys {
+    let [obligation, lending_market, lending_market_authority, reserve, reserve_liquidity_mint, reserve_liquidity_supply, reserve_collateral_mint, reserve_destination_deposit_collateral, user_source_liquidity, collateral_token_program, liquidity_token_program, instruction_sysvar_account, obligation_farm_user_state, reserve_farm_state, farms_program, scope_oracle, other_reserve] =
+        addresses();
+    KaminoDepositKeys {
+        owner,
+        obligation,
+        lending_market,
+        lending_market_authority,
+        reserve,
+        reserve_liquidity_mint,
+        reserve_liquidity_supply,
+        reserve_collateral_mint,
+        reserve_destination_deposit_collateral,
+        user_source_liquidity,
+        placeholder_user_destination_collateral: KAMINO_LEND_PROGRAM_ID,
+        collateral_token_program,
+        liquidity_token_program,
+        instruction_sysvar_account,
+        obligation_farm_user_state,
+        reserve_farm_state,
+        farms_program,
+        scope_oracle,
+        reserve_accounts: vec![reserve, other_reserve],
+    }
+}
+
+#[test]
+fn test_kamino_deposit_cpi_matches_client() {
+    let owner = Keypair::new();
+    let keys = kamino_keys(owner.pubkey());
+
+    let instruction = deposit_instruction(TEST_PROGRAM_ID, &keys, AMOUNT);
+    assert_eq!(
+        echo_cpi_svm(instruction, KAMINO_LEND_PROGRAM_ID, &owner),
+        kamino::deposit_instruction(&keys, AMOUNT)
+    );
+}
 
 #[test]
-fn test_kamino_deposit() {
-    let mut svm = setup_svm();
-    let payer = Keypair::new();
-    svm.airdrop(&payer.pubkey(), 10_000_000_000).unwrap();
-
-    // TODO: Load beethoven-test program
-    // TODO: Load kamino program or mock
-    // TODO: Set up accounts from fixtures
-    // TODO: Execute deposit instruction
-    // TODO: Verify results
+fn test_kamino_deposit_refreshes_reserves_then_obligation() {
+    let owner = Keypair::new();
+    let keys = kamino_keys(owner.pubkey());
+    let mut svm = echo_svm(KAMINO_LEND_PROGRAM_ID);
+    let slot = svm.get_sysvar::<Clock>().slot;
+    for reserve in &keys.reserve_accounts {
+        svm.set_account(*reserve, kamino_reserve_account(slot, true))
+            .unwrap();
+    }
+
+    let instruction = deposit_instruction(TEST_PROGRAM_ID, &keys, AMOUNT);
+    let cpis = venue_cpis_svm(&mut svm, instruction, KAMINO_LEND_PROGRAM_ID, &owner);
+
+    let deposit = kamino::deposit_instruction(&keys, AMOUNT);
+    assert_eq!(
+        cpis,
+        vec![
+            (
+                kamino_refresh_accounts(
+                    &keys.reserve_accounts,
+                    &keys.lending_market,
+                    &keys.scope_oracle,
+                ),
+                KAMINO_REFRESH_RESERVES_BATCH_DATA.to_vec(),
+            ),
+            (
+                [keys.obligation, keys.lending_market]
+                    .into_iter()
+                    .chain(keys.reserve_accounts.iter().copied())
+                    .collect(),
+                REFRESH_OBLIGATION_DATA.to_vec(),
+            ),
+            (
+                deposit.accounts.iter().map(|meta| meta.pubkey).collect(),
+                deposit.data,
+            ),
+        ]
+    );
 }
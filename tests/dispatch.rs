@@ -0,0 +1,185 @@
+//! Dispatch failures through the test program: unknown detector programs, account
+//! lists one short of an adapter's minimum and swap data for another venue must fail
+//! with Beethoven's own error codes before any venue is invoked.
+
+use {
+    crate::helper::*,
+    beethoven::{BeethovenError, ProtocolKind, Swap, SwapContext, SwapData},
+    beethoven_client::{
+        aldrin::AldrinSwapKeys,
+        aldrin_v2::AldrinV2SwapKeys,
+        futarchy::FutarchySwapKeys,
+        gamma::GammaSwapKeys,
+        heaven::HeavenSwapKeys,
+        jupiter::JupiterEarnDepositKeys,
+        kamino::KaminoDepositKeys,
+        manifest::ManifestSwapKeys,
+        mock_dex::MockDexSwapKeys,
+        perena::PerenaSwapKeys,
+        solfi::SolFiSwapKeys,
+        solfi_v2::SolFiV2SwapKeys,
+        test_program::{registry_swap_instruction, REGISTERED_PROGRAM_ID, REGISTERED_SWAP_ERROR},
+        AdapterKeys,
+    },
+    litesvm::LiteSVM,
+    solana_instruction::AccountMeta,
+    solana_keypair::Keypair,
+    solana_signer::Signer,
+};
+
+/// Swap data every swap adapter accepts: Perena indices 0 and 1, Manifest flags,
+/// variant 0 for the enum-led venues, a one-byte Heaven event
+const VALID_SWAP_DATA: [u8; 2] = [0, 1];
+
+fn setup() -> (LiteSVM, Keypair) {
+    let mut svm = setup_svm();
+    let payer = Keypair::new();
+    svm.airdrop(&payer.pubkey(), 10_000_000_000).unwrap();
+    load_program(&mut svm, TEST_PROGRAM_ID, &test_program_path());
+    (svm, payer)
+}
+
+fn assert_swap_one_short<K: AdapterKeys>(protocol: ProtocolKind) {
+    let (mut svm, payer) = setup();
+    let mut metas = routed_metas::<K>(&payer.pubkey());
+    metas.pop();
+
+    let instruction = build_swap_instruction(metas, 1_000, 1, &VALID_SWAP_DATA);
+    assert_beethoven_error(
+        send_transaction(&mut svm, &payer, instruction),
+        BeethovenError::AdapterParseError { protocol },
+    );
+}
+
+fn assert_deposit_one_short<K: AdapterKeys>(protocol: ProtocolKind) {
+    let (mut svm, payer) = setup();
+    let mut metas = routed_metas::<K>(&payer.pubkey());
+    metas.pop();
+
+    let instruction = build_deposit_instruction(metas, 1_000);
+    assert_beethoven_error(
+        send_transaction(&mut svm, &payer, instruction),
+        BeethovenError::AdapterParseError { protocol },
+    );
+}
+
+#[test]
+fn test_unknown_detector_program() {
+    let (mut svm, payer) = setup();
+    let unknown = AccountMeta::new_readonly(Keypair::new().pubkey(), false);
+
+    let instruction = build_swap_instruction(vec![unknown.clone()], 1_000, 1, &VALID_SWAP_DATA);
+    assert_beethoven_error(
+        send_transaction(&mut svm, &payer, instruction),
+        BeethovenError::UnknownProtocol,
+    );
+
+    let instruction = build_deposit_instruction(vec![unknown], 1_000);
+    assert_beethoven_error(
+        send_transaction(&mut svm, &payer, instruction),
+        BeethovenError::UnknownProtocol,
+    );
+}
+
+#[test]
+fn test_registered_venue_handles_unknown_program() {
+    let (mut svm, payer) = setup();
+    let registered = AccountMeta::new_readonly(REGISTERED_PROGRAM_ID, false);
+    let instruction = registry_swap_instruction(
+        TEST_PROGRAM_ID,
+        vec![registered],
+        1_000,
+        1,
+        &VALID_SWAP_DATA,
+    );
+    assert_fails_with(
+        send_transaction(&mut svm, &payer, instruction),
+        &format!("Custom({REGISTERED_SWAP_ERROR})"),
+    );
+
+    // Programs neither an adapter nor the registry knows still fail detection
+    let unknown = AccountMeta::new_readonly(Keypair::new().pubkey(), false);
+    let instruction =
+        registry_swap_instruction(TEST_PROGRAM_ID, vec![unknown], 1_000, 1, &VALID_SWAP_DATA);
+    assert_beethoven_error(
+        send_transaction(&mut svm, &payer, instruction),
+        BeethovenError::UnknownProtocol,
+    );
+}
+
+#[test]
+fn test_missing_detector_account() {
+    let (mut svm, payer) = setup();
+
+    let instruction = build_swap_instruction(vec![], 1_000, 1, &VALID_SWAP_DATA);
+    assert_fails_with(
+        send_transaction(&mut svm, &payer, instruction),
+        "NotEnoughAccountKeys",
+    );
+
+    let instruction = build_deposit_instruction(vec![], 1_000);
+    assert_fails_with(
+        send_transaction(&mut svm, &payer, instruction),
+        "NotEnoughAccountKeys",
+    );
+}
+
+#[test]
+fn test_swap_accounts_one_short() {
+    assert_swap_one_short::<PerenaSwapKeys>(ProtocolKind::Perena);
+    assert_swap_one_short::<SolFiSwapKeys>(ProtocolKind::SolFi);
+    assert_swap_one_short::<SolFiV2SwapKeys>(ProtocolKind::SolFiV2);
+    assert_swap_one_short::<ManifestSwapKeys>(ProtocolKind::Manifest);
+    assert_swap_one_short::<HeavenSwapKeys>(ProtocolKind::Heaven);
+    assert_swap_one_short::<AldrinSwapKeys>(ProtocolKind::Aldrin);
+    assert_swap_one_short::<AldrinV2SwapKeys>(ProtocolKind::AldrinV2);
+    assert_swap_one_short::<FutarchySwapKeys>(ProtocolKind::Futarchy);
+    assert_swap_one_short::<GammaSwapKeys>(ProtocolKind::Gamma);
+    assert_swap_one_short::<MockDexSwapKeys>(ProtocolKind::MockDex);
+}
+
+#[test]
+fn test_deposit_accounts_one_short() {
+    assert_deposit_one_short::<KaminoDepositKeys>(ProtocolKind::Kamino);
+    assert_deposit_one_short::<JupiterEarnDepositKeys>(ProtocolKind::Jupiter);
+}
+
+#[test]
+fn test_swap_data_for_another_venue() {
+    let (mut svm, payer) = setup();
+
+    // Perena's indices 2 and 0 lead with a byte that is no Futarchy swap type
+    let metas = routed_metas::<FutarchySwapKeys>(&payer.pubkey());
+    let instruction = build_swap_instruction(metas, 1_000, 1, &[2, 0]);
+    assert_beethoven_error(
+        send_transaction(&mut svm, &payer, instruction),
+        BeethovenError::InvalidExtraData,
+    );
+
+    // SolFi's single direction byte is one short of Manifest's two flags
+    let metas = routed_metas::<ManifestSwapKeys>(&payer.pubkey());
+    let instruction = build_swap_instruction(metas, 1_000, 1, &[1]);
+    assert_beethoven_error(
+        send_transaction(&mut svm, &payer, instruction),
+        BeethovenError::InvalidExtraData,
+    );
+}
+
+#[test]
+fn test_swap_data_for_another_context() {
+    // The detector account, then the labelled ones
+    let mut accounts = blank_accounts(1 + beethoven::mock_dex::ACCOUNT_LABELS.len());
+    let views = account_views(&mut accounts);
+    let ctx = SwapContext::parse(ProtocolKind::MockDex, &views)
+        .unwrap_or_else(|_| panic!("mock DEX accounts did not parse"));
+    let data = SwapData::Gamma(());
+
+    assert_eq!(
+        SwapContext::swap(&ctx, 1_000, 1, &data),
+        Err(BeethovenError::InvalidExtraData.into())
+    );
+    assert_eq!(
+        ctx.user_source_account(&data).err(),
+        Some(BeethovenError::InvalidExtraData.into())
+    );
+}
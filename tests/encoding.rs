@@ -0,0 +1,241 @@
+//! serde encodings of swap data, as services persisting routes through
+//! `beethoven-client` see them, the versioned envelope dispatchers carry it in, and the
+//! deposit data parsers.
+
+use {
+    beethoven::{
+        aldrin::{AldrinSwapData, Side},
+        envelope::{Envelope, ENVELOPE_V1},
+        futarchy::{FutarchySwapData, SwapType},
+        heaven::{HeavenSwapData, SwapDirection},
+        jupiter::JupiterEarnDepositData,
+        kamino::KaminoDepositData,
+        manifest::ManifestSwapData,
+        perena::PerenaSwapData,
+        BeethovenError, DepositData, Direction, ProtocolKind, SwapData,
+    },
+    solana_program_error::ProgramError,
+};
+
+#[test]
+fn test_swap_data_json_round_trip() {
+    let data = SwapData::Manifest(ManifestSwapData {
+        is_base_in: true,
+        is_exact_in: false,
+    });
+
+    let json = serde_json::to_string(&data).unwrap();
+    assert_eq!(
+        json,
+        r#"{"Manifest":{"is_base_in":true,"is_exact_in":false}}"#
+    );
+
+    let SwapData::Manifest(decoded) = serde_json::from_str::<SwapData>(&json).unwrap() else {
+        panic!("decoded a different venue");
+    };
+    assert!(decoded.is_base_in);
+    assert!(!decoded.is_exact_in);
+}
+
+#[test]
+fn test_protocol_kind_json_round_trip() {
+    let json = serde_json::to_string(&ProtocolKind::SolFiV2).unwrap();
+    assert_eq!(json, r#""SolFiV2""#);
+    assert_eq!(
+        serde_json::from_str::<ProtocolKind>(&json).unwrap(),
+        ProtocolKind::SolFiV2
+    );
+}
+
+#[test]
+fn test_heaven_swap_data_serializes_only() {
+    let data = SwapData::Heaven(beethoven::heaven::HeavenSwapData {
+        direction: beethoven::heaven::SwapDirection::Sell,
+        event: &[1, 2],
+    });
+
+    let json = serde_json::to_string(&data).unwrap();
+    assert_eq!(json, r#"{"Heaven":{"direction":"Sell","event":[1,2]}}"#);
+    assert!(serde_json::from_str::<SwapData>(&json).is_err());
+}
+
+#[test]
+fn test_constructed_swap_data_matches_parsed() {
+    let cases: [(SwapData, ProtocolKind, &[u8]); 5] = [
+        (
+            ManifestSwapData::new(true, false).into(),
+            ProtocolKind::Manifest,
+            &[1, 0],
+        ),
+        (
+            PerenaSwapData::new(2, 0).into(),
+            ProtocolKind::Perena,
+            &[2, 0],
+        ),
+        (
+            AldrinSwapData::from(Side::Ask).into(),
+            ProtocolKind::Aldrin,
+            &[1],
+        ),
+        (
+            FutarchySwapData::from(SwapType::Buy).into(),
+            ProtocolKind::Futarchy,
+            &[0],
+        ),
+        (
+            HeavenSwapData::new(SwapDirection::Sell, &[7, 8]).into(),
+            ProtocolKind::Heaven,
+            &[1, 7, 8],
+        ),
+    ];
+
+    for (constructed, protocol, bytes) in cases {
+        let parsed = SwapData::parse(protocol, bytes).unwrap();
+        assert_eq!(
+            serde_json::to_string(&constructed).unwrap(),
+            serde_json::to_string(&parsed).unwrap(),
+            "{protocol:?}"
+        );
+    }
+}
+
+#[test]
+fn test_direction_converts_to_each_side() {
+    for (direction, byte) in [(Direction::Buy, 0u8), (Direction::Sell, 1)] {
+        let cases: [(SwapData, ProtocolKind); 4] = [
+            (
+                AldrinSwapData::from(Side::from(direction)).into(),
+                ProtocolKind::Aldrin,
+            ),
+            (
+                beethoven::aldrin_v2::AldrinV2SwapData::new(direction.into()).into(),
+                ProtocolKind::AldrinV2,
+            ),
+            (
+                FutarchySwapData::from(SwapType::from(direction)).into(),
+                ProtocolKind::Futarchy,
+            ),
+            (
+                HeavenSwapData::new(direction.into(), &[]).into(),
+                ProtocolKind::Heaven,
+            ),
+        ];
+
+        for (converted, protocol) in cases {
+            let parsed = SwapData::parse(protocol, &[byte]).unwrap();
+            assert_eq!(
+                serde_json::to_string(&converted).unwrap(),
+                serde_json::to_string(&parsed).unwrap(),
+                "{protocol:?} {direction:?}"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_envelope_wraps_swap_data_with_version_and_protocol() {
+    let payload = [1, 0];
+    let envelope = Envelope::new(ProtocolKind::Manifest, &payload);
+    let bytes = [envelope.header().as_slice(), envelope.payload].concat();
+    assert_eq!(bytes, [ENVELOPE_V1, ProtocolKind::Manifest as u8, 1, 0]);
+
+    let parsed = Envelope::try_from(bytes.as_slice()).unwrap();
+    assert_eq!(parsed, envelope);
+    assert_eq!(parsed.payload_for(ProtocolKind::Manifest), Ok(&payload[..]));
+    assert_eq!(
+        parsed.payload_for(ProtocolKind::Gamma),
+        Err(BeethovenError::EnvelopeProtocolMismatch.into())
+    );
+
+    // Version 1 carries the adapter's data as is, so it parses like the bare format
+    let SwapData::Manifest(data) = SwapData::parse(
+        parsed.protocol,
+        parsed.payload_for(ProtocolKind::Manifest).unwrap(),
+    )
+    .unwrap() else {
+        panic!("parsed a different venue");
+    };
+    assert!(data.is_base_in);
+    assert!(!data.is_exact_in);
+
+    // An empty payload is valid for venues without swap data
+    let gamma = [ENVELOPE_V1, ProtocolKind::Gamma as u8];
+    assert!(Envelope::try_from(gamma.as_slice())
+        .unwrap()
+        .payload
+        .is_empty());
+}
+
+#[test]
+fn test_envelope_rejects_unknown_versions_and_protocols() {
+    let manifest = ProtocolKind::Manifest as u8;
+    assert_eq!(
+        Envelope::try_from([ENVELOPE_V1 + 1, manifest, 1, 0].as_slice()),
+        Err(BeethovenError::UnsupportedEnvelopeVersion.into())
+    );
+    assert_eq!(
+        Envelope::try_from([0, manifest, 1, 0].as_slice()),
+        Err(BeethovenError::UnsupportedEnvelopeVersion.into())
+    );
+    assert_eq!(
+        Envelope::try_from([ENVELOPE_V1, 0xff, 1, 0].as_slice()),
+        Err(BeethovenError::UnknownProtocol.into())
+    );
+    assert_eq!(
+        Envelope::try_from([ENVELOPE_V1].as_slice()),
+        Err(ProgramError::InvalidInstructionData)
+    );
+}
+
+#[test]
+fn test_deposit_data_parses_an_optional_minimum() {
+    assert_eq!(
+        DepositData::parse(ProtocolKind::Kamino, &[]),
+        Ok(DepositData::Kamino(KaminoDepositData::new()))
+    );
+    assert_eq!(
+        DepositData::parse(ProtocolKind::Kamino, &7u64.to_le_bytes()),
+        Ok(DepositData::Kamino(
+            KaminoDepositData::new().with_minimum_collateral_out(7)
+        ))
+    );
+    assert_eq!(
+        DepositData::parse(ProtocolKind::Jupiter, &[]),
+        Ok(DepositData::Jupiter(JupiterEarnDepositData::new()))
+    );
+    assert_eq!(
+        DepositData::parse(ProtocolKind::Jupiter, &7u64.to_le_bytes()),
+        Ok(DepositData::Jupiter(
+            JupiterEarnDepositData::new().with_minimum_shares_out(7)
+        ))
+    );
+}
+
+#[test]
+fn test_deposit_data_rejects_malformed_minimums() {
+    for protocol in [ProtocolKind::Kamino, ProtocolKind::Jupiter] {
+        for data in [&[1][..], &[0; 7], &[0; 9]] {
+            assert_eq!(
+                DepositData::parse(protocol, data),
+                Err(ProgramError::InvalidInstructionData),
+                "{protocol:?} accepted {} bytes",
+                data.len()
+            );
+        }
+    }
+
+    // A swap venue has no deposit data
+    assert_eq!(
+        DepositData::parse(ProtocolKind::Manifest, &[]),
+        Err(BeethovenError::ProtocolFeatureDisabled.into())
+    );
+}
+
+#[test]
+fn test_deposit_data_json_round_trip() {
+    let data = DepositData::Jupiter(JupiterEarnDepositData::new().with_minimum_shares_out(5));
+
+    let json = serde_json::to_string(&data).unwrap();
+    assert_eq!(json, r#"{"Jupiter":{"minimum_shares_out":5}}"#);
+    assert_eq!(serde_json::from_str::<DepositData>(&json).unwrap(), data);
+}
@@ -0,0 +1,92 @@
+//! Builds Beethoven with no protocols, each protocol alone, every pair of protocols and
+//! all of them, running clippy on the library and `beethoven-feature-check`'s dispatch
+//! check for each combination.
+//!
+//! The protocol enums and dispatchers are gated per feature, so a combination the
+//! default build never sees can stop compiling or dispatch the wrong venues. This
+//! takes a few minutes and is skipped by default; run it with `make check-features`.
+
+use std::process::Command;
+
+const PROTOCOLS_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/crates/core/protocols.toml");
+
+/// Separate from the outer build's target directory, whose lock `cargo test` holds
+const TARGET_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/target/features");
+
+/// Every protocol feature in `protocols.toml`, in file order.
+fn protocol_features() -> Vec<String> {
+    std::fs::read_to_string(PROTOCOLS_PATH)
+        .unwrap_or_else(|_| panic!("Failed to read {PROTOCOLS_PATH}"))
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("feature = "))
+        .map(|value| value.trim_matches('"').to_string())
+        .collect()
+}
+
+/// No features, each feature alone, every pair and all of them, comma-separated.
+fn feature_sets(features: &[String]) -> Vec<String> {
+    let mut sets = vec![String::new()];
+    sets.extend(features.iter().cloned());
+    for (index, first) in features.iter().enumerate() {
+        for second in &features[index + 1..] {
+            sets.push(format!("{first},{second}"));
+        }
+    }
+    sets.push(features.join(","));
+    sets
+}
+
+/// Run `cargo <subcommand>` on `package` with exactly `features`, then `trailing`,
+/// returning whether it succeeded.
+fn cargo(subcommand: &str, package: &str, features: &str, trailing: &[&str]) -> bool {
+    let mut command = Command::new(env!("CARGO"));
+    command
+        .arg(subcommand)
+        .args([
+            "-q",
+            "-p",
+            package,
+            "--no-default-features",
+            "--target-dir",
+            TARGET_DIR,
+        ])
+        .current_dir(env!("CARGO_MANIFEST_DIR"));
+    if !features.is_empty() {
+        command.args(["--features", features]);
+    }
+    command
+        .args(trailing)
+        .status()
+        .unwrap_or_else(|error| panic!("Failed to run cargo: {error}"))
+        .success()
+}
+
+#[test]
+fn test_feature_sets_cover_every_combination() {
+    let features = protocol_features();
+    assert!(features.contains(&"mock_dex-swap".to_string()));
+
+    let sets = feature_sets(&features);
+    let n = features.len();
+    assert_eq!(sets.len(), 1 + n + n * (n - 1) / 2 + 1);
+}
+
+#[test]
+#[ignore = "builds every feature combination; run with `make check-features`"]
+fn test_feature_combinations_build_and_dispatch() {
+    let failed: Vec<String> = feature_sets(&protocol_features())
+        .into_iter()
+        .filter(|features| {
+            println!("features: [{features}]");
+            let clippy = &["--lib", "--", "-D", "warnings"];
+            !(cargo("clippy", "beethoven", features, clippy)
+                && cargo("run", "beethoven-feature-check", features, &[]))
+        })
+        .collect();
+
+    assert!(
+        failed.is_empty(),
+        "feature combinations failed:\n{}",
+        failed.join("\n")
+    );
+}
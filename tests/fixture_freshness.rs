@@ -0,0 +1,429 @@
+//! Re-reads the dumped JSON fixtures and checks the invariants the adapters and
+//! resolvers rely on: account owners, data lengths, state discriminators and the
+//! cross-references between a pool and its vaults and mints.
+//!
+//! Fixtures are snapshots of mainnet; when a venue upgrade changes an account layout,
+//! re-dumping them should fail here, naming the fixture and what moved, rather than
+//! as an opaque CPI error in the venue tests.
+
+use {
+    crate::helper::*,
+    beethoven_client::{
+        gamma::{self, GammaSwapKeys},
+        kamino::{self, KaminoDepositKeys},
+        manifest::{self, ManifestSwapKeys},
+        resolve::{AccountSource, FetchedAccount},
+        AdapterKeys,
+    },
+    solana_address::Address,
+    solana_keypair::Keypair,
+    solana_signer::Signer,
+    std::{collections::HashMap, path::Path},
+};
+
+const TOKEN_2022_PROGRAM_ID: Address =
+    Address::from_str_const("TokenzQdBNbLqP5VZ86cqYnZs2P9UoFqHjuVzPLLqWMN");
+
+/// `spl_token` account and mint lengths, before any Token-2022 extensions
+const TOKEN_ACCOUNT_LEN: usize = 165;
+const MINT_LEN: usize = 82;
+/// `Account.state` and `Mint.is_initialized`
+const TOKEN_ACCOUNT_STATE_OFFSET: usize = 108;
+const MINT_IS_INITIALIZED_OFFSET: usize = 45;
+
+/// Manifest's `MarketFixed` and `GlobalFixed` discriminants, little-endian u64s
+const MANIFEST_MARKET_DISCRIMINANT: [u8; 8] = [240, 175, 97, 143, 147, 159, 113, 67];
+const MANIFEST_GLOBAL_DISCRIMINANT: [u8; 8] = [1, 170, 151, 47, 187, 160, 180, 149];
+/// Manifest's `MarketFixed` header, which the resolver reads mints and vaults from
+const MANIFEST_MARKET_FIXED_LEN: usize = 256;
+
+/// Anchor account discriminators of Kamino's `Reserve` and `LendingMarket`
+const KAMINO_RESERVE_DISCRIMINATOR: [u8; 8] = [43, 242, 204, 202, 26, 247, 59, 127];
+const KAMINO_LENDING_MARKET_DISCRIMINATOR: [u8; 8] = [246, 114, 50, 98, 72, 157, 28, 120];
+
+/// Every dumped fixture, keyed by address, as the client's resolvers read them.
+struct Fixtures {
+    accounts: HashMap<Address, FetchedAccount>,
+    paths: HashMap<Address, String>,
+    problems: Vec<String>,
+}
+
+impl Fixtures {
+    /// Load every `*.json` fixture under `fixtures/{common,swap,deposit}`.
+    fn load() -> Self {
+        let mut fixtures = Self {
+            accounts: HashMap::new(),
+            paths: HashMap::new(),
+            problems: Vec::new(),
+        };
+        for dir in ["common", "swap", "deposit"] {
+            fixtures.load_dir(
+                &Path::new(env!("CARGO_MANIFEST_DIR"))
+                    .join("fixtures")
+                    .join(dir),
+            );
+        }
+        fixtures
+    }
+
+    fn load_dir(&mut self, dir: &Path) {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return;
+        };
+        for path in entries.map(|entry| entry.unwrap().path()) {
+            if path.is_dir() {
+                self.load_dir(&path);
+            } else if path
+                .extension()
+                .is_some_and(|extension| extension == "json")
+            {
+                let path = path.to_str().unwrap().to_string();
+                let (address, account) = load_json_fixture(&path);
+                self.accounts.insert(
+                    address,
+                    FetchedAccount {
+                        owner: account.owner,
+                        data: account.data,
+                    },
+                );
+                self.paths.insert(address, path);
+            }
+        }
+    }
+
+    /// The fixture path of `address`, relative to the repository root.
+    fn path(&self, address: &Address) -> String {
+        self.paths.get(address).map_or_else(
+            || format!("<no fixture for {address}>"),
+            |path| {
+                path.trim_start_matches(env!("CARGO_MANIFEST_DIR"))
+                    .trim_start_matches('/')
+                    .to_string()
+            },
+        )
+    }
+
+    fn fail(&mut self, address: &Address, problem: impl Into<String>) {
+        let path = self.path(address);
+        self.problems.push(format!("{path}: {}", problem.into()));
+    }
+
+    /// The dumped `address`, recording a problem when it was not dumped.
+    fn account(&mut self, address: &Address, role: &str) -> Option<FetchedAccount> {
+        let account = self.accounts.get(address).cloned();
+        if account.is_none() {
+            self.problems.push(format!(
+                "{role} {address} was not dumped alongside the pool"
+            ));
+        }
+        account
+    }
+
+    fn expect_owner(&mut self, address: &Address, expected: &Address) -> Option<FetchedAccount> {
+        let account = self.account(address, "account")?;
+        if account.owner != *expected {
+            let owner = account.owner;
+            self.fail(address, format!("owned by {owner}, expected {expected}"));
+        }
+        Some(account)
+    }
+
+    fn expect_prefix(&mut self, address: &Address, discriminator: &[u8], min_len: usize) {
+        let Some(account) = self.account(address, "account") else {
+            return;
+        };
+        if account.data.len() < min_len {
+            let len = account.data.len();
+            self.fail(address, format!("{len} bytes, expected at least {min_len}"));
+        } else if !account.data.starts_with(discriminator) {
+            let found = &account.data[..discriminator.len()];
+            self.fail(
+                address,
+                format!("discriminator {found:?}, expected {discriminator:?}"),
+            );
+        }
+    }
+
+    /// `address` is a token account of `mint` owned by `authority`.
+    fn expect_vault(&mut self, address: &Address, mint: &Address, authority: &Address) {
+        let Some(account) = self.account(address, "vault") else {
+            return;
+        };
+        if !is_token_program(&account.owner) || account.data.len() < TOKEN_ACCOUNT_LEN {
+            self.fail(address, "not a token account");
+            return;
+        }
+        let vault_mint = read_address(&account.data, 0);
+        let vault_authority = read_address(&account.data, 32);
+        if vault_mint != *mint {
+            self.fail(
+                address,
+                format!("vault of mint {vault_mint}, expected {mint}"),
+            );
+        }
+        if vault_authority != *authority {
+            self.fail(
+                address,
+                format!("vault owned by {vault_authority}, expected {authority}"),
+            );
+        }
+    }
+
+    /// `address` is a mint of `token_program`.
+    fn expect_mint(&mut self, address: &Address, token_program: &Address) {
+        if let Some(account) = self.expect_owner(address, token_program) {
+            if account.data.len() < MINT_LEN {
+                self.fail(address, "not a mint");
+            }
+        }
+    }
+
+    /// Panic listing every problem found, with how to refresh the fixtures.
+    fn assert_fresh(self, venue: &str) {
+        assert!(
+            self.problems.is_empty(),
+            "{venue} fixtures no longer match what the adapter expects:\n  {}\n\
+             If {venue} upgraded its program, re-dump the fixtures with `fixture-snapshot \
+             {venue} <pool> --with-program` and update the adapter and client for the new \
+             layout.",
+            self.problems.join("\n  ")
+        );
+    }
+}
+
+impl AccountSource for Fixtures {
+    type Error = core::convert::Infallible;
+
+    fn get_account(&self, address: &Address) -> Result<Option<FetchedAccount>, Self::Error> {
+        Ok(self.accounts.get(address).cloned())
+    }
+}
+
+fn is_token_program(owner: &Address) -> bool {
+    *owner == TOKEN_PROGRAM_ID || *owner == TOKEN_2022_PROGRAM_ID
+}
+
+fn read_address(data: &[u8], offset: usize) -> Address {
+    Address::new_from_array(data[offset..offset + 32].try_into().unwrap())
+}
+
+/// The address dumped as `name` for `fixtures`' venue, if it was.
+fn dumped(fixtures: &VenueFixtures, name: &str) -> Option<Address> {
+    let path = fixtures.account_path(name);
+    Path::new(&path)
+        .exists()
+        .then(|| load_json_fixture(&path).0)
+}
+
+#[test]
+fn test_token_fixtures_are_initialized() {
+    let mut fixtures = Fixtures::load();
+    let token_accounts: Vec<(Address, FetchedAccount)> = fixtures
+        .accounts
+        .iter()
+        .filter(|(_, account)| is_token_program(&account.owner))
+        .map(|(address, account)| (*address, account.clone()))
+        .collect();
+
+    for (address, account) in token_accounts {
+        let len = account.data.len();
+        if len == MINT_LEN {
+            if account.data[MINT_IS_INITIALIZED_OFFSET] != 1 {
+                fixtures.fail(&address, "mint is not initialized");
+            }
+        } else if len >= TOKEN_ACCOUNT_LEN {
+            // Token-2022 mints with extensions are padded past the account length and
+            // tagged after it
+            let is_mint = len > TOKEN_ACCOUNT_LEN && account.data[TOKEN_ACCOUNT_LEN] == 1;
+            let initialized = if is_mint {
+                account.data[MINT_IS_INITIALIZED_OFFSET] == 1
+            } else {
+                matches!(account.data[TOKEN_ACCOUNT_STATE_OFFSET], 1 | 2)
+            };
+            if !initialized {
+                fixtures.fail(&address, "token account is not initialized");
+            }
+        } else {
+            fixtures.fail(
+                &address,
+                format!("{len} bytes is neither a mint nor a token account"),
+            );
+        }
+    }
+    fixtures.assert_fresh("token");
+}
+
+#[test]
+fn test_manifest_fixtures_match_adapter_layout() {
+    let venue = VenueFixtures::swap("manifest");
+    let Some(market) = dumped(&venue, "usdc_sol_market") else {
+        println!("Skipping manifest: no market fixture");
+        return;
+    };
+    let mut fixtures = Fixtures::load();
+    fixtures.expect_owner(&market, &ManifestSwapKeys::PROGRAM_ID);
+    fixtures.expect_prefix(
+        &market,
+        &MANIFEST_MARKET_DISCRIMINANT,
+        MANIFEST_MARKET_FIXED_LEN,
+    );
+    if !fixtures.problems.is_empty() {
+        return fixtures.assert_fresh("manifest");
+    }
+
+    let owner = Keypair::new().pubkey();
+    let keys = match manifest::resolve_swap_keys(&fixtures, &market, &owner, true) {
+        Ok(keys) => keys,
+        Err(error) => {
+            fixtures.fail(
+                &market,
+                format!("the client no longer resolves it: {error}"),
+            );
+            return fixtures.assert_fresh("manifest");
+        }
+    };
+
+    // The market's header must still point at the dumped vaults and mints
+    for (name, vault, mint) in [
+        ("sol_usdc_base_vault", keys.base_vault, keys.base_mint),
+        ("sol_usdc_quote_vault", keys.quote_vault, keys.quote_mint),
+    ] {
+        if dumped(&venue, name) != Some(vault) {
+            fixtures.fail(&market, format!("reads {vault} where {name} was dumped"));
+        }
+        // Manifest vaults are PDAs that own themselves
+        fixtures.expect_vault(&vault, &mint, &vault);
+    }
+    fixtures.expect_mint(&keys.base_mint, &keys.token_program_base);
+    fixtures.expect_mint(&keys.quote_mint, &keys.token_program_quote);
+
+    // The dumped global pair is the one `global_addresses` derives for one of the mints
+    if let (Some(global), Some(global_vault)) =
+        (dumped(&venue, "global"), dumped(&venue, "global_vault"))
+    {
+        let mint = [keys.base_mint, keys.quote_mint]
+            .into_iter()
+            .find(|mint| manifest::global_addresses(mint) == (global, global_vault));
+        match mint {
+            Some(mint) => {
+                fixtures.expect_owner(&global, &ManifestSwapKeys::PROGRAM_ID);
+                fixtures.expect_prefix(&global, &MANIFEST_GLOBAL_DISCRIMINANT, 8);
+                fixtures.expect_vault(&global_vault, &mint, &global_vault);
+            }
+            None => fixtures.fail(
+                &global,
+                "not the global pair `global_addresses` derives for either market mint",
+            ),
+        }
+    }
+    fixtures.assert_fresh("manifest");
+}
+
+#[test]
+fn test_gamma_fixtures_match_adapter_layout() {
+    let venue = VenueFixtures::swap("gamma");
+    let (Some(pool_state), Some(input_token_mint)) = (
+        dumped(&venue, "pool_state"),
+        dumped(&venue, "input_token_mint"),
+    ) else {
+        println!("Skipping gamma: no pool fixtures");
+        return;
+    };
+    let mut fixtures = Fixtures::load();
+    fixtures.expect_owner(&pool_state, &GammaSwapKeys::PROGRAM_ID);
+    fixtures.expect_prefix(
+        &pool_state,
+        &beethoven::gamma::state::POOL_STATE_DISCRIMINATOR,
+        8,
+    );
+
+    match gamma::resolve_swap_keys(
+        &fixtures,
+        &pool_state,
+        &Keypair::new().pubkey(),
+        &input_token_mint,
+    ) {
+        Ok(keys) => {
+            for (name, address) in [
+                ("amm_config", keys.amm_config),
+                ("input_vault", keys.input_vault),
+                ("output_vault", keys.output_vault),
+                ("output_token_mint", keys.output_token_mint),
+                ("observation_state", keys.observation_state),
+            ] {
+                if dumped(&venue, name) != Some(address) {
+                    fixtures.fail(
+                        &pool_state,
+                        format!("reads {address} where {name} was dumped"),
+                    );
+                }
+            }
+            fixtures.expect_owner(&keys.amm_config, &GammaSwapKeys::PROGRAM_ID);
+            fixtures.expect_prefix(
+                &keys.amm_config,
+                &beethoven::gamma::state::AMM_CONFIG_DISCRIMINATOR,
+                8,
+            );
+            fixtures.expect_vault(&keys.input_vault, &keys.input_token_mint, &keys.authority);
+            fixtures.expect_vault(&keys.output_vault, &keys.output_token_mint, &keys.authority);
+            fixtures.expect_mint(&keys.input_token_mint, &keys.input_token_program);
+            fixtures.expect_mint(&keys.output_token_mint, &keys.output_token_program);
+        }
+        Err(error) => fixtures.fail(
+            &pool_state,
+            format!("the client no longer resolves it: {error}"),
+        ),
+    }
+    fixtures.assert_fresh("gamma");
+}
+
+#[test]
+fn test_kamino_fixtures_match_adapter_layout() {
+    let venue = VenueFixtures::deposit("kamino");
+    let (Some(reserve), Some(lending_market)) =
+        (dumped(&venue, "pool"), dumped(&venue, "lending_market"))
+    else {
+        println!("Skipping kamino: no reserve fixtures");
+        return;
+    };
+    let mut fixtures = Fixtures::load();
+    fixtures.expect_owner(&reserve, &KaminoDepositKeys::PROGRAM_ID);
+    fixtures.expect_prefix(&reserve, &KAMINO_RESERVE_DISCRIMINATOR, 8);
+    fixtures.expect_owner(&lending_market, &KaminoDepositKeys::PROGRAM_ID);
+    fixtures.expect_prefix(&lending_market, &KAMINO_LENDING_MARKET_DISCRIMINATOR, 8);
+
+    let owner = Keypair::new().pubkey();
+    match kamino::resolve_deposit_keys(&fixtures, &owner, &lending_market, &reserve) {
+        Ok(keys) => {
+            for (name, address) in [
+                ("reserve_liquidity_mint", keys.reserve_liquidity_mint),
+                ("reserve_liquidity_supply", keys.reserve_liquidity_supply),
+                ("reserve_collateral_mint", keys.reserve_collateral_mint),
+                (
+                    "reserve_collateral_supply",
+                    keys.reserve_destination_deposit_collateral,
+                ),
+            ] {
+                if dumped(&venue, name) != Some(address) {
+                    fixtures.fail(&reserve, format!("reads {address} where {name} was dumped"));
+                }
+            }
+            let authority = keys.lending_market_authority;
+            fixtures.expect_vault(
+                &keys.reserve_liquidity_supply,
+                &keys.reserve_liquidity_mint,
+                &authority,
+            );
+            fixtures.expect_vault(
+                &keys.reserve_destination_deposit_collateral,
+                &keys.reserve_collateral_mint,
+                &authority,
+            );
+        }
+        Err(error) => fixtures.fail(
+            &reserve,
+            format!("the client no longer resolves it: {error}"),
+        ),
+    }
+    fixtures.assert_fresh("kamino");
+}
@@ -0,0 +1,120 @@
+//! Property tests feeding arbitrary bytes to every swap data parser.
+//!
+//! Programs hand these parsers instruction data straight from the transaction, so
+//! beyond the happy-path bytes covered elsewhere they must never panic, must only
+//! accept inputs long enough for their fields, must read fields from the bytes they
+//! were given, and must reject enum discriminants outside their variants.
+
+use {
+    beethoven::{
+        aldrin::{AldrinSwapData, Side},
+        aldrin_v2::AldrinV2SwapData,
+        futarchy::{FutarchySwapData, SwapType},
+        heaven::{HeavenSwapData, SwapDirection},
+        manifest::ManifestSwapData,
+        mock_dex::MockDexSwapData,
+        perena::PerenaSwapData,
+        solfi::SolFiSwapData,
+        solfi_v2::SolFiV2SwapData,
+        ProtocolKind, SwapData,
+    },
+    proptest::prelude::*,
+};
+
+/// Every swap protocol `SwapData::parse` dispatches on.
+const SWAP_PROTOCOLS: [ProtocolKind; 10] = [
+    ProtocolKind::Perena,
+    ProtocolKind::SolFi,
+    ProtocolKind::SolFiV2,
+    ProtocolKind::Manifest,
+    ProtocolKind::Heaven,
+    ProtocolKind::Aldrin,
+    ProtocolKind::AldrinV2,
+    ProtocolKind::Futarchy,
+    ProtocolKind::Gamma,
+    ProtocolKind::MockDex,
+];
+
+fn bytes() -> impl Strategy<Value = Vec<u8>> {
+    proptest::collection::vec(any::<u8>(), 0..64)
+}
+
+proptest! {
+    #[test]
+    fn test_bool_flag_parsers(data in bytes()) {
+        let expected = data.first().map(|flag| *flag != 0);
+        let solfi = SolFiSwapData::try_from(data.as_slice()).ok();
+        prop_assert_eq!(solfi.map(|parsed| parsed.is_quote_to_base), expected);
+        let solfi_v2 = SolFiV2SwapData::try_from(data.as_slice()).ok();
+        prop_assert_eq!(solfi_v2.map(|parsed| parsed.is_quote_to_base), expected);
+        let mock_dex = MockDexSwapData::try_from(data.as_slice()).ok();
+        prop_assert_eq!(mock_dex.map(|parsed| parsed.is_quote_to_base), expected);
+    }
+
+    #[test]
+    fn test_manifest_parser(data in bytes()) {
+        match ManifestSwapData::try_from(data.as_slice()) {
+            Ok(parsed) => {
+                prop_assert!(data.len() >= 2);
+                prop_assert_eq!(parsed.is_base_in, data[0] != 0);
+                prop_assert_eq!(parsed.is_exact_in, data[1] != 0);
+            }
+            Err(_) => prop_assert!(data.len() < 2),
+        }
+    }
+
+    #[test]
+    fn test_perena_parser(data in bytes()) {
+        match PerenaSwapData::try_from(data.as_slice()) {
+            Ok(parsed) => {
+                prop_assert!(data.len() >= 2);
+                prop_assert_eq!(parsed.in_index, data[0]);
+                prop_assert_eq!(parsed.out_index, data[1]);
+            }
+            Err(_) => prop_assert!(data.len() < 2),
+        }
+    }
+
+    #[test]
+    fn test_enum_parsers_reject_unknown_discriminants(data in bytes()) {
+        let valid = matches!(data.first(), Some(0 | 1));
+
+        let aldrin = AldrinSwapData::try_from(data.as_slice());
+        prop_assert_eq!(aldrin.is_ok(), valid);
+        if let Ok(parsed) = aldrin {
+            prop_assert_eq!(matches!(parsed.side, Side::Ask), data[0] == 1);
+        }
+
+        let aldrin_v2 = AldrinV2SwapData::try_from(data.as_slice());
+        prop_assert_eq!(aldrin_v2.is_ok(), valid);
+        if let Ok(parsed) = aldrin_v2 {
+            prop_assert_eq!(matches!(parsed.side, beethoven::aldrin_v2::Side::Ask), data[0] == 1);
+        }
+
+        let futarchy = FutarchySwapData::try_from(data.as_slice());
+        prop_assert_eq!(futarchy.is_ok(), valid);
+        if let Ok(parsed) = futarchy {
+            prop_assert_eq!(matches!(parsed.swap_type, SwapType::Sell), data[0] == 1);
+        }
+    }
+
+    #[test]
+    fn test_heaven_parser_borrows_within_input(data in bytes()) {
+        let heaven = HeavenSwapData::try_from(data.as_slice());
+        prop_assert_eq!(heaven.is_ok(), matches!(data.first(), Some(0 | 1)));
+        if let Ok(parsed) = heaven {
+            prop_assert_eq!(matches!(parsed.direction, SwapDirection::Sell), data[0] == 1);
+            // The event is the rest of the input, not a read past it
+            prop_assert_eq!(parsed.event, &data[1..]);
+            prop_assert_eq!(parsed.event.as_ptr_range().end, data.as_ptr_range().end);
+        }
+    }
+
+    #[test]
+    fn test_swap_data_parse_never_panics(
+        protocol in proptest::sample::select(SWAP_PROTOCOLS.to_vec()),
+        data in bytes(),
+    ) {
+        let _ = SwapData::parse(protocol, &data);
+    }
+}
@@ -0,0 +1,141 @@
+//! Golden-byte checks of adapter-built CPIs against venue instructions recorded in
+//! `fixtures/golden`.
+//!
+//! Each fixture holds one venue instruction (program id, named account metas in
+//! order, base64 data) and the `args` the adapter is called with to reproduce it.
+//! Data must match byte for byte and every meta must match the adapter's account
+//! spec by name, signer and writable flags, so discriminator or layout drift on
+//! either side fails here before it fails on chain.
+//!
+//! Only the Manifest swap has a golden so far, laid out from the program's `SwapV2`
+//! decoding on the dumped market (see its `source`). The other adapters are checked
+//! against their clients by `tests/cpi_echo.rs` and `tests/account_order.rs`, which
+//! catch drift between the two but not against the venue. A venue's golden, captured
+//! from a landed transaction, goes in as `<venue>_<action>.json` next to a test here
+//! and an entry in [`GOLDENS`].
+
+use {
+    base64::{engine::general_purpose::STANDARD, Engine},
+    beethoven::scratch::Scratch,
+    beethoven_client::{manifest::ManifestSwapKeys, AdapterKeys},
+    serde_json::Value,
+    solana_address::Address,
+    std::str::FromStr,
+};
+
+/// Every fixture under `fixtures/golden`, each read by a test below
+const GOLDENS: &[&str] = &["manifest_swap"];
+
+struct GoldenMeta {
+    name: String,
+    pubkey: Address,
+    is_signer: bool,
+    is_writable: bool,
+}
+
+struct GoldenInstruction {
+    program_id: Address,
+    accounts: Vec<GoldenMeta>,
+    data: Vec<u8>,
+    args: Value,
+}
+
+fn load_golden(name: &str) -> GoldenInstruction {
+    let path = format!("{}/fixtures/golden/{name}.json", env!("CARGO_MANIFEST_DIR"));
+    let contents =
+        std::fs::read_to_string(&path).unwrap_or_else(|_| panic!("Failed to read {path}"));
+    let json: Value =
+        serde_json::from_str(&contents).unwrap_or_else(|_| panic!("Failed to parse {path}"));
+
+    let address = |value: &Value| Address::from_str(value.as_str().unwrap()).unwrap();
+    let accounts = json["accounts"]
+        .as_array()
+        .expect("Missing accounts")
+        .iter()
+        .map(|meta| GoldenMeta {
+            name: meta["name"].as_str().unwrap().to_string(),
+            pubkey: address(&meta["pubkey"]),
+            is_signer: meta["is_signer"].as_bool().unwrap(),
+            is_writable: meta["is_writable"].as_bool().unwrap(),
+        })
+        .collect();
+
+    GoldenInstruction {
+        program_id: address(&json["program_id"]),
+        accounts,
+        data: STANDARD.decode(json["data"].as_str().unwrap()).unwrap(),
+        args: json["args"].clone(),
+    }
+}
+
+/// `golden`'s program and metas against `K`'s spec, returning the recorded addresses.
+fn assert_metas_match<K: AdapterKeys>(golden: &GoldenInstruction) -> Vec<Address> {
+    assert_eq!(golden.program_id, K::PROGRAM_ID);
+    assert_eq!(golden.accounts.len(), K::ACCOUNTS.len());
+    for (meta, spec) in golden.accounts.iter().zip(K::ACCOUNTS) {
+        assert_eq!(meta.name, spec.name);
+        assert_eq!(meta.is_signer, spec.signer, "signer flag of {}", spec.name);
+        assert_eq!(
+            meta.is_writable, spec.writable,
+            "writable flag of {}",
+            spec.name
+        );
+    }
+    golden.accounts.iter().map(|meta| meta.pubkey).collect()
+}
+
+#[test]
+fn test_manifest_swap_matches_golden() {
+    let golden = load_golden("manifest_swap");
+    let [payer, owner, market, system_program, trader_base, trader_quote, base_vault, quote_vault, token_program_base, base_mint, token_program_quote, quote_mint, global, global_vault] =
+        <[Address; 14]>::try_from(assert_metas_match::<ManifestSwapKeys>(&golden)).unwrap();
+
+    let keys = ManifestSwapKeys {
+        payer,
+        owner,
+        market,
+        system_program,
+        trader_base,
+        trader_quote,
+        base_vault,
+        quote_vault,
+        token_program_base,
+        base_mint,
+        token_program_quote,
+        quote_mint,
+        global,
+        global_vault,
+    };
+    let metas = keys.account_metas();
+    for (meta, recorded) in metas.iter().zip(&golden.accounts) {
+        assert_eq!(meta.pubkey, recorded.pubkey);
+    }
+
+    let args = &golden.args;
+    let mut scratch = Scratch::<{ beethoven::manifest::MAX_INSTRUCTION_DATA_LEN }>::new();
+    let data = beethoven::manifest::swap_instruction_data(
+        scratch.as_uninit_mut(),
+        args["in_amount"].as_u64().unwrap(),
+        args["minimum_out_amount"].as_u64().unwrap(),
+        &beethoven::manifest::ManifestSwapData {
+            is_base_in: args["is_base_in"].as_bool().unwrap(),
+            is_exact_in: args["is_exact_in"].as_bool().unwrap(),
+        },
+    )
+    .unwrap();
+    assert_eq!(data, golden.data.as_slice());
+}
+
+#[test]
+fn test_every_golden_is_checked() {
+    let dir = format!("{}/fixtures/golden", env!("CARGO_MANIFEST_DIR"));
+    let mut fixtures: Vec<String> = std::fs::read_dir(&dir)
+        .unwrap_or_else(|_| panic!("Failed to read {dir}"))
+        .map(|entry| {
+            let path = entry.unwrap().path();
+            path.file_stem().unwrap().to_string_lossy().into_owned()
+        })
+        .collect();
+    fixtures.sort();
+    assert_eq!(fixtures, GOLDENS, "list new goldens in GOLDENS with a test");
+}
@@ -1,431 +1,583 @@
+//! Helpers specific to the `program-test` program, on top of `beethoven-test-kit`.
+
 use {
-    base64::{engine::general_purpose::STANDARD, Engine as _},
+    beethoven::BeethovenError,
+    beethoven_client::{
+        resolve::{AccountSource, FetchedAccount},
+        AdapterKeys,
+    },
     litesvm::LiteSVM,
-    mollusk_svm::{program::keyed_account_for_system_program, result::ProgramResult, Mollusk},
-    solana_account::Account,
-    solana_address::{address, Address},
+    mollusk_svm::Mollusk,
+    solana_account_view::{AccountView, RuntimeAccount},
+    solana_address::Address,
     solana_instruction::{AccountMeta, Instruction},
     solana_keypair::Keypair,
-    solana_program_option::COption,
     solana_program_pack::Pack,
-    solana_rent::Rent,
     solana_signer::Signer,
     solana_transaction::Transaction,
-    spl_token_interface::state::{Account as TokenAccount, AccountState, Mint},
-    std::str::FromStr,
+    spl_token_interface::state::Account as TokenAccount,
+    std::sync::Mutex,
+};
+pub use {
+    // Protocol program IDs (for detection), the adapters' own values
+    beethoven::{
+        gamma::GAMMA_PROGRAM_ID, manifest::MANIFEST_PROGRAM_ID, perena::PERENA_PROGRAM_ID,
+        solfi::SOLFI_PROGRAM_ID,
+    },
+    // The test program's interface, shared with `program-test` through
+    // `beethoven-test-support`
+    beethoven_client::test_program::{discriminator, TEST_PROGRAM_ID},
+    beethoven_test_kit::*,
 };
 
 // =============================================================================
-// Constants
+// Program Setup
 // =============================================================================
 
-pub const TEST_PROGRAM_ID: Address = Address::new_from_array([0x01; 32]);
-pub const TOKEN_PROGRAM_ID: Address = address!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
-
-// Protocol program IDs (for detection)
-pub const KAMINO_PROGRAM_ID: Address = address!("KLend2g3cP87fffoy8q1mQqGKjrxjC8boSyAYavgmjD");
-pub const JUPITER_PROGRAM_ID: Address = address!("JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4");
-pub const PERENA_PROGRAM_ID: Address = address!("NUMERUNsFCP3kuNmWZuXtm1AaQCPj9uw6Guv2Ekoi5P");
-pub const SOLFI_PROGRAM_ID: Address = address!("SoLFiHG9TfgtdUXUjWAxi3LtvYuFyDLVhBWxdMZxyCe");
-pub const GAMMA_PROGRAM_ID: Address = address!("GAMMA7meSFWaBXF25oSUgmGRwaWJfSFLQzPiSfPKqp2W");
-pub const MANIFEST_PROGRAM_ID: Address = address!("MNFSTqtC93rEfYHB6hF82sKdZpUDFWkViLByLd1k1Ms");
-pub const SYSTEM_PROGRAM_ID: Address = address!("11111111111111111111111111111111");
-pub const BPF_LOADER: Address = address!("BPFLoader2111111111111111111111111111111111");
+pub fn setup_svm_with_program(program_bytes: &[u8]) -> LiteSVM {
+    beethoven_test_kit::setup_svm_with_program(TEST_PROGRAM_ID, program_bytes)
+}
 
-pub mod discriminator {
-    pub const DEPOSIT: u8 = 0;
-    pub const SWAP: u8 = 1;
+pub fn setup_mollusk_with_programs(
+    beethoven_bytes: &[u8],
+    additional_programs: &[(Address, &[u8])],
+) -> Mollusk {
+    let mut programs = vec![(TEST_PROGRAM_ID, beethoven_bytes)];
+    programs.extend_from_slice(additional_programs);
+    beethoven_test_kit::setup_mollusk_with_programs(&programs)
 }
 
 // =============================================================================
-// SVM Setup
+// Instruction Builders
 // =============================================================================
 
-pub fn setup_svm() -> LiteSVM {
-    LiteSVM::new()
+pub fn build_deposit_instruction(accounts: Vec<AccountMeta>, amount: u64) -> Instruction {
+    let mut data = vec![discriminator::DEPOSIT];
+    data.extend_from_slice(&amount.to_le_bytes());
+
+    Instruction {
+        program_id: TEST_PROGRAM_ID,
+        accounts,
+        data,
+    }
 }
 
-pub fn setup_svm_with_program(program_bytes: &[u8]) -> LiteSVM {
-    let mut svm = LiteSVM::new();
-    let _ = svm.add_program(TEST_PROGRAM_ID, program_bytes);
-    svm
+pub fn build_swap_instruction(
+    accounts: Vec<AccountMeta>,
+    in_amount: u64,
+    min_out_amount: u64,
+    extra_data: &[u8],
+) -> Instruction {
+    let mut data = vec![discriminator::SWAP];
+    data.extend_from_slice(&in_amount.to_le_bytes());
+    data.extend_from_slice(&min_out_amount.to_le_bytes());
+    data.extend_from_slice(extra_data);
+
+    Instruction {
+        program_id: TEST_PROGRAM_ID,
+        accounts,
+        data,
+    }
+}
+
+/// The `beethoven-test` program built by `make test` (or `make test-upstream`).
+#[cfg(not(feature = "upstream-bpf"))]
+pub fn test_program_path() -> String {
+    format!(
+        "{}/target/deploy/beethoven_test.so",
+        env!("CARGO_MANIFEST_DIR")
+    )
+}
+
+#[cfg(feature = "upstream-bpf")]
+pub fn test_program_path() -> String {
+    format!(
+        "{}/target/bpfel-unknown-none/release/libbeethoven_test.so",
+        env!("CARGO_MANIFEST_DIR")
+    )
 }
 
 // =============================================================================
-// Mollusk Setup
+// Dispatch Failures
 // =============================================================================
 
-pub fn setup_mollusk_with_programs(
-    beethoven_bytes: &[u8],
-    additional_programs: &[(Address, &[u8])],
-) -> Mollusk {
-    let mut mollusk = Mollusk::default();
-    mollusk.add_program_with_loader_and_elf(&TEST_PROGRAM_ID, &BPF_LOADER, beethoven_bytes);
+/// `K`'s routed metas with fresh addresses, `signer` for every signer account.
+pub fn routed_metas<K: AdapterKeys>(signer: &Address) -> Vec<AccountMeta> {
+    std::iter::once(AccountMeta::new_readonly(K::PROGRAM_ID, false))
+        .chain(K::ACCOUNTS.iter().map(|spec| AccountMeta {
+            pubkey: if spec.signer {
+                *signer
+            } else {
+                Keypair::new().pubkey()
+            },
+            is_signer: spec.signer,
+            is_writable: spec.writable,
+        }))
+        .collect()
+}
 
-    for (program_id, bytes) in additional_programs {
-        mollusk.add_program_with_loader_and_elf(program_id, &BPF_LOADER, bytes);
-    }
+/// The routed meta of `K`'s account `name` in `metas` built by [`routed_metas`].
+pub fn routed_meta<'a, K: AdapterKeys>(
+    metas: &'a mut [AccountMeta],
+    name: &str,
+) -> &'a mut AccountMeta {
+    let index = K::ACCOUNTS
+        .iter()
+        .position(|spec| spec.name == name)
+        .unwrap_or_else(|| panic!("no account {name}"));
+    // The detector account comes first
+    &mut metas[index + 1]
+}
 
-    // Add the SPL Token program
-    mollusk_svm_programs_token::token::add_program(&mut mollusk);
+pub fn assert_fails_with(result: Result<u64, String>, expected: &str) {
+    let error = result.expect_err("instruction unexpectedly succeeded");
+    assert!(error.contains(expected), "expected {expected}, got {error}");
+}
 
-    mollusk
+pub fn assert_beethoven_error(result: Result<u64, String>, expected: BeethovenError) {
+    assert_fails_with(result, &format!("Custom({})", expected.code()));
 }
 
-pub fn get_mollusk_system_program() -> (Address, Account) {
-    keyed_account_for_system_program()
+/// `count` zeroed runtime account records, for calling the dispatchers off-chain on
+/// paths that fail before reading any account.
+pub fn blank_accounts(count: usize) -> Vec<RuntimeAccount> {
+    // SAFETY: a runtime account record is plain integers and addresses
+    (0..count).map(|_| unsafe { core::mem::zeroed() }).collect()
 }
 
-pub fn get_mollusk_token_program() -> (Address, Account) {
-    mollusk_svm_programs_token::token::keyed_account()
+/// `AccountView`s over `accounts`, which have no data after them.
+pub fn account_views(accounts: &mut [RuntimeAccount]) -> Vec<AccountView> {
+    accounts
+        .iter_mut()
+        // SAFETY: every record is live for the views' use and has a zero data length
+        .map(|account| unsafe { AccountView::new_unchecked(account) })
+        .collect()
 }
 
-pub fn create_mollusk_program_account(program_bytes: &[u8]) -> Account {
-    Account {
-        lamports: 1,
-        data: program_bytes.to_vec(),
-        owner: BPF_LOADER,
-        executable: true,
-        rent_epoch: 0,
-    }
+// =============================================================================
+// Dumped Venue Fixtures
+// =============================================================================
+
+/// Fixtures `fixture-snapshot` dumped for one venue under `fixtures/<action>/<venue>`:
+/// `<venue>_<account>.json` per account and `<venue>_program.so`.
+pub struct VenueFixtures {
+    dir: String,
+    venue: &'static str,
 }
 
-/// Verify mollusk result is successful and return resulting accounts
-pub fn assert_mollusk_success(result: &mollusk_svm::result::InstructionResult) {
-    match &result.program_result {
-        ProgramResult::Success => {}
-        ProgramResult::Failure(e) => {
-            panic!(
-                "Mollusk execution failed: {:?}. Compute units: {}",
-                e, result.compute_units_consumed
-            );
+impl VenueFixtures {
+    pub fn swap(venue: &'static str) -> Self {
+        Self {
+            dir: format!("{}/fixtures/swap/{}", env!("CARGO_MANIFEST_DIR"), venue),
+            venue,
         }
-        ProgramResult::UnknownError(e) => {
-            panic!(
-                "Mollusk unknown error: {:?}. Compute units: {}",
-                e, result.compute_units_consumed
-            );
+    }
+
+    pub fn deposit(venue: &'static str) -> Self {
+        Self {
+            dir: format!("{}/fixtures/deposit/{}", env!("CARGO_MANIFEST_DIR"), venue),
+            venue,
         }
     }
-}
 
-// =============================================================================
-// Token Program Helpers
-// =============================================================================
+    pub fn program_path(&self) -> String {
+        format!("{}/{}_program.so", self.dir, self.venue)
+    }
 
-/// Create an Account for a Mint
-pub fn create_account_for_mint(mint_data: Mint) -> Account {
-    let mut data = vec![0u8; Mint::LEN];
-    Mint::pack(mint_data, &mut data).unwrap();
+    pub fn account_path(&self, name: &str) -> String {
+        format!("{}/{}_{}.json", self.dir, self.venue, name)
+    }
 
-    Account {
-        lamports: Rent::default().minimum_balance(Mint::LEN),
-        data,
-        owner: TOKEN_PROGRAM_ID,
-        executable: false,
-        rent_epoch: 0,
+    /// The program and account fixtures among `accounts` that have not been dumped.
+    fn missing(&self, accounts: &[&str]) -> Vec<String> {
+        std::iter::once(self.program_path())
+            .chain(accounts.iter().map(|name| self.account_path(name)))
+            .filter(|path| !std::path::Path::new(path).exists())
+            .collect()
+    }
+
+    /// Fail unless the program and every one of `accounts` have been dumped, naming what
+    /// is missing and how to dump it.
+    pub fn require(&self, accounts: &[&str]) {
+        let missing = self.missing(accounts);
+        assert!(
+            missing.is_empty(),
+            "{}: missing {}; dump them with `fixture-snapshot {} <pool> --with-program`",
+            self.venue,
+            missing.join(", "),
+            self.venue
+        );
+    }
+
+    /// Whether the program and every one of `accounts` have been dumped, printing what
+    /// is missing otherwise so the calling test can skip.
+    pub fn available(&self, accounts: &[&str]) -> bool {
+        let missing = self.missing(accounts);
+        if !missing.is_empty() {
+            println!(
+                "Skipping {}: missing {}; dump them with `fixture-snapshot {} <pool> --with-program`",
+                self.venue,
+                missing.join(", "),
+                self.venue
+            );
+        }
+        missing.is_empty()
+    }
+
+    /// Deploy the dumped program at `program_id`.
+    pub fn load_program(&self, svm: &mut LiteSVM, program_id: Address) {
+        load_program(svm, program_id, &self.program_path());
+    }
+
+    /// Set the dumped `name` account, returning its address.
+    pub fn load(&self, svm: &mut LiteSVM, name: &str) -> Address {
+        load_and_set_json_fixture(svm, &self.account_path(name))
     }
 }
 
-/// Create an Account for a Token Account
-pub fn create_account_for_token_account(token_account_data: TokenAccount) -> Account {
-    let mut data = vec![0u8; TokenAccount::LEN];
-    TokenAccount::pack(token_account_data, &mut data).unwrap();
+/// The accounts of a LiteSVM, as the client's resolvers read them.
+pub struct SvmSource<'a>(pub &'a LiteSVM);
 
-    Account {
-        lamports: Rent::default().minimum_balance(TokenAccount::LEN),
-        data,
-        owner: TOKEN_PROGRAM_ID,
-        executable: false,
-        rent_epoch: 0,
+impl AccountSource for SvmSource<'_> {
+    type Error = core::convert::Infallible;
+
+    fn get_account(&self, address: &Address) -> Result<Option<FetchedAccount>, Self::Error> {
+        Ok(self.0.get_account(address).map(|account| FetchedAccount {
+            owner: account.owner,
+            data: account.data,
+        }))
     }
 }
 
-/// Create and set a token account in the SVM
-pub fn create_token_account(
-    svm: &mut LiteSVM,
-    owner: &Address,
-    mint: &Address,
-    amount: u64,
-) -> Address {
-    let pubkey = Keypair::new().pubkey();
-    let account = create_account_for_token_account(TokenAccount {
-        mint: *mint,
-        owner: *owner,
-        amount,
-        delegate: COption::None,
-        state: AccountState::Initialized,
-        is_native: COption::None,
-        delegated_amount: 0,
-        close_authority: COption::None,
-    });
-    svm.set_account(pubkey, account).unwrap();
-    pubkey
-}
-
-/// Create and set a token account at a specific address
-pub fn create_token_account_at(
-    svm: &mut LiteSVM,
-    pubkey: Address,
-    owner: &Address,
-    mint: &Address,
-    amount: u64,
-) {
-    let account = create_account_for_token_account(TokenAccount {
-        mint: *mint,
-        owner: *owner,
-        amount,
-        delegate: COption::None,
-        state: AccountState::Initialized,
-        is_native: COption::None,
-        delegated_amount: 0,
-        close_authority: COption::None,
-    });
-    svm.set_account(pubkey, account).unwrap();
-}
-
-/// Create and set a mint in the SVM
-pub fn create_mint(svm: &mut LiteSVM, mint_authority: &Address, decimals: u8) -> Address {
-    let pubkey = Keypair::new().pubkey();
-    let account = create_account_for_mint(Mint {
-        mint_authority: COption::Some(*mint_authority),
-        supply: 0,
-        decimals,
-        is_initialized: true,
-        freeze_authority: COption::None,
-    });
-    svm.set_account(pubkey, account).unwrap();
-    pubkey
-}
-
-/// Create and set a mint at a specific address
-pub fn create_mint_at(
-    svm: &mut LiteSVM,
-    pubkey: Address,
-    mint_authority: &Address,
-    decimals: u8,
-    supply: u64,
-) {
-    let account = create_account_for_mint(Mint {
-        mint_authority: COption::Some(*mint_authority),
-        supply,
-        decimals,
-        is_initialized: true,
-        freeze_authority: COption::None,
-    });
-    svm.set_account(pubkey, account).unwrap();
+/// Mint of the token account at `token_account`.
+pub fn token_mint(svm: &LiteSVM, token_account: &Address) -> Address {
+    let account = svm
+        .get_account(token_account)
+        .expect("Token account not found");
+    TokenAccount::unpack(&account.data)
+        .expect("Failed to unpack token account")
+        .mint
 }
 
-// =============================================================================
-// Mock Protocol Account Helpers
-// =============================================================================
+/// Balance of the token account at `token_account`.
+pub fn token_balance(svm: &LiteSVM, token_account: &Address) -> u64 {
+    let account = svm
+        .get_account(token_account)
+        .expect("Token account not found");
+    TokenAccount::unpack(&account.data)
+        .expect("Failed to unpack token account")
+        .amount
+}
 
-pub fn create_program_account(svm: &mut LiteSVM, program_id: Address) {
-    svm.set_account(
-        program_id,
-        Account {
-            lamports: Rent::default().minimum_balance(0),
-            data: vec![],
-            owner: solana_sdk_ids::bpf_loader::ID,
-            executable: true,
-            rent_epoch: 0,
-        },
-    )
-    .unwrap();
-}
-
-pub fn create_mock_account(svm: &mut LiteSVM, owner: &Address, data: Vec<u8>) -> Address {
-    let pubkey = Keypair::new().pubkey();
-    svm.set_account(
-        pubkey,
-        Account {
-            lamports: Rent::default().minimum_balance(data.len()),
-            data,
-            owner: *owner,
-            executable: false,
-            rent_epoch: 0,
-        },
-    )
-    .unwrap();
-    pubkey
-}
-
-pub fn create_mock_account_at(svm: &mut LiteSVM, pubkey: Address, owner: &Address, data: Vec<u8>) {
-    svm.set_account(
-        pubkey,
-        Account {
-            lamports: Rent::default().minimum_balance(data.len()),
-            data,
-            owner: *owner,
-            executable: false,
-            rent_epoch: 0,
-        },
+/// The `beethoven-test` program built with `strict-validation` by `make test`, or
+/// `None` when it has not been built.
+pub fn strict_test_program_path() -> Option<String> {
+    let path = format!(
+        "{}/target/strict/beethoven_test.so",
+        env!("CARGO_MANIFEST_DIR")
+    );
+    std::path::Path::new(&path).exists().then_some(path)
+}
+
+/// The `beethoven-mock-dex` program built by `make test`.
+pub fn mock_dex_program_path() -> String {
+    format!(
+        "{}/target/deploy/beethoven_mock_dex.so",
+        env!("CARGO_MANIFEST_DIR")
     )
-    .unwrap();
 }
 
 // =============================================================================
-// Instruction Builders
+// CPI Echo
 // =============================================================================
 
-pub fn build_deposit_instruction(accounts: Vec<AccountMeta>, amount: u64) -> Instruction {
-    let mut data = vec![discriminator::DEPOSIT];
-    data.extend_from_slice(&amount.to_le_bytes());
+/// The `beethoven-cpi-echo` program built by `make test`.
+pub fn cpi_echo_program_path() -> String {
+    format!(
+        "{}/target/deploy/beethoven_cpi_echo.so",
+        env!("CARGO_MANIFEST_DIR")
+    )
+}
 
-    Instruction {
-        program_id: TEST_PROGRAM_ID,
-        accounts,
-        data,
-    }
+/// `N` distinct addresses to fill a venue's keys with.
+pub fn addresses<const N: usize>() -> [Address; N] {
+    core::array::from_fn(|index| Address::new_from_array([0x10 + index as u8; 32]))
 }
 
-pub fn build_swap_instruction(
-    accounts: Vec<AccountMeta>,
-    in_amount: u64,
-    min_out_amount: u64,
-    extra_data: &[u8],
-) -> Instruction {
-    let mut data = vec![discriminator::SWAP];
-    data.extend_from_slice(&in_amount.to_le_bytes());
-    data.extend_from_slice(&min_out_amount.to_le_bytes());
-    data.extend_from_slice(extra_data);
+/// The CPI the test program issues to `venue` for `instruction`, recorded in Mollusk
+/// by `program-cpi-echo` deployed at `venue`. Every other account is left empty, so
+/// only adapters that CPI without reading account data can be checked this way.
+pub fn echo_cpi(instruction: &Instruction, venue: Address) -> Instruction {
+    let echo_bytes = load_fixture_bytes(&cpi_echo_program_path());
+    let mollusk = setup_mollusk_with_programs(
+        &load_fixture_bytes(&test_program_path()),
+        &[(venue, &echo_bytes)],
+    );
 
-    Instruction {
-        program_id: TEST_PROGRAM_ID,
-        accounts,
-        data,
+    let mut accounts: Vec<(Address, solana_account::Account)> = vec![];
+    for meta in &instruction.accounts {
+        if accounts.iter().any(|(address, _)| *address == meta.pubkey) {
+            continue;
+        }
+        let account = if meta.pubkey == venue {
+            create_mollusk_program_account(&echo_bytes)
+        } else {
+            solana_account::Account::default()
+        };
+        accounts.push((meta.pubkey, account));
     }
+
+    let result = mollusk.process_instruction(instruction, &accounts);
+    assert_mollusk_success(&result);
+    parse_echo(&result.return_data)
 }
 
-// =============================================================================
-// Transaction Helpers
-// =============================================================================
+/// [`echo_cpi`] in LiteSVM, with `payer` paying for and signing `instruction`: the last
+/// CPI the test program issues to `venue`, for adapters that CPI more than once or need
+/// a real signature. Missing accounts are empty, as in [`echo_cpi`].
+pub fn echo_cpi_svm(instruction: Instruction, venue: Address, payer: &Keypair) -> Instruction {
+    let mut svm = setup_svm_with_program(&load_fixture_bytes(&test_program_path()));
+    load_program(&mut svm, venue, &cpi_echo_program_path());
+    svm.airdrop(&payer.pubkey(), 1_000_000_000).unwrap();
 
-pub fn send_transaction(
-    svm: &mut LiteSVM,
-    payer: &Keypair,
-    instruction: Instruction,
-) -> Result<u64, String> {
     let tx = Transaction::new_signed_with_payer(
         &[instruction],
         Some(&payer.pubkey()),
         &[payer],
         svm.latest_blockhash(),
     );
+    let meta = svm.send_transaction(tx).expect("CPI echo failed");
 
-    match svm.send_transaction(tx) {
-        Ok(meta) => {
-            for log in &meta.logs {
-                println!("{}", log);
-            }
-            println!("Compute units consumed: {}", meta.compute_units_consumed);
-            Ok(meta.compute_units_consumed)
-        }
-        Err(e) => {
-            for log in &e.meta.logs {
-                println!("{}", log);
-            }
-            Err(format!("{:?}", e.err))
-        }
-    }
+    assert_eq!(meta.return_data.program_id, venue);
+    parse_echo(&meta.return_data.data)
 }
 
-pub fn send_transaction_with_signers(
+/// The accounts and data of every CPI the test program issues to `venue` for
+/// `instruction`, in order, with `program-cpi-echo` deployed at `venue` as in
+/// [`echo_cpi_svm`]. Unlike the echo, which only returns the last CPI, this reads the
+/// transaction's inner instructions, which carry no signer or writable flags.
+pub fn venue_cpis_svm(
     svm: &mut LiteSVM,
-    payer: &Keypair,
-    signers: &[&Keypair],
     instruction: Instruction,
-) -> Result<u64, String> {
-    let mut all_signers: Vec<&Keypair> = vec![payer];
-    all_signers.extend(signers);
+    venue: Address,
+    payer: &Keypair,
+) -> Vec<(Vec<Address>, Vec<u8>)> {
+    svm.airdrop(&payer.pubkey(), 1_000_000_000).unwrap();
 
     let tx = Transaction::new_signed_with_payer(
         &[instruction],
         Some(&payer.pubkey()),
-        &all_signers,
+        &[payer],
         svm.latest_blockhash(),
     );
+    let account_keys = tx.message.account_keys.clone();
+    let meta = svm.send_transaction(tx).expect("CPI echo failed");
+
+    meta.inner_instructions
+        .into_iter()
+        .flatten()
+        .map(|inner| inner.instruction)
+        .filter(|cpi| account_keys[cpi.program_id_index as usize] == venue)
+        .map(|cpi| {
+            let accounts = cpi
+                .accounts
+                .iter()
+                .map(|&index| account_keys[index as usize])
+                .collect();
+            (accounts, cpi.data)
+        })
+        .collect()
+}
 
-    match svm.send_transaction(tx) {
-        Ok(meta) => {
-            for log in &meta.logs {
-                println!("{}", log);
-            }
-            println!("Compute units consumed: {}", meta.compute_units_consumed);
-            Ok(meta.compute_units_consumed)
-        }
-        Err(e) => {
-            for log in &e.meta.logs {
-                println!("{}", log);
-            }
-            Err(format!("{:?}", e.err))
-        }
+/// A LiteSVM with the test program and `program-cpi-echo` deployed at `venue`, for
+/// [`venue_cpis_svm`].
+pub fn echo_svm(venue: Address) -> LiteSVM {
+    let mut svm = setup_svm_with_program(&load_fixture_bytes(&test_program_path()));
+    load_program(&mut svm, venue, &cpi_echo_program_path());
+    svm
+}
+
+/// Decode `program-cpi-echo`'s return data back into the instruction it received.
+pub fn parse_echo(echo: &[u8]) -> Instruction {
+    let address = |bytes: &[u8]| Address::new_from_array(bytes[..32].try_into().unwrap());
+    let (program_id, rest) = echo.split_at(32);
+    let (count, mut rest) = rest.split_first().expect("Empty CPI echo");
+
+    let mut accounts = Vec::with_capacity(*count as usize);
+    for _ in 0..*count {
+        let (account, tail) = rest.split_at(34);
+        accounts.push(AccountMeta {
+            pubkey: address(account),
+            is_signer: account[32] != 0,
+            is_writable: account[33] != 0,
+        });
+        rest = tail;
+    }
+
+    Instruction {
+        program_id: address(program_id),
+        accounts,
+        data: rest.to_vec(),
     }
 }
 
 // =============================================================================
-// Fixture Loading
+// Kamino Reserves
 // =============================================================================
 
-pub fn load_fixture_bytes(path: &str) -> Vec<u8> {
-    std::fs::read(path).unwrap_or_else(|_| panic!("Failed to read fixture: {}", path))
-}
-
-pub fn load_fixture_account(path: &str, owner: &Address) -> Account {
-    let data = load_fixture_bytes(path);
-    Account {
-        lamports: Rent::default().minimum_balance(data.len()),
+/// `refresh_reserves_batch`'s discriminator followed by `skip_price_updates = false`
+pub const KAMINO_REFRESH_RESERVES_BATCH_DATA: [u8; 9] = [144, 110, 26, 103, 162, 204, 252, 147, 0];
+/// `Reserve.last_update`: `slot` (u64) then `stale` (u8), after the discriminator and
+/// `version`
+const RESERVE_LAST_UPDATE_SLOT_OFFSET: usize = 16;
+const RESERVE_LAST_UPDATE_STALE_OFFSET: usize = 24;
+
+/// A Kamino-owned reserve whose `last_update` is `slot`, marked `stale` or not, which the
+/// adapters take as an obligation reserve and refresh unless it is fresh.
+pub fn kamino_reserve_account(slot: u64, stale: bool) -> solana_account::Account {
+    let mut data = vec![0; RESERVE_LAST_UPDATE_STALE_OFFSET + 8];
+    data[RESERVE_LAST_UPDATE_SLOT_OFFSET..RESERVE_LAST_UPDATE_SLOT_OFFSET + 8]
+        .copy_from_slice(&slot.to_le_bytes());
+    data[RESERVE_LAST_UPDATE_STALE_OFFSET] = stale as u8;
+    solana_account::Account {
+        lamports: 1_000_000_000,
         data,
-        owner: *owner,
-        executable: false,
-        rent_epoch: 0,
+        owner: beethoven::kamino::KAMINO_LEND_PROGRAM_ID,
+        ..solana_account::Account::default()
     }
 }
 
-/// Load a JSON fixture exported by `solana account --output json-compact`
-/// Returns (pubkey, Account)
-pub fn load_json_fixture(path: &str) -> (Address, Account) {
-    let contents = std::fs::read_to_string(path)
-        .unwrap_or_else(|_| panic!("Failed to read fixture: {}", path));
-    let json: serde_json::Value = serde_json::from_str(&contents)
-        .unwrap_or_else(|_| panic!("Failed to parse JSON: {}", path));
-
-    let pubkey_str = json["pubkey"].as_str().expect("Missing pubkey field");
-    let pubkey = Address::from_str(pubkey_str).expect("Invalid pubkey");
-
-    let account_json = &json["account"];
-    let lamports = account_json["lamports"].as_u64().expect("Missing lamports");
-    let owner_str = account_json["owner"].as_str().expect("Missing owner");
-    let owner = Address::from_str(owner_str).expect("Invalid owner pubkey");
-    let executable = account_json["executable"].as_bool().unwrap_or(false);
-
-    let data_array = account_json["data"].as_array().expect("Missing data array");
-    let data_b64 = data_array[0].as_str().expect("Missing data string");
-    let data = STANDARD
-        .decode(data_b64)
-        .expect("Failed to decode base64 data");
-
-    (
-        pubkey,
-        Account {
-            lamports,
-            data,
-            owner,
-            executable,
-            rent_epoch: 0,
+/// The accounts of a `refresh_reserves_batch` CPI over `reserves`: `refresh_reserve`'s
+/// for each, the unused Pyth and Switchboard oracles standing in as the program id.
+pub fn kamino_refresh_accounts(
+    reserves: &[Address],
+    lending_market: &Address,
+    scope_oracle: &Address,
+) -> Vec<Address> {
+    let program = beethoven::kamino::KAMINO_LEND_PROGRAM_ID;
+    reserves
+        .iter()
+        .flat_map(|reserve| {
+            [
+                *reserve,
+                *lending_market,
+                program,
+                program,
+                program,
+                *scope_oracle,
+            ]
+        })
+        .collect()
+}
+
+// =============================================================================
+// Compute Unit and Program Size Baselines
+// =============================================================================
+
+pub const CU_BASELINES_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/cu_baselines.json");
+pub const DEFAULT_CU_TOLERANCE_PCT: u64 = 5;
+
+pub const SIZE_BASELINES_PATH: &str =
+    concat!(env!("CARGO_MANIFEST_DIR"), "/tests/size_baselines.json");
+pub const DEFAULT_SIZE_TOLERANCE_PCT: u64 = 2;
+
+/// Serializes read-modify-write access to the baselines files across parallel tests
+static BASELINES_LOCK: Mutex<()> = Mutex::new(());
+
+/// A checked-in JSON file of per-name baselines and the knobs controlling it
+struct Baselines {
+    path: &'static str,
+    unit: &'static str,
+    update_env: &'static str,
+    tolerance_env: &'static str,
+    default_tolerance_pct: u64,
+    make_target: &'static str,
+}
+
+/// Compare compute units consumed by `name` (dispatch + CPI) against its checked-in baseline.
+///
+/// Fails when `consumed` exceeds the baseline by more than `CU_TOLERANCE_PCT` percent
+/// (default 5), or when `name` has no baseline. Set `UPDATE_CU_BASELINES=1` to record
+/// `consumed` as the new baseline.
+pub fn assert_cu_within_baseline(name: &str, consumed: u64) {
+    assert_within_baseline(
+        &Baselines {
+            path: CU_BASELINES_PATH,
+            unit: "CU",
+            update_env: "UPDATE_CU_BASELINES",
+            tolerance_env: "CU_TOLERANCE_PCT",
+            default_tolerance_pct: DEFAULT_CU_TOLERANCE_PCT,
+            make_target: "bench-baseline",
         },
-    )
+        name,
+        consumed,
+    );
 }
 
-/// Load JSON fixture and set it in the SVM
-pub fn load_and_set_json_fixture(svm: &mut LiteSVM, path: &str) -> Address {
-    let (pubkey, account) = load_json_fixture(path);
-    svm.set_account(pubkey, account).unwrap();
-    pubkey
+/// Compare the size in bytes of the program-test `.so` built for `name` against its
+/// checked-in baseline.
+///
+/// Fails when `size` exceeds the baseline by more than `SIZE_TOLERANCE_PCT` percent
+/// (default 2), or when `name` has no baseline. Set `UPDATE_SIZE_BASELINES=1` to record
+/// `size` as the new baseline.
+pub fn assert_size_within_baseline(name: &str, size: u64) {
+    assert_within_baseline(
+        &Baselines {
+            path: SIZE_BASELINES_PATH,
+            unit: "bytes",
+            update_env: "UPDATE_SIZE_BASELINES",
+            tolerance_env: "SIZE_TOLERANCE_PCT",
+            default_tolerance_pct: DEFAULT_SIZE_TOLERANCE_PCT,
+            make_target: "size-baseline",
+        },
+        name,
+        size,
+    );
 }
 
-/// Load and deploy a program from .so file
-pub fn load_program(svm: &mut LiteSVM, program_id: Address, so_path: &str) {
-    let program_bytes = load_fixture_bytes(so_path);
-    let _ = svm.add_program(program_id, &program_bytes);
+fn assert_within_baseline(kind: &Baselines, name: &str, value: u64) {
+    let _guard = BASELINES_LOCK
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+    let contents = std::fs::read_to_string(kind.path)
+        .unwrap_or_else(|_| panic!("Failed to read baselines: {}", kind.path));
+    let mut baselines: serde_json::Map<String, serde_json::Value> =
+        serde_json::from_str(&contents).expect("Failed to parse baselines");
+
+    if std::env::var(kind.update_env).is_ok() {
+        baselines.insert(name.to_string(), value.into());
+        let contents =
+            serde_json::to_string_pretty(&baselines).expect("Failed to serialize baselines");
+        std::fs::write(kind.path, contents + "\n").expect("Failed to write baselines");
+        println!("Recorded {} baseline for {}: {}", kind.unit, name, value);
+        return;
+    }
+
+    let Some(baseline) = baselines.get(name).and_then(|value| value.as_u64()) else {
+        panic!(
+            "No {} baseline recorded for {} (measured {}); run `make {}` and commit {}",
+            kind.unit, name, value, kind.make_target, kind.path
+        );
+    };
+
+    let tolerance_pct = std::env::var(kind.tolerance_env)
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(kind.default_tolerance_pct);
+    let limit = baseline + baseline * tolerance_pct / 100;
+
+    println!(
+        "{} {}: {} (baseline {}, limit {})",
+        kind.unit, name, value, baseline, limit
+    );
+    assert!(
+        value <= limit,
+        "{} regressed: {} {}, baseline {} (+{}% allowed)",
+        name,
+        value,
+        kind.unit,
+        baseline,
+        tolerance_pct
+    );
 }
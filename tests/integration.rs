@@ -1,7 +1,23 @@
+mod account_order;
+mod actions;
+mod client;
+mod cpi_echo;
 mod deposit;
+mod dispatch;
+mod encoding;
+mod fixture_freshness;
+mod fuzz;
+mod golden;
 #[allow(dead_code)]
 mod helper;
+mod layouts;
+mod program_ids;
+mod route;
+mod size;
+mod strict_validation;
 mod swap;
+mod swap_then_deposit;
+mod withdraw;
 
 #[test]
 fn test_svm_setup() {
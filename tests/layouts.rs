@@ -0,0 +1,528 @@
+//! Differential checks of each adapter's templated `swap` instruction data against the
+//! layout spelled out field by field.
+
+use beethoven::scratch::Scratch;
+
+const IN_AMOUNT: u64 = 1_000_000;
+const MIN_OUT: u64 = 42;
+
+fn layout(fields: &[&[u8]]) -> Vec<u8> {
+    fields.concat()
+}
+
+#[test]
+fn test_solfi_swap_layout() {
+    let mut scratch = Scratch::<{ beethoven::solfi::MAX_INSTRUCTION_DATA_LEN }>::new();
+    let data = beethoven::solfi::swap_instruction_data(
+        scratch.as_uninit_mut(),
+        IN_AMOUNT,
+        MIN_OUT,
+        &beethoven::solfi::SolFiSwapData {
+            is_quote_to_base: true,
+        },
+    )
+    .unwrap();
+
+    let expected = layout(&[&[7], &IN_AMOUNT.to_le_bytes(), &MIN_OUT.to_le_bytes(), &[1]]);
+    assert_eq!(data, expected.as_slice());
+}
+
+#[test]
+fn test_solfi_v2_swap_layout() {
+    let mut scratch = Scratch::<{ beethoven::solfi_v2::MAX_INSTRUCTION_DATA_LEN }>::new();
+    let data = beethoven::solfi_v2::swap_instruction_data(
+        scratch.as_uninit_mut(),
+        IN_AMOUNT,
+        MIN_OUT,
+        &beethoven::solfi_v2::SolFiV2SwapData::new(false),
+    )
+    .unwrap();
+
+    let expected = layout(&[&[7], &IN_AMOUNT.to_le_bytes(), &MIN_OUT.to_le_bytes(), &[0]]);
+    assert_eq!(data, expected.as_slice());
+}
+
+#[test]
+fn test_manifest_swap_layout() {
+    let mut scratch = Scratch::<{ beethoven::manifest::MAX_INSTRUCTION_DATA_LEN }>::new();
+    let data = beethoven::manifest::swap_instruction_data(
+        scratch.as_uninit_mut(),
+        IN_AMOUNT,
+        MIN_OUT,
+        &beethoven::manifest::ManifestSwapData {
+            is_base_in: true,
+            is_exact_in: false,
+        },
+    )
+    .unwrap();
+
+    let expected = layout(&[
+        &[13],
+        &IN_AMOUNT.to_le_bytes(),
+        &MIN_OUT.to_le_bytes(),
+        &[1],
+        &[0],
+    ]);
+    assert_eq!(data, expected.as_slice());
+}
+
+#[test]
+fn test_perena_swap_layout() {
+    let mut scratch = Scratch::<{ beethoven::perena::MAX_INSTRUCTION_DATA_LEN }>::new();
+    let data = beethoven::perena::swap_instruction_data(
+        scratch.as_uninit_mut(),
+        IN_AMOUNT,
+        MIN_OUT,
+        &beethoven::perena::PerenaSwapData {
+            in_index: 2,
+            out_index: 5,
+        },
+    )
+    .unwrap();
+
+    let expected = layout(&[
+        &[104, 104, 131, 86, 161, 189, 180, 216],
+        &[2, 5],
+        &IN_AMOUNT.to_le_bytes(),
+        &MIN_OUT.to_le_bytes(),
+    ]);
+    assert_eq!(data, expected.as_slice());
+}
+
+#[test]
+fn test_gamma_swap_layout() {
+    let mut scratch = Scratch::<{ beethoven::gamma::MAX_INSTRUCTION_DATA_LEN }>::new();
+    let data = beethoven::gamma::swap_instruction_data(scratch.as_uninit_mut(), IN_AMOUNT, MIN_OUT)
+        .unwrap();
+
+    let expected = layout(&[
+        &[239, 82, 192, 187, 160, 26, 223, 223],
+        &IN_AMOUNT.to_le_bytes(),
+        &MIN_OUT.to_le_bytes(),
+    ]);
+    assert_eq!(data, expected.as_slice());
+}
+
+#[test]
+fn test_futarchy_swap_layout() {
+    let mut scratch = Scratch::<{ beethoven::futarchy::MAX_INSTRUCTION_DATA_LEN }>::new();
+    let data = beethoven::futarchy::swap_instruction_data(
+        scratch.as_uninit_mut(),
+        IN_AMOUNT,
+        MIN_OUT,
+        &beethoven::futarchy::FutarchySwapData {
+            swap_type: beethoven::futarchy::SwapType::Sell,
+        },
+    )
+    .unwrap();
+
+    let expected = layout(&[
+        &[167, 97, 12, 231, 237, 78, 166, 251],
+        &IN_AMOUNT.to_le_bytes(),
+        &[1],
+        &MIN_OUT.to_le_bytes(),
+    ]);
+    assert_eq!(data, expected.as_slice());
+}
+
+#[test]
+fn test_aldrin_swap_layouts() {
+    let expected = layout(&[
+        &[248, 198, 158, 145, 225, 117, 135, 200],
+        &IN_AMOUNT.to_le_bytes(),
+        &MIN_OUT.to_le_bytes(),
+        &[1],
+    ]);
+
+    let mut scratch = Scratch::<{ beethoven::aldrin::MAX_INSTRUCTION_DATA_LEN }>::new();
+    let data = beethoven::aldrin::swap_instruction_data(
+        scratch.as_uninit_mut(),
+        IN_AMOUNT,
+        MIN_OUT,
+        &beethoven::aldrin::AldrinSwapData {
+            side: beethoven::aldrin::Side::Ask,
+        },
+    )
+    .unwrap();
+    assert_eq!(data, expected.as_slice());
+
+    let mut scratch = Scratch::<{ beethoven::aldrin_v2::MAX_INSTRUCTION_DATA_LEN }>::new();
+    let data = beethoven::aldrin_v2::swap_instruction_data(
+        scratch.as_uninit_mut(),
+        IN_AMOUNT,
+        MIN_OUT,
+        &beethoven::aldrin_v2::AldrinV2SwapData {
+            side: beethoven::aldrin_v2::Side::Ask,
+        },
+    )
+    .unwrap();
+    assert_eq!(data, expected.as_slice());
+}
+
+#[test]
+fn test_template_rejects_short_scratch() {
+    let mut scratch = Scratch::<8>::new();
+    assert!(
+        beethoven::gamma::swap_instruction_data(scratch.as_uninit_mut(), IN_AMOUNT, MIN_OUT)
+            .is_err()
+    );
+}
+
+#[test]
+fn test_deposit_layouts() {
+    let amount: u64 = 5_000;
+
+    let expected = layout(&[
+        &[216, 224, 191, 27, 204, 151, 102, 175],
+        &amount.to_le_bytes(),
+    ]);
+    assert_eq!(
+        beethoven::kamino::deposit_instruction_data(amount),
+        expected.as_slice()
+    );
+
+    let expected = layout(&[
+        &[242, 35, 198, 137, 82, 225, 242, 182],
+        &amount.to_le_bytes(),
+    ]);
+    assert_eq!(
+        beethoven::jupiter::deposit_instruction_data(amount),
+        expected.as_slice()
+    );
+}
+
+#[test]
+fn test_withdraw_layouts() {
+    let collateral_amount: u64 = 5_000;
+
+    let expected = layout(&[
+        &[235, 52, 119, 152, 149, 197, 20, 7],
+        &collateral_amount.to_le_bytes(),
+    ]);
+    assert_eq!(
+        beethoven::kamino::withdraw_instruction_data(collateral_amount),
+        expected.as_slice()
+    );
+
+    let expected = layout(&[
+        &[184, 12, 86, 149, 70, 196, 97, 225],
+        &collateral_amount.to_le_bytes(),
+    ]);
+    assert_eq!(
+        beethoven::jupiter::redeem_instruction_data(collateral_amount),
+        expected.as_slice()
+    );
+}
+
+#[test]
+fn test_borrow_and_repay_layouts() {
+    let liquidity_amount: u64 = 5_000;
+
+    let expected = layout(&[
+        &[161, 128, 143, 245, 171, 199, 194, 6],
+        &liquidity_amount.to_le_bytes(),
+    ]);
+    assert_eq!(
+        beethoven::kamino::borrow_instruction_data(liquidity_amount),
+        expected.as_slice()
+    );
+
+    let expected = layout(&[
+        &[116, 174, 213, 76, 180, 53, 210, 144],
+        &liquidity_amount.to_le_bytes(),
+    ]);
+    assert_eq!(
+        beethoven::kamino::repay_instruction_data(liquidity_amount),
+        expected.as_slice()
+    );
+}
+
+#[test]
+fn test_raydium_cpmm_add_liquidity_layout() {
+    let lp_token_amount: u64 = 7_500;
+    let maximum_token_0_amount: u64 = 1_000;
+    let maximum_token_1_amount: u64 = 2_000;
+
+    let expected = layout(&[
+        &[242, 35, 198, 137, 82, 225, 242, 182],
+        &lp_token_amount.to_le_bytes(),
+        &maximum_token_0_amount.to_le_bytes(),
+        &maximum_token_1_amount.to_le_bytes(),
+    ]);
+    assert_eq!(
+        beethoven::raydium_cpmm::deposit_instruction_data(
+            lp_token_amount,
+            maximum_token_0_amount,
+            maximum_token_1_amount,
+        ),
+        expected.as_slice()
+    );
+}
+
+#[test]
+fn test_raydium_cpmm_remove_liquidity_layout() {
+    let lp_token_amount: u64 = 7_500;
+    let minimum_token_0_amount: u64 = 900;
+    let minimum_token_1_amount: u64 = 1_800;
+
+    let expected = layout(&[
+        &[183, 18, 70, 156, 148, 109, 161, 34],
+        &lp_token_amount.to_le_bytes(),
+        &minimum_token_0_amount.to_le_bytes(),
+        &minimum_token_1_amount.to_le_bytes(),
+    ]);
+    assert_eq!(
+        beethoven::raydium_cpmm::withdraw_instruction_data(
+            lp_token_amount,
+            minimum_token_0_amount,
+            minimum_token_1_amount,
+        ),
+        expected.as_slice()
+    );
+}
+
+#[test]
+fn test_marinade_stake_layout() {
+    let lamports: u64 = 2_000_000_000;
+
+    let expected = layout(&[
+        &[242, 35, 198, 137, 82, 225, 242, 182],
+        &lamports.to_le_bytes(),
+    ]);
+    assert_eq!(
+        beethoven::marinade::deposit_instruction_data(lamports),
+        expected.as_slice()
+    );
+}
+
+#[test]
+fn test_unstake_layouts() {
+    use beethoven::marinade::MarinadeUnstakeData;
+
+    let amount: u64 = 1_500_000_000;
+
+    let expected = layout(&[&[30, 30, 119, 240, 191, 227, 12, 16], &amount.to_le_bytes()]);
+    assert_eq!(
+        beethoven::marinade::liquid_unstake_instruction_data(amount),
+        expected.as_slice()
+    );
+
+    let expected = layout(&[
+        &[97, 167, 144, 107, 117, 190, 128, 36],
+        &amount.to_le_bytes(),
+    ]);
+    assert_eq!(
+        beethoven::marinade::order_unstake_instruction_data(amount),
+        expected.as_slice()
+    );
+
+    let expected = layout(&[&[16], &amount.to_le_bytes()]);
+    assert_eq!(
+        beethoven::spl_stake_pool::withdraw_sol_instruction_data(amount),
+        expected.as_slice()
+    );
+
+    assert_eq!(
+        MarinadeUnstakeData::try_from([0u8].as_slice()),
+        Ok(MarinadeUnstakeData::Instant)
+    );
+    assert_eq!(
+        MarinadeUnstakeData::try_from([1u8].as_slice()),
+        Ok(MarinadeUnstakeData::Delayed)
+    );
+    assert!(MarinadeUnstakeData::try_from([2u8].as_slice()).is_err());
+    assert!(MarinadeUnstakeData::try_from([].as_slice()).is_err());
+}
+
+#[test]
+fn test_kamino_flashloan_layouts() {
+    let liquidity_amount: u64 = 250_000;
+
+    let expected = layout(&[
+        &[135, 231, 52, 167, 7, 52, 212, 193],
+        &liquidity_amount.to_le_bytes(),
+    ]);
+    assert_eq!(
+        beethoven::kamino::flash_borrow_instruction_data(liquidity_amount),
+        expected.as_slice()
+    );
+
+    let expected = layout(&[
+        &[185, 117, 0, 203, 96, 245, 180, 186],
+        &liquidity_amount.to_le_bytes(),
+        &[3],
+    ]);
+    assert_eq!(
+        beethoven::kamino::flash_repay_instruction_data(liquidity_amount, 3),
+        expected.as_slice()
+    );
+}
+
+#[test]
+fn test_manifest_place_order_layout() {
+    use beethoven::{manifest::ManifestOrderData, Direction, OrderType};
+
+    let base_atoms: u64 = 5_000_000;
+    let order = ManifestOrderData::new(1_234, -3, 900);
+
+    let expected = layout(&[
+        &[6],
+        &[0],
+        &0u32.to_le_bytes(),
+        &1u32.to_le_bytes(),
+        &base_atoms.to_le_bytes(),
+        &1_234u32.to_le_bytes(),
+        &[(-3i8) as u8],
+        &[1],
+        &900u32.to_le_bytes(),
+        &[2],
+    ]);
+    assert_eq!(
+        beethoven::manifest::place_order_instruction_data(
+            Direction::Buy,
+            base_atoms,
+            OrderType::PostOnly,
+            &order,
+        ),
+        expected.as_slice()
+    );
+
+    let data = beethoven::manifest::place_order_instruction_data(
+        Direction::Sell,
+        base_atoms,
+        OrderType::ImmediateOrCancel,
+        &order,
+    );
+    assert_eq!((data[23], data[28]), (0, 1));
+}
+
+#[test]
+fn test_manifest_cancel_order_layout() {
+    let order_sequence_number: u64 = 77;
+
+    let expected = layout(&[
+        &[6],
+        &[0],
+        &1u32.to_le_bytes(),
+        &order_sequence_number.to_le_bytes(),
+        &[0],
+        &0u32.to_le_bytes(),
+    ]);
+    assert_eq!(
+        beethoven::manifest::cancel_order_instruction_data(order_sequence_number),
+        expected.as_slice()
+    );
+}
+
+#[test]
+fn test_drift_place_perp_order_layout() {
+    use beethoven::Direction;
+
+    let base_amount: u64 = 2_500_000_000;
+    let price_limit: u64 = 151_250_000;
+
+    let expected = layout(&[
+        &beethoven::drift::PLACE_PERP_ORDER_DISCRIMINATOR,
+        &[0],
+        &[1],
+        &[1],
+        &[0],
+        &base_amount.to_le_bytes(),
+        &price_limit.to_le_bytes(),
+        &7u16.to_le_bytes(),
+        &[0],
+        &[0],
+        &[0],
+        &[0],
+        &[0],
+        &[0],
+        &[0],
+        &[0],
+        &[0],
+        &[0],
+    ]);
+    assert_eq!(
+        beethoven::drift::place_perp_order_instruction_data(
+            7,
+            Direction::Sell,
+            base_amount,
+            price_limit,
+        ),
+        expected.as_slice()
+    );
+
+    let data =
+        beethoven::drift::place_perp_order_instruction_data(7, Direction::Buy, base_amount, 0);
+    assert_eq!((data[10], &data[20..28]), (0, &[0u8; 8][..]));
+}
+
+#[test]
+fn test_gamma_pool_state_reader() {
+    use beethoven::gamma::state::{PoolState, POOL_STATE_DISCRIMINATOR};
+
+    let mut data = vec![0u8; 8 + 340];
+    data[..8].copy_from_slice(&POOL_STATE_DISCRIMINATOR);
+    data[8 + 64..8 + 96].copy_from_slice(&[3; 32]);
+    data[8 + 300..8 + 308].copy_from_slice(&10u64.to_le_bytes());
+    data[8 + 316..8 + 324].copy_from_slice(&5u64.to_le_bytes());
+
+    let pool = PoolState::from_account_data(&data).unwrap();
+    assert_eq!(pool.token_0_vault.as_ref(), &[3; 32]);
+    assert_eq!(pool.accrued_fees(true), 15);
+    assert_eq!(pool.accrued_fees(false), 0);
+
+    data[0] ^= 1;
+    assert!(PoolState::from_account_data(&data).is_err());
+    assert!(PoolState::from_account_data(&data[..100]).is_err());
+}
+
+#[test]
+fn test_aldrin_pool_reader_and_quote() {
+    use beethoven::aldrin::{
+        quote::quote_exact_in,
+        state::{Pool, POOL_DISCRIMINATOR},
+    };
+
+    let mut data = vec![0u8; 8 + 433];
+    data[..8].copy_from_slice(&POOL_DISCRIMINATOR);
+    data[8 + 257..8 + 289].copy_from_slice(&[5; 32]);
+    // 0.25% trade fee, 0.05% owner fee
+    for (index, value) in [25u64, 10_000, 5, 10_000].into_iter().enumerate() {
+        let offset = 8 + 385 + index * 8;
+        data[offset..offset + 8].copy_from_slice(&value.to_le_bytes());
+    }
+
+    let pool = Pool::from_account_data(&data).unwrap();
+    assert_eq!(pool.base_token_vault.as_ref(), &[5; 32]);
+
+    let quote = quote_exact_in(&pool.fees, 10_000, 1_000_000, 2_000_000).unwrap();
+    assert_eq!(quote.fee_amount, 30);
+    assert_eq!(quote.out_amount, 9_970 * 2_000_000 / 1_009_970);
+
+    assert!(quote_exact_in(&pool.fees, 0, 0, 0).is_none());
+
+    data[0] ^= 1;
+    assert!(Pool::from_account_data(&data).is_err());
+}
+
+#[test]
+fn test_mock_dex_pool_round_trip() {
+    use beethoven::mock_dex::state::{Pool, POOL_ACCOUNT_LEN};
+
+    let pool = Pool {
+        base_mint: [1; 32].into(),
+        quote_mint: [2; 32].into(),
+        base_vault: [3; 32].into(),
+        quote_vault: [4; 32].into(),
+        authority_bump: 254,
+        fee_bps: 30.into(),
+    };
+    let data = pool.to_account_data();
+    assert_eq!(data.len(), POOL_ACCOUNT_LEN);
+
+    let read = Pool::from_account_data(&data).unwrap();
+    assert_eq!(read.base_vault.as_ref(), &[3; 32]);
+    assert_eq!(read.quote_vault.as_ref(), &[4; 32]);
+    assert_eq!(read.authority_bump, 254);
+    assert_eq!(read.fee_bps.get(), 30);
+}
@@ -0,0 +1,198 @@
+//! Checks that every program id the client and the tests use is the one the on-chain
+//! adapters detect, as generated from `protocols.toml`.
+//!
+//! The tests once hand-typed their own copies, and a Gamma id that had drifted from the
+//! adapter's went unnoticed; `helper` now re-exports the adapters' constants, and this
+//! keeps every other copy honest.
+
+use {
+    crate::helper::*,
+    beethoven::ProtocolKind,
+    beethoven_client::{
+        aldrin::AldrinSwapKeys, aldrin_v2::AldrinV2SwapKeys, drift::DriftPerpKeys,
+        futarchy::FutarchySwapKeys, gamma::GammaSwapKeys, heaven::HeavenSwapKeys,
+        jupiter::JupiterEarnDepositKeys, kamino::KaminoDepositKeys, manifest::ManifestSwapKeys,
+        marinade::MarinadeStakeKeys, mock_dex::MockDexSwapKeys, perena::PerenaSwapKeys,
+        raydium_cpmm::RaydiumCpmmAddLiquidityKeys, solfi::SolFiSwapKeys, solfi_v2::SolFiV2SwapKeys,
+        spl_stake_pool::SplStakePoolUnstakeKeys, AdapterKeys,
+    },
+    solana_address::Address,
+    std::str::FromStr,
+};
+
+const PROTOCOLS_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/crates/core/protocols.toml");
+
+/// Per `program_id_const`: the adapter's constant and the client's `PROGRAM_ID`
+const ADAPTER_IDS: [(&str, Address, Address); 16] = [
+    (
+        "KAMINO_LEND_PROGRAM_ID",
+        beethoven::kamino::KAMINO_LEND_PROGRAM_ID,
+        KaminoDepositKeys::PROGRAM_ID,
+    ),
+    (
+        "JUPITER_EARN_PROGRAM_ID",
+        beethoven::jupiter::JUPITER_EARN_PROGRAM_ID,
+        JupiterEarnDepositKeys::PROGRAM_ID,
+    ),
+    (
+        "PERENA_PROGRAM_ID",
+        PERENA_PROGRAM_ID,
+        PerenaSwapKeys::PROGRAM_ID,
+    ),
+    (
+        "SOLFI_PROGRAM_ID",
+        SOLFI_PROGRAM_ID,
+        SolFiSwapKeys::PROGRAM_ID,
+    ),
+    (
+        "SOLFI_V2_PROGRAM_ID",
+        beethoven::solfi_v2::SOLFI_V2_PROGRAM_ID,
+        SolFiV2SwapKeys::PROGRAM_ID,
+    ),
+    (
+        "MANIFEST_PROGRAM_ID",
+        MANIFEST_PROGRAM_ID,
+        ManifestSwapKeys::PROGRAM_ID,
+    ),
+    (
+        "HEAVEN_PROGRAM_ID",
+        beethoven::heaven::HEAVEN_PROGRAM_ID,
+        HeavenSwapKeys::PROGRAM_ID,
+    ),
+    (
+        "ALDRIN_PROGRAM_ID",
+        beethoven::aldrin::ALDRIN_PROGRAM_ID,
+        AldrinSwapKeys::PROGRAM_ID,
+    ),
+    (
+        "ALDRIN_V2_PROGRAM_ID",
+        beethoven::aldrin_v2::ALDRIN_V2_PROGRAM_ID,
+        AldrinV2SwapKeys::PROGRAM_ID,
+    ),
+    (
+        "FUTARCHY_PROGRAM_ID",
+        beethoven::futarchy::FUTARCHY_PROGRAM_ID,
+        FutarchySwapKeys::PROGRAM_ID,
+    ),
+    (
+        "GAMMA_PROGRAM_ID",
+        GAMMA_PROGRAM_ID,
+        GammaSwapKeys::PROGRAM_ID,
+    ),
+    (
+        "MOCK_DEX_PROGRAM_ID",
+        beethoven::mock_dex::MOCK_DEX_PROGRAM_ID,
+        MockDexSwapKeys::PROGRAM_ID,
+    ),
+    (
+        "RAYDIUM_CPMM_PROGRAM_ID",
+        beethoven::raydium_cpmm::RAYDIUM_CPMM_PROGRAM_ID,
+        RaydiumCpmmAddLiquidityKeys::PROGRAM_ID,
+    ),
+    (
+        "MARINADE_PROGRAM_ID",
+        beethoven::marinade::MARINADE_PROGRAM_ID,
+        MarinadeStakeKeys::PROGRAM_ID,
+    ),
+    (
+        "SPL_STAKE_POOL_PROGRAM_ID",
+        beethoven::spl_stake_pool::SPL_STAKE_POOL_PROGRAM_ID,
+        SplStakePoolUnstakeKeys::PROGRAM_ID,
+    ),
+    (
+        "DRIFT_PROGRAM_ID",
+        beethoven::drift::DRIFT_PROGRAM_ID,
+        DriftPerpKeys::PROGRAM_ID,
+    ),
+];
+
+/// `(program_id_const, program id)` of every protocol in `protocols.toml`, resolved the
+/// way `crates/core/build.rs` does: a `BEETHOVEN_<CONST>` override, then the devnet id
+/// with the `devnet` feature, then the mainnet id, then the all-zero placeholder.
+fn expected_program_ids() -> Vec<(String, Address)> {
+    let contents = std::fs::read_to_string(PROTOCOLS_PATH)
+        .unwrap_or_else(|_| panic!("Failed to read {PROTOCOLS_PATH}"));
+
+    let mut protocols: Vec<(String, Option<String>, Option<String>)> = vec![];
+    for line in contents.lines().map(str::trim) {
+        let Some((key, value)) = line.split_once(" = ") else {
+            continue;
+        };
+        let value = value.trim_matches('"').to_string();
+        match (key, protocols.last_mut()) {
+            ("program_id_const", _) => protocols.push((value, None, None)),
+            ("program_id", Some(protocol)) => protocol.1 = Some(value),
+            ("devnet_program_id", Some(protocol)) => protocol.2 = Some(value),
+            _ => {}
+        }
+    }
+
+    protocols
+        .into_iter()
+        .map(|(name, mainnet, devnet)| {
+            let id = std::env::var(format!("BEETHOVEN_{name}"))
+                .ok()
+                .or(devnet.filter(|_| cfg!(feature = "devnet")))
+                .or(mainnet)
+                .map_or(Address::default(), |id| {
+                    Address::from_str(&id).unwrap_or_else(|_| panic!("invalid {name}: {id}"))
+                });
+            (name, id)
+        })
+        .collect()
+}
+
+#[test]
+fn test_program_ids_match_protocols_toml() {
+    let expected = expected_program_ids();
+    assert_eq!(
+        expected.len(),
+        ADAPTER_IDS.len(),
+        "protocols.toml and ADAPTER_IDS list different protocols"
+    );
+
+    for (name, expected) in expected {
+        let (_, adapter, client) = ADAPTER_IDS
+            .iter()
+            .find(|(const_name, ..)| *const_name == name)
+            .unwrap_or_else(|| panic!("{name} is missing from ADAPTER_IDS"));
+        assert_eq!(*adapter, expected, "adapter {name}");
+        assert_eq!(*client, expected, "client {name}");
+    }
+}
+
+#[test]
+fn test_client_protocols_match_discriminants() {
+    // In discriminant order, as `protocols.toml` lists them
+    let protocols = [
+        KaminoDepositKeys::PROTOCOL,
+        JupiterEarnDepositKeys::PROTOCOL,
+        PerenaSwapKeys::PROTOCOL,
+        SolFiSwapKeys::PROTOCOL,
+        SolFiV2SwapKeys::PROTOCOL,
+        ManifestSwapKeys::PROTOCOL,
+        HeavenSwapKeys::PROTOCOL,
+        AldrinSwapKeys::PROTOCOL,
+        AldrinV2SwapKeys::PROTOCOL,
+        FutarchySwapKeys::PROTOCOL,
+        GammaSwapKeys::PROTOCOL,
+        MockDexSwapKeys::PROTOCOL,
+        RaydiumCpmmAddLiquidityKeys::PROTOCOL,
+        MarinadeStakeKeys::PROTOCOL,
+        SplStakePoolUnstakeKeys::PROTOCOL,
+        DriftPerpKeys::PROTOCOL,
+    ];
+    for (discriminant, protocol) in protocols.into_iter().enumerate() {
+        assert_eq!(ProtocolKind::from_u8(discriminant as u8), Some(protocol));
+    }
+}
+
+#[test]
+fn test_protocol_kind_program_ids_and_names() {
+    // `ADAPTER_IDS` is in discriminant order too
+    for (discriminant, (name, adapter, _)) in ADAPTER_IDS.iter().enumerate() {
+        let protocol = ProtocolKind::from_u8(discriminant as u8).unwrap();
+        assert_eq!(protocol.program_id(), *adapter, "{name}");
+        assert_eq!(protocol.name(), format!("{protocol:?}"));
+    }
+}
@@ -0,0 +1,308 @@
+//! Routes through the test program's route executors over mock DEX pools, each selling
+//! one fresh mint for the next, in one instruction.
+//!
+//! The executors swap legs in order against their own account slices, so a leg can
+//! spend what the leg before it just received. `ROUTE` fixes each leg's `in_amount` in
+//! the instruction, so its legs are sized from the pools' quotes.
+
+use {
+    crate::helper::*,
+    beethoven::{
+        mock_dex::{
+            quote::quote_exact_in, MockDexSwapData, MOCK_DEX_PROGRAM_ID, SLIPPAGE_EXCEEDED_ERROR,
+        },
+        BeethovenError, ProtocolKind,
+    },
+    beethoven_client::{
+        mock_dex::{pool_account_data, pool_authority_address, MockDexSwapKeys},
+        planner::{RouteLeg, RoutePlan},
+        test_program::{route_instruction, shared_swap_route_instruction, swap_route_instruction},
+        AdapterKeys, EncodeSwapData,
+    },
+    litesvm::LiteSVM,
+    solana_address::Address,
+    solana_instruction::AccountMeta,
+    solana_keypair::Keypair,
+    solana_signer::Signer,
+};
+
+const FEE_BPS: u16 = 30;
+/// Reserves of each mint in the pools of [`mock_dex_hops`]
+const HOP_RESERVE: u64 = 1_000_000_000_000;
+const HOP_IN: u64 = 1_000_000;
+
+/// Mock DEX pools selling each of `mints` for the next, with `payer` spending `HOP_IN`
+/// of the first from a fresh token account and each hop's output account the next
+/// hop's input.
+fn mock_dex_hops(svm: &mut LiteSVM, payer: &Keypair, mints: &[Address]) -> Vec<MockDexSwapKeys> {
+    let mut user_base_token = create_token_account(svm, &payer.pubkey(), &mints[0], HOP_IN);
+    let mut hops = Vec::new();
+    for pair in mints.windows(2) {
+        let pool = Keypair::new().pubkey();
+        let (pool_authority, _) = pool_authority_address(&pool);
+        let base_vault = create_token_account(svm, &pool_authority, &pair[0], HOP_RESERVE);
+        let quote_vault = create_token_account(svm, &pool_authority, &pair[1], HOP_RESERVE);
+        create_mock_account_at(
+            svm,
+            pool,
+            &MOCK_DEX_PROGRAM_ID,
+            pool_account_data(
+                &pool,
+                &pair[0],
+                &pair[1],
+                &base_vault,
+                &quote_vault,
+                FEE_BPS,
+            ),
+        );
+
+        let hop = MockDexSwapKeys {
+            user: payer.pubkey(),
+            pool,
+            pool_authority,
+            base_vault,
+            quote_vault,
+            user_base_token,
+            user_quote_token: create_token_account(svm, &payer.pubkey(), &pair[1], 0),
+            token_program: TOKEN_PROGRAM_ID,
+        };
+        user_base_token = hop.user_quote_token;
+        hops.push(hop);
+    }
+    hops
+}
+
+struct HopRoute {
+    svm: LiteSVM,
+    payer: Keypair,
+    hops: Vec<MockDexSwapKeys>,
+}
+
+/// Both programs and `hops` mock DEX pools chained over fresh mints.
+fn hop_route(hops: usize) -> HopRoute {
+    let mut svm = setup_svm();
+    let payer = Keypair::new();
+    svm.airdrop(&payer.pubkey(), 10_000_000_000).unwrap();
+    load_program(&mut svm, TEST_PROGRAM_ID, &test_program_path());
+    load_program(&mut svm, MOCK_DEX_PROGRAM_ID, &mock_dex_program_path());
+
+    let mints: Vec<Address> = (0..=hops)
+        .map(|_| create_mint(&mut svm, &payer.pubkey(), 6))
+        .collect();
+    let hops = mock_dex_hops(&mut svm, &payer, &mints);
+    HopRoute { svm, payer, hops }
+}
+
+/// What a hop's pool returns for `in_amount`.
+fn hop_out_amount(in_amount: u64) -> u64 {
+    quote_exact_in(FEE_BPS, in_amount, HOP_RESERVE, HOP_RESERVE)
+        .unwrap()
+        .out_amount
+}
+
+/// Each hop selling its base for its quote, as `swap_route_instruction` takes them.
+fn swap_route_legs(hops: &[MockDexSwapKeys]) -> Vec<(Vec<AccountMeta>, Vec<u8>)> {
+    let data = MockDexSwapData {
+        is_quote_to_base: false,
+    };
+    hops.iter()
+        .map(|hop| (hop.routed_account_metas(), data.encode()))
+        .collect()
+}
+
+/// `HOP_IN` through both hops, each leg spending all the one before it returned, with
+/// the last leg requiring `minimum_out`.
+fn two_hop_route(route: &HopRoute, minimum_out: u64) -> RoutePlan {
+    let middle_out = hop_out_amount(HOP_IN);
+    let out = hop_out_amount(middle_out);
+    let [first, second] = &route.hops[..] else {
+        panic!("two hops expected");
+    };
+
+    let data = MockDexSwapData {
+        is_quote_to_base: false,
+    };
+
+    // `RoutePlan` models splits of one input, but the encoding carries any legs, and
+    // the executor runs them in order
+    RoutePlan {
+        input_mint: token_mint(&route.svm, &first.user_base_token),
+        output_mint: token_mint(&route.svm, &second.user_quote_token),
+        in_amount: HOP_IN,
+        out_amount: out,
+        legs: vec![
+            RouteLeg {
+                protocol: ProtocolKind::MockDex,
+                in_amount: HOP_IN,
+                out_amount: middle_out,
+                minimum_out_amount: middle_out,
+                accounts: first.routed_account_metas(),
+                data: data.encode(),
+            },
+            RouteLeg {
+                protocol: ProtocolKind::MockDex,
+                in_amount: middle_out,
+                out_amount: out,
+                minimum_out_amount: minimum_out,
+                accounts: second.routed_account_metas(),
+                data: data.encode(),
+            },
+        ],
+    }
+}
+
+#[test]
+fn test_route_swaps_each_leg_in_order() {
+    let mut route = hop_route(2);
+    let middle_out = hop_out_amount(HOP_IN);
+    let out = hop_out_amount(middle_out);
+    let plan = two_hop_route(&route, out);
+
+    let instruction = route_instruction(TEST_PROGRAM_ID, &plan.encode());
+    send_transaction(&mut route.svm, &route.payer, instruction).expect("Route CPI failed");
+
+    let HopRoute { svm, hops, .. } = &route;
+    // The input is spent and the middle mint only passes through the user's account
+    assert_eq!(token_balance(svm, &hops[0].user_base_token), 0);
+    assert_eq!(token_balance(svm, &hops[0].user_quote_token), 0);
+    assert_eq!(
+        token_balance(svm, &hops[0].base_vault),
+        HOP_RESERVE + HOP_IN
+    );
+    assert_eq!(
+        token_balance(svm, &hops[0].quote_vault),
+        HOP_RESERVE - middle_out
+    );
+    assert_eq!(
+        token_balance(svm, &hops[1].base_vault),
+        HOP_RESERVE + middle_out
+    );
+    assert_eq!(token_balance(svm, &hops[1].quote_vault), HOP_RESERVE - out);
+    assert_eq!(token_balance(svm, &hops[1].user_quote_token), out);
+}
+
+#[test]
+fn test_route_enforces_final_minimum_out() {
+    let mut route = hop_route(2);
+    let out = hop_out_amount(hop_out_amount(HOP_IN));
+    let plan = two_hop_route(&route, out + 1);
+
+    let instruction = route_instruction(TEST_PROGRAM_ID, &plan.encode());
+    let error = send_transaction(&mut route.svm, &route.payer, instruction).unwrap_err();
+    assert!(
+        error.contains(&format!("Custom({SLIPPAGE_EXCEEDED_ERROR})")),
+        "unexpected error: {error}"
+    );
+
+    // The first leg already ran in the failed instruction and is rolled back with it
+    let HopRoute { svm, hops, .. } = &route;
+    assert_eq!(token_balance(svm, &hops[0].user_base_token), HOP_IN);
+    assert_eq!(token_balance(svm, &hops[0].user_quote_token), 0);
+    assert_eq!(token_balance(svm, &hops[1].user_quote_token), 0);
+}
+
+#[test]
+fn test_swap_route_threads_each_leg_output() {
+    let mut route = hop_route(2);
+    let middle_out = hop_out_amount(HOP_IN);
+    let out = hop_out_amount(middle_out);
+
+    // Only the route's input and final minimum are given; the second leg is sized
+    // on-chain from what the first credited
+    let instruction =
+        swap_route_instruction(TEST_PROGRAM_ID, HOP_IN, out, &swap_route_legs(&route.hops));
+    send_transaction(&mut route.svm, &route.payer, instruction).expect("Swap route CPI failed");
+
+    let HopRoute { svm, hops, .. } = &route;
+    assert_eq!(token_balance(svm, &hops[0].user_base_token), 0);
+    assert_eq!(token_balance(svm, &hops[0].user_quote_token), 0);
+    assert_eq!(
+        token_balance(svm, &hops[1].base_vault),
+        HOP_RESERVE + middle_out
+    );
+    assert_eq!(token_balance(svm, &hops[1].user_quote_token), out);
+}
+
+#[test]
+fn test_swap_route_enforces_overall_minimum_out() {
+    let mut route = hop_route(2);
+    let out = hop_out_amount(hop_out_amount(HOP_IN));
+
+    let instruction = swap_route_instruction(
+        TEST_PROGRAM_ID,
+        HOP_IN,
+        out + 1,
+        &swap_route_legs(&route.hops),
+    );
+    let error = send_transaction(&mut route.svm, &route.payer, instruction).unwrap_err();
+    assert!(
+        error.contains(&format!(
+            "Custom({})",
+            BeethovenError::SlippageExceeded.code()
+        )),
+        "unexpected error: {error}"
+    );
+    assert_eq!(
+        token_balance(&route.svm, &route.hops[0].user_base_token),
+        HOP_IN
+    );
+}
+
+#[test]
+fn test_swap_route_rejects_leg_spending_another_account() {
+    let mut route = hop_route(2);
+    // A second account of the middle mint the first leg never credits
+    let middle_mint = token_mint(&route.svm, &route.hops[0].user_quote_token);
+    let payer = route.payer.pubkey();
+    route.hops[1].user_base_token = create_token_account(&mut route.svm, &payer, &middle_mint, 0);
+
+    let instruction =
+        swap_route_instruction(TEST_PROGRAM_ID, HOP_IN, 0, &swap_route_legs(&route.hops));
+    let error = send_transaction(&mut route.svm, &route.payer, instruction).unwrap_err();
+    assert!(
+        error.contains(&format!(
+            "Custom({})",
+            BeethovenError::RouteLegMismatch.code()
+        )),
+        "unexpected error: {error}"
+    );
+}
+
+#[test]
+fn test_shared_swap_route_passes_each_account_once() {
+    let mut svm = setup_svm();
+    let payer = Keypair::new();
+    svm.airdrop(&payer.pubkey(), 10_000_000_000).unwrap();
+    load_program(&mut svm, TEST_PROGRAM_ID, &test_program_path());
+    load_program(&mut svm, MOCK_DEX_PROGRAM_ID, &mock_dex_program_path());
+
+    let mints: Vec<Address> = (0..4)
+        .map(|_| create_mint(&mut svm, &payer.pubkey(), 6))
+        .collect();
+    let hops = mock_dex_hops(&mut svm, &payer, &mints);
+    let data = MockDexSwapData {
+        is_quote_to_base: false,
+    };
+    let legs: Vec<_> = hops
+        .iter()
+        .map(|hop| (hop.routed_account_metas(), data.encode()))
+        .collect();
+    let out = hops.iter().fold(HOP_IN, |amount, _| {
+        quote_exact_in(FEE_BPS, amount, HOP_RESERVE, HOP_RESERVE)
+            .unwrap()
+            .out_amount
+    });
+
+    // The program, user and token program are passed once instead of three times, and
+    // the two intermediate token accounts once instead of twice
+    let flat = swap_route_instruction(TEST_PROGRAM_ID, HOP_IN, out, &legs);
+    let shared = shared_swap_route_instruction(TEST_PROGRAM_ID, HOP_IN, out, &legs);
+    assert_eq!(shared.accounts.len(), flat.accounts.len() - 8);
+
+    send_transaction(&mut svm, &payer, shared).expect("Shared swap route CPI failed");
+
+    assert_eq!(token_balance(&svm, &hops[0].user_base_token), 0);
+    assert_eq!(token_balance(&svm, &hops[0].user_quote_token), 0);
+    assert_eq!(token_balance(&svm, &hops[1].user_quote_token), 0);
+    assert_eq!(token_balance(&svm, &hops[2].user_quote_token), out);
+}
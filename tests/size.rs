@@ -0,0 +1,20 @@
+use crate::helper::*;
+
+/// program-test feature sets built by `make size`, named as in `tests/size_baselines.json`
+const SIZE_FEATURE_SETS: &[&str] = &["deposit", "swap", "deposit+swap"];
+
+#[test]
+fn test_program_size_per_feature_set() {
+    for name in SIZE_FEATURE_SETS {
+        let path = format!(
+            "{}/target/size/beethoven_test-{}.so",
+            env!("CARGO_MANIFEST_DIR"),
+            name
+        );
+        let Ok(metadata) = std::fs::metadata(&path) else {
+            println!("Skipping {}: {} not built; run `make size`", name, path);
+            continue;
+        };
+        assert_size_within_baseline(name, metadata.len());
+    }
+}
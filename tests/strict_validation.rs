@@ -0,0 +1,351 @@
+//! The `strict-validation` checks, run through the test program built with the feature
+//! (`make build-program-strict`): each misconfiguration must fail before the venue is
+//! invoked, with its own error.
+//!
+//! No venue program is deployed, so an instruction that gets past the checks fails at
+//! the CPI instead, which is how the matching-account cases show a check only fires on
+//! the mistake it is for.
+
+use {
+    crate::helper::*,
+    beethoven::{kamino::LENDING_MARKET_AUTHORITY_SEED, BeethovenError},
+    beethoven_client::{
+        aldrin::AldrinSwapKeys, aldrin_v2::AldrinV2SwapKeys, futarchy::FutarchySwapKeys,
+        gamma::GammaSwapKeys, heaven::HeavenSwapKeys, jupiter::JupiterEarnDepositKeys,
+        kamino::KaminoDepositKeys, manifest::ManifestSwapKeys, mock_dex::MockDexSwapKeys,
+        perena::PerenaSwapKeys, solfi::SolFiSwapKeys, solfi_v2::SolFiV2SwapKeys,
+        test_program::authority_address, AdapterKeys,
+    },
+    litesvm::LiteSVM,
+    solana_address::Address,
+    solana_keypair::Keypair,
+    solana_signer::Signer,
+};
+
+/// Swap data every swap adapter accepts; Aldrin and Aldrin V2 read it as `Side::Bid`
+/// and Futarchy as `SwapType::Buy`
+const VALID_SWAP_DATA: [u8; 2] = [0, 1];
+/// `Side::Ask` / `SwapType::Sell`: spends the base token
+const SELL_BASE: [u8; 1] = [1];
+
+const MISSING_SIGNATURE: &str = "MissingRequiredSignature";
+
+fn setup() -> Option<(LiteSVM, Keypair)> {
+    let Some(path) = strict_test_program_path() else {
+        println!("Skipping: the strict-validation test program is not built; run `make build-program-strict`");
+        return None;
+    };
+    let mut svm = setup_svm();
+    let payer = Keypair::new();
+    svm.airdrop(&payer.pubkey(), 10_000_000_000).unwrap();
+    load_program(&mut svm, TEST_PROGRAM_ID, &path);
+    Some((svm, payer))
+}
+
+/// Swap on `K` with its signer accounts held by someone who did not sign.
+fn swap_without_authority<K: AdapterKeys>(
+    svm: &mut LiteSVM,
+    payer: &Keypair,
+) -> Result<u64, String> {
+    let mut metas = routed_metas::<K>(&Keypair::new().pubkey());
+    for meta in &mut metas {
+        meta.is_signer = false;
+    }
+    let instruction = build_swap_instruction(metas, 1_000, 1, &VALID_SWAP_DATA);
+    send_transaction(svm, payer, instruction)
+}
+
+/// Sell base on `K` from a user base account of `user_mint` into a base vault of
+/// `vault_mint`.
+fn sell_base<K: AdapterKeys>(
+    svm: &mut LiteSVM,
+    payer: &Keypair,
+    (user_account, vault_account): (&str, &str),
+    user_mint: &Address,
+    vault_mint: &Address,
+) -> Result<u64, String> {
+    let mut metas = routed_metas::<K>(&payer.pubkey());
+    routed_meta::<K>(&mut metas, user_account).pubkey =
+        create_token_account(svm, &payer.pubkey(), user_mint, 1_000);
+    routed_meta::<K>(&mut metas, vault_account).pubkey =
+        create_token_account(svm, &Keypair::new().pubkey(), vault_mint, 1_000_000);
+
+    let instruction = build_swap_instruction(metas, 1_000, 1, &SELL_BASE);
+    send_transaction(svm, payer, instruction)
+}
+
+/// Deposit on `K` funded from `source` holding tokens of `owner`.
+fn deposit_from<K: AdapterKeys>(
+    svm: &mut LiteSVM,
+    payer: &Keypair,
+    source: &str,
+    owner: &Address,
+) -> Result<u64, String> {
+    let mut metas = routed_metas::<K>(&payer.pubkey());
+    routed_meta::<K>(&mut metas, source).pubkey =
+        create_token_account(svm, owner, &Keypair::new().pubkey(), 1_000);
+    let instruction = build_deposit_instruction(metas, 1_000);
+    send_transaction(svm, payer, instruction)
+}
+
+#[test]
+fn test_strict_swaps_require_authority_signature() {
+    let Some((mut svm, payer)) = setup() else {
+        return;
+    };
+
+    let results = [
+        swap_without_authority::<PerenaSwapKeys>(&mut svm, &payer),
+        swap_without_authority::<SolFiSwapKeys>(&mut svm, &payer),
+        swap_without_authority::<SolFiV2SwapKeys>(&mut svm, &payer),
+        swap_without_authority::<ManifestSwapKeys>(&mut svm, &payer),
+        swap_without_authority::<HeavenSwapKeys>(&mut svm, &payer),
+        swap_without_authority::<AldrinSwapKeys>(&mut svm, &payer),
+        swap_without_authority::<AldrinV2SwapKeys>(&mut svm, &payer),
+        swap_without_authority::<FutarchySwapKeys>(&mut svm, &payer),
+        swap_without_authority::<GammaSwapKeys>(&mut svm, &payer),
+        swap_without_authority::<MockDexSwapKeys>(&mut svm, &payer),
+    ];
+    for result in results {
+        assert_fails_with(result, MISSING_SIGNATURE);
+    }
+}
+
+/// Swap on MockDex through `SWAP_SIGNED`, the test program signing for its authority
+/// PDA, with `user` as the trader.
+fn signed_swap_as(svm: &mut LiteSVM, payer: &Keypair, user: Address) -> Result<u64, String> {
+    let mut metas = routed_metas::<MockDexSwapKeys>(&payer.pubkey());
+    let user_meta = routed_meta::<MockDexSwapKeys>(&mut metas, "user");
+    user_meta.pubkey = user;
+    user_meta.is_signer = false;
+    let mut instruction = build_swap_instruction(metas, 1_000, 1, &VALID_SWAP_DATA);
+    instruction.data[0] = discriminator::SWAP_SIGNED;
+    send_transaction(svm, payer, instruction)
+}
+
+#[test]
+fn test_strict_signer_seeds_must_derive_the_authority() {
+    let Some((mut svm, payer)) = setup() else {
+        return;
+    };
+
+    // The program signs for its own PDA, not for whichever account is passed
+    let result = signed_swap_as(&mut svm, &payer, Keypair::new().pubkey());
+    assert_fails_with(result, MISSING_SIGNATURE);
+
+    let (authority, _) = authority_address(&TEST_PROGRAM_ID);
+    let error = signed_swap_as(&mut svm, &payer, authority)
+        .expect_err("swap on an undeployed venue succeeded");
+    assert!(!error.contains(MISSING_SIGNATURE), "unexpected {error}");
+}
+
+#[test]
+fn test_strict_swap_direction_must_match_user_accounts() {
+    let Some((mut svm, payer)) = setup() else {
+        return;
+    };
+    let base_mint = Keypair::new().pubkey();
+    let quote_mint = Keypair::new().pubkey();
+
+    let aldrin_accounts = ("user_base_token_account", "base_token_vault");
+    let futarchy_accounts = ("user_base_account", "amm_base_vault");
+
+    // Selling base from the user's quote account
+    let mismatched = [
+        sell_base::<AldrinSwapKeys>(&mut svm, &payer, aldrin_accounts, &quote_mint, &base_mint),
+        sell_base::<AldrinV2SwapKeys>(&mut svm, &payer, aldrin_accounts, &quote_mint, &base_mint),
+        sell_base::<FutarchySwapKeys>(&mut svm, &payer, futarchy_accounts, &quote_mint, &base_mint),
+    ];
+    for result in mismatched {
+        assert_beethoven_error(result, BeethovenError::DirectionMismatch);
+    }
+
+    // With the mints lined up the check passes, and the missing venue fails the CPI
+    let matched = [
+        sell_base::<AldrinSwapKeys>(&mut svm, &payer, aldrin_accounts, &base_mint, &base_mint),
+        sell_base::<AldrinV2SwapKeys>(&mut svm, &payer, aldrin_accounts, &base_mint, &base_mint),
+        sell_base::<FutarchySwapKeys>(&mut svm, &payer, futarchy_accounts, &base_mint, &base_mint),
+    ];
+    let direction_mismatch = format!("Custom({})", BeethovenError::DirectionMismatch.code());
+    for result in matched {
+        let error = result.expect_err("swap on an undeployed venue succeeded");
+        assert!(!error.contains(&direction_mismatch), "unexpected {error}");
+    }
+}
+
+#[test]
+fn test_strict_deposit_source_must_belong_to_depositor() {
+    let Some((mut svm, payer)) = setup() else {
+        return;
+    };
+    let deposits: [(
+        &str,
+        fn(&mut LiteSVM, &Keypair, &Address) -> Result<u64, String>,
+    ); 2] = [
+        ("kamino", |svm, payer, owner| {
+            deposit_from::<KaminoDepositKeys>(svm, payer, "user_source_liquidity", owner)
+        }),
+        ("jupiter", |svm, payer, owner| {
+            deposit_from::<JupiterEarnDepositKeys>(svm, payer, "depositor_token_account", owner)
+        }),
+    ];
+
+    for (venue, deposit) in deposits {
+        let someone_else = Keypair::new().pubkey();
+        assert_beethoven_error(
+            deposit(&mut svm, &payer, &someone_else),
+            BeethovenError::TokenAccountOwnerMismatch,
+        );
+
+        let error = deposit(&mut svm, &payer, &payer.pubkey())
+            .expect_err("deposit on an undeployed venue succeeded");
+        let owner_mismatch = format!(
+            "Custom({})",
+            BeethovenError::TokenAccountOwnerMismatch.code()
+        );
+        assert!(
+            !error.contains(&owner_mismatch),
+            "{venue}: unexpected {error}"
+        );
+    }
+}
+
+/// Deposit on Kamino from `payer`'s own tokens, through `lending_market` and
+/// `lending_market_authority`.
+fn kamino_deposit_through(
+    svm: &mut LiteSVM,
+    payer: &Keypair,
+    lending_market: Address,
+    lending_market_authority: Address,
+) -> Result<u64, String> {
+    let mut metas = routed_metas::<KaminoDepositKeys>(&payer.pubkey());
+    routed_meta::<KaminoDepositKeys>(&mut metas, "user_source_liquidity").pubkey =
+        create_token_account(svm, &payer.pubkey(), &Keypair::new().pubkey(), 1_000);
+    routed_meta::<KaminoDepositKeys>(&mut metas, "lending_market").pubkey = lending_market;
+    routed_meta::<KaminoDepositKeys>(&mut metas, "lending_market_authority").pubkey =
+        lending_market_authority;
+    let instruction = build_deposit_instruction(metas, 1_000);
+    send_transaction(svm, payer, instruction)
+}
+
+#[test]
+fn test_strict_kamino_lending_market_authority_must_be_derived() {
+    let Some((mut svm, payer)) = setup() else {
+        return;
+    };
+
+    // A lending market storing its authority's bump after the discriminator and version
+    let lending_market = Keypair::new().pubkey();
+    let (authority, bump) = Address::find_program_address(
+        &[LENDING_MARKET_AUTHORITY_SEED, lending_market.as_ref()],
+        &KaminoDepositKeys::PROGRAM_ID,
+    );
+    let mut data = vec![0; 64];
+    data[16] = bump;
+    create_mock_account_at(
+        &mut svm,
+        lending_market,
+        &KaminoDepositKeys::PROGRAM_ID,
+        data,
+    );
+
+    let other_market = Keypair::new().pubkey();
+    let result = kamino_deposit_through(&mut svm, &payer, lending_market, other_market);
+    assert_fails_with(result, "InvalidSeeds");
+
+    let error = kamino_deposit_through(&mut svm, &payer, lending_market, authority)
+        .expect_err("deposit on an undeployed venue succeeded");
+    assert!(!error.contains("InvalidSeeds"), "unexpected {error}");
+}
+
+/// Swap on SolFi through the test program's `kind` swap instruction, with
+/// `token_program` at `token_program_id` and a random instructions sysvar.
+fn solfi_swap_through(
+    svm: &mut LiteSVM,
+    payer: &Keypair,
+    kind: u8,
+    token_program_id: Address,
+) -> Result<u64, String> {
+    let mut metas = routed_metas::<SolFiSwapKeys>(&payer.pubkey());
+    routed_meta::<SolFiSwapKeys>(&mut metas, "token_program").pubkey = token_program_id;
+    let mut instruction = build_swap_instruction(metas, 1_000, 1, &VALID_SWAP_DATA);
+    instruction.data[0] = kind;
+    send_transaction(svm, payer, instruction)
+}
+
+#[test]
+fn test_strict_program_and_sysvar_accounts_must_match() {
+    let Some((mut svm, payer)) = setup() else {
+        return;
+    };
+
+    let result = solfi_swap_through(
+        &mut svm,
+        &payer,
+        discriminator::SWAP,
+        Keypair::new().pubkey(),
+    );
+    assert_fails_with(result, "IncorrectProgramId");
+
+    // With the token program right, the random instructions sysvar is caught next
+    let result = solfi_swap_through(&mut svm, &payer, discriminator::SWAP, TOKEN_PROGRAM_ID);
+    assert_fails_with(result, "UnsupportedSysvar");
+}
+
+#[test]
+fn test_strict_passthrough_checks_program_and_sysvar_accounts() {
+    let Some((mut svm, payer)) = setup() else {
+        return;
+    };
+
+    // `swap_signed_passthrough` never builds the typed accounts, but checks them the same
+    let result = solfi_swap_through(
+        &mut svm,
+        &payer,
+        discriminator::SWAP_PASSTHROUGH,
+        Keypair::new().pubkey(),
+    );
+    assert_fails_with(result, "IncorrectProgramId");
+
+    let result = solfi_swap_through(
+        &mut svm,
+        &payer,
+        discriminator::SWAP_PASSTHROUGH,
+        TOKEN_PROGRAM_ID,
+    );
+    assert_fails_with(result, "UnsupportedSysvar");
+}
+
+#[test]
+fn test_strict_deposit_source_must_be_a_token_account() {
+    let Some((mut svm, payer)) = setup() else {
+        return;
+    };
+
+    // `routed_metas` leaves the source at an address nothing was ever created at
+    let metas = routed_metas::<JupiterEarnDepositKeys>(&payer.pubkey());
+    let instruction = build_deposit_instruction(metas, 1_000);
+    assert_fails_with(
+        send_transaction(&mut svm, &payer, instruction),
+        "InvalidAccountOwner",
+    );
+}
+
+#[test]
+fn test_strict_errors_are_distinct() {
+    let errors = [
+        MISSING_SIGNATURE.to_string(),
+        format!("Custom({})", BeethovenError::DirectionMismatch.code()),
+        format!(
+            "Custom({})",
+            BeethovenError::TokenAccountOwnerMismatch.code()
+        ),
+        "InvalidSeeds".to_string(),
+        "IncorrectProgramId".to_string(),
+        "UnsupportedSysvar".to_string(),
+        "InvalidAccountOwner".to_string(),
+    ];
+    for (index, error) in errors.iter().enumerate() {
+        assert!(!errors[index + 1..].contains(error), "{error} is shared");
+    }
+}
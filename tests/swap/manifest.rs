@@ -1,8 +1,8 @@
 use {
     crate::helper::*,
+    beethoven_client::swap_accounts,
     solana_account::Account,
     solana_address::Address,
-    solana_instruction::AccountMeta,
     solana_keypair::Keypair,
     solana_program_option::COption,
     solana_program_pack::Pack,
@@ -149,23 +149,22 @@ fn test_manifest_swap_cpi() {
     let in_amount = 100_000_000u64; // 0.1 SOL
     let min_out_amount = 1u64; // Very loose slippage for test
 
-    let accounts = vec![
-        AccountMeta::new_readonly(MANIFEST_PROGRAM_ID, false), // manifest_program (for detection)
-        AccountMeta::new(payer.pubkey(), true),                // payer
-        AccountMeta::new_readonly(payer.pubkey(), true),       // owner
-        AccountMeta::new(market, false),                       // market
-        AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),   // system_program
-        AccountMeta::new(trader_base, false),                  // trader_base (SOL)
-        AccountMeta::new(trader_quote, false),                 // trader_quote (USDC)
-        AccountMeta::new(base_vault, false),                   // base_vault
-        AccountMeta::new(quote_vault, false),                  // quote_vault
-        AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),    // token_program_base
-        AccountMeta::new_readonly(wsol_mint, false),           // base_mint
-        AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),    // token_program_quote
-        AccountMeta::new_readonly(usdc_mint, false),           // quote_mint
-        AccountMeta::new(global, false),                       // global
-        AccountMeta::new(global_vault, false),                 // global_vault
-    ];
+    let accounts = swap_accounts!(manifest, {
+        payer: payer.pubkey(),
+        owner: payer.pubkey(),
+        market,
+        system_program: SYSTEM_PROGRAM_ID,
+        trader_base,
+        trader_quote,
+        base_vault,
+        quote_vault,
+        token_program_base: TOKEN_PROGRAM_ID,
+        base_mint: wsol_mint,
+        token_program_quote: TOKEN_PROGRAM_ID,
+        quote_mint: usdc_mint,
+        global,
+        global_vault,
+    });
 
     // is_base_in=true (selling base/SOL), is_exact_in=true (exact input amount)
     let extra_data = [1u8, 1u8];
@@ -284,23 +283,22 @@ fn test_manifest_swap_cpi_mollusk() {
     let in_amount = 100_000_000u64; // 0.1 SOL
     let min_out_amount = 1u64; // Very loose slippage for test
 
-    let account_metas = vec![
-        AccountMeta::new_readonly(MANIFEST_PROGRAM_ID, false), // manifest_program (for detection)
-        AccountMeta::new(payer, true),                         // payer
-        AccountMeta::new_readonly(payer, true),                // owner
-        AccountMeta::new(market_addr, false),                  // market
-        AccountMeta::new_readonly(solana_sdk_ids::system_program::ID, false), // system_program
-        AccountMeta::new(trader_base_addr, false),             // trader_base (SOL)
-        AccountMeta::new(trader_quote_addr, false),            // trader_quote (USDC)
-        AccountMeta::new(base_vault_addr, false),              // base_vault
-        AccountMeta::new(quote_vault_addr, false),             // quote_vault
-        AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),    // token_program_base
-        AccountMeta::new_readonly(wsol_mint_addr, false),      // base_mint
-        AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),    // token_program_quote
-        AccountMeta::new_readonly(usdc_mint_addr, false),      // quote_mint
-        AccountMeta::new(global_addr, false),                  // global
-        AccountMeta::new(global_vault_addr, false),            // global_vault
-    ];
+    let account_metas = swap_accounts!(manifest, {
+        payer,
+        owner: payer,
+        market: market_addr,
+        system_program: solana_sdk_ids::system_program::ID,
+        trader_base: trader_base_addr,
+        trader_quote: trader_quote_addr,
+        base_vault: base_vault_addr,
+        quote_vault: quote_vault_addr,
+        token_program_base: TOKEN_PROGRAM_ID,
+        base_mint: wsol_mint_addr,
+        token_program_quote: TOKEN_PROGRAM_ID,
+        quote_mint: usdc_mint_addr,
+        global: global_addr,
+        global_vault: global_vault_addr,
+    });
 
     // is_base_in=true (selling base/SOL), is_exact_in=true (exact input amount)
     let extra_data = [1u8, 1u8];
@@ -335,6 +333,7 @@ fn test_manifest_swap_cpi_mollusk() {
 
     // Verify success
     assert_mollusk_success(&result);
+    assert_cu_within_baseline("manifest_swap", result.compute_units_consumed);
 
     // Check resulting account data
     for (pubkey, account) in &result.resulting_accounts {
@@ -0,0 +1,780 @@
+use {
+    crate::helper::*,
+    beethoven::{
+        envelope::ENVELOPE_V1,
+        mock_dex::{
+            quote::quote_exact_in, MockDexSwapData, MOCK_DEX_PROGRAM_ID, SLIPPAGE_EXCEEDED_ERROR,
+        },
+        token::FULL_BALANCE,
+        BeethovenError, Detection, ProtocolKind, SwapFees, SwapOutcome,
+    },
+    beethoven_client::{
+        fee::SwapFeeKeys,
+        mock_dex::{pool_account_data, pool_authority_address, MockDexSwapKeys},
+        planner::{RouteLeg, RoutePlan},
+        test_program::{
+            authority_address, detected_swap_instruction, enveloped_swap_instruction,
+            parse_swap_outcome, route_instruction, signed_swap_instruction,
+            swap_exact_out_instruction, swap_outcome_instruction, swap_with_fees_instruction,
+        },
+        AdapterKeys, EncodeSwapData,
+    },
+    litesvm::LiteSVM,
+    solana_address::Address,
+    solana_instruction::AccountMeta,
+    solana_keypair::Keypair,
+    solana_program_pack::Pack,
+    solana_signer::Signer,
+    solana_transaction::Transaction,
+    spl_token_interface::state::Account as TokenAccount,
+};
+
+const FEE_BPS: u16 = 30;
+const BASE_RESERVE: u64 = 1_000_000_000;
+const QUOTE_RESERVE: u64 = 150_000_000_000;
+
+#[cfg(feature = "upstream-bpf")]
+fn beethoven_program_path() -> String {
+    format!(
+        "{}/target/bpfel-unknown-none/release/libbeethoven_test.so",
+        env!("CARGO_MANIFEST_DIR")
+    )
+}
+
+#[cfg(not(feature = "upstream-bpf"))]
+fn beethoven_program_path() -> String {
+    format!(
+        "{}/target/deploy/beethoven_test.so",
+        env!("CARGO_MANIFEST_DIR")
+    )
+}
+
+fn get_token_balance(svm: &LiteSVM, token_account: &Address) -> u64 {
+    let account = svm
+        .get_account(token_account)
+        .expect("Token account not found");
+    TokenAccount::unpack(&account.data)
+        .expect("Failed to unpack token account")
+        .amount
+}
+
+/// Deploy both programs and a funded pool, returning the swap keys for `payer`, who
+/// holds `user_base` base tokens and no quote tokens.
+fn setup_pool(svm: &mut LiteSVM, payer: &Keypair, user_base: u64) -> MockDexSwapKeys {
+    load_program(svm, TEST_PROGRAM_ID, &beethoven_program_path());
+    load_program(svm, MOCK_DEX_PROGRAM_ID, &mock_dex_program_path());
+
+    let base_mint = create_mint(svm, &payer.pubkey(), 9);
+    let quote_mint = create_mint(svm, &payer.pubkey(), 6);
+
+    let (pool, pool_authority, base_vault, quote_vault) = create_pool(svm, &base_mint, &quote_mint);
+
+    MockDexSwapKeys {
+        user: payer.pubkey(),
+        pool,
+        pool_authority,
+        base_vault,
+        quote_vault,
+        user_base_token: create_token_account(svm, &payer.pubkey(), &base_mint, user_base),
+        user_quote_token: create_token_account(svm, &payer.pubkey(), &quote_mint, 0),
+        token_program: TOKEN_PROGRAM_ID,
+    }
+}
+
+/// A second funded pool trading the same pair as `keys`, for the same user accounts.
+fn add_pool(svm: &mut LiteSVM, keys: &MockDexSwapKeys) -> MockDexSwapKeys {
+    let (pool, pool_authority, base_vault, quote_vault) = create_pool(
+        svm,
+        &token_mint(svm, &keys.user_base_token),
+        &token_mint(svm, &keys.user_quote_token),
+    );
+
+    MockDexSwapKeys {
+        pool,
+        pool_authority,
+        base_vault,
+        quote_vault,
+        ..keys.clone()
+    }
+}
+
+/// A pool holding `BASE_RESERVE` and `QUOTE_RESERVE`, returned as its address, authority
+/// and base and quote vaults.
+fn create_pool(
+    svm: &mut LiteSVM,
+    base_mint: &Address,
+    quote_mint: &Address,
+) -> (Address, Address, Address, Address) {
+    let pool = Keypair::new().pubkey();
+    let (pool_authority, _) = pool_authority_address(&pool);
+    let base_vault = create_token_account(svm, &pool_authority, base_mint, BASE_RESERVE);
+    let quote_vault = create_token_account(svm, &pool_authority, quote_mint, QUOTE_RESERVE);
+    create_mock_account_at(
+        svm,
+        pool,
+        &MOCK_DEX_PROGRAM_ID,
+        pool_account_data(
+            &pool,
+            base_mint,
+            quote_mint,
+            &base_vault,
+            &quote_vault,
+            FEE_BPS,
+        ),
+    );
+    (pool, pool_authority, base_vault, quote_vault)
+}
+
+#[test]
+fn test_mock_dex_swap_cpi() {
+    let mut svm = setup_svm();
+    let payer = Keypair::new();
+    svm.airdrop(&payer.pubkey(), 10_000_000_000).unwrap();
+
+    let in_amount = 10_000_000;
+    let keys = setup_pool(&mut svm, &payer, in_amount);
+    let expected = quote_exact_in(FEE_BPS, in_amount, BASE_RESERVE, QUOTE_RESERVE).unwrap();
+
+    let data = MockDexSwapData {
+        is_quote_to_base: false,
+    };
+    let instruction = build_swap_instruction(
+        keys.routed_account_metas(),
+        in_amount,
+        expected.out_amount,
+        &data.encode(),
+    );
+    send_transaction(&mut svm, &payer, instruction).expect("Swap CPI failed");
+
+    assert_eq!(get_token_balance(&svm, &keys.user_base_token), 0);
+    assert_eq!(
+        get_token_balance(&svm, &keys.user_quote_token),
+        expected.out_amount
+    );
+    assert_eq!(
+        get_token_balance(&svm, &keys.base_vault),
+        BASE_RESERVE + in_amount
+    );
+    assert_eq!(
+        get_token_balance(&svm, &keys.quote_vault),
+        QUOTE_RESERVE - expected.out_amount
+    );
+}
+
+#[test]
+fn test_mock_dex_swap_cpi_rejects_slippage() {
+    let mut svm = setup_svm();
+    let payer = Keypair::new();
+    svm.airdrop(&payer.pubkey(), 10_000_000_000).unwrap();
+
+    let in_amount = 10_000_000;
+    let keys = setup_pool(&mut svm, &payer, in_amount);
+    let expected = quote_exact_in(FEE_BPS, in_amount, BASE_RESERVE, QUOTE_RESERVE).unwrap();
+
+    let data = MockDexSwapData {
+        is_quote_to_base: false,
+    };
+    let instruction = build_swap_instruction(
+        keys.routed_account_metas(),
+        in_amount,
+        expected.out_amount + 1,
+        &data.encode(),
+    );
+    let error = send_transaction(&mut svm, &payer, instruction).unwrap_err();
+
+    assert!(
+        error.contains(&format!("Custom({SLIPPAGE_EXCEEDED_ERROR})")),
+        "unexpected error: {error}"
+    );
+    assert_eq!(get_token_balance(&svm, &keys.user_base_token), in_amount);
+    assert_eq!(get_token_balance(&svm, &keys.user_quote_token), 0);
+}
+
+#[test]
+fn test_mock_dex_rejects_exact_out() {
+    let mut svm = setup_svm();
+    let payer = Keypair::new();
+    svm.airdrop(&payer.pubkey(), 10_000_000_000).unwrap();
+
+    let in_amount = 10_000_000;
+    let keys = setup_pool(&mut svm, &payer, in_amount);
+
+    let instruction = swap_exact_out_instruction(
+        TEST_PROGRAM_ID,
+        &keys,
+        1_000_000,
+        in_amount,
+        &MockDexSwapData {
+            is_quote_to_base: false,
+        },
+    );
+    assert_beethoven_error(
+        send_transaction(&mut svm, &payer, instruction),
+        BeethovenError::UnsupportedSwapMode,
+    );
+    assert_eq!(get_token_balance(&svm, &keys.user_base_token), in_amount);
+}
+
+#[test]
+fn test_mock_dex_swap_cpi_full_balance() {
+    let mut svm = setup_svm();
+    let payer = Keypair::new();
+    svm.airdrop(&payer.pubkey(), 10_000_000_000).unwrap();
+
+    let balance = 7_500_000;
+    let keys = setup_pool(&mut svm, &payer, balance);
+    let expected = quote_exact_in(FEE_BPS, balance, BASE_RESERVE, QUOTE_RESERVE).unwrap();
+
+    let data = MockDexSwapData {
+        is_quote_to_base: false,
+    };
+    let instruction = build_swap_instruction(
+        keys.routed_account_metas(),
+        FULL_BALANCE,
+        expected.out_amount,
+        &data.encode(),
+    );
+    send_transaction(&mut svm, &payer, instruction).expect("Full-balance swap CPI failed");
+
+    assert_eq!(get_token_balance(&svm, &keys.user_base_token), 0);
+    assert_eq!(
+        get_token_balance(&svm, &keys.user_quote_token),
+        expected.out_amount
+    );
+}
+
+#[test]
+fn test_mock_dex_swap_cpi_returns_outcome() {
+    let mut svm = setup_svm();
+    let payer = Keypair::new();
+    svm.airdrop(&payer.pubkey(), 10_000_000_000).unwrap();
+
+    let in_amount = 10_000_000;
+    // The user keeps some base tokens, so the outcome is a difference, not a balance
+    let keys = setup_pool(&mut svm, &payer, 3 * in_amount);
+    let expected = quote_exact_in(FEE_BPS, in_amount, BASE_RESERVE, QUOTE_RESERVE).unwrap();
+
+    let data = MockDexSwapData {
+        is_quote_to_base: false,
+    };
+    let instruction = swap_outcome_instruction(TEST_PROGRAM_ID, &keys, in_amount, 1, &data);
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&payer.pubkey()),
+        &[&payer],
+        svm.latest_blockhash(),
+    );
+    let meta = svm.send_transaction(tx).expect("Swap CPI failed");
+
+    assert_eq!(meta.return_data.program_id, TEST_PROGRAM_ID);
+    assert_eq!(
+        parse_swap_outcome(&meta.return_data.data),
+        Some(SwapOutcome {
+            in_spent: in_amount,
+            out_received: expected.out_amount,
+        })
+    );
+    assert_eq!(
+        get_token_balance(&svm, &keys.user_base_token),
+        2 * in_amount
+    );
+}
+
+#[test]
+fn test_mock_dex_swap_cpi_signed_by_program_authority() {
+    let mut svm = setup_svm();
+    let payer = Keypair::new();
+    svm.airdrop(&payer.pubkey(), 10_000_000_000).unwrap();
+
+    let in_amount = 10_000_000;
+    let keys = setup_pool(&mut svm, &payer, 0);
+    let (authority, _) = authority_address(&TEST_PROGRAM_ID);
+    let base_mint = token_mint(&svm, &keys.user_base_token);
+    let quote_mint = token_mint(&svm, &keys.user_quote_token);
+    let keys = MockDexSwapKeys {
+        user: authority,
+        user_base_token: create_token_account(&mut svm, &authority, &base_mint, in_amount),
+        user_quote_token: create_token_account(&mut svm, &authority, &quote_mint, 0),
+        ..keys
+    };
+    let expected = quote_exact_in(FEE_BPS, in_amount, BASE_RESERVE, QUOTE_RESERVE).unwrap();
+
+    let data = MockDexSwapData {
+        is_quote_to_base: false,
+    };
+    let instruction = signed_swap_instruction(
+        TEST_PROGRAM_ID,
+        &keys,
+        in_amount,
+        expected.out_amount,
+        &data,
+    );
+    assert!(instruction
+        .accounts
+        .iter()
+        .all(|meta| meta.pubkey != authority || !meta.is_signer));
+    send_transaction(&mut svm, &payer, instruction).expect("Signed swap CPI failed");
+
+    assert_eq!(get_token_balance(&svm, &keys.user_base_token), 0);
+    assert_eq!(
+        get_token_balance(&svm, &keys.user_quote_token),
+        expected.out_amount
+    );
+}
+
+#[test]
+fn test_mock_dex_enveloped_swap_cpi() {
+    let mut svm = setup_svm();
+    let payer = Keypair::new();
+    svm.airdrop(&payer.pubkey(), 10_000_000_000).unwrap();
+
+    let in_amount = 10_000_000;
+    let keys = setup_pool(&mut svm, &payer, in_amount);
+    let expected = quote_exact_in(FEE_BPS, in_amount, BASE_RESERVE, QUOTE_RESERVE).unwrap();
+
+    let data = MockDexSwapData {
+        is_quote_to_base: false,
+    };
+    let instruction = enveloped_swap_instruction(
+        TEST_PROGRAM_ID,
+        &keys,
+        in_amount,
+        expected.out_amount,
+        &data,
+    );
+
+    // The envelope header sits between the amounts and the swap data
+    let header = 1 + 8 + 8;
+    let mut other_version = instruction.clone();
+    other_version.data[header] = ENVELOPE_V1 + 1;
+    assert_beethoven_error(
+        send_transaction(&mut svm, &payer, other_version),
+        BeethovenError::UnsupportedEnvelopeVersion,
+    );
+    let mut other_protocol = instruction.clone();
+    other_protocol.data[header + 1] = ProtocolKind::Gamma as u8;
+    assert_beethoven_error(
+        send_transaction(&mut svm, &payer, other_protocol),
+        BeethovenError::EnvelopeProtocolMismatch,
+    );
+    assert_eq!(get_token_balance(&svm, &keys.user_base_token), in_amount);
+
+    send_transaction(&mut svm, &payer, instruction).expect("Enveloped swap CPI failed");
+    assert_eq!(get_token_balance(&svm, &keys.user_base_token), 0);
+    assert_eq!(
+        get_token_balance(&svm, &keys.user_quote_token),
+        expected.out_amount
+    );
+}
+
+#[test]
+fn test_mock_dex_swap_cpi_with_detection_strategies() {
+    let mut svm = setup_svm();
+    let payer = Keypair::new();
+    svm.airdrop(&payer.pubkey(), 10_000_000_000).unwrap();
+
+    let in_amount = 10_000_000;
+    let keys = setup_pool(&mut svm, &payer, 3 * in_amount);
+    let data = MockDexSwapData {
+        is_quote_to_base: false,
+    };
+    let routed = keys.routed_account_metas();
+    let swap = |accounts: Vec<AccountMeta>, detection| {
+        detected_swap_instruction(TEST_PROGRAM_ID, accounts, detection, in_amount, 1, &data)
+    };
+
+    // A router account ahead of the venue's
+    let mut prefixed = vec![AccountMeta::new_readonly(Keypair::new().pubkey(), false)];
+    prefixed.extend(routed.iter().cloned());
+    send_transaction(&mut svm, &payer, swap(prefixed, Detection::ProgramAt(1)))
+        .expect("ProgramAt swap failed");
+
+    // The pool, after the detector and the user
+    send_transaction(
+        &mut svm,
+        &payer,
+        swap(routed.clone(), Detection::OwnerOf(2)),
+    )
+    .expect("OwnerOf swap failed");
+    assert_beethoven_error(
+        send_transaction(
+            &mut svm,
+            &payer,
+            swap(routed.clone(), Detection::OwnerOf(1)),
+        ),
+        BeethovenError::UnknownProtocol,
+    );
+
+    send_transaction(
+        &mut svm,
+        &payer,
+        swap(routed, Detection::Tagged(ProtocolKind::MockDex)),
+    )
+    .expect("Tagged swap failed");
+
+    assert_eq!(get_token_balance(&svm, &keys.user_base_token), 0);
+    assert!(get_token_balance(&svm, &keys.user_quote_token) > 0);
+}
+
+#[test]
+fn test_mock_dex_route_splits_across_pools() {
+    let mut svm = setup_svm();
+    let payer = Keypair::new();
+    svm.airdrop(&payer.pubkey(), 10_000_000_000).unwrap();
+
+    let in_amount = 10_000_000;
+    let first = setup_pool(&mut svm, &payer, in_amount);
+    let second = add_pool(&mut svm, &first);
+
+    let data = MockDexSwapData {
+        is_quote_to_base: false,
+    };
+    let leg = |keys: &MockDexSwapKeys, leg_in: u64| {
+        let quote = quote_exact_in(FEE_BPS, leg_in, BASE_RESERVE, QUOTE_RESERVE).unwrap();
+        RouteLeg {
+            protocol: ProtocolKind::MockDex,
+            in_amount: leg_in,
+            out_amount: quote.out_amount,
+            minimum_out_amount: quote.out_amount,
+            accounts: keys.routed_account_metas(),
+            data: data.encode(),
+        }
+    };
+    let legs = vec![
+        leg(&first, in_amount / 4),
+        leg(&second, in_amount - in_amount / 4),
+    ];
+    let plan = RoutePlan {
+        input_mint: token_mint(&svm, &first.user_base_token),
+        output_mint: token_mint(&svm, &first.user_quote_token),
+        in_amount,
+        out_amount: legs.iter().map(|leg| leg.out_amount).sum(),
+        legs,
+    };
+
+    let instruction = route_instruction(TEST_PROGRAM_ID, &plan.encode());
+    send_transaction(&mut svm, &payer, instruction).expect("Route CPI failed");
+
+    assert_eq!(get_token_balance(&svm, &first.user_base_token), 0);
+    assert_eq!(
+        get_token_balance(&svm, &first.user_quote_token),
+        plan.out_amount
+    );
+    for (keys, leg) in [&first, &second].into_iter().zip(&plan.legs) {
+        assert_eq!(
+            get_token_balance(&svm, &keys.base_vault),
+            BASE_RESERVE + leg.in_amount
+        );
+        assert_eq!(
+            get_token_balance(&svm, &keys.quote_vault),
+            QUOTE_RESERVE - leg.out_amount
+        );
+    }
+}
+
+/// `out_amount` less `slippage_bps`, as an integrator would set `minimum_out_amount`.
+fn with_slippage(out_amount: u64, slippage_bps: u64) -> u64 {
+    out_amount * (10_000 - slippage_bps) / 10_000
+}
+
+/// Quote selling `in_amount` base for `payer` at the pool's current reserves, let a
+/// second trader sell `mover_in` base first, then submit `payer`'s swap with the stale
+/// quote less 1% as its minimum. Returns the swap's result and the pool's reserves
+/// after the move.
+fn swap_after_price_move(
+    svm: &mut LiteSVM,
+    payer: &Keypair,
+    keys: &MockDexSwapKeys,
+    in_amount: u64,
+    mover_in: u64,
+) -> (Result<u64, String>, u64, u64) {
+    let quoted = quote_exact_in(FEE_BPS, in_amount, BASE_RESERVE, QUOTE_RESERVE).unwrap();
+    let data = MockDexSwapData {
+        is_quote_to_base: false,
+    };
+
+    let mover = Keypair::new();
+    svm.airdrop(&mover.pubkey(), 1_000_000_000).unwrap();
+    let mover_keys = MockDexSwapKeys {
+        user: mover.pubkey(),
+        user_base_token: create_token_account(
+            svm,
+            &mover.pubkey(),
+            &token_mint(svm, &keys.user_base_token),
+            mover_in,
+        ),
+        user_quote_token: create_token_account(
+            svm,
+            &mover.pubkey(),
+            &token_mint(svm, &keys.user_quote_token),
+            0,
+        ),
+        ..keys.clone()
+    };
+    let instruction = build_swap_instruction(
+        mover_keys.routed_account_metas(),
+        mover_in,
+        1,
+        &data.encode(),
+    );
+    send_transaction(svm, &mover, instruction).expect("Price-moving swap failed");
+    let base_reserve = get_token_balance(svm, &keys.base_vault);
+    let quote_reserve = get_token_balance(svm, &keys.quote_vault);
+
+    let instruction = build_swap_instruction(
+        keys.routed_account_metas(),
+        in_amount,
+        with_slippage(quoted.out_amount, 100),
+        &data.encode(),
+    );
+    (
+        send_transaction(svm, payer, instruction),
+        base_reserve,
+        quote_reserve,
+    )
+}
+
+#[test]
+fn test_mock_dex_swap_fills_within_slippage_after_price_move() {
+    let mut svm = setup_svm();
+    let payer = Keypair::new();
+    svm.airdrop(&payer.pubkey(), 10_000_000_000).unwrap();
+
+    let in_amount = 10_000_000;
+    let keys = setup_pool(&mut svm, &payer, in_amount);
+    // A 0.1% sale moves the price about 0.2%, inside the 1% tolerance
+    let (result, base_reserve, quote_reserve) =
+        swap_after_price_move(&mut svm, &payer, &keys, in_amount, BASE_RESERVE / 1_000);
+    result.expect("Swap within slippage failed");
+
+    let filled = quote_exact_in(FEE_BPS, in_amount, base_reserve, quote_reserve).unwrap();
+    let quoted = quote_exact_in(FEE_BPS, in_amount, BASE_RESERVE, QUOTE_RESERVE).unwrap();
+    assert!(filled.out_amount < quoted.out_amount);
+    assert_eq!(
+        get_token_balance(&svm, &keys.user_quote_token),
+        filled.out_amount
+    );
+}
+
+#[test]
+fn test_mock_dex_swap_aborts_beyond_slippage_after_price_move() {
+    let mut svm = setup_svm();
+    let payer = Keypair::new();
+    svm.airdrop(&payer.pubkey(), 10_000_000_000).unwrap();
+
+    let in_amount = 10_000_000;
+    let keys = setup_pool(&mut svm, &payer, in_amount);
+    // A 10% sale moves the price about 17%, far outside the 1% tolerance
+    let (result, base_reserve, quote_reserve) =
+        swap_after_price_move(&mut svm, &payer, &keys, in_amount, BASE_RESERVE / 10);
+    let error = result.unwrap_err();
+
+    assert!(
+        error.contains(&format!("Custom({SLIPPAGE_EXCEEDED_ERROR})")),
+        "unexpected error: {error}"
+    );
+    // Nothing moved: the user keeps their input and the pool its post-move reserves
+    assert_eq!(get_token_balance(&svm, &keys.user_base_token), in_amount);
+    assert_eq!(get_token_balance(&svm, &keys.user_quote_token), 0);
+    assert_eq!(get_token_balance(&svm, &keys.base_vault), base_reserve);
+    assert_eq!(get_token_balance(&svm, &keys.quote_vault), quote_reserve);
+}
+
+/// Empty platform and referral fee ATAs of `keys`' quote mint, paid by `keys.user`.
+fn create_fee_accounts(svm: &mut LiteSVM, keys: &MockDexSwapKeys) -> SwapFeeKeys {
+    let quote_mint = token_mint(svm, &keys.user_quote_token);
+    let (platform, referrer) = (Keypair::new().pubkey(), Keypair::new().pubkey());
+    let fee_keys = SwapFeeKeys::to_associated_accounts(
+        TOKEN_PROGRAM_ID,
+        &quote_mint,
+        keys.user,
+        &platform,
+        &referrer,
+    );
+    create_token_account_at(
+        svm,
+        fee_keys.platform_fee_account,
+        &platform,
+        &quote_mint,
+        0,
+    );
+    create_token_account_at(
+        svm,
+        fee_keys.referral_fee_account,
+        &referrer,
+        &quote_mint,
+        0,
+    );
+    fee_keys
+}
+
+#[test]
+fn test_mock_dex_swap_pays_fee_bps_to_fee_accounts() {
+    let mut svm = setup_svm();
+    let payer = Keypair::new();
+    svm.airdrop(&payer.pubkey(), 10_000_000_000).unwrap();
+
+    let in_amount = 10_000_000;
+    let keys = setup_pool(&mut svm, &payer, in_amount);
+    let fee_keys = create_fee_accounts(&mut svm, &keys);
+    let out_amount = quote_exact_in(FEE_BPS, in_amount, BASE_RESERVE, QUOTE_RESERVE)
+        .unwrap()
+        .out_amount;
+    let fees = SwapFees {
+        platform_bps: 50,
+        referral_bps: 10,
+    };
+    let platform_fee = out_amount * 50 / 10_000;
+    let referral_fee = out_amount * 10 / 10_000;
+    let kept = out_amount - platform_fee - referral_fee;
+
+    let data = MockDexSwapData {
+        is_quote_to_base: false,
+    };
+    let instruction = swap_with_fees_instruction(
+        TEST_PROGRAM_ID,
+        &fee_keys,
+        &keys,
+        in_amount,
+        kept,
+        &fees,
+        &data,
+    );
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&payer.pubkey()),
+        &[&payer],
+        svm.latest_blockhash(),
+    );
+    let meta = svm.send_transaction(tx).expect("Swap with fees failed");
+
+    assert!(platform_fee > 0 && referral_fee > 0);
+    assert_eq!(
+        get_token_balance(&svm, &fee_keys.platform_fee_account),
+        platform_fee
+    );
+    assert_eq!(
+        get_token_balance(&svm, &fee_keys.referral_fee_account),
+        referral_fee
+    );
+    assert_eq!(get_token_balance(&svm, &keys.user_quote_token), kept);
+    assert_eq!(
+        parse_swap_outcome(&meta.return_data.data),
+        Some(SwapOutcome {
+            in_spent: in_amount,
+            out_received: kept,
+        })
+    );
+}
+
+#[test]
+fn test_mock_dex_swap_with_fees_checks_minimum_after_fees() {
+    let mut svm = setup_svm();
+    let payer = Keypair::new();
+    svm.airdrop(&payer.pubkey(), 10_000_000_000).unwrap();
+
+    let in_amount = 10_000_000;
+    let keys = setup_pool(&mut svm, &payer, in_amount);
+    let fee_keys = create_fee_accounts(&mut svm, &keys);
+    let out_amount = quote_exact_in(FEE_BPS, in_amount, BASE_RESERVE, QUOTE_RESERVE)
+        .unwrap()
+        .out_amount;
+    let fees = SwapFees {
+        platform_bps: 50,
+        referral_bps: 0,
+    };
+    let data = MockDexSwapData {
+        is_quote_to_base: false,
+    };
+
+    // The swap alone returns enough, but not once the platform takes its share
+    let instruction = swap_with_fees_instruction(
+        TEST_PROGRAM_ID,
+        &fee_keys,
+        &keys,
+        in_amount,
+        out_amount - out_amount * 50 / 10_000 + 1,
+        &fees,
+        &data,
+    );
+    assert_beethoven_error(
+        send_transaction(&mut svm, &payer, instruction),
+        BeethovenError::SlippageExceeded,
+    );
+
+    // Rates above the whole output are refused before the swap
+    let fees = SwapFees {
+        platform_bps: 9_000,
+        referral_bps: 1_001,
+    };
+    let instruction = swap_with_fees_instruction(
+        TEST_PROGRAM_ID,
+        &fee_keys,
+        &keys,
+        in_amount,
+        1,
+        &fees,
+        &data,
+    );
+    assert_beethoven_error(
+        send_transaction(&mut svm, &payer, instruction),
+        BeethovenError::InvalidFee,
+    );
+
+    assert_eq!(get_token_balance(&svm, &keys.user_base_token), in_amount);
+    assert_eq!(get_token_balance(&svm, &keys.user_quote_token), 0);
+    assert_eq!(get_token_balance(&svm, &fee_keys.platform_fee_account), 0);
+}
+
+#[test]
+fn test_mock_dex_swap_with_fees_checks_fee_account_mints() {
+    let mut svm = setup_svm();
+    let payer = Keypair::new();
+    svm.airdrop(&payer.pubkey(), 10_000_000_000).unwrap();
+
+    let in_amount = 10_000_000;
+    let keys = setup_pool(&mut svm, &payer, in_amount);
+    let mut fee_keys = create_fee_accounts(&mut svm, &keys);
+    let data = MockDexSwapData {
+        is_quote_to_base: false,
+    };
+
+    // A referral account of the input mint is refused before the swap
+    let base_mint = token_mint(&svm, &keys.user_base_token);
+    fee_keys.referral_fee_account = create_token_account(&mut svm, &payer.pubkey(), &base_mint, 0);
+    let fees = SwapFees {
+        platform_bps: 50,
+        referral_bps: 10,
+    };
+    let instruction = swap_with_fees_instruction(
+        TEST_PROGRAM_ID,
+        &fee_keys,
+        &keys,
+        in_amount,
+        1,
+        &fees,
+        &data,
+    );
+    assert_beethoven_error(
+        send_transaction(&mut svm, &payer, instruction),
+        BeethovenError::FeeMintMismatch,
+    );
+    assert_eq!(get_token_balance(&svm, &keys.user_base_token), in_amount);
+
+    // The same account is not read without a referral fee
+    let fees = SwapFees {
+        platform_bps: 50,
+        referral_bps: 0,
+    };
+    let instruction = swap_with_fees_instruction(
+        TEST_PROGRAM_ID,
+        &fee_keys,
+        &keys,
+        in_amount,
+        1,
+        &fees,
+        &data,
+    );
+    send_transaction(&mut svm, &payer, instruction).expect("Swap with fees failed");
+    assert!(get_token_balance(&svm, &fee_keys.platform_fee_account) > 0);
+}
@@ -1,9 +1,2 @@
-mod aldrin;
-mod aldrin_v2;
-mod futarchy;
-mod gamma;
-mod heaven;
 mod manifest;
-mod perena;
-mod solfi;
-mod solfi_v2;
+mod mock_dex;
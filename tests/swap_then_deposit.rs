@@ -0,0 +1,535 @@
+//! A swap and a deposit through the test program in one transaction, swapping into
+//! USDC and depositing that USDC into Jupiter Earn.
+//!
+//! The two dispatchers share one message, so the swap's routed accounts and the
+//! deposit's 17 are deduplicated into one account list, and the USDC account the swap
+//! credits is the one the deposit debits.
+//!
+//! The mock tests sell a fresh token on a mock DEX pool, with `program-cpi-echo`
+//! deployed at Jupiter Earn's program id as the deposit venue: it records the deposit
+//! CPI, so the tests see which amount reached the venue, but mints no fTokens. The
+//! zap tests do both in one `zap` instruction, which deposits whatever the swap
+//! returned.
+//!
+//! The fixture tests sell SOL on the dumped Manifest market and deposit into the
+//! dumped Jupiter Earn; their deposit amount is fixed in its instruction, so it is
+//! sized from the swap's output measured in a separate, identical SVM.
+
+use {
+    crate::helper::*,
+    beethoven::{
+        manifest::ManifestSwapData,
+        mock_dex::{
+            quote::quote_exact_in, MockDexSwapData, MOCK_DEX_PROGRAM_ID, SLIPPAGE_EXCEEDED_ERROR,
+        },
+        token::FULL_BALANCE,
+        BeethovenError,
+    },
+    beethoven_client::{
+        jupiter::{self, JupiterEarnDepositKeys},
+        manifest::{self, ManifestSwapKeys},
+        mock_dex::{pool_account_data, pool_authority_address, MockDexSwapKeys},
+        resolve::ASSOCIATED_TOKEN_PROGRAM_ID,
+        test_program, AdapterKeys, EncodeSwapData,
+    },
+    litesvm::LiteSVM,
+    solana_instruction::Instruction,
+    solana_keypair::Keypair,
+    solana_signer::Signer,
+    solana_transaction::Transaction,
+};
+
+const TOKEN_IN: u64 = 10_000_000;
+const FEE_BPS: u16 = 30;
+const TOKEN_RESERVE: u64 = 1_000_000_000;
+const USDC_RESERVE: u64 = 150_000_000_000;
+
+struct MockSwapThenDeposit {
+    svm: LiteSVM,
+    payer: Keypair,
+    mock_dex: MockDexSwapKeys,
+    jupiter: JupiterEarnDepositKeys,
+}
+
+/// A mock DEX pool selling USDC for a fresh token and the CPI echo at Jupiter Earn's
+/// program id, `payer` holding `TOKEN_IN` tokens and an empty USDC account the deposit
+/// spends from.
+fn setup_mock() -> MockSwapThenDeposit {
+    let mut svm = setup_svm();
+    let payer = Keypair::new();
+    svm.airdrop(&payer.pubkey(), 10_000_000_000).unwrap();
+
+    load_program(&mut svm, TEST_PROGRAM_ID, &test_program_path());
+    load_program(&mut svm, MOCK_DEX_PROGRAM_ID, &mock_dex_program_path());
+    load_program(
+        &mut svm,
+        JupiterEarnDepositKeys::PROGRAM_ID,
+        &cpi_echo_program_path(),
+    );
+
+    let token_mint = create_mint(&mut svm, &payer.pubkey(), 9);
+    let usdc_mint = create_mint(&mut svm, &payer.pubkey(), 6);
+    let pool = Keypair::new().pubkey();
+    let (pool_authority, _) = pool_authority_address(&pool);
+    let base_vault = create_token_account(&mut svm, &pool_authority, &token_mint, TOKEN_RESERVE);
+    let quote_vault = create_token_account(&mut svm, &pool_authority, &usdc_mint, USDC_RESERVE);
+    create_mock_account_at(
+        &mut svm,
+        pool,
+        &MOCK_DEX_PROGRAM_ID,
+        pool_account_data(
+            &pool,
+            &token_mint,
+            &usdc_mint,
+            &base_vault,
+            &quote_vault,
+            FEE_BPS,
+        ),
+    );
+    let mock_dex = MockDexSwapKeys {
+        user: payer.pubkey(),
+        pool,
+        pool_authority,
+        base_vault,
+        quote_vault,
+        user_base_token: create_token_account(&mut svm, &payer.pubkey(), &token_mint, TOKEN_IN),
+        user_quote_token: create_token_account(&mut svm, &payer.pubkey(), &usdc_mint, 0),
+        token_program: TOKEN_PROGRAM_ID,
+    };
+
+    // The echo reads none of the lending accounts, so they are left empty
+    let [recipient_token_account, lending_admin, lending, f_token_mint, supply_token_reserves_liquidity, lending_supply_position_on_liquidity, rate_model, vault, liquidity, liquidity_program, rewards_rate_model] =
+        addresses();
+    let jupiter = JupiterEarnDepositKeys {
+        signer: payer.pubkey(),
+        depositor_token_account: mock_dex.user_quote_token,
+        recipient_token_account,
+        mint: usdc_mint,
+        lending_admin,
+        lending,
+        f_token_mint,
+        supply_token_reserves_liquidity,
+        lending_supply_position_on_liquidity,
+        rate_model,
+        vault,
+        liquidity,
+        liquidity_program,
+        rewards_rate_model,
+        token_program: TOKEN_PROGRAM_ID,
+        associated_token_program: ASSOCIATED_TOKEN_PROGRAM_ID,
+        system_program: solana_sdk_ids::system_program::ID,
+    };
+
+    MockSwapThenDeposit {
+        svm,
+        payer,
+        mock_dex,
+        jupiter,
+    }
+}
+
+const SELL_TOKEN: MockDexSwapData = MockDexSwapData {
+    is_quote_to_base: false,
+};
+
+/// USDC the mock DEX returns for `TOKEN_IN` at its initial reserves.
+fn mock_usdc_out_amount() -> u64 {
+    quote_exact_in(FEE_BPS, TOKEN_IN, TOKEN_RESERVE, USDC_RESERVE)
+        .unwrap()
+        .out_amount
+}
+
+/// Send `instructions` in one transaction, returning the deposit CPI the echo recorded
+/// last.
+fn send_recording_deposit(
+    svm: &mut LiteSVM,
+    payer: &Keypair,
+    instructions: &[Instruction],
+) -> Instruction {
+    let tx = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&payer.pubkey()),
+        &[payer],
+        svm.latest_blockhash(),
+    );
+    let meta = svm.send_transaction(tx).expect("Swap then deposit failed");
+
+    assert_eq!(
+        meta.return_data.program_id,
+        JupiterEarnDepositKeys::PROGRAM_ID
+    );
+    parse_echo(&meta.return_data.data)
+}
+
+#[test]
+fn test_mock_swap_then_deposit_in_one_transaction() {
+    let MockSwapThenDeposit {
+        mut svm,
+        payer,
+        mock_dex,
+        jupiter,
+    } = setup_mock();
+    let usdc_out = mock_usdc_out_amount();
+
+    let instructions = [
+        build_swap_instruction(
+            mock_dex.routed_account_metas(),
+            TOKEN_IN,
+            usdc_out,
+            &SELL_TOKEN.encode(),
+        ),
+        build_deposit_instruction(jupiter.routed_account_metas(), usdc_out),
+    ];
+    let deposit = send_recording_deposit(&mut svm, &payer, &instructions);
+
+    assert_eq!(deposit, jupiter::deposit_instruction(&jupiter, usdc_out));
+    assert_eq!(token_balance(&svm, &mock_dex.user_base_token), 0);
+    // The echo leaves the USDC where the swap paid it
+    assert_eq!(token_balance(&svm, &mock_dex.user_quote_token), usdc_out);
+}
+
+#[test]
+fn test_mock_full_balance_deposit_spends_the_swap_output() {
+    let MockSwapThenDeposit {
+        mut svm,
+        payer,
+        mock_dex,
+        jupiter,
+    } = setup_mock();
+    let usdc_out = mock_usdc_out_amount();
+
+    // The deposit reads the balance when it runs, after the swap earlier in the message
+    let instructions = [
+        build_swap_instruction(
+            mock_dex.routed_account_metas(),
+            TOKEN_IN,
+            usdc_out,
+            &SELL_TOKEN.encode(),
+        ),
+        build_deposit_instruction(jupiter.routed_account_metas(), FULL_BALANCE),
+    ];
+    let deposit = send_recording_deposit(&mut svm, &payer, &instructions);
+
+    assert_eq!(deposit, jupiter::deposit_instruction(&jupiter, usdc_out));
+}
+
+#[test]
+fn test_mock_zap_deposits_the_realized_output() {
+    let MockSwapThenDeposit {
+        mut svm,
+        payer,
+        mock_dex,
+        jupiter,
+    } = setup_mock();
+    let usdc_out = mock_usdc_out_amount();
+
+    // One instruction, with no deposit amount for the caller to size
+    let zap = |min_out| {
+        test_program::zap_instruction(
+            TEST_PROGRAM_ID,
+            &mock_dex,
+            &jupiter,
+            TOKEN_IN,
+            min_out,
+            &SELL_TOKEN,
+        )
+    };
+    let error = send_transaction(&mut svm, &payer, zap(usdc_out + 1)).unwrap_err();
+    assert!(
+        error.contains(&format!("Custom({SLIPPAGE_EXCEEDED_ERROR})")),
+        "unexpected error: {error}"
+    );
+    assert_eq!(token_balance(&svm, &mock_dex.user_base_token), TOKEN_IN);
+
+    let deposit = send_recording_deposit(&mut svm, &payer, &[zap(usdc_out)]);
+    assert_eq!(deposit, jupiter::deposit_instruction(&jupiter, usdc_out));
+    assert_eq!(token_balance(&svm, &mock_dex.user_base_token), 0);
+}
+
+#[test]
+fn test_mock_zap_rejects_a_deposit_from_another_account() {
+    let MockSwapThenDeposit {
+        mut svm,
+        payer,
+        mock_dex,
+        jupiter,
+    } = setup_mock();
+    let jupiter = JupiterEarnDepositKeys {
+        depositor_token_account: create_token_account(&mut svm, &payer.pubkey(), &jupiter.mint, 0),
+        ..jupiter
+    };
+
+    let instruction = test_program::zap_instruction(
+        TEST_PROGRAM_ID,
+        &mock_dex,
+        &jupiter,
+        TOKEN_IN,
+        1,
+        &SELL_TOKEN,
+    );
+    assert_beethoven_error(
+        send_transaction(&mut svm, &payer, instruction),
+        BeethovenError::RouteLegMismatch,
+    );
+    assert_eq!(token_balance(&svm, &mock_dex.user_base_token), TOKEN_IN);
+}
+
+/// The Manifest market, first, and the vaults and wSOL global pair dumped next to it
+const MANIFEST_ACCOUNTS: &[&str] = &[
+    "usdc_sol_market",
+    "sol_usdc_base_vault",
+    "sol_usdc_quote_vault",
+    "global",
+    "global_vault",
+];
+/// The Jupiter Earn lending pool, first, and its accounts, dumped with `fixture-snapshot
+/// jupiter <lending> --account mint=<mint> --account lending_admin=<admin>
+/// --account f_token_mint=<mint> --account supply_token_reserves_liquidity=<reserve>
+/// --account lending_supply_position_on_liquidity=<position>
+/// --account rate_model=<rate model> --account vault=<vault>
+/// --account liquidity=<liquidity> --account rewards_rate_model=<rate model>
+/// --with-program --out fixtures/deposit/jupiter` for the market's USDC. Deposits CPI
+/// into the liquidity program owning `liquidity`, dumped alongside with `solana program
+/// dump <owner> fixtures/deposit/jupiter/jupiter_liquidity_program.so`.
+const JUPITER_ACCOUNTS: &[&str] = &[
+    "pool",
+    "mint",
+    "lending_admin",
+    "f_token_mint",
+    "supply_token_reserves_liquidity",
+    "lending_supply_position_on_liquidity",
+    "rate_model",
+    "vault",
+    "liquidity",
+    "rewards_rate_model",
+];
+/// Mints shared across venues under `fixtures/common`
+const COMMON_ACCOUNTS: &[&str] = &["wsol_mint", "usdc_mint"];
+
+const LIQUIDITY_PROGRAM_PATH: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/fixtures/deposit/jupiter/jupiter_liquidity_program.so"
+);
+
+const SOL_IN: u64 = 100_000_000;
+
+struct SwapThenDeposit {
+    svm: LiteSVM,
+    payer: Keypair,
+    manifest: ManifestSwapKeys,
+    jupiter: JupiterEarnDepositKeys,
+}
+
+/// Both venues with their dumped accounts, `payer` holding `SOL_IN` wSOL and empty USDC
+/// and fToken accounts.
+fn setup() -> SwapThenDeposit {
+    let mut svm = setup_svm();
+    let payer = Keypair::new();
+    svm.airdrop(&payer.pubkey(), 10_000_000_000).unwrap();
+
+    load_program(&mut svm, TEST_PROGRAM_ID, &test_program_path());
+    let venue = VenueFixtures::swap("manifest");
+    venue.load_program(&mut svm, MANIFEST_PROGRAM_ID);
+    let market = venue.load(&mut svm, MANIFEST_ACCOUNTS[0]);
+    for name in &MANIFEST_ACCOUNTS[1..] {
+        venue.load(&mut svm, name);
+    }
+    for name in COMMON_ACCOUNTS {
+        load_and_set_json_fixture(
+            &mut svm,
+            &format!("{}/fixtures/common/{name}.json", env!("CARGO_MANIFEST_DIR")),
+        );
+    }
+
+    // Sell wSOL (the market's base) from `payer`'s associated token accounts
+    let manifest = manifest::resolve_swap_keys(&SvmSource(&svm), &market, &payer.pubkey(), true)
+        .expect("Failed to resolve the Manifest market");
+    create_token_account_at(
+        &mut svm,
+        manifest.trader_base,
+        &payer.pubkey(),
+        &manifest.base_mint,
+        SOL_IN,
+    );
+    create_token_account_at(
+        &mut svm,
+        manifest.trader_quote,
+        &payer.pubkey(),
+        &manifest.quote_mint,
+        0,
+    );
+
+    let venue = VenueFixtures::deposit("jupiter");
+    venue.load_program(&mut svm, JupiterEarnDepositKeys::PROGRAM_ID);
+    let [lending, mint, lending_admin, f_token_mint, supply_token_reserves_liquidity, lending_supply_position_on_liquidity, rate_model, vault, liquidity, rewards_rate_model] =
+        [0, 1, 2, 3, 4, 5, 6, 7, 8, 9].map(|index| venue.load(&mut svm, JUPITER_ACCOUNTS[index]));
+    let liquidity_program = svm.get_account(&liquidity).unwrap().owner;
+    load_program(&mut svm, liquidity_program, LIQUIDITY_PROGRAM_PATH);
+
+    // Deposit from the account the swap pays its USDC into
+    let jupiter = JupiterEarnDepositKeys {
+        signer: payer.pubkey(),
+        depositor_token_account: manifest.trader_quote,
+        recipient_token_account: create_token_account(&mut svm, &payer.pubkey(), &f_token_mint, 0),
+        mint,
+        lending_admin,
+        lending,
+        f_token_mint,
+        supply_token_reserves_liquidity,
+        lending_supply_position_on_liquidity,
+        rate_model,
+        vault,
+        liquidity,
+        liquidity_program,
+        rewards_rate_model,
+        token_program: TOKEN_PROGRAM_ID,
+        associated_token_program: ASSOCIATED_TOKEN_PROGRAM_ID,
+        system_program: solana_sdk_ids::system_program::ID,
+    };
+
+    SwapThenDeposit {
+        svm,
+        payer,
+        manifest,
+        jupiter,
+    }
+}
+
+const SELL_SOL: ManifestSwapData = ManifestSwapData {
+    is_base_in: true,
+    is_exact_in: true,
+};
+
+fn swap_instruction(manifest: &ManifestSwapKeys) -> Instruction {
+    build_swap_instruction(
+        manifest.routed_account_metas(),
+        SOL_IN,
+        1,
+        &SELL_SOL.encode(),
+    )
+}
+
+/// USDC the Manifest swap returns for `SOL_IN`, swapped on its own in a fresh setup.
+fn usdc_out_amount() -> u64 {
+    let SwapThenDeposit {
+        mut svm,
+        payer,
+        manifest,
+        ..
+    } = setup();
+    send_transaction(&mut svm, &payer, swap_instruction(&manifest)).expect("Manifest swap failed");
+    token_balance(&svm, &manifest.trader_quote)
+}
+
+/// Fail unless both venues' fixtures are dumped, for the USDC Manifest pays out.
+fn require_venues() {
+    VenueFixtures::swap("manifest").require(MANIFEST_ACCOUNTS);
+    VenueFixtures::deposit("jupiter").require(JUPITER_ACCOUNTS);
+    assert!(
+        std::path::Path::new(LIQUIDITY_PROGRAM_PATH).exists(),
+        "missing {LIQUIDITY_PROGRAM_PATH}"
+    );
+
+    let SwapThenDeposit {
+        manifest, jupiter, ..
+    } = setup();
+    assert_eq!(
+        jupiter.mint, manifest.quote_mint,
+        "the Jupiter Earn fixtures are not for the market's USDC"
+    );
+}
+
+#[test]
+#[ignore = "needs dumped mainnet fixtures; run with `make test-fixtures`"]
+fn test_swap_then_deposit_in_one_transaction() {
+    require_venues();
+    let usdc_out = usdc_out_amount();
+    assert!(usdc_out > 0, "Manifest returned no USDC");
+
+    let SwapThenDeposit {
+        mut svm,
+        payer,
+        manifest,
+        jupiter,
+    } = setup();
+    let vault_before = token_balance(&svm, &jupiter.vault);
+
+    let instructions = [
+        swap_instruction(&manifest),
+        build_deposit_instruction(jupiter.routed_account_metas(), usdc_out),
+    ];
+    send_instructions(&mut svm, &payer, &instructions).expect("Swap then deposit failed");
+
+    // The USDC only passes through the depositor's account
+    assert_eq!(token_balance(&svm, &manifest.trader_base), 0);
+    assert_eq!(token_balance(&svm, &manifest.trader_quote), 0);
+    assert_eq!(token_balance(&svm, &jupiter.vault), vault_before + usdc_out);
+    assert!(
+        token_balance(&svm, &jupiter.recipient_token_account) > 0,
+        "no fTokens received"
+    );
+}
+
+#[test]
+#[ignore = "needs dumped mainnet fixtures; run with `make test-fixtures`"]
+fn test_deposit_before_swap_fails_atomically() {
+    require_venues();
+    let usdc_out = usdc_out_amount();
+
+    let SwapThenDeposit {
+        mut svm,
+        payer,
+        manifest,
+        jupiter,
+    } = setup();
+
+    // Instructions run in message order, so the deposit finds no USDC yet
+    let instructions = [
+        build_deposit_instruction(jupiter.routed_account_metas(), usdc_out),
+        swap_instruction(&manifest),
+    ];
+    send_instructions(&mut svm, &payer, &instructions)
+        .expect_err("deposit spent USDC the swap had not paid out yet");
+
+    assert_eq!(token_balance(&svm, &manifest.trader_base), SOL_IN);
+    assert_eq!(token_balance(&svm, &manifest.trader_quote), 0);
+    assert_eq!(token_balance(&svm, &jupiter.recipient_token_account), 0);
+}
+
+#[test]
+#[ignore = "needs dumped mainnet fixtures; run with `make test-fixtures`"]
+fn test_zap_deposits_the_realized_output() {
+    require_venues();
+    let usdc_out = usdc_out_amount();
+
+    let SwapThenDeposit {
+        mut svm,
+        payer,
+        manifest,
+        jupiter,
+    } = setup();
+    let vault_before = token_balance(&svm, &jupiter.vault);
+
+    // One instruction, with no deposit amount for the caller to size
+    let zap = |min_out| {
+        test_program::zap_instruction(
+            TEST_PROGRAM_ID,
+            &manifest,
+            &jupiter,
+            SOL_IN,
+            min_out,
+            &SELL_SOL,
+        )
+    };
+    send_transaction(&mut svm, &payer, zap(usdc_out + 1))
+        .expect_err("zap ignored its minimum output");
+    send_transaction(&mut svm, &payer, zap(usdc_out)).expect("Zap failed");
+
+    assert_eq!(token_balance(&svm, &manifest.trader_base), 0);
+    assert_eq!(token_balance(&svm, &manifest.trader_quote), 0);
+    assert_eq!(token_balance(&svm, &jupiter.vault), vault_before + usdc_out);
+    assert!(
+        token_balance(&svm, &jupiter.recipient_token_account) > 0,
+        "no fTokens received"
+    );
+}
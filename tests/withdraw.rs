@@ -0,0 +1,163 @@
+//! Withdraws dispatched through the test program's `WITHDRAW` instruction, checked
+//! against the venue instruction the client builds for a direct call.
+//!
+//! The venue is replaced by `program-cpi-echo` in LiteSVM, so the owner signs for real
+//! and the echo records the last CPI: for Kamino the withdraw itself, after the
+//! refreshes it issues for reserves without data.
+
+use {
+    crate::helper::*,
+    beethoven::{jupiter::JUPITER_EARN_PROGRAM_ID, kamino::KAMINO_LEND_PROGRAM_ID},
+    beethoven_client::{
+        jupiter::{self, JupiterEarnWithdrawKeys},
+        kamino::{self, KaminoWithdrawKeys},
+        test_program::withdraw_instruction,
+    },
+    solana_address::Address,
+    solana_clock::Clock,
+    solana_keypair::Keypair,
+    solana_signer::Signer,
+};
+
+const AMOUNT: u64 = 1_000_000;
+
+/// Kamino withdraw keys with `owner` owning the obligation.
+fn kamino_keys(owner: Address) -> KaminoWithdrawKeys {
+    let [obligation, lending_market, lending_market_authority, withdraw_reserve, reserve_liquidity_mint, reserve_source_collateral, reserve_collateral_mint, reserve_liquidity_supply, user_destination_liquidity, collateral_token_program, liquidity_token_program, instruction_sysvar_account, obligation_farm_user_state, reserve_farm_state, farms_program, scope_oracle, other_reserve] =
+        addresses();
+    KaminoWithdrawKeys {
+        owner,
+        obligation,
+        lending_market,
+        lending_market_authority,
+        withdraw_reserve,
+        reserve_liquidity_mint,
+        reserve_source_collateral,
+        reserve_collateral_mint,
+        reserve_liquidity_supply,
+        user_destination_liquidity,
+        placeholder_user_destination_collateral: KAMINO_LEND_PROGRAM_ID,
+        collateral_token_program,
+        liquidity_token_program,
+        instruction_sysvar_account,
+        obligation_farm_user_state,
+        reserve_farm_state,
+        farms_program,
+        scope_oracle,
+        reserve_accounts: vec![withdraw_reserve, other_reserve],
+    }
+}
+
+#[test]
+fn test_kamino_withdraw_cpi_matches_client() {
+    let owner = Keypair::new();
+    let keys = kamino_keys(owner.pubkey());
+
+    let instruction = withdraw_instruction(TEST_PROGRAM_ID, &keys, AMOUNT);
+    assert_eq!(
+        echo_cpi_svm(instruction, KAMINO_LEND_PROGRAM_ID, &owner),
+        kamino::withdraw_instruction(&keys, AMOUNT)
+    );
+}
+
+#[test]
+fn test_kamino_withdraw_refreshes_stale_reserves_in_batches() {
+    let owner = Keypair::new();
+    let mut keys = kamino_keys(owner.pubkey());
+    let mut svm = echo_svm(KAMINO_LEND_PROGRAM_ID);
+    let slot = svm.get_sysvar::<Clock>().slot;
+
+    // Six stale obligation reserves besides the withdrawn one, plus one refreshed this
+    // slot that is skipped. Only Kamino-owned accounts are taken as obligation reserves.
+    let stale: Vec<Address> = (0..6).map(|_| Address::new_unique()).collect();
+    let fresh = Address::new_unique();
+    for reserve in stale.iter().chain([&keys.withdraw_reserve]) {
+        svm.set_account(*reserve, kamino_reserve_account(slot, true))
+            .unwrap();
+    }
+    svm.set_account(fresh, kamino_reserve_account(slot, false))
+        .unwrap();
+    keys.reserve_accounts = [keys.withdraw_reserve, fresh]
+        .into_iter()
+        .chain(stale.iter().copied())
+        .collect();
+
+    let instruction = withdraw_instruction(TEST_PROGRAM_ID, &keys, AMOUNT);
+    let cpis = venue_cpis_svm(&mut svm, instruction, KAMINO_LEND_PROGRAM_ID, &owner);
+
+    let refreshed: Vec<Address> = core::iter::once(keys.withdraw_reserve)
+        .chain(stale.iter().copied())
+        .collect();
+    let batches: Vec<_> = cpis
+        .iter()
+        .filter(|(_, data)| data[..] == KAMINO_REFRESH_RESERVES_BATCH_DATA)
+        .map(|(accounts, _)| accounts.clone())
+        .collect();
+    assert_eq!(
+        batches,
+        vec![
+            kamino_refresh_accounts(&refreshed[..5], &keys.lending_market, &keys.scope_oracle),
+            kamino_refresh_accounts(&refreshed[5..], &keys.lending_market, &keys.scope_oracle),
+        ]
+    );
+    assert_eq!(
+        cpis.first().map(|(accounts, _)| accounts),
+        batches.first(),
+        "the reserves are refreshed before the obligation and the withdraw"
+    );
+}
+
+/// Jupiter Earn withdraw keys with `signer` burning the fTokens.
+fn jupiter_keys(signer: Address) -> JupiterEarnWithdrawKeys {
+    let [owner_token_account, recipient_token_account, lending_admin, lending, mint, f_token_mint, supply_token_reserves_liquidity, lending_supply_position_on_liquidity, rate_model, vault, claim_account, liquidity, liquidity_program, rewards_rate_model, token_program, associated_token_program, system_program] =
+        addresses();
+    JupiterEarnWithdrawKeys {
+        signer,
+        owner_token_account,
+        recipient_token_account,
+        lending_admin,
+        lending,
+        mint,
+        f_token_mint,
+        supply_token_reserves_liquidity,
+        lending_supply_position_on_liquidity,
+        rate_model,
+        vault,
+        claim_account,
+        liquidity,
+        liquidity_program,
+        rewards_rate_model,
+        token_program,
+        associated_token_program,
+        system_program,
+    }
+}
+
+#[test]
+fn test_jupiter_withdraw_cpi_matches_client() {
+    let signer = Keypair::new();
+    let keys = jupiter_keys(signer.pubkey());
+
+    let instruction = withdraw_instruction(TEST_PROGRAM_ID, &keys, AMOUNT);
+    assert_eq!(
+        echo_cpi_svm(instruction, JUPITER_EARN_PROGRAM_ID, &signer),
+        jupiter::withdraw_instruction(&keys, AMOUNT)
+    );
+}
+
+#[test]
+fn test_withdraw_rejects_truncated_amount() {
+    let payer = Keypair::new();
+    let mut svm = setup_svm_with_program(&load_fixture_bytes(&test_program_path()));
+    svm.airdrop(&payer.pubkey(), 1_000_000_000).unwrap();
+
+    let mut instruction =
+        withdraw_instruction(TEST_PROGRAM_ID, &jupiter_keys(payer.pubkey()), AMOUNT);
+    // The discriminator and half the amount
+    instruction.data.truncate(5);
+
+    assert_fails_with(
+        send_transaction(&mut svm, &payer, instruction),
+        "InvalidInstructionData",
+    );
+}